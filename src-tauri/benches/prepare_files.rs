@@ -0,0 +1,61 @@
+//! 索引构建分块阶段基准：对比单文件串行与默认并发下 `IndexManager::prepare_files` 的耗时
+//!
+//! 运行：cargo bench --bench prepare_files
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use futures::StreamExt;
+use terminal_lib::vector_db::core::{ParseConcurrencyConfig, VectorDbConfig};
+use terminal_lib::vector_db::storage::IndexManager;
+
+const FILE_COUNT: usize = 400;
+
+fn write_workspace(dir: &std::path::Path) -> Vec<PathBuf> {
+    (0..FILE_COUNT)
+        .map(|i| {
+            let path = dir.join(format!("module_{i}.rs"));
+            let body: String = (0..40)
+                .map(|f| {
+                    format!(
+                        "pub fn handler_{i}_{f}(input: &str) -> usize {{\n    input.len() + {f}\n}}\n\n"
+                    )
+                })
+                .collect();
+            std::fs::write(&path, body).unwrap();
+            path
+        })
+        .collect()
+}
+
+fn bench_prepare_files(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let files = write_workspace(dir.path());
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("prepare_files");
+    group.sample_size(10);
+    // 单核机器上默认并发为 1，取至少 2 避免与串行基准的 BenchmarkId 重复
+    let default_workers = ParseConcurrencyConfig::default()
+        .max_concurrent_files
+        .max(2);
+    for workers in [1, default_workers] {
+        let mut config = VectorDbConfig::default();
+        config.parsing.max_concurrent_files = workers;
+        let config = Arc::new(config);
+        group.bench_with_input(BenchmarkId::from_parameter(workers), &workers, |b, _| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    IndexManager::prepare_files(Arc::clone(&config), files.clone())
+                        .collect::<Vec<_>>()
+                        .await
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_prepare_files);
+criterion_main!(benches);
@@ -124,6 +124,14 @@ CREATE TABLE IF NOT EXISTS session_summaries (
     FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
 );
 
+-- 会话级设置：model_id 为会话固定的模型，NULL 时跟随全局默认模型
+CREATE TABLE IF NOT EXISTS session_settings (
+    session_id INTEGER PRIMARY KEY,
+    model_id TEXT,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
 CREATE TABLE IF NOT EXISTS workspace_file_context (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     workspace_path TEXT NOT NULL,
@@ -176,6 +184,20 @@ CREATE TABLE IF NOT EXISTS execution_messages (
     FOREIGN KEY (execution_id) REFERENCES agent_executions(execution_id) ON DELETE CASCADE
 );
 
+-- 对话消息的 embedding，仅在启用历史回忆时写入
+CREATE TABLE IF NOT EXISTS execution_message_embeddings (
+    message_id INTEGER PRIMARY KEY,
+    execution_id TEXT NOT NULL,
+    session_id INTEGER NOT NULL,
+    workspace_path TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('user', 'assistant')),
+    content TEXT NOT NULL,
+    embedding_model TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES execution_messages(id) ON DELETE CASCADE
+);
+
 CREATE TABLE IF NOT EXISTS tool_executions (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     execution_id TEXT NOT NULL,
@@ -254,6 +276,8 @@ CREATE INDEX IF NOT EXISTS idx_agent_executions_status
     ON agent_executions(status, created_at DESC);
 CREATE INDEX IF NOT EXISTS idx_execution_messages_iter
     ON execution_messages(execution_id, iteration, sequence);
+CREATE INDEX IF NOT EXISTS idx_message_embeddings_workspace
+    ON execution_message_embeddings(workspace_path, created_at DESC);
 CREATE INDEX IF NOT EXISTS idx_tool_executions_started
     ON tool_executions(execution_id, started_at);
 CREATE INDEX IF NOT EXISTS idx_execution_events_iter
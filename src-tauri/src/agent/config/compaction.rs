@@ -1,15 +1,39 @@
 //! Compaction configuration for tool result clearing.
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CompactionConfig {
     /// Number of recent messages to keep unmodified
     pub keep_recent_count: usize,
+    /// 何时压缩较早的消息
+    pub policy: CompactionPolicy,
+    /// proactive：每累计多少轮压缩一次较早的消息，0 关闭
+    pub proactive_every_turns: usize,
+    /// proactive：原样保留的最近轮数
+    pub proactive_keep_turns: usize,
+}
+
+/// 消息压缩策略。接近上下文窗口时的溢出压缩是防止请求超限的兜底，不受策略影响
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionPolicy {
+    /// 仅在接近上下文窗口时压缩
+    #[default]
+    #[serde(alias = "off")]
+    OverflowOnly,
+    /// 除溢出压缩外，按轮数定期把较早的消息压缩为摘要，控制每次请求的体积
+    Proactive,
 }
 
 impl Default for CompactionConfig {
     fn default() -> Self {
         Self {
             keep_recent_count: 3,
+            policy: CompactionPolicy::default(),
+            proactive_every_turns: 10,
+            proactive_keep_turns: 4,
         }
     }
 }
@@ -1,10 +1,48 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
+use super::CompactionConfig;
+
 /// Execution pipeline configuration shared across agent tasks.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskExecutionConfig {
     pub max_iterations: u32,
     pub max_errors: u32,
+    /// 同一批次内可并行工具的最大并发数
+    #[serde(default = "default_max_concurrent_tools")]
+    pub max_concurrent_tools: usize,
+    /// 达到 max_iterations 时的处理方式
+    #[serde(default)]
+    pub on_max_iterations: MaxIterationsBehavior,
+    /// 跨迭代复用相同只读工具调用结果的迭代数，0 表示关闭
+    #[serde(default = "default_tool_dedup_window")]
+    pub tool_dedup_window: u32,
+    /// 消息压缩策略
+    #[serde(default)]
+    pub compaction: CompactionConfig,
+    /// 文件类工具可访问的根目录，越界路径（含 `..` 与符号链接）会被拒绝；None 表示以工作区根目录为界
+    #[serde(default)]
+    pub filesystem_root: Option<PathBuf>,
+}
+
+/// 达到最大迭代次数后的行为
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaxIterationsBehavior {
+    /// 直接停止，任务以 Error 结束
+    #[default]
+    HardStop,
+    /// 先让模型总结目前进展再停止，任务以 Completed 结束
+    SummarizeThenStop,
+}
+
+fn default_max_concurrent_tools() -> usize {
+    4
+}
+
+fn default_tool_dedup_window() -> u32 {
+    crate::agent::tools::DEFAULT_TOOL_DEDUP_WINDOW
 }
 
 impl Default for TaskExecutionConfig {
@@ -12,6 +50,11 @@ impl Default for TaskExecutionConfig {
         Self {
             max_iterations: 100,
             max_errors: 5,
+            max_concurrent_tools: default_max_concurrent_tools(),
+            on_max_iterations: MaxIterationsBehavior::default(),
+            tool_dedup_window: default_tool_dedup_window(),
+            compaction: CompactionConfig::default(),
+            filesystem_root: None,
         }
     }
 }
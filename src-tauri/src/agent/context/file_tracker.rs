@@ -179,6 +179,12 @@ impl FileContextTracker {
         guard.drain().collect()
     }
 
+    /// 丢弃尚未注入上下文的文件变更记录
+    pub async fn clear_pending_changes(&self) {
+        self.recently_modified.write().await.clear();
+        self.recently_agent_edits.write().await.clear();
+    }
+
     fn normalized_path(&self, path: &Path) -> String {
         let resolved = if path.is_absolute() {
             path.to_path_buf()
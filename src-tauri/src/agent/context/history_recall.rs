@@ -0,0 +1,208 @@
+//! 跨对话历史回忆
+//!
+//! 启用后，用户与助手消息在持久化时异步向量化并写入 `execution_message_embeddings`，
+//! `recall_history` 工具在当前工作区的历史对话中做语义检索。默认关闭：消息正文会发送给
+//! embedding 服务。
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::agent::persistence::{
+    AgentPersistence, ExecutionMessage, MessageEmbedding, MessageRole,
+};
+use crate::storage::repositories::AppPreferences;
+use crate::storage::DatabaseManager;
+
+/// 是否启用历史回忆的偏好项，值为 "true" / "false"
+pub const RECALL_HISTORY_ENABLED_KEY: &str = "agent.recall_history_enabled";
+
+/// 过短的消息（"好的"、"继续"）没有回忆价值
+const MIN_INDEXED_CHARS: usize = 20;
+/// 送入 embedding 的最大字符数
+const MAX_EMBED_CHARS: usize = 4000;
+/// 返回片段的最大字符数
+const SNIPPET_MAX_CHARS: usize = 600;
+
+pub async fn recall_enabled(db: &DatabaseManager) -> bool {
+    AppPreferences::new(db)
+        .get(RECALL_HISTORY_ENABLED_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true")
+}
+
+/// 后台向量化一条刚持久化的消息；未启用、角色不符或 embedding 不可用时静默跳过
+pub fn spawn_index_message(
+    persistence: Arc<AgentPersistence>,
+    message: ExecutionMessage,
+    session_id: i64,
+    workspace_path: String,
+) {
+    if !matches!(message.role, MessageRole::User | MessageRole::Assistant)
+        || message.content.trim().chars().count() < MIN_INDEXED_CHARS
+    {
+        return;
+    }
+
+    tokio::spawn(async move {
+        if !recall_enabled(persistence.database()).await {
+            return;
+        }
+        // 向量化可以延后，不与用户的前台操作争抢 embedding 资源
+        crate::setup::idle::wait_until_idle().await;
+        let Some(global) = crate::vector_db::commands::get_global_state() else {
+            return;
+        };
+        let embedder = global.search_engine.embedder();
+        let text = truncate_chars(&message.content, MAX_EMBED_CHARS);
+        let embedding = match embedder.embed(&[text]).await {
+            Ok(mut vectors) if !vectors.is_empty() => vectors.swap_remove(0),
+            Ok(_) => return,
+            Err(e) => {
+                debug!("历史消息向量化失败: message_id={}, err={}", message.id, e);
+                return;
+            }
+        };
+        if let Err(e) = persistence
+            .message_embeddings()
+            .upsert(
+                &message,
+                session_id,
+                &workspace_path,
+                embedder.model_name(),
+                &embedding,
+            )
+            .await
+        {
+            debug!("保存历史消息向量失败: message_id={}, err={}", message.id, e);
+        }
+    });
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryMatch {
+    pub session_id: i64,
+    pub execution_id: String,
+    pub role: &'static str,
+    pub snippet: String,
+    pub score: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 在工作区的历史对话中检索与 query 相关的消息，exclude_execution_id 为当前任务
+pub async fn recall(
+    persistence: &AgentPersistence,
+    workspace_path: &str,
+    exclude_execution_id: &str,
+    query: &str,
+    top_k: usize,
+) -> Result<Vec<HistoryMatch>, String> {
+    let global = crate::vector_db::commands::get_global_state()
+        .ok_or_else(|| "Vector DB not initialized".to_string())?;
+    let embedder = global.search_engine.embedder();
+    let query_embedding = embedder
+        .embed(&[query])
+        .await
+        .map_err(|e| format!("Failed to embed query: {}", e))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Embedding service returned no vector".to_string())?;
+
+    let candidates = persistence
+        .message_embeddings()
+        .list_for_workspace(workspace_path, embedder.model_name(), exclude_execution_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rank(&query_embedding, &candidates, top_k)
+        .into_iter()
+        .map(|(score, m)| HistoryMatch {
+            session_id: m.session_id,
+            execution_id: m.execution_id.clone(),
+            role: m.role.as_str(),
+            snippet: truncate_chars(&m.content, SNIPPET_MAX_CHARS).to_string(),
+            score,
+            created_at: m.created_at,
+        })
+        .collect())
+}
+
+/// 按余弦相似度降序取前 top_k；维度不一致的记录跳过
+fn rank<'a>(
+    query: &[f32],
+    candidates: &'a [MessageEmbedding],
+    top_k: usize,
+) -> Vec<(f32, &'a MessageEmbedding)> {
+    let mut scored: Vec<(f32, &MessageEmbedding)> = candidates
+        .iter()
+        .filter(|c| c.embedding.len() == query.len())
+        .map(|c| (cosine(query, &c.embedding), c))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.truncate(top_k);
+    scored
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm =
+        a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|y| y * y).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        0.0
+    } else {
+        dot / norm
+    }
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => &text[..idx],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::persistence::{decode_embedding, encode_embedding};
+
+    fn embedding(id: i64, vector: Vec<f32>) -> MessageEmbedding {
+        MessageEmbedding {
+            message_id: id,
+            execution_id: format!("exec-{id}"),
+            session_id: 1,
+            role: MessageRole::User,
+            content: "消息内容".repeat(10),
+            embedding_model: "m".into(),
+            embedding: vector,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn ranks_by_cosine_and_skips_other_dimensions() {
+        let candidates = vec![
+            embedding(1, vec![0.0, 1.0]),
+            embedding(2, vec![2.0, 0.1]),
+            embedding(3, vec![1.0, 0.0, 0.0]),
+        ];
+        let ranked = rank(&[1.0, 0.0], &candidates, 5);
+        let ids: Vec<i64> = ranked.iter().map(|(_, m)| m.message_id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert!(ranked[0].0 > 0.99);
+        assert_eq!(rank(&[1.0, 0.0], &candidates, 1).len(), 1);
+    }
+
+    #[test]
+    fn embedding_blob_round_trips_and_snippets_truncate_on_chars() {
+        let vector = vec![0.5, -1.25, 3.0];
+        assert_eq!(decode_embedding(&encode_embedding(&vector)), vector);
+        assert_eq!(truncate_chars("历史回忆", 2), "历史");
+        assert_eq!(truncate_chars("abc", 10), "abc");
+    }
+}
@@ -2,12 +2,15 @@
 
 pub mod builder;
 pub mod file_tracker;
+pub mod history_recall;
+pub mod path_summary;
 pub mod project_context;
 pub mod summarizer;
 
 pub use crate::agent::config::ContextBuilderConfig;
 pub use builder::ContextBuilder;
 pub use file_tracker::{FileContextTracker, FileOperationRecord};
+pub use path_summary::{PathSummarizer, PathSummary};
 pub use project_context::{ProjectContext, ProjectContextLoader};
 pub use summarizer::{SessionSummarizer, SummaryResult};
 
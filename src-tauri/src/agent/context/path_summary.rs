@@ -0,0 +1,540 @@
+//! 文件/目录摘要
+//!
+//! 不启动 ReAct 循环，直接以单轮 LLM 调用总结指定路径。输入超出上下文预算时先分批总结、
+//! 再合并（map-reduce）；工作区已建立向量索引时附带语义搜索找到的相关代码作为参考。
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use futures::{StreamExt, TryStreamExt};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::agent::error::{AgentError, AgentResult};
+use crate::agent::prompt::components::path_summary::{
+    build_path_summary_merge_prompt, build_path_summary_user_prompt,
+    PATH_SUMMARY_MERGE_SYSTEM_PROMPT, PATH_SUMMARY_SYSTEM_PROMPT,
+};
+use crate::agent::utils::tokenizer::count_text_tokens;
+use crate::llm::anthropic_types::{
+    ContentBlock, CreateMessageRequest, MessageContent, MessageParam, MessageRole, SystemPrompt,
+};
+use crate::llm::service::LLMService;
+use crate::storage::repositories::AIModels;
+use crate::storage::DatabaseManager;
+use crate::vector_db::search::SearchOptions;
+use crate::vector_db::utils::collect_source_files;
+
+/// 目录下最多读取的文件数
+const MAX_FILES: usize = 50;
+/// 单个文件的大小上限
+const MAX_FILE_BYTES: u64 = 200 * 1024;
+/// 单次调用的输入 token 上限，与模型上下文窗口换算出的预算取较小值
+const MAX_INPUT_TOKENS_PER_CALL: usize = 48_000;
+/// 输入可占用的上下文窗口比例，其余留给提示词与输出
+const CONTEXT_BUDGET_RATIO: f64 = 0.6;
+const DEFAULT_CONTEXT_WINDOW: usize = 128_000;
+const SUMMARY_MAX_TOKENS: u32 = 1024;
+/// 附带的相关代码片段数
+const RELATED_SNIPPETS: usize = 5;
+/// 分批总结的并发数
+const MAP_CONCURRENCY: usize = 3;
+/// 合并阶段的最大轮数，防止异常输入无限递归
+const MAX_REDUCE_ROUNDS: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathSummary {
+    pub path: String,
+    pub summary: String,
+    /// 参与总结的文件
+    pub files: Vec<String>,
+    /// 超出数量上限、过大或非 UTF-8 而被跳过的文件数
+    pub skipped_files: usize,
+    /// 作为参考附带的相关代码位置
+    pub related: Vec<String>,
+    /// LLM 调用次数，大于 1 表示使用了分批总结
+    pub llm_calls: usize,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+}
+
+struct SourceFile {
+    display: String,
+    content: String,
+}
+
+impl SourceFile {
+    fn render(&self) -> String {
+        format!(
+            "### {}\n```\n{}\n```\n",
+            self.display,
+            self.content.trim_end()
+        )
+    }
+}
+
+struct Completion {
+    text: String,
+    input_tokens: u32,
+    output_tokens: u32,
+}
+
+#[derive(Default)]
+struct UsageTotals {
+    calls: usize,
+    input_tokens: u32,
+    output_tokens: u32,
+}
+
+impl UsageTotals {
+    fn record(&mut self, completion: &Completion) {
+        self.calls += 1;
+        self.input_tokens = self.input_tokens.saturating_add(completion.input_tokens);
+        self.output_tokens = self.output_tokens.saturating_add(completion.output_tokens);
+    }
+}
+
+pub struct PathSummarizer {
+    database: Arc<DatabaseManager>,
+    model_id: String,
+}
+
+impl PathSummarizer {
+    pub fn new(database: Arc<DatabaseManager>, model_id: impl Into<String>) -> Self {
+        Self {
+            database,
+            model_id: model_id.into(),
+        }
+    }
+
+    /// 总结文件或目录；`workspace_root` 用于展示相对路径和查找向量索引
+    pub async fn summarize(
+        &self,
+        path: &Path,
+        workspace_root: Option<&Path>,
+        include_related: bool,
+    ) -> AgentResult<PathSummary> {
+        let base = display_base(path, workspace_root);
+        let display = display_path(path, &base);
+
+        let (sources, skipped_files) = {
+            let path = path.to_path_buf();
+            let base = base.clone();
+            tokio::task::spawn_blocking(move || collect_sources(&path, &base))
+                .await
+                .map_err(|e| AgentError::Internal(format!("Failed to read sources: {}", e)))?
+        };
+        if sources.is_empty() {
+            return Err(AgentError::Internal(format!(
+                "No readable text files under {}",
+                path.display()
+            )));
+        }
+
+        let related = match workspace_root {
+            Some(root) if include_related => {
+                self.related_snippets(path, root, &base, &display, &sources)
+                    .await
+            }
+            _ => Vec::new(),
+        };
+        let related_text = related
+            .iter()
+            .map(|(location, preview)| format!("### {}\n```\n{}\n```\n", location, preview))
+            .collect::<String>();
+
+        let budget = self.input_budget().await;
+        let source_budget = budget
+            .saturating_sub(count_text_tokens(&related_text))
+            .max(budget / 2);
+        let batches = pack_batches(&sources, source_budget);
+
+        let mut usage = UsageTotals::default();
+        let summary = if batches.len() == 1 {
+            let prompt = build_path_summary_user_prompt(&display, &batches[0], &related_text, None);
+            let completion = self.complete(PATH_SUMMARY_SYSTEM_PROMPT, prompt).await?;
+            usage.record(&completion);
+            completion.text
+        } else {
+            let total = batches.len();
+            let prompts: Vec<String> = batches
+                .iter()
+                .enumerate()
+                .map(|(index, batch)| {
+                    // 相关片段只随第一批发送，避免重复占用预算
+                    let related = if index == 0 {
+                        related_text.as_str()
+                    } else {
+                        ""
+                    };
+                    build_path_summary_user_prompt(
+                        &display,
+                        batch,
+                        related,
+                        Some((index + 1, total)),
+                    )
+                })
+                .collect();
+            let partials: Vec<Completion> = futures::stream::iter(prompts)
+                .map(|prompt| self.complete(PATH_SUMMARY_SYSTEM_PROMPT, prompt))
+                .buffered(MAP_CONCURRENCY)
+                .try_collect()
+                .await?;
+            partials.iter().for_each(|c| usage.record(c));
+            let partials = partials.into_iter().map(|c| c.text).collect();
+            self.reduce(&display, partials, budget, &mut usage).await?
+        };
+
+        Ok(PathSummary {
+            path: display,
+            summary,
+            files: sources.into_iter().map(|s| s.display).collect(),
+            skipped_files,
+            related: related.into_iter().map(|(location, _)| location).collect(),
+            llm_calls: usage.calls,
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+        })
+    }
+
+    /// 合并分批摘要；合并提示词仍超出预算时先分组合并
+    async fn reduce(
+        &self,
+        display: &str,
+        mut partials: Vec<String>,
+        budget: usize,
+        usage: &mut UsageTotals,
+    ) -> AgentResult<String> {
+        for _ in 0..MAX_REDUCE_ROUNDS {
+            if partials.len() <= 1 {
+                break;
+            }
+            let prompt = build_path_summary_merge_prompt(display, &partials);
+            if count_text_tokens(&prompt) <= budget {
+                let completion = self
+                    .complete(PATH_SUMMARY_MERGE_SYSTEM_PROMPT, prompt)
+                    .await?;
+                usage.record(&completion);
+                return Ok(completion.text);
+            }
+
+            let mut merged = Vec::new();
+            for group in group_by_tokens(&partials, budget) {
+                if group.len() == 1 {
+                    merged.push(group[0].clone());
+                    continue;
+                }
+                let prompt = build_path_summary_merge_prompt(display, &group);
+                let completion = self
+                    .complete(PATH_SUMMARY_MERGE_SYSTEM_PROMPT, prompt)
+                    .await?;
+                usage.record(&completion);
+                merged.push(completion.text);
+            }
+            partials = merged;
+        }
+        Ok(partials.join("\n\n"))
+    }
+
+    async fn related_snippets(
+        &self,
+        path: &Path,
+        workspace_root: &Path,
+        base: &Path,
+        display: &str,
+        sources: &[SourceFile],
+    ) -> Vec<(String, String)> {
+        let Some(global) = crate::vector_db::commands::get_global_state() else {
+            return Vec::new();
+        };
+
+        // 用路径和首个文件开头作为查询，找目标之外引用或实现相关逻辑的代码
+        let head: String = sources[0].content.chars().take(500).collect();
+        let query = format!("{}\n{}", display, head);
+        let options = SearchOptions {
+            top_k: RELATED_SNIPPETS * 3,
+            ..SearchOptions::default()
+        };
+
+        match global
+            .search_engine
+            .search_in_workspace(workspace_root, &query, options)
+            .await
+        {
+            Ok(results) => results
+                .into_iter()
+                .filter(|r| !r.file_path.starts_with(path))
+                .take(RELATED_SNIPPETS)
+                .map(|r| {
+                    let location = format!(
+                        "{}:{}-{}",
+                        display_path(&r.file_path, base),
+                        r.span.line_start,
+                        r.span.line_end
+                    );
+                    (location, r.preview)
+                })
+                .collect(),
+            Err(e) => {
+                debug!("Related code lookup skipped: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn input_budget(&self) -> usize {
+        let context_window = AIModels::new(&self.database)
+            .find_by_id(&self.model_id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|model| model.options)
+            .and_then(|options| options.get("maxContextTokens").and_then(|v| v.as_u64()))
+            .map(|v| v as usize)
+            .unwrap_or(DEFAULT_CONTEXT_WINDOW);
+
+        ((context_window as f64 * CONTEXT_BUDGET_RATIO) as usize)
+            .saturating_sub(SUMMARY_MAX_TOKENS as usize)
+            .clamp(1_000, MAX_INPUT_TOKENS_PER_CALL)
+    }
+
+    async fn complete(&self, system: &str, prompt: String) -> AgentResult<Completion> {
+        let request = CreateMessageRequest {
+            model: self.model_id.clone(),
+            messages: vec![MessageParam {
+                role: MessageRole::User,
+                content: MessageContent::Text(prompt),
+            }],
+            max_tokens: SUMMARY_MAX_TOKENS,
+            system: Some(SystemPrompt::Text(system.to_string())),
+            tools: None,
+            temperature: Some(0.2),
+            stop_sequences: None,
+            thinking: None,
+            stream: false,
+            top_p: None,
+            top_k: None,
+            metadata: None,
+        };
+
+        let response = LLMService::new(Arc::clone(&self.database))
+            .call(request)
+            .await
+            .map_err(|e| {
+                AgentError::Internal(format!("Failed to call LLM for path summary: {}", e))
+            })?;
+
+        let text = response
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if text.trim().is_empty() {
+            return Err(AgentError::Internal(
+                "LLM path summary is empty".to_string(),
+            ));
+        }
+
+        Ok(Completion {
+            text: text.trim().to_string(),
+            input_tokens: response.usage.input_tokens,
+            output_tokens: response.usage.output_tokens,
+        })
+    }
+}
+
+/// 展示路径的基准目录：位于工作区内时相对工作区，否则相对目标自身所在目录
+fn display_base(path: &Path, workspace_root: Option<&Path>) -> PathBuf {
+    match workspace_root {
+        Some(root) if path.starts_with(root) => root.to_path_buf(),
+        _ if path.is_dir() => path.to_path_buf(),
+        _ => path.parent().unwrap_or(path).to_path_buf(),
+    }
+}
+
+fn display_path(path: &Path, base: &Path) -> String {
+    match path.strip_prefix(base) {
+        Ok(relative) if !relative.as_os_str().is_empty() => relative.display().to_string(),
+        _ => path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string()),
+    }
+}
+
+/// 读取目标下的文本文件（阻塞调用），返回文件与跳过数
+fn collect_sources(path: &Path, base: &Path) -> (Vec<SourceFile>, usize) {
+    let mut candidates = if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        collect_source_files(path, MAX_FILE_BYTES)
+    };
+    candidates.sort();
+
+    let mut skipped = candidates.len().saturating_sub(MAX_FILES);
+    candidates.truncate(MAX_FILES);
+
+    let mut sources = Vec::with_capacity(candidates.len());
+    for file in candidates {
+        let too_large = std::fs::metadata(&file).map_or(true, |m| m.len() > MAX_FILE_BYTES);
+        let content = if too_large {
+            None
+        } else {
+            std::fs::read(&file)
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+        };
+        match content {
+            Some(content) if content.trim().is_empty() => {}
+            Some(content) => sources.push(SourceFile {
+                display: display_path(&file, base),
+                content,
+            }),
+            None => skipped += 1,
+        }
+    }
+    (sources, skipped)
+}
+
+/// 按 token 预算把文件打包成若干批，单个超出预算的文件按行切分
+fn pack_batches(sources: &[SourceFile], budget: usize) -> Vec<String> {
+    let mut batches = Vec::new();
+    let mut current = String::new();
+    let mut current_tokens = 0usize;
+
+    for source in sources {
+        let rendered = source.render();
+        let tokens = count_text_tokens(&rendered);
+        let pieces = if tokens > budget {
+            split_oversized(source, budget)
+        } else {
+            vec![(rendered, tokens)]
+        };
+
+        for (piece, piece_tokens) in pieces {
+            if current_tokens + piece_tokens > budget && !current.is_empty() {
+                batches.push(std::mem::take(&mut current));
+                current_tokens = 0;
+            }
+            current.push_str(&piece);
+            current.push('\n');
+            current_tokens += piece_tokens;
+        }
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+fn split_oversized(source: &SourceFile, budget: usize) -> Vec<(String, usize)> {
+    let mut pieces = Vec::new();
+    let mut lines: Vec<&str> = Vec::new();
+    let mut tokens = 0usize;
+
+    let flush = |lines: &mut Vec<&str>, pieces: &mut Vec<(String, usize)>, tokens: usize| {
+        if lines.is_empty() {
+            return;
+        }
+        let piece = format!(
+            "### {} (part {})\n```\n{}\n```\n",
+            source.display,
+            pieces.len() + 1,
+            lines.join("\n")
+        );
+        pieces.push((piece, tokens));
+        lines.clear();
+    };
+
+    for line in source.content.lines() {
+        let line_tokens = count_text_tokens(line) + 1;
+        if tokens + line_tokens > budget && !lines.is_empty() {
+            flush(&mut lines, &mut pieces, tokens);
+            tokens = 0;
+        }
+        lines.push(line);
+        tokens += line_tokens;
+    }
+    flush(&mut lines, &mut pieces, tokens);
+    pieces
+}
+
+/// 把摘要按 token 预算分组，每组至少一个
+fn group_by_tokens(partials: &[String], budget: usize) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let mut current_tokens = 0usize;
+    for partial in partials {
+        let tokens = count_text_tokens(partial);
+        match groups.last_mut() {
+            Some(group) if current_tokens + tokens <= budget => {
+                group.push(partial.clone());
+                current_tokens += tokens;
+            }
+            _ => {
+                groups.push(vec![partial.clone()]);
+                current_tokens = tokens;
+            }
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(display: &str, lines: usize) -> SourceFile {
+        SourceFile {
+            display: display.to_string(),
+            content: (0..lines)
+                .map(|i| format!("fn function_{}() {{ do_something({}); }}", i, i))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    #[test]
+    fn packs_small_files_together_and_splits_oversized_ones() {
+        let sources = vec![source("a.rs", 3), source("b.rs", 3), source("big.rs", 400)];
+        let budget = 1_000;
+
+        let batches = pack_batches(&sources, budget);
+        assert!(batches.len() > 1);
+        assert!(batches[0].contains("### a.rs") && batches[0].contains("### b.rs"));
+        assert!(batches.iter().any(|b| b.contains("### big.rs (part 2)")));
+        for batch in &batches {
+            assert!(count_text_tokens(batch) <= budget + 50);
+        }
+
+        let everything = batches.concat();
+        assert!(everything.contains("function_0()"));
+        assert!(everything.contains("function_399()"));
+    }
+
+    #[test]
+    fn groups_partials_within_budget() {
+        let partials = vec!["one two three".to_string(); 5];
+        let per = count_text_tokens(&partials[0]);
+        let groups = group_by_tokens(&partials, per * 2);
+        assert_eq!(
+            groups.iter().map(|g| g.len()).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+    }
+
+    #[test]
+    fn displays_paths_relative_to_workspace() {
+        let root = Path::new("/repo");
+        let file = Path::new("/repo/src/lib.rs");
+        assert_eq!(
+            display_path(file, &display_base(file, Some(root))),
+            "src/lib.rs"
+        );
+        assert_eq!(display_path(root, root), "repo");
+    }
+}
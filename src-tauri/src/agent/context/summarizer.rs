@@ -12,6 +12,7 @@ use crate::llm::anthropic_types::{
     ContentBlock, CreateMessageRequest, MessageContent, MessageParam, SystemPrompt,
 };
 use crate::llm::service::LLMService;
+use crate::storage::cache::UnifiedCache;
 use crate::storage::DatabaseManager;
 
 const COMPRESSION_THRESHOLD: f32 = 0.85;
@@ -32,6 +33,7 @@ pub struct SessionSummarizer {
     session_id: i64,
     persistence: Arc<AgentPersistence>,
     repositories: Arc<DatabaseManager>,
+    cache: Arc<UnifiedCache>,
 }
 
 impl SessionSummarizer {
@@ -39,11 +41,13 @@ impl SessionSummarizer {
         session_id: i64,
         persistence: Arc<AgentPersistence>,
         repositories: Arc<DatabaseManager>,
+        cache: Arc<UnifiedCache>,
     ) -> Self {
         Self {
             session_id,
             persistence,
             repositories,
+            cache,
         }
     }
 
@@ -64,7 +68,7 @@ impl SessionSummarizer {
             return Ok(None);
         }
 
-        let context_window = self.lookup_context_window(model_id);
+        let context_window = self.lookup_context_window(model_id).await;
         let current_tokens = estimate_messages_tokens(messages);
 
         if !self.should_compress(current_tokens, context_window) {
@@ -125,6 +129,27 @@ impl SessionSummarizer {
         }
     }
 
+    /// 把给定的消息整体总结为一段文本，不写入会话摘要；用于主动压缩较早的轮次
+    pub async fn summarize_messages(
+        &self,
+        model_id: &str,
+        messages: &[MessageParam],
+    ) -> AgentResult<String> {
+        let request = self.build_summary_request(model_id, messages);
+        let response = LLMService::new(self.repositories())
+            .call(request)
+            .await
+            .map_err(|e| {
+                AgentError::Internal(format!("Failed to call LLM for summary generation: {}", e))
+            })?;
+
+        let summary_text = render_content_blocks(&response.content);
+        if summary_text.trim().is_empty() {
+            return Err(AgentError::Internal("LLM summary is empty".to_string()));
+        }
+        Ok(summary_text.trim().to_string())
+    }
+
     async fn summarize_conversation(
         &self,
         model_id: &str,
@@ -208,6 +233,7 @@ impl SessionSummarizer {
             tools: None,
             temperature: Some(0.3),
             stop_sequences: None,
+            thinking: None,
             stream: false,
             top_p: None,
             top_k: None,
@@ -259,28 +285,14 @@ impl SessionSummarizer {
         .await
     }
 
-    fn lookup_context_window(&self, model_id: &str) -> u32 {
-        // 同步获取模型配置中的 context window
-        let model_result = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                crate::storage::repositories::AIModels::new(&self.repositories)
-                    .find_by_id(model_id)
-                    .await
-            })
-        });
-
-        if let Ok(Some(model)) = model_result {
-            if let Some(options) = model.options {
-                if let Some(max_tokens) = options.get("maxContextTokens") {
-                    if let Some(value) = max_tokens.as_u64() {
-                        return value as u32;
-                    }
-                }
-            }
-        }
-
-        // 默认值：大多数现代 LLM 支持 128K tokens
-        128_000
+    async fn lookup_context_window(&self, model_id: &str) -> u32 {
+        crate::agent::react::orchestrator::model_context_window(
+            &self.repositories,
+            &self.cache,
+            model_id,
+        )
+        .await
+        .unwrap_or(crate::agent::react::orchestrator::DEFAULT_CONTEXT_WINDOW)
     }
 }
 
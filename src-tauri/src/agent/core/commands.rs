@@ -2,19 +2,34 @@
  * TaskExecutor Tauri命令接口（已迁移至 agent/core/commands）
  */
 
-use crate::agent::context::SummaryResult;
+use crate::agent::context::history_recall::{recall_enabled, RECALL_HISTORY_ENABLED_KEY};
+use crate::agent::context::{PathSummarizer, PathSummary, SummaryResult};
+use crate::agent::core::context::ToolCallResult;
 use crate::agent::core::executor::{
-    ExecuteTaskParams, FileContextStatus, TaskExecutor, TaskSummary,
+    BulkCancelReport, ConversationExport, EffectiveAgentConfig, ExecuteTaskParams,
+    ExecutionMessagesPage, FileContextStatus, FinalAnswer, MessageRebuildReport,
+    RenderedSystemPrompt, TaskExecutor, TaskResetReport, TaskSummary, MAX_RUNNING_TASKS_KEY,
+    MAX_RUNNING_TASKS_LIMIT,
 };
+use crate::agent::core::terminal_error::TerminalErrorCapture;
+use crate::agent::error::{ConversationImportError, TaskExecutorError};
+use crate::agent::react::ReactTrace;
+use crate::agent::state::session::MemorySnapshot;
+use crate::agent::tools::builtin::git_branch::GIT_BRANCH_PUSH_ENABLED_KEY;
+use crate::agent::tools::builtin::web_fetch::{WebFetchDomainPolicy, WEB_FETCH_DOMAINS_KEY};
 use crate::agent::tools::registry::ToolConfirmationDecision;
-use crate::agent::types::TaskEvent;
+use crate::agent::tools::write_preview::{preview_writes_enabled, PREVIEW_WRITES_ENABLED_KEY};
+use crate::agent::tools::WritePreviewDecision;
+use crate::agent::types::{CancelReason, SystemMessage, TaskEvent, AGENT_SYSTEM_MESSAGE_EVENT};
+use crate::mux::{get_mux, PaneId};
 use crate::storage::repositories::AppPreferences;
 use crate::storage::{DatabaseManager, UnifiedCache};
 use crate::utils::{EmptyData, TauriApiResult};
+use crate::workspace::WorkspaceService;
 use crate::{api_error, api_success};
 use serde::Deserialize;
 use std::sync::Arc;
-use tauri::{ipc::Channel, State};
+use tauri::{ipc::Channel, AppHandle, Emitter, Runtime, State};
 
 /// TaskExecutor状态管理
 pub struct TaskExecutorState {
@@ -43,14 +58,117 @@ pub async fn agent_execute_task(
     }
 }
 
-/// 取消任务
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainTerminalErrorParams {
+    pub pane_id: u32,
+    pub session_id: i64,
+    pub workspace_path: String,
+    pub model_id: String,
+}
+
+/// 从终端面板采集失败命令与输出尾部，作为初始提示词启动任务
+#[tauri::command]
+pub async fn agent_explain_terminal_error(
+    state: State<'_, TaskExecutorState>,
+    params: ExplainTerminalErrorParams,
+    channel: Channel<TaskEvent>,
+) -> TauriApiResult<EmptyData> {
+    let mux = get_mux();
+    let pane_id = PaneId::from(params.pane_id);
+    if !mux.pane_exists(pane_id) {
+        return Ok(api_error!("shell.pane_not_exist"));
+    }
+
+    let capture = TerminalErrorCapture::capture(&mux, pane_id);
+    if capture.is_empty() {
+        return Ok(api_error!("agent.terminal_output_empty"));
+    }
+
+    let task_params = ExecuteTaskParams {
+        workspace_path: params.workspace_path,
+        session_id: params.session_id,
+        user_prompt: capture.to_prompt(),
+        model_id: params.model_id,
+        images: None,
+        on_max_iterations: None,
+        tool_dedup_window: None,
+        compaction_policy: None,
+        compaction_every_turns: None,
+        compaction_keep_turns: None,
+        filesystem_root: None,
+    };
+
+    match state.executor.execute_task(task_params, channel).await {
+        Ok(_context) => Ok(api_success!()),
+        Err(e) => {
+            tracing::error!("Failed to start explain-error task: {}", e);
+            Ok(api_error!("agent.execute_failed"))
+        }
+    }
+}
+
+/// 单轮 LLM 总结文件或目录；相对路径按会话所属工作区解析
+#[tauri::command]
+pub async fn agent_summarize_path(
+    state: State<'_, TaskExecutorState>,
+    path: String,
+    session_id: i64,
+    model_id: String,
+    include_related: Option<bool>,
+) -> TauriApiResult<PathSummary> {
+    let workspace_root = match state
+        .executor
+        .agent_persistence()
+        .sessions()
+        .get(session_id)
+        .await
+    {
+        Ok(session) => session
+            .map(|s| s.workspace_path)
+            .filter(|p| !p.is_empty() && p != crate::workspace::UNGROUPED_WORKSPACE_PATH)
+            .map(std::path::PathBuf::from),
+        Err(e) => {
+            tracing::error!("Failed to load session {}: {}", session_id, e);
+            return Ok(api_error!("agent.summarize_path_failed"));
+        }
+    };
+
+    let target = std::path::PathBuf::from(&path);
+    let target = match &workspace_root {
+        Some(root) if target.is_relative() => root.join(target),
+        _ => target,
+    };
+    if !target.exists() {
+        return Ok(api_error!("agent.summarize_path_not_found"));
+    }
+
+    let summarizer = PathSummarizer::new(state.executor.database(), model_id);
+    match summarizer
+        .summarize(
+            &target,
+            workspace_root.as_deref(),
+            include_related.unwrap_or(true),
+        )
+        .await
+    {
+        Ok(summary) => Ok(api_success!(summary)),
+        Err(e) => {
+            tracing::error!("Failed to summarize path {}: {}", target.display(), e);
+            Ok(api_error!("agent.summarize_path_failed"))
+        }
+    }
+}
+
+/// 取消任务；kind 为机器可读的取消原因，reason 为附带的说明文字
 #[tauri::command]
 pub async fn agent_cancel_task(
     state: State<'_, TaskExecutorState>,
     task_id: String,
     reason: Option<String>,
+    kind: Option<CancelReason>,
 ) -> TauriApiResult<EmptyData> {
-    match state.executor.cancel_task(&task_id, reason).await {
+    match state.executor.cancel_task(&task_id, kind, reason).await {
         Ok(_) => Ok(api_success!()),
         Err(e) => {
             tracing::error!("Failed to cancel task: {}", e);
@@ -59,6 +177,307 @@ pub async fn agent_cancel_task(
     }
 }
 
+/// 取消全部运行中与排队中的任务，没有任务时返回 0；完成后广播一条系统消息
+#[tauri::command]
+pub async fn agent_cancel_all_tasks<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, TaskExecutorState>,
+) -> TauriApiResult<BulkCancelReport> {
+    let report = state.executor.cancel_all_tasks().await;
+    if report.cancelled > 0 {
+        let message = SystemMessage {
+            message: format!(
+                "Cancelled {} task(s), {} of them still queued",
+                report.cancelled, report.queued
+            ),
+            task_ids: report.task_ids.clone(),
+            timestamp: chrono::Utc::now(),
+        };
+        if let Err(e) = app.emit(AGENT_SYSTEM_MESSAGE_EVENT, &message) {
+            tracing::warn!("Failed to emit bulk cancel message: {}", e);
+        }
+    }
+    Ok(api_success!(report))
+}
+
+/// 强制把卡住的任务重置为终态（崩溃恢复用）；中止仍在运行的任务需要 confirm
+#[tauri::command]
+pub async fn agent_force_reset_task(
+    state: State<'_, TaskExecutorState>,
+    task_id: String,
+    confirm: Option<bool>,
+) -> TauriApiResult<TaskResetReport> {
+    match state
+        .executor
+        .force_reset_task(&task_id, confirm.unwrap_or(false))
+        .await
+    {
+        Ok(report) => Ok(api_success!(report)),
+        Err(TaskExecutorError::TaskNotFound(_)) => Ok(api_error!("agent.task_not_found")),
+        Err(TaskExecutorError::TaskStillRunning(_)) => Ok(api_error!("agent.task_still_running")),
+        Err(e) => {
+            tracing::error!("Failed to force reset task: {}", e);
+            Ok(api_error!("agent.force_reset_failed"))
+        }
+    }
+}
+
+/// 用 UI 消息重建会话的模型侧历史（execution_messages 损坏时的修复手段）；
+/// confirm 为 true 时才会覆盖已存储的消息，否则只返回预览
+#[tauri::command]
+pub async fn agent_rebuild_messages_from_ui(
+    state: State<'_, TaskExecutorState>,
+    session_id: i64,
+    confirm: Option<bool>,
+) -> TauriApiResult<MessageRebuildReport> {
+    match state
+        .executor
+        .rebuild_messages_from_ui(session_id, confirm.unwrap_or(false))
+        .await
+    {
+        Ok(Some(report)) => Ok(api_success!(report)),
+        Ok(None) => Ok(api_error!("agent.rebuild_messages_empty")),
+        Err(TaskExecutorError::TaskStillRunning(_)) => {
+            Ok(api_error!("agent.rebuild_messages_session_busy"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to rebuild messages from UI: {}", e);
+            Ok(api_error!("agent.rebuild_messages_failed"))
+        }
+    }
+}
+
+/// 把会话导出为带版本号的 JSON，可用 agent_import_conversation 导入
+#[tauri::command]
+pub async fn agent_export_conversation(
+    state: State<'_, TaskExecutorState>,
+    session_id: i64,
+) -> TauriApiResult<ConversationExport> {
+    match state.executor.export_conversation(session_id).await {
+        Ok(Some(export)) => Ok(api_success!(export)),
+        Ok(None) => Ok(api_error!("agent.export_conversation_not_found")),
+        Err(e) => {
+            tracing::error!("Failed to export conversation: {}", e);
+            Ok(api_error!("agent.export_conversation_failed"))
+        }
+    }
+}
+
+/// 从导出的 JSON 创建新会话，返回新会话 ID；workspace_path 为空时沿用导出时的工作区
+#[tauri::command]
+pub async fn agent_import_conversation(
+    state: State<'_, TaskExecutorState>,
+    json: String,
+    workspace_path: Option<String>,
+) -> TauriApiResult<i64> {
+    match state
+        .executor
+        .import_conversation(&json, workspace_path)
+        .await
+    {
+        Ok(session_id) => Ok(api_success!(session_id)),
+        Err(ConversationImportError::UnsupportedVersion(version)) => {
+            tracing::warn!("Unsupported conversation export version: {}", version);
+            Ok(api_error!("agent.import_conversation_unsupported_version"))
+        }
+        Err(e @ ConversationImportError::InvalidFormat(_))
+        | Err(e @ ConversationImportError::InvalidStructure(_)) => {
+            tracing::warn!("Rejected conversation import: {}", e);
+            Ok(api_error!("agent.import_conversation_invalid"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to import conversation: {}", e);
+            Ok(api_error!("agent.import_conversation_failed"))
+        }
+    }
+}
+
+/// 获取会话最新的最终回答文本（不含思考与工具调用），供朗读、复制等集成使用；
+/// 会话没有助手文字时返回 null
+#[tauri::command]
+pub async fn agent_get_final_answer(
+    state: State<'_, TaskExecutorState>,
+    session_id: i64,
+) -> TauriApiResult<Option<FinalAnswer>> {
+    match state.executor.final_answer(session_id).await {
+        Ok(answer) => Ok(api_success!(answer)),
+        Err(e) => {
+            tracing::error!("Failed to load final answer: {}", e);
+            Ok(api_error!("agent.final_answer_failed"))
+        }
+    }
+}
+
+/// 查看会话发起任务时实际生效的配置及每项来源；model_id 为前端当前的全局默认模型
+#[tauri::command]
+pub async fn agent_get_effective_config(
+    state: State<'_, TaskExecutorState>,
+    database: State<'_, Arc<DatabaseManager>>,
+    session_id: i64,
+    model_id: Option<String>,
+) -> TauriApiResult<EffectiveAgentConfig> {
+    match WorkspaceService::new(Arc::clone(&database))
+        .get_session(session_id)
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return Ok(api_error!("workspace.session_not_found")),
+        Err(e) => {
+            tracing::error!("Failed to load session for effective config: {}", e);
+            return Ok(api_error!("agent.effective_config_failed"));
+        }
+    }
+
+    let config = state.executor.effective_config(session_id, model_id).await;
+    Ok(api_success!(config))
+}
+
+/// 查看会话当前的 system prompt（只读，不调用模型）；内容过长时按 offset/limit 分页
+#[tauri::command]
+pub async fn agent_get_rendered_system_prompt(
+    state: State<'_, TaskExecutorState>,
+    session_id: i64,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> TauriApiResult<RenderedSystemPrompt> {
+    match state
+        .executor
+        .rendered_system_prompt(session_id, offset.unwrap_or(0), limit)
+        .await
+    {
+        Ok(Some(prompt)) => Ok(api_success!(prompt)),
+        Ok(None) => Ok(api_error!("workspace.session_not_found")),
+        Err(e) => {
+            tracing::error!("Failed to render system prompt: {}", e);
+            Ok(api_error!("agent.system_prompt_failed"))
+        }
+    }
+}
+
+/// 暂停任务；abort_current_step 为 true 时中断当前工具调用（软停止）
+#[tauri::command]
+pub async fn agent_pause_task(
+    state: State<'_, TaskExecutorState>,
+    task_id: String,
+    abort_current_step: Option<bool>,
+) -> TauriApiResult<EmptyData> {
+    match state
+        .executor
+        .pause_task(&task_id, abort_current_step.unwrap_or(false))
+        .await
+    {
+        Ok(()) => Ok(api_success!()),
+        Err(e) => {
+            tracing::error!("Failed to pause task: {}", e);
+            Ok(api_error!("agent.task_not_found"))
+        }
+    }
+}
+
+/// 恢复暂停的任务
+#[tauri::command]
+pub async fn agent_resume_task(
+    state: State<'_, TaskExecutorState>,
+    task_id: String,
+) -> TauriApiResult<EmptyData> {
+    match state.executor.resume_task(&task_id).await {
+        Ok(()) => Ok(api_success!()),
+        Err(e) => {
+            tracing::error!("Failed to resume task: {}", e);
+            Ok(api_error!("agent.task_not_found"))
+        }
+    }
+}
+
+/// 取消单个正在执行的工具调用（不取消任务）
+#[tauri::command]
+pub async fn agent_cancel_tool(
+    state: State<'_, TaskExecutorState>,
+    task_id: String,
+    tool_id: String,
+) -> TauriApiResult<EmptyData> {
+    match state.executor.cancel_tool(&task_id, &tool_id).await {
+        Ok(true) => Ok(api_success!()),
+        Ok(false) => Ok(api_error!("agent.tool_not_running")),
+        Err(e) => {
+            tracing::error!("Failed to cancel tool: {}", e);
+            Ok(api_error!("agent.task_not_found"))
+        }
+    }
+}
+
+/// 按原参数重跑最近一轮中失败的工具调用，新结果替换旧结果后任务可直接继续
+#[tauri::command]
+pub async fn agent_retry_tool(
+    state: State<'_, TaskExecutorState>,
+    task_id: String,
+    tool_id: String,
+) -> TauriApiResult<ToolCallResult> {
+    match state.executor.retry_tool(&task_id, &tool_id).await {
+        Ok(Some(result)) => Ok(api_success!(result)),
+        Ok(None) => Ok(api_error!("agent.tool_not_in_latest_iteration")),
+        Err(TaskExecutorError::TaskNotFound(_)) => Ok(api_error!("agent.task_not_found")),
+        Err(TaskExecutorError::ToolAlreadySucceeded(_)) => {
+            Ok(api_error!("agent.tool_already_succeeded"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to retry tool: {}", e);
+            Ok(api_error!("agent.tool_retry_failed"))
+        }
+    }
+}
+
+/// 查看运行中任务的压缩记忆（只读）
+#[tauri::command]
+pub async fn agent_get_memory(
+    state: State<'_, TaskExecutorState>,
+    task_id: String,
+) -> TauriApiResult<MemorySnapshot> {
+    let ctx = state
+        .executor
+        .active_tasks()
+        .get(&task_id)
+        .map(|entry| Arc::clone(entry.value()));
+
+    let ctx = match ctx {
+        Some(ctx) => ctx,
+        None => return Ok(api_error!("agent.task_not_found")),
+    };
+
+    let message_count = ctx.get_messages().await.len();
+    let snapshot = ctx
+        .session()
+        .memory_snapshot(ctx.current_iteration().await, message_count)
+        .await;
+    Ok(api_success!(snapshot))
+}
+
+/// 查看任务的 ReAct 推理/工具调用轨迹（调试用，区别于 UI 步骤）；
+/// 支持运行中与最近结束的任务，last 只返回最近 N 轮
+#[tauri::command]
+pub async fn agent_get_react_trace(
+    state: State<'_, TaskExecutorState>,
+    task_id: String,
+    last: Option<usize>,
+) -> TauriApiResult<ReactTrace> {
+    let ctx = state
+        .executor
+        .active_tasks()
+        .get(&task_id)
+        .map(|entry| Arc::clone(entry.value()));
+
+    if let Some(ctx) = ctx {
+        let runtime = ctx.states.react_runtime.read().await;
+        return Ok(api_success!(ReactTrace::from_runtime(
+            &task_id, &runtime, true, last
+        )));
+    }
+    match state.executor.finished_trace(&task_id) {
+        Some(trace) => Ok(api_success!(trace.keep_last(last))),
+        None => Ok(api_error!("agent.task_not_found")),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ToolConfirmationParams {
@@ -95,6 +514,42 @@ pub async fn agent_tool_confirm(
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WritePreviewParams {
+    pub task_id: String,
+    pub request_id: String,
+    /// 各文件的决定，未列出的文件按拒绝处理
+    pub decisions: Vec<WritePreviewDecision>,
+}
+
+/// 回传写入预览的审阅结果
+#[tauri::command]
+pub async fn agent_resolve_write_preview(
+    state: State<'_, TaskExecutorState>,
+    params: WritePreviewParams,
+) -> TauriApiResult<EmptyData> {
+    let ctx = state
+        .executor
+        .active_tasks()
+        .get(&params.task_id)
+        .map(|entry| Arc::clone(entry.value()));
+
+    let ctx = match ctx {
+        Some(ctx) => ctx,
+        None => return Ok(api_error!("agent.task_not_found")),
+    };
+
+    if ctx
+        .tool_registry()
+        .resolve_write_preview(&params.request_id, params.decisions)
+    {
+        Ok(api_success!())
+    } else {
+        Ok(api_error!("agent.write_preview.not_found"))
+    }
+}
+
 /// 列出任务
 #[tauri::command]
 pub async fn agent_list_tasks(
@@ -111,6 +566,65 @@ pub async fn agent_list_tasks(
     }
 }
 
+/// 读取可同时运行的任务数上限
+#[tauri::command]
+pub async fn agent_get_max_concurrent_tasks(
+    state: State<'_, TaskExecutorState>,
+) -> TauriApiResult<usize> {
+    Ok(api_success!(state.executor.scheduler().max_running()))
+}
+
+/// 设置可同时运行的任务数上限，超出的任务排队等待
+#[tauri::command]
+pub async fn agent_set_max_concurrent_tasks(
+    limit: usize,
+    state: State<'_, TaskExecutorState>,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<usize> {
+    if limit == 0 || limit > MAX_RUNNING_TASKS_LIMIT {
+        return Ok(api_error!("agent.invalid_max_concurrent_tasks"));
+    }
+    if let Err(e) = AppPreferences::new(&database)
+        .set(MAX_RUNNING_TASKS_KEY, Some(&limit.to_string()))
+        .await
+    {
+        tracing::error!("Failed to save max concurrent tasks: {}", e);
+        return Ok(api_error!("agent.save_max_concurrent_tasks_failed"));
+    }
+    let applied = state.executor.scheduler().set_max_running(limit);
+    Ok(api_success!(applied))
+}
+
+/// 执行消息单页默认/最大条数
+const EXECUTION_MESSAGES_DEFAULT_LIMIT: i64 = 200;
+const EXECUTION_MESSAGES_MAX_LIMIT: i64 = 1000;
+
+/// 获取任务持久化的执行消息（模型侧历史，区别于 UI 消息）
+#[tauri::command]
+pub async fn agent_get_execution_messages(
+    state: State<'_, TaskExecutorState>,
+    task_id: String,
+    offset: Option<i64>,
+    limit: Option<i64>,
+) -> TauriApiResult<ExecutionMessagesPage> {
+    let offset = offset.unwrap_or(0).max(0);
+    let limit = limit
+        .unwrap_or(EXECUTION_MESSAGES_DEFAULT_LIMIT)
+        .clamp(1, EXECUTION_MESSAGES_MAX_LIMIT);
+
+    match state
+        .executor
+        .get_execution_messages(&task_id, offset, limit)
+        .await
+    {
+        Ok(page) => Ok(api_success!(page)),
+        Err(e) => {
+            tracing::error!("Failed to load execution messages: {}", e);
+            Ok(api_error!("agent.execution_messages_failed"))
+        }
+    }
+}
+
 /// 获取文件上下文状态
 #[tauri::command]
 pub async fn agent_get_file_context_status(
@@ -164,6 +678,127 @@ pub async fn agent_set_user_rules(
     }
 }
 
+/// 读取 web_fetch 的域名白名单/黑名单
+#[tauri::command]
+pub async fn agent_get_web_fetch_domains(
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<WebFetchDomainPolicy> {
+    Ok(api_success!(WebFetchDomainPolicy::load(&database).await))
+}
+
+/// 保存 web_fetch 的域名白名单/黑名单
+#[tauri::command]
+pub async fn agent_set_web_fetch_domains(
+    policy: WebFetchDomainPolicy,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<WebFetchDomainPolicy> {
+    let policy = policy.normalized();
+    let raw = match serde_json::to_string(&policy) {
+        Ok(raw) => raw,
+        Err(e) => {
+            tracing::error!("Failed to serialize web fetch domain policy: {}", e);
+            return Ok(api_error!("agent.web_fetch.save_failed"));
+        }
+    };
+    match AppPreferences::new(&database)
+        .set(WEB_FETCH_DOMAINS_KEY, Some(&raw))
+        .await
+    {
+        Ok(_) => Ok(api_success!(policy)),
+        Err(e) => {
+            tracing::error!("Failed to save web fetch domain policy: {}", e);
+            Ok(api_error!("agent.web_fetch.save_failed"))
+        }
+    }
+}
+
+/// 是否允许向量化对话消息供 recall_history 工具检索
+#[tauri::command]
+pub async fn agent_get_recall_history_enabled(
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<bool> {
+    Ok(api_success!(recall_enabled(&database).await))
+}
+
+/// 开关历史回忆；关闭后不再向量化新消息，已保存的向量保留
+#[tauri::command]
+pub async fn agent_set_recall_history_enabled(
+    enabled: bool,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<EmptyData> {
+    let value = if enabled { "true" } else { "false" };
+    match AppPreferences::new(&database)
+        .set(RECALL_HISTORY_ENABLED_KEY, Some(value))
+        .await
+    {
+        Ok(_) => Ok(api_success!()),
+        Err(e) => {
+            tracing::error!("Failed to save recall history setting: {}", e);
+            Ok(api_error!("agent.recall_history.save_failed"))
+        }
+    }
+}
+
+/// git_branch 工具是否允许推送分支
+#[tauri::command]
+pub async fn agent_get_git_branch_push_enabled(
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<bool> {
+    let enabled = AppPreferences::new(&database)
+        .get(GIT_BRANCH_PUSH_ENABLED_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true");
+    Ok(api_success!(enabled))
+}
+
+/// 开关 git_branch 工具的推送能力；强制推送始终不支持
+#[tauri::command]
+pub async fn agent_set_git_branch_push_enabled(
+    enabled: bool,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<EmptyData> {
+    let value = if enabled { "true" } else { "false" };
+    match AppPreferences::new(&database)
+        .set(GIT_BRANCH_PUSH_ENABLED_KEY, Some(value))
+        .await
+    {
+        Ok(_) => Ok(api_success!()),
+        Err(e) => {
+            tracing::error!("Failed to save git branch push setting: {}", e);
+            Ok(api_error!("agent.git_branch.save_failed"))
+        }
+    }
+}
+
+/// agent 写文件前是否先展示 diff 预览
+#[tauri::command]
+pub async fn agent_get_preview_writes_enabled(
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<bool> {
+    Ok(api_success!(preview_writes_enabled(&database).await))
+}
+
+/// 开关写入预览
+#[tauri::command]
+pub async fn agent_set_preview_writes_enabled(
+    enabled: bool,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<EmptyData> {
+    let value = if enabled { "true" } else { "false" };
+    match AppPreferences::new(&database)
+        .set(PREVIEW_WRITES_ENABLED_KEY, Some(value))
+        .await
+    {
+        Ok(_) => Ok(api_success!()),
+        Err(e) => {
+            tracing::error!("Failed to save write preview setting: {}", e);
+            Ok(api_error!("agent.write_preview.save_failed"))
+        }
+    }
+}
+
 /// 手动触发会话摘要
 #[tauri::command]
 pub async fn agent_trigger_session_summary(
@@ -7,7 +7,7 @@ use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::ipc::Channel;
@@ -16,8 +16,8 @@ use tokio::time::sleep;
 
 use self::chain::Chain;
 use self::states::{ExecutionState, PlanningState, TaskStates};
-use crate::agent::config::{AgentConfig, TaskExecutionConfig};
-use crate::agent::context::FileContextTracker;
+use crate::agent::config::TaskExecutionConfig;
+use crate::agent::context::{history_recall, FileContextTracker};
 use crate::agent::core::executor::ImageAttachment;
 use crate::agent::core::status::AgentTaskStatus;
 use crate::agent::error::{TaskExecutorError, TaskExecutorResult};
@@ -28,10 +28,10 @@ use crate::agent::state::manager::{
     StateEventEmitter, StateManager, TaskState, TaskStatus, TaskThresholds,
 };
 use crate::agent::state::session::SessionContext;
-use crate::agent::tools::ToolRegistry;
+use crate::agent::tools::{ToolRegistry, ToolResultCache};
 use crate::agent::types::{
-    Block, ErrorBlock, MessageRole as UiMessageRole, MessageStatus, TaskDetail, TaskEvent,
-    TokenUsage, ToolStatus, UserImageBlock, UserTextBlock,
+    Block, CancelReason, ErrorBlock, Message, MessageRole as UiMessageRole, MessageStatus,
+    TaskDetail, TaskEvent, TokenUsage, ToolStatus, UserImageBlock, UserTextBlock,
 };
 use crate::agent::utils::tokenizer::count_text_tokens;
 use crate::checkpoint::CheckpointService;
@@ -54,6 +54,7 @@ pub struct TaskContext {
     state_manager: Arc<StateManager>,
     checkpoint_service: Option<Arc<CheckpointService>>,
     active_checkpoint: Arc<RwLock<Option<ActiveCheckpoint>>>,
+    tool_result_cache: parking_lot::Mutex<ToolResultCache>,
 
     pub(crate) states: TaskStates,
 
@@ -72,15 +73,15 @@ impl TaskContext {
         agent_persistence: Arc<AgentPersistence>,
         checkpoint_service: Option<Arc<CheckpointService>>,
     ) -> TaskExecutorResult<Self> {
-        let agent_config = AgentConfig::default();
+        // 阈值与有效配置同源，避免上报值与实际生效值不一致
         let runtime_config = ReactRuntimeConfig {
-            max_iterations: agent_config.max_react_num,
-            max_consecutive_errors: agent_config.max_react_error_streak,
+            max_iterations: config.max_iterations,
+            max_consecutive_errors: config.max_errors,
         };
 
         let thresholds = TaskThresholds {
-            max_consecutive_errors: agent_config.max_react_error_streak,
-            max_iterations: agent_config.max_react_num,
+            max_consecutive_errors: config.max_errors,
+            max_iterations: config.max_iterations,
         };
 
         let record = execution;
@@ -103,7 +104,7 @@ impl TaskContext {
             session_id,
             PathBuf::from(&normalized_workspace),
             user_prompt.clone(),
-            config,
+            config.clone(),
             Arc::clone(&repositories),
             Arc::clone(&agent_persistence),
         ));
@@ -113,6 +114,7 @@ impl TaskContext {
         let react_runtime = ReactRuntime::new(runtime_config);
 
         let states = TaskStates::new(execution, planning, react_runtime, progress_channel);
+        let tool_dedup_window = config.tool_dedup_window;
 
         Ok(Self {
             task_id: Arc::from(task_id.as_str()),
@@ -125,6 +127,7 @@ impl TaskContext {
             state_manager: Arc::new(StateManager::new(task_state, StateEventEmitter::new())),
             checkpoint_service,
             active_checkpoint: Arc::new(RwLock::new(None)),
+            tool_result_cache: parking_lot::Mutex::new(ToolResultCache::new(tool_dedup_window)),
             states,
             pause_status: AtomicU8::new(0),
         })
@@ -160,6 +163,15 @@ impl TaskContext {
         Ok(())
     }
 
+    pub fn checkpoint_service(&self) -> Option<Arc<CheckpointService>> {
+        self.checkpoint_service.clone()
+    }
+
+    /// 任务开始时创建的 checkpoint；未启用 checkpoint 或尚未初始化时为 None
+    pub(crate) async fn active_checkpoint(&self) -> Option<ActiveCheckpoint> {
+        self.active_checkpoint.read().await.clone()
+    }
+
     pub async fn snapshot_file_before_edit(&self, path: &Path) -> TaskExecutorResult<()> {
         let service = match &self.checkpoint_service {
             Some(service) => Arc::clone(service),
@@ -324,11 +336,22 @@ impl TaskContext {
             || errors >= self.config.max_errors
     }
 
+    /// 当前迭代数是否已达到配置的上限
+    pub async fn reached_max_iterations(&self) -> bool {
+        let iteration = self.states.execution.read().await.record.current_iteration as u32;
+        iteration >= self.config.max_iterations
+    }
+
     /// Access the execution configuration (零成本访问).
     pub fn config(&self) -> &TaskExecutionConfig {
         &self.config
     }
 
+    /// 跨迭代复用的只读工具结果
+    pub(crate) fn tool_result_cache(&self) -> &parking_lot::Mutex<ToolResultCache> {
+        &self.tool_result_cache
+    }
+
     /// Access repositories (used by LLM/tool bridges).
     pub fn repositories(&self) -> Arc<DatabaseManager> {
         self.session.repositories()
@@ -396,6 +419,10 @@ impl TaskContext {
         planning.root_task_id = Some(root_task_id.unwrap_or(parent_task_id));
     }
 
+    pub async fn children(&self) -> Vec<String> {
+        self.states.planning.read().await.children.clone()
+    }
+
     pub async fn add_child(&self, child_task_id: String) {
         let mut planning = self.states.planning.write().await;
         if !planning.children.contains(&child_task_id) {
@@ -451,9 +478,10 @@ impl TaskContext {
         Ok(())
     }
 
-    /// 中止任务执行
+    /// 中止任务执行并记录原因
     /// 简化版：只需设置 aborted 标志
-    pub fn abort(&self) {
+    pub fn abort(&self, reason: CancelReason) {
+        *self.states.cancel_reason.lock() = Some(reason);
         self.states.aborted.store(true, Ordering::SeqCst);
 
         // 标记 react 运行时为中止状态
@@ -469,9 +497,29 @@ impl TaskContext {
         self.states.aborted.load(Ordering::SeqCst)
     }
 
-    pub fn set_pause(&self, paused: bool, _abort_current_step: bool) {
+    /// 最近一次取消的原因
+    pub fn cancel_reason(&self) -> Option<CancelReason> {
+        *self.states.cancel_reason.lock()
+    }
+
+    /// 记录中断当前步骤的原因（如工具超时），之后未指明原因的取消沿用它
+    pub fn record_cancel_reason(&self, reason: CancelReason) {
+        *self.states.cancel_reason.lock() = Some(reason);
+    }
+
+    /// 暂停或恢复任务；暂停时 abort_current_step 为 true 即软停止，
+    /// 取消正在执行的工具调用并记录 SoftStop 原因。恢复时清除已记录的原因
+    pub fn set_pause(&self, paused: bool, abort_current_step: bool) {
         let new_status = if paused { 1 } else { 0 };
         self.pause_status.store(new_status, Ordering::SeqCst);
+        if !paused {
+            *self.states.cancel_reason.lock() = None;
+        } else if abort_current_step {
+            self.record_cancel_reason(CancelReason::SoftStop);
+            for entry in self.states.step_tokens.iter() {
+                entry.value().cancel();
+            }
+        }
     }
 
     /// 为 LLM 流创建取消令牌
@@ -481,20 +529,49 @@ impl TaskContext {
         let aborted = Arc::clone(&self.states.aborted);
         let child_token = token.clone();
 
-        // 监控 aborted 标志，如果被设置则取消 token
+        // 监控 aborted 标志，如果被设置则取消 token；token 被其他途径取消（如请求超时）后退出
         tokio::spawn(async move {
             loop {
                 if aborted.load(Ordering::SeqCst) {
                     child_token.cancel();
                     break;
                 }
-                sleep(Duration::from_millis(100)).await;
+                tokio::select! {
+                    _ = child_token.cancelled() => break,
+                    _ = sleep(Duration::from_millis(100)) => {}
+                }
             }
         });
 
         token
     }
 
+    /// 为指定工具调用注册取消令牌
+    /// 每个 tool_id 独立持有一个 token，取消其中一个不会影响其他调用
+    pub fn register_step_token(&self, tool_id: &str) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.states
+            .step_tokens
+            .insert(tool_id.to_string(), token.clone());
+        token
+    }
+
+    /// 工具调用结束后移除其取消令牌
+    pub fn release_step_token(&self, tool_id: &str) {
+        self.states.step_tokens.remove(tool_id);
+    }
+
+    /// 取消单个正在执行的工具调用，返回是否找到对应令牌
+    pub fn cancel_step(&self, tool_id: &str) -> bool {
+        match self.states.step_tokens.get(tool_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Add assistant message using Anthropic-native types (text and/or tool uses).
     pub async fn add_assistant_message(
         &self,
@@ -503,9 +580,13 @@ impl TaskContext {
     ) -> TaskExecutorResult<()> {
         let content: MessageContent = match (text, tool_calls) {
             (Some(t), Some(mut calls)) => {
-                // Put text as a Text block, then append tool_use blocks
+                // Thinking blocks must stay first, then the Text block, then tool_use blocks
+                let text_index = calls
+                    .iter()
+                    .take_while(|block| matches!(block, ContentBlock::Thinking { .. }))
+                    .count();
                 calls.insert(
-                    0,
+                    text_index,
                     ContentBlock::Text {
                         text: t,
                         cache_control: None,
@@ -539,9 +620,7 @@ impl TaskContext {
             .iter()
             .map(|r| ContentBlock::ToolResult {
                 tool_use_id: r.call_id.clone(),
-                content: Some(ToolResultContent::Text(
-                    serde_json::to_string(&r.result).unwrap_or_else(|_| "{}".to_string()),
-                )),
+                content: Some(ToolResultContent::Text(r.model_content())),
                 is_error: Some(r.status != crate::agent::tools::ToolResultStatus::Success),
             })
             .collect();
@@ -565,6 +644,108 @@ impl TaskContext {
         Ok(())
     }
 
+    /// 在最近一次带工具调用的 assistant 消息中查找 tool_id，返回 (工具名, 参数)
+    pub async fn find_latest_tool_call(&self, tool_id: &str) -> Option<(String, Value)> {
+        let exec = self.states.execution.read().await;
+        let latest_calls = exec.messages.iter().rev().find_map(|msg| {
+            if msg.role != AnthropicRole::Assistant {
+                return None;
+            }
+            match &msg.content {
+                MessageContent::Blocks(blocks)
+                    if blocks
+                        .iter()
+                        .any(|b| matches!(b, ContentBlock::ToolUse { .. })) =>
+                {
+                    Some(blocks)
+                }
+                _ => None,
+            }
+        })?;
+
+        latest_calls.iter().find_map(|block| match block {
+            ContentBlock::ToolUse { id, name, input } if id == tool_id => {
+                Some((name.clone(), input.clone()))
+            }
+            _ => None,
+        })
+    }
+
+    /// 已记录的某个工具调用结果的状态
+    pub async fn tool_result_status(
+        &self,
+        call_id: &str,
+    ) -> Option<crate::agent::tools::ToolResultStatus> {
+        let exec = self.states.execution.read().await;
+        exec.tool_results
+            .iter()
+            .find(|r| r.call_id == call_id)
+            .map(|r| r.status)
+    }
+
+    /// 用重新执行的结果替换历史中同一 tool_use_id 的结果，模型下一轮看到的是新结果
+    pub async fn replace_tool_result(&self, result: ToolCallResult) -> TaskExecutorResult<()> {
+        let new_block = ContentBlock::ToolResult {
+            tool_use_id: result.call_id.clone(),
+            content: Some(ToolResultContent::Text(result.model_content())),
+            is_error: Some(result.status != crate::agent::tools::ToolResultStatus::Success),
+        };
+
+        if let Ok(serialized) = serde_json::to_string(&result) {
+            let updated = self
+                .agent_persistence()
+                .execution_messages()
+                .update_tool_result(
+                    &self.task_id,
+                    &result.call_id,
+                    &serialized,
+                    i64::try_from(count_text_tokens(&serialized)).unwrap_or(i64::MAX),
+                )
+                .await
+                .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+            if !updated {
+                self.append_message(MessageRole::Tool, &serialized, false)
+                    .await?;
+            }
+        }
+
+        let mut exec = self.states.execution.write().await;
+        let replaced = exec.messages.iter_mut().rev().any(|msg| {
+            let MessageContent::Blocks(blocks) = &mut msg.content else {
+                return false;
+            };
+            match blocks.iter_mut().find(|b| {
+                matches!(
+                    b,
+                    ContentBlock::ToolResult { tool_use_id, .. } if *tool_use_id == result.call_id
+                )
+            }) {
+                Some(slot) => {
+                    *slot = new_block.clone();
+                    true
+                }
+                None => false,
+            }
+        });
+        if !replaced {
+            // 原结果尚未写入（例如执行中途失败），按正常流程追加
+            exec.messages.push(MessageParam {
+                role: AnthropicRole::User,
+                content: MessageContent::Blocks(vec![new_block]),
+            });
+        }
+
+        match exec
+            .tool_results
+            .iter_mut()
+            .find(|r| r.call_id == result.call_id)
+        {
+            Some(slot) => *slot = result,
+            None => exec.tool_results.push(result),
+        }
+        Ok(())
+    }
+
     // Deprecated in zero-abstraction model: initial prompts are handled explicitly by caller.
     // Retained signature temporarily, but now implemented using set_system_prompt + add_user_message semantics without DB writes for system.
     pub async fn set_initial_prompts(
@@ -697,7 +878,8 @@ impl TaskContext {
             (iteration, seq)
         };
 
-        self.agent_persistence()
+        let persistence = self.agent_persistence();
+        let message = persistence
             .execution_messages()
             .append_message(
                 &self.task_id,
@@ -709,8 +891,17 @@ impl TaskContext {
                 seq,
             )
             .await
-            .map(|_| ())
-            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+
+        if !is_summary {
+            history_recall::spawn_index_message(
+                persistence,
+                message,
+                self.session_id,
+                self.cwd.to_string(),
+            );
+        }
+        Ok(())
     }
 
     pub async fn emit_event(&self, event: TaskEvent) -> TaskExecutorResult<()> {
@@ -852,6 +1043,22 @@ impl TaskContext {
         .await
     }
 
+    /// 累加一轮 LLM 调用的 token 用量
+    pub async fn record_token_usage(&self, usage: TokenUsage) {
+        self.states
+            .messages
+            .lock()
+            .await
+            .token_usage
+            .get_or_insert_with(TokenUsage::default)
+            .accumulate(&usage);
+    }
+
+    /// 当前累计的 token 用量
+    pub async fn token_usage(&self) -> Option<TokenUsage> {
+        self.states.messages.lock().await.token_usage.clone()
+    }
+
     pub async fn finish_assistant_message(
         &self,
         status: MessageStatus,
@@ -903,7 +1110,8 @@ impl TaskContext {
     pub async fn fail_assistant_message(&self, error: ErrorBlock) -> TaskExecutorResult<()> {
         self.assistant_append_block(Block::Error(error.clone()))
             .await?;
-        self.finish_assistant_message(MessageStatus::Error, None)
+        let token_usage = self.token_usage().await;
+        self.finish_assistant_message(MessageStatus::Error, token_usage)
             .await?;
         Ok(())
     }
@@ -914,45 +1122,7 @@ impl TaskContext {
         };
 
         let now = Utc::now();
-        let mut changed_blocks: Vec<(String, Block)> = Vec::new();
-
-        for block in &mut message.blocks {
-            match block {
-                Block::Thinking(b) => {
-                    if b.is_streaming {
-                        b.is_streaming = false;
-                        changed_blocks.push((b.id.clone(), Block::Thinking(b.clone())));
-                    }
-                }
-                Block::Text(b) => {
-                    if b.is_streaming {
-                        b.is_streaming = false;
-                        changed_blocks.push((b.id.clone(), Block::Text(b.clone())));
-                    }
-                }
-                Block::Tool(b) => {
-                    if matches!(b.status, ToolStatus::Running) {
-                        b.status = ToolStatus::Cancelled;
-                        b.finished_at = Some(now);
-                        b.duration_ms = Some(
-                            now.signed_duration_since(b.started_at)
-                                .num_milliseconds()
-                                .max(0) as i64,
-                        );
-                        changed_blocks.push((b.id.clone(), Block::Tool(b.clone())));
-                    }
-                }
-                _ => {}
-            }
-        }
-
-        message.status = MessageStatus::Cancelled;
-        message.finished_at = Some(now);
-        message.duration_ms = Some(
-            now.signed_duration_since(message.created_at)
-                .num_milliseconds()
-                .max(0) as i64,
-        );
+        let changed_blocks = settle_message(&mut message, MessageStatus::Cancelled, now);
 
         self.agent_persistence()
             .messages()
@@ -985,14 +1155,14 @@ impl TaskContext {
 }
 
 #[derive(Clone)]
-struct ActiveCheckpoint {
-    id: i64,
-    workspace_root: PathBuf,
+pub(crate) struct ActiveCheckpoint {
+    pub(crate) id: i64,
+    pub(crate) workspace_root: PathBuf,
 }
 
 fn map_status(status: &AgentTaskStatus) -> TaskStatus {
     match status {
-        AgentTaskStatus::Created => TaskStatus::Init,
+        AgentTaskStatus::Created | AgentTaskStatus::Queued => TaskStatus::Init,
         AgentTaskStatus::Running => TaskStatus::Running,
         AgentTaskStatus::Paused => TaskStatus::Paused,
         AgentTaskStatus::Completed => TaskStatus::Done,
@@ -1001,7 +1171,7 @@ fn map_status(status: &AgentTaskStatus) -> TaskStatus {
     }
 }
 
-fn render_message_content(content: &MessageContent) -> String {
+pub(crate) fn render_message_content(content: &MessageContent) -> String {
     match content {
         MessageContent::Text(text) => text.clone(),
         MessageContent::Blocks(blocks) => serde_json::to_string(blocks).unwrap_or_default(),
@@ -1024,6 +1194,49 @@ fn map_user_image_blocks(images: &[ImageAttachment]) -> Vec<Block> {
         .collect()
 }
 
+/// 结束被中断的消息：停止流式块、取消仍在运行的工具，并写入终态与耗时。
+/// 返回发生变化的块，供调用方推送 BlockUpdated。
+pub(crate) fn settle_message(
+    message: &mut Message,
+    status: MessageStatus,
+    now: DateTime<Utc>,
+) -> Vec<(String, Block)> {
+    let mut changed_blocks: Vec<(String, Block)> = Vec::new();
+
+    for block in &mut message.blocks {
+        match block {
+            Block::Thinking(b) if b.is_streaming => {
+                b.is_streaming = false;
+                changed_blocks.push((b.id.clone(), Block::Thinking(b.clone())));
+            }
+            Block::Text(b) if b.is_streaming => {
+                b.is_streaming = false;
+                changed_blocks.push((b.id.clone(), Block::Text(b.clone())));
+            }
+            Block::Tool(b) if matches!(b.status, ToolStatus::Running) => {
+                b.status = ToolStatus::Cancelled;
+                b.finished_at = Some(now);
+                b.duration_ms = Some(
+                    now.signed_duration_since(b.started_at)
+                        .num_milliseconds()
+                        .max(0),
+                );
+                changed_blocks.push((b.id.clone(), Block::Tool(b.clone())));
+            }
+            _ => {}
+        }
+    }
+
+    message.status = status;
+    message.finished_at = Some(now);
+    message.duration_ms = Some(
+        now.signed_duration_since(message.created_at)
+            .num_milliseconds()
+            .max(0),
+    );
+    changed_blocks
+}
+
 fn find_block_index(blocks: &[Block], block_id: &str) -> Option<usize> {
     blocks.iter().position(|block| match block {
         Block::Thinking(b) => b.id == block_id,
@@ -1040,4 +1253,167 @@ pub struct ToolCallResult {
     pub result: Value,
     pub status: crate::agent::tools::ToolResultStatus,
     pub execution_time_ms: u64,
+    /// 工具注册的 formatter 渲染出的模型侧文本；为空时模型看到 `result` 的 JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_text: Option<String>,
+}
+
+impl ToolCallResult {
+    /// 交给模型的结果文本
+    pub fn model_content(&self) -> String {
+        match &self.model_text {
+            Some(text) => text.clone(),
+            None => serde_json::to_string(&self.result).unwrap_or_else(|_| "{}".to_string()),
+        }
+    }
+}
+
+/// 测试用任务上下文，数据库位于 app_dir 下，不连接前端通道
+#[cfg(test)]
+pub(crate) async fn test_task_context(app_dir: &Path, workspace: &Path) -> TaskContext {
+    test_task_context_with_config(app_dir, workspace, TaskExecutionConfig::default()).await
+}
+
+#[cfg(test)]
+pub(crate) async fn test_task_context_with_config(
+    app_dir: &Path,
+    workspace: &Path,
+    config: TaskExecutionConfig,
+) -> TaskContext {
+    build_test_task_context(app_dir, workspace, config, false).await
+}
+
+/// 启用 checkpoint 的测试上下文；预先写入工作区与会话记录以满足外键约束
+#[cfg(test)]
+pub(crate) async fn test_task_context_with_checkpoints(
+    app_dir: &Path,
+    workspace: &Path,
+) -> TaskContext {
+    build_test_task_context(app_dir, workspace, TaskExecutionConfig::default(), true).await
+}
+
+#[cfg(test)]
+async fn build_test_task_context(
+    app_dir: &Path,
+    workspace: &Path,
+    config: TaskExecutionConfig,
+    with_checkpoints: bool,
+) -> TaskContext {
+    let paths = crate::storage::paths::StoragePathsBuilder::new()
+        .app_dir(app_dir.to_path_buf())
+        .build()
+        .unwrap();
+    paths.ensure_directories().unwrap();
+    let database = Arc::new(
+        DatabaseManager::new(paths, crate::storage::DatabaseOptions::default())
+            .await
+            .unwrap(),
+    );
+    let persistence = Arc::new(AgentPersistence::new(Arc::clone(&database)));
+    let workspace_path = workspace.to_string_lossy().into_owned();
+    let checkpoint_service = if with_checkpoints {
+        persistence
+            .workspaces()
+            .upsert(&workspace_path, None)
+            .await
+            .unwrap();
+        let session = persistence
+            .sessions()
+            .create(&workspace_path, None)
+            .await
+            .unwrap();
+        assert_eq!(session.id, 1);
+
+        let pool = database.pool().clone();
+        Some(Arc::new(CheckpointService::new(
+            Arc::new(crate::checkpoint::CheckpointStorage::new(pool.clone())),
+            Arc::new(crate::checkpoint::BlobStore::new(pool)),
+        )))
+    } else {
+        None
+    };
+    let now = Utc::now();
+    let execution = AgentExecution {
+        id: 1,
+        execution_id: "test-task".to_string(),
+        session_id: 1,
+        user_request: "test".to_string(),
+        system_prompt_used: String::new(),
+        execution_config: None,
+        has_conversation_context: false,
+        status: ExecutionStatus::Running,
+        current_iteration: 0,
+        error_count: 0,
+        max_iterations: 0,
+        total_input_tokens: 0,
+        total_output_tokens: 0,
+        total_cost: 0.0,
+        context_tokens: 0,
+        created_at: now,
+        updated_at: now,
+        started_at: Some(now),
+        completed_at: None,
+    };
+    TaskContext::new(
+        execution,
+        config,
+        workspace_path,
+        Arc::new(ToolRegistry::new(Vec::new())),
+        None,
+        database,
+        persistence,
+        checkpoint_service,
+    )
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn soft_stop_is_cleared_on_resume_and_recorded_reason_reaches_cancel() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = test_task_context(dir.path(), dir.path()).await;
+
+        let token = ctx.register_step_token("call-1");
+        ctx.set_pause(true, true);
+        assert!(token.is_cancelled());
+        assert_eq!(ctx.cancel_reason(), Some(CancelReason::SoftStop));
+        assert!(ctx.check_aborted(false).is_err());
+
+        ctx.set_pause(false, false);
+        assert_eq!(ctx.cancel_reason(), None);
+        assert!(ctx.check_aborted(false).is_ok());
+        assert_eq!(
+            CancelReason::resolve(None, ctx.cancel_reason()),
+            CancelReason::UserCancelled
+        );
+
+        ctx.record_cancel_reason(CancelReason::Timeout);
+        ctx.abort(CancelReason::resolve(None, ctx.cancel_reason()));
+        assert!(ctx.is_aborted());
+        assert_eq!(ctx.cancel_reason(), Some(CancelReason::Timeout));
+    }
+
+    #[tokio::test]
+    async fn runtime_thresholds_follow_task_execution_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskExecutionConfig {
+            max_iterations: 7,
+            max_errors: 2,
+            ..TaskExecutionConfig::default()
+        };
+        let ctx = test_task_context_with_config(dir.path(), dir.path(), config).await;
+
+        let state = ctx.state_manager().snapshot().await;
+        assert_eq!(state.max_iterations, 7);
+        assert_eq!(state.max_consecutive_errors, 2);
+
+        assert!(!ctx.state_manager().should_halt().await);
+        ctx.state_manager().increment_error_count().await;
+        ctx.state_manager().increment_error_count().await;
+        assert!(ctx.state_manager().should_halt().await);
+    }
 }
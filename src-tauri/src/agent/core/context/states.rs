@@ -1,14 +1,17 @@
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+
+use dashmap::DashMap;
 use tauri::ipc::Channel;
 use tokio::sync::{Mutex, RwLock};
+use tokio_util::sync::CancellationToken;
 
 use crate::agent::core::context::ToolCallResult;
 use crate::agent::core::status::AgentTaskStatus;
 use crate::agent::persistence::AgentExecution;
 use crate::agent::react::runtime::ReactRuntime;
 use crate::agent::types::TaskDetail;
-use crate::agent::types::{Message, TaskEvent};
+use crate::agent::types::{CancelReason, Message, TaskEvent, TokenUsage};
 use crate::llm::anthropic_types::{MessageParam, SystemPrompt};
 
 use super::chain::Chain;
@@ -73,6 +76,8 @@ impl PlanningState {
 #[derive(Default)]
 pub(crate) struct MessageState {
     pub(crate) assistant_message: Option<Message>,
+    /// 本次任务各轮 LLM 调用累计的用量
+    pub(crate) token_usage: Option<TokenUsage>,
 }
 
 pub(crate) struct TaskStates {
@@ -83,6 +88,12 @@ pub(crate) struct TaskStates {
     pub progress_channel: Arc<Mutex<Option<Channel<TaskEvent>>>>,
     /// 简化的取消标志 - 用 AtomicBool 替代 CancellationToken
     pub aborted: Arc<AtomicBool>,
+    /// 最近一次取消的原因，发出终态事件时读取
+    pub cancel_reason: Arc<parking_lot::Mutex<Option<CancelReason>>>,
+    /// 正在执行的工具调用取消令牌（tool_id -> token），用于单独取消某个工具
+    pub step_tokens: Arc<DashMap<String, CancellationToken>>,
+    /// 主动压缩生成的摘要及其覆盖的消息数；压缩边界不变时复用，避免每轮都调用模型
+    pub proactive_summary: Arc<parking_lot::Mutex<Option<(usize, String)>>>,
 }
 
 impl TaskStates {
@@ -99,6 +110,9 @@ impl TaskStates {
             react_runtime: Arc::new(RwLock::new(react_runtime)),
             progress_channel: Arc::new(Mutex::new(progress_channel)),
             aborted: Arc::new(AtomicBool::new(false)),
+            cancel_reason: Arc::new(parking_lot::Mutex::new(None)),
+            step_tokens: Arc::new(DashMap::new()),
+            proactive_summary: Arc::new(parking_lot::Mutex::new(None)),
         }
     }
 }
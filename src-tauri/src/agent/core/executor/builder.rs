@@ -19,6 +19,30 @@ use crate::agent::error::{TaskExecutorError, TaskExecutorResult};
 use crate::agent::persistence::{AgentExecution, ExecutionStatus};
 use crate::agent::types::TaskEvent;
 
+/// 新任务使用的执行配置：默认值叠加请求中的覆盖项
+pub(crate) fn execution_config_for(params: &ExecuteTaskParams) -> TaskExecutionConfig {
+    let mut execution_config = TaskExecutionConfig::default();
+    if let Some(behavior) = params.on_max_iterations {
+        execution_config.on_max_iterations = behavior;
+    }
+    if let Some(window) = params.tool_dedup_window {
+        execution_config.tool_dedup_window = window;
+    }
+    if let Some(policy) = params.compaction_policy {
+        execution_config.compaction.policy = policy;
+    }
+    if let Some(every) = params.compaction_every_turns {
+        execution_config.compaction.proactive_every_turns = every;
+    }
+    if let Some(keep) = params.compaction_keep_turns {
+        execution_config.compaction.proactive_keep_turns = keep;
+    }
+    if let Some(root) = &params.filesystem_root {
+        execution_config.filesystem_root = Some(root.clone());
+    }
+    execution_config
+}
+
 impl TaskExecutor {
     /// 构建新的 TaskContext
     ///
@@ -71,6 +95,8 @@ impl TaskExecutor {
     ) -> TaskExecutorResult<Arc<TaskContext>> {
         let task_id = format!("exec_{}", uuid::Uuid::new_v4());
 
+        let execution_config = execution_config_for(params);
+
         // 创建execution记录
         let execution = AgentExecution {
             id: 0, // 由数据库自动生成
@@ -78,12 +104,12 @@ impl TaskExecutor {
             session_id: params.session_id,
             user_request: params.user_prompt.clone(),
             system_prompt_used: String::new(),
-            execution_config: Some(serde_json::to_string(&TaskExecutionConfig::default()).unwrap()),
+            execution_config: Some(serde_json::to_string(&execution_config).unwrap()),
             has_conversation_context: false, // 由后端自动检测
             status: ExecutionStatus::Running,
             current_iteration: 0,
             error_count: 0,
-            max_iterations: TaskExecutionConfig::default().max_iterations as i64,
+            max_iterations: execution_config.max_iterations as i64,
             total_input_tokens: 0,
             total_output_tokens: 0,
             total_cost: 0.0,
@@ -0,0 +1,299 @@
+/*!
+ * 会话的 JSON 导出与导入 - 在设备间迁移对话或从备份恢复
+ *
+ * 导出内容只包含 UI 消息（messages 表），模型侧历史在导入时按 ui_rebuild 的规则重建，
+ * 写入一条已完成的任务，后续任务可以接着这段对话继续。格式带版本号，
+ * 只接受 CONVERSATION_EXPORT_VERSION，结构变化时递增版本并在此处做迁移。
+ */
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::agent::core::executor::TaskExecutor;
+use crate::agent::error::{ConversationImportError, TaskExecutorError, TaskExecutorResult};
+use crate::agent::persistence::SessionImport;
+use crate::agent::types::{Block, Message, MessageRole, MessageStatus, TokenUsage};
+use crate::agent::utils::tokenizer::count_text_tokens;
+
+use super::ui_rebuild::{history_rows, rebuild_history, validate_tool_pairing};
+
+/// 当前导出格式的版本
+pub const CONVERSATION_EXPORT_VERSION: u64 = 1;
+
+/// 会话导出（版本 1）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationExport {
+    pub version: u64,
+    pub exported_at: DateTime<Utc>,
+    pub conversation: ExportedConversation,
+    pub messages: Vec<ExportedMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedConversation {
+    pub title: Option<String>,
+    pub workspace_path: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 一条 UI 消息，不含本机的 ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedMessage {
+    pub role: MessageRole,
+    pub status: MessageStatus,
+    pub blocks: Vec<Block>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub duration_ms: Option<i64>,
+    #[serde(default)]
+    pub token_usage: Option<TokenUsage>,
+}
+
+impl From<Message> for ExportedMessage {
+    fn from(message: Message) -> Self {
+        Self {
+            role: message.role,
+            status: message.status,
+            blocks: message.blocks,
+            created_at: message.created_at,
+            finished_at: message.finished_at,
+            duration_ms: message.duration_ms,
+            token_usage: message.token_usage,
+        }
+    }
+}
+
+impl ExportedMessage {
+    /// 导出时仍在输出的消息不会再结束，导入后标记为已取消
+    fn into_message(self) -> Message {
+        let status = match self.status {
+            MessageStatus::Streaming => MessageStatus::Cancelled,
+            other => other,
+        };
+        Message {
+            id: 0,
+            session_id: 0,
+            role: self.role,
+            status,
+            blocks: self.blocks,
+            created_at: self.created_at,
+            finished_at: self.finished_at,
+            duration_ms: self.duration_ms,
+            token_usage: self.token_usage,
+        }
+    }
+}
+
+/// 先只读版本号，未知版本给出明确错误，而不是笼统的解析失败
+fn parse_conversation_export(json: &str) -> Result<ConversationExport, ConversationImportError> {
+    let value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| ConversationImportError::InvalidFormat(e.to_string()))?;
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| ConversationImportError::InvalidFormat("missing version".to_string()))?;
+    if version != CONVERSATION_EXPORT_VERSION {
+        return Err(ConversationImportError::UnsupportedVersion(version));
+    }
+    serde_json::from_value(value).map_err(|e| ConversationImportError::InvalidFormat(e.to_string()))
+}
+
+/// 校验消息结构：至少一条消息，且工具块 ID 不重复（配对在重建模型历史后校验）
+fn validate_messages(messages: &[Message]) -> Result<(), ConversationImportError> {
+    if messages.is_empty() {
+        return Err(ConversationImportError::InvalidStructure(
+            "conversation has no messages".to_string(),
+        ));
+    }
+
+    let mut tool_ids = HashSet::new();
+    for block in messages.iter().flat_map(|m| m.blocks.iter()) {
+        if let Block::Tool(tool) = block {
+            if !tool_ids.insert(tool.id.as_str()) {
+                return Err(ConversationImportError::InvalidStructure(format!(
+                    "duplicate tool block {}",
+                    tool.id
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+impl TaskExecutor {
+    /// 导出会话的 UI 消息；会话不存在时返回 None
+    pub async fn export_conversation(
+        &self,
+        session_id: i64,
+    ) -> TaskExecutorResult<Option<ConversationExport>> {
+        let persistence = self.agent_persistence();
+        let Some(session) = persistence
+            .sessions()
+            .get(session_id)
+            .await
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+        let messages = persistence
+            .messages()
+            .list_by_session(session_id)
+            .await
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+
+        Ok(Some(ConversationExport {
+            version: CONVERSATION_EXPORT_VERSION,
+            exported_at: Utc::now(),
+            conversation: ExportedConversation {
+                title: session.title,
+                workspace_path: session.workspace_path,
+                created_at: session.created_at,
+                updated_at: session.updated_at,
+            },
+            messages: messages.into_iter().map(ExportedMessage::from).collect(),
+        }))
+    }
+
+    /// 从导出的 JSON 创建新会话，返回新会话 ID。workspace_path 为空时沿用导出时的工作区。
+    /// 校验通过后在一个事务中写入，失败时不会留下半成品会话
+    pub async fn import_conversation(
+        &self,
+        json: &str,
+        workspace_path: Option<String>,
+    ) -> Result<i64, ConversationImportError> {
+        let export = parse_conversation_export(json)?;
+        let messages: Vec<Message> = export
+            .messages
+            .into_iter()
+            .map(ExportedMessage::into_message)
+            .collect();
+        validate_messages(&messages)?;
+
+        let history = rebuild_history(&messages);
+        validate_tool_pairing(&history.messages)
+            .map_err(ConversationImportError::InvalidStructure)?;
+        let history = history_rows(&history)
+            .map_err(|e| ConversationImportError::InvalidStructure(e.to_string()))?
+            .into_iter()
+            .map(|(role, content)| {
+                let tokens = i64::try_from(count_text_tokens(&content)).unwrap_or(i64::MAX);
+                (role, content, tokens)
+            })
+            .collect();
+
+        let user_request = messages
+            .iter()
+            .flat_map(|m| m.blocks.iter())
+            .find_map(|b| match b {
+                Block::UserText(t) => Some(t.content.clone()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let import = SessionImport {
+            workspace_path: workspace_path
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or(export.conversation.workspace_path),
+            title: export.conversation.title,
+            created_at: export.conversation.created_at,
+            updated_at: export.conversation.updated_at,
+            messages,
+            execution_id: format!("exec_{}", uuid::Uuid::new_v4()),
+            user_request,
+            history,
+        };
+
+        Ok(self.agent_persistence().sessions().import(&import).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn export_json(version: u64, messages: serde_json::Value) -> String {
+        json!({
+            "version": version,
+            "exportedAt": "2026-01-02T03:04:05Z",
+            "conversation": {
+                "title": "Imported",
+                "workspacePath": "/tmp/project",
+                "createdAt": "2026-01-01T00:00:00Z",
+                "updatedAt": "2026-01-02T00:00:00Z"
+            },
+            "messages": messages
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn parses_current_version_and_rejects_unknown_ones() {
+        let messages = json!([
+            {
+                "role": "user",
+                "status": "completed",
+                "blocks": [{ "type": "user_text", "content": "hello" }],
+                "createdAt": "2026-01-01T00:00:00Z"
+            }
+        ]);
+
+        let export = parse_conversation_export(&export_json(1, messages.clone())).unwrap();
+        assert_eq!(export.messages.len(), 1);
+        assert_eq!(export.conversation.workspace_path, "/tmp/project");
+
+        assert!(matches!(
+            parse_conversation_export(&export_json(2, messages)),
+            Err(ConversationImportError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            parse_conversation_export("{\"messages\": []}"),
+            Err(ConversationImportError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_duplicate_tool_blocks() {
+        let tool = json!({
+            "type": "tool",
+            "id": "toolu_1",
+            "name": "read_file",
+            "status": "completed",
+            "input": { "path": "a.rs" },
+            "output": { "content": "ok" },
+            "startedAt": "2026-01-01T00:00:01Z"
+        });
+        let messages = json!([
+            {
+                "role": "user",
+                "status": "completed",
+                "blocks": [{ "type": "user_text", "content": "read a.rs twice" }],
+                "createdAt": "2026-01-01T00:00:00Z"
+            },
+            {
+                "role": "assistant",
+                "status": "completed",
+                "blocks": [tool.clone(), tool],
+                "createdAt": "2026-01-01T00:00:01Z"
+            }
+        ]);
+
+        let export = parse_conversation_export(&export_json(1, messages)).unwrap();
+        let messages: Vec<Message> = export
+            .messages
+            .into_iter()
+            .map(ExportedMessage::into_message)
+            .collect();
+        assert!(matches!(
+            validate_messages(&messages),
+            Err(ConversationImportError::InvalidStructure(_))
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+/*!
+ * 有效配置解析 - 展示某个会话发起任务时实际生效的配置及每一项的来源
+ *
+ * 复用执行器创建任务时的同一套解析逻辑（执行配置、会话固定模型、模型上下文窗口），
+ * 避免报告与运行时行为不一致。只读。
+ */
+
+use serde::Serialize;
+
+use crate::agent::config::{CompactionPolicy, MaxIterationsBehavior};
+use crate::agent::core::executor::builder::execution_config_for;
+use crate::agent::core::executor::{ExecuteTaskParams, TaskExecutor};
+use crate::agent::react::orchestrator::DEFAULT_CONTEXT_WINDOW;
+use crate::agent::tools::builtin::shell::DEFAULT_TIMEOUT_MS;
+use crate::ai::model_metadata::model_metadata;
+use crate::llm::providers::{DEFAULT_STREAM_REQUEST_TIMEOUT, REQUEST_TIMEOUT};
+
+/// 配置项的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    /// 内置默认值
+    Default,
+    /// 模型配置中的选项
+    Model,
+    /// 会话级设置
+    Conversation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Resolved<T> {
+    pub value: T,
+    pub source: ConfigSource,
+}
+
+impl<T> Resolved<T> {
+    fn new(value: T, source: ConfigSource) -> Self {
+        Self { value, source }
+    }
+
+    fn builtin(value: T) -> Self {
+        Self::new(value, ConfigSource::Default)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveAgentConfig {
+    pub session_id: i64,
+    /// 未固定模型且未提供默认模型时为 None
+    pub model_id: Resolved<Option<String>>,
+    pub max_iterations: Resolved<u32>,
+    pub max_errors: Resolved<u32>,
+    pub max_concurrent_tools: Resolved<usize>,
+    pub on_max_iterations: Resolved<MaxIterationsBehavior>,
+    /// 跨迭代工具调用去重的窗口（迭代数）
+    pub tool_dedup_window: Resolved<u32>,
+    /// 触发消息压缩的上下文窗口（token）
+    pub context_window: Resolved<u32>,
+    /// 压缩时保留不动的最近消息数
+    pub compaction_keep_recent: Resolved<usize>,
+    pub compaction_policy: Resolved<CompactionPolicy>,
+    /// proactive 策略每累计多少轮压缩一次
+    pub compaction_every_turns: Resolved<usize>,
+    /// proactive 策略原样保留的最近轮数
+    pub compaction_keep_turns: Resolved<usize>,
+    pub llm_request_timeout_secs: Resolved<u64>,
+    /// 流式请求的整体超时，可由模型选项 requestTimeoutMs 覆盖
+    pub llm_stream_timeout_ms: Resolved<u64>,
+    pub shell_timeout_ms: Resolved<u64>,
+}
+
+impl TaskExecutor {
+    /// 解析会话发起任务时生效的配置；default_model_id 为前端将随请求携带的全局默认模型
+    pub async fn effective_config(
+        &self,
+        session_id: i64,
+        default_model_id: Option<String>,
+    ) -> EffectiveAgentConfig {
+        let params = ExecuteTaskParams {
+            session_id,
+            model_id: default_model_id.unwrap_or_default(),
+            ..Default::default()
+        };
+        let execution = execution_config_for(&params);
+
+        let (model_id, model_source) = self
+            .resolve_session_model(session_id, params.model_id)
+            .await;
+        let model_id = (!model_id.is_empty()).then_some(model_id);
+
+        let metadata = match model_id.as_deref() {
+            Some(id) => model_metadata(&self.database(), &self.cache(), id).await,
+            None => None,
+        }
+        .unwrap_or_default();
+        let context_window = metadata
+            .context_window
+            .map(|window| Resolved::new(window, ConfigSource::Model))
+            .unwrap_or_else(|| Resolved::builtin(DEFAULT_CONTEXT_WINDOW));
+        let llm_stream_timeout_ms = match metadata.request_timeout_ms {
+            Some(_) => Resolved::new(
+                metadata.stream_request_timeout().as_millis() as u64,
+                ConfigSource::Model,
+            ),
+            None => Resolved::builtin(DEFAULT_STREAM_REQUEST_TIMEOUT.as_millis() as u64),
+        };
+
+        EffectiveAgentConfig {
+            session_id,
+            model_id: Resolved::new(model_id, model_source),
+            max_iterations: Resolved::builtin(execution.max_iterations),
+            max_errors: Resolved::builtin(execution.max_errors),
+            max_concurrent_tools: Resolved::builtin(execution.max_concurrent_tools),
+            on_max_iterations: Resolved::builtin(execution.on_max_iterations),
+            tool_dedup_window: Resolved::builtin(execution.tool_dedup_window),
+            context_window,
+            compaction_keep_recent: Resolved::builtin(execution.compaction.keep_recent_count),
+            compaction_policy: Resolved::builtin(execution.compaction.policy),
+            compaction_every_turns: Resolved::builtin(execution.compaction.proactive_every_turns),
+            compaction_keep_turns: Resolved::builtin(execution.compaction.proactive_keep_turns),
+            llm_request_timeout_secs: Resolved::builtin(REQUEST_TIMEOUT.as_secs()),
+            llm_stream_timeout_ms,
+            shell_timeout_ms: Resolved::builtin(DEFAULT_TIMEOUT_MS),
+        }
+    }
+}
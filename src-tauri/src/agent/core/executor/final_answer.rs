@@ -0,0 +1,173 @@
+/*!
+ * 最终回答提取 - 供朗读、复制、脚本等集成直接获取任务结果
+ *
+ * 从持久化的 UI 消息中取会话最新一条助手消息，最后一次工具调用之后的文字即最终回答，
+ * 思考过程、工具调用和错误块都不返回。任务以工具调用或错误结束、没有单独的最终回答时，
+ * 退回到会话中最后一段助手文字。
+ */
+
+use serde::Serialize;
+
+use crate::agent::core::executor::TaskExecutor;
+use crate::agent::error::{TaskExecutorError, TaskExecutorResult};
+use crate::agent::types::{Block, Message, MessageRole, MessageStatus};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalAnswer {
+    pub session_id: i64,
+    /// 文字所在的助手消息
+    pub message_id: i64,
+    pub status: MessageStatus,
+    pub content: String,
+    /// false 表示任务没有单独的最终回答，content 为最后一段助手文字
+    pub is_final: bool,
+}
+
+/// 消息中最后一个工具块之后的文字，多段之间以空行连接
+fn trailing_text(message: &Message) -> Option<String> {
+    let start = message
+        .blocks
+        .iter()
+        .rposition(|b| matches!(b, Block::Tool(_)))
+        .map_or(0, |idx| idx + 1);
+    let parts: Vec<&str> = message.blocks[start..]
+        .iter()
+        .filter_map(|b| match b {
+            Block::Text(text) => Some(text.content.trim()),
+            _ => None,
+        })
+        .filter(|text| !text.is_empty())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("\n\n"))
+}
+
+/// 消息中最后一段非空文字
+fn last_text(message: &Message) -> Option<String> {
+    message.blocks.iter().rev().find_map(|b| match b {
+        Block::Text(text) if !text.content.trim().is_empty() => {
+            Some(text.content.trim().to_string())
+        }
+        _ => None,
+    })
+}
+
+fn extract_final_answer(session_id: i64, messages: &[Message]) -> Option<FinalAnswer> {
+    let mut assistant = messages
+        .iter()
+        .rev()
+        .filter(|m| matches!(m.role, MessageRole::Assistant));
+    let latest = assistant.next()?;
+    if let Some(content) = trailing_text(latest) {
+        return Some(FinalAnswer {
+            session_id,
+            message_id: latest.id,
+            status: latest.status.clone(),
+            content,
+            is_final: true,
+        });
+    }
+
+    std::iter::once(latest)
+        .chain(assistant)
+        .find_map(|m| last_text(m).map(|content| (m, content)))
+        .map(|(m, content)| FinalAnswer {
+            session_id,
+            message_id: m.id,
+            status: m.status.clone(),
+            content,
+            is_final: false,
+        })
+}
+
+impl TaskExecutor {
+    /// 会话最新的最终回答；会话没有任何助手文字时返回 None
+    pub async fn final_answer(&self, session_id: i64) -> TaskExecutorResult<Option<FinalAnswer>> {
+        let messages = self
+            .agent_persistence()
+            .messages()
+            .list_by_session(session_id)
+            .await
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+        Ok(extract_final_answer(session_id, &messages))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(id: i64, role: &str, blocks: serde_json::Value) -> Message {
+        serde_json::from_value(json!({
+            "id": id,
+            "sessionId": 1,
+            "role": role,
+            "status": "completed",
+            "blocks": blocks,
+            "createdAt": "2026-01-01T00:00:00Z"
+        }))
+        .unwrap()
+    }
+
+    fn tool() -> serde_json::Value {
+        json!({
+            "type": "tool",
+            "id": "toolu_1",
+            "name": "read_file",
+            "status": "completed",
+            "input": { "path": "a.rs" },
+            "startedAt": "2026-01-01T00:00:01Z"
+        })
+    }
+
+    fn text(content: &str) -> serde_json::Value {
+        json!({ "type": "text", "id": "t", "content": content, "isStreaming": false })
+    }
+
+    #[test]
+    fn returns_text_after_the_last_tool_call() {
+        let messages = vec![
+            message(
+                1,
+                "user",
+                json!([{ "type": "user_text", "content": "explain a.rs" }]),
+            ),
+            message(
+                2,
+                "assistant",
+                json!([
+                    { "type": "thinking", "id": "th", "content": "hmm", "isStreaming": false },
+                    text("Let me read it."),
+                    tool(),
+                    text("a.rs defines the parser.")
+                ]),
+            ),
+        ];
+
+        let answer = extract_final_answer(1, &messages).unwrap();
+        assert_eq!(answer.message_id, 2);
+        assert_eq!(answer.content, "a.rs defines the parser.");
+        assert!(answer.is_final);
+    }
+
+    #[test]
+    fn falls_back_to_last_assistant_text() {
+        let messages = vec![
+            message(1, "assistant", json!([text("Earlier answer")])),
+            message(
+                2,
+                "user",
+                json!([{ "type": "user_text", "content": "go on" }]),
+            ),
+            message(3, "assistant", json!([text("Reading the file."), tool()])),
+        ];
+        let answer = extract_final_answer(1, &messages).unwrap();
+        assert_eq!(answer.message_id, 3);
+        assert_eq!(answer.content, "Reading the file.");
+        assert!(!answer.is_final);
+
+        let messages = vec![message(1, "assistant", json!([tool()]))];
+        assert!(extract_final_answer(1, &messages).is_none());
+    }
+}
@@ -2,17 +2,28 @@
  * 任务生命周期管理
  */
 
+use std::path::Path;
 use std::sync::Arc;
 
 use tauri::ipc::Channel;
-use tokio::task;
 use tracing::{error, warn};
 
-use crate::agent::core::context::TaskContext;
-use crate::agent::core::executor::{ExecuteTaskParams, TaskExecutor};
+use crate::agent::core::context::{settle_message, TaskContext, ToolCallResult};
+use crate::agent::core::executor::react_impl::convert_result;
+use crate::agent::core::executor::{
+    Admission, BulkCancelReport, ConfigSource, ExecuteTaskParams, TaskExecutor, TaskResetReport,
+};
 use crate::agent::core::status::AgentTaskStatus;
 use crate::agent::error::{TaskExecutorError, TaskExecutorResult};
-use crate::agent::types::{ErrorBlock, TaskEvent};
+use crate::agent::persistence::ExecutionStatus;
+use crate::agent::react::types::FinishReasonOrTerminal;
+use crate::agent::react::ReactTrace;
+use crate::agent::tools::builtin::file_utils::same_path;
+use crate::agent::tools::{self, ToolResultStatus};
+use crate::agent::types::{
+    Block, CancelReason, ErrorBlock, MessageStatus, TaskEvent, ToolBlock, ToolOutput, ToolStatus,
+};
+use crate::storage::repositories::AIModels;
 use crate::workspace::{WorkspaceService, UNGROUPED_WORKSPACE_PATH};
 
 impl TaskExecutor {
@@ -74,17 +85,30 @@ impl TaskExecutor {
 
         ctx.add_user_message_with_images(params.user_prompt, params.images.as_deref())
             .await?;
-        ctx.set_status(AgentTaskStatus::Running).await?;
+        // 先标记为排队，真正开始执行时再切换为 Running
+        ctx.set_status(AgentTaskStatus::Queued).await?;
 
         let executor = self.clone();
         let ctx_for_spawn = Arc::clone(&ctx);
         let model_id = params.model_id.clone();
 
-        task::spawn(async move {
+        let job = async move {
+            if let Err(e) = ctx_for_spawn.set_status(AgentTaskStatus::Running).await {
+                error!("Failed to mark task as running: {}", e);
+            }
             if let Err(e) = executor.run_task_loop(ctx_for_spawn, model_id).await {
                 error!("Task execution failed: {}", e);
             }
-        });
+        };
+
+        let admission = self.scheduler().submit(ctx.task_id.to_string(), job);
+        if let Admission::Queued(position) = admission {
+            ctx.emit_event(TaskEvent::TaskQueued {
+                task_id: ctx.task_id.to_string(),
+                position,
+            })
+            .await?;
+        }
 
         Ok(ctx)
     }
@@ -104,8 +128,26 @@ impl TaskExecutor {
         match result {
             Ok(()) => {
                 ctx.set_status(AgentTaskStatus::Completed).await?;
-                ctx.finish_assistant_message(crate::agent::types::MessageStatus::Completed, None)
+                let token_usage = ctx.token_usage().await;
+                ctx.finish_assistant_message(
+                    crate::agent::types::MessageStatus::Completed,
+                    token_usage,
+                )
+                .await?;
+                let truncated = ctx
+                    .states
+                    .react_runtime
+                    .read()
+                    .await
+                    .get_snapshot()
+                    .stop_reason
+                    == Some(FinishReasonOrTerminal::Length);
+                if truncated {
+                    ctx.emit_event(TaskEvent::ResponseTruncated {
+                        task_id: ctx.task_id.to_string(),
+                    })
                     .await?;
+                }
                 ctx.emit_event(TaskEvent::TaskCompleted {
                     task_id: ctx.task_id.to_string(),
                 })
@@ -115,8 +157,12 @@ impl TaskExecutor {
                 error!("Task failed: {}", e);
                 ctx.set_status(AgentTaskStatus::Error).await?;
 
+                let code = match e {
+                    TaskExecutorError::MaxIterationsReached { .. } => "task.max_iterations_reached",
+                    _ => "task.execution_error",
+                };
                 let error_block = ErrorBlock {
-                    code: "task.execution_error".to_string(),
+                    code: code.to_string(),
                     message: e.to_string(),
                     details: None,
                 };
@@ -131,16 +177,25 @@ impl TaskExecutor {
             }
         }
 
-        // 任务结束后立刻从 active_tasks 移除，避免内存/确认状态泄漏
+        // 任务结束后立刻从 active_tasks 移除，避免内存/确认状态泄漏；只保留截断后的轨迹
+        let trace = ReactTrace::from_runtime(
+            ctx.task_id.as_ref(),
+            &*ctx.states.react_runtime.read().await,
+            false,
+            None,
+        );
+        self.retain_finished_trace(trace);
         self.active_tasks().remove(ctx.task_id.as_ref());
 
         Ok(())
     }
 
+    /// 取消任务及其子任务；reason 为空时沿用任务上记录的原因（如软停止），否则视为用户取消
     pub async fn cancel_task(
         &self,
         task_id: &str,
-        _reason: Option<String>,
+        reason: Option<CancelReason>,
+        detail: Option<String>,
     ) -> TaskExecutorResult<()> {
         let ctx = self
             .active_tasks()
@@ -148,21 +203,332 @@ impl TaskExecutor {
             .map(|entry| Arc::clone(entry.value()))
             .ok_or_else(|| TaskExecutorError::TaskNotFound(task_id.to_string()))?;
 
-        ctx.abort();
+        let reason = CancelReason::resolve(reason, ctx.cancel_reason());
+        let children = self.cancel_context(&ctx, reason, detail.as_deref()).await?;
+
+        // 父任务结束后，仍在运行的子任务一并取消
+        let mut pending = children;
+        while let Some(child_id) = pending.pop() {
+            let Some(child) = self
+                .active_tasks()
+                .get(&child_id)
+                .map(|entry| Arc::clone(entry.value()))
+            else {
+                continue;
+            };
+            pending.extend(
+                self.cancel_context(&child, CancelReason::ParentCancelled, None)
+                    .await?,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// 取消全部运行中与排队中的任务。先清空队列，避免运行中的任务让出槽位后排队任务被启动；
+    /// 先收集任务 ID 再逐个取消，不在持有 active_tasks 分片锁时 await
+    pub async fn cancel_all_tasks(&self) -> BulkCancelReport {
+        const DETAIL: &str = "All tasks were cancelled";
+
+        let queued = self.scheduler().cancel_all_queued();
+        let mut pending: Vec<String> = self
+            .active_tasks()
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect();
+        pending.extend(queued.iter().cloned());
+
+        let mut task_ids: Vec<String> = Vec::new();
+        while let Some(task_id) = pending.pop() {
+            if task_ids.contains(&task_id) {
+                continue;
+            }
+            let Some(ctx) = self
+                .active_tasks()
+                .get(&task_id)
+                .map(|entry| Arc::clone(entry.value()))
+            else {
+                // 只在队列中、没有上下文的任务出队即视为取消
+                if queued.contains(&task_id) {
+                    task_ids.push(task_id);
+                }
+                continue;
+            };
+            match self
+                .cancel_context(&ctx, CancelReason::UserCancelled, Some(DETAIL))
+                .await
+            {
+                Ok(children) => pending.extend(children),
+                Err(e) => warn!(
+                    "Failed to cancel task {} during bulk cancel: {}",
+                    task_id, e
+                ),
+            }
+            task_ids.push(task_id);
+        }
+
+        BulkCancelReport {
+            cancelled: task_ids.len(),
+            queued: task_ids.iter().filter(|id| queued.contains(*id)).count(),
+            task_ids,
+        }
+    }
+
+    /// 中止单个任务并发出取消事件，返回其子任务 ID
+    async fn cancel_context(
+        &self,
+        ctx: &Arc<TaskContext>,
+        reason: CancelReason,
+        detail: Option<&str>,
+    ) -> TaskExecutorResult<Vec<String>> {
+        let task_id = ctx.task_id.to_string();
+
+        // 仍在排队的任务直接出队，不会启动
+        self.scheduler().cancel_queued(&task_id);
+        ctx.abort(reason);
         ctx.set_status(AgentTaskStatus::Cancelled).await?;
 
         let _ = ctx.cancel_assistant_message().await;
         let _ = ctx
-            .emit_event(TaskEvent::TaskCancelled {
-                task_id: task_id.to_string(),
-            })
+            .emit_event(TaskEvent::cancelled(task_id.as_str(), reason, detail))
             .await;
 
-        self.active_tasks().remove(task_id);
+        self.active_tasks().remove(&task_id);
 
+        Ok(ctx.children().await)
+    }
+
+    /// 把任务强制恢复到干净的终态，用于崩溃后持久化状态仍为 Running、
+    /// 但内存中已没有上下文导致常规取消无效的情况。
+    ///
+    /// 执行记录标记为 Cancelled，会话中残留的 streaming 消息被结束，内存上下文被丢弃。
+    /// 任务确实仍在运行或排队时，只有 `confirm` 为 true 才会中止它。
+    pub async fn force_reset_task(
+        &self,
+        task_id: &str,
+        confirm: bool,
+    ) -> TaskExecutorResult<TaskResetReport> {
+        let persistence = self.agent_persistence();
+        let execution = persistence
+            .agent_executions()
+            .get_by_execution_id(task_id)
+            .await
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+        let live = self
+            .active_tasks()
+            .get(task_id)
+            .map(|entry| Arc::clone(entry.value()));
+
+        let session_id = match (&live, &execution) {
+            (Some(ctx), _) => ctx.session_id,
+            (None, Some(execution)) => execution.session_id,
+            (None, None) => return Err(TaskExecutorError::TaskNotFound(task_id.to_string())),
+        };
+
+        let mut previous_status = execution.as_ref().map(|e| AgentTaskStatus::from(e.status));
+        let mut aborted_running = false;
+
+        if let Some(ctx) = live {
+            let status = ctx.status().await;
+            previous_status = Some(status);
+            let running = !matches!(
+                status,
+                AgentTaskStatus::Completed | AgentTaskStatus::Cancelled | AgentTaskStatus::Error
+            );
+            if running && !confirm {
+                return Err(TaskExecutorError::TaskStillRunning(task_id.to_string()));
+            }
+
+            self.scheduler().cancel_queued(task_id);
+            ctx.abort(CancelReason::ForceReset);
+            if running {
+                aborted_running = true;
+                ctx.set_status(AgentTaskStatus::Cancelled).await?;
+                let _ = ctx.cancel_assistant_message().await;
+                let _ = ctx
+                    .emit_event(TaskEvent::cancelled(
+                        task_id,
+                        CancelReason::ForceReset,
+                        None,
+                    ))
+                    .await;
+            }
+            self.active_tasks().remove(task_id);
+        } else {
+            self.scheduler().cancel_queued(task_id);
+            if execution.as_ref().map(|e| e.status) == Some(ExecutionStatus::Running) {
+                persistence
+                    .agent_executions()
+                    .mark_finished(task_id, ExecutionStatus::Cancelled)
+                    .await
+                    .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+            }
+        }
+
+        // 同一会话中还有其他活跃任务时，其 streaming 消息是真实的，不能动
+        let session_busy = self
+            .active_tasks()
+            .iter()
+            .any(|entry| entry.value().session_id == session_id);
+        let settled_messages = if session_busy {
+            0
+        } else {
+            self.settle_streaming_messages(session_id).await?
+        };
+
+        Ok(TaskResetReport {
+            task_id: task_id.to_string(),
+            previous_status: previous_status.map(|s| s.as_str().to_string()),
+            aborted_running,
+            settled_messages,
+        })
+    }
+
+    /// 结束会话中残留为 streaming 的 UI 消息，返回处理的条数
+    async fn settle_streaming_messages(&self, session_id: i64) -> TaskExecutorResult<usize> {
+        let persistence = self.agent_persistence();
+        let messages = persistence.messages();
+        let stale = messages
+            .list_by_session(session_id)
+            .await
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?
+            .into_iter()
+            .filter(|message| matches!(message.status, MessageStatus::Streaming));
+
+        let now = chrono::Utc::now();
+        let mut settled = 0;
+        for mut message in stale {
+            settle_message(&mut message, MessageStatus::Cancelled, now);
+            messages
+                .update(&message)
+                .await
+                .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+            settled += 1;
+        }
+        Ok(settled)
+    }
+
+    /// 取消任务中某个正在执行的工具调用，任务本身继续运行
+    ///
+    /// 返回 false 表示该工具调用不存在或已经结束。
+    pub async fn cancel_tool(&self, task_id: &str, tool_id: &str) -> TaskExecutorResult<bool> {
+        let ctx = self
+            .active_tasks()
+            .get(task_id)
+            .map(|entry| Arc::clone(entry.value()))
+            .ok_or_else(|| TaskExecutorError::TaskNotFound(task_id.to_string()))?;
+
+        Ok(ctx.cancel_step(tool_id))
+    }
+
+    /// 暂停任务，下一次迭代开始前等待恢复；abort_current_step 为 true 时为软停止，
+    /// 同时中断正在执行的工具调用
+    pub async fn pause_task(
+        &self,
+        task_id: &str,
+        abort_current_step: bool,
+    ) -> TaskExecutorResult<()> {
+        let ctx = self
+            .active_tasks()
+            .get(task_id)
+            .map(|entry| Arc::clone(entry.value()))
+            .ok_or_else(|| TaskExecutorError::TaskNotFound(task_id.to_string()))?;
+
+        ctx.set_pause(true, abort_current_step);
+        Ok(())
+    }
+
+    /// 恢复暂停的任务，暂停时记录的取消原因随之清除
+    pub async fn resume_task(&self, task_id: &str) -> TaskExecutorResult<()> {
+        let ctx = self
+            .active_tasks()
+            .get(task_id)
+            .map(|entry| Arc::clone(entry.value()))
+            .ok_or_else(|| TaskExecutorError::TaskNotFound(task_id.to_string()))?;
+
+        ctx.set_pause(false, false);
         Ok(())
     }
 
+    /// 按原参数重新执行最近一轮中的某个工具调用，并用新结果替换历史中的旧结果。
+    /// 返回 None 表示 tool_id 不属于最近一轮工具调用；已成功的调用不允许重新执行。
+    pub async fn retry_tool(
+        &self,
+        task_id: &str,
+        tool_id: &str,
+    ) -> TaskExecutorResult<Option<ToolCallResult>> {
+        let ctx = self
+            .active_tasks()
+            .get(task_id)
+            .map(|entry| Arc::clone(entry.value()))
+            .ok_or_else(|| TaskExecutorError::TaskNotFound(task_id.to_string()))?;
+
+        let Some((tool_name, params)) = ctx.find_latest_tool_call(tool_id).await else {
+            return Ok(None);
+        };
+        if ctx.tool_result_status(tool_id).await == Some(ToolResultStatus::Success) {
+            return Err(TaskExecutorError::ToolAlreadySucceeded(tool_id.to_string()));
+        }
+
+        let started_at = chrono::Utc::now();
+        let call = tools::ToolCall {
+            id: tool_id.to_string(),
+            name: tool_name.clone(),
+            params: params.clone(),
+        };
+        let Some(resp) = tools::execute_batch(&ctx.tool_registry(), &ctx, vec![call])
+            .await
+            .into_iter()
+            .next()
+        else {
+            return Err(TaskExecutorError::InternalError(format!(
+                "Tool {} produced no result on retry",
+                tool_name
+            )));
+        };
+
+        let (status, content) = convert_result(&resp.result);
+        let finished_at = chrono::Utc::now();
+        let block = Block::Tool(ToolBlock {
+            id: tool_id.to_string(),
+            name: tool_name.clone(),
+            status: match status {
+                ToolResultStatus::Success => ToolStatus::Completed,
+                ToolResultStatus::Error => ToolStatus::Error,
+                ToolResultStatus::Cancelled => ToolStatus::Cancelled,
+            },
+            input: params,
+            output: Some(ToolOutput {
+                content: content.clone(),
+                cancel_reason: resp.result.cancel_reason.clone(),
+                ext: resp.result.ext_info.clone(),
+            }),
+            started_at,
+            finished_at: Some(finished_at),
+            duration_ms: Some(
+                finished_at
+                    .signed_duration_since(started_at)
+                    .num_milliseconds()
+                    .max(0),
+            ),
+        });
+        // 工具块可能属于已结束的 assistant 消息，UI 更新失败不影响结果注入
+        if let Err(e) = ctx.assistant_update_block(tool_id, block).await {
+            warn!("Failed to update tool block on retry: {}", e);
+        }
+
+        let result = ToolCallResult {
+            model_text: ctx.tool_registry().format_result(&tool_name, &resp.result),
+            call_id: tool_id.to_string(),
+            tool_name,
+            result: content,
+            status,
+            execution_time_ms: resp.result.execution_time_ms.unwrap_or(0),
+        };
+        ctx.replace_tool_result(result.clone()).await?;
+        Ok(Some(result))
+    }
+
     pub async fn trigger_session_summary(
         &self,
         session_id: i64,
@@ -204,7 +570,12 @@ impl TaskExecutor {
             })
             .collect();
 
-        let summarizer = SessionSummarizer::new(session_id, persistence.clone(), self.database());
+        let summarizer = SessionSummarizer::new(
+            session_id,
+            persistence.clone(),
+            self.database(),
+            self.cache(),
+        );
 
         let model_id = model_override.unwrap_or_else(|| "claude-3-5-sonnet-20241022".to_string());
 
@@ -282,6 +653,22 @@ impl TaskExecutor {
         Ok(())
     }
 
+    /// 前端切换工作区后调用：其它工作区中仍在运行的任务丢弃待注入的文件变更提示，
+    /// 避免把旧工作区的文件上下文带入后续迭代
+    pub async fn on_workspace_changed(&self, workspace_path: &str) {
+        let workspace = Path::new(workspace_path);
+        let stale: Vec<Arc<TaskContext>> = self
+            .active_tasks()
+            .iter()
+            .filter(|entry| !same_path(Path::new(&*entry.value().cwd), workspace))
+            .map(|entry| Arc::clone(entry.value()))
+            .collect();
+
+        for ctx in stale {
+            ctx.file_tracker().clear_pending_changes().await;
+        }
+    }
+
     /// 规范化任务参数：空工作区时自动使用未分组会话
     async fn normalize_task_params(
         &self,
@@ -307,6 +694,49 @@ impl TaskExecutor {
             params.session_id = session.id;
         }
 
+        params.model_id = self
+            .resolve_session_model(params.session_id, params.model_id)
+            .await
+            .0;
+
         Ok(params)
     }
+
+    /// 会话固定了模型时优先使用，固定的模型已被删除则回退到请求携带的全局默认模型
+    pub(crate) async fn resolve_session_model(
+        &self,
+        session_id: i64,
+        default_model_id: String,
+    ) -> (String, ConfigSource) {
+        let service = WorkspaceService::new(self.database());
+        let pinned = match service.get_session_model(session_id).await {
+            Ok(Some(model_id)) => model_id,
+            Ok(None) => return (default_model_id, ConfigSource::Default),
+            Err(e) => {
+                warn!("读取会话模型失败: session_id={}, err={}", session_id, e);
+                return (default_model_id, ConfigSource::Default);
+            }
+        };
+        if pinned == default_model_id {
+            return (default_model_id, ConfigSource::Conversation);
+        }
+
+        match AIModels::new(&self.database()).find_by_id(&pinned).await {
+            Ok(Some(_)) => (pinned, ConfigSource::Conversation),
+            Ok(None) => {
+                warn!(
+                    "会话固定的模型已不存在，回退到默认模型: session_id={}, pinned={}, fallback={}",
+                    session_id, pinned, default_model_id
+                );
+                (default_model_id, ConfigSource::Default)
+            }
+            Err(e) => {
+                warn!(
+                    "校验会话模型失败，回退到默认模型: session_id={}, err={}",
+                    session_id, e
+                );
+                (default_model_id, ConfigSource::Default)
+            }
+        }
+    }
 }
@@ -14,23 +14,41 @@
  */
 
 mod builder;
+mod conversation_io;
+mod effective_config;
+mod final_answer;
 mod lifecycle;
+mod queue;
 mod react_handler;
 mod react_impl;
+mod rendered_prompt;
 mod state;
 mod types;
-
+mod ui_rebuild;
+
+pub use conversation_io::{ConversationExport, CONVERSATION_EXPORT_VERSION};
+pub use effective_config::{ConfigSource, EffectiveAgentConfig, Resolved};
+pub use final_answer::FinalAnswer;
+pub use queue::{
+    Admission, TaskScheduler, DEFAULT_MAX_RUNNING_TASKS, MAX_RUNNING_TASKS_KEY,
+    MAX_RUNNING_TASKS_LIMIT,
+};
 pub use react_handler::ReactHandler;
+pub use rendered_prompt::{RenderedSystemPrompt, SystemPromptSource, SYSTEM_PROMPT_PAGE_CHARS};
 pub use state::TaskExecutorStats;
 pub use types::*;
+pub use ui_rebuild::MessageRebuildReport;
 
+use std::collections::VecDeque;
 use std::sync::Arc;
 
 use dashmap::DashMap;
+use parking_lot::Mutex;
 
 use crate::agent::persistence::AgentPersistence;
 use crate::agent::prompt::orchestrator::PromptOrchestrator;
 use crate::agent::react::orchestrator::ReactOrchestrator;
+use crate::agent::react::ReactTrace;
 use crate::checkpoint::CheckpointService;
 use crate::storage::{DatabaseManager, UnifiedCache};
 
@@ -51,8 +69,17 @@ struct TaskExecutorInner {
     // 任务状态管理 - 仅用于查找正在运行的任务以便中断
     // 不再缓存 conversation_contexts，每次从 DB 加载
     active_tasks: DashMap<String, Arc<crate::agent::core::context::TaskContext>>,
+
+    // 限制并发运行的任务数，其余排队
+    scheduler: TaskScheduler,
+
+    // 最近结束任务的 ReAct 轨迹，供调试查看
+    finished_traces: Mutex<VecDeque<ReactTrace>>,
 }
 
+/// 保留轨迹的已结束任务数
+const FINISHED_TRACE_CAPACITY: usize = 20;
+
 /// TaskExecutor - 任务执行器
 ///
 /// # 零成本抽象设计
@@ -77,6 +104,7 @@ impl TaskExecutor {
         ));
         let react_orchestrator = Arc::new(ReactOrchestrator::new(
             Arc::clone(&database),
+            Arc::clone(&cache),
             Arc::clone(&agent_persistence),
         ));
 
@@ -89,6 +117,8 @@ impl TaskExecutor {
                 prompt_orchestrator,
                 react_orchestrator,
                 active_tasks: DashMap::new(),
+                scheduler: TaskScheduler::default(),
+                finished_traces: Mutex::new(VecDeque::new()),
             }),
         }
     }
@@ -106,6 +136,7 @@ impl TaskExecutor {
         ));
         let react_orchestrator = Arc::new(ReactOrchestrator::new(
             Arc::clone(&database),
+            Arc::clone(&cache),
             Arc::clone(&agent_persistence),
         ));
 
@@ -118,6 +149,8 @@ impl TaskExecutor {
                 prompt_orchestrator,
                 react_orchestrator,
                 active_tasks: DashMap::new(),
+                scheduler: TaskScheduler::default(),
+                finished_traces: Mutex::new(VecDeque::new()),
             }),
         }
     }
@@ -150,6 +183,30 @@ impl TaskExecutor {
         &self.inner.active_tasks
     }
 
+    pub fn scheduler(&self) -> &TaskScheduler {
+        &self.inner.scheduler
+    }
+
+    /// 记录已结束任务的轨迹，超出容量时丢弃最早的
+    pub(crate) fn retain_finished_trace(&self, trace: ReactTrace) {
+        let mut finished = self.inner.finished_traces.lock();
+        finished.retain(|t| t.task_id != trace.task_id);
+        if finished.len() >= FINISHED_TRACE_CAPACITY {
+            finished.pop_front();
+        }
+        finished.push_back(trace);
+    }
+
+    /// 最近结束任务的轨迹
+    pub fn finished_trace(&self, task_id: &str) -> Option<ReactTrace> {
+        self.inner
+            .finished_traces
+            .lock()
+            .iter()
+            .find(|t| t.task_id == task_id)
+            .cloned()
+    }
+
     /// 获取 Checkpoint 服务（如果已配置）
     pub fn checkpoint_service(&self) -> Option<Arc<CheckpointService>> {
         self.inner.checkpoint_service.clone()
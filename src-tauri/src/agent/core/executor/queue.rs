@@ -0,0 +1,237 @@
+/*!
+ * 任务调度队列
+ *
+ * 限制同时运行的任务循环数量，超出上限的任务按提交顺序排队。
+ * LLM 调用与 embedding 请求都发生在任务循环内，因此并发上限同时约束了这些共享资源的使用。
+ * 排队中的任务被取消时直接出队，其任务循环不会启动。
+ */
+
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tracing::warn;
+
+/// 默认可同时运行的任务数
+pub const DEFAULT_MAX_RUNNING_TASKS: usize = 3;
+/// 并发上限允许的最大值
+pub const MAX_RUNNING_TASKS_LIMIT: usize = 16;
+/// 偏好设置中的键
+pub const MAX_RUNNING_TASKS_KEY: &str = "agent.max_concurrent_tasks";
+
+type TaskJob = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+struct SchedulerState {
+    max_running: usize,
+    running: HashSet<String>,
+    queued: VecDeque<(String, TaskJob)>,
+}
+
+/// 提交结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    Started,
+    /// 排队位置，从 1 开始
+    Queued(usize),
+}
+
+#[derive(Clone)]
+pub struct TaskScheduler {
+    state: Arc<Mutex<SchedulerState>>,
+}
+
+impl TaskScheduler {
+    pub fn new(max_running: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState {
+                max_running: max_running.clamp(1, MAX_RUNNING_TASKS_LIMIT),
+                running: HashSet::new(),
+                queued: VecDeque::new(),
+            })),
+        }
+    }
+
+    pub fn max_running(&self) -> usize {
+        self.state.lock().max_running
+    }
+
+    /// 调整并发上限；调大时立即启动排队中的任务
+    pub fn set_max_running(&self, max_running: usize) -> usize {
+        let max_running = max_running.clamp(1, MAX_RUNNING_TASKS_LIMIT);
+        self.state.lock().max_running = max_running;
+        self.drain();
+        max_running
+    }
+
+    /// 有空闲槽位时立即启动，否则排到队尾
+    pub fn submit<F>(&self, task_id: String, job: F) -> Admission
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let mut state = self.state.lock();
+        if state.running.len() < state.max_running {
+            state.running.insert(task_id.clone());
+            drop(state);
+            self.spawn(task_id, Box::pin(job));
+            Admission::Started
+        } else {
+            state.queued.push_back((task_id, Box::pin(job)));
+            Admission::Queued(state.queued.len())
+        }
+    }
+
+    /// 从队列中移除尚未启动的任务；任务不在队列中时返回 false
+    pub fn cancel_queued(&self, task_id: &str) -> bool {
+        let mut state = self.state.lock();
+        let Some(index) = state.queued.iter().position(|(id, _)| id == task_id) else {
+            return false;
+        };
+        // 丢弃 job，任务循环不会被执行
+        state.queued.remove(index);
+        true
+    }
+
+    /// 清空队列，返回被移除的任务 ID；job 在锁外丢弃
+    pub fn cancel_all_queued(&self) -> Vec<String> {
+        let drained: Vec<(String, TaskJob)> = self.state.lock().queued.drain(..).collect();
+        drained.into_iter().map(|(task_id, _)| task_id).collect()
+    }
+
+    /// 排队位置，从 1 开始；不在队列中时返回 None
+    pub fn queue_position(&self, task_id: &str) -> Option<usize> {
+        self.state
+            .lock()
+            .queued
+            .iter()
+            .position(|(id, _)| id == task_id)
+            .map(|index| index + 1)
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.state.lock().running.len()
+    }
+
+    pub fn queued_count(&self) -> usize {
+        self.state.lock().queued.len()
+    }
+
+    fn spawn(&self, task_id: String, job: TaskJob) {
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            // 在独立任务中执行，panic 时也能释放槽位
+            if let Err(e) = tokio::spawn(job).await {
+                warn!("Task {} terminated abnormally: {}", task_id, e);
+            }
+            scheduler.finish(&task_id);
+        });
+    }
+
+    fn finish(&self, task_id: &str) {
+        self.state.lock().running.remove(task_id);
+        self.drain();
+    }
+
+    fn drain(&self) {
+        loop {
+            let next = {
+                let mut state = self.state.lock();
+                if state.running.len() >= state.max_running {
+                    None
+                } else if let Some((task_id, job)) = state.queued.pop_front() {
+                    state.running.insert(task_id.clone());
+                    Some((task_id, job))
+                } else {
+                    None
+                }
+            };
+            match next {
+                Some((task_id, job)) => self.spawn(task_id, job),
+                None => break,
+            }
+        }
+    }
+}
+
+impl Default for TaskScheduler {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_RUNNING_TASKS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::sync::oneshot;
+
+    #[tokio::test]
+    async fn caps_running_tasks_and_starts_queued_in_order() {
+        let scheduler = TaskScheduler::new(1);
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+        let (started_tx, mut started_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let first_started = started_tx.clone();
+        assert_eq!(
+            scheduler.submit("a".into(), async move {
+                first_started.send("a").unwrap();
+                let _ = release_rx.await;
+            }),
+            Admission::Started
+        );
+        let second_started = started_tx.clone();
+        assert_eq!(
+            scheduler.submit("b".into(), async move {
+                second_started.send("b").unwrap();
+            }),
+            Admission::Queued(1)
+        );
+        assert_eq!(
+            scheduler.submit("c".into(), async move {
+                started_tx.send("c").unwrap();
+            }),
+            Admission::Queued(2)
+        );
+
+        assert_eq!(started_rx.recv().await, Some("a"));
+        assert_eq!(scheduler.queue_position("c"), Some(2));
+        assert!(scheduler.cancel_queued("b"));
+        assert_eq!(scheduler.queue_position("c"), Some(1));
+
+        release_tx.send(()).unwrap();
+        assert_eq!(started_rx.recv().await, Some("c"));
+        // b 已被取消，不会启动
+        assert_eq!(started_rx.recv().await, None);
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(scheduler.running_count(), 0);
+        assert_eq!(scheduler.queued_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn cancel_all_queued_drops_every_pending_job() {
+        let scheduler = TaskScheduler::new(1);
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+        let (started_tx, mut started_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        scheduler.submit("a".into(), async move {
+            let _ = release_rx.await;
+        });
+        for id in ["b", "c"] {
+            let tx = started_tx.clone();
+            scheduler.submit(id.into(), async move {
+                tx.send(id).unwrap();
+            });
+        }
+        drop(started_tx);
+
+        assert_eq!(scheduler.cancel_all_queued(), vec!["b", "c"]);
+        assert!(scheduler.cancel_all_queued().is_empty());
+
+        release_tx.send(()).unwrap();
+        assert_eq!(started_rx.recv().await, None);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(scheduler.running_count(), 0);
+    }
+}
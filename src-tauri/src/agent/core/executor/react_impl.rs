@@ -5,7 +5,7 @@
  */
 
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::agent::context::ContextBuilder;
@@ -16,7 +16,7 @@ use crate::agent::tools::{
     self, ToolDescriptionContext, ToolRegistry, ToolResultContent, ToolResultStatus,
 };
 use crate::agent::types::{Block, ToolBlock, ToolOutput, ToolStatus};
-use crate::llm::anthropic_types::CreateMessageRequest;
+use crate::llm::anthropic_types::{CreateMessageRequest, ThinkingConfig};
 
 #[async_trait::async_trait]
 impl ReactHandler for TaskExecutor {
@@ -29,7 +29,7 @@ impl ReactHandler for TaskExecutor {
         cwd: &str,
         messages: Option<Vec<crate::llm::anthropic_types::MessageParam>>,
     ) -> TaskExecutorResult<CreateMessageRequest> {
-        use crate::storage::repositories::AIModels;
+        use crate::storage::repositories::{AIModels, AIProvider};
 
         let model_config = AIModels::new(&self.inner.database)
             .find_by_id(model_id)
@@ -41,7 +41,7 @@ impl ReactHandler for TaskExecutor {
                 ))
             })?;
 
-        let max_tokens = model_config
+        let mut max_tokens = model_config
             .options
             .as_ref()
             .and_then(|opts| opts.get("maxTokens"))
@@ -49,26 +49,64 @@ impl ReactHandler for TaskExecutor {
             .map(|v| v as u32)
             .unwrap_or(4096);
 
-        let temperature = model_config
+        let mut temperature = model_config
             .options
             .as_ref()
             .and_then(|opts| opts.get("temperature"))
             .and_then(|v| v.as_f64())
             .or(Some(0.7));
 
-        let top_p = model_config
+        let mut top_p = model_config
             .options
             .as_ref()
             .and_then(|opts| opts.get("topP"))
             .and_then(|v| v.as_f64());
 
-        let top_k = model_config
+        let mut top_k = model_config
             .options
             .as_ref()
             .and_then(|opts| opts.get("topK"))
             .and_then(|v| v.as_u64())
             .map(|v| v as u32);
 
+        // 模型配置中的 stopSequences: 字符串数组，空串忽略
+        let stop_sequences = model_config
+            .options
+            .as_ref()
+            .and_then(|opts| opts.get("stopSequences"))
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|seqs| !seqs.is_empty());
+
+        // thinkingBudgetTokens: 仅 Anthropic 的 Extended Thinking 模型生效，其余模型忽略
+        let thinking_budget_tokens: Option<u32> = model_config
+            .options
+            .as_ref()
+            .and_then(|opts| opts.get("thinkingBudgetTokens"))
+            .and_then(|v| v.as_u64())
+            .filter(|v| *v > 0)
+            .map(|v| (v.min(u32::MAX as u64) as u32).max(ThinkingConfig::MIN_BUDGET_TOKENS))
+            .filter(|_| {
+                model_config.provider == AIProvider::Anthropic
+                    && ThinkingConfig::supported_by(&model_config.model)
+            });
+        let thinking = thinking_budget_tokens.map(|budget_tokens| {
+            // 思考预算计入 max_tokens，需留出正文输出空间；采样参数与 thinking 不兼容
+            if max_tokens <= budget_tokens {
+                max_tokens = budget_tokens.saturating_add(max_tokens);
+            }
+            temperature = None;
+            top_p = None;
+            top_k = None;
+            ThinkingConfig::Enabled { budget_tokens }
+        });
+
         let tool_schemas = tool_registry.get_tool_schemas_with_context(&ToolDescriptionContext {
             cwd: cwd.to_string(),
         });
@@ -100,7 +138,8 @@ impl ReactHandler for TaskExecutor {
             top_p,
             top_k,
             metadata: None,
-            stop_sequences: None,
+            stop_sequences,
+            thinking,
         })
     }
 
@@ -108,7 +147,7 @@ impl ReactHandler for TaskExecutor {
     async fn execute_tools(
         &self,
         context: &TaskContext,
-        _iteration: u32,
+        iteration: u32,
         tool_calls: Vec<(String, String, Value)>,
     ) -> TaskExecutorResult<Vec<ToolCallResult>> {
         let mut tool_started_at: HashMap<String, chrono::DateTime<chrono::Utc>> = HashMap::new();
@@ -133,12 +172,89 @@ impl ReactHandler for TaskExecutor {
         }
 
         // 转换为 ToolCall 并并行执行
+        let call_order: Vec<String> = tool_calls.iter().map(|(id, _, _)| id.clone()).collect();
         let calls: Vec<tools::ToolCall> = tool_calls
             .into_iter()
             .map(|(id, name, params)| tools::ToolCall { id, name, params })
             .collect();
 
-        let responses = tools::execute_batch(&context.tool_registry(), context, calls).await;
+        // 启用写入预览时先让用户审阅本批次的写入，被拒绝或取消的调用不执行
+        let registry = context.tool_registry();
+        let mut skipped = tools::write_preview::review_writes(&registry, context, &calls).await;
+
+        // 窗口内已执行过的相同只读调用直接复用结果
+        let mut cacheable = HashMap::new();
+        let mut mutating = HashSet::new();
+        for call in &calls {
+            let Some(metadata) = registry.get_tool_metadata(&call.name).await else {
+                continue;
+            };
+            if metadata.cacheable() {
+                cacheable.insert(call.id.clone(), (call.name.clone(), call.params.clone()));
+            } else if metadata.category.mutates_workspace() && !skipped.contains_key(&call.id) {
+                mutating.insert(call.id.clone());
+            }
+        }
+        {
+            let mut cache = context.tool_result_cache().lock();
+            cacheable.retain(
+                |id, (name, params)| match cache.get(iteration, name, params) {
+                    Some(hit) => {
+                        skipped.insert(id.clone(), hit);
+                        false
+                    }
+                    None => true,
+                },
+            );
+        }
+        let (skipped_calls, calls): (Vec<_>, Vec<_>) = calls
+            .into_iter()
+            .partition(|call| skipped.contains_key(&call.id));
+        let mut executed = tools::execute_batch(&registry, context, calls)
+            .await
+            .into_iter();
+        let responses: Vec<tools::ToolCallResult> = call_order
+            .iter()
+            .filter_map(|id| match skipped.remove(id) {
+                Some(result) => skipped_calls
+                    .iter()
+                    .find(|call| &call.id == id)
+                    .map(|call| tools::ToolCallResult {
+                        id: call.id.clone(),
+                        name: call.name.clone(),
+                        result,
+                    }),
+                None => executed.next(),
+            })
+            .collect();
+
+        {
+            let mut cache = context.tool_result_cache().lock();
+            if !mutating.is_empty() {
+                cache.clear();
+            } else {
+                for resp in &responses {
+                    if let Some((name, params)) = cacheable.get(&resp.id) {
+                        cache.insert(iteration, name, params, &resp.result);
+                    }
+                }
+            }
+        }
+
+        // 本轮写入的文件在工具全部结束后统一同步一次索引，期间的文件变化事件被忽略。
+        // 登记在写入之前发生，写入工具全部失败或被拒绝时只解除抑制，不做同步
+        let written = crate::vector_db::agent_writes().take_task(&context.task_id);
+        let wrote_files = responses.iter().any(|resp| {
+            mutating.contains(&resp.id) && resp.result.status == ToolResultStatus::Success
+        });
+        if wrote_files && !written.is_empty() {
+            if let Some(global) = crate::vector_db::commands::get_global_state() {
+                let engine = Arc::clone(&global.search_engine);
+                tokio::spawn(async move {
+                    engine.reconcile_files(&written).await;
+                });
+            }
+        }
 
         // 转换结果并发送事件
         let mut results = Vec::with_capacity(responses.len());
@@ -188,6 +304,7 @@ impl ReactHandler for TaskExecutor {
                 .await?;
 
             results.push(ToolCallResult {
+                model_text: registry.format_result(&resp.name, &resp.result),
                 call_id: resp.id,
                 tool_name: resp.name,
                 result: result_value,
@@ -209,7 +326,7 @@ impl ReactHandler for TaskExecutor {
 
 /// 转换 ToolResult 到 (status, json_value)
 #[inline]
-fn convert_result(result: &tools::ToolResult) -> (ToolResultStatus, Value) {
+pub(super) fn convert_result(result: &tools::ToolResult) -> (ToolResultStatus, Value) {
     match result.status {
         ToolResultStatus::Success => {
             let content = result
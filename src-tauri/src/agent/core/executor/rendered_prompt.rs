@@ -0,0 +1,139 @@
+/*!
+ * 系统提示词查看 - 展示模型实际看到的 system prompt，调试 agent 行为用
+ *
+ * 会话有运行中的任务时直接读取其上下文中的 system prompt（已包含注入的 `[summary]`/`[history]`）；
+ * 否则按新任务的构建流程（工具说明、项目上下文、用户规则）重新生成。两条路径都只读，
+ * 不修改 TaskContext，也不发起 LLM 请求。
+ */
+
+use serde::Serialize;
+
+use crate::agent::core::executor::TaskExecutor;
+use crate::agent::error::{TaskExecutorError, TaskExecutorResult};
+use crate::llm::anthropic_types::SystemPrompt;
+use crate::workspace::WorkspaceService;
+
+/// 单次返回的最大字符数
+pub const SYSTEM_PROMPT_PAGE_CHARS: usize = 32_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemPromptSource {
+    /// 运行中任务最近一次发送的 system prompt
+    ActiveTask,
+    /// 按新任务流程重新构建
+    Rebuilt,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedSystemPrompt {
+    pub session_id: i64,
+    pub task_id: Option<String>,
+    pub source: SystemPromptSource,
+    /// 从 offset 开始的一页内容
+    pub text: String,
+    pub offset: usize,
+    pub total_chars: usize,
+    /// 还有剩余内容时为下一页的 offset
+    pub next_offset: Option<usize>,
+}
+
+impl TaskExecutor {
+    /// 生成会话当前的 system prompt；会话不存在时返回 None。offset/limit 按字符计
+    pub async fn rendered_system_prompt(
+        &self,
+        session_id: i64,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> TaskExecutorResult<Option<RenderedSystemPrompt>> {
+        let active = self
+            .active_tasks()
+            .iter()
+            .find(|entry| entry.value().session_id == session_id)
+            .map(|entry| std::sync::Arc::clone(entry.value()));
+
+        let (task_id, source, prompt) = match active {
+            Some(ctx) => {
+                let prompt = ctx
+                    .get_system_prompt()
+                    .await
+                    .map(|prompt| system_prompt_text(&prompt))
+                    .unwrap_or_default();
+                (
+                    Some(ctx.task_id.to_string()),
+                    SystemPromptSource::ActiveTask,
+                    prompt,
+                )
+            }
+            None => {
+                let session = WorkspaceService::new(self.database())
+                    .get_session(session_id)
+                    .await
+                    .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+                let Some(session) = session else {
+                    return Ok(None);
+                };
+                let registry = crate::agent::tools::create_tool_registry("agent").await;
+                let (prompt, _) = self
+                    .prompt_orchestrator()
+                    .build_task_prompts(
+                        session_id,
+                        String::new(),
+                        "",
+                        &session.workspace_path,
+                        &registry,
+                    )
+                    .await?;
+                (None, SystemPromptSource::Rebuilt, prompt)
+            }
+        };
+
+        let limit = limit
+            .unwrap_or(SYSTEM_PROMPT_PAGE_CHARS)
+            .clamp(1, SYSTEM_PROMPT_PAGE_CHARS);
+        let (text, next_offset) = page(&prompt, offset, limit);
+        Ok(Some(RenderedSystemPrompt {
+            session_id,
+            task_id,
+            source,
+            text,
+            offset,
+            total_chars: prompt.chars().count(),
+            next_offset,
+        }))
+    }
+}
+
+fn system_prompt_text(prompt: &SystemPrompt) -> String {
+    match prompt {
+        SystemPrompt::Text(text) => text.clone(),
+        SystemPrompt::Blocks(blocks) => blocks
+            .iter()
+            .map(|block| block.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    }
+}
+
+/// 按字符截取一页，避免切在多字节字符中间
+fn page(text: &str, offset: usize, limit: usize) -> (String, Option<usize>) {
+    let mut chars = text.chars().skip(offset);
+    let page: String = chars.by_ref().take(limit).collect();
+    let next_offset = chars.next().map(|_| offset + limit);
+    (page, next_offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_by_chars() {
+        let text = "系统提示abcdef";
+        assert_eq!(page(text, 0, 4), ("系统提示".to_string(), Some(4)));
+        assert_eq!(page(text, 4, 4), ("abcd".to_string(), Some(8)));
+        assert_eq!(page(text, 8, 4), ("ef".to_string(), None));
+        assert_eq!(page(text, 20, 4), (String::new(), None));
+    }
+}
@@ -6,7 +6,9 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::agent::core::context::TaskContext;
-use crate::agent::core::executor::{FileContextStatus, TaskExecutor, TaskSummary};
+use crate::agent::core::executor::{
+    ExecutionMessagesPage, FileContextStatus, TaskExecutor, TaskSummary,
+};
 use crate::agent::core::types::status::AgentTaskStatus;
 use crate::agent::error::{TaskExecutorError, TaskExecutorResult};
 
@@ -39,6 +41,7 @@ impl TaskExecutor {
             error_count: error_count as i32,
             created_at: created_at.to_rfc3339(),
             updated_at: updated_at.to_rfc3339(),
+            queue_position: self.scheduler().queue_position(task_id),
         })
     }
 
@@ -145,6 +148,8 @@ impl TaskExecutor {
     pub fn get_stats(&self) -> TaskExecutorStats {
         TaskExecutorStats {
             active_tasks: self.active_tasks().len(),
+            running_tasks: self.scheduler().running_count(),
+            queued_tasks: self.scheduler().queued_count(),
         }
     }
 
@@ -171,7 +176,13 @@ impl TaskExecutor {
 
         let mut summaries = Vec::new();
         for execution in executions {
-            let status = AgentTaskStatus::from(execution.status);
+            // 持久化层不区分排队与运行，以调度队列为准
+            let queue_position = self.scheduler().queue_position(&execution.execution_id);
+            let status = if queue_position.is_some() {
+                AgentTaskStatus::Queued
+            } else {
+                AgentTaskStatus::from(execution.status)
+            };
             if let Some(filter) = &status_filter {
                 if status.as_str() != filter {
                     continue;
@@ -186,12 +197,40 @@ impl TaskExecutor {
                 error_count: execution.error_count as i32,
                 created_at: execution.created_at.to_rfc3339(),
                 updated_at: execution.updated_at.to_rfc3339(),
+                queue_position,
             });
         }
 
         Ok(summaries)
     }
 
+    /// 分页读取任务持久化的执行消息，用于排查模型实际看到的上下文
+    pub async fn get_execution_messages(
+        &self,
+        task_id: &str,
+        offset: i64,
+        limit: i64,
+    ) -> TaskExecutorResult<ExecutionMessagesPage> {
+        let repo = self.agent_persistence();
+        let repo = repo.execution_messages();
+
+        let total = repo
+            .count_by_execution(task_id)
+            .await
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+        let messages = repo
+            .list_page_by_execution(task_id, offset, limit)
+            .await
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+
+        Ok(ExecutionMessagesPage {
+            task_id: task_id.to_string(),
+            total,
+            offset,
+            messages,
+        })
+    }
+
     pub(crate) fn workspace_relative_to_absolute(
         workspace_path: &str,
         stored_path: &str,
@@ -208,4 +247,6 @@ impl TaskExecutor {
 #[derive(Debug, Clone)]
 pub struct TaskExecutorStats {
     pub active_tasks: usize,
+    pub running_tasks: usize,
+    pub queued_tasks: usize,
 }
@@ -2,8 +2,13 @@
  * TaskExecutor类型定义
  */
 
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
+use crate::agent::config::{CompactionPolicy, MaxIterationsBehavior};
+use crate::agent::persistence::ExecutionMessage;
+
 /// 图片附件
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -15,7 +20,7 @@ pub struct ImageAttachment {
 }
 
 /// 任务执行参数
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExecuteTaskParams {
     /// 归属工作区（绝对路径/规范化）
@@ -26,6 +31,24 @@ pub struct ExecuteTaskParams {
     pub model_id: String,
     #[serde(default)]
     pub images: Option<Vec<ImageAttachment>>,
+    /// 覆盖达到最大迭代次数时的行为，缺省使用配置默认值
+    #[serde(default)]
+    pub on_max_iterations: Option<MaxIterationsBehavior>,
+    /// 覆盖跨迭代工具调用去重的窗口（迭代数，0 关闭）
+    #[serde(default)]
+    pub tool_dedup_window: Option<u32>,
+    /// 覆盖消息压缩策略
+    #[serde(default)]
+    pub compaction_policy: Option<CompactionPolicy>,
+    /// 覆盖 proactive 策略每累计多少轮压缩一次
+    #[serde(default)]
+    pub compaction_every_turns: Option<usize>,
+    /// 覆盖 proactive 策略原样保留的最近轮数
+    #[serde(default)]
+    pub compaction_keep_turns: Option<usize>,
+    /// 把文件类工具限制在该目录内，缺省为工作区根目录
+    #[serde(default)]
+    pub filesystem_root: Option<PathBuf>,
 }
 
 /// 任务摘要信息
@@ -39,6 +62,33 @@ pub struct TaskSummary {
     pub error_count: i32,
     pub created_at: String,
     pub updated_at: String,
+    /// 排队位置（从 1 开始），仅 status 为 queued 时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+}
+
+/// 强制重置任务的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskResetReport {
+    pub task_id: String,
+    /// 重置前的状态；内存与持久化都找不到时为 None
+    pub previous_status: Option<String>,
+    /// 是否中止了仍在运行的任务（需要 confirm）
+    pub aborted_running: bool,
+    /// 被结束的残留 streaming 消息数
+    pub settled_messages: usize,
+}
+
+/// 批量取消的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkCancelReport {
+    /// 被取消的任务总数（含排队中的任务）
+    pub cancelled: usize,
+    /// 其中尚未启动、直接出队的任务数
+    pub queued: usize,
+    pub task_ids: Vec<String>,
 }
 
 /// 文件上下文状态
@@ -49,3 +99,13 @@ pub struct FileContextStatus {
     pub file_count: usize,
     pub files: Vec<String>,
 }
+
+/// 持久化的执行消息分页（模型侧真实上下文，含工具结果行）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionMessagesPage {
+    pub task_id: String,
+    pub total: i64,
+    pub offset: i64,
+    pub messages: Vec<ExecutionMessage>,
+}
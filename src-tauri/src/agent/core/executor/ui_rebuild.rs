@@ -0,0 +1,459 @@
+/*!
+ * 从 UI 消息重建模型侧历史 - execution_messages 丢失或损坏时的修复手段
+ *
+ * UI 消息（messages 表）保存了用户文本、助手文本和工具块（含输入与输出），据此可以还原出
+ * 一份近似的 MessageParam 历史：文本块还原为对应角色的文本，已结束的工具块还原为
+ * assistant 的 tool_use 与紧随其后 user 消息中的 tool_result。thinking 块缺少签名、
+ * 仍在运行的工具没有结果，二者都会被丢弃。结果尽力而为，不保证与原始历史逐字一致。
+ */
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::agent::core::context::{render_message_content, ToolCallResult};
+use crate::agent::core::executor::TaskExecutor;
+use crate::agent::error::{TaskExecutorError, TaskExecutorResult};
+use crate::agent::persistence::MessageRole as ExecutionRole;
+use crate::agent::tools::ToolResultStatus;
+use crate::agent::types::{Block, Message, MessageRole as UiRole, ToolBlock, ToolStatus};
+use crate::agent::utils::tokenizer::count_text_tokens;
+use crate::llm::anthropic_types::{
+    ContentBlock, MessageContent, MessageParam, MessageRole, ToolResultContent,
+};
+
+/// 重建结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageRebuildReport {
+    pub session_id: i64,
+    /// 写入重建历史的任务（会话最近一次任务）
+    pub execution_id: String,
+    /// 读取的 UI 消息数
+    pub ui_messages: usize,
+    /// 重建出的模型消息数
+    pub rebuilt_messages: usize,
+    /// 还原的 tool_use/tool_result 对数
+    pub tool_pairs: usize,
+    /// 因没有结果而丢弃的工具调用数
+    pub dropped_tool_calls: usize,
+    /// 是否已覆盖存储的消息；未确认时只预览
+    pub applied: bool,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct RebuiltHistory {
+    pub(super) messages: Vec<MessageParam>,
+    tool_results: Vec<ToolCallResult>,
+    dropped_tool_calls: usize,
+}
+
+#[derive(Default)]
+struct HistoryBuilder {
+    history: RebuiltHistory,
+    assistant: Vec<ContentBlock>,
+    pending_results: Vec<ContentBlock>,
+}
+
+impl HistoryBuilder {
+    /// 同角色的相邻消息合并为一条，保持 user/assistant 交替
+    fn push(&mut self, role: MessageRole, blocks: Vec<ContentBlock>) {
+        if blocks.is_empty() {
+            return;
+        }
+        if let Some(last) = self.history.messages.last_mut() {
+            if last.role == role {
+                let mut merged =
+                    match std::mem::replace(&mut last.content, MessageContent::Text(String::new()))
+                    {
+                        MessageContent::Text(text) => vec![text_block(text)],
+                        MessageContent::Blocks(existing) => existing,
+                    };
+                merged.extend(blocks);
+                last.content = MessageContent::Blocks(merged);
+                return;
+            }
+        }
+        let content = match blocks.as_slice() {
+            [ContentBlock::Text { text, .. }] => MessageContent::Text(text.clone()),
+            _ => MessageContent::Blocks(blocks),
+        };
+        self.history.messages.push(MessageParam { role, content });
+    }
+
+    /// 结束当前一轮：写出 assistant 消息，再写出其工具结果
+    fn flush(&mut self) {
+        let assistant = std::mem::take(&mut self.assistant);
+        self.push(MessageRole::Assistant, assistant);
+        let results = std::mem::take(&mut self.pending_results);
+        self.push(MessageRole::User, results);
+    }
+
+    fn user_message(&mut self, message: &Message) {
+        self.flush();
+        let text = message
+            .blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::UserText(b) if !b.content.trim().is_empty() => Some(b.content.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !text.is_empty() {
+            self.push(MessageRole::User, vec![text_block(text)]);
+        }
+    }
+
+    fn assistant_message(&mut self, message: &Message) {
+        for block in &message.blocks {
+            match block {
+                Block::Text(b) if !b.content.trim().is_empty() => {
+                    // 工具调用之后出现的文本属于下一轮迭代
+                    if !self.pending_results.is_empty() {
+                        self.flush();
+                    }
+                    self.assistant.push(text_block(b.content.clone()));
+                }
+                Block::Tool(tool) => self.tool_block(tool),
+                _ => {}
+            }
+        }
+        self.flush();
+    }
+
+    fn tool_block(&mut self, tool: &ToolBlock) {
+        let Some(output) = tool.output.as_ref() else {
+            self.history.dropped_tool_calls += 1;
+            return;
+        };
+        let status = match tool.status {
+            ToolStatus::Completed => ToolResultStatus::Success,
+            ToolStatus::Error => ToolResultStatus::Error,
+            ToolStatus::Cancelled => ToolResultStatus::Cancelled,
+            ToolStatus::Running => {
+                self.history.dropped_tool_calls += 1;
+                return;
+            }
+        };
+
+        let result = ToolCallResult {
+            call_id: tool.id.clone(),
+            tool_name: tool.name.clone(),
+            result: output.content.clone(),
+            status,
+            execution_time_ms: tool.duration_ms.unwrap_or(0).max(0) as u64,
+            model_text: None,
+        };
+        self.assistant.push(ContentBlock::ToolUse {
+            id: tool.id.clone(),
+            name: tool.name.clone(),
+            input: tool.input.clone(),
+        });
+        self.pending_results.push(ContentBlock::ToolResult {
+            tool_use_id: tool.id.clone(),
+            content: Some(ToolResultContent::Text(result.model_content())),
+            is_error: Some(status != ToolResultStatus::Success),
+        });
+        self.history.tool_results.push(result);
+    }
+}
+
+fn text_block(text: String) -> ContentBlock {
+    ContentBlock::Text {
+        text,
+        cache_control: None,
+    }
+}
+
+pub(super) fn rebuild_history(messages: &[Message]) -> RebuiltHistory {
+    let mut builder = HistoryBuilder::default();
+    for message in messages {
+        match message.role {
+            UiRole::User => builder.user_message(message),
+            UiRole::Assistant => builder.assistant_message(message),
+        }
+    }
+    builder.flush();
+    builder.history
+}
+
+fn blocks_of(message: &MessageParam) -> &[ContentBlock] {
+    match &message.content {
+        MessageContent::Blocks(blocks) => blocks,
+        MessageContent::Text(_) => &[],
+    }
+}
+
+/// 校验 tool_use 与 tool_result 一一配对：每个 tool_use 的结果必须出现在紧随其后的 user 消息中，
+/// tool_result 只能引用上一条 assistant 消息中的 tool_use
+pub(super) fn validate_tool_pairing(messages: &[MessageParam]) -> Result<(), String> {
+    let mut open: HashSet<&str> = HashSet::new();
+    for (index, message) in messages.iter().enumerate() {
+        match message.role {
+            MessageRole::Assistant => {
+                if let Some(id) = open.iter().next() {
+                    return Err(format!("tool_use {} has no tool_result", id));
+                }
+                for block in blocks_of(message) {
+                    if let ContentBlock::ToolUse { id, .. } = block {
+                        if !open.insert(id.as_str()) {
+                            return Err(format!("duplicate tool_use {}", id));
+                        }
+                    }
+                }
+            }
+            MessageRole::User => {
+                for block in blocks_of(message) {
+                    if let ContentBlock::ToolResult { tool_use_id, .. } = block {
+                        if !open.remove(tool_use_id.as_str()) {
+                            return Err(format!(
+                                "tool_result {} at message {} has no preceding tool_use",
+                                tool_use_id, index
+                            ));
+                        }
+                    }
+                }
+                if let Some(id) = open.iter().next() {
+                    return Err(format!("tool_use {} has no tool_result", id));
+                }
+            }
+        }
+    }
+    match open.iter().next() {
+        Some(id) => Err(format!("tool_use {} has no tool_result", id)),
+        None => Ok(()),
+    }
+}
+
+/// 转换为 execution_messages 的行，与 TaskContext 的落库格式一致：
+/// assistant 存渲染后的内容，每个工具结果单独存一条 Tool 消息
+pub(super) fn history_rows(
+    history: &RebuiltHistory,
+) -> TaskExecutorResult<Vec<(ExecutionRole, String)>> {
+    let mut results = history.tool_results.iter();
+    let mut rows = Vec::new();
+    for message in &history.messages {
+        match (&message.role, &message.content) {
+            (MessageRole::Assistant, content) => {
+                rows.push((ExecutionRole::Assistant, render_message_content(content)));
+            }
+            (MessageRole::User, MessageContent::Text(text)) => {
+                rows.push((ExecutionRole::User, text.clone()));
+            }
+            (MessageRole::User, MessageContent::Blocks(blocks)) => {
+                for block in blocks {
+                    match block {
+                        ContentBlock::ToolResult { .. } => {
+                            if let Some(result) = results.next() {
+                                rows.push((ExecutionRole::Tool, serde_json::to_string(result)?));
+                            }
+                        }
+                        ContentBlock::Text { text, .. } => {
+                            rows.push((ExecutionRole::User, text.clone()));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    Ok(rows)
+}
+
+impl TaskExecutor {
+    /// 用会话的 UI 消息重建模型侧历史，写入会话最近一次任务并清空更早任务的执行消息。
+    /// confirm 为 false 时只返回预览；会话没有 UI 消息或任务时返回 None
+    pub async fn rebuild_messages_from_ui(
+        &self,
+        session_id: i64,
+        confirm: bool,
+    ) -> TaskExecutorResult<Option<MessageRebuildReport>> {
+        if let Some(entry) = self
+            .active_tasks()
+            .iter()
+            .find(|entry| entry.value().session_id == session_id)
+        {
+            return Err(TaskExecutorError::TaskStillRunning(entry.key().clone()));
+        }
+
+        let persistence = self.agent_persistence();
+        let ui_messages = persistence
+            .messages()
+            .list_by_session(session_id)
+            .await
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+        let executions = persistence
+            .agent_executions()
+            .list_recent_by_session(session_id, i64::MAX)
+            .await
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+        let Some(latest) = executions.first() else {
+            return Ok(None);
+        };
+        if ui_messages.is_empty() {
+            return Ok(None);
+        }
+
+        let history = rebuild_history(&ui_messages);
+        validate_tool_pairing(&history.messages).map_err(|e| {
+            TaskExecutorError::ContextRecoveryFailed(format!("Rebuilt history is invalid: {}", e))
+        })?;
+
+        let report = MessageRebuildReport {
+            session_id,
+            execution_id: latest.execution_id.clone(),
+            ui_messages: ui_messages.len(),
+            rebuilt_messages: history.messages.len(),
+            tool_pairs: history.tool_results.len(),
+            dropped_tool_calls: history.dropped_tool_calls,
+            applied: confirm,
+        };
+        if !confirm {
+            return Ok(Some(report));
+        }
+
+        // 删除与写入在同一事务中完成，中途失败不会留下被清空的历史
+        let rows: Vec<_> = history_rows(&history)?
+            .into_iter()
+            .map(|(role, content)| {
+                let tokens = i64::try_from(count_text_tokens(&content)).unwrap_or(i64::MAX);
+                (role, content, tokens)
+            })
+            .collect();
+        let cleared: Vec<String> = executions
+            .iter()
+            .map(|execution| execution.execution_id.clone())
+            .collect();
+        persistence
+            .execution_messages()
+            .replace_history(&cleared, &latest.execution_id, &rows)
+            .await
+            .map_err(|e| TaskExecutorError::StatePersistenceFailed(e.to_string()))?;
+
+        Ok(Some(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::types::{MessageStatus, TextBlock, ThinkingBlock, ToolOutput, UserTextBlock};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn message(role: UiRole, blocks: Vec<Block>) -> Message {
+        Message {
+            id: 0,
+            session_id: 1,
+            role,
+            status: MessageStatus::Completed,
+            blocks,
+            created_at: Utc::now(),
+            finished_at: None,
+            duration_ms: None,
+            token_usage: None,
+        }
+    }
+
+    fn text(content: &str) -> Block {
+        Block::Text(TextBlock {
+            id: "t".to_string(),
+            content: content.to_string(),
+            is_streaming: false,
+        })
+    }
+
+    fn tool(id: &str, status: ToolStatus, output: Option<&str>) -> Block {
+        Block::Tool(ToolBlock {
+            id: id.to_string(),
+            name: "read_file".to_string(),
+            status,
+            input: json!({ "path": "a.rs" }),
+            output: output.map(|o| ToolOutput {
+                content: json!(o),
+                cancel_reason: None,
+                ext: None,
+            }),
+            started_at: Utc::now(),
+            finished_at: None,
+            duration_ms: Some(5),
+        })
+    }
+
+    #[test]
+    fn rebuilds_paired_tool_history_across_iterations() {
+        let ui = vec![
+            message(
+                UiRole::User,
+                vec![Block::UserText(UserTextBlock {
+                    content: "read a.rs".to_string(),
+                })],
+            ),
+            message(
+                UiRole::Assistant,
+                vec![
+                    Block::Thinking(ThinkingBlock {
+                        id: "th".to_string(),
+                        content: "hmm".to_string(),
+                        is_streaming: false,
+                    }),
+                    text("Reading it."),
+                    tool("toolu_1", ToolStatus::Completed, Some("fn main() {}")),
+                    tool("toolu_2", ToolStatus::Running, None),
+                    text("It has a main function."),
+                ],
+            ),
+            message(
+                UiRole::User,
+                vec![Block::UserText(UserTextBlock {
+                    content: "thanks".to_string(),
+                })],
+            ),
+        ];
+
+        let history = rebuild_history(&ui);
+        assert!(validate_tool_pairing(&history.messages).is_ok());
+        assert_eq!(history.tool_results.len(), 1);
+        assert_eq!(history.dropped_tool_calls, 1);
+
+        let roles: Vec<MessageRole> = history.messages.iter().map(|m| m.role).collect();
+        assert_eq!(
+            roles,
+            vec![
+                MessageRole::User,
+                MessageRole::Assistant,
+                MessageRole::User,
+                MessageRole::Assistant,
+                MessageRole::User,
+            ]
+        );
+        assert!(matches!(
+            blocks_of(&history.messages[1]),
+            [ContentBlock::Text { .. }, ContentBlock::ToolUse { id, .. }] if id == "toolu_1"
+        ));
+        assert!(matches!(
+            blocks_of(&history.messages[2]),
+            [ContentBlock::ToolResult { tool_use_id, .. }] if tool_use_id == "toolu_1"
+        ));
+    }
+
+    #[test]
+    fn rejects_unpaired_tool_use() {
+        let messages = vec![
+            MessageParam {
+                role: MessageRole::Assistant,
+                content: MessageContent::Blocks(vec![ContentBlock::ToolUse {
+                    id: "toolu_1".to_string(),
+                    name: "read_file".to_string(),
+                    input: json!({}),
+                }]),
+            },
+            MessageParam {
+                role: MessageRole::User,
+                content: MessageContent::Text("next".to_string()),
+            },
+        ];
+        assert!(validate_tool_pairing(&messages).is_err());
+    }
+}
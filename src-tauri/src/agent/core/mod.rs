@@ -1,6 +1,7 @@
 pub mod commands;
 pub mod context;
 pub mod executor;
+pub mod terminal_error;
 pub mod types;
 pub mod utils;
 
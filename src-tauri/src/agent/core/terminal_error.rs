@@ -0,0 +1,138 @@
+/*!
+ * "解释这个错误"入口：从终端面板采集失败命令及输出，构造初始提示词
+ */
+
+use crate::completion::output_analyzer::OutputAnalyzer;
+use crate::mux::{PaneId, TerminalMux};
+use crate::utils::ansi::strip_ansi;
+
+/// 输出尾部最多保留的行数与字节数
+const OUTPUT_TAIL_MAX_LINES: usize = 200;
+const OUTPUT_TAIL_MAX_BYTES: usize = 16 * 1024;
+
+/// 从面板采集的失败现场
+#[derive(Debug, Clone, Default)]
+pub struct TerminalErrorCapture {
+    pub command_line: Option<String>,
+    pub exit_code: Option<i32>,
+    pub working_directory: Option<String>,
+    pub output_tail: String,
+    /// false 表示面板没有 shell integration，仅有原始滚动缓冲
+    pub has_integration: bool,
+}
+
+impl TerminalErrorCapture {
+    /// 采集面板最近一次命令与输出；没有 shell integration 时退化为原始缓冲尾部
+    pub fn capture(mux: &TerminalMux, pane_id: PaneId) -> Self {
+        let buffer = OutputAnalyzer::global()
+            .get_pane_buffer(pane_id.as_u32())
+            .unwrap_or_default();
+        let output_tail = tail(
+            &strip_ansi(&buffer),
+            OUTPUT_TAIL_MAX_LINES,
+            OUTPUT_TAIL_MAX_BYTES,
+        );
+
+        let has_integration = mux.shell_integration().is_integration_enabled(pane_id);
+        // 优先取已结束的最近一条命令，正在运行的命令没有退出码
+        let command = mux
+            .get_pane_command_history(pane_id)
+            .into_iter()
+            .last()
+            .or_else(|| mux.get_pane_current_command(pane_id));
+
+        match command {
+            Some(cmd) if has_integration => Self {
+                command_line: cmd.command_line.clone(),
+                exit_code: cmd.exit_code,
+                working_directory: cmd
+                    .working_directory
+                    .clone()
+                    .or_else(|| mux.shell_get_pane_cwd(pane_id)),
+                output_tail,
+                has_integration,
+            },
+            _ => Self {
+                working_directory: mux.shell_get_pane_cwd(pane_id),
+                output_tail,
+                has_integration,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.command_line.is_none() && self.output_tail.trim().is_empty()
+    }
+
+    /// 构造发给 agent 的初始提示词
+    pub fn to_prompt(&self) -> String {
+        let mut prompt = String::from(
+            "A command failed in my terminal. Explain what went wrong and how to fix it.\n\n",
+        );
+        if let Some(command) = &self.command_line {
+            prompt.push_str(&format!("Command: `{}`\n", command));
+        }
+        if let Some(code) = self.exit_code {
+            prompt.push_str(&format!("Exit code: {}\n", code));
+        }
+        if let Some(cwd) = &self.working_directory {
+            prompt.push_str(&format!("Working directory: {}\n", cwd));
+        }
+        if !self.has_integration {
+            prompt.push_str(
+                "(Shell integration unavailable: the output below is raw terminal scrollback.)\n",
+            );
+        }
+        prompt.push_str("\nRecent output:\n```\n");
+        prompt.push_str(self.output_tail.trim_end());
+        prompt.push_str("\n```\n");
+        prompt
+    }
+}
+
+/// 取最后 max_lines 行，且总长度不超过 max_bytes（按字符边界截断）
+fn tail(text: &str, max_lines: usize, max_bytes: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    let joined = lines[start..].join("\n");
+    if joined.len() <= max_bytes {
+        return joined;
+    }
+    let mut cut = joined.len() - max_bytes;
+    while !joined.is_char_boundary(cut) {
+        cut += 1;
+    }
+    joined[cut..].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_caps_lines_and_bytes() {
+        let text = (0..10)
+            .map(|i| format!("line{}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(tail(&text, 2, 1024), "line8\nline9");
+        assert_eq!(tail(&text, 10, 5), "line9");
+    }
+
+    #[test]
+    fn prompt_includes_exit_code_and_output() {
+        let capture = TerminalErrorCapture {
+            command_line: Some("cargo build".to_string()),
+            exit_code: Some(101),
+            working_directory: Some("/tmp/project".to_string()),
+            output_tail: "error[E0425]: cannot find value".to_string(),
+            has_integration: true,
+        };
+        let prompt = capture.to_prompt();
+        assert!(prompt.contains("Command: `cargo build`"));
+        assert!(prompt.contains("Exit code: 101"));
+        assert!(prompt.contains("error[E0425]"));
+        assert!(!prompt.contains("Shell integration unavailable"));
+    }
+}
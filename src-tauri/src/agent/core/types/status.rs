@@ -6,6 +6,8 @@ use crate::agent::persistence::ExecutionStatus;
 #[serde(rename_all = "lowercase")]
 pub enum AgentTaskStatus {
     Created,
+    /// 等待调度队列中的空闲槽位
+    Queued,
     Running,
     Paused,
     Completed,
@@ -17,6 +19,7 @@ impl AgentTaskStatus {
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Created => "created",
+            Self::Queued => "queued",
             Self::Running => "running",
             Self::Paused => "paused",
             Self::Completed => "completed",
@@ -43,9 +46,10 @@ impl From<&AgentTaskStatus> for ExecutionStatus {
             AgentTaskStatus::Cancelled => ExecutionStatus::Cancelled,
             AgentTaskStatus::Completed => ExecutionStatus::Completed,
             AgentTaskStatus::Error => ExecutionStatus::Error,
-            AgentTaskStatus::Created | AgentTaskStatus::Running | AgentTaskStatus::Paused => {
-                ExecutionStatus::Running
-            }
+            AgentTaskStatus::Created
+            | AgentTaskStatus::Queued
+            | AgentTaskStatus::Running
+            | AgentTaskStatus::Paused => ExecutionStatus::Running,
         }
     }
 }
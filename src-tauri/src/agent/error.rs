@@ -65,6 +65,9 @@ pub enum TaskExecutorError {
     #[error("Task cancelled: {0}")]
     TaskCancelled(String),
 
+    #[error("Task is still running: {0}")]
+    TaskStillRunning(String),
+
     #[error("Maximum iteration limit reached: {current}/{max}")]
     MaxIterationsReached { current: u32, max: u32 },
 
@@ -104,6 +107,9 @@ pub enum TaskExecutorError {
     #[error("Invalid task state transition: {from} -> {to}")]
     InvalidStateTransition { from: String, to: String },
 
+    #[error("Tool call already succeeded: {0}")]
+    ToolAlreadySucceeded(String),
+
     #[error("Internal task executor error: {0}")]
     InternalError(String),
 }
@@ -114,6 +120,7 @@ impl TaskExecutorError {
             TaskExecutorError::TaskNotFound(_) => false,
             TaskExecutorError::TaskAlreadyCompleted(_) => false,
             TaskExecutorError::TaskCancelled(_) => false,
+            TaskExecutorError::TaskStillRunning(_) => false,
             TaskExecutorError::MaxIterationsReached { .. } => false,
             TaskExecutorError::TooManyErrors { .. } => false,
             TaskExecutorError::LLMCallFailed(_) => true,
@@ -127,6 +134,7 @@ impl TaskExecutorError {
             TaskExecutorError::RepositoryError(_) => true,
             TaskExecutorError::TaskInterrupted => true,
             TaskExecutorError::InvalidStateTransition { .. } => false,
+            TaskExecutorError::ToolAlreadySucceeded(_) => false,
             TaskExecutorError::InternalError(_) => false,
         }
     }
@@ -136,6 +144,7 @@ impl TaskExecutorError {
             TaskExecutorError::TaskNotFound(_) => ErrorSeverity::Warning,
             TaskExecutorError::TaskAlreadyCompleted(_) => ErrorSeverity::Info,
             TaskExecutorError::TaskCancelled(_) => ErrorSeverity::Info,
+            TaskExecutorError::TaskStillRunning(_) => ErrorSeverity::Warning,
             TaskExecutorError::MaxIterationsReached { .. } => ErrorSeverity::Warning,
             TaskExecutorError::TooManyErrors { .. } => ErrorSeverity::Error,
             TaskExecutorError::LLMCallFailed(_) => ErrorSeverity::Error,
@@ -149,6 +158,7 @@ impl TaskExecutorError {
             TaskExecutorError::RepositoryError(_) => ErrorSeverity::Error,
             TaskExecutorError::TaskInterrupted => ErrorSeverity::Info,
             TaskExecutorError::InvalidStateTransition { .. } => ErrorSeverity::Error,
+            TaskExecutorError::ToolAlreadySucceeded(_) => ErrorSeverity::Warning,
             TaskExecutorError::InternalError(_) => ErrorSeverity::Critical,
         }
     }
@@ -296,6 +306,23 @@ impl From<AgentError> for ToolExecutorError {
     }
 }
 
+// ==================== 会话导入错误 ====================
+
+#[derive(Error, Debug)]
+pub enum ConversationImportError {
+    #[error("Invalid conversation export: {0}")]
+    InvalidFormat(String),
+
+    #[error("Unsupported conversation export version: {0}")]
+    UnsupportedVersion(u64),
+
+    #[error("Invalid conversation structure: {0}")]
+    InvalidStructure(String),
+
+    #[error("Failed to save imported conversation: {0}")]
+    Persistence(#[from] AgentError),
+}
+
 // ==================== 共用类型 ====================
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -2,7 +2,8 @@ use crate::agent::config::CompactionConfig;
 use crate::agent::error::AgentResult;
 use crate::agent::utils::tokenizer::count_message_param_tokens;
 use crate::llm::anthropic_types::{
-    ContentBlock, MessageContent, MessageParam, ToolResultContent as AnthropicToolResultContent,
+    ContentBlock, MessageContent, MessageParam, MessageRole,
+    ToolResultContent as AnthropicToolResultContent,
 };
 
 pub struct MessageCompactor {
@@ -102,6 +103,55 @@ impl MessageCompactor {
         })
     }
 
+    /// 按轮数主动压缩：以 assistant 消息为一轮的起点，保留最近 proactive_keep_turns 轮，
+    /// 更早的消息清理工具结果。压缩边界按 proactive_every_turns 取整，
+    /// 两次压缩之间被压缩的前缀保持不变，不会每轮都打破 prompt cache
+    pub fn compact_by_turns(&self, messages: Vec<MessageParam>) -> CompactionResult {
+        let every = self.config.proactive_every_turns;
+        if every == 0 {
+            return CompactionResult::NoCompaction(messages);
+        }
+
+        let turn_starts: Vec<usize> = messages
+            .iter()
+            .enumerate()
+            .filter(|(_, msg)| msg.role == MessageRole::Assistant)
+            .map(|(idx, _)| idx)
+            .collect();
+        let eligible = turn_starts
+            .len()
+            .saturating_sub(self.config.proactive_keep_turns);
+        let compact_turns = eligible / every * every;
+        if compact_turns == 0 {
+            return CompactionResult::NoCompaction(messages);
+        }
+
+        let split_point = turn_starts
+            .get(compact_turns)
+            .copied()
+            .unwrap_or(messages.len());
+        let cleared = self.clear_tool_results(&messages[..split_point]);
+        let tokens_saved = messages[..split_point]
+            .iter()
+            .zip(&cleared)
+            .map(|(old, new)| {
+                count_message_param_tokens(old).saturating_sub(count_message_param_tokens(new))
+                    as u32
+            })
+            .fold(0u32, |acc, n| acc.saturating_add(n));
+        if tokens_saved == 0 {
+            return CompactionResult::NoCompaction(messages);
+        }
+
+        let mut compacted = cleared;
+        compacted.extend(messages.into_iter().skip(split_point));
+        CompactionResult::Compacted {
+            messages: compacted,
+            tokens_saved,
+            messages_summarized: split_point,
+        }
+    }
+
     // 按配置切分为中段和最近段（不再假定第一条是系统消息）
     fn split_messages(&self, messages: &[MessageParam]) -> (Vec<MessageParam>, Vec<MessageParam>) {
         let keep_count = self.config.keep_recent_count.min(messages.len());
@@ -170,3 +220,75 @@ impl CompactionResult {
         matches!(self, CompactionResult::Compacted { .. })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::config::CompactionPolicy;
+
+    fn turn(idx: usize) -> [MessageParam; 2] {
+        [
+            MessageParam {
+                role: MessageRole::Assistant,
+                content: MessageContent::Blocks(vec![ContentBlock::ToolUse {
+                    id: format!("call_{}", idx),
+                    name: "read_file".to_string(),
+                    input: serde_json::json!({ "path": "a.rs" }),
+                }]),
+            },
+            MessageParam {
+                role: MessageRole::User,
+                content: MessageContent::Blocks(vec![ContentBlock::ToolResult {
+                    tool_use_id: format!("call_{}", idx),
+                    content: Some(AnthropicToolResultContent::Text(
+                        "fn main() { println!(\"hello world\"); }".repeat(20),
+                    )),
+                    is_error: None,
+                }]),
+            },
+        ]
+    }
+
+    fn compactor() -> MessageCompactor {
+        MessageCompactor::new().with_config(CompactionConfig {
+            policy: CompactionPolicy::Proactive,
+            proactive_every_turns: 3,
+            proactive_keep_turns: 2,
+            ..CompactionConfig::default()
+        })
+    }
+
+    #[test]
+    fn proactive_compaction_waits_for_a_full_batch_of_turns() {
+        let messages: Vec<MessageParam> = (0..4).flat_map(turn).collect();
+        assert!(!compactor().compact_by_turns(messages).was_compacted());
+    }
+
+    #[test]
+    fn proactive_compaction_keeps_recent_turns_verbatim() {
+        let messages: Vec<MessageParam> = (0..6).flat_map(turn).collect();
+        let result = compactor().compact_by_turns(messages.clone());
+        let CompactionResult::Compacted {
+            messages: compacted,
+            messages_summarized,
+            tokens_saved,
+        } = result
+        else {
+            panic!("expected compaction");
+        };
+
+        // 6 轮中可压缩 4 轮，按每 3 轮取整后压缩前 3 轮
+        assert_eq!(messages_summarized, 6);
+        assert!(tokens_saved > 0);
+        assert_eq!(compacted.len(), messages.len());
+        let cleared = serde_json::to_string(&compacted[1]).unwrap();
+        assert!(cleared.contains("[tool result cleared]"));
+        assert_eq!(compacted[6..], messages[6..]);
+    }
+
+    #[test]
+    fn off_policy_keeps_overflow_compaction() {
+        let policy: CompactionPolicy = serde_json::from_str("\"off\"").unwrap();
+        assert_eq!(policy, CompactionPolicy::OverflowOnly);
+    }
+}
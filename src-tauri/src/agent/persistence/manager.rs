@@ -4,8 +4,8 @@ use crate::storage::database::DatabaseManager;
 
 use super::repositories::{
     AgentExecutionRepository, ExecutionEventRepository, ExecutionMessageRepository,
-    MessageRepository, SessionRepository, SessionSummaryRepository, ToolExecutionRepository,
-    WorkspaceFileContextRepository, WorkspaceRepository,
+    MessageEmbeddingRepository, MessageRepository, SessionRepository, SessionSummaryRepository,
+    ToolExecutionRepository, WorkspaceFileContextRepository, WorkspaceRepository,
 };
 
 /// Facade that wires all persistence repositories together for the agent backend.
@@ -19,6 +19,7 @@ pub struct AgentPersistence {
     file_context: WorkspaceFileContextRepository,
     agent_executions: AgentExecutionRepository,
     execution_messages: ExecutionMessageRepository,
+    message_embeddings: MessageEmbeddingRepository,
     tool_executions: ToolExecutionRepository,
     execution_events: ExecutionEventRepository,
 }
@@ -33,6 +34,7 @@ impl AgentPersistence {
             file_context: WorkspaceFileContextRepository::new(Arc::clone(&database)),
             agent_executions: AgentExecutionRepository::new(Arc::clone(&database)),
             execution_messages: ExecutionMessageRepository::new(Arc::clone(&database)),
+            message_embeddings: MessageEmbeddingRepository::new(Arc::clone(&database)),
             tool_executions: ToolExecutionRepository::new(Arc::clone(&database)),
             execution_events: ExecutionEventRepository::new(Arc::clone(&database)),
             database,
@@ -71,6 +73,10 @@ impl AgentPersistence {
         &self.execution_messages
     }
 
+    pub fn message_embeddings(&self) -> &MessageEmbeddingRepository {
+        &self.message_embeddings
+    }
+
     pub fn tool_executions(&self) -> &ToolExecutionRepository {
         &self.tool_executions
     }
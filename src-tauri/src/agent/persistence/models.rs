@@ -27,6 +27,22 @@ pub struct Session {
     pub updated_at: DateTime<Utc>,
 }
 
+/// 导入会话时一次性写入的数据，见 SessionRepository::import
+#[derive(Debug, Clone)]
+pub struct SessionImport {
+    pub workspace_path: String,
+    pub title: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// UI 消息，id 与 session_id 会被忽略
+    pub messages: Vec<crate::agent::types::Message>,
+    /// 承载模型侧历史的任务；history 为空时不创建
+    pub execution_id: String,
+    pub user_request: String,
+    /// (角色, 内容, token 数)
+    pub history: Vec<(MessageRole, String, i64)>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionSummary {
     pub session_id: i64,
@@ -222,6 +238,31 @@ pub struct ExecutionMessage {
     pub created_at: DateTime<Utc>,
 }
 
+/// 已向量化的对话消息，用于跨对话的历史回忆
+#[derive(Debug, Clone)]
+pub struct MessageEmbedding {
+    pub message_id: i64,
+    pub execution_id: String,
+    pub session_id: i64,
+    pub role: MessageRole,
+    pub content: String,
+    pub embedding_model: String,
+    pub embedding: Vec<f32>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// embedding 以小端 f32 序列存入 BLOB
+pub(crate) fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub(crate) fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ToolExecutionStatus {
     Pending,
@@ -428,6 +469,21 @@ pub(crate) fn build_execution_message(
     })
 }
 
+pub(crate) fn build_message_embedding(
+    row: &sqlx::sqlite::SqliteRow,
+) -> AgentResult<MessageEmbedding> {
+    Ok(MessageEmbedding {
+        message_id: row.try_get("message_id")?,
+        execution_id: row.try_get("execution_id")?,
+        session_id: row.try_get("session_id")?,
+        role: MessageRole::from_str(row.try_get::<String, _>("role")?.as_str())?,
+        content: row.try_get("content")?,
+        embedding_model: row.try_get("embedding_model")?,
+        embedding: decode_embedding(&row.try_get::<Vec<u8>, _>("embedding")?),
+        created_at: timestamp_to_datetime(row.try_get::<i64, _>("created_at")?),
+    })
+}
+
 pub(crate) fn build_tool_execution(row: &sqlx::sqlite::SqliteRow) -> AgentResult<ToolExecution> {
     Ok(ToolExecution {
         id: row.try_get("id")?,
@@ -10,15 +10,16 @@ use crate::agent::types::{
 use crate::storage::database::DatabaseManager;
 
 use super::models::{
-    build_agent_execution, build_execution_event, build_execution_message, build_session,
-    build_session_summary, build_tool_execution, build_workspace, build_workspace_file_record,
-    AgentExecution, ExecutionEvent, ExecutionEventType, ExecutionMessage, ExecutionStatus,
-    FileRecordSource, FileRecordState, MessageRole as AgentMessageRole, Session, SessionSummary,
+    build_agent_execution, build_execution_event, build_execution_message, build_message_embedding,
+    build_session, build_session_summary, build_tool_execution, build_workspace,
+    build_workspace_file_record, encode_embedding, AgentExecution, ExecutionEvent,
+    ExecutionEventType, ExecutionMessage, ExecutionStatus, FileRecordSource, FileRecordState,
+    MessageEmbedding, MessageRole as AgentMessageRole, Session, SessionImport, SessionSummary,
     TokenUsageStats, ToolExecution, ToolExecutionStatus, Workspace, WorkspaceFileRecord,
 };
 use super::{
-    bool_to_sql, now_timestamp, opt_datetime_to_timestamp, opt_timestamp_to_datetime,
-    timestamp_to_datetime,
+    bool_to_sql, datetime_to_timestamp, now_timestamp, opt_datetime_to_timestamp,
+    opt_timestamp_to_datetime, timestamp_to_datetime,
 };
 
 #[derive(Debug)]
@@ -189,6 +190,116 @@ impl SessionRepository {
         Ok(())
     }
 
+    /// 在一个事务中创建会话及其 UI 消息与模型侧历史，保留原有时间戳；任一步失败都不会留下数据。
+    /// 返回新会话 ID
+    pub async fn import(&self, import: &SessionImport) -> AgentResult<i64> {
+        let ts = now_timestamp();
+        let mut tx = self.pool().begin().await?;
+
+        sqlx::query(
+            "INSERT INTO workspaces (path, display_name, active_session_id, created_at, updated_at, last_accessed_at)
+             VALUES (?, NULL, NULL, ?, ?, ?)
+             ON CONFLICT(path) DO NOTHING",
+        )
+        .bind(&import.workspace_path)
+        .bind(ts)
+        .bind(ts)
+        .bind(ts)
+        .execute(&mut *tx)
+        .await?;
+
+        // 与新建消息时一致，未提供标题时取第一条用户消息
+        let title = import.title.clone().or_else(|| {
+            import
+                .messages
+                .iter()
+                .filter(|m| matches!(m.role, UiMessageRole::User))
+                .flat_map(|m| m.blocks.iter())
+                .find_map(|b| match b {
+                    Block::UserText(t) => derive_session_title(&t.content),
+                    _ => None,
+                })
+        });
+        let session_id = sqlx::query(
+            "INSERT INTO sessions (workspace_path, title, created_at, updated_at)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(&import.workspace_path)
+        .bind(title)
+        .bind(datetime_to_timestamp(import.created_at))
+        .bind(datetime_to_timestamp(import.updated_at))
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        for message in &import.messages {
+            let blocks_json = serde_json::to_string(&message.blocks).map_err(|e| {
+                AgentError::Internal(format!("Failed to serialize message blocks: {}", e))
+            })?;
+            let (input_tokens, output_tokens, cache_read_tokens, cache_write_tokens) =
+                token_usage_to_columns(message.token_usage.as_ref());
+
+            sqlx::query(
+                "INSERT INTO messages (
+                    session_id, role, status, blocks_json, created_at, finished_at, duration_ms,
+                    input_tokens, output_tokens, cache_read_tokens, cache_write_tokens
+                 ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(session_id)
+            .bind(role_as_str(&message.role))
+            .bind(status_as_str(&message.status))
+            .bind(blocks_json)
+            .bind(datetime_to_timestamp(message.created_at))
+            .bind(opt_datetime_to_timestamp(message.finished_at))
+            .bind(message.duration_ms)
+            .bind(input_tokens)
+            .bind(output_tokens)
+            .bind(cache_read_tokens)
+            .bind(cache_write_tokens)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if !import.history.is_empty() {
+            let finished_at = datetime_to_timestamp(import.updated_at);
+            sqlx::query(
+                "INSERT INTO agent_executions (
+                    execution_id, session_id, user_request, system_prompt_used, execution_config,
+                    has_conversation_context, status, created_at, updated_at, started_at, completed_at
+                 ) VALUES (?, ?, ?, '', NULL, 0, 'completed', ?, ?, ?, ?)",
+            )
+            .bind(&import.execution_id)
+            .bind(session_id)
+            .bind(&import.user_request)
+            .bind(datetime_to_timestamp(import.created_at))
+            .bind(finished_at)
+            .bind(datetime_to_timestamp(import.created_at))
+            .bind(finished_at)
+            .execute(&mut *tx)
+            .await?;
+
+            for (sequence, (role, content, tokens)) in import.history.iter().enumerate() {
+                sqlx::query(
+                    "INSERT INTO execution_messages (
+                        execution_id, role, content, tokens, is_summary,
+                        iteration, sequence, created_at
+                     ) VALUES (?, ?, ?, ?, 0, 0, ?, ?)",
+                )
+                .bind(&import.execution_id)
+                .bind(role.as_str())
+                .bind(content)
+                .bind(tokens)
+                .bind(sequence as i64)
+                .bind(finished_at)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(session_id)
+    }
+
     pub async fn delete(&self, id: i64) -> AgentResult<()> {
         sqlx::query("DELETE FROM sessions WHERE id = ?")
             .bind(id)
@@ -582,6 +693,7 @@ fn build_message(row: &sqlx::sqlite::SqliteRow) -> AgentResult<Message> {
                 output_tokens,
                 cache_read_tokens,
                 cache_write_tokens,
+                thinking_tokens: None,
             })
         }
         _ => None,
@@ -1016,6 +1128,44 @@ impl ExecutionMessageRepository {
             .ok_or_else(|| AgentError::Internal("Failed to append execution message".to_string()))
     }
 
+    /// 原地更新 call_id 对应的最近一条 Tool 消息；不存在时返回 false
+    pub async fn update_tool_result(
+        &self,
+        execution_id: &str,
+        call_id: &str,
+        content: &str,
+        tokens: i64,
+    ) -> AgentResult<bool> {
+        let rows = sqlx::query(
+            "SELECT id, content FROM execution_messages
+             WHERE execution_id = ? AND role = ?
+             ORDER BY iteration DESC, sequence DESC",
+        )
+        .bind(execution_id)
+        .bind(AgentMessageRole::Tool.as_str())
+        .fetch_all(self.pool())
+        .await?;
+
+        let target = rows.iter().find_map(|row| {
+            let stored: String = row.try_get("content").ok()?;
+            let value: serde_json::Value = serde_json::from_str(&stored).ok()?;
+            (value.get("call_id")?.as_str()? == call_id)
+                .then(|| row.try_get::<i64, _>("id").ok())
+                .flatten()
+        });
+        let Some(message_id) = target else {
+            return Ok(false);
+        };
+
+        sqlx::query("UPDATE execution_messages SET content = ?, tokens = ? WHERE id = ?")
+            .bind(content)
+            .bind(tokens)
+            .bind(message_id)
+            .execute(self.pool())
+            .await?;
+        Ok(true)
+    }
+
     pub async fn latest_for_execution(
         &self,
         execution_id: &str,
@@ -1050,6 +1200,79 @@ impl ExecutionMessageRepository {
             .collect()
     }
 
+    /// 分页读取，顺序与 `list_by_execution` 一致
+    pub async fn list_page_by_execution(
+        &self,
+        execution_id: &str,
+        offset: i64,
+        limit: i64,
+    ) -> AgentResult<Vec<ExecutionMessage>> {
+        let rows = sqlx::query(
+            "SELECT * FROM execution_messages
+             WHERE execution_id = ?
+             ORDER BY iteration ASC, sequence ASC
+             LIMIT ? OFFSET ?",
+        )
+        .bind(execution_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.pool())
+        .await?;
+
+        rows.into_iter()
+            .map(|r| build_execution_message(&r))
+            .collect()
+    }
+
+    pub async fn count_by_execution(&self, execution_id: &str) -> AgentResult<i64> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM execution_messages WHERE execution_id = ?")
+                .bind(execution_id)
+                .fetch_one(self.pool())
+                .await?;
+        Ok(count)
+    }
+
+    /// 在一个事务中清空 cleared 中各任务的执行消息，并把 rows（角色、内容、token 数）
+    /// 按顺序写入 execution_id，失败时保持原样
+    pub async fn replace_history(
+        &self,
+        cleared: &[String],
+        execution_id: &str,
+        rows: &[(AgentMessageRole, String, i64)],
+    ) -> AgentResult<()> {
+        let ts = now_timestamp();
+        let mut tx = self.pool().begin().await?;
+
+        for id in cleared {
+            sqlx::query("DELETE FROM execution_messages WHERE execution_id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for (sequence, (role, content, tokens)) in rows.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO execution_messages (
+                    execution_id, role, content, tokens, is_summary,
+                    iteration, sequence, created_at
+                 ) VALUES (?, ?, ?, ?, ?, 0, ?, ?)",
+            )
+            .bind(execution_id)
+            .bind(role.as_str())
+            .bind(content)
+            .bind(tokens)
+            .bind(bool_to_sql(false))
+            .bind(sequence as i64)
+            .bind(ts)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     pub async fn delete_for_execution(&self, execution_id: &str) -> AgentResult<()> {
         sqlx::query("DELETE FROM execution_messages WHERE execution_id = ?")
             .bind(execution_id)
@@ -1059,6 +1282,75 @@ impl ExecutionMessageRepository {
     }
 }
 
+#[derive(Debug)]
+pub struct MessageEmbeddingRepository {
+    database: Arc<DatabaseManager>,
+}
+
+impl MessageEmbeddingRepository {
+    pub fn new(database: Arc<DatabaseManager>) -> Self {
+        Self { database }
+    }
+
+    fn pool(&self) -> &sqlx::SqlitePool {
+        self.database.pool()
+    }
+
+    pub async fn upsert(
+        &self,
+        message: &ExecutionMessage,
+        session_id: i64,
+        workspace_path: &str,
+        embedding_model: &str,
+        embedding: &[f32],
+    ) -> AgentResult<()> {
+        sqlx::query(
+            "INSERT INTO execution_message_embeddings (
+                message_id, execution_id, session_id, workspace_path, role,
+                content, embedding_model, embedding, created_at
+             ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(message_id) DO UPDATE SET
+                embedding_model = excluded.embedding_model,
+                embedding = excluded.embedding",
+        )
+        .bind(message.id)
+        .bind(&message.execution_id)
+        .bind(session_id)
+        .bind(workspace_path)
+        .bind(message.role.as_str())
+        .bind(&message.content)
+        .bind(embedding_model)
+        .bind(encode_embedding(embedding))
+        .bind(datetime_to_timestamp(message.created_at))
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// 工作区内由指定模型生成的消息向量，排除当前任务自身的消息
+    pub async fn list_for_workspace(
+        &self,
+        workspace_path: &str,
+        embedding_model: &str,
+        exclude_execution_id: &str,
+    ) -> AgentResult<Vec<MessageEmbedding>> {
+        let rows = sqlx::query(
+            "SELECT * FROM execution_message_embeddings
+             WHERE workspace_path = ? AND embedding_model = ? AND execution_id != ?
+             ORDER BY created_at DESC",
+        )
+        .bind(workspace_path)
+        .bind(embedding_model)
+        .bind(exclude_execution_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        rows.into_iter()
+            .map(|r| build_message_embedding(&r))
+            .collect()
+    }
+}
+
 #[derive(Debug)]
 pub struct ToolExecutionRepository {
     database: Arc<DatabaseManager>,
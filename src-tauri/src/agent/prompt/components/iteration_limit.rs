@@ -0,0 +1,7 @@
+/// 达到最大迭代次数后，请求模型总结目前进展的提示词。
+pub const ITERATION_LIMIT_SUMMARY_PROMPT: &str = r#"You have reached the maximum number of steps allowed for this task and must stop now. Do not call any tools.
+
+Write a brief final report for the user:
+1. What has been completed so far.
+2. What remains unfinished, and where you stopped.
+3. The concrete next steps the user can take (or ask you to take) to finish the task."#;
@@ -1,5 +1,7 @@
 pub mod agent;
 pub mod conversation_summary;
+pub mod iteration_limit;
+pub mod path_summary;
 pub mod registry;
 pub mod system;
 pub mod task;
@@ -0,0 +1,57 @@
+/// Prompt template for summarizing a file or directory in a single LLM call.
+///
+/// Used by the lightweight `agent_summarize_path` entrypoint, which does not
+/// run the ReAct loop and therefore has no tools available.
+pub const PATH_SUMMARY_SYSTEM_PROMPT: &str = r#"You are a senior engineer explaining a piece of a codebase to a teammate who has not read it yet.
+Given the source files below, explain what this code does.
+
+Structure your answer as:
+1. Purpose: One or two sentences on what the module/file is responsible for.
+2. Key Components: The most important types, functions, or files and what each does.
+3. Interactions: How it is used by, or depends on, other parts of the codebase (use the related snippets if provided).
+4. Notes: Anything non-obvious — invariants, side effects, error handling, or TODOs worth knowing.
+
+Be concise and concrete. Refer to identifiers and file paths exactly as they appear. Do not invent behavior that is not visible in the code."#;
+
+/// System prompt for the reduce step when the input had to be split into parts.
+pub const PATH_SUMMARY_MERGE_SYSTEM_PROMPT: &str = r#"You are given partial summaries of different parts of the same file or directory.
+Merge them into a single summary with the sections Purpose, Key Components, Interactions and Notes.
+Remove duplication, keep concrete identifiers and file paths, and do not add information that is not present in the partial summaries."#;
+
+/// User prompt for summarizing one batch of source files.
+///
+/// # Arguments
+/// * `path` - The file or directory being summarized (as shown to the user)
+/// * `sources` - Formatted source files
+/// * `related` - Formatted related snippets from elsewhere in the workspace, may be empty
+/// * `part` - `Some((index, total))` when this is one part of a larger input
+pub fn build_path_summary_user_prompt(
+    path: &str,
+    sources: &str,
+    related: &str,
+    part: Option<(usize, usize)>,
+) -> String {
+    let mut prompt = match part {
+        Some((index, total)) => format!(
+            "Summarize part {} of {} of `{}`. Other parts are summarized separately.\n\n",
+            index, total, path
+        ),
+        None => format!("Summarize `{}`.\n\n", path),
+    };
+    prompt.push_str("Source files:\n\n");
+    prompt.push_str(sources);
+    if !related.is_empty() {
+        prompt.push_str("\n\nRelated code elsewhere in the workspace:\n\n");
+        prompt.push_str(related);
+    }
+    prompt
+}
+
+/// User prompt for merging partial summaries.
+pub fn build_path_summary_merge_prompt(path: &str, partials: &[String]) -> String {
+    let mut prompt = format!("Partial summaries of `{}`:\n\n", path);
+    for (index, partial) in partials.iter().enumerate() {
+        prompt.push_str(&format!("## Part {}\n{}\n\n", index + 1, partial.trim()));
+    }
+    prompt
+}
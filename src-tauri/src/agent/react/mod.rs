@@ -3,11 +3,13 @@
 pub mod loop_detector;
 pub mod orchestrator;
 pub mod runtime;
+pub mod trace;
 pub mod types;
 
 pub use loop_detector::LoopDetector;
 pub use orchestrator::ReactOrchestrator;
 pub use runtime::ReactRuntime;
+pub use trace::ReactTrace;
 pub use types::*;
 
 use once_cell::sync::Lazy;
@@ -17,21 +17,40 @@ use tokio_stream::StreamExt;
 use tracing::warn;
 use uuid::Uuid;
 
-use crate::agent::config::CompactionConfig;
+use crate::agent::config::{CompactionPolicy, MaxIterationsBehavior};
 use crate::agent::context::SessionSummarizer;
 use crate::agent::core::context::TaskContext;
 use crate::agent::core::iteration_outcome::IterationOutcome;
 use crate::agent::error::{TaskExecutorError, TaskExecutorResult};
 use crate::agent::memory::compactor::{CompactionResult, MessageCompactor};
 use crate::agent::persistence::AgentPersistence;
+use crate::agent::prompt::components::iteration_limit::ITERATION_LIMIT_SUMMARY_PROMPT;
+use crate::agent::react::types::CompactionTrigger;
 use crate::agent::state::iteration::{IterationContext, IterationSnapshot};
-use crate::agent::state::session::CompressedMemory;
-use crate::agent::types::{Block, TextBlock, ThinkingBlock};
+use crate::agent::state::session::{CompressedMemory, MEMORY_COMPRESSION_THRESHOLD};
+use crate::agent::types::{Block, TaskEvent, TextBlock, ThinkingBlock, TokenUsage};
+use crate::agent::utils::tokenizer::{count_message_param_tokens, count_text_tokens};
 use crate::llm::anthropic_types::{
-    ContentBlock, ContentBlockStart, ContentDelta, StreamEvent, SystemPrompt,
+    ContentBlock, ContentBlockStart, ContentDelta, MessageContent, MessageParam, MessageRole,
+    StreamEvent, SystemPrompt,
 };
+use crate::storage::cache::UnifiedCache;
 use crate::storage::DatabaseManager;
 
+/// 模型未配置 maxContextTokens 时使用的上下文窗口
+pub const DEFAULT_CONTEXT_WINDOW: u32 = 128_000;
+
+/// 模型选项中配置的上下文窗口（maxContextTokens），优先读缓存
+pub(crate) async fn model_context_window(
+    database: &DatabaseManager,
+    cache: &UnifiedCache,
+    model_id: &str,
+) -> Option<u32> {
+    crate::ai::model_metadata::model_metadata(database, cache, model_id)
+        .await?
+        .context_window
+}
+
 /// 内容块累积器（用于流式组装）
 enum BlockAccumulator {
     Text(String),
@@ -40,19 +59,28 @@ enum BlockAccumulator {
         name: String,
         input_json: String,
     },
-    Thinking(String),
+    Thinking {
+        thinking: String,
+        signature: Option<String>,
+    },
 }
 
 /// ReAct 循环编排器
 pub struct ReactOrchestrator {
     database: Arc<DatabaseManager>,
+    cache: Arc<UnifiedCache>,
     agent_persistence: Arc<AgentPersistence>,
 }
 
 impl ReactOrchestrator {
-    pub fn new(database: Arc<DatabaseManager>, agent_persistence: Arc<AgentPersistence>) -> Self {
+    pub fn new(
+        database: Arc<DatabaseManager>,
+        cache: Arc<UnifiedCache>,
+        agent_persistence: Arc<AgentPersistence>,
+    ) -> Self {
         Self {
             database,
+            cache,
             agent_persistence,
         }
     }
@@ -71,6 +99,8 @@ impl ReactOrchestrator {
         H: crate::agent::core::executor::ReactHandler,
     {
         let mut iteration_snapshots: Vec<IterationSnapshot> = Vec::new();
+        // 模型给出最终回复（或空回复）而退出时为 true，区别于被阈值截停
+        let mut ended_by_model = false;
 
         while !context.should_stop().await {
             context.check_aborted_async(false).await?;
@@ -99,6 +129,7 @@ impl ReactOrchestrator {
                 context.session_id,
                 Arc::clone(&self.agent_persistence),
                 Arc::clone(&self.database),
+                Arc::clone(&self.cache),
             );
             if let Ok(Some(summary)) = summarizer
                 .summarize_if_needed(&model_id, &working_messages, &system_prompt)
@@ -146,24 +177,52 @@ impl ReactOrchestrator {
                 working_messages.push(file_msg);
             }
 
-            // 消息压缩（超过上下文窗口时）
-            let context_window = self
-                .get_model_context_window(&model_id)
+            // 消息压缩：超过上下文窗口时始终压缩；proactive 策略下再按轮数把较早的轮次压缩为摘要
+            let compaction = context.config().compaction;
+            let context_window = model_context_window(&self.database, &self.cache, model_id)
                 .await
-                .unwrap_or(128_000);
-            let compaction_result = MessageCompactor::new()
-                .with_config(CompactionConfig::default())
+                .unwrap_or(DEFAULT_CONTEXT_WINDOW);
+            let compactor = MessageCompactor::new().with_config(compaction);
+            let result = compactor
                 .compact_if_needed(
                     working_messages,
                     system_prompt.clone(),
-                    &model_id,
+                    model_id,
                     context_window,
                 )
                 .await
                 .map_err(|e| {
                     TaskExecutorError::InternalError(format!("Compaction failed: {}", e))
                 })?;
-            if let CompactionResult::Compacted { .. } = &compaction_result {}
+            let (compaction_result, trigger) =
+                if compaction.policy == CompactionPolicy::Proactive && !result.was_compacted() {
+                    let compacted = compactor.compact_by_turns(result.messages());
+                    (
+                        self.summarize_compacted_turns(context, model_id, compacted)
+                            .await,
+                        CompactionTrigger::Proactive,
+                    )
+                } else {
+                    (result, CompactionTrigger::Overflow)
+                };
+            if let CompactionResult::Compacted {
+                tokens_saved,
+                messages_summarized,
+                ..
+            } = &compaction_result
+            {
+                context
+                    .states
+                    .react_runtime
+                    .write()
+                    .await
+                    .record_compaction(
+                        react_iteration_index,
+                        trigger,
+                        *messages_summarized,
+                        *tokens_saved,
+                    );
+            }
             let final_messages = compaction_result.messages();
 
             let llm_request = handler
@@ -176,6 +235,18 @@ impl ReactOrchestrator {
                 )
                 .await?;
 
+            let request_timeout =
+                crate::ai::model_metadata::model_metadata(&self.database, &self.cache, model_id)
+                    .await
+                    .unwrap_or_default()
+                    .stream_request_timeout();
+            context
+                .states
+                .react_runtime
+                .write()
+                .await
+                .record_request_timeout(react_iteration_index, request_timeout.as_millis() as u64);
+
             let llm_service = crate::llm::service::LLMService::new(Arc::clone(&self.database));
             let cancel_token = context.create_stream_cancel_token();
             let mut stream = llm_service
@@ -189,12 +260,17 @@ impl ReactOrchestrator {
             let mut current_blocks: HashMap<usize, BlockAccumulator> = HashMap::new();
             let mut text_content: Vec<String> = Vec::new();
             let mut tool_use_blocks: Vec<ContentBlock> = Vec::new();
+            // 带签名的 thinking 块需原样回传，且位于 tool_use 之前
+            let mut thinking_blocks: Vec<ContentBlock> = Vec::new();
+            let mut thinking_tokens: i64 = 0;
+            let mut iteration_usage = TokenUsage::default();
             let mut pending_tool_calls: Vec<(String, String, Value)> = Vec::new();
 
             let mut thinking_stream_id: Option<String> = None;
             let mut text_stream_id: Option<String> = None;
             let mut thinking_created = false;
             let mut text_created = false;
+            let mut finish_reason: Option<crate::agent::react::types::FinishReason> = None;
 
             // ===== Phase 3: 处理 Anthropic StreamEvent =====
             while let Some(item) = stream.next().await {
@@ -204,7 +280,14 @@ impl ReactOrchestrator {
                 context.check_aborted_async(true).await?;
 
                 match item {
-                    Ok(StreamEvent::MessageStart { .. }) => {}
+                    Ok(StreamEvent::MessageStart { message }) => {
+                        iteration_usage.input_tokens = message.usage.input_tokens as i64;
+                        iteration_usage.output_tokens = message.usage.output_tokens as i64;
+                        iteration_usage.cache_read_tokens =
+                            message.usage.cache_read_input_tokens.map(i64::from);
+                        iteration_usage.cache_write_tokens =
+                            message.usage.cache_creation_input_tokens.map(i64::from);
+                    }
                     Ok(StreamEvent::ContentBlockStart {
                         index,
                         content_block,
@@ -223,7 +306,13 @@ impl ReactOrchestrator {
                             );
                         }
                         ContentBlockStart::Thinking { thinking } => {
-                            current_blocks.insert(index, BlockAccumulator::Thinking(thinking));
+                            current_blocks.insert(
+                                index,
+                                BlockAccumulator::Thinking {
+                                    thinking,
+                                    signature: None,
+                                },
+                            );
                         }
                     },
                     Ok(StreamEvent::ContentBlockDelta { index, delta }) => {
@@ -256,7 +345,7 @@ impl ReactOrchestrator {
                                     }
                                 }
                                 ContentDelta::ThinkingDelta { thinking } => {
-                                    if let BlockAccumulator::Thinking(s) = block {
+                                    if let BlockAccumulator::Thinking { thinking: s, .. } = block {
                                         s.push_str(&thinking);
                                         if thinking_stream_id.is_none() {
                                             thinking_stream_id = Some(Uuid::new_v4().to_string());
@@ -276,6 +365,11 @@ impl ReactOrchestrator {
                                         iter_ctx.append_thinking(&thinking).await;
                                     }
                                 }
+                                ContentDelta::SignatureDelta { signature } => {
+                                    if let BlockAccumulator::Thinking { signature: s, .. } = block {
+                                        s.get_or_insert_with(String::new).push_str(&signature);
+                                    }
+                                }
                             }
                         }
                     }
@@ -321,23 +415,46 @@ impl ReactOrchestrator {
                                         .await;
                                     pending_tool_calls.push((id, name, input));
                                 }
-                                BlockAccumulator::Thinking(thinking) => {
+                                BlockAccumulator::Thinking {
+                                    thinking,
+                                    signature,
+                                } => {
+                                    thinking_tokens += count_text_tokens(&thinking) as i64;
                                     if thinking_created {
                                         if let Some(id) = &thinking_stream_id {
                                             let block = Block::Thinking(ThinkingBlock {
                                                 id: id.clone(),
-                                                content: thinking,
+                                                content: thinking.clone(),
                                                 is_streaming: false,
                                             });
                                             let _ = context.assistant_update_block(id, block).await;
                                         }
                                     }
+                                    if signature.is_some() {
+                                        thinking_blocks.push(ContentBlock::Thinking {
+                                            thinking,
+                                            signature,
+                                        });
+                                    }
                                 }
                             }
                         }
                     }
                     Ok(StreamEvent::MessageDelta { delta, usage }) => {
-                        let _ = (delta, usage);
+                        if let Some(reason) = delta.stop_reason {
+                            finish_reason = Some(reason.into());
+                        }
+                        // message_delta 中的用量为累计值
+                        iteration_usage.output_tokens = usage.output_tokens as i64;
+                        if usage.input_tokens > 0 {
+                            iteration_usage.input_tokens = usage.input_tokens as i64;
+                        }
+                        if let Some(read) = usage.cache_read_input_tokens {
+                            iteration_usage.cache_read_tokens = Some(read as i64);
+                        }
+                        if let Some(write) = usage.cache_creation_input_tokens {
+                            iteration_usage.cache_write_tokens = Some(write as i64);
+                        }
                     }
                     Ok(StreamEvent::MessageStop) => {
                         break;
@@ -346,20 +463,42 @@ impl ReactOrchestrator {
                     Ok(StreamEvent::Error { error }) => {
                         return Err(TaskExecutorError::InternalError(error.message));
                     }
+                    // 请求超时等可恢复错误记入轨迹，便于事后分析
+                    Err(e) if e.is_recoverable() => {
+                        context
+                            .states
+                            .react_runtime
+                            .write()
+                            .await
+                            .fail_iteration(react_iteration_index, e.to_string());
+                        return Err(TaskExecutorError::LLMCallFailed(e.to_string()));
+                    }
                     Err(e) => {
                         return Err(TaskExecutorError::InternalError(e.to_string()));
                     }
                 }
             }
 
+            // 思考 token 计入 output_tokens，API 不单独返回，按思考文本估算
+            if thinking_tokens > 0 {
+                let estimated = if iteration_usage.output_tokens > 0 {
+                    thinking_tokens.min(iteration_usage.output_tokens)
+                } else {
+                    thinking_tokens
+                };
+                iteration_usage.thinking_tokens = Some(estimated);
+            }
+            context.record_token_usage(iteration_usage).await;
+
             // ===== Phase 4: 将累积内容写入上下文 =====
             let final_text = if !text_content.is_empty() {
                 Some(text_content.join("\n"))
             } else {
                 None
             };
+            thinking_blocks.extend(tool_use_blocks);
             context
-                .add_assistant_message(final_text.clone(), Some(tool_use_blocks))
+                .add_assistant_message(final_text.clone(), Some(thinking_blocks))
                 .await?;
 
             // ===== Phase 5: 分类迭代结果 =====
@@ -463,10 +602,15 @@ impl ReactOrchestrator {
                         .react_runtime
                         .write()
                         .await
-                        .complete_iteration(react_iteration_index, output.clone(), None);
+                        .complete_iteration(
+                            react_iteration_index,
+                            output.clone(),
+                            finish_reason.clone(),
+                        );
 
                     let snapshot = iter_ctx.finalize().await;
                     Self::finalize_iteration(context, snapshot, &mut iteration_snapshots).await?;
+                    ended_by_model = true;
                     break;
                 }
 
@@ -475,9 +619,18 @@ impl ReactOrchestrator {
                         "Iteration {}: empty response - terminating immediately",
                         iteration
                     );
+                    if let Some(reason) = finish_reason.clone() {
+                        context
+                            .states
+                            .react_runtime
+                            .write()
+                            .await
+                            .set_stop_reason(reason.into());
+                    }
 
                     let snapshot = iter_ctx.finalize().await;
                     Self::finalize_iteration(context, snapshot, &mut iteration_snapshots).await?;
+                    ended_by_model = true;
                     break;
                 }
             }
@@ -486,9 +639,197 @@ impl ReactOrchestrator {
         if !iteration_snapshots.is_empty() {
             Self::compress_iteration_batch(context, &iteration_snapshots).await?;
         }
+
+        if !ended_by_model && !context.is_aborted() && context.reached_max_iterations().await {
+            return self
+                .handle_iteration_limit(context, model_id, handler)
+                .await;
+        }
         Ok(())
     }
 
+    /// 把主动压缩覆盖的较早轮次替换为一条摘要消息；摘要失败或不能进一步缩小请求时
+    /// 保留只清理了工具结果的消息。压缩边界不变时复用上次的摘要
+    async fn summarize_compacted_turns(
+        &self,
+        context: &TaskContext,
+        model_id: &str,
+        result: CompactionResult,
+    ) -> CompactionResult {
+        let CompactionResult::Compacted {
+            messages,
+            tokens_saved,
+            messages_summarized,
+        } = result
+        else {
+            return result;
+        };
+
+        let cached = context
+            .states
+            .proactive_summary
+            .lock()
+            .clone()
+            .filter(|(covered, _)| *covered == messages_summarized)
+            .map(|(_, summary)| summary);
+        let summary = match cached {
+            Some(summary) => summary,
+            None => {
+                let summarizer = SessionSummarizer::new(
+                    context.session_id,
+                    Arc::clone(&self.agent_persistence),
+                    Arc::clone(&self.database),
+                    Arc::clone(&self.cache),
+                );
+                match summarizer
+                    .summarize_messages(model_id, &messages[..messages_summarized])
+                    .await
+                {
+                    Ok(summary) => {
+                        *context.states.proactive_summary.lock() =
+                            Some((messages_summarized, summary.clone()));
+                        summary
+                    }
+                    Err(e) => {
+                        warn!("Proactive compaction summary failed: {}", e);
+                        return CompactionResult::Compacted {
+                            messages,
+                            tokens_saved,
+                            messages_summarized,
+                        };
+                    }
+                }
+            }
+        };
+
+        let summary_message = MessageParam {
+            role: MessageRole::User,
+            content: MessageContent::Text(format!("[Summary of earlier turns]\n{}", summary)),
+        };
+        let prefix_tokens = messages[..messages_summarized]
+            .iter()
+            .map(|msg| count_message_param_tokens(msg) as u32)
+            .fold(0u32, |acc, n| acc.saturating_add(n));
+        let summary_tokens = count_message_param_tokens(&summary_message) as u32;
+        if summary_tokens >= prefix_tokens {
+            return CompactionResult::Compacted {
+                messages,
+                tokens_saved,
+                messages_summarized,
+            };
+        }
+
+        let mut compacted = Vec::with_capacity(messages.len() - messages_summarized + 1);
+        compacted.push(summary_message);
+        compacted.extend(messages.into_iter().skip(messages_summarized));
+        CompactionResult::Compacted {
+            messages: compacted,
+            tokens_saved: tokens_saved.saturating_add(prefix_tokens - summary_tokens),
+            messages_summarized,
+        }
+    }
+
+    /// 达到最大迭代次数：通知前端，并按配置决定直接报错还是先总结进展
+    async fn handle_iteration_limit<H>(
+        &self,
+        context: &TaskContext,
+        model_id: &str,
+        handler: &H,
+    ) -> TaskExecutorResult<()>
+    where
+        H: crate::agent::core::executor::ReactHandler,
+    {
+        let max_iterations = context.config().max_iterations;
+        warn!(
+            "Task {} reached max iterations ({})",
+            context.task_id, max_iterations
+        );
+
+        let summarized = match context.config().on_max_iterations {
+            MaxIterationsBehavior::HardStop => false,
+            MaxIterationsBehavior::SummarizeThenStop => {
+                match self.summarize_progress(context, model_id, handler).await {
+                    Ok(summarized) => summarized,
+                    Err(e) => {
+                        warn!("Failed to summarize progress at iteration limit: {}", e);
+                        false
+                    }
+                }
+            }
+        };
+
+        context
+            .emit_event(TaskEvent::IterationLimitReached {
+                task_id: context.task_id.to_string(),
+                max_iterations,
+                summarized,
+            })
+            .await?;
+
+        if summarized {
+            Ok(())
+        } else {
+            Err(TaskExecutorError::MaxIterationsReached {
+                current: max_iterations,
+                max: max_iterations,
+            })
+        }
+    }
+
+    /// 请求模型对目前进展做一次总结，并作为文本块追加到助手消息
+    async fn summarize_progress<H>(
+        &self,
+        context: &TaskContext,
+        model_id: &str,
+        handler: &H,
+    ) -> TaskExecutorResult<bool>
+    where
+        H: crate::agent::core::executor::ReactHandler,
+    {
+        let mut messages = context.batch_read_state(|exec| exec.messages.clone()).await;
+        messages.push(MessageParam::user(ITERATION_LIMIT_SUMMARY_PROMPT));
+
+        let mut request = handler
+            .build_llm_request(
+                context,
+                model_id,
+                &context.tool_registry(),
+                &context.cwd,
+                Some(messages),
+            )
+            .await?;
+        request.stream = false;
+
+        let llm_service = crate::llm::service::LLMService::new(Arc::clone(&self.database));
+        let response = llm_service.call(request).await.map_err(|e| {
+            TaskExecutorError::InternalError(format!("LLM summary call failed: {}", e))
+        })?;
+
+        // 历史中含 tool_use 时请求必须带上工具定义，这里只取文本部分
+        let summary = response
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let summary = summary.trim();
+        if summary.is_empty() {
+            return Ok(false);
+        }
+
+        context
+            .assistant_append_block(Block::Text(TextBlock {
+                id: Uuid::new_v4().to_string(),
+                content: summary.to_string(),
+                is_streaming: false,
+            }))
+            .await?;
+        Ok(true)
+    }
+
     async fn finalize_iteration(
         context: &TaskContext,
         snapshot: IterationSnapshot,
@@ -505,7 +846,7 @@ impl ReactOrchestrator {
             })
             .await;
         snapshots.push(snapshot);
-        if snapshots.len() >= 5 {
+        if snapshots.len() >= MEMORY_COMPRESSION_THRESHOLD {
             Self::compress_iteration_batch(context, snapshots).await?;
             snapshots.clear();
         }
@@ -551,21 +892,4 @@ impl ReactOrchestrator {
 
         Ok(())
     }
-
-    async fn get_model_context_window(&self, model_id: &str) -> Option<u32> {
-        let model = crate::storage::repositories::AIModels::new(&self.database)
-            .find_by_id(model_id)
-            .await
-            .ok()??;
-
-        if let Some(options) = model.options {
-            if let Some(max_tokens) = options.get("maxContextTokens") {
-                if let Some(value) = max_tokens.as_u64() {
-                    return Some(value as u32);
-                }
-            }
-        }
-
-        None
-    }
 }
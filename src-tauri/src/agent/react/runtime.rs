@@ -4,8 +4,9 @@ use uuid::Uuid;
 use crate::agent::tools::ToolResult;
 
 use super::types::{
-    FinishReason, FinishReasonOrTerminal, ReactAction, ReactIteration, ReactObservation,
-    ReactPhase, ReactRuntimeConfig, ReactRuntimeSnapshot, ReactThought,
+    CompactionTrigger, FinishReason, FinishReasonOrTerminal, ReactAction, ReactCompaction,
+    ReactIteration, ReactObservation, ReactPhase, ReactRuntimeConfig, ReactRuntimeSnapshot,
+    ReactThought,
 };
 
 #[derive(Debug, Clone)]
@@ -16,6 +17,7 @@ pub struct ReactRuntime {
     final_response: Option<String>,
     stop_reason: Option<FinishReasonOrTerminal>,
     aborted: bool,
+    compactions: Vec<ReactCompaction>,
 }
 
 impl ReactRuntime {
@@ -27,6 +29,7 @@ impl ReactRuntime {
             final_response: None,
             stop_reason: None,
             aborted: false,
+            compactions: Vec::new(),
         }
     }
 
@@ -43,6 +46,7 @@ impl ReactRuntime {
             response: None,
             finish_reason: None,
             error_message: None,
+            request_timeout_ms: None,
         };
         self.iterations.push(iteration);
         index
@@ -128,6 +132,12 @@ impl ReactRuntime {
         self.consecutive_errors = 0;
     }
 
+    pub fn record_request_timeout(&mut self, iteration_index: usize, timeout_ms: u64) {
+        if let Some(iteration) = self.iterations.get_mut(iteration_index) {
+            iteration.request_timeout_ms = Some(timeout_ms);
+        }
+    }
+
     pub fn fail_iteration(&mut self, iteration_index: usize, error_message: String) {
         if let Some(iteration) = self.iterations.get_mut(iteration_index) {
             iteration.error_message = Some(error_message);
@@ -140,6 +150,33 @@ impl ReactRuntime {
         self.consecutive_errors = 0;
     }
 
+    /// 记录一次消息压缩；消息历史每轮重新构建，与上一条记录相同的压缩不重复记录
+    pub fn record_compaction(
+        &mut self,
+        iteration_index: usize,
+        trigger: CompactionTrigger,
+        messages_compacted: usize,
+        tokens_saved: u32,
+    ) {
+        let repeated = self.compactions.last().is_some_and(|last| {
+            last.trigger == trigger && last.messages_compacted == messages_compacted
+        });
+        if repeated {
+            return;
+        }
+        self.compactions.push(ReactCompaction {
+            iteration: iteration_index,
+            trigger,
+            messages_compacted,
+            tokens_saved,
+            compacted_at: Utc::now().timestamp_millis(),
+        });
+    }
+
+    pub fn compactions(&self) -> &[ReactCompaction] {
+        &self.compactions
+    }
+
     pub fn set_stop_reason(&mut self, reason: FinishReasonOrTerminal) {
         self.stop_reason = Some(reason);
     }
@@ -172,6 +209,10 @@ impl ReactRuntime {
         false
     }
 
+    pub fn consecutive_errors(&self) -> u32 {
+        self.consecutive_errors
+    }
+
     pub fn config(&self) -> &ReactRuntimeConfig {
         &self.config
     }
@@ -0,0 +1,228 @@
+//! ReAct 运行时轨迹（调试用）
+//!
+//! 从 `ReactRuntime` 快照整理出每轮迭代的推理、工具调用、观察结果与结束方式，
+//! 用于排查 agent 为何循环或出错。与 UI 展示的步骤（UiStep）相互独立。
+
+use serde::Serialize;
+
+use super::runtime::ReactRuntime;
+use super::types::{
+    FinishReason, FinishReasonOrTerminal, ReactCompaction, ReactIteration, ReactPhase,
+};
+use crate::agent::tools::{ToolResultContent, ToolResultStatus};
+
+/// 单次最多返回的迭代数
+pub const MAX_TRACE_ITERATIONS: usize = 50;
+/// 推理、回复、观察等文本字段的最大字符数
+const MAX_TEXT_CHARS: usize = 2000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactTraceAction {
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+    pub issued_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactTraceObservation {
+    pub tool_name: String,
+    pub status: ToolResultStatus,
+    pub output: String,
+    pub execution_time_ms: Option<u64>,
+    pub observed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactTraceIteration {
+    pub index: usize,
+    pub started_at: i64,
+    /// 迭代停留的阶段：completion / failed 为已结束，其余表示仍在进行或被中断
+    pub outcome: ReactPhase,
+    pub thought: Option<String>,
+    pub action: Option<ReactTraceAction>,
+    pub observation: Option<ReactTraceObservation>,
+    pub response: Option<String>,
+    pub finish_reason: Option<FinishReason>,
+    pub error_message: Option<String>,
+    /// LLM 流式请求的整体超时，便于判断失败是否由超时造成
+    pub request_timeout_ms: Option<u64>,
+    /// 迭代失败，或工具返回了错误
+    pub has_error: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactTrace {
+    pub task_id: String,
+    pub running: bool,
+    pub total_iterations: usize,
+    /// 因数量限制省略的较早迭代数
+    pub omitted_iterations: usize,
+    pub consecutive_errors: u32,
+    pub stop_reason: Option<FinishReasonOrTerminal>,
+    pub aborted: bool,
+    pub iterations: Vec<ReactTraceIteration>,
+    /// 本任务发生的消息压缩
+    pub compactions: Vec<ReactCompaction>,
+}
+
+impl ReactTrace {
+    /// last 为只返回最近的迭代数，默认且最多为 MAX_TRACE_ITERATIONS
+    pub fn from_runtime(
+        task_id: &str,
+        runtime: &ReactRuntime,
+        running: bool,
+        last: Option<usize>,
+    ) -> Self {
+        let snapshot = runtime.get_snapshot();
+        let total = snapshot.iterations.len();
+        let keep = last
+            .unwrap_or(MAX_TRACE_ITERATIONS)
+            .clamp(1, MAX_TRACE_ITERATIONS)
+            .min(total);
+        let iterations = snapshot.iterations[total - keep..]
+            .iter()
+            .map(trace_iteration)
+            .collect();
+
+        Self {
+            task_id: task_id.to_string(),
+            running,
+            total_iterations: total,
+            omitted_iterations: total - keep,
+            consecutive_errors: runtime.consecutive_errors(),
+            stop_reason: snapshot.stop_reason,
+            aborted: snapshot.aborted,
+            iterations,
+            compactions: runtime.compactions().to_vec(),
+        }
+    }
+
+    /// 在已有轨迹上只保留最近 last 轮
+    pub fn keep_last(mut self, last: Option<usize>) -> Self {
+        let Some(last) = last else {
+            return self;
+        };
+        let drop = self.iterations.len().saturating_sub(last.max(1));
+        self.iterations.drain(..drop);
+        self.omitted_iterations += drop;
+        self
+    }
+}
+
+fn trace_iteration(iteration: &ReactIteration) -> ReactTraceIteration {
+    let observation = iteration.observation.as_ref().map(|obs| {
+        let output = obs
+            .outcome
+            .content
+            .iter()
+            .map(|c| match c {
+                ToolResultContent::Success(text) | ToolResultContent::Error(text) => text.as_str(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        ReactTraceObservation {
+            tool_name: obs.tool_name.clone(),
+            status: obs.outcome.status,
+            output: truncate(&output),
+            execution_time_ms: obs.outcome.execution_time_ms,
+            observed_at: obs.observed_at,
+        }
+    });
+    let has_error = iteration.status == ReactPhase::Failed
+        || iteration.error_message.is_some()
+        || observation
+            .as_ref()
+            .is_some_and(|obs| obs.status == ToolResultStatus::Error);
+
+    ReactTraceIteration {
+        index: iteration.index,
+        started_at: iteration.started_at,
+        outcome: iteration.status,
+        thought: iteration.thought.as_ref().map(|t| truncate(&t.normalized)),
+        action: iteration.action.as_ref().map(|a| ReactTraceAction {
+            tool_name: a.tool_name.clone(),
+            arguments: a.arguments.clone(),
+            issued_at: a.issued_at,
+        }),
+        observation,
+        response: iteration.response.as_deref().map(truncate),
+        finish_reason: iteration.finish_reason.clone(),
+        error_message: iteration.error_message.clone(),
+        request_timeout_ms: iteration.request_timeout_ms,
+        has_error,
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((idx, _)) => format!("{}…", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::react::types::ReactRuntimeConfig;
+    use crate::agent::tools::ToolResult;
+
+    #[test]
+    fn keeps_last_iterations_and_flags_tool_errors() {
+        let mut runtime = ReactRuntime::new(ReactRuntimeConfig {
+            max_iterations: 10,
+            max_consecutive_errors: 3,
+        });
+        for i in 0..3 {
+            let idx = runtime.start_iteration();
+            runtime.record_action(idx, "read_file".into(), serde_json::json!({ "path": i }));
+            let (status, content) = if i == 1 {
+                (
+                    ToolResultStatus::Error,
+                    ToolResultContent::Error("missing".into()),
+                )
+            } else {
+                (
+                    ToolResultStatus::Success,
+                    ToolResultContent::Success("ok".into()),
+                )
+            };
+            runtime.record_observation(
+                idx,
+                "read_file".into(),
+                ToolResult {
+                    content: vec![content],
+                    status,
+                    cancel_reason: None,
+                    execution_time_ms: Some(3),
+                    ext_info: None,
+                },
+            );
+        }
+        runtime.record_request_timeout(2, 600_000);
+        runtime.fail_iteration(2, "model error".into());
+
+        let trace = ReactTrace::from_runtime("task", &runtime, true, Some(2));
+        assert_eq!(trace.total_iterations, 3);
+        assert_eq!(trace.omitted_iterations, 1);
+        assert_eq!(trace.consecutive_errors, 1);
+        let indexes: Vec<usize> = trace.iterations.iter().map(|i| i.index).collect();
+        assert_eq!(indexes, vec![1, 2]);
+        assert!(trace.iterations[0].has_error);
+        assert_eq!(
+            trace.iterations[0].observation.as_ref().unwrap().output,
+            "missing"
+        );
+        assert_eq!(trace.iterations[1].outcome, ReactPhase::Failed);
+        assert_eq!(trace.iterations[1].request_timeout_ms, Some(600_000));
+
+        let all = ReactTrace::from_runtime("task", &runtime, false, Some(500));
+        assert_eq!(all.iterations.len(), 3);
+        let last = all.keep_last(Some(1));
+        assert_eq!(last.omitted_iterations, 2);
+        assert_eq!(last.iterations[0].index, 2);
+    }
+}
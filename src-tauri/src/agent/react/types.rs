@@ -2,14 +2,30 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::agent::tools::ToolResult;
+use crate::llm::anthropic_types::StopReason;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum FinishReason {
     Stop,
+    /// 达到 max_tokens，回复被截断
     Length,
     ToolCalls,
     ContentFilter,
+    /// 命中配置的 stop sequence
+    StopSequence,
+}
+
+/// 各 provider 的停止原因统一归一到 FinishReason
+impl From<StopReason> for FinishReason {
+    fn from(value: StopReason) -> Self {
+        match value {
+            StopReason::EndTurn => FinishReason::Stop,
+            StopReason::MaxTokens => FinishReason::Length,
+            StopReason::ToolUse => FinishReason::ToolCalls,
+            StopReason::StopSequence => FinishReason::StopSequence,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -61,6 +77,30 @@ pub struct ReactIteration {
     pub response: Option<String>,
     pub finish_reason: Option<FinishReason>,
     pub error_message: Option<String>,
+    /// 本轮 LLM 流式请求的整体超时
+    #[serde(default)]
+    pub request_timeout_ms: Option<u64>,
+}
+
+/// 触发消息压缩的原因
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionTrigger {
+    /// 接近上下文窗口
+    Overflow,
+    /// 按轮数主动压缩
+    Proactive,
+}
+
+/// 一次消息压缩的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactCompaction {
+    pub iteration: usize,
+    pub trigger: CompactionTrigger,
+    pub messages_compacted: usize,
+    pub tokens_saved: u32,
+    pub compacted_at: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -78,6 +118,7 @@ pub enum FinishReasonOrTerminal {
     Length,
     ToolCalls,
     ContentFilter,
+    StopSequence,
     Abort,
     Error,
 }
@@ -89,6 +130,7 @@ impl From<FinishReason> for FinishReasonOrTerminal {
             FinishReason::Length => FinishReasonOrTerminal::Length,
             FinishReason::ToolCalls => FinishReasonOrTerminal::ToolCalls,
             FinishReason::ContentFilter => FinishReasonOrTerminal::ContentFilter,
+            FinishReason::StopSequence => FinishReasonOrTerminal::StopSequence,
         }
     }
 }
@@ -125,7 +125,10 @@ impl AgentShellExecutor {
             cmd.current_dir(cwd);
         }
 
-        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        // 超时或调用方被取消（future 被丢弃）时终止子进程
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
 
         // 执行命令
         let result = timeout(timeout_duration, async {
@@ -2,6 +2,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 use tokio::sync::RwLock;
 
 use crate::agent::config::TaskExecutionConfig;
@@ -9,7 +10,8 @@ use crate::agent::context::FileContextTracker;
 use crate::agent::persistence::AgentPersistence;
 use crate::storage::DatabaseManager;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CompressedMemory {
     pub created_at: DateTime<Utc>,
     pub iteration_range: (u32, u32),
@@ -32,6 +34,24 @@ pub struct SessionStats {
 /// 最大压缩历史记录数
 const MAX_COMPRESSED_HISTORY: usize = 32;
 
+/// 累计多少轮迭代后压缩为一条记忆
+pub const MEMORY_COMPRESSION_THRESHOLD: usize = 5;
+
+/// 压缩记忆的只读快照
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemorySnapshot {
+    pub task_id: String,
+    pub memories: Vec<CompressedMemory>,
+    /// 最近一次压缩之后尚未压缩的迭代数
+    pub uncompressed_iterations: u32,
+    /// 当前上下文中的消息数
+    pub message_count: usize,
+    pub compression_threshold: usize,
+    /// 超出后最早的记忆会被丢弃
+    pub max_memories: usize,
+}
+
 pub struct SessionContext {
     pub task_id: String,
     pub session_id: i64,
@@ -111,6 +131,24 @@ impl SessionContext {
         self.compressed_history.read().await.clone()
     }
 
+    /// 构造记忆快照；current_iteration 为任务当前迭代号
+    pub async fn memory_snapshot(
+        &self,
+        current_iteration: u32,
+        message_count: usize,
+    ) -> MemorySnapshot {
+        let memories = self.compressed_history().await;
+        let compressed_until = memories.last().map_or(0, |m| m.iteration_range.1);
+        MemorySnapshot {
+            task_id: self.task_id.clone(),
+            uncompressed_iterations: current_iteration.saturating_sub(compressed_until),
+            memories,
+            message_count,
+            compression_threshold: MEMORY_COMPRESSION_THRESHOLD,
+            max_memories: MAX_COMPRESSED_HISTORY,
+        }
+    }
+
     pub async fn get_compressed_history_text(&self) -> String {
         let history = self.compressed_history.read().await;
         if history.is_empty() {
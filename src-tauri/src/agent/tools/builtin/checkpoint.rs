@@ -0,0 +1,371 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::agent::core::context::{ActiveCheckpoint, TaskContext};
+use crate::agent::error::ToolExecutorResult;
+use crate::agent::tools::{
+    RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority, ToolResult,
+    ToolResultContent, ToolResultStatus,
+};
+use crate::checkpoint::CheckpointService;
+
+/// 当前任务可用的 checkpoint 服务与起始 checkpoint
+async fn task_checkpoints(
+    context: &TaskContext,
+) -> Result<(Arc<CheckpointService>, ActiveCheckpoint), ToolResult> {
+    let Some(service) = context.checkpoint_service() else {
+        return Err(error_result("Checkpoints are disabled for this task"));
+    };
+    let Some(active) = context.active_checkpoint().await else {
+        return Err(error_result("This task has no checkpoint yet"));
+    };
+    Ok((service, active))
+}
+
+pub struct ListCheckpointsTool;
+
+impl ListCheckpointsTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl RunnableTool for ListCheckpointsTool {
+    fn name(&self) -> &str {
+        "list_checkpoints"
+    }
+
+    fn description(&self) -> &str {
+        "Lists the checkpoints (restore points) available to the current task.
+
+Usage:
+- A checkpoint records the original content of every file changed after it was taken
+- The first entry is the task's starting checkpoint; rolling back to it undoes every file change made by this task
+- Use rollback_checkpoint with one of the listed ids to undo changes"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::new(ToolCategory::FileRead, ToolPriority::Standard)
+            .with_tags(vec!["checkpoint".into(), "undo".into()])
+    }
+
+    fn required_permissions(&self) -> Vec<ToolPermission> {
+        vec![ToolPermission::ReadOnly]
+    }
+
+    async fn run(
+        &self,
+        context: &TaskContext,
+        _args: serde_json::Value,
+    ) -> ToolExecutorResult<ToolResult> {
+        let (service, active) = match task_checkpoints(context).await {
+            Ok(found) => found,
+            Err(result) => return Ok(result),
+        };
+
+        let workspace = active.workspace_root.to_string_lossy();
+        let summaries = match service
+            .list_by_session(context.session_id, &workspace)
+            .await
+        {
+            Ok(list) => list,
+            Err(e) => return Ok(error_result(format!("Failed to list checkpoints: {}", e))),
+        };
+        let mut available: Vec<_> = summaries
+            .into_iter()
+            .filter(|cp| cp.id >= active.id)
+            .collect();
+        available.sort_by_key(|cp| cp.id);
+
+        let mut text = format!("{} checkpoint(s) available", available.len());
+        for cp in &available {
+            text.push_str(&format!(
+                "\n- id={} created={} files_changed={}{}",
+                cp.id,
+                cp.created_at.to_rfc3339(),
+                cp.file_count,
+                if cp.id == active.id {
+                    " (task start)"
+                } else {
+                    ""
+                }
+            ));
+        }
+
+        Ok(ToolResult {
+            content: vec![ToolResultContent::Success(text)],
+            status: ToolResultStatus::Success,
+            cancel_reason: None,
+            execution_time_ms: None,
+            ext_info: Some(json!({
+                "taskCheckpointId": active.id,
+                "checkpoints": available,
+            })),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RollbackCheckpointArgs {
+    checkpoint_id: i64,
+}
+
+pub struct RollbackCheckpointTool;
+
+impl RollbackCheckpointTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl RunnableTool for RollbackCheckpointTool {
+    fn name(&self) -> &str {
+        "rollback_checkpoint"
+    }
+
+    fn description(&self) -> &str {
+        "Reverts workspace files to the state recorded by a checkpoint, undoing later file changes.
+
+Usage:
+- Use this when you realize your changes went down a wrong path and you want to start over from a clean state
+- Get checkpoint ids from list_checkpoints; you cannot roll back past the task's starting checkpoint
+- Files created after the checkpoint are deleted, modified files get their original content back
+- Only changes made through the file tools are tracked; effects of shell commands are not reverted
+- The result lists every restored file. Re-read them before editing again"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "checkpointId": {
+                    "type": "integer",
+                    "description": "The checkpoint to restore, as returned by list_checkpoints."
+                }
+            },
+            "required": ["checkpointId"]
+        })
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::new(ToolCategory::FileWrite, ToolPriority::Standard)
+            .with_confirmation()
+            .with_tags(vec!["checkpoint".into(), "undo".into(), "write".into()])
+            .with_summary_key_arg("checkpointId")
+    }
+
+    fn required_permissions(&self) -> Vec<ToolPermission> {
+        vec![ToolPermission::FileSystem]
+    }
+
+    async fn run(
+        &self,
+        context: &TaskContext,
+        args: serde_json::Value,
+    ) -> ToolExecutorResult<ToolResult> {
+        let args: RollbackCheckpointArgs = serde_json::from_value(args)?;
+        let (service, active) = match task_checkpoints(context).await {
+            Ok(found) => found,
+            Err(result) => return Ok(result),
+        };
+
+        let checkpoint = match service.get(args.checkpoint_id).await {
+            Ok(Some(cp)) => cp,
+            Ok(None) => {
+                return Ok(error_result(format!(
+                    "Checkpoint {} not found",
+                    args.checkpoint_id
+                )))
+            }
+            Err(e) => return Ok(error_result(format!("Failed to load checkpoint: {}", e))),
+        };
+        if checkpoint.session_id != context.session_id
+            || checkpoint.workspace_path != active.workspace_root.to_string_lossy()
+        {
+            return Ok(error_result(format!(
+                "Checkpoint {} does not belong to this task",
+                checkpoint.id
+            )));
+        }
+        if checkpoint.id < active.id {
+            return Ok(error_result(format!(
+                "Refusing to roll back past this task's starting checkpoint {}",
+                active.id
+            )));
+        }
+
+        let result = match service.rollback(checkpoint.id).await {
+            Ok(result) => result,
+            Err(e) => return Ok(error_result(format!("Rollback failed: {}", e))),
+        };
+
+        // 文件内容已变，标记为过期，后续迭代会提示模型重新读取
+        let tracker = context.file_tracker();
+        for relative in &result.restored_files {
+            let path = active.workspace_root.join(relative);
+            if let Err(e) = tracker.mark_file_as_stale(&path).await {
+                tracing::warn!("Failed to mark {} as stale: {}", path.display(), e);
+            }
+        }
+
+        let mut text = format!(
+            "rollback_checkpoint applied\ncheckpoint={}\nrestored_files={}",
+            checkpoint.id,
+            result.restored_files.len()
+        );
+        for relative in &result.restored_files {
+            text.push_str(&format!("\n- {}", relative));
+        }
+        if !result.failed_files.is_empty() {
+            text.push_str("\nfailed_files:");
+            for (relative, error) in &result.failed_files {
+                text.push_str(&format!("\n- {}: {}", relative, error));
+            }
+        }
+        if !result.restored_files.is_empty() {
+            text.push_str("\nThese files changed on disk; re-read them before editing.");
+        }
+
+        let status = if result.restored_files.is_empty() && !result.failed_files.is_empty() {
+            ToolResultStatus::Error
+        } else {
+            ToolResultStatus::Success
+        };
+        Ok(ToolResult {
+            content: vec![match status {
+                ToolResultStatus::Success => ToolResultContent::Success(text),
+                _ => ToolResultContent::Error(text),
+            }],
+            status,
+            cancel_reason: None,
+            execution_time_ms: None,
+            ext_info: serde_json::to_value(&result).ok(),
+        })
+    }
+}
+
+fn error_result(message: impl Into<String>) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Error(message.into())],
+        status: ToolResultStatus::Error,
+        cancel_reason: None,
+        execution_time_ms: None,
+        ext_info: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::core::context::test_task_context_with_checkpoints;
+    use crate::agent::types::{MessageRole, MessageStatus};
+    use std::path::{Path, PathBuf};
+
+    async fn setup(dir: &Path) -> (TaskContext, PathBuf) {
+        let workspace = dir.join("ws");
+        std::fs::create_dir_all(&workspace).unwrap();
+        let workspace = workspace.canonicalize().unwrap();
+        let ctx = test_task_context_with_checkpoints(&dir.join("app"), &workspace).await;
+        (ctx, workspace)
+    }
+
+    async fn new_message_id(ctx: &TaskContext) -> i64 {
+        ctx.agent_persistence()
+            .messages()
+            .create(
+                ctx.session_id,
+                MessageRole::User,
+                MessageStatus::Completed,
+                Vec::new(),
+            )
+            .await
+            .unwrap()
+            .id
+    }
+
+    fn text(result: &ToolResult) -> &str {
+        match &result.content[0] {
+            ToolResultContent::Success(text) | ToolResultContent::Error(text) => text,
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_to_roll_back_past_the_task_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let (ctx, workspace) = setup(dir.path()).await;
+
+        let earlier_message = new_message_id(&ctx).await;
+        let earlier = ctx
+            .checkpoint_service()
+            .unwrap()
+            .create_empty(ctx.session_id, earlier_message, &workspace)
+            .await
+            .unwrap();
+        let task_message = new_message_id(&ctx).await;
+        ctx.init_checkpoint(task_message).await.unwrap();
+
+        let result = RollbackCheckpointTool::new()
+            .run(&ctx, json!({ "checkpointId": earlier.id }))
+            .await
+            .unwrap();
+
+        assert_eq!(result.status, ToolResultStatus::Error);
+        assert!(text(&result).contains("Refusing to roll back past"));
+    }
+
+    #[tokio::test]
+    async fn rollback_restores_files_and_marks_them_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let (ctx, workspace) = setup(dir.path()).await;
+        let modified = workspace.join("a.txt");
+        let added = workspace.join("b.txt");
+        std::fs::write(&modified, "original").unwrap();
+
+        let message = new_message_id(&ctx).await;
+        ctx.init_checkpoint(message).await.unwrap();
+        let start = ctx.active_checkpoint().await.unwrap();
+
+        ctx.snapshot_file_before_edit(&modified).await.unwrap();
+        std::fs::write(&modified, "changed").unwrap();
+        ctx.snapshot_file_before_edit(&added).await.unwrap();
+        std::fs::write(&added, "new").unwrap();
+
+        let result = RollbackCheckpointTool::new()
+            .run(&ctx, json!({ "checkpointId": start.id }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.status,
+            ToolResultStatus::Success,
+            "{}",
+            text(&result)
+        );
+        assert_eq!(std::fs::read_to_string(&modified).unwrap(), "original");
+        assert!(!added.exists());
+
+        let mut stale: Vec<String> = ctx
+            .file_tracker()
+            .get_stale_files()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|record| record.relative_path)
+            .collect();
+        stale.sort();
+        assert_eq!(stale, vec!["a.txt", "b.txt"]);
+    }
+}
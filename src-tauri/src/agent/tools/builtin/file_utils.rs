@@ -1,5 +1,6 @@
 use std::path::{Component, Path, PathBuf};
 
+use crate::agent::core::context::TaskContext;
 use crate::agent::error::ToolExecutorError;
 
 /// List of extensions treated as binary to mirror front-end safeguards.
@@ -124,3 +125,130 @@ pub fn ensure_absolute(path: &str, cwd: &str) -> Result<PathBuf, ToolExecutorErr
 
     Ok(normalize_path(&cwd_path.join(candidate)))
 }
+
+/// Canonicalize the deepest existing ancestor so symlinks are resolved even for paths
+/// that do not exist yet (e.g. a file about to be written).
+fn resolve_existing_prefix(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return rest
+                .iter()
+                .rev()
+                .fold(canonical, |acc: PathBuf, name| acc.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// Whether two paths name the same location once `..`, trailing separators and symlinks
+/// are resolved. Case is ignored on macOS and Windows, whose default filesystems are
+/// case-insensitive.
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    let key = |path: &Path| {
+        let resolved = resolve_existing_prefix(&normalize_path(path));
+        if cfg!(any(target_os = "macos", windows)) {
+            PathBuf::from(resolved.to_string_lossy().to_lowercase())
+        } else {
+            resolved
+        }
+    };
+    key(a) == key(b)
+}
+
+/// Reject paths that escape `root` once `..` and symlinks are resolved.
+/// Returns the resolved path, so a symlink swapped in later cannot redirect the access.
+pub fn confine_to_root(path: &Path, root: &Path) -> Result<PathBuf, ToolExecutorError> {
+    let outside = || ToolExecutorError::InvalidArguments {
+        tool_name: "file_utils".to_string(),
+        error: format!(
+            "Path {} is outside allowed root {}",
+            path.display(),
+            root.display()
+        ),
+    };
+
+    let canonical_root = root.canonicalize().map_err(|_| outside())?;
+    let resolved = resolve_existing_prefix(&normalize_path(path));
+    if resolved.starts_with(&canonical_root) {
+        Ok(resolved)
+    } else {
+        Err(outside())
+    }
+}
+
+/// The directory file tools are confined to: the configured filesystem root, or else the
+/// workspace root. Tasks without a real workspace directory (ungrouped sessions) are unconfined.
+pub fn task_filesystem_root(context: &TaskContext) -> Option<PathBuf> {
+    if let Some(root) = &context.config().filesystem_root {
+        return Some(root.clone());
+    }
+    let workspace = Path::new(&*context.cwd);
+    (workspace.is_absolute() && workspace.is_dir()).then(|| workspace.to_path_buf())
+}
+
+/// Resolve a tool path against the task cwd, confined to the task's filesystem root.
+pub fn resolve_task_path(path: &str, context: &TaskContext) -> Result<PathBuf, ToolExecutorError> {
+    let resolved = ensure_absolute(path, &context.cwd)?;
+    match task_filesystem_root(context) {
+        Some(root) => confine_to_root(&resolved, &root),
+        None => Ok(resolved),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confine_to_root_rejects_escapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+
+        let inside = confine_to_root(&root.join("src/main.rs"), &root).unwrap();
+        assert_eq!(inside, root.canonicalize().unwrap().join("src/main.rs"));
+        assert!(confine_to_root(&root.join("src/new_file.rs"), &root).is_ok());
+
+        let traversal =
+            ensure_absolute("../../etc/passwd", root.join("src").to_str().unwrap()).unwrap();
+        let err = confine_to_root(&traversal, &root).unwrap_err();
+        assert!(err.to_string().contains("outside allowed root"));
+
+        #[cfg(unix)]
+        {
+            let outside = dir.path().join("outside");
+            std::fs::create_dir_all(&outside).unwrap();
+            std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+            assert!(confine_to_root(&root.join("link/secret.txt"), &root).is_err());
+            assert!(confine_to_root(&root.join("link"), &root).is_err());
+        }
+    }
+
+    #[test]
+    fn same_path_ignores_trailing_separators_and_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        std::fs::create_dir_all(&root).unwrap();
+
+        let with_separator = format!("{}/", root.display());
+        assert!(same_path(Path::new(&with_separator), &root));
+        assert!(same_path(&root.join("src/.."), &root));
+        assert!(!same_path(&root.join("src"), &root));
+
+        #[cfg(unix)]
+        {
+            let link = dir.path().join("link");
+            std::os::unix::fs::symlink(&root, &link).unwrap();
+            assert!(same_path(&link, &root));
+        }
+    }
+}
@@ -0,0 +1,518 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::fs;
+
+use crate::agent::context::FileOperationRecord;
+use crate::agent::core::context::TaskContext;
+use crate::agent::error::{ToolExecutorError, ToolExecutorResult};
+use crate::agent::persistence::FileRecordSource;
+use crate::agent::tools::builtin::shell::get_executor;
+use crate::agent::tools::{
+    RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority, ToolResult,
+    ToolResultContent, ToolResultStatus,
+};
+use crate::utils::ansi::strip_ansi;
+
+use super::file_utils::{resolve_task_path, task_filesystem_root};
+use super::run_tests::quote_arg;
+
+/// 单次格式化的超时时间（毫秒）
+const DEFAULT_TIMEOUT_MS: u64 = 120_000;
+/// 单次调用最多处理的文件数
+const MAX_PATHS: usize = 200;
+/// 每个格式化命令保留的诊断输出上限
+const MAX_DIAGNOSTIC_BYTES: usize = 8 * 1024;
+
+const PRETTIER_EXTENSIONS: &[&str] = &[
+    "js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts", "vue", "css", "scss", "less", "html",
+    "json", "md", "yaml", "yml",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Formatter {
+    Rustfmt,
+    Prettier,
+    Black,
+    Gofmt,
+}
+
+impl Formatter {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Rustfmt => "rustfmt",
+            Self::Prettier => "prettier",
+            Self::Black => "black",
+            Self::Gofmt => "gofmt",
+        }
+    }
+
+    fn for_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "rs" => Some(Self::Rustfmt),
+            "py" | "pyi" => Some(Self::Black),
+            "go" => Some(Self::Gofmt),
+            e if PRETTIER_EXTENSIONS.contains(&e) => Some(Self::Prettier),
+            _ => None,
+        }
+    }
+
+    /// 向上查找使用该格式化工具的项目目录，不越过文件系统根；项目没有配置时返回 None
+    fn project_dir(&self, file: &Path, root: Option<&Path>) -> Option<PathBuf> {
+        file.ancestors()
+            .skip(1)
+            .take_while(|dir| root.is_none_or(|root| dir.starts_with(root)))
+            .find(|dir| self.configured_in(dir))
+            .map(Path::to_path_buf)
+    }
+
+    fn configured_in(&self, dir: &Path) -> bool {
+        match self {
+            Self::Rustfmt => {
+                dir.join("Cargo.toml").is_file()
+                    || dir.join("rustfmt.toml").is_file()
+                    || dir.join(".rustfmt.toml").is_file()
+            }
+            // 只使用项目本地安装的 prettier，避免按全局版本的默认规则改写整个文件
+            Self::Prettier => dir
+                .join("node_modules")
+                .join(".bin")
+                .join("prettier")
+                .exists(),
+            Self::Black => std::fs::read_to_string(dir.join("pyproject.toml"))
+                .is_ok_and(|content| content.contains("[tool.black]")),
+            Self::Gofmt => dir.join("go.mod").is_file(),
+        }
+    }
+
+    /// 一次处理全部文件的命令；rustfmt 需逐个文件经 stdin 处理，返回 None
+    fn command(&self, project_dir: &Path, files: &[PathBuf]) -> Option<String> {
+        let program = match self {
+            Self::Rustfmt => return None,
+            Self::Prettier => format!(
+                "{} --write --log-level warn",
+                quote_arg(
+                    &project_dir
+                        .join("node_modules")
+                        .join(".bin")
+                        .join("prettier")
+                        .to_string_lossy()
+                )
+            ),
+            Self::Black => "black --quiet".to_string(),
+            Self::Gofmt => "gofmt -w".to_string(),
+        };
+        let args: Vec<String> = files
+            .iter()
+            .map(|f| quote_arg(&f.to_string_lossy()))
+            .collect();
+        Some(format!("{} {}", program, args.join(" ")))
+    }
+}
+
+/// rustfmt 直接处理文件时会连同 `mod foo;` 引用的子模块一起改写，
+/// 因此经 stdin 逐个格式化，结果先写到 `output`
+fn rustfmt_command(project_dir: &Path, file: &Path, output: &Path) -> String {
+    format!(
+        "rustfmt --edition {} < {} > {}",
+        rust_edition(project_dir),
+        quote_arg(&file.to_string_lossy()),
+        quote_arg(&output.to_string_lossy())
+    )
+}
+
+/// Cargo.toml 中的 edition，未声明时按 2021 处理
+fn rust_edition(project_dir: &Path) -> String {
+    std::fs::read_to_string(project_dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| {
+            content.lines().find_map(|line| {
+                let value = line.trim().strip_prefix("edition")?.trim_start();
+                let value = value.strip_prefix('=')?.trim().trim_matches('"');
+                (!value.is_empty() && value.chars().all(|c| c.is_ascii_digit()))
+                    .then(|| value.to_string())
+            })
+        })
+        .unwrap_or_else(|| "2021".to_string())
+}
+
+async fn execute(command: &str, cwd: &Path) -> (Option<i32>, String) {
+    match get_executor()
+        .execute(
+            command,
+            &cwd.to_string_lossy(),
+            Some(Duration::from_millis(DEFAULT_TIMEOUT_MS)),
+        )
+        .await
+    {
+        Ok(result) => (result.exit_code, strip_ansi(&result.output)),
+        Err(e) => (None, e.to_string()),
+    }
+}
+
+/// 逐个文件运行 rustfmt 并写回结果；返回执行过的命令、首个失败的退出码与诊断输出
+async fn format_rust_files(project_dir: &Path, files: &[PathBuf]) -> (String, Option<i32>, String) {
+    let mut commands = Vec::with_capacity(files.len());
+    let mut exit_code = Some(0);
+    let mut output = String::new();
+    for file in files {
+        let formatted =
+            std::env::temp_dir().join(format!("orbitx-rustfmt-{}.rs", uuid::Uuid::new_v4()));
+        let command = rustfmt_command(project_dir, file, &formatted);
+        let (mut code, out) = execute(&command, project_dir).await;
+        output.push_str(&out);
+        if code == Some(0) {
+            if let Err(e) = write_back(file, &formatted).await {
+                output.push_str(&format!("{}: {}\n", file.display(), e));
+                code = Some(1);
+            }
+        }
+        let _ = fs::remove_file(&formatted).await;
+        commands.push(command);
+        if exit_code == Some(0) {
+            exit_code = code;
+        }
+        if is_missing_command(code, &out) {
+            break;
+        }
+    }
+    (commands.join("\n"), exit_code, output)
+}
+
+/// 内容有变化时才写回，避免无谓地更新修改时间
+async fn write_back(file: &Path, formatted: &Path) -> std::io::Result<()> {
+    let content = fs::read(formatted).await?;
+    if fs::read(file).await.ok().as_deref() != Some(content.as_slice()) {
+        fs::write(file, content).await?;
+    }
+    Ok(())
+}
+
+/// 格式化工具未安装（shell 返回 127 或提示找不到命令）
+fn is_missing_command(exit_code: Option<i32>, output: &str) -> bool {
+    exit_code == Some(127)
+        || output.contains("command not found")
+        || output.contains("is not recognized as")
+}
+
+fn truncate(text: &str, max_bytes: usize) -> String {
+    let text = text.trim();
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n... (truncated)", &text[..end])
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FormatRun {
+    formatter: Formatter,
+    project_dir: String,
+    command: String,
+    exit_code: Option<i32>,
+    success: bool,
+    /// 内容被修改的文件
+    changed: Vec<String>,
+    diagnostics: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FormatCodeArgs {
+    paths: Vec<String>,
+}
+
+pub struct FormatCodeTool;
+
+impl FormatCodeTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl RunnableTool for FormatCodeTool {
+    fn name(&self) -> &str {
+        "format_code"
+    }
+
+    fn description(&self) -> &str {
+        "Formats files with the project's own formatter after you edit them.
+
+Usage:
+- Detects the formatter per file from the project: rustfmt (Cargo.toml), prettier (installed in node_modules), black ([tool.black] in pyproject.toml), gofmt (go.mod)
+- Files without a configured formatter are left untouched and listed in the result
+- A checkpoint of every file is taken first, so the change can be rolled back
+- Formatting errors (usually syntax errors) are returned as diagnostics
+- Files that were changed must be re-read before editing them again"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "minItems": 1,
+                    "maxItems": MAX_PATHS,
+                    "description": "Absolute paths of the files to format."
+                }
+            },
+            "required": ["paths"]
+        })
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::new(ToolCategory::FileWrite, ToolPriority::Standard)
+            .with_confirmation()
+            .with_timeout(Duration::from_millis(DEFAULT_TIMEOUT_MS))
+            .with_tags(vec!["format".into(), "write".into(), "command".into()])
+            .with_summary_key_arg("paths")
+    }
+
+    fn required_permissions(&self) -> Vec<ToolPermission> {
+        vec![ToolPermission::FileSystem, ToolPermission::SystemCommand]
+    }
+
+    async fn run(
+        &self,
+        context: &TaskContext,
+        args: serde_json::Value,
+    ) -> ToolExecutorResult<ToolResult> {
+        let args: FormatCodeArgs = serde_json::from_value(args)?;
+        if args.paths.is_empty() {
+            return Ok(error_result("paths must not be empty"));
+        }
+        if args.paths.len() > MAX_PATHS {
+            return Ok(error_result(format!(
+                "Too many paths ({}), format at most {} files per call",
+                args.paths.len(),
+                MAX_PATHS
+            )));
+        }
+
+        let root = task_filesystem_root(context).and_then(|root| std::fs::canonicalize(root).ok());
+        let mut groups: BTreeMap<(Formatter, PathBuf), Vec<PathBuf>> = BTreeMap::new();
+        let mut unsupported = Vec::new();
+        for raw in &args.paths {
+            let path = match resolve_task_path(raw, context) {
+                Ok(path) => path,
+                Err(err) => return Ok(error_result(err.to_string())),
+            };
+            if !path.is_file() {
+                return Ok(error_result(format!("{} is not a file", path.display())));
+            }
+            match Formatter::for_path(&path)
+                .and_then(|f| f.project_dir(&path, root.as_deref()).map(|dir| (f, dir)))
+            {
+                Some(key) => {
+                    let files = groups.entry(key).or_default();
+                    if !files.contains(&path) {
+                        files.push(path);
+                    }
+                }
+                None => unsupported.push(path.display().to_string()),
+            }
+        }
+
+        let mut runs = Vec::new();
+        let mut missing = Vec::new();
+        for ((formatter, project_dir), files) in groups {
+            for file in &files {
+                snapshot_before_edit(context, self.name(), file).await?;
+            }
+            let mut before = Vec::with_capacity(files.len());
+            for file in &files {
+                before.push(fs::read(file).await.ok());
+            }
+
+            let (command, exit_code, output) = match formatter.command(&project_dir, &files) {
+                Some(command) => {
+                    let (exit_code, output) = execute(&command, &project_dir).await;
+                    (command, exit_code, output)
+                }
+                None => format_rust_files(&project_dir, &files).await,
+            };
+            if is_missing_command(exit_code, &output) {
+                missing.push(formatter);
+                continue;
+            }
+
+            let mut changed = Vec::new();
+            for (file, old) in files.iter().zip(before) {
+                if fs::read(file).await.ok() != old {
+                    context
+                        .file_tracker()
+                        .track_file_operation(FileOperationRecord::new(
+                            file.as_path(),
+                            FileRecordSource::AgentEdited,
+                        ))
+                        .await?;
+                    changed.push(file.display().to_string());
+                }
+            }
+            runs.push(FormatRun {
+                formatter,
+                project_dir: project_dir.to_string_lossy().to_string(),
+                command,
+                exit_code,
+                success: exit_code == Some(0),
+                changed,
+                diagnostics: truncate(&output, MAX_DIAGNOSTIC_BYTES),
+            });
+        }
+
+        let text = render(&runs, &missing, &unsupported);
+        let is_success = runs.iter().all(|r| r.success);
+        Ok(ToolResult {
+            content: vec![if is_success {
+                ToolResultContent::Success(text)
+            } else {
+                ToolResultContent::Error(text)
+            }],
+            status: if is_success {
+                ToolResultStatus::Success
+            } else {
+                ToolResultStatus::Error
+            },
+            cancel_reason: None,
+            execution_time_ms: None,
+            ext_info: Some(json!({
+                "runs": runs,
+                "missingFormatters": missing,
+                "unformatted": unsupported,
+            })),
+        })
+    }
+}
+
+fn render(runs: &[FormatRun], missing: &[Formatter], unsupported: &[String]) -> String {
+    let mut text = String::new();
+    for run in runs {
+        text.push_str(&format!(
+            "{} in {}: {} ({} file(s) changed)\n",
+            run.formatter.label(),
+            run.project_dir,
+            if run.success { "ok" } else { "failed" },
+            run.changed.len()
+        ));
+        for file in &run.changed {
+            text.push_str(&format!("- {}\n", file));
+        }
+        if !run.success && !run.diagnostics.is_empty() {
+            text.push_str(&format!("```\n{}\n```\n", run.diagnostics));
+        }
+    }
+    for formatter in missing {
+        text.push_str(&format!(
+            "{} is configured for this project but is not installed; files were left unchanged.\n",
+            formatter.label()
+        ));
+    }
+    if !unsupported.is_empty() {
+        text.push_str(&format!(
+            "No formatter is configured for {} file(s); left unchanged:\n",
+            unsupported.len()
+        ));
+        for file in unsupported {
+            text.push_str(&format!("- {}\n", file));
+        }
+    }
+    if runs.iter().any(|r| !r.changed.is_empty()) {
+        text.push_str("Changed files must be re-read before editing them again.\n");
+    }
+    text.trim_end().to_string()
+}
+
+fn error_result(message: impl Into<String>) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Error(message.into())],
+        status: ToolResultStatus::Error,
+        cancel_reason: None,
+        execution_time_ms: None,
+        ext_info: None,
+    }
+}
+
+async fn snapshot_before_edit(
+    context: &TaskContext,
+    tool_name: &str,
+    path: &Path,
+) -> ToolExecutorResult<()> {
+    crate::vector_db::agent_writes().register(&context.task_id, path);
+    context
+        .snapshot_file_before_edit(path)
+        .await
+        .map_err(|err| ToolExecutorError::ExecutionFailed {
+            tool_name: tool_name.to_string(),
+            error: err.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_formatter_from_project_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let rust_file = root.join("src/main.rs");
+        let py_file = root.join("tool.py");
+
+        assert_eq!(Formatter::Rustfmt.project_dir(&rust_file, None), None);
+        assert_eq!(Formatter::for_path(Path::new("notes.txt")), None);
+
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nedition = \"2018\"\n",
+        )
+        .unwrap();
+        assert_eq!(Formatter::for_path(&rust_file), Some(Formatter::Rustfmt));
+        assert_eq!(
+            Formatter::Rustfmt.project_dir(&rust_file, None).as_deref(),
+            Some(root)
+        );
+        // 不越过文件系统根查找项目配置
+        assert_eq!(
+            Formatter::Rustfmt.project_dir(&rust_file, Some(&root.join("src"))),
+            None
+        );
+        assert_eq!(Formatter::Rustfmt.command(root, &[rust_file.clone()]), None);
+        assert!(rustfmt_command(root, &rust_file, &root.join("out.rs"))
+            .starts_with("rustfmt --edition 2018 < "));
+
+        std::fs::write(root.join("pyproject.toml"), "[project]\nname = \"demo\"\n").unwrap();
+        assert_eq!(Formatter::Black.project_dir(&py_file, None), None);
+        std::fs::write(
+            root.join("pyproject.toml"),
+            "[tool.black]\nline-length = 100\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Formatter::Black
+                .project_dir(&py_file, Some(root))
+                .as_deref(),
+            Some(root)
+        );
+    }
+
+    #[test]
+    fn recognizes_missing_formatter() {
+        assert!(is_missing_command(Some(127), ""));
+        assert!(is_missing_command(Some(1), "zsh: command not found: black"));
+        assert!(!is_missing_command(Some(1), "error: expected `;`"));
+    }
+}
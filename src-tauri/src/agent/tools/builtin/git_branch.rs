@@ -0,0 +1,354 @@
+use std::time::Instant;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::agent::core::context::TaskContext;
+use crate::agent::error::ToolExecutorResult;
+use crate::agent::tools::{
+    RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority, ToolResult,
+    ToolResultContent, ToolResultStatus,
+};
+use crate::git::{GitError, GitErrorCode, GitService, RepositoryStatus};
+use crate::storage::repositories::AppPreferences;
+
+/// 是否允许 git_branch 推送分支的偏好项，值为 "true" / "false"，默认不允许
+pub const GIT_BRANCH_PUSH_ENABLED_KEY: &str = "agent.git_branch.push_enabled";
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum BranchOperation {
+    List,
+    Create,
+    Checkout,
+    Push,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GitBranchArgs {
+    operation: BranchOperation,
+    name: Option<String>,
+    start_point: Option<String>,
+    /// create 时是否同时切换到新分支，默认 true
+    checkout: Option<bool>,
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BranchState {
+    current_branch: Option<String>,
+    is_detached: bool,
+    local_branches: Vec<String>,
+    staged_files: usize,
+    modified_files: usize,
+    conflicted_files: usize,
+}
+
+pub struct GitBranchTool;
+
+impl GitBranchTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl RunnableTool for GitBranchTool {
+    fn name(&self) -> &str {
+        "git_branch"
+    }
+
+    fn description(&self) -> &str {
+        "Lists, creates, switches and pushes git branches in the current workspace.
+
+Usage:
+- operation \"list\": show the current branch, local branches and uncommitted change counts
+- operation \"create\": create branch `name` (from `startPoint`, default HEAD) and switch to it unless `checkout` is false
+- operation \"checkout\": switch to the existing local branch `name`
+- operation \"push\": push branch `name` (default: current branch) to origin and set upstream; only available when the user has enabled pushing in the settings. Force-push is never performed
+- Switching branches is refused while there are uncommitted or conflicted changes; set `force` to true only when the user explicitly wants to carry those changes over. git still refuses any switch that would overwrite local changes
+- Every operation returns the resulting branch state"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["list", "create", "checkout", "push"],
+                    "description": "Branch operation to perform."
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Branch name. Required for create and checkout; defaults to the current branch for push."
+                },
+                "startPoint": {
+                    "type": "string",
+                    "description": "Commit, tag or branch to create the new branch from (create only, default: HEAD)."
+                },
+                "checkout": {
+                    "type": "boolean",
+                    "description": "Switch to the new branch after creating it (create only, default: true)."
+                },
+                "force": {
+                    "type": "boolean",
+                    "description": "Switch branches even though there are uncommitted or conflicted changes (default: false)."
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::new(ToolCategory::Execution, ToolPriority::Standard)
+            .with_confirmation()
+            .with_tags(vec!["git".into(), "branch".into()])
+    }
+
+    fn required_permissions(&self) -> Vec<ToolPermission> {
+        vec![ToolPermission::SystemCommand]
+    }
+
+    async fn run(
+        &self,
+        context: &TaskContext,
+        args: serde_json::Value,
+    ) -> ToolExecutorResult<ToolResult> {
+        let args: GitBranchArgs = serde_json::from_value(args)?;
+        let cwd = &*context.cwd;
+        let started = Instant::now();
+
+        let repo_root = match GitService::is_repository(cwd).await {
+            Ok(Some(root)) => root,
+            Ok(None) => {
+                return Ok(tool_error(format!(
+                    "Not a git repository: {}. git_branch only works inside a git working tree.",
+                    cwd
+                )))
+            }
+            Err(e) => return Ok(tool_error(git_error_message(&e))),
+        };
+
+        let status = match GitService::get_status(&repo_root).await {
+            Ok(status) => status,
+            Err(e) => return Ok(tool_error(git_error_message(&e))),
+        };
+        let name = args
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty());
+
+        let summary = match args.operation {
+            BranchOperation::List => "Branch state".to_string(),
+            BranchOperation::Create => {
+                let Some(name) = name else {
+                    return Ok(tool_error("name is required for create"));
+                };
+                let checkout = args.checkout.unwrap_or(true);
+                let start_point = args
+                    .start_point
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty());
+                // 从 HEAD 新建并切换不会改动工作区，只有换到别的起点才需要检查
+                if checkout && start_point.is_some() && !args.force {
+                    if let Some(reason) = dirty_reason(&status) {
+                        return Ok(refused(&reason));
+                    }
+                }
+                if let Err(e) =
+                    GitService::create_branch(&repo_root, name, start_point, checkout).await
+                {
+                    return Ok(tool_error(git_error_message(&e)));
+                }
+                if checkout {
+                    format!("Created and switched to branch '{}'", name)
+                } else {
+                    format!("Created branch '{}'", name)
+                }
+            }
+            BranchOperation::Checkout => {
+                let Some(name) = name else {
+                    return Ok(tool_error("name is required for checkout"));
+                };
+                if status.current_branch.as_deref() == Some(name) {
+                    format!("Already on branch '{}'", name)
+                } else {
+                    if !args.force {
+                        if let Some(reason) = dirty_reason(&status) {
+                            return Ok(refused(&reason));
+                        }
+                    }
+                    if let Err(e) = GitService::checkout_branch(&repo_root, name).await {
+                        return Ok(tool_error(git_error_message(&e)));
+                    }
+                    format!("Switched to branch '{}'", name)
+                }
+            }
+            BranchOperation::Push => {
+                if !push_enabled(context).await {
+                    return Ok(tool_error(
+                        "Pushing branches is disabled. The user can enable it in the agent settings.",
+                    ));
+                }
+                let Some(branch) = name.map(str::to_string).or(status.current_branch.clone())
+                else {
+                    return Ok(tool_error(
+                        "HEAD is detached; specify the branch name to push",
+                    ));
+                };
+                if let Err(e) = GitService::push_branch(&repo_root, &branch).await {
+                    return Ok(tool_error(git_error_message(&e)));
+                }
+                format!("Pushed branch '{}' to origin", branch)
+            }
+        };
+
+        let state = match branch_state(&repo_root).await {
+            Ok(state) => state,
+            Err(e) => return Ok(tool_error(git_error_message(&e))),
+        };
+
+        Ok(ToolResult {
+            content: vec![ToolResultContent::Success(format!(
+                "{}\n\n{}",
+                summary,
+                format_state(&state)
+            ))],
+            status: ToolResultStatus::Success,
+            cancel_reason: None,
+            execution_time_ms: Some(started.elapsed().as_millis() as u64),
+            ext_info: Some(json!({
+                "repositoryRoot": repo_root,
+                "state": state,
+            })),
+        })
+    }
+}
+
+async fn push_enabled(context: &TaskContext) -> bool {
+    AppPreferences::new(context.agent_persistence().database())
+        .get(GIT_BRANCH_PUSH_ENABLED_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true")
+}
+
+/// 切换分支前需要用户确认的工作区状态；未跟踪文件由 git 自行判断是否冲突
+fn dirty_reason(status: &RepositoryStatus) -> Option<String> {
+    if !status.conflicted_files.is_empty() {
+        return Some(format!(
+            "{} file(s) have unresolved merge conflicts",
+            status.conflicted_files.len()
+        ));
+    }
+    let changed = status.staged_files.len() + status.modified_files.len();
+    (changed > 0).then(|| format!("{} file(s) have uncommitted changes", changed))
+}
+
+async fn branch_state(repo_root: &str) -> Result<BranchState, GitError> {
+    let status = GitService::get_status(repo_root).await?;
+    let local_branches = GitService::get_branches(repo_root)
+        .await?
+        .into_iter()
+        .filter(|b| !b.is_remote)
+        .map(|b| b.name)
+        .collect();
+    Ok(BranchState {
+        current_branch: status.current_branch,
+        is_detached: status.is_detached,
+        local_branches,
+        staged_files: status.staged_files.len(),
+        modified_files: status.modified_files.len(),
+        conflicted_files: status.conflicted_files.len(),
+    })
+}
+
+fn format_state(state: &BranchState) -> String {
+    let current = match (&state.current_branch, state.is_detached) {
+        (_, true) => "(detached HEAD)".to_string(),
+        (Some(branch), false) => branch.clone(),
+        (None, false) => "(no commits yet)".to_string(),
+    };
+    format!(
+        "Current branch: {}\nLocal branches: {}\nUncommitted: {} staged, {} modified, {} conflicted",
+        current,
+        state.local_branches.join(", "),
+        state.staged_files,
+        state.modified_files,
+        state.conflicted_files
+    )
+}
+
+fn git_error_message(error: &GitError) -> String {
+    match error.code {
+        GitErrorCode::NotARepository => "Not a git repository".to_string(),
+        GitErrorCode::GitNotInstalled => "git is not installed or not in PATH".to_string(),
+        _ => format!("git failed: {}", error.message),
+    }
+}
+
+fn refused(reason: &str) -> ToolResult {
+    tool_error(format!(
+        "Refusing to switch branches: {}. Commit or stash them first, or set force to true if the user explicitly wants to carry them over.",
+        reason
+    ))
+}
+
+fn tool_error(message: impl Into<String>) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Error(message.into())],
+        status: ToolResultStatus::Error,
+        cancel_reason: None,
+        execution_time_ms: None,
+        ext_info: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(staged: usize, modified: usize, conflicted: usize) -> RepositoryStatus {
+        let files = |n: usize| {
+            (0..n)
+                .map(|i| crate::git::FileChange {
+                    path: format!("f{i}"),
+                    status: crate::git::FileChangeStatus::Modified,
+                    old_path: None,
+                })
+                .collect::<Vec<_>>()
+        };
+        RepositoryStatus {
+            is_repository: true,
+            root_path: Some("/repo".into()),
+            current_branch: Some("main".into()),
+            staged_files: files(staged),
+            modified_files: files(modified),
+            untracked_files: files(3),
+            conflicted_files: files(conflicted),
+            ahead: None,
+            behind: None,
+            is_empty: false,
+            is_detached: false,
+        }
+    }
+
+    #[test]
+    fn dirty_reason_ignores_untracked_and_prefers_conflicts() {
+        assert_eq!(dirty_reason(&status(0, 0, 0)), None);
+        assert!(dirty_reason(&status(1, 2, 0))
+            .unwrap()
+            .starts_with("3 file(s) have uncommitted"));
+        assert!(dirty_reason(&status(1, 0, 1))
+            .unwrap()
+            .contains("merge conflicts"));
+    }
+}
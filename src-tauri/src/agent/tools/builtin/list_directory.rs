@@ -0,0 +1,309 @@
+use async_trait::async_trait;
+use ignore::WalkBuilder;
+use serde::Deserialize;
+use serde_json::json;
+use std::path::Path;
+
+use crate::agent::context::FileOperationRecord;
+use crate::agent::core::context::TaskContext;
+use crate::agent::error::ToolExecutorResult;
+use crate::agent::persistence::FileRecordSource;
+use crate::agent::tools::{
+    RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority, ToolResult,
+    ToolResultContent, ToolResultStatus,
+};
+use crate::vector_db::utils::filter_dirs;
+
+use super::file_utils::resolve_task_path;
+
+const DEFAULT_DEPTH: usize = 2;
+const MAX_DEPTH: usize = 8;
+const DEFAULT_MAX_ENTRIES: usize = 200;
+const MAX_ENTRIES_LIMIT: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListDirectoryArgs {
+    path: String,
+    depth: Option<usize>,
+    respect_gitignore: Option<bool>,
+    max_entries: Option<usize>,
+}
+
+/// 目录树遍历结果
+#[derive(Debug, Default)]
+struct DirectoryTree {
+    lines: Vec<String>,
+    files: usize,
+    directories: usize,
+    truncated: bool,
+}
+
+pub struct ListDirectoryTool;
+
+impl ListDirectoryTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl RunnableTool for ListDirectoryTool {
+    fn name(&self) -> &str {
+        "list_directory"
+    }
+
+    fn description(&self) -> &str {
+        "Shows a bounded directory tree with file sizes, for a quick overview of project structure.
+
+Usage:
+- The path must be a directory inside the current workspace (absolute, or relative to the workspace)
+- depth controls how many levels are expanded (default 2, max 8)
+- By default .gitignore rules and common build/dependency directories (node_modules, target, dist, ...) are skipped
+- Output is capped at maxEntries entries (default 200, max 1000); when the cap is hit the result is marked as truncated and you should list a narrower subdirectory or reduce depth
+- Use this before searching to understand the layout; use orbit_search or read_file for content"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Directory to list. Must be inside the workspace. For example: '/Users/user/project/src'."
+                },
+                "depth": {
+                    "type": "integer",
+                    "description": "How many directory levels to expand. Default: 2, maximum: 8."
+                },
+                "respectGitignore": {
+                    "type": "boolean",
+                    "description": "Skip files matched by .gitignore and common build/dependency directories. Default: true."
+                },
+                "maxEntries": {
+                    "type": "integer",
+                    "description": "Maximum number of entries to return. Default: 200, maximum: 1000."
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::new(ToolCategory::FileSystem, ToolPriority::Standard)
+            .with_tags(vec!["filesystem".into(), "tree".into()])
+            .with_summary_key_arg("path")
+    }
+
+    fn required_permissions(&self) -> Vec<ToolPermission> {
+        vec![ToolPermission::FileSystem]
+    }
+
+    async fn run(
+        &self,
+        context: &TaskContext,
+        args: serde_json::Value,
+    ) -> ToolExecutorResult<ToolResult> {
+        let args: ListDirectoryArgs = serde_json::from_value(args)?;
+        let trimmed = args.path.trim();
+        if trimmed.is_empty() {
+            return Ok(error_result("Directory path cannot be empty"));
+        }
+
+        let path = match resolve_task_path(trimmed, context) {
+            Ok(resolved) => resolved,
+            Err(err) => return Ok(error_result(err.to_string())),
+        };
+
+        // resolve_task_path 已限制在文件系统根内，这里只确认目录存在
+        let target = match std::fs::canonicalize(&path) {
+            Ok(target) => target,
+            Err(_) => {
+                return Ok(error_result(format!(
+                    "Directory does not exist: {}",
+                    path.display()
+                )))
+            }
+        };
+        if !target.is_dir() {
+            return Ok(error_result(format!(
+                "Path {} is not a directory, use read_file to view file contents",
+                path.display()
+            )));
+        }
+
+        let depth = args.depth.unwrap_or(DEFAULT_DEPTH).clamp(1, MAX_DEPTH);
+        let respect_gitignore = args.respect_gitignore.unwrap_or(true);
+        let max_entries = args
+            .max_entries
+            .unwrap_or(DEFAULT_MAX_ENTRIES)
+            .clamp(1, MAX_ENTRIES_LIMIT);
+
+        let walk_root = target.clone();
+        let tree = match tokio::task::spawn_blocking(move || {
+            build_tree(&walk_root, depth, respect_gitignore, max_entries)
+        })
+        .await
+        {
+            Ok(tree) => tree,
+            Err(err) => return Ok(error_result(format!("Directory listing failed: {}", err))),
+        };
+
+        let mut text = format!(
+            "Directory tree for {} (depth {}, {} directories, {} files{}):\n{}/",
+            path.display(),
+            depth,
+            tree.directories,
+            tree.files,
+            if tree.truncated { ", truncated" } else { "" },
+            path.display()
+        );
+        for line in &tree.lines {
+            text.push('\n');
+            text.push_str(line);
+        }
+        if tree.truncated {
+            text.push_str(&format!(
+                "\n... output truncated at {} entries; list a subdirectory or reduce depth to see more",
+                max_entries
+            ));
+        }
+
+        context
+            .file_tracker()
+            .track_file_operation(FileOperationRecord::new(
+                path.as_path(),
+                FileRecordSource::FileMentioned,
+            ))
+            .await?;
+
+        Ok(ToolResult {
+            content: vec![ToolResultContent::Success(text)],
+            status: ToolResultStatus::Success,
+            cancel_reason: None,
+            execution_time_ms: None,
+            ext_info: Some(json!({
+                "path": path.display().to_string(),
+                "depth": depth,
+                "count": tree.lines.len(),
+                "files": tree.files,
+                "directories": tree.directories,
+                "truncated": tree.truncated,
+                "respectGitIgnore": respect_gitignore,
+            })),
+        })
+    }
+}
+
+/// 按文件名排序深度优先遍历，每个条目一行，用缩进表示层级
+fn build_tree(
+    root: &Path,
+    depth: usize,
+    respect_gitignore: bool,
+    max_entries: usize,
+) -> DirectoryTree {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(false)
+        .ignore(respect_gitignore)
+        .git_ignore(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .git_global(respect_gitignore)
+        .parents(respect_gitignore)
+        .require_git(false)
+        .max_depth(Some(depth))
+        .sort_by_file_name(|a, b| a.cmp(b));
+    if respect_gitignore {
+        builder.filter_entry(filter_dirs);
+    } else {
+        builder.filter_entry(|e| e.file_name() != ".git");
+    }
+
+    let mut tree = DirectoryTree::default();
+    for entry in builder.build().flatten() {
+        // depth 0 是根目录本身
+        if entry.depth() == 0 {
+            continue;
+        }
+        if tree.lines.len() >= max_entries {
+            tree.truncated = true;
+            break;
+        }
+
+        let indent = "  ".repeat(entry.depth());
+        let name = entry.file_name().to_string_lossy();
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        if is_dir {
+            tree.directories += 1;
+            tree.lines.push(format!("{}{}/", indent, name));
+        } else {
+            tree.files += 1;
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            tree.lines
+                .push(format!("{}{} ({})", indent, name, format_size(size)));
+        }
+    }
+    tree
+}
+
+fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    let b = bytes as f64;
+    if b >= MB {
+        format!("{:.1} MB", b / MB)
+    } else if b >= KB {
+        format!("{:.1} KB", b / KB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+fn error_result(message: impl Into<String>) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Error(message.into())],
+        status: ToolResultStatus::Error,
+        cancel_reason: None,
+        execution_time_ms: None,
+        ext_info: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn respects_gitignore_and_caps_entries() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(root.join(".gitignore"), "secret.txt\n").unwrap();
+        std::fs::write(root.join("secret.txt"), "x").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/nested/deep.rs"), "").unwrap();
+
+        let tree = build_tree(root, 2, true, 100);
+        assert_eq!(
+            tree.lines,
+            vec![
+                "  .gitignore (11 B)",
+                "  src/",
+                "    main.rs (12 B)",
+                "    nested/",
+            ]
+        );
+        assert_eq!((tree.directories, tree.files), (2, 2));
+        assert!(!tree.truncated);
+
+        let all = build_tree(root, 3, false, 100);
+        assert!(all.lines.iter().any(|l| l.contains("secret.txt")));
+        assert!(all.lines.iter().any(|l| l.contains("deep.rs")));
+
+        let capped = build_tree(root, 3, false, 2);
+        assert_eq!(capped.lines.len(), 2);
+        assert!(capped.truncated);
+    }
+}
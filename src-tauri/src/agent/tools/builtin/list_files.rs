@@ -12,7 +12,7 @@ use crate::agent::tools::{
 };
 use crate::filesystem::commands::fs_list_directory;
 
-use super::file_utils::ensure_absolute;
+use super::file_utils::resolve_task_path;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -85,7 +85,7 @@ Usage:
             return Ok(validation_error("Directory path cannot be empty"));
         }
 
-        let path = match ensure_absolute(trimmed, &context.cwd) {
+        let path = match resolve_task_path(trimmed, context) {
             Ok(resolved) => resolved,
             Err(err) => return Ok(validation_error(err.to_string())),
         };
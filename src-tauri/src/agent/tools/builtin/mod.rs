@@ -1,19 +1,35 @@
 pub(crate) mod file_utils;
 
+pub mod checkpoint;
+pub mod format_code;
+pub mod git_branch;
+pub mod list_directory;
 pub mod list_files;
+pub mod move_file;
 pub mod orbit_search;
 pub mod read_file;
 pub mod read_terminal;
+pub mod recall_history;
+pub mod run_tests;
 pub mod shell;
+pub mod system_info;
 pub mod unified_edit;
 pub mod web_fetch;
 pub mod write_file;
 
+pub use checkpoint::{ListCheckpointsTool, RollbackCheckpointTool};
+pub use format_code::FormatCodeTool;
+pub use git_branch::GitBranchTool;
+pub use list_directory::ListDirectoryTool;
 pub use list_files::ListFilesTool;
+pub use move_file::MoveFileTool;
 pub use orbit_search::OrbitSearchTool;
 pub use read_file::ReadFileTool;
 pub use read_terminal::ReadTerminalTool;
+pub use recall_history::RecallHistoryTool;
+pub use run_tests::RunTestsTool;
 pub use shell::ShellTool;
+pub use system_info::SystemInfoTool;
 pub use unified_edit::UnifiedEditTool;
 pub use web_fetch::WebFetchTool;
 pub use write_file::WriteFileTool;
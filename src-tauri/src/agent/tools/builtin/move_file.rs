@@ -0,0 +1,534 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use ignore::WalkBuilder;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::fs;
+
+use crate::agent::context::FileOperationRecord;
+use crate::agent::core::context::TaskContext;
+use crate::agent::error::{ToolExecutorError, ToolExecutorResult};
+use crate::agent::persistence::FileRecordSource;
+use crate::agent::tools::{
+    RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority, ToolResult,
+    ToolResultContent, ToolResultStatus,
+};
+use crate::vector_db::core::VectorDbError;
+
+use super::file_utils::{normalize_path, resolve_task_path, task_filesystem_root};
+
+/// 参与相对导入路径更新的扩展名（JS/TS 系）
+const MODULE_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs", "vue"];
+
+/// 更新引用时最多扫描的文件数
+const MAX_REFERENCE_SCAN_FILES: usize = 5_000;
+
+/// `from '...'`、`import('...')`、`require('...')`、`import '...'` 中的相对路径
+static RELATIVE_IMPORT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?:\bfrom\s*|\bimport\s*\(\s*|\brequire\s*\(\s*|\bimport\s+)['"](\.\.?/[^'"\n]*)['"]"#,
+    )
+    .unwrap()
+});
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MoveFileArgs {
+    from: String,
+    to: String,
+    #[serde(default)]
+    overwrite: bool,
+    #[serde(default = "default_true")]
+    update_references: bool,
+}
+
+pub struct MoveFileTool;
+
+impl MoveFileTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl RunnableTool for MoveFileTool {
+    fn name(&self) -> &str {
+        "move_file"
+    }
+
+    fn description(&self) -> &str {
+        "Moves or renames a file or directory inside the workspace.
+
+Usage:
+- Use this instead of writing a copy and deleting the original; history, the search index and imports are kept in sync
+- from and to must both be inside the current workspace (absolute, or relative to the workspace)
+- Missing parent directories of to are created
+- Refuses to overwrite an existing file unless overwrite is true; an existing directory is never replaced
+- Directories are moved with all their contents
+- By default, relative import paths in JS/TS files (import/require) that point to the moved files are updated, as well as the moved files' own relative imports. Other languages are not rewritten; check them yourself
+- The result lists every file whose references were updated"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "from": {
+                    "type": "string",
+                    "description": "The file or directory to move."
+                },
+                "to": {
+                    "type": "string",
+                    "description": "The new path, including the new file or directory name."
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Replace an existing file at the destination. Default: false."
+                },
+                "updateReferences": {
+                    "type": "boolean",
+                    "description": "Update relative JS/TS import paths that point to the moved files. Default: true."
+                }
+            },
+            "required": ["from", "to"]
+        })
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::new(ToolCategory::FileWrite, ToolPriority::Standard)
+            .with_confirmation()
+            .with_tags(vec!["filesystem".into(), "write".into(), "move".into()])
+            .with_summary_key_arg("from")
+    }
+
+    fn required_permissions(&self) -> Vec<ToolPermission> {
+        vec![ToolPermission::FileSystem]
+    }
+
+    async fn run(
+        &self,
+        context: &TaskContext,
+        args: serde_json::Value,
+    ) -> ToolExecutorResult<ToolResult> {
+        let args: MoveFileArgs = serde_json::from_value(args)?;
+        let (from, to) = match (
+            resolve_task_path(&args.from, context),
+            resolve_task_path(&args.to, context),
+        ) {
+            (Ok(from), Ok(to)) => (from, to),
+            (Err(err), _) | (_, Err(err)) => return Ok(error_result(err.to_string())),
+        };
+
+        // resolve_task_path 已限制在文件系统根内，这里只需拒绝移动根目录本身
+        if let Some(root) =
+            task_filesystem_root(context).and_then(|root| std::fs::canonicalize(root).ok())
+        {
+            if from == root || to == root {
+                return Ok(error_result(format!(
+                    "Cannot move the workspace root {}",
+                    root.display()
+                )));
+            }
+        }
+        // 索引与引用扫描以工作区为根，与解析后的路径一样使用规范化形式
+        let workspace = Path::new(context.cwd.as_ref());
+        let root = std::fs::canonicalize(workspace).unwrap_or_else(|_| normalize_path(workspace));
+        if from == to {
+            return Ok(error_result("Source and destination are the same path"));
+        }
+        if to.starts_with(&from) {
+            return Ok(error_result(format!(
+                "Cannot move {} into itself",
+                from.display()
+            )));
+        }
+
+        let from_meta = match fs::symlink_metadata(&from).await {
+            Ok(meta) => meta,
+            Err(_) => {
+                return Ok(error_result(format!(
+                    "Source does not exist: {}",
+                    from.display()
+                )))
+            }
+        };
+        let replaces_existing = match fs::symlink_metadata(&to).await {
+            Ok(meta) if meta.is_dir() => {
+                return Ok(error_result(format!(
+                    "Destination {} is an existing directory; choose a path that does not exist",
+                    to.display()
+                )))
+            }
+            Ok(_) if from_meta.is_dir() => {
+                return Ok(error_result(format!(
+                    "Cannot replace file {} with a directory",
+                    to.display()
+                )))
+            }
+            Ok(_) if !args.overwrite => {
+                return Ok(error_result(format!(
+                    "Destination {} already exists. Set overwrite to true to replace it.",
+                    to.display()
+                )))
+            }
+            Ok(_) => true,
+            Err(_) => false,
+        };
+
+        let moves = collect_moves(&from, &to, from_meta.is_dir());
+        let mapping: HashMap<PathBuf, PathBuf> = moves.iter().cloned().collect();
+
+        // 在移动前读取并计算需要更新的导入，写入发生在移动之后
+        let rewrites = if args.update_references && moves.iter().any(|(old, _)| is_module(old)) {
+            plan_reference_updates(&root, &mapping).await
+        } else {
+            Vec::new()
+        };
+
+        for (old, new) in &moves {
+            snapshot_before_edit(context, self.name(), old).await?;
+            snapshot_before_edit(context, self.name(), new).await?;
+        }
+        for (path, _) in &rewrites {
+            snapshot_before_edit(context, self.name(), path).await?;
+        }
+
+        if let Some(parent) = to.parent() {
+            if let Err(err) = fs::create_dir_all(parent).await {
+                return Ok(error_result(format!(
+                    "Failed to create parent directory {}: {}",
+                    parent.display(),
+                    err
+                )));
+            }
+        }
+        if replaces_existing {
+            if let Err(err) = fs::remove_file(&to).await {
+                return Ok(error_result(format!(
+                    "Failed to replace {}: {}",
+                    to.display(),
+                    err
+                )));
+            }
+        }
+        if let Err(err) = fs::rename(&from, &to).await {
+            return Ok(error_result(format!(
+                "Failed to move {} to {}: {}",
+                from.display(),
+                to.display(),
+                err
+            )));
+        }
+
+        // 先按原内容迁移索引（内容未变时复用向量），改写过导入的文件由本轮结束后的增量同步处理
+        let index_updated = update_index(&root, &moves).await;
+
+        let mut updated_references = Vec::new();
+        for (path, content) in rewrites {
+            match fs::write(&path, content).await {
+                Ok(()) => updated_references.push(path),
+                Err(err) => tracing::warn!(
+                    "move_file: failed to update references in {}: {}",
+                    path.display(),
+                    err
+                ),
+            }
+        }
+
+        let tracker = context.file_tracker();
+        for path in moves.iter().map(|(_, new)| new).chain(&updated_references) {
+            tracker
+                .track_file_operation(FileOperationRecord::new(
+                    path.as_path(),
+                    FileRecordSource::AgentEdited,
+                ))
+                .await?;
+        }
+
+        let mut text = format!(
+            "move_file applied\nfrom={}\nto={}\nfiles_moved={}",
+            from.display(),
+            to.display(),
+            moves.len()
+        );
+        if updated_references.is_empty() {
+            text.push_str("\nreferences_updated=none");
+        } else {
+            text.push_str("\nreferences_updated:");
+            for path in &updated_references {
+                text.push_str(&format!("\n- {}", path.display()));
+            }
+        }
+
+        Ok(ToolResult {
+            content: vec![ToolResultContent::Success(text)],
+            status: ToolResultStatus::Success,
+            cancel_reason: None,
+            execution_time_ms: None,
+            ext_info: Some(json!({
+                "from": from.display().to_string(),
+                "to": to.display().to_string(),
+                "filesMoved": moves.len(),
+                "updatedReferences": updated_references
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>(),
+                "indexUpdated": index_updated,
+            })),
+        })
+    }
+}
+
+/// 展开为逐文件的 (旧路径, 新路径)；目录递归收集其中的文件
+fn collect_moves(from: &Path, to: &Path, is_dir: bool) -> Vec<(PathBuf, PathBuf)> {
+    if !is_dir {
+        return vec![(from.to_path_buf(), to.to_path_buf())];
+    }
+    walkdir::WalkDir::new(from)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(from).ok()?;
+            Some((entry.path().to_path_buf(), to.join(relative)))
+        })
+        .collect()
+}
+
+fn is_module(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| MODULE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// 扫描工作区的 JS/TS 文件，返回 (移动后的路径, 新内容)
+async fn plan_reference_updates(
+    root: &Path,
+    mapping: &HashMap<PathBuf, PathBuf>,
+) -> Vec<(PathBuf, String)> {
+    let root = root.to_path_buf();
+    let mapping = mapping.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut builder = WalkBuilder::new(&root);
+        builder
+            .hidden(false)
+            .require_git(false)
+            .filter_entry(|e| e.file_name() != ".git" && e.file_name() != "node_modules");
+
+        let mut rewrites = Vec::new();
+        let files = builder
+            .build()
+            .flatten()
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .filter(|entry| is_module(entry.path()))
+            .take(MAX_REFERENCE_SCAN_FILES);
+        for entry in files {
+            let old_path = entry.path();
+            let Ok(content) = std::fs::read_to_string(old_path) else {
+                continue;
+            };
+            let new_path = mapping.get(old_path).cloned();
+            let (Some(old_dir), Some(new_dir)) = (
+                old_path.parent(),
+                new_path.as_deref().unwrap_or(old_path).parent(),
+            ) else {
+                continue;
+            };
+            if let Some(updated) = rewrite_imports(&content, old_dir, new_dir, &mapping) {
+                rewrites.push((new_path.unwrap_or_else(|| old_path.to_path_buf()), updated));
+            }
+        }
+        rewrites
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// 改写文件中的相对导入：目标被移动时指向新位置，文件自身被移动时按新目录重新计算。
+/// 没有变化时返回 None
+fn rewrite_imports(
+    content: &str,
+    old_dir: &Path,
+    new_dir: &Path,
+    mapping: &HashMap<PathBuf, PathBuf>,
+) -> Option<String> {
+    let importer_moved = old_dir != new_dir;
+    let mut output = String::with_capacity(content.len());
+    let mut last = 0;
+    let mut changed = false;
+
+    for caps in RELATIVE_IMPORT_RE.captures_iter(content) {
+        let spec = caps.get(1)?;
+        let target = normalize_path(&old_dir.join(spec.as_str()));
+        let new_target = match resolve_moved(&target, mapping) {
+            Some(moved) => moved,
+            None if importer_moved => target,
+            None => continue,
+        };
+        let new_spec = relative_specifier(new_dir, &new_target);
+        if new_spec == spec.as_str() {
+            continue;
+        }
+        output.push_str(&content[last..spec.start()]);
+        output.push_str(&new_spec);
+        last = spec.end();
+        changed = true;
+    }
+
+    if !changed {
+        return None;
+    }
+    output.push_str(&content[last..]);
+    Some(output)
+}
+
+/// 导入目标（可能省略扩展名或指向带 index 的目录）被移动时，返回保持同样写法的新目标
+fn resolve_moved(target: &Path, mapping: &HashMap<PathBuf, PathBuf>) -> Option<PathBuf> {
+    if let Some(new) = mapping.get(target) {
+        return Some(new.clone());
+    }
+    for ext in MODULE_EXTENSIONS {
+        let mut with_ext = target.as_os_str().to_owned();
+        with_ext.push(".");
+        with_ext.push(ext);
+        if let Some(new) = mapping.get(Path::new(&with_ext)) {
+            return Some(new.with_extension(""));
+        }
+        if let Some(new) = mapping.get(&target.join(format!("index.{}", ext))) {
+            let dir_import = new.file_stem().is_some_and(|stem| stem == "index");
+            return Some(match (dir_import, new.parent()) {
+                (true, Some(parent)) => parent.to_path_buf(),
+                _ => new.with_extension(""),
+            });
+        }
+    }
+    None
+}
+
+/// 从 dir 指向 target 的 `./` 或 `../` 开头、以 `/` 分隔的路径
+fn relative_specifier(dir: &Path, target: &Path) -> String {
+    let dir: Vec<Component> = dir.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = dir.iter().zip(&target).take_while(|(a, b)| a == b).count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); dir.len() - common];
+    parts.extend(
+        target[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned()),
+    );
+    let joined = parts.join("/");
+    if joined.starts_with("..") {
+        joined
+    } else if joined.is_empty() {
+        ".".to_string()
+    } else {
+        format!("./{}", joined)
+    }
+}
+
+/// 在索引中迁移被移动的文件，工作区未建索引时跳过；返回是否更新了索引
+async fn update_index(root: &Path, moves: &[(PathBuf, PathBuf)]) -> bool {
+    let Some(global) = crate::vector_db::commands::get_global_state() else {
+        return false;
+    };
+    let mut updated = false;
+    for (old, new) in moves {
+        match global
+            .search_engine
+            .handle_file_renamed(root, old, new)
+            .await
+        {
+            Ok(_) => updated = true,
+            Err(VectorDbError::IndexNotFound(_)) => return false,
+            Err(err) => tracing::warn!(
+                "move_file: failed to update index for {}: {}",
+                new.display(),
+                err
+            ),
+        }
+    }
+    updated
+}
+
+fn error_result(message: impl Into<String>) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Error(message.into())],
+        status: ToolResultStatus::Error,
+        cancel_reason: None,
+        execution_time_ms: None,
+        ext_info: None,
+    }
+}
+
+async fn snapshot_before_edit(
+    context: &TaskContext,
+    tool_name: &str,
+    path: &Path,
+) -> ToolExecutorResult<()> {
+    crate::vector_db::agent_writes().register(&context.task_id, path);
+    context
+        .snapshot_file_before_edit(path)
+        .await
+        .map_err(|err| ToolExecutorError::ExecutionFailed {
+            tool_name: tool_name.to_string(),
+            error: err.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_imports_of_moved_targets_and_moved_importers() {
+        let mapping: HashMap<PathBuf, PathBuf> = [
+            (
+                PathBuf::from("/ws/src/utils/format.ts"),
+                PathBuf::from("/ws/src/lib/format.ts"),
+            ),
+            (
+                PathBuf::from("/ws/src/widgets/index.ts"),
+                PathBuf::from("/ws/src/ui/widgets/index.ts"),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let app = "import { fmt } from './utils/format'\nimport w from \"./widgets\"\nimport x from './other'\n";
+        assert_eq!(
+            rewrite_imports(app, Path::new("/ws/src"), Path::new("/ws/src"), &mapping).as_deref(),
+            Some("import { fmt } from './lib/format'\nimport w from \"./ui/widgets\"\nimport x from './other'\n")
+        );
+
+        // 被移动的文件自身的相对导入按新目录重新计算
+        let moved = "const a = require('../app.js')\nexport * from './helpers'\n";
+        assert_eq!(
+            rewrite_imports(
+                moved,
+                Path::new("/ws/src/utils"),
+                Path::new("/ws/src/lib"),
+                &mapping
+            )
+            .as_deref(),
+            Some("const a = require('../app.js')\nexport * from '../utils/helpers'\n")
+        );
+
+        let untouched = "import x from './other'\nimport y from 'react'\n";
+        assert!(rewrite_imports(
+            untouched,
+            Path::new("/ws/src"),
+            Path::new("/ws/src"),
+            &mapping
+        )
+        .is_none());
+    }
+}
@@ -14,7 +14,7 @@ use serde::Serialize;
 use serde_json::json;
 use tokio::fs;
 
-use super::file_utils::{ensure_absolute, normalize_path};
+use super::file_utils::{confine_to_root, ensure_absolute, normalize_path, task_filesystem_root};
 use crate::agent::context::FileOperationRecord;
 use crate::agent::core::context::TaskContext;
 use crate::agent::error::ToolExecutorResult;
@@ -169,6 +169,13 @@ Usage:
             Ok(path) => path,
             Err(result) => return Ok(result),
         };
+        let search_path = match task_filesystem_root(context) {
+            Some(root) => match confine_to_root(&search_path, &root) {
+                Ok(path) => path,
+                Err(err) => return Ok(validation_error(err.to_string())),
+            },
+            None => search_path,
+        };
 
         if !search_path.exists() {
             return Ok(tool_error(format!(
@@ -188,8 +195,12 @@ Usage:
         let search_options = crate::vector_db::search::SearchOptions {
             top_k: max_results,
             threshold: 0.3,
+            threshold_is_percentile: false,
             include_snippet: true,
             filter_languages: vec![],
+            symbol_filter: None,
+            relative_paths: false,
+            chunk_type_weights: Default::default(),
         };
 
         let results = match global
@@ -204,6 +215,17 @@ Usage:
                     query
                 )))
             }
+            Err(crate::vector_db::core::VectorDbError::IndexNotFound(_)) => {
+                return Ok(tool_error(
+                    "This directory has not been indexed yet. Build the code index first, or use regex mode.",
+                ))
+            }
+            Err(e @ crate::vector_db::core::VectorDbError::EmbeddingModelMismatch { .. }) => {
+                return Ok(tool_error(format!(
+                    "{}. Semantic search is unavailable until the index is rebuilt or the original embedding model is selected again; use regex mode instead.",
+                    e
+                )))
+            }
             Err(e) => return Ok(tool_error(format!("Search failed: {}", e))),
         };
 
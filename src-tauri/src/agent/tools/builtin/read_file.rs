@@ -17,7 +17,7 @@ use crate::agent::tools::{
 };
 use crate::vector_db::core::Language;
 
-use super::file_utils::{ensure_absolute, is_probably_binary};
+use super::file_utils::{is_probably_binary, resolve_task_path};
 
 const DEFAULT_MAX_LINES: usize = 2000;
 const MAX_LINE_LENGTH: usize = 2000;
@@ -669,7 +669,7 @@ Usage:
         args: serde_json::Value,
     ) -> ToolExecutorResult<ToolResult> {
         let args: ReadFileArgs = serde_json::from_value(args)?;
-        let path = match ensure_absolute(&args.path, &context.cwd) {
+        let path = match resolve_task_path(&args.path, context) {
             Ok(resolved) => resolved,
             Err(err) => return Ok(validation_error(err.to_string())),
         };
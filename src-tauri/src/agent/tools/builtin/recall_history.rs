@@ -0,0 +1,165 @@
+use std::time::Instant;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::agent::context::history_recall::{self, HistoryMatch};
+use crate::agent::core::context::TaskContext;
+use crate::agent::error::ToolExecutorResult;
+use crate::agent::tools::{
+    RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority, ToolResult,
+    ToolResultContent, ToolResultStatus,
+};
+
+const DEFAULT_MAX_RESULTS: usize = 5;
+const MAX_RESULTS_LIMIT: usize = 20;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecallHistoryArgs {
+    query: String,
+    max_results: Option<usize>,
+}
+
+pub struct RecallHistoryTool;
+
+impl RecallHistoryTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl RunnableTool for RecallHistoryTool {
+    fn name(&self) -> &str {
+        "recall_history"
+    }
+
+    fn description(&self) -> &str {
+        "Semantically searches earlier conversations in the current workspace and returns relevant message snippets.
+
+Usage:
+- Use this to recall decisions, explanations or context from previous conversations that are not in the current context
+- Only conversations from the current workspace are searched; messages of the current task are excluded
+- Each result includes the conversation (session) id, the message role, a timestamp and a relevance score
+- Only available when history recall is enabled in the settings"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Natural language description of what to recall (e.g., 'why we switched the database driver')."
+                },
+                "maxResults": {
+                    "type": "number",
+                    "minimum": 1,
+                    "maximum": 20,
+                    "description": "Maximum number of snippets to return (default: 5, max: 20)."
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::new(ToolCategory::CodeAnalysis, ToolPriority::Expensive)
+            .with_tags(vec!["search".into(), "history".into()])
+    }
+
+    fn required_permissions(&self) -> Vec<ToolPermission> {
+        vec![ToolPermission::ReadOnly]
+    }
+
+    async fn run(
+        &self,
+        context: &TaskContext,
+        args: serde_json::Value,
+    ) -> ToolExecutorResult<ToolResult> {
+        let args: RecallHistoryArgs = serde_json::from_value(args)?;
+        let query = args.query.trim();
+        if query.is_empty() {
+            return Ok(tool_error("Query cannot be empty"));
+        }
+        let max_results = args
+            .max_results
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_RESULTS_LIMIT);
+
+        let persistence = context.agent_persistence();
+        if !history_recall::recall_enabled(persistence.database()).await {
+            return Ok(tool_error(
+                "History recall is disabled. The user can enable it in the agent settings.",
+            ));
+        }
+
+        let started = Instant::now();
+        let matches = match history_recall::recall(
+            &persistence,
+            &context.cwd,
+            &context.task_id,
+            query,
+            max_results,
+        )
+        .await
+        {
+            Ok(matches) => matches,
+            Err(e) => return Ok(tool_error(format!("History recall failed: {}", e))),
+        };
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+        let text = if matches.is_empty() {
+            format!("No earlier conversation found matching \"{}\".", query)
+        } else {
+            format!(
+                "Found {} snippet{} from earlier conversations matching \"{}\":\n\n{}",
+                matches.len(),
+                if matches.len() == 1 { "" } else { "s" },
+                query,
+                format_matches(&matches)
+            )
+        };
+
+        Ok(ToolResult {
+            content: vec![ToolResultContent::Success(text)],
+            status: ToolResultStatus::Success,
+            cancel_reason: None,
+            execution_time_ms: Some(elapsed_ms),
+            ext_info: Some(json!({
+                "results": matches,
+                "totalFound": matches.len(),
+                "query": query,
+            })),
+        })
+    }
+}
+
+fn format_matches(matches: &[HistoryMatch]) -> String {
+    matches
+        .iter()
+        .map(|m| {
+            format!(
+                "[conversation {} | {} | {} | score {:.2}]\n{}",
+                m.session_id,
+                m.role,
+                m.created_at.format("%Y-%m-%d %H:%M"),
+                m.score,
+                m.snippet
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn tool_error(message: impl Into<String>) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Error(message.into())],
+        status: ToolResultStatus::Error,
+        cancel_reason: None,
+        execution_time_ms: None,
+        ext_info: None,
+    }
+}
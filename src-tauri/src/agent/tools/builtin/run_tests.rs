@@ -0,0 +1,766 @@
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::agent::core::context::TaskContext;
+use crate::agent::error::ToolExecutorResult;
+use crate::agent::shell::CommandStatus;
+use crate::agent::tools::builtin::shell::{error_result, get_executor};
+use crate::agent::tools::{
+    RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority, ToolResult,
+    ToolResultContent, ToolResultStatus,
+};
+use crate::utils::ansi::strip_ansi;
+
+/// 默认超时时间（毫秒），测试通常比普通命令耗时更长
+const DEFAULT_TIMEOUT_MS: u64 = 600_000;
+/// 保留的测试输出上限，超出时保留尾部（汇总信息在末尾）
+const MAX_CAPTURED_BYTES: usize = 512 * 1024;
+/// 摘要中展示的失败用例数
+const MAX_REPORTED_FAILURES: usize = 5;
+/// 每个失败用例摘录的行数
+const MAX_EXCERPT_LINES: usize = 15;
+/// 为后续查看保留的单个失败用例输出上限
+const MAX_FAILURE_OUTPUT_BYTES: usize = 16 * 1024;
+/// 无法解析出失败用例时附带的输出尾部行数
+const FALLBACK_TAIL_LINES: usize = 60;
+/// 保留最近一次运行结果的任务数
+const LAST_RUN_CACHE_CAPACITY: usize = 32;
+
+/// 每个任务最近一次运行的失败用例，供 showFailure 查看完整输出
+static LAST_RUNS: Lazy<Mutex<LruCache<String, Vec<TestFailure>>>> = Lazy::new(|| {
+    Mutex::new(LruCache::new(
+        NonZeroUsize::new(LAST_RUN_CACHE_CAPACITY).unwrap(),
+    ))
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TestRunner {
+    Cargo,
+    Go,
+    Npm,
+    Pytest,
+}
+
+impl TestRunner {
+    /// 按工作目录中的项目文件识别测试框架
+    fn detect(cwd: &Path) -> Option<Self> {
+        if cwd.join("Cargo.toml").is_file() {
+            return Some(Self::Cargo);
+        }
+        if cwd.join("go.mod").is_file() {
+            return Some(Self::Go);
+        }
+        if has_npm_test_script(cwd) {
+            return Some(Self::Npm);
+        }
+        let pytest_markers = [
+            "pytest.ini",
+            "conftest.py",
+            "pyproject.toml",
+            "setup.cfg",
+            "tox.ini",
+        ];
+        if pytest_markers.iter().any(|m| cwd.join(m).is_file()) {
+            return Some(Self::Pytest);
+        }
+        None
+    }
+
+    fn command(&self, cwd: &Path, filter: Option<&str>) -> String {
+        let filter = filter.map(str::trim).filter(|f| !f.is_empty());
+        match self {
+            Self::Cargo => match filter {
+                Some(f) => format!("cargo test --color never {}", quote_arg(f)),
+                None => "cargo test --color never".to_string(),
+            },
+            Self::Go => match filter {
+                Some(f) => format!("go test -v ./... -run {}", quote_arg(f)),
+                None => "go test -v ./...".to_string(),
+            },
+            Self::Npm => {
+                let manager = node_package_manager(cwd);
+                match filter {
+                    Some(f) => format!("{} test -- {}", manager, quote_arg(f)),
+                    None => format!("{} test", manager),
+                }
+            }
+            Self::Pytest => match filter {
+                // 路径或 node id 直接作为参数，其余按 -k 表达式过滤
+                Some(f) if f.contains("::") || f.contains('/') || f.ends_with(".py") => {
+                    format!("python -m pytest -rf --color=no {}", quote_arg(f))
+                }
+                Some(f) => format!("python -m pytest -rf --color=no -k {}", quote_arg(f)),
+                None => "python -m pytest -rf --color=no".to_string(),
+            },
+        }
+    }
+
+    fn parse(&self, output: &str) -> TestReport {
+        match self {
+            Self::Cargo => parse_cargo(output),
+            Self::Go => parse_go(output),
+            Self::Npm => parse_npm(output),
+            Self::Pytest => parse_pytest(output),
+        }
+    }
+}
+
+fn has_npm_test_script(cwd: &Path) -> bool {
+    std::fs::read_to_string(cwd.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|pkg| {
+            pkg.get("scripts")?
+                .get("test")?
+                .as_str()
+                .map(str::to_string)
+        })
+        .is_some_and(|script| !script.contains("no test specified"))
+}
+
+fn node_package_manager(cwd: &Path) -> &'static str {
+    if cwd.join("pnpm-lock.yaml").is_file() {
+        "pnpm"
+    } else if cwd.join("yarn.lock").is_file() {
+        "yarn"
+    } else if cwd.join("bun.lockb").is_file() {
+        "bun"
+    } else {
+        "npm"
+    }
+}
+
+pub(crate) fn quote_arg(arg: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!("\"{}\"", arg.replace('"', "\\\""))
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TestFailure {
+    name: String,
+    output: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TestReport {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    failures: Vec<TestFailure>,
+}
+
+impl TestReport {
+    fn push_failure(&mut self, name: &str, lines: &[&str]) {
+        let name = name.trim();
+        if name.is_empty() || self.failures.iter().any(|f| f.name == name) {
+            return;
+        }
+        let output = truncate_tail(lines.join("\n").trim(), MAX_FAILURE_OUTPUT_BYTES, false);
+        self.failures.push(TestFailure {
+            name: name.to_string(),
+            output,
+        });
+    }
+}
+
+static COUNT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d+) (passed|passing|failed|failing|ignored|skipped|errors?|pending)").unwrap()
+});
+
+fn add_counts(report: &mut TestReport, line: &str) {
+    for cap in COUNT_RE.captures_iter(line) {
+        let n: usize = cap[1].parse().unwrap_or(0);
+        match &cap[2] {
+            "passed" | "passing" => report.passed += n,
+            "failed" | "failing" | "error" | "errors" => report.failed += n,
+            _ => report.skipped += n,
+        }
+    }
+}
+
+fn parse_cargo(output: &str) -> TestReport {
+    let mut report = TestReport::default();
+    let mut current: Option<(&str, Vec<&str>)> = None;
+
+    for line in output.lines() {
+        if line.starts_with("test result:") {
+            add_counts(&mut report, line);
+        }
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        {
+            if let Some((prev, lines)) = current.take() {
+                report.push_failure(prev, &lines);
+            }
+            current = Some((name, Vec::new()));
+            continue;
+        }
+        if let Some((name, lines)) = current.as_mut() {
+            // 失败详情在 "failures:" 名单或 test result 行前结束
+            if line == "failures:" || line.starts_with("test result:") {
+                report.push_failure(name, lines);
+                current = None;
+            } else {
+                lines.push(line);
+            }
+        }
+    }
+    if let Some((name, lines)) = current {
+        report.push_failure(name, &lines);
+    }
+
+    // 没有 stdout 段落的失败（如超时）仍记录名称
+    for line in output.lines() {
+        if let Some(name) = line
+            .strip_prefix("test ")
+            .and_then(|rest| rest.strip_suffix(" ... FAILED"))
+        {
+            report.push_failure(name, &[]);
+        }
+    }
+    report
+}
+
+fn parse_go(output: &str) -> TestReport {
+    let mut report = TestReport::default();
+    let mut running: Vec<(String, Vec<&str>)> = Vec::new();
+
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        if let Some(name) = trimmed.strip_prefix("=== RUN") {
+            running.push((name.trim().to_string(), Vec::new()));
+        } else if let Some(rest) = trimmed.strip_prefix("--- FAIL: ") {
+            report.failed += 1;
+            let name = rest.split(" (").next().unwrap_or(rest);
+            // go test -v 在 --- FAIL 之前输出该用例的日志
+            let lines = running
+                .iter()
+                .rposition(|(n, _)| n == name)
+                .map(|i| running.remove(i).1)
+                .unwrap_or_default();
+            report.push_failure(name, &lines);
+        } else if trimmed.starts_with("--- PASS: ") {
+            report.passed += 1;
+        } else if trimmed.starts_with("--- SKIP: ") {
+            report.skipped += 1;
+        } else if let Some((_, lines)) = running.last_mut() {
+            lines.push(line);
+        }
+    }
+    report
+}
+
+static PYTEST_HEADER_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^_{3,} (.+?) _{3,}$").unwrap());
+
+fn parse_pytest(output: &str) -> TestReport {
+    let mut report = TestReport::default();
+    let mut current: Option<(&str, Vec<&str>)> = None;
+
+    for line in output.lines() {
+        if let Some(cap) = PYTEST_HEADER_RE.captures(line) {
+            if let Some((name, lines)) = current.take() {
+                report.push_failure(name, &lines);
+            }
+            current = Some((cap.get(1).map_or("", |m| m.as_str()), Vec::new()));
+            continue;
+        }
+        if line.starts_with("===") {
+            if let Some((name, lines)) = current.take() {
+                report.push_failure(name, &lines);
+            }
+            if line.contains(" in ") {
+                add_counts(&mut report, line);
+            }
+            continue;
+        }
+        if let Some((_, lines)) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+    if let Some((name, lines)) = current {
+        report.push_failure(name, &lines);
+    }
+    report
+}
+
+fn parse_npm(output: &str) -> TestReport {
+    let mut report = TestReport::default();
+    let mut current: Option<(&str, Vec<&str>)> = None;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        // jest: "Tests: 1 failed, 4 passed"；vitest: "Tests  1 failed | 4 passed"；mocha: "4 passing"
+        if trimmed.starts_with("Tests:")
+            || trimmed.starts_with("Tests ")
+            || trimmed.ends_with(" passing")
+            || trimmed.ends_with(" failing")
+            || trimmed.ends_with(" pending")
+        {
+            add_counts(&mut report, trimmed);
+        }
+
+        let header = trimmed
+            .strip_prefix("● ")
+            .filter(|rest| !rest.starts_with("Test suite failed"))
+            .or_else(|| {
+                trimmed
+                    .strip_prefix("FAIL ")
+                    .filter(|rest| rest.contains(" > "))
+            });
+        if let Some(name) = header {
+            if let Some((prev, lines)) = current.take() {
+                report.push_failure(prev, &lines);
+            }
+            current = Some((name, Vec::new()));
+            continue;
+        }
+
+        if trimmed.starts_with("Test Suites:")
+            || trimmed.starts_with("Test Files")
+            || (!trimmed.is_empty() && trimmed.chars().all(|c| c == '⎯'))
+        {
+            if let Some((name, lines)) = current.take() {
+                report.push_failure(name, &lines);
+            }
+            continue;
+        }
+        if let Some((_, lines)) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+    if let Some((name, lines)) = current {
+        report.push_failure(name, &lines);
+    }
+    report
+}
+
+/// 截断到 max_bytes；keep_tail 为 true 时保留尾部
+fn truncate_tail(text: &str, max_bytes: usize, keep_tail: bool) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    if keep_tail {
+        let mut start = text.len() - max_bytes;
+        while !text.is_char_boundary(start) {
+            start += 1;
+        }
+        text[start..].to_string()
+    } else {
+        let mut end = max_bytes;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}\n... (truncated)", &text[..end])
+    }
+}
+
+fn excerpt(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().filter(|l| !l.trim().is_empty()).collect();
+    let mut excerpt = lines
+        .iter()
+        .take(MAX_EXCERPT_LINES)
+        .map(|l| format!("   {}", l.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if lines.len() > MAX_EXCERPT_LINES {
+        excerpt.push_str(&format!(
+            "\n   ... ({} more lines)",
+            lines.len() - MAX_EXCERPT_LINES
+        ));
+    }
+    excerpt
+}
+
+fn format_summary(
+    command: &str,
+    exit_code: Option<i32>,
+    duration_ms: u64,
+    report: &TestReport,
+    output: &str,
+) -> String {
+    let mut text = format!(
+        "{}: {} passed, {} failed, {} skipped (exit code {}, {:.1}s)\n",
+        command,
+        report.passed,
+        report.failed,
+        report.skipped,
+        exit_code.map_or("unknown".to_string(), |c| c.to_string()),
+        duration_ms as f64 / 1000.0
+    );
+
+    if !report.failures.is_empty() {
+        let shown = report.failures.len().min(MAX_REPORTED_FAILURES);
+        text.push_str(&format!(
+            "\nFailing tests (showing {} of {}):\n",
+            shown,
+            report.failures.len()
+        ));
+        for (index, failure) in report.failures.iter().take(shown).enumerate() {
+            text.push_str(&format!("{}. {}\n", index + 1, failure.name));
+            if !failure.output.is_empty() {
+                text.push_str(&excerpt(&failure.output));
+                text.push('\n');
+            }
+        }
+        text.push_str(
+            "\nCall run_tests with showFailure=\"<test name>\" to see the full output of a failing test.",
+        );
+    } else if exit_code != Some(0) {
+        // 编译错误等情况下没有可解析的用例，附带输出尾部
+        let lines: Vec<&str> = output.lines().collect();
+        let tail = &lines[lines.len().saturating_sub(FALLBACK_TAIL_LINES)..];
+        text.push_str("\nNo failing test could be identified. Last lines of output:\n```\n");
+        text.push_str(&tail.join("\n"));
+        text.push_str("\n```");
+    }
+    text
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RunTestsArgs {
+    /// 只运行匹配的测试（可选）
+    filter: Option<String>,
+    /// 显式指定测试框架（可选）
+    runner: Option<TestRunner>,
+    /// 工作目录（可选）
+    cwd: Option<String>,
+    /// 超时时间毫秒（可选）
+    timeout_ms: Option<u64>,
+    /// 查看上次运行中某个失败用例的完整输出，不重新运行
+    show_failure: Option<String>,
+}
+
+pub struct RunTestsTool;
+
+impl RunTestsTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn show_failure(&self, context: &TaskContext, name: &str) -> ToolResult {
+        let failures = LAST_RUNS.lock().get(context.task_id.as_ref()).cloned();
+        let Some(failures) = failures else {
+            return error_tool_result("No previous test run in this task. Run the tests first.");
+        };
+
+        let found = failures
+            .iter()
+            .find(|f| f.name == name)
+            .or_else(|| failures.iter().find(|f| f.name.contains(name)));
+        match found {
+            Some(failure) => ToolResult {
+                content: vec![ToolResultContent::Success(format!(
+                    "{}\n\n{}",
+                    failure.name,
+                    if failure.output.is_empty() {
+                        "(no output captured for this test)"
+                    } else {
+                        failure.output.as_str()
+                    }
+                ))],
+                status: ToolResultStatus::Success,
+                cancel_reason: None,
+                execution_time_ms: None,
+                ext_info: Some(json!({ "test": failure.name })),
+            },
+            None => error_tool_result(&format!(
+                "No failing test named '{}' in the last run. Failing tests: {}",
+                name,
+                failures
+                    .iter()
+                    .map(|f| f.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+}
+
+fn error_tool_result(message: &str) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Error(message.to_string())],
+        status: ToolResultStatus::Error,
+        cancel_reason: None,
+        execution_time_ms: None,
+        ext_info: None,
+    }
+}
+
+#[async_trait]
+impl RunnableTool for RunTestsTool {
+    fn name(&self) -> &str {
+        "run_tests"
+    }
+
+    fn description(&self) -> &str {
+        "Runs the project's test suite and returns a structured summary instead of the full log.
+
+Usage:
+- Detects the test runner from the workspace: cargo test, go test, npm/pnpm/yarn test, or pytest
+- filter: Only run tests matching this name or path (optional)
+- runner: Force a runner: cargo, go, npm or pytest (optional)
+- showFailure: Return the full output of one failing test from the previous run without re-running
+
+Notes:
+- The summary contains passed/failed counts and the first failing tests with short error excerpts
+- Use showFailure with a test name from the summary when the excerpt is not enough
+- Prefer this over the shell tool when running tests"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "filter": {
+                    "type": "string",
+                    "description": "Only run tests whose name (or path, for pytest) matches this value."
+                },
+                "runner": {
+                    "type": "string",
+                    "enum": ["cargo", "go", "npm", "pytest"],
+                    "description": "Test runner to use. Detected from project files when omitted."
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Directory to run the tests in. Defaults to current workspace."
+                },
+                "timeoutMs": {
+                    "type": "integer",
+                    "minimum": 1000,
+                    "maximum": 600000,
+                    "description": "Timeout in milliseconds (default: 600000)."
+                },
+                "showFailure": {
+                    "type": "string",
+                    "description": "Name of a failing test from the previous run whose full output should be returned. Tests are not re-run."
+                }
+            }
+        })
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::new(ToolCategory::Execution, ToolPriority::Standard)
+            .with_confirmation()
+            .with_timeout(Duration::from_millis(DEFAULT_TIMEOUT_MS))
+            .with_tags(vec!["test".into(), "command".into()])
+            .with_summary_key_arg("filter")
+    }
+
+    fn required_permissions(&self) -> Vec<ToolPermission> {
+        vec![ToolPermission::SystemCommand]
+    }
+
+    async fn run(
+        &self,
+        context: &TaskContext,
+        args: serde_json::Value,
+    ) -> ToolExecutorResult<ToolResult> {
+        let args: RunTestsArgs = serde_json::from_value(args)?;
+        if let Some(name) = args
+            .show_failure
+            .as_deref()
+            .filter(|n| !n.trim().is_empty())
+        {
+            return Ok(self.show_failure(context, name.trim()));
+        }
+
+        let cwd = args.cwd.as_deref().unwrap_or(&context.cwd);
+        let Some(runner) = args.runner.or_else(|| TestRunner::detect(Path::new(cwd))) else {
+            return Ok(error_tool_result(&format!(
+                "Could not detect a test runner in {}. Pass runner explicitly or use the shell tool.",
+                cwd
+            )));
+        };
+        let command = runner.command(Path::new(cwd), args.filter.as_deref());
+        let timeout_duration = Duration::from_millis(args.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+
+        // 任务取消或单独取消该工具时，执行 future 被丢弃，子进程随之终止
+        let result = match get_executor()
+            .execute(&command, cwd, Some(timeout_duration))
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => return Ok(error_result(&command, cwd, &e)),
+        };
+
+        let stripped = strip_ansi(&result.output);
+        let output_truncated = stripped.len() > MAX_CAPTURED_BYTES;
+        let output = truncate_tail(&stripped, MAX_CAPTURED_BYTES, true);
+        let report = runner.parse(&output);
+
+        let exit_code = match &result.status {
+            CommandStatus::Completed { exit_code, .. } => Some(*exit_code),
+            _ => None,
+        };
+        let is_success = exit_code == Some(0);
+        let summary = format_summary(&command, exit_code, result.duration_ms, &report, &output);
+
+        let failure_names: Vec<&str> = report.failures.iter().map(|f| f.name.as_str()).collect();
+        let ext_info = json!({
+            "command": command,
+            "cwd": cwd,
+            "runner": runner,
+            "exitCode": exit_code,
+            "durationMs": result.duration_ms,
+            "passed": report.passed,
+            "failed": report.failed,
+            "skipped": report.skipped,
+            "failures": failure_names,
+            "outputTruncated": output_truncated || result.output_truncated,
+        });
+
+        LAST_RUNS
+            .lock()
+            .put(context.task_id.to_string(), report.failures);
+
+        Ok(ToolResult {
+            content: vec![if is_success {
+                ToolResultContent::Success(summary)
+            } else {
+                ToolResultContent::Error(summary)
+            }],
+            status: if is_success {
+                ToolResultStatus::Success
+            } else {
+                ToolResultStatus::Error
+            },
+            cancel_reason: None,
+            execution_time_ms: Some(result.duration_ms),
+            ext_info: Some(ext_info),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cargo_failures_and_counts() {
+        let output = "\
+running 3 tests
+test tests::ok ... ok
+test tests::broken ... FAILED
+test tests::slow ... FAILED
+
+failures:
+
+---- tests::broken stdout ----
+thread 'tests::broken' panicked at src/lib.rs:10:5:
+assertion `left == right` failed
+  left: 1
+ right: 2
+
+
+failures:
+    tests::broken
+    tests::slow
+
+test result: FAILED. 1 passed; 2 failed; 1 ignored; 0 measured; 0 filtered out
+";
+        let report = parse_cargo(output);
+        assert_eq!((report.passed, report.failed, report.skipped), (1, 2, 1));
+        assert_eq!(report.failures.len(), 2);
+        assert_eq!(report.failures[0].name, "tests::broken");
+        assert!(report.failures[0].output.contains("right: 2"));
+        assert!(!report.failures[0].output.contains("tests::slow"));
+        assert_eq!(report.failures[1].name, "tests::slow");
+    }
+
+    #[test]
+    fn parses_go_and_pytest_failures() {
+        let go = "\
+=== RUN   TestAdd
+--- PASS: TestAdd (0.00s)
+=== RUN   TestSub
+    math_test.go:14: expected 1, got 2
+--- FAIL: TestSub (0.00s)
+FAIL
+";
+        let report = parse_go(go);
+        assert_eq!((report.passed, report.failed), (1, 1));
+        assert_eq!(report.failures[0].name, "TestSub");
+        assert!(report.failures[0].output.contains("expected 1, got 2"));
+
+        let pytest = "\
+=================================== FAILURES ===================================
+__________________________________ test_sub ____________________________________
+
+    def test_sub():
+>       assert 1 - 1 == 1
+E       assert 0 == 1
+
+test_math.py:5: AssertionError
+=========================== short test summary info ============================
+FAILED test_math.py::test_sub - assert 0 == 1
+========================= 1 failed, 3 passed in 0.05s ==========================
+";
+        let report = parse_pytest(pytest);
+        assert_eq!((report.passed, report.failed), (3, 1));
+        assert_eq!(report.failures[0].name, "test_sub");
+        assert!(report.failures[0].output.contains("assert 0 == 1"));
+    }
+
+    #[test]
+    fn parses_jest_failures() {
+        let jest = "\
+FAIL src/math.test.ts
+  ● math › subtracts
+
+    expect(received).toBe(expected)
+
+    Expected: 1
+    Received: 0
+
+Test Suites: 1 failed, 1 total
+Tests:       1 failed, 2 passed, 3 total
+";
+        let report = parse_npm(jest);
+        assert_eq!((report.passed, report.failed), (2, 1));
+        assert_eq!(report.failures[0].name, "math › subtracts");
+        assert!(report.failures[0].output.contains("Received: 0"));
+    }
+
+    #[test]
+    fn detects_runner_from_project_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(TestRunner::detect(dir.path()), None);
+
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts":{"test":"echo \"Error: no test specified\" && exit 1"}}"#,
+        )
+        .unwrap();
+        assert_eq!(TestRunner::detect(dir.path()), None);
+
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts":{"test":"vitest"}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("pnpm-lock.yaml"), "").unwrap();
+        assert_eq!(TestRunner::detect(dir.path()), Some(TestRunner::Npm));
+        assert_eq!(
+            TestRunner::Npm.command(dir.path(), Some("math")),
+            format!("pnpm test -- {}", quote_arg("math"))
+        );
+
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]").unwrap();
+        assert_eq!(TestRunner::detect(dir.path()), Some(TestRunner::Cargo));
+    }
+}
@@ -14,12 +14,12 @@ use crate::agent::tools::{
 };
 
 /// 默认超时时间（毫秒）
-const DEFAULT_TIMEOUT_MS: u64 = 120_000;
+pub(crate) const DEFAULT_TIMEOUT_MS: u64 = 120_000;
 
 /// 全局 Shell 执行器
 static SHELL_EXECUTOR: OnceLock<AgentShellExecutor> = OnceLock::new();
 
-fn get_executor() -> &'static AgentShellExecutor {
+pub(crate) fn get_executor() -> &'static AgentShellExecutor {
     SHELL_EXECUTOR.get_or_init(AgentShellExecutor::new)
 }
 
@@ -233,7 +233,7 @@ Notes:
     }
 }
 
-fn error_result(command: &str, cwd: &str, error: &ShellError) -> ToolResult {
+pub(crate) fn error_result(command: &str, cwd: &str, error: &ShellError) -> ToolResult {
     let (message, status, tool_status, cancel_reason) = match error {
         ShellError::Timeout(ms) => (
             format!("Command timed out after {}ms", ms),
@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+use std::process::Command;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::OnceCell;
+
+use crate::agent::core::context::TaskContext;
+use crate::agent::error::{ToolExecutorError, ToolExecutorResult};
+use crate::agent::tools::{
+    RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority, ToolResult,
+    ToolResultContent, ToolResultStatus,
+};
+use crate::mux::singleton::get_mux;
+use crate::mux::{ShellInfo, ShellManager};
+
+/// 允许返回的环境变量，其余一律不返回，避免把凭据泄露给模型
+const ENV_ALLOWLIST: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "USERNAME",
+    "USERPROFILE",
+    "SHELL",
+    "TERM",
+    "LANG",
+    "LC_ALL",
+    "TMPDIR",
+    "TEMP",
+    "EDITOR",
+    "VIRTUAL_ENV",
+    "CONDA_DEFAULT_ENV",
+    "NODE_ENV",
+    "NVM_DIR",
+    "JAVA_HOME",
+    "GOPATH",
+    "CARGO_HOME",
+    "RUSTUP_HOME",
+    "PROCESSOR_ARCHITECTURE",
+];
+/// 单个环境变量值的最大字符数
+const MAX_ENV_VALUE_CHARS: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Toolchain {
+    name: &'static str,
+    /// 未安装或探测失败时为 None
+    version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SystemInfo {
+    os: &'static str,
+    arch: &'static str,
+    os_version: String,
+    shell: String,
+    shell_path: String,
+    workspace_root: String,
+    toolchains: Vec<Toolchain>,
+    env: BTreeMap<String, String>,
+}
+
+impl SystemInfo {
+    fn collect(workspace_root: &str, shell: ShellInfo) -> Self {
+        let node = crate::node::detector::get_current_version(Some(workspace_root))
+            .ok()
+            .flatten();
+
+        Self {
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            os_version: crate::window::commands::platform::detect_os_version(),
+            shell: shell.name,
+            shell_path: shell.path,
+            workspace_root: workspace_root.to_string(),
+            toolchains: vec![
+                Toolchain {
+                    name: "node",
+                    version: node,
+                },
+                Toolchain {
+                    name: "python",
+                    version: probe_version("python3").or_else(|| probe_version("python")),
+                },
+                Toolchain {
+                    name: "cargo",
+                    version: probe_version("cargo"),
+                },
+            ],
+            env: safe_env_vars(std::env::vars()),
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = format!(
+            "os: {} {} ({})\nshell: {} ({})\nworkspace: {}\n",
+            self.os, self.arch, self.os_version, self.shell, self.shell_path, self.workspace_root
+        );
+        for toolchain in &self.toolchains {
+            out.push_str(&format!(
+                "{}: {}\n",
+                toolchain.name,
+                toolchain.version.as_deref().unwrap_or("not found")
+            ));
+        }
+        out.push_str("env:\n");
+        for (name, value) in &self.env {
+            out.push_str(&format!("  {}={}\n", name, value));
+        }
+        out.trim_end().to_string()
+    }
+}
+
+/// 执行 `<program> --version`，取第一行输出
+fn probe_version(program: &str) -> Option<String> {
+    let output = Command::new(program).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // 旧版 python 把版本写到 stderr
+    let text = if output.stdout.is_empty() {
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    } else {
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    text.lines()
+        .next()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+fn safe_env_vars(vars: impl Iterator<Item = (String, String)>) -> BTreeMap<String, String> {
+    vars.filter(|(name, _)| ENV_ALLOWLIST.contains(&name.as_str()))
+        .map(|(name, value)| {
+            let value = match value.char_indices().nth(MAX_ENV_VALUE_CHARS) {
+                Some((idx, _)) => format!("{}…", &value[..idx]),
+                None => value,
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+/// 优先取活跃终端的 shell，没有活跃终端时回退到默认 shell
+fn active_shell() -> ShellInfo {
+    crate::terminal::context_registry::global_registry()
+        .and_then(|registry| registry.terminal_context_get_active_pane())
+        .and_then(|pane_id| get_mux().get_pane(pane_id))
+        .map(|pane| pane.shell_info().clone())
+        .unwrap_or_else(ShellManager::terminal_get_default_shell)
+}
+
+/// 注册表按任务创建，快照在任务内只采集一次
+pub struct SystemInfoTool {
+    snapshot: OnceCell<SystemInfo>,
+}
+
+impl SystemInfoTool {
+    pub fn new() -> Self {
+        Self {
+            snapshot: OnceCell::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl RunnableTool for SystemInfoTool {
+    fn name(&self) -> &str {
+        "system_info"
+    }
+
+    fn description(&self) -> &str {
+        "Returns a snapshot of the user's environment: OS and architecture, the shell of the active terminal, workspace root, installed toolchain versions (node, python, cargo) and a small set of environment variables.
+
+Usage:
+- Call this instead of running probe commands like `uname`, `node -v` or `echo $SHELL`
+- Only a fixed allowlist of environment variables (PATH, HOME, SHELL, LANG...) is included
+- The snapshot is collected once per task"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::new(ToolCategory::FileSystem, ToolPriority::Standard)
+            .with_tags(vec!["system".into(), "environment".into()])
+    }
+
+    fn required_permissions(&self) -> Vec<ToolPermission> {
+        vec![ToolPermission::ReadOnly]
+    }
+
+    async fn run(
+        &self,
+        context: &TaskContext,
+        _args: serde_json::Value,
+    ) -> ToolExecutorResult<ToolResult> {
+        let workspace_root = context.cwd.to_string();
+        let info = self
+            .snapshot
+            .get_or_try_init(|| async move {
+                let shell = active_shell();
+                tokio::task::spawn_blocking(move || SystemInfo::collect(&workspace_root, shell))
+                    .await
+                    .map_err(|e| ToolExecutorError::ExecutionFailed {
+                        tool_name: "system_info".to_string(),
+                        error: e.to_string(),
+                    })
+            })
+            .await?;
+
+        Ok(ToolResult {
+            content: vec![ToolResultContent::Success(info.render())],
+            status: ToolResultStatus::Success,
+            cancel_reason: None,
+            execution_time_ms: None,
+            ext_info: serde_json::to_value(info).ok(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_allowlisted_env_vars_are_kept() {
+        let vars = [
+            ("PATH", "/usr/bin"),
+            ("HOME", "/home/me"),
+            ("PWD", "/home/me/project"),
+            ("OPENAI_API_KEY", "sk-123"),
+            ("GITHUB_TOKEN", "ghp_1"),
+            ("db_password", "hunter2"),
+            ("DATABASE_URL", "postgres://me:pw@localhost/db"),
+            ("AWS_PROFILE_CONFIG", "prod"),
+            ("LANG", "en_US.UTF-8"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+
+        let env = safe_env_vars(vars);
+        let names: Vec<&str> = env.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["HOME", "LANG", "PATH"]);
+    }
+}
@@ -11,11 +11,11 @@ use crate::agent::core::context::TaskContext;
 use crate::agent::error::{ToolExecutorError, ToolExecutorResult};
 use crate::agent::persistence::FileRecordSource;
 use crate::agent::tools::{
-    RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority, ToolResult,
-    ToolResultContent, ToolResultStatus,
+    ProposedWrite, RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority,
+    ToolResult, ToolResultContent, ToolResultStatus,
 };
 
-use super::file_utils::{ensure_absolute, is_probably_binary};
+use super::file_utils::{is_probably_binary, resolve_task_path};
 
 /// 默认模糊匹配阈值 (1.0 = 精确匹配, 0.9 = 允许10%差异)
 const DEFAULT_FUZZY_THRESHOLD: f64 = 0.9;
@@ -245,6 +245,15 @@ fn apply_replacement_with_indent(
         .collect()
 }
 
+/// 编辑后的完整内容及成功时返回给模型的结果
+struct PlannedEdit {
+    /// 文件不存在时为 None（仅 insert 模式会创建文件）
+    original: Option<String>,
+    updated: String,
+    summary: String,
+    ext: serde_json::Value,
+}
+
 pub struct UnifiedEditTool;
 
 impl UnifiedEditTool {
@@ -298,98 +307,16 @@ impl UnifiedEditTool {
         }
         Ok(())
     }
-}
-
-#[async_trait]
-impl RunnableTool for UnifiedEditTool {
-    fn name(&self) -> &str {
-        "edit_file"
-    }
-
-    fn description(&self) -> &str {
-        "Performs smart string replacements or insertions in files with fuzzy matching and intelligent indentation preservation.
-
-Usage:
-- The path parameter must be an absolute path (e.g., '/Users/user/project/src/main.ts')
-- You MUST use the read_file tool at least once before editing. This tool will error if you attempt an edit without reading the file first.
-- The tool uses fuzzy matching (90% similarity threshold) to find the target text, tolerating minor whitespace differences
-- Indentation is automatically preserved: the tool detects the original file's indentation style and applies it to replacements
-- ALWAYS prefer editing existing files in the codebase. NEVER write new files unless explicitly required.
-- Only use emojis if the user explicitly requests it. Avoid adding emojis to files unless asked.
-- For replace mode, include enough surrounding context to make the old_text unique"
-    }
-
-    fn parameters_schema(&self) -> serde_json::Value {
-        json!({
-            "type": "object",
-            "properties": {
-                "path": {
-                    "type": "string",
-                    "description": "The absolute path to the file to modify. For example: \"/Users/user/project/src/main.ts\""
-                },
-                "mode": {
-                    "type": "string",
-                    "enum": ["replace", "insert", "diff"],
-                    "description": "Edit mode: 'replace' for find-and-replace, 'insert' for inserting at a line number, 'diff' for applying unified diffs"
-                },
-                "old_text": {
-                    "type": "string",
-                    "description": "[replace mode only] The exact text to find and replace. Must match exactly including whitespace and indentation. Include enough surrounding context to make this unique in the file."
-                },
-                "new_text": {
-                    "type": "string",
-                    "description": "[replace mode only] The text to replace old_text with. Must be different from old_text."
-                },
-                "after_line": {
-                    "type": "integer",
-                    "minimum": 0,
-                    "description": "[insert mode only] 0-based line number after which to insert content. Use 0 to insert at the beginning of the file."
-                },
-                "content": {
-                    "type": "string",
-                    "description": "[insert mode only] The content to insert at the specified line."
-                },
-                "diff_content": {
-                    "type": "string",
-                    "description": "[diff mode only] A unified diff format patch to apply to the file."
-                }
-            },
-            "required": ["path", "mode"]
-        })
-    }
-
-    fn metadata(&self) -> ToolMetadata {
-        ToolMetadata::new(ToolCategory::FileWrite, ToolPriority::Standard)
-            .with_confirmation()
-            .with_tags(vec!["filesystem".into(), "edit".into()])
-            .with_summary_key_arg("path")
-    }
-
-    fn required_permissions(&self) -> Vec<ToolPermission> {
-        vec![ToolPermission::FileSystem]
-    }
-
-    async fn run(
-        &self,
-        context: &TaskContext,
-        args: serde_json::Value,
-    ) -> ToolExecutorResult<ToolResult> {
-        let args: UnifiedEditArgs = serde_json::from_value(args)?;
-        let path = match ensure_absolute(&args.path, &context.cwd) {
-            Ok(resolved) => resolved,
-            Err(err) => return Ok(error_result(err.to_string())),
-        };
 
-        let result = match args.mode {
+    /// 计算编辑后的完整内容但不写入；run 与写入预览共用
+    async fn plan_edit(path: &PathBuf, mode: EditMode) -> Result<PlannedEdit, ToolResult> {
+        match mode {
             EditMode::Replace { old_text, new_text } => {
-                let original = match Self::load_existing_text(&path).await {
-                    Ok(text) => text,
-                    Err(err) => return Ok(err),
-                };
+                let original = Self::load_existing_text(path).await?;
 
                 // 验证搜索和替换内容不相同
                 if old_text == new_text {
-                    return Ok(error_result(
+                    return Err(error_result(
                         "Search and replace content are identical - no changes would be made",
                     ));
                 }
@@ -405,7 +332,7 @@ Usage:
                 let replace_lines: Vec<&str> = new_text.lines().collect();
 
                 if search_lines.is_empty() {
-                    return Ok(error_result("Search content cannot be empty"));
+                    return Err(error_result("Search content cannot be empty"));
                 }
 
                 // 1. 首先尝试精确匹配
@@ -413,7 +340,7 @@ Usage:
                     // 检查是否有多个匹配
                     let match_count = original.matches(&old_text).count();
                     if match_count > 1 {
-                        return Ok(error_result(format!(
+                        return Err(error_result(format!(
                             "Found {} matches. Please provide more context to make a unique match.",
                             match_count
                         )));
@@ -443,29 +370,22 @@ Usage:
                     let updated =
                         format!("{}{}{}", before, indented_replace.join(line_ending), after);
 
-                    snapshot_before_edit(context, self.name(), path.as_path()).await?;
-
-                    if let Err(err) = fs::write(&path, &updated).await {
-                        return Ok(error_result(format!(
-                            "Failed to write file {}: {}",
-                            path.display(),
-                            err
-                        )));
-                    }
-
-                    return Ok(success_result(
-                        format!(
+                    return Ok(PlannedEdit {
+                        summary: format!(
                             "edit_file applied\nmode=replace\nfile={}\nmatch=exact",
                             path.display()
                         ),
-                        json!({
+                        ext: json!({
                             "file": path.display().to_string(),
                             "mode": "replace",
                             "matchType": "exact",
+                            "startLine": before.matches('\n').count() + 1,
                             "old": old_text,
                             "new": new_text
                         }),
-                    ));
+                        original: Some(original),
+                        updated,
+                    });
                 }
 
                 // 2. 精确匹配失败，尝试模糊匹配
@@ -505,31 +425,24 @@ Usage:
 
                         let updated = result_lines.join(line_ending);
 
-                        snapshot_before_edit(context, self.name(), path.as_path()).await?;
-
-                        if let Err(err) = fs::write(&path, &updated).await {
-                            return Ok(error_result(format!(
-                                "Failed to write file {}: {}",
-                                path.display(),
-                                err
-                            )));
-                        }
-
-                        return Ok(success_result(
-                            format!(
+                        return Ok(PlannedEdit {
+                            summary: format!(
                                 "edit_file applied\nmode=replace\nfile={}\nmatch=fuzzy ({}% similar)",
                                 path.display(),
                                 (fuzzy_result.best_score * 100.0) as u32
                             ),
-                            json!({
+                            ext: json!({
                                 "file": path.display().to_string(),
                                 "mode": "replace",
                                 "matchType": "fuzzy",
                                 "similarity": fuzzy_result.best_score,
+                                "startLine": match_index + 1,
                                 "old": fuzzy_result.best_match_content,
                                 "new": new_text
                             }),
-                        ));
+                            original: Some(original),
+                            updated,
+                        });
                     }
                 }
 
@@ -558,49 +471,42 @@ Usage:
                     }
                 );
 
-                return Ok(error_result(error_msg));
+                Err(error_result(error_msg))
             }
             EditMode::Insert {
                 after_line,
                 content,
             } => {
-                if let Err(err) = Self::ensure_parent(&path).await {
-                    return Ok(err);
-                }
+                Self::ensure_parent(path).await?;
 
-                if is_probably_binary(&path) {
-                    return Ok(error_result(format!(
+                if is_probably_binary(path) {
+                    return Err(error_result(format!(
                         "File {} may be binary",
                         path.display()
                     )));
                 }
 
-                let (mut lines, trailing_newline) = match fs::metadata(&path).await {
+                let original = match fs::metadata(path).await {
                     Ok(meta) => {
                         if meta.is_dir() {
-                            return Ok(error_result(format!(
+                            return Err(error_result(format!(
                                 "Path {} is a directory",
                                 path.display()
                             )));
                         }
-                        if is_probably_binary(&path) {
-                            return Ok(error_result(format!(
-                                "File {} may be binary",
-                                path.display()
-                            )));
-                        }
-                        match fs::read_to_string(&path).await {
-                            Ok(existing) => (
-                                existing
-                                    .lines()
-                                    .map(|s| s.to_string())
-                                    .collect::<Vec<String>>(),
-                                existing.ends_with('\n'),
-                            ),
-                            Err(_) => (Vec::new(), false),
-                        }
+                        fs::read_to_string(path).await.ok()
                     }
-                    Err(_) => (Vec::new(), false),
+                    Err(_) => None,
+                };
+                let (mut lines, trailing_newline) = match &original {
+                    Some(existing) => (
+                        existing
+                            .lines()
+                            .map(|s| s.to_string())
+                            .collect::<Vec<String>>(),
+                        existing.ends_with('\n'),
+                    ),
+                    None => (Vec::new(), false),
                 };
 
                 let insert_lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
@@ -613,73 +519,160 @@ Usage:
                     }
                 }
 
-                snapshot_before_edit(context, self.name(), path.as_path()).await?;
-
-                if let Err(err) = fs::write(&path, &updated).await {
-                    return Ok(error_result(format!(
-                        "Failed to write file {}: {}",
-                        path.display(),
-                        err
-                    )));
-                }
-
-                success_result(
-                    format!(
+                Ok(PlannedEdit {
+                    summary: format!(
                         "edit_file applied\nmode=insert\nfile={}\nline={}",
                         path.display(),
                         after_line
                     ),
-                    json!({
+                    ext: json!({
                         "file": path.display().to_string(),
                         "mode": "insert",
                         "line": after_line,
+                        "startLine": position + 1,
                         "old": "",
                         "new": content
                     }),
-                )
+                    original,
+                    updated,
+                })
             }
             EditMode::Diff { diff_content } => {
-                let original = match Self::load_existing_text(&path).await {
-                    Ok(text) => text,
-                    Err(err) => return Ok(err),
-                };
-
-                let patch = match Patch::from_str(&diff_content) {
-                    Ok(patch) => patch,
-                    Err(err) => {
-                        return Ok(error_result(format!("Failed to parse patch: {}", err)));
-                    }
-                };
+                let original = Self::load_existing_text(path).await?;
 
-                let updated = match apply(&original, &patch) {
-                    Ok(result) => result,
-                    Err(err) => {
-                        return Ok(error_result(format!("Failed to apply patch: {}", err)));
-                    }
-                };
+                let patch = Patch::from_str(&diff_content)
+                    .map_err(|err| error_result(format!("Failed to parse patch: {}", err)))?;
 
-                snapshot_before_edit(context, self.name(), path.as_path()).await?;
-
-                if let Err(err) = fs::write(&path, &updated).await {
-                    return Ok(error_result(format!(
-                        "Failed to write file {}: {}",
-                        path.display(),
-                        err
-                    )));
-                }
+                let updated = apply(&original, &patch)
+                    .map_err(|err| error_result(format!("Failed to apply patch: {}", err)))?;
 
-                success_result(
-                    format!("edit_file applied\nmode=diff\nfile={}", path.display()),
-                    json!({
+                Ok(PlannedEdit {
+                    summary: format!("edit_file applied\nmode=diff\nfile={}", path.display()),
+                    ext: json!({
                         "file": path.display().to_string(),
                         "mode": "diff",
                         "old": "",
                         "new": ""
                     }),
-                )
+                    original: Some(original),
+                    updated,
+                })
             }
+        }
+    }
+}
+
+#[async_trait]
+impl RunnableTool for UnifiedEditTool {
+    fn name(&self) -> &str {
+        "edit_file"
+    }
+
+    fn description(&self) -> &str {
+        "Performs smart string replacements or insertions in files with fuzzy matching and intelligent indentation preservation.
+
+Usage:
+- The path parameter must be an absolute path (e.g., '/Users/user/project/src/main.ts')
+- You MUST use the read_file tool at least once before editing. This tool will error if you attempt an edit without reading the file first.
+- The tool uses fuzzy matching (90% similarity threshold) to find the target text, tolerating minor whitespace differences
+- Indentation is automatically preserved: the tool detects the original file's indentation style and applies it to replacements
+- ALWAYS prefer editing existing files in the codebase. NEVER write new files unless explicitly required.
+- Only use emojis if the user explicitly requests it. Avoid adding emojis to files unless asked.
+- For replace mode, include enough surrounding context to make the old_text unique"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "The absolute path to the file to modify. For example: \"/Users/user/project/src/main.ts\""
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["replace", "insert", "diff"],
+                    "description": "Edit mode: 'replace' for find-and-replace, 'insert' for inserting at a line number, 'diff' for applying unified diffs"
+                },
+                "old_text": {
+                    "type": "string",
+                    "description": "[replace mode only] The exact text to find and replace. Must match exactly including whitespace and indentation. Include enough surrounding context to make this unique in the file."
+                },
+                "new_text": {
+                    "type": "string",
+                    "description": "[replace mode only] The text to replace old_text with. Must be different from old_text."
+                },
+                "after_line": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "[insert mode only] 0-based line number after which to insert content. Use 0 to insert at the beginning of the file."
+                },
+                "content": {
+                    "type": "string",
+                    "description": "[insert mode only] The content to insert at the specified line."
+                },
+                "diff_content": {
+                    "type": "string",
+                    "description": "[diff mode only] A unified diff format patch to apply to the file."
+                }
+            },
+            "required": ["path", "mode"]
+        })
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata::new(ToolCategory::FileWrite, ToolPriority::Standard)
+            .with_confirmation()
+            .with_tags(vec!["filesystem".into(), "edit".into()])
+            .with_summary_key_arg("path")
+    }
+
+    fn required_permissions(&self) -> Vec<ToolPermission> {
+        vec![ToolPermission::FileSystem]
+    }
+
+    async fn preview_write(
+        &self,
+        context: &TaskContext,
+        args: &serde_json::Value,
+    ) -> Option<ProposedWrite> {
+        let args: UnifiedEditArgs = serde_json::from_value(args.clone()).ok()?;
+        let path = resolve_task_path(&args.path, context).ok()?;
+        // 会被 run 拒绝的编辑不参与预览
+        let plan = Self::plan_edit(&path, args.mode).await.ok()?;
+        Some(ProposedWrite {
+            path,
+            old_content: plan.original,
+            new_content: plan.updated,
+        })
+    }
+
+    async fn run(
+        &self,
+        context: &TaskContext,
+        args: serde_json::Value,
+    ) -> ToolExecutorResult<ToolResult> {
+        let args: UnifiedEditArgs = serde_json::from_value(args)?;
+        let path = match resolve_task_path(&args.path, context) {
+            Ok(resolved) => resolved,
+            Err(err) => return Ok(error_result(err.to_string())),
         };
 
+        let plan = match Self::plan_edit(&path, args.mode).await {
+            Ok(plan) => plan,
+            Err(err) => return Ok(err),
+        };
+
+        snapshot_before_edit(context, self.name(), path.as_path()).await?;
+
+        if let Err(err) = fs::write(&path, &plan.updated).await {
+            return Ok(error_result(format!(
+                "Failed to write file {}: {}",
+                path.display(),
+                err
+            )));
+        }
+
         context
             .file_tracker()
             .track_file_operation(FileOperationRecord::new(
@@ -688,7 +681,7 @@ Usage:
             ))
             .await?;
 
-        Ok(result)
+        Ok(success_result(plan.summary, plan.ext))
     }
 }
 
@@ -717,6 +710,7 @@ async fn snapshot_before_edit(
     tool_name: &str,
     path: &Path,
 ) -> ToolExecutorResult<()> {
+    crate::vector_db::agent_writes().register(&context.task_id, path);
     context
         .snapshot_file_before_edit(path)
         .await
@@ -725,3 +719,42 @@ async fn snapshot_before_edit(
             error: err.to_string(),
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn plan_edit_computes_content_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "one\ntwo\n").unwrap();
+
+        let plan = UnifiedEditTool::plan_edit(
+            &path,
+            EditMode::Replace {
+                old_text: "two".into(),
+                new_text: "three".into(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(plan.original.as_deref(), Some("one\ntwo\n"));
+        assert_eq!(plan.updated, "one\nthree\n");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+
+        let new_file = dir.path().join("new.txt");
+        let plan = UnifiedEditTool::plan_edit(
+            &new_file,
+            EditMode::Insert {
+                after_line: 0,
+                content: "hello\n".into(),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(plan.original.is_none());
+        assert_eq!(plan.updated, "hello\n");
+        assert!(!new_file.exists());
+    }
+}
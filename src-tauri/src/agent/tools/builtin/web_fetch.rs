@@ -2,14 +2,22 @@
  * Web Fetch Tool
  *
  * Provides headless HTTP requests as an Agent tool so LLM can call it via tool-calls.
+ * HTML pages are reduced to their main content and returned as Markdown.
+ *
+ * 取消：工具调用由 step token 的 select! 驱动，取消时整个 future 被丢弃，进行中的请求随之中断。
  */
 
 use async_trait::async_trait;
-use serde::Deserialize;
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 use std::net::IpAddr;
-use std::time::Duration;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
 use tokio::net::lookup_host;
 use url::Url;
 
@@ -19,6 +27,92 @@ use crate::agent::tools::{
     BackoffStrategy, RateLimitConfig, RunnableTool, ToolCategory, ToolMetadata, ToolPermission,
     ToolPriority, ToolResult, ToolResultContent, ToolResultStatus,
 };
+use crate::storage::repositories::AppPreferences;
+use crate::storage::DatabaseManager;
+
+/// 返回给模型的最大字符数
+const MAX_CONTENT_CHARS: usize = 8000;
+/// 直接抓取时最多读取的响应体字节数
+const MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+/// html2text 的换行宽度
+const MARKDOWN_WIDTH: usize = 120;
+/// 抓取结果的缓存时长与容量
+const CACHE_TTL: Duration = Duration::from_secs(300);
+const CACHE_CAPACITY: usize = 32;
+
+/// 域名策略在偏好设置中的键
+pub const WEB_FETCH_DOMAINS_KEY: &str = "agent.web_fetch.domains";
+
+/// 抓取前去掉的页面框架元素（导航、广告位、脚本等）
+const BOILERPLATE_TAGS: &[&str] = &[
+    "script", "style", "noscript", "nav", "header", "footer", "aside", "form", "iframe", "svg",
+];
+
+static BOILERPLATE_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    BOILERPLATE_TAGS
+        .iter()
+        .map(|tag| Regex::new(&format!(r"(?is)<{tag}\b[^>]*>.*?</{tag}\s*>")).unwrap())
+        .collect()
+});
+
+static FETCH_CACHE: Lazy<Mutex<LruCache<String, (Instant, ToolResult)>>> =
+    Lazy::new(|| Mutex::new(LruCache::new(NonZeroUsize::new(CACHE_CAPACITY).unwrap())));
+
+/// 域名白名单 / 黑名单；黑名单优先，白名单非空时只允许其中的域名（含子域名）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebFetchDomainPolicy {
+    pub allowed: Vec<String>,
+    pub denied: Vec<String>,
+}
+
+impl WebFetchDomainPolicy {
+    pub async fn load(db: &DatabaseManager) -> Self {
+        AppPreferences::new(db)
+            .get(WEB_FETCH_DOMAINS_KEY)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    /// 去掉空白、统一小写并去重
+    pub fn normalized(self) -> Self {
+        fn clean(list: Vec<String>) -> Vec<String> {
+            let mut out: Vec<String> = list
+                .into_iter()
+                .map(|d| d.trim().trim_start_matches("*.").to_lowercase())
+                .filter(|d| !d.is_empty())
+                .collect();
+            out.sort();
+            out.dedup();
+            out
+        }
+        Self {
+            allowed: clean(self.allowed),
+            denied: clean(self.denied),
+        }
+    }
+
+    pub fn check(&self, host: &str) -> Result<(), String> {
+        let host = host.to_lowercase();
+        let matches = |domain: &String| host == *domain || host.ends_with(&format!(".{domain}"));
+        if self.denied.iter().any(matches) {
+            return Err(format!(
+                "Domain '{}' is blocked by the web fetch policy",
+                host
+            ));
+        }
+        if !self.allowed.is_empty() && !self.allowed.iter().any(matches) {
+            return Err(format!(
+                "Domain '{}' is not in the web fetch allowlist",
+                host
+            ));
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug, Deserialize)]
 struct WebFetchArgs {
@@ -45,13 +139,15 @@ Usage:
 - Takes a URL as input
 - Performs an HTTP GET request
 - Follows up to 10 redirects
-- Simplifies HTML to plain text for easier analysis
-- Returns at most 2000 characters
+- Converts the main content of HTML pages to Markdown (navigation, headers, footers and scripts are stripped)
+- Returns at most 8000 characters
+- Non-text responses (images, PDFs, binaries) return a short note instead of the body
 
 Usage notes:
   - The URL must be a fully-formed valid URL (http:// or https://)
   - Timeout is fixed at 30000ms (30 seconds)
-  - max_content_length is fixed at 2000 characters
+  - Results are cached for 5 minutes; fetching the same URL again returns the cached copy
+  - Domains may be restricted by the user's allowlist/denylist
   - This tool is read-only and does not modify any files
   - Rate limited to 10 calls per minute to prevent abuse
   - SSRF protection: Blocks requests to localhost/private IPs (including via DNS)"
@@ -82,6 +178,7 @@ Usage notes:
             })
             .with_timeout(Duration::from_secs(60))
             .with_tags(vec!["network".into(), "http".into()])
+            .with_time_varying()
     }
 
     fn required_permissions(&self) -> Vec<ToolPermission> {
@@ -90,7 +187,7 @@ Usage notes:
 
     async fn run(
         &self,
-        _context: &TaskContext,
+        context: &TaskContext,
         args: serde_json::Value,
     ) -> ToolExecutorResult<ToolResult> {
         let args: WebFetchArgs = serde_json::from_value(args)?;
@@ -111,106 +208,217 @@ Usage notes:
             ));
         }
 
+        let policy = WebFetchDomainPolicy::load(&context.repositories()).await;
+        if let Some(host) = parsed_url.host_str() {
+            if let Err(reason) = policy.check(host) {
+                return Ok(validation_error(reason));
+            }
+        }
+
         if let Err(err) = validate_fetch_url(&parsed_url).await {
             return Ok(validation_error(err.to_string()));
         }
 
-        let timeout_ms = 30_000; // 固定 30 秒超时
-        let max_len = 2000; // 固定 2000 字符限制
-
-        match try_jina_reader(&parsed_url, timeout_ms).await {
-            Ok(Some(jina_content)) => {
-                return Ok(ToolResult {
-                    content: vec![ToolResultContent::Success(jina_content.clone())],
-                    status: ToolResultStatus::Success,
-                    cancel_reason: None,
-                    execution_time_ms: None,
-                    ext_info: Some(json!({
-                        "url": parsed_url.as_str(),
-                        "source": "jina",
-                    })),
-                });
-            }
-            Ok(None) => {
-                // Fall through to direct fetching below
-            }
-            Err(err_result) => {
-                return Ok(err_result);
-            }
+        let cache_key = cache_key(&parsed_url);
+        if let Some(cached) = cached_result(&cache_key) {
+            return Ok(cached);
         }
 
-        let client_builder = reqwest::Client::builder()
-            .timeout(Duration::from_millis(timeout_ms))
-            .redirect(reqwest::redirect::Policy::none())
-            .user_agent("OrbitX-Agent/1.0");
-
-        let client = client_builder.build()?;
-
-        let started = std::time::Instant::now();
-        let resp = match fetch_follow_redirects(&client, parsed_url.clone(), 10).await {
-            Ok(r) => r,
-            Err(err) => {
-                return Ok(ToolResult {
-                    content: vec![ToolResultContent::Error(err.to_string())],
-                    status: ToolResultStatus::Error,
-                    cancel_reason: None,
-                    execution_time_ms: Some(started.elapsed().as_millis() as u64),
-                    ext_info: None,
-                });
-            }
-        };
-
-        let status = resp.status().as_u16();
-        let final_url = resp.url().to_string();
-        let mut headers = HashMap::new();
-        for (k, v) in resp.headers() {
-            if let Ok(s) = v.to_str() {
-                headers.insert(k.to_string(), s.to_string());
-            }
+        let result = fetch_page(&parsed_url, &policy).await?;
+        if result.status == ToolResultStatus::Success {
+            FETCH_CACHE
+                .lock()
+                .put(cache_key, (Instant::now(), result.clone()));
         }
-        let content_type = headers.get("content-type").cloned();
+        Ok(result)
+    }
+}
 
-        let raw_text = match resp.text().await {
-            Ok(t) => t,
-            Err(e) => format!("<read-error>{}", e),
-        };
+/// 去掉 fragment 后的 URL 作为缓存键
+fn cache_key(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.to_string()
+}
 
-        let (data_text, extracted_text) = if content_type
-            .as_deref()
-            .is_some_and(|ct| ct.contains("text/html"))
-        {
-            let (text, _title) = extract_content_from_html(&raw_text, max_len);
-            (summarize_text(&text, max_len), Some(text))
-        } else {
-            (truncate_text(&raw_text, max_len), None)
-        };
+fn cached_result(key: &str) -> Option<ToolResult> {
+    let mut cache = FETCH_CACHE.lock();
+    let (fetched_at, result) = cache.get(key)?;
+    if fetched_at.elapsed() > CACHE_TTL {
+        cache.pop(key);
+        return None;
+    }
+    let mut result = result.clone();
+    if let Some(serde_json::Value::Object(ext)) = result.ext_info.as_mut() {
+        ext.insert("cached".into(), json!(true));
+    }
+    Some(result)
+}
 
-        let meta = json!({
-            "status": status,
-            "final_url": final_url,
-            "headers": headers,
-            "content_type": content_type,
-            "extracted": extracted_text.is_some(),
-            "elapsed_ms": started.elapsed().as_millis() as u64,
-            "source": "direct",
+async fn fetch_page(
+    parsed_url: &Url,
+    policy: &WebFetchDomainPolicy,
+) -> ToolExecutorResult<ToolResult> {
+    let timeout_ms = 30_000; // 固定 30 秒超时
+    let max_len = MAX_CONTENT_CHARS;
+
+    // Jina 与直连共用同一个客户端与重定向/域名策略
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(timeout_ms))
+        .redirect(reqwest::redirect::Policy::none())
+        .user_agent("OrbitX-Agent/1.0")
+        .build()?;
+
+    if let Some(jina_content) = try_jina_reader(&client, parsed_url, policy).await {
+        return Ok(ToolResult {
+            content: vec![ToolResultContent::Success(truncate_text(
+                &jina_content,
+                max_len,
+            ))],
+            status: ToolResultStatus::Success,
+            cancel_reason: None,
+            execution_time_ms: None,
+            ext_info: Some(json!({
+                "url": parsed_url.as_str(),
+                "source": "jina",
+            })),
         });
+    }
 
-        let status_flag = if (200..400).contains(&status) {
-            ToolResultStatus::Success
-        } else {
-            ToolResultStatus::Error
-        };
+    let started = std::time::Instant::now();
+    let resp = match fetch_follow_redirects(&client, parsed_url.clone(), 10, policy).await {
+        Ok(r) => r,
+        Err(err) => {
+            return Ok(ToolResult {
+                content: vec![ToolResultContent::Error(err.to_string())],
+                status: ToolResultStatus::Error,
+                cancel_reason: None,
+                execution_time_ms: Some(started.elapsed().as_millis() as u64),
+                ext_info: None,
+            });
+        }
+    };
 
-        Ok(ToolResult {
-            content: vec![ToolResultContent::Success(data_text)],
-            status: status_flag,
+    let status = resp.status().as_u16();
+    let final_url = resp.url().to_string();
+    let mut headers = HashMap::new();
+    for (k, v) in resp.headers() {
+        if let Ok(s) = v.to_str() {
+            headers.insert(k.to_string(), s.to_string());
+        }
+    }
+    let content_type = headers.get("content-type").cloned();
+    let kind = ContentKind::from_content_type(content_type.as_deref());
+
+    if kind == ContentKind::Binary {
+        let note = format!(
+                "The response from {} has content type '{}', which is not text. The body was not returned.{}",
+                final_url,
+                content_type.as_deref().unwrap_or("unknown"),
+                headers
+                    .get("content-length")
+                    .map(|len| format!(" Size: {} bytes.", len))
+                    .unwrap_or_default()
+            );
+        return Ok(ToolResult {
+            content: vec![ToolResultContent::Success(note)],
+            status: ToolResultStatus::Success,
             cancel_reason: None,
             execution_time_ms: Some(started.elapsed().as_millis() as u64),
-            ext_info: Some(meta),
-        })
+            ext_info: Some(json!({
+                "status": status,
+                "final_url": final_url,
+                "content_type": content_type,
+                "source": "direct",
+                "skipped_binary": true,
+            })),
+        });
+    }
+
+    let raw_text = match read_body_capped(resp, MAX_BODY_BYTES).await {
+        Ok(t) => t,
+        Err(e) => format!("<read-error>{}", e),
+    };
+
+    let (data_text, title) = if kind == ContentKind::Html {
+        let (markdown, title) = html_to_markdown(&raw_text);
+        (truncate_text(&markdown, max_len), title)
+    } else {
+        (truncate_text(&raw_text, max_len), None)
+    };
+
+    let meta = json!({
+        "status": status,
+        "final_url": final_url,
+        "headers": headers,
+        "content_type": content_type,
+        "title": title,
+        "extracted": kind == ContentKind::Html,
+        "elapsed_ms": started.elapsed().as_millis() as u64,
+        "source": "direct",
+    });
+
+    let status_flag = if (200..400).contains(&status) {
+        ToolResultStatus::Success
+    } else {
+        ToolResultStatus::Error
+    };
+
+    Ok(ToolResult {
+        content: vec![ToolResultContent::Success(data_text)],
+        status: status_flag,
+        cancel_reason: None,
+        execution_time_ms: Some(started.elapsed().as_millis() as u64),
+        ext_info: Some(meta),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentKind {
+    Html,
+    Text,
+    Binary,
+}
+
+impl ContentKind {
+    fn from_content_type(content_type: Option<&str>) -> Self {
+        let Some(ct) = content_type else {
+            // 缺失时按文本处理，由后续 UTF-8 解码兜底
+            return Self::Text;
+        };
+        let ct = ct.to_lowercase();
+        if ct.contains("text/html") || ct.contains("application/xhtml") {
+            Self::Html
+        } else if ct.starts_with("text/")
+            || ct.contains("json")
+            || ct.contains("xml")
+            || ct.contains("javascript")
+            || ct.contains("yaml")
+            || ct.contains("toml")
+        {
+            Self::Text
+        } else {
+            Self::Binary
+        }
     }
 }
 
+/// 分块读取响应体，超过上限后停止读取
+async fn read_body_capped(
+    mut resp: reqwest::Response,
+    max_bytes: usize,
+) -> reqwest::Result<String> {
+    let mut body: Vec<u8> = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        let remaining = max_bytes.saturating_sub(body.len());
+        body.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+        if body.len() >= max_bytes {
+            break;
+        }
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
 fn truncate_text(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         return s.to_string();
@@ -219,47 +427,53 @@ fn truncate_text(s: &str, max_len: usize) -> String {
     format!("{}...\n[truncated, original {} chars]", truncated, s.len())
 }
 
-fn summarize_text(content: &str, max_len: usize) -> String {
-    let lines: Vec<&str> = content.lines().collect();
-    if lines.len() <= 50 {
-        return truncate_text(content, max_len);
-    }
-    let mut out = String::new();
-    for l in lines.iter().take(20) {
-        out.push_str(l);
-        out.push('\n');
+/// 去掉页面框架元素，优先取 <main>/<article> 的内容转换为 Markdown，返回 (markdown, title)
+fn html_to_markdown(html: &str) -> (String, Option<String>) {
+    use scraper::{Html, Selector};
+
+    let mut cleaned = html.to_string();
+    for pattern in BOILERPLATE_PATTERNS.iter() {
+        cleaned = pattern.replace_all(&cleaned, "").into_owned();
     }
-    out.push_str(&format!(
-        "\n... [omitted {} lines] ...\n\n",
-        lines.len().saturating_sub(30)
-    ));
-    for l in lines.iter().skip(lines.len().saturating_sub(10)) {
-        out.push_str(l);
+
+    let document = Html::parse_document(&cleaned);
+    let title = Selector::parse("title").ok().and_then(|sel| {
+        document
+            .select(&sel)
+            .next()
+            .map(|el| el.text().collect::<String>().trim().to_string())
+            .filter(|t| !t.is_empty())
+    });
+
+    let main_html = ["main", "article", "[role=main]", "body"]
+        .iter()
+        .filter_map(|sel| Selector::parse(sel).ok())
+        .find_map(|sel| document.select(&sel).next().map(|el| el.inner_html()))
+        .unwrap_or(cleaned);
+
+    let markdown = html2text::from_read(main_html.as_bytes(), MARKDOWN_WIDTH);
+    // 合并连续空行
+    let mut out = String::with_capacity(markdown.len());
+    let mut blank = false;
+    for line in markdown.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            if !blank && !out.is_empty() {
+                out.push('\n');
+            }
+            blank = true;
+            continue;
+        }
+        blank = false;
+        out.push_str(line);
         out.push('\n');
     }
-    truncate_text(&out, max_len)
-}
 
-fn extract_content_from_html(html: &str, max_length: usize) -> (String, Option<String>) {
-    use html2text::from_read;
-    let text = from_read(html.as_bytes(), max_length.max(4096));
-    let cleaned = text
-        .lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .collect::<Vec<_>>()
-        .join("\n");
-    let final_text = if cleaned.len() > max_length {
-        let truncated = crate::agent::utils::truncate_at_char_boundary(&cleaned, max_length);
-        format!(
-            "{}...\n\n[内容被截断，原始长度: {} 字符]",
-            truncated,
-            cleaned.len()
-        )
-    } else {
-        cleaned
+    let markdown = match &title {
+        Some(t) if !out.starts_with('#') => format!("# {}\n\n{}", t, out),
+        _ => out,
     };
-    (final_text, None)
+    (markdown, title)
 }
 
 fn is_private_ip(addr: &IpAddr) -> bool {
@@ -339,9 +553,19 @@ async fn fetch_follow_redirects(
     client: &reqwest::Client,
     mut url: Url,
     max_redirects: usize,
+    policy: &WebFetchDomainPolicy,
 ) -> ToolExecutorResult<reqwest::Response> {
     for _ in 0..=max_redirects {
         validate_fetch_url(&url).await?;
+        // 重定向目标同样受域名策略约束
+        if let Some(host) = url.host_str() {
+            policy
+                .check(host)
+                .map_err(|error| ToolExecutorError::InvalidArguments {
+                    tool_name: "web_fetch".to_string(),
+                    error,
+                })?;
+        }
 
         let resp = client.get(url.clone()).send().await.map_err(|e| {
             ToolExecutorError::ExecutionFailed {
@@ -378,26 +602,24 @@ async fn fetch_follow_redirects(
     })
 }
 
-async fn try_jina_reader(url: &Url, timeout_ms: u64) -> Result<Option<String>, ToolResult> {
-    let jina_url = format!("https://r.jina.ai/{}", url.as_str());
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_millis(timeout_ms))
-        .user_agent("OrbitX-Agent/1.0")
-        .build()
-        .map_err(|e| tool_error(format!("Failed to build request client: {}", e)))?;
-
-    let response = match client.get(jina_url).send().await {
-        Ok(resp) => resp,
-        Err(_) => return Ok(None),
-    };
+/// 通过 Jina Reader 获取正文；任何失败都返回 None，由调用方回退到直连
+async fn try_jina_reader(
+    client: &reqwest::Client,
+    url: &Url,
+    policy: &WebFetchDomainPolicy,
+) -> Option<String> {
+    let jina_url = Url::parse(&format!("https://r.jina.ai/{}", url.as_str())).ok()?;
+    let response = fetch_follow_redirects(client, jina_url, 10, policy)
+        .await
+        .ok()?;
 
     if !response.status().is_success() {
-        return Ok(None);
+        return None;
     }
 
-    match response.text().await {
-        Ok(text) if text.trim().len() > 50 => Ok(Some(text)),
-        _ => Ok(None),
+    match read_body_capped(response, MAX_BODY_BYTES).await {
+        Ok(text) if text.trim().len() > 50 => Some(text),
+        _ => None,
     }
 }
 
@@ -411,12 +633,67 @@ fn validation_error(message: impl Into<String>) -> ToolResult {
     }
 }
 
-fn tool_error(message: impl Into<String>) -> ToolResult {
-    ToolResult {
-        content: vec![ToolResultContent::Error(message.into())],
-        status: ToolResultStatus::Error,
-        cancel_reason: None,
-        execution_time_ms: None,
-        ext_info: None,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fetched_pages_are_not_reused_across_iterations() {
+        // 同类别的只读网络工具会被缓存，网页内容随时间变化，需要每次重新请求
+        assert!(ToolMetadata::new(ToolCategory::Network, ToolPriority::Expensive).cacheable());
+        assert!(!WebFetchTool::new().metadata().cacheable());
+    }
+
+    #[test]
+    fn domain_policy_denylist_wins_and_matches_subdomains() {
+        let policy = WebFetchDomainPolicy {
+            allowed: vec!["Example.com".into(), " *.rust-lang.org ".into()],
+            denied: vec!["ads.example.com".into()],
+        }
+        .normalized();
+
+        assert!(policy.check("example.com").is_ok());
+        assert!(policy.check("docs.example.com").is_ok());
+        assert!(policy.check("doc.rust-lang.org").is_ok());
+        assert!(policy.check("ads.example.com").is_err());
+        assert!(policy.check("notexample.com").is_err());
+        assert!(WebFetchDomainPolicy::default().check("any.host").is_ok());
+    }
+
+    #[test]
+    fn html_to_markdown_keeps_main_content_only() {
+        let html = r#"<html><head><title>Guide</title><style>p{}</style></head>
+            <body><nav><a href="/">Home</a> Menu</nav>
+            <main><h2>Install</h2><p>Run <code>cargo add</code>.</p></main>
+            <footer>Copyright</footer><script>track()</script></body></html>"#;
+        let (markdown, title) = html_to_markdown(html);
+
+        assert_eq!(title.as_deref(), Some("Guide"));
+        assert!(markdown.starts_with("# Guide"));
+        assert!(markdown.contains("Install"));
+        assert!(markdown.contains("cargo add"));
+        assert!(!markdown.contains("Menu"));
+        assert!(!markdown.contains("Copyright"));
+        assert!(!markdown.contains("track()"));
+    }
+
+    #[test]
+    fn classifies_content_types() {
+        assert_eq!(
+            ContentKind::from_content_type(Some("text/html; charset=utf-8")),
+            ContentKind::Html
+        );
+        assert_eq!(
+            ContentKind::from_content_type(Some("application/json")),
+            ContentKind::Text
+        );
+        assert_eq!(
+            ContentKind::from_content_type(Some("image/png")),
+            ContentKind::Binary
+        );
+        assert_eq!(
+            ContentKind::from_content_type(Some("application/pdf")),
+            ContentKind::Binary
+        );
     }
 }
@@ -10,11 +10,11 @@ use crate::agent::core::context::TaskContext;
 use crate::agent::error::{ToolExecutorError, ToolExecutorResult};
 use crate::agent::persistence::FileRecordSource;
 use crate::agent::tools::{
-    RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority, ToolResult,
-    ToolResultContent, ToolResultStatus,
+    ProposedWrite, RunnableTool, ToolCategory, ToolMetadata, ToolPermission, ToolPriority,
+    ToolResult, ToolResultContent, ToolResultStatus,
 };
 
-use super::file_utils::{ensure_absolute, is_probably_binary};
+use super::file_utils::{is_probably_binary, resolve_task_path};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -77,13 +77,36 @@ Usage:
         vec![ToolPermission::FileSystem]
     }
 
+    async fn preview_write(
+        &self,
+        context: &TaskContext,
+        args: &serde_json::Value,
+    ) -> Option<ProposedWrite> {
+        let args: WriteFileArgs = serde_json::from_value(args.clone()).ok()?;
+        let path = resolve_task_path(&args.path, context).ok()?;
+        // 会被 run 拒绝的写入不参与预览
+        if is_probably_binary(&path) || !path.parent().is_some_and(|p| p.exists()) {
+            return None;
+        }
+        let old_content = match fs::metadata(&path).await {
+            Ok(meta) if meta.is_dir() => return None,
+            Ok(_) => Some(String::from_utf8_lossy(&fs::read(&path).await.ok()?).into_owned()),
+            Err(_) => None,
+        };
+        Some(ProposedWrite {
+            path,
+            old_content,
+            new_content: args.content,
+        })
+    }
+
     async fn run(
         &self,
         context: &TaskContext,
         args: serde_json::Value,
     ) -> ToolExecutorResult<ToolResult> {
         let args: WriteFileArgs = serde_json::from_value(args)?;
-        let path = match ensure_absolute(&args.path, &context.cwd) {
+        let path = match resolve_task_path(&args.path, context) {
             Ok(resolved) => resolved,
             Err(err) => return Ok(error_result(err.to_string())),
         };
@@ -161,6 +184,7 @@ async fn snapshot_before_edit(
     tool_name: &str,
     path: &Path,
 ) -> ToolExecutorResult<()> {
+    crate::vector_db::agent_writes().register(&context.task_id, path);
     context
         .snapshot_file_before_edit(path)
         .await
@@ -0,0 +1,132 @@
+//! 跨迭代的工具调用去重
+//!
+//! 同一迭代内的重复调用由 `deduplicate_tool_uses` 处理；这里缓存最近若干轮中只读工具的成功结果，
+//! 模型在窗口内以相同参数再次调用时直接返回缓存结果并附带说明，不再执行。
+//! 时变工具（metadata 标记 `time_varying`）不缓存；一旦执行了可能修改工作区的工具，缓存整体失效。
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use serde_json::Value;
+
+use super::{ToolResult, ToolResultContent, ToolResultStatus};
+
+/// 默认缓存最近 3 轮迭代的结果
+pub const DEFAULT_TOOL_DEDUP_WINDOW: u32 = 3;
+
+struct CachedResult {
+    iteration: u32,
+    result: ToolResult,
+}
+
+pub struct ToolResultCache {
+    /// 0 表示关闭跨迭代去重
+    window: u32,
+    entries: HashMap<(String, u64), CachedResult>,
+}
+
+fn args_hash(params: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(params)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+impl ToolResultCache {
+    pub fn new(window: u32) -> Self {
+        Self {
+            window,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// 查找此前迭代中相同调用的结果，命中时返回附带说明的副本
+    pub fn get(&mut self, iteration: u32, name: &str, params: &Value) -> Option<ToolResult> {
+        if self.window == 0 {
+            return None;
+        }
+        let window = self.window;
+        self.entries
+            .retain(|_, cached| iteration.saturating_sub(cached.iteration) <= window);
+
+        let cached = self.entries.get(&(name.to_string(), args_hash(params)))?;
+        if cached.iteration >= iteration {
+            return None;
+        }
+        let mut result = cached.result.clone();
+        result.content.insert(
+            0,
+            ToolResultContent::Success(format!(
+                "[Identical `{}` call already ran in iteration {}; returning the cached result instead of re-executing. Use it rather than calling again.]",
+                name, cached.iteration
+            )),
+        );
+        result.execution_time_ms = Some(0);
+        Some(result)
+    }
+
+    /// 记录成功结果；失败或取消的调用下次仍会执行
+    pub fn insert(&mut self, iteration: u32, name: &str, params: &Value, result: &ToolResult) {
+        if self.window == 0 || result.status != ToolResultStatus::Success {
+            return;
+        }
+        self.entries.insert(
+            (name.to_string(), args_hash(params)),
+            CachedResult {
+                iteration,
+                result: result.clone(),
+            },
+        );
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ok(text: &str) -> ToolResult {
+        ToolResult {
+            content: vec![ToolResultContent::Success(text.to_string())],
+            status: ToolResultStatus::Success,
+            cancel_reason: None,
+            execution_time_ms: Some(12),
+            ext_info: None,
+        }
+    }
+
+    #[test]
+    fn returns_cached_result_within_window() {
+        let mut cache = ToolResultCache::new(2);
+        let args = json!({ "path": "src/main.rs" });
+        cache.insert(1, "read_file", &args, &ok("fn main() {}"));
+
+        assert!(cache.get(1, "read_file", &args).is_none());
+        assert!(cache
+            .get(2, "read_file", &json!({ "path": "src/lib.rs" }))
+            .is_none());
+
+        let hit = cache
+            .get(3, "read_file", &args)
+            .expect("cached within window");
+        assert_eq!(hit.content.len(), 2);
+        assert!(matches!(&hit.content[1], ToolResultContent::Success(t) if t == "fn main() {}"));
+
+        assert!(cache.get(4, "read_file", &args).is_none());
+
+        let mut failed = ok("boom");
+        failed.status = ToolResultStatus::Error;
+        cache.insert(4, "list_files", &args, &failed);
+        assert!(cache.get(5, "list_files", &args).is_none());
+
+        let mut disabled = ToolResultCache::new(0);
+        disabled.insert(1, "read_file", &args, &ok("x"));
+        assert!(disabled.get(2, "read_file", &args).is_none());
+    }
+}
@@ -0,0 +1,232 @@
+//! 工具结果的模型侧渲染
+//!
+//! 工具结果默认以 JSON 交给模型；在 `ToolRegistry` 中为某个工具注册 formatter 后，
+//! 成功结果改为由 formatter 渲染成更贴近工具语义的文本（搜索结果按 grep 风格列出、编辑结果显示 diff），
+//! 减少转义带来的 token 开销。formatter 只影响模型看到的文本，UI 仍使用结构化结果。
+
+use serde_json::Value;
+
+use super::{ToolResult, ToolResultContent};
+
+/// 把工具的成功结果渲染为模型看到的文本；返回 None 时回退到 JSON
+pub type ToolResultFormatter = fn(&ToolResult) -> Option<String>;
+
+/// 单个 diff 的行数上限，超出部分截断
+const MAX_DIFF_LINES: usize = 200;
+
+fn success_text(result: &ToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|c| match c {
+            ToolResultContent::Success(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// orbit_search：regex 模式按 grep 输出 `path:line: text`，语义/混合模式按相关度列出片段
+pub fn format_search_result(result: &ToolResult) -> Option<String> {
+    let ext = result.ext_info.as_ref()?;
+    let entries = ext.get("results")?.as_array()?;
+    if entries.is_empty() {
+        return Some(success_text(result));
+    }
+
+    let mode = ext
+        .get("mode")
+        .and_then(Value::as_str)
+        .unwrap_or("semantic");
+    let query = ext.get("query").and_then(Value::as_str).unwrap_or_default();
+    let mut out = format!("{} results for \"{}\" ({})\n", entries.len(), query, mode);
+
+    for entry in entries {
+        let file = entry.get("filePath").and_then(Value::as_str)?;
+        let start = entry.get("startLine").and_then(Value::as_u64).unwrap_or(0);
+        let end = entry
+            .get("endLine")
+            .and_then(Value::as_u64)
+            .unwrap_or(start);
+        let snippet = entry
+            .get("snippet")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        if mode == "regex" {
+            for (offset, line) in snippet.lines().enumerate() {
+                out.push_str(&format!("{}:{}: {}\n", file, start + offset as u64, line));
+            }
+        } else {
+            let score = entry
+                .get("score")
+                .and_then(Value::as_f64)
+                .map(|s| format!(" score={:.2}", s))
+                .unwrap_or_default();
+            out.push_str(&format!("\n{}:{}-{}{}\n", file, start, end, score));
+            for line in snippet.lines() {
+                out.push_str("  ");
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    Some(out.trim_end().to_string())
+}
+
+/// 把片段内的 hunk 头 `@@ -a,b +c,d @@` 平移 offset 行，得到文件中的实际行号
+fn shift_hunk_header(line: &str, offset: usize) -> Option<String> {
+    let ranges = line.strip_prefix("@@ ")?.strip_suffix(" @@")?;
+    let (old, new) = ranges.split_once(' ')?;
+    let shift = |range: &str, sign: char| -> Option<String> {
+        let range = range.strip_prefix(sign)?;
+        let (start, len) = match range.split_once(',') {
+            Some((start, len)) => (start, Some(len)),
+            None => (range, None),
+        };
+        let start = start.parse::<usize>().ok()? + offset;
+        Some(match len {
+            Some(len) => format!("{}{},{}", sign, start, len),
+            None => format!("{}{}", sign, start),
+        })
+    };
+    Some(format!("@@ {} {} @@", shift(old, '-')?, shift(new, '+')?))
+}
+
+/// edit_file：在原结果文本下方以 unified diff 展示修改，hunk 行号对应文件中的位置；
+/// diff 模式下模型已持有补丁，只回显摘要
+pub fn format_edit_result(result: &ToolResult) -> Option<String> {
+    let ext = result.ext_info.as_ref()?;
+    let file = ext.get("file").and_then(Value::as_str)?;
+    let old = ext.get("old").and_then(Value::as_str).unwrap_or_default();
+    let new = ext.get("new").and_then(Value::as_str).unwrap_or_default();
+    let summary = success_text(result);
+    if old.is_empty() && new.is_empty() {
+        return Some(summary);
+    }
+    // 片段在文件中的起始行（从 1 开始）
+    let offset = ext
+        .get("startLine")
+        .and_then(Value::as_u64)
+        .map_or(0, |line| line.saturating_sub(1) as usize);
+
+    let patch = diffy::create_patch(old, new).to_string();
+    // 去掉 diffy 的 original/modified 文件头，换成实际路径
+    let hunks: Vec<&str> = patch.lines().skip(2).collect();
+    let mut out = format!("{}\n--- {}\n+++ {}\n", summary, file, file);
+    for line in hunks.iter().take(MAX_DIFF_LINES) {
+        match shift_hunk_header(line, offset) {
+            Some(header) => out.push_str(&header),
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    }
+    if hunks.len() > MAX_DIFF_LINES {
+        out.push_str(&format!(
+            "... ({} more diff lines)\n",
+            hunks.len() - MAX_DIFF_LINES
+        ));
+    }
+    Some(out.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::tools::ToolResultStatus;
+    use serde_json::json;
+
+    fn ok(text: &str, ext: Value) -> ToolResult {
+        ToolResult {
+            content: vec![ToolResultContent::Success(text.to_string())],
+            status: ToolResultStatus::Success,
+            cancel_reason: None,
+            execution_time_ms: None,
+            ext_info: Some(ext),
+        }
+    }
+
+    #[test]
+    fn regex_search_renders_grep_lines() {
+        let result = ok(
+            "Found 1 code snippet",
+            json!({
+                "mode": "regex",
+                "query": "fn main",
+                "results": [{
+                    "filePath": "/p/src/main.rs",
+                    "startLine": 3,
+                    "endLine": 4,
+                    "snippet": "fn main() {\n    run();",
+                    "score": null,
+                }],
+            }),
+        );
+        let text = format_search_result(&result).unwrap();
+        assert!(text.contains("/p/src/main.rs:3: fn main() {"));
+        assert!(text.contains("/p/src/main.rs:4:     run();"));
+    }
+
+    #[test]
+    fn semantic_search_lists_ranges_with_scores() {
+        let result = ok(
+            "Found 1 code snippet",
+            json!({
+                "mode": "semantic",
+                "query": "auth",
+                "results": [{
+                    "filePath": "/p/src/auth.rs",
+                    "startLine": 10,
+                    "endLine": 12,
+                    "snippet": "fn login() {}",
+                    "score": 0.8125,
+                }],
+            }),
+        );
+        let text = format_search_result(&result).unwrap();
+        assert!(text.contains("/p/src/auth.rs:10-12 score=0.81"));
+        assert!(text.contains("  fn login() {}"));
+    }
+
+    #[test]
+    fn edit_result_renders_unified_diff() {
+        let result = ok(
+            "edit_file applied\nmode=replace\nfile=/p/a.rs\nmatch=fuzzy (93% similar)",
+            json!({
+                "file": "/p/a.rs",
+                "mode": "replace",
+                "startLine": 12,
+                "old": "let x = 1;\n",
+                "new": "let x = 2;\n",
+            }),
+        );
+        let text = format_edit_result(&result).unwrap();
+        assert!(text.starts_with("edit_file applied\nmode=replace"));
+        assert!(text.contains("match=fuzzy (93% similar)\n--- /p/a.rs\n+++ /p/a.rs\n@@ -12 +12 @@"));
+        assert!(text.contains("-let x = 1;"));
+        assert!(text.contains("+let x = 2;"));
+
+        let inserted = ok(
+            "edit_file applied\nmode=insert",
+            json!({
+                "file": "/p/a.rs",
+                "mode": "insert",
+                "startLine": 6,
+                "old": "",
+                "new": "a\nb\n",
+            }),
+        );
+        assert!(format_edit_result(&inserted)
+            .unwrap()
+            .contains("@@ -5,0 +6,2 @@"));
+
+        let applied_patch = ok(
+            "edit_file applied\nmode=diff",
+            json!({ "file": "/p/a.rs", "mode": "diff", "old": "", "new": "" }),
+        );
+        assert_eq!(
+            format_edit_result(&applied_patch).unwrap(),
+            "edit_file applied\nmode=diff"
+        );
+    }
+}
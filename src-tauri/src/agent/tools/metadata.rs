@@ -46,6 +46,12 @@ impl ToolCategory {
             Self::FileWrite | Self::Execution | Self::Terminal => ExecutionMode::Sequential,
         }
     }
+
+    /// 该类别的工具可能修改工作区，执行后此前缓存的只读结果不再可信
+    #[inline]
+    pub const fn mutates_workspace(&self) -> bool {
+        matches!(self, Self::FileWrite | Self::Execution)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -83,6 +89,8 @@ pub struct ToolMetadata {
     pub tags: Vec<String>,
     /// Key argument field name for summarization (e.g., "path" for file tools, "command" for shell)
     pub summary_key_arg: Option<&'static str>,
+    /// 相同参数的结果随时间变化（如终端实时输出），不参与跨迭代去重
+    pub time_varying: bool,
 }
 
 impl ToolMetadata {
@@ -95,6 +103,7 @@ impl ToolMetadata {
             requires_confirmation: false,
             tags: Vec::new(),
             summary_key_arg: None,
+            time_varying: false,
         }
     }
 
@@ -123,6 +132,23 @@ impl ToolMetadata {
         self
     }
 
+    pub fn with_time_varying(mut self) -> Self {
+        self.time_varying = true;
+        self
+    }
+
+    /// 结果可在后续迭代中复用：只读类别且不随时间变化
+    pub fn cacheable(&self) -> bool {
+        !self.time_varying
+            && matches!(
+                self.category,
+                ToolCategory::FileRead
+                    | ToolCategory::CodeAnalysis
+                    | ToolCategory::FileSystem
+                    | ToolCategory::Network
+            )
+    }
+
     pub fn effective_timeout(&self) -> Duration {
         self.custom_timeout
             .unwrap_or_else(|| Duration::from_millis(self.priority.timeout_millis()))
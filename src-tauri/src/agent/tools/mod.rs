@@ -2,12 +2,17 @@
 // Real implementation after migration
 
 pub mod builtin;
+pub mod dedup;
+pub mod formatters;
 pub mod logger;
 pub mod metadata;
 pub mod parallel;
 pub mod registry;
 pub mod r#trait;
+pub mod write_preview;
 // Re-exports for external use
+pub use dedup::{ToolResultCache, DEFAULT_TOOL_DEDUP_WINDOW};
+pub use formatters::ToolResultFormatter;
 pub use logger::ToolExecutionLogger;
 pub use metadata::{
     BackoffStrategy, ExecutionMode, RateLimitConfig, ToolCategory, ToolMetadata, ToolPriority,
@@ -18,11 +23,14 @@ pub use r#trait::{
     ToolResultStatus, ToolSchema,
 };
 pub use registry::{get_permissions_for_mode, ToolExecutionStats, ToolRegistry};
+pub use write_preview::{FileWritePreview, ProposedWrite, WritePreviewDecision};
 
 // Builtin tool type re-exports
 pub use builtin::{
-    ListFilesTool, OrbitSearchTool, ReadFileTool, ReadTerminalTool, ShellTool, UnifiedEditTool,
-    WebFetchTool, WriteFileTool,
+    FormatCodeTool, GitBranchTool, ListCheckpointsTool, ListDirectoryTool, ListFilesTool,
+    MoveFileTool, OrbitSearchTool, ReadFileTool, ReadTerminalTool, RecallHistoryTool,
+    RollbackCheckpointTool, RunTestsTool, ShellTool, SystemInfoTool, UnifiedEditTool, WebFetchTool,
+    WriteFileTool,
 };
 
 use std::sync::Arc;
@@ -55,15 +63,55 @@ async fn register_builtin_tools(registry: &ToolRegistry, is_chat_mode: bool) {
         .register("edit_file", Arc::new(UnifiedEditTool::new()), is_chat_mode)
         .await
         .ok();
+    registry
+        .register("move_file", Arc::new(MoveFileTool::new()), is_chat_mode)
+        .await
+        .ok();
     registry
         .register("list_files", Arc::new(ListFilesTool::new()), is_chat_mode)
         .await
         .ok();
+    registry
+        .register(
+            "list_directory",
+            Arc::new(ListDirectoryTool::new()),
+            is_chat_mode,
+        )
+        .await
+        .ok();
 
     registry
         .register("shell", Arc::new(ShellTool::new()), is_chat_mode)
         .await
         .ok();
+    registry
+        .register("run_tests", Arc::new(RunTestsTool::new()), is_chat_mode)
+        .await
+        .ok();
+    registry
+        .register("format_code", Arc::new(FormatCodeTool::new()), is_chat_mode)
+        .await
+        .ok();
+    registry
+        .register("git_branch", Arc::new(GitBranchTool::new()), is_chat_mode)
+        .await
+        .ok();
+    registry
+        .register(
+            "list_checkpoints",
+            Arc::new(ListCheckpointsTool::new()),
+            is_chat_mode,
+        )
+        .await
+        .ok();
+    registry
+        .register(
+            "rollback_checkpoint",
+            Arc::new(RollbackCheckpointTool::new()),
+            is_chat_mode,
+        )
+        .await
+        .ok();
     registry
         .register(
             "orbit_search",
@@ -80,4 +128,19 @@ async fn register_builtin_tools(registry: &ToolRegistry, is_chat_mode: bool) {
         )
         .await
         .ok();
+    registry
+        .register("system_info", Arc::new(SystemInfoTool::new()), is_chat_mode)
+        .await
+        .ok();
+    registry
+        .register(
+            "recall_history",
+            Arc::new(RecallHistoryTool::new()),
+            is_chat_mode,
+        )
+        .await
+        .ok();
+
+    registry.register_result_formatter("orbit_search", formatters::format_search_result);
+    registry.register_result_formatter("edit_file", formatters::format_edit_result);
 }
@@ -2,19 +2,23 @@
  * 并行工具执行器
  *
  * 根据 ToolCategory 自动判断并行性：
- * - FileRead/CodeAnalysis/FileSystem/Network: 可并行
+ * - FileRead/CodeAnalysis/FileSystem/Network: 可并行（受并发上限约束）
  * - FileWrite/Execution/Terminal: 必须串行
  */
 
+use std::future::Future;
+use std::time::Instant;
+
 use futures::future::join_all;
 use serde_json::Value;
+use tokio::sync::Semaphore;
 
 use super::metadata::ExecutionMode;
 use super::registry::ToolRegistry;
-use super::ToolResult;
+use super::{ToolResult, ToolResultContent, ToolResultStatus};
 use crate::agent::core::context::TaskContext;
 
-/// 最大并行数，防止资源耗尽
+/// 并发上限的硬性封顶，配置值超出时按此截断，防止资源耗尽
 const MAX_CONCURRENCY: usize = 8;
 
 /// 工具调用请求
@@ -46,14 +50,7 @@ pub async fn execute_batch(
     // 单个调用直接执行，无需分组
     if calls.len() == 1 {
         let call = calls.into_iter().next().unwrap();
-        let result = registry
-            .execute_tool(&call.name, context, call.params)
-            .await;
-        return vec![ToolCallResult {
-            id: call.id,
-            name: call.name,
-            result,
-        }];
+        return vec![execute_one(registry, context, &call).await];
     }
 
     // 分组并执行
@@ -116,33 +113,39 @@ async fn execute_group(
     }
 }
 
-/// 并行执行（限制并发数）
+/// 并行执行（信号量限制并发数，结果保持调用顺序）
 async fn execute_parallel(
     registry: &ToolRegistry,
     context: &TaskContext,
     _start_idx: usize,
     calls: Vec<&ToolCall>,
 ) -> Vec<ToolCallResult> {
-    // 分批执行，每批最多 MAX_CONCURRENCY 个
-    let mut results = Vec::with_capacity(calls.len());
+    let limit = context
+        .config()
+        .max_concurrent_tools
+        .clamp(1, MAX_CONCURRENCY);
 
-    for chunk in calls.chunks(MAX_CONCURRENCY) {
-        let futures = chunk.iter().map(|call| async {
-            let result = registry
-                .execute_tool(&call.name, context, call.params.clone())
-                .await;
-            ToolCallResult {
-                id: call.id.clone(),
-                name: call.name.clone(),
-                result,
-            }
-        });
+    run_bounded(calls, limit, |call| execute_one(registry, context, call)).await
+}
 
-        let chunk_results = join_all(futures).await;
-        results.extend(chunk_results);
-    }
+/// 以不超过 `limit` 的并发度执行，返回顺序与输入一致
+async fn run_bounded<I, F, Fut>(items: I, limit: usize, f: F) -> Vec<Fut::Output>
+where
+    I: IntoIterator,
+    F: Fn(I::Item) -> Fut,
+    Fut: Future,
+{
+    let semaphore = Semaphore::new(limit.max(1));
+    let futures = items.into_iter().map(|item| {
+        let semaphore = &semaphore;
+        let fut = f(item);
+        async move {
+            let _permit = semaphore.acquire().await.ok();
+            fut.await
+        }
+    });
 
-    results
+    join_all(futures).await
 }
 
 /// 串行执行
@@ -155,19 +158,46 @@ async fn execute_sequential(
     let mut results = Vec::with_capacity(calls.len());
 
     for call in calls {
-        let result = registry
-            .execute_tool(&call.name, context, call.params.clone())
-            .await;
-        results.push(ToolCallResult {
-            id: call.id.clone(),
-            name: call.name.clone(),
-            result,
-        });
+        results.push(execute_one(registry, context, call).await);
     }
 
     results
 }
 
+/// 执行单个调用，并为其注册可单独取消的 step token
+///
+/// 取消只影响当前调用：返回 Cancelled 结果供模型继续推理，其余调用不受影响。
+async fn execute_one(
+    registry: &ToolRegistry,
+    context: &TaskContext,
+    call: &ToolCall,
+) -> ToolCallResult {
+    let start = Instant::now();
+    let token = context.register_step_token(&call.id);
+
+    let result = tokio::select! {
+        result = registry.execute_tool(&call.name, context, call.params.clone()) => result,
+        _ = token.cancelled() => ToolResult {
+            content: vec![ToolResultContent::Error(format!(
+                "Tool {} was cancelled by the user",
+                call.name
+            ))],
+            status: ToolResultStatus::Cancelled,
+            cancel_reason: Some("user_cancelled".to_string()),
+            execution_time_ms: Some(start.elapsed().as_millis() as u64),
+            ext_info: None,
+        },
+    };
+
+    context.release_step_token(&call.id);
+
+    ToolCallResult {
+        id: call.id.clone(),
+        name: call.name.clone(),
+        result,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,4 +223,30 @@ mod tests {
             ExecutionMode::Sequential
         );
     }
+
+    #[tokio::test]
+    async fn test_bounded_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let running = AtomicUsize::new(0);
+        let max_observed = AtomicUsize::new(0);
+
+        let results = run_bounded(0..5, 2, |i| {
+            let running = &running;
+            let max_observed = &max_observed;
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_observed.fetch_max(now, Ordering::SeqCst);
+                // 倒序耗时，验证结果顺序不受完成顺序影响
+                tokio::time::sleep(Duration::from_millis(10 * (5 - i) as u64)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        })
+        .await;
+
+        assert_eq!(max_observed.load(Ordering::SeqCst), 2);
+        assert_eq!(results, vec![0, 1, 2, 3, 4]);
+    }
 }
@@ -7,14 +7,16 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, warn};
 use uuid::Uuid;
 
+use super::formatters::ToolResultFormatter;
 use super::metadata::{RateLimitConfig, ToolCategory, ToolMetadata};
 use super::r#trait::{
     RunnableTool, ToolDescriptionContext, ToolPermission, ToolResult, ToolResultContent,
     ToolResultStatus, ToolSchema,
 };
+use super::write_preview::{ProposedWrite, WritePreviewDecision};
 use crate::agent::core::context::TaskContext;
 use crate::agent::error::{ToolExecutorError, ToolExecutorResult};
-use crate::agent::types::TaskEvent;
+use crate::agent::types::{CancelReason, TaskEvent};
 use crate::storage::repositories::AppPreferences;
 
 /// 根据 chat_mode 获取授予的权限集合
@@ -76,6 +78,9 @@ pub struct ToolRegistry {
     granted_permissions: Vec<ToolPermission>,
     execution_stats: DashMap<String, ToolExecutionStats>,
     pending_confirmations: DashMap<String, tokio::sync::oneshot::Sender<ToolConfirmationDecision>>,
+    pending_write_previews:
+        DashMap<String, tokio::sync::oneshot::Sender<Vec<WritePreviewDecision>>>,
+    result_formatters: DashMap<String, ToolResultFormatter>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -100,9 +105,30 @@ impl ToolRegistry {
             granted_permissions: granted,
             execution_stats: DashMap::new(),
             pending_confirmations: DashMap::new(),
+            pending_write_previews: DashMap::new(),
+            result_formatters: DashMap::new(),
         }
     }
 
+    /// 为工具注册模型侧的结果渲染函数，覆盖默认的 JSON 序列化
+    pub fn register_result_formatter(&self, name: &str, formatter: ToolResultFormatter) {
+        self.result_formatters.insert(name.to_string(), formatter);
+    }
+
+    /// 按注册的 formatter 渲染成功结果；未注册、失败结果或 formatter 放弃时返回 None，由调用方回退到 JSON
+    pub fn format_result(&self, name: &str, result: &ToolResult) -> Option<String> {
+        if result.status != ToolResultStatus::Success {
+            return None;
+        }
+        let name = self
+            .aliases
+            .get(name)
+            .map(|target| target.clone())
+            .unwrap_or_else(|| name.to_string());
+        let formatter = *self.result_formatters.get(&name)?;
+        formatter(result)
+    }
+
     pub fn resolve_confirmation(
         &self,
         request_id: &str,
@@ -118,6 +144,44 @@ impl ToolRegistry {
         }
     }
 
+    pub fn resolve_write_preview(
+        &self,
+        request_id: &str,
+        decisions: Vec<WritePreviewDecision>,
+    ) -> bool {
+        match self.pending_write_previews.remove(request_id) {
+            Some((_, tx)) => tx.send(decisions).is_ok(),
+            None => false,
+        }
+    }
+
+    pub(crate) fn register_write_preview(
+        &self,
+        request_id: &str,
+    ) -> tokio::sync::oneshot::Receiver<Vec<WritePreviewDecision>> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.pending_write_previews
+            .insert(request_id.to_string(), tx);
+        rx
+    }
+
+    pub(crate) fn cancel_write_preview(&self, request_id: &str) {
+        self.pending_write_previews.remove(request_id);
+    }
+
+    /// 工具即将写入的内容；非写文件工具或参数无效时为 None
+    pub async fn preview_write(
+        &self,
+        tool_name: &str,
+        context: &TaskContext,
+        args: &serde_json::Value,
+    ) -> Option<ProposedWrite> {
+        self.get_tool(tool_name)
+            .await?
+            .preview_write(context, args)
+            .await
+    }
+
     pub async fn register(
         &self,
         name: &str,
@@ -200,6 +264,7 @@ impl ToolRegistry {
         self.aliases.retain(|_, v| v != name);
         self.execution_stats.remove(name);
         self.rate_limiters.remove(name);
+        self.result_formatters.remove(name);
 
         let category = self
             .metadata_index
@@ -333,6 +398,7 @@ impl ToolRegistry {
                 let elapsed = start.elapsed().as_millis() as u64;
                 self.update_stats(&resolved, false, elapsed).await;
                 error!("Tool {} timed out {:?}", resolved, timeout);
+                context.record_cancel_reason(CancelReason::Timeout);
 
                 ToolResult {
                     content: vec![ToolResultContent::Error(format!(
@@ -342,7 +408,7 @@ impl ToolRegistry {
                         metadata.priority.as_str()
                     ))],
                     status: ToolResultStatus::Error,
-                    cancel_reason: None,
+                    cancel_reason: Some("timeout".to_string()),
                     execution_time_ms: Some(elapsed),
                     ext_info: None,
                 }
@@ -507,6 +573,9 @@ impl ToolRegistry {
             });
         }
 
+        // 等待期间显示 Dock 角标，无论以何种方式结束都会清除
+        let _badge = crate::dock::badge::ApprovalBadgeGuard::new();
+
         let decision = tokio::select! {
             res = tokio::time::timeout(Duration::from_secs(600), rx) => {
                 match res {
@@ -791,3 +860,64 @@ async fn is_within_workspace(workspace_root: &Path, resolved: &Path) -> bool {
 
     resolved_canon.starts_with(&workspace_canon)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+
+    use crate::agent::core::context::test_task_context;
+    use crate::agent::tools::metadata::ToolPriority;
+
+    struct SlowTool;
+
+    #[async_trait]
+    impl RunnableTool for SlowTool {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn description(&self) -> &str {
+            "sleeps past its timeout"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({ "type": "object", "properties": {} })
+        }
+
+        fn metadata(&self) -> ToolMetadata {
+            ToolMetadata::new(ToolCategory::FileRead, ToolPriority::Standard)
+                .with_timeout(Duration::from_millis(20))
+        }
+
+        async fn run(
+            &self,
+            _context: &TaskContext,
+            _args: Value,
+        ) -> ToolExecutorResult<ToolResult> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            unreachable!("the registry times the tool out first")
+        }
+    }
+
+    #[tokio::test]
+    async fn tool_timeout_records_timeout_cancel_reason() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = test_task_context(dir.path(), dir.path()).await;
+        let registry = ToolRegistry::new(vec![ToolPermission::ReadOnly]);
+        registry
+            .register("slow", Arc::new(SlowTool), false)
+            .await
+            .unwrap();
+
+        let result = registry.execute_tool("slow", &ctx, json!({})).await;
+        assert_eq!(result.status, ToolResultStatus::Error);
+        assert_eq!(result.cancel_reason.as_deref(), Some("timeout"));
+        assert_eq!(ctx.cancel_reason(), Some(CancelReason::Timeout));
+
+        // 恢复任务后不再沿用超时原因
+        ctx.set_pause(false, false);
+        assert_eq!(ctx.cancel_reason(), None);
+    }
+}
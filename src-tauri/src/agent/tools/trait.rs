@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::metadata::ToolMetadata;
+use super::write_preview::ProposedWrite;
 use crate::agent::core::context::TaskContext;
 use crate::agent::error::ToolExecutorResult;
 
@@ -99,6 +100,12 @@ pub trait RunnableTool: Send + Sync {
         Ok(())
     }
 
+    /// Write tools return the content they are about to write, used for diff previews;
+    /// returning None keeps the call out of previews
+    async fn preview_write(&self, _context: &TaskContext, _args: &Value) -> Option<ProposedWrite> {
+        None
+    }
+
     async fn run(&self, context: &TaskContext, args: Value) -> ToolExecutorResult<ToolResult>;
 
     /// Default: build ToolSchema from basic fields
@@ -0,0 +1,222 @@
+//! 写入前的 diff 预览
+//!
+//! 启用 `agent.preview_writes` 后，一轮工具调用中的写文件操作在执行前合并为一个
+//! `FileWritePreview` 事件，前端逐个文件确认或拒绝后再执行。被拒绝的调用不会执行，
+//! 直接向模型返回拒绝说明（附带用户反馈）；等待期间可通过 step token 单独取消某个调用。
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::parallel::ToolCall;
+use super::registry::ToolRegistry;
+use super::{ToolResult, ToolResultContent, ToolResultStatus};
+use crate::agent::core::context::TaskContext;
+use crate::agent::types::TaskEvent;
+use crate::storage::repositories::AppPreferences;
+use crate::storage::DatabaseManager;
+
+/// 是否启用写入预览的偏好项，值为 "true" / "false"
+pub const PREVIEW_WRITES_ENABLED_KEY: &str = "agent.preview_writes";
+
+const PREVIEW_TIMEOUT: Duration = Duration::from_secs(600);
+
+pub async fn preview_writes_enabled(db: &DatabaseManager) -> bool {
+    AppPreferences::new(db)
+        .get(PREVIEW_WRITES_ENABLED_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true")
+}
+
+/// 写文件类工具即将写入的内容
+#[derive(Debug, Clone)]
+pub struct ProposedWrite {
+    pub path: PathBuf,
+    /// 文件不存在时为 None
+    pub old_content: Option<String>,
+    pub new_content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileWritePreview {
+    pub call_id: String,
+    pub tool_name: String,
+    pub path: String,
+    pub is_new_file: bool,
+    /// 旧内容到新内容的 unified diff，与 checkpoint diff 格式一致
+    pub diff: String,
+}
+
+impl FileWritePreview {
+    pub fn new(call_id: &str, tool_name: &str, proposed: &ProposedWrite) -> Self {
+        let old = proposed.old_content.as_deref().unwrap_or_default();
+        Self {
+            call_id: call_id.to_string(),
+            tool_name: tool_name.to_string(),
+            path: proposed.path.display().to_string(),
+            is_new_file: proposed.old_content.is_none(),
+            diff: crate::checkpoint::compute_diff(old.as_bytes(), proposed.new_content.as_bytes()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WritePreviewDecision {
+    pub call_id: String,
+    pub approved: bool,
+    #[serde(default)]
+    pub feedback: Option<String>,
+}
+
+/// 预览本批次中的写入并等待用户决定；返回不应执行的调用及替代它们的工具结果
+pub async fn review_writes(
+    registry: &ToolRegistry,
+    context: &TaskContext,
+    calls: &[ToolCall],
+) -> HashMap<String, ToolResult> {
+    if !preview_writes_enabled(context.agent_persistence().database()).await {
+        return HashMap::new();
+    }
+    let mut previews = Vec::new();
+    for call in calls {
+        if let Some(proposed) = registry
+            .preview_write(&call.name, context, &call.params)
+            .await
+        {
+            previews.push(FileWritePreview::new(&call.id, &call.name, &proposed));
+        }
+    }
+    if previews.is_empty() {
+        return HashMap::new();
+    }
+
+    let tokens: Vec<_> = previews
+        .iter()
+        .map(|p| context.register_step_token(&p.call_id))
+        .collect();
+    let request_id = Uuid::new_v4().to_string();
+    let rx = registry.register_write_preview(&request_id);
+
+    let emitted = context
+        .emit_event(TaskEvent::FileWritePreview {
+            task_id: context.task_id.to_string(),
+            request_id: request_id.clone(),
+            files: previews.clone(),
+        })
+        .await;
+
+    let decisions: Result<Vec<WritePreviewDecision>, String> = match emitted {
+        Err(err) => Err(format!(
+            "Failed to request write preview (UI channel unavailable): {}",
+            err
+        )),
+        Ok(()) => {
+            let _badge = crate::dock::badge::ApprovalBadgeGuard::new();
+            tokio::select! {
+                res = tokio::time::timeout(PREVIEW_TIMEOUT, rx) => match res {
+                    Ok(Ok(decisions)) => Ok(decisions),
+                    Ok(Err(_)) => Err("Write preview channel closed".to_string()),
+                    Err(_) => Err("Write preview timed out waiting for the user".to_string()),
+                },
+                _ = async {
+                    while !context.is_aborted() {
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                } => Err("Task aborted; write preview cancelled".to_string()),
+                _ = futures::future::join_all(tokens.iter().map(|t| t.cancelled())) => {
+                    Err("Write was cancelled by the user".to_string())
+                }
+            }
+        }
+    };
+    registry.cancel_write_preview(&request_id);
+
+    let mut skipped = HashMap::new();
+    for (preview, token) in previews.iter().zip(&tokens) {
+        context.release_step_token(&preview.call_id);
+        let result = match &decisions {
+            _ if token.is_cancelled() => {
+                cancelled_result("Write was cancelled by the user", "user_cancelled")
+            }
+            Err(reason) => cancelled_result(reason, "preview_cancelled"),
+            Ok(decisions) => {
+                let decision = decisions.iter().find(|d| d.call_id == preview.call_id);
+                match decision {
+                    Some(d) if d.approved => continue,
+                    // 未给出决定的文件按拒绝处理
+                    _ => {
+                        declined_result(&preview.path, decision.and_then(|d| d.feedback.as_deref()))
+                    }
+                }
+            }
+        };
+        skipped.insert(preview.call_id.clone(), result);
+    }
+    skipped
+}
+
+fn declined_result(path: &str, feedback: Option<&str>) -> ToolResult {
+    let mut message = format!(
+        "The user reviewed the diff and declined this write; {} was not modified.",
+        path
+    );
+    if let Some(feedback) = feedback.map(str::trim).filter(|f| !f.is_empty()) {
+        message.push_str("\nUser feedback: ");
+        message.push_str(feedback);
+    }
+    ToolResult {
+        content: vec![ToolResultContent::Error(message)],
+        status: ToolResultStatus::Cancelled,
+        cancel_reason: Some("declined".to_string()),
+        execution_time_ms: None,
+        ext_info: None,
+    }
+}
+
+fn cancelled_result(message: &str, reason: &str) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Error(message.to_string())],
+        status: ToolResultStatus::Cancelled,
+        cancel_reason: Some(reason.to_string()),
+        execution_time_ms: None,
+        ext_info: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_renders_diff_and_declined_result_carries_feedback() {
+        let preview = FileWritePreview::new(
+            "call-1",
+            "write_file",
+            &ProposedWrite {
+                path: PathBuf::from("/ws/a.txt"),
+                old_content: Some("one\ntwo\n".into()),
+                new_content: "one\nthree\n".into(),
+            },
+        );
+        assert!(!preview.is_new_file);
+        assert!(preview.diff.contains("-two"));
+        assert!(preview.diff.contains("+three"));
+
+        let result = declined_result(&preview.path, Some("  keep two  "));
+        assert_eq!(result.status, ToolResultStatus::Cancelled);
+        match &result.content[0] {
+            ToolResultContent::Error(text) => {
+                assert!(text.contains("/ws/a.txt was not modified"));
+                assert!(text.ends_with("User feedback: keep two"));
+            }
+            other => panic!("unexpected content: {:?}", other),
+        }
+    }
+}
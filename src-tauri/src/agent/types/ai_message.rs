@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::agent::tools::FileWritePreview;
+
 /// 消息 - 用户或助手的一条完整消息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -33,13 +35,32 @@ pub enum MessageStatus {
     Error,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenUsage {
     pub input_tokens: i64,
     pub output_tokens: i64,
     pub cache_read_tokens: Option<i64>,
     pub cache_write_tokens: Option<i64>,
+    /// 思考消耗的 token（已包含在 output_tokens 中，按思考文本估算；不落库）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking_tokens: Option<i64>,
+}
+
+impl TokenUsage {
+    /// 累加一次 LLM 调用的用量
+    pub fn accumulate(&mut self, other: &TokenUsage) {
+        fn add(total: &mut Option<i64>, value: Option<i64>) {
+            if let Some(value) = value {
+                *total = Some(total.unwrap_or(0) + value);
+            }
+        }
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        add(&mut self.cache_read_tokens, other.cache_read_tokens);
+        add(&mut self.cache_write_tokens, other.cache_write_tokens);
+        add(&mut self.thinking_tokens, other.thinking_tokens);
+    }
 }
 
 /// 内容块 - 消息的组成单元
@@ -123,6 +144,61 @@ pub struct ErrorBlock {
     pub details: Option<String>,
 }
 
+/// 任务取消原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelReason {
+    /// 用户主动取消
+    UserCancelled,
+    /// 执行超时
+    Timeout,
+    /// 父任务结束，子任务随之取消
+    ParentCancelled,
+    /// 软停止：中断当前步骤并暂停后结束
+    SoftStop,
+    /// 崩溃恢复时强制重置
+    ForceReset,
+}
+
+impl CancelReason {
+    pub fn description(self) -> &'static str {
+        match self {
+            CancelReason::UserCancelled => "Cancelled by user",
+            CancelReason::Timeout => "Task timed out",
+            CancelReason::ParentCancelled => "Parent task ended",
+            CancelReason::SoftStop => "Task stopped after the current step",
+            CancelReason::ForceReset => "Task was force reset",
+        }
+    }
+
+    /// 调用方显式给出的原因优先，否则沿用任务上记录的原因，都没有时视为用户取消
+    pub fn resolve(requested: Option<CancelReason>, recorded: Option<CancelReason>) -> Self {
+        requested
+            .or(recorded)
+            .unwrap_or(CancelReason::UserCancelled)
+    }
+}
+
+impl TaskEvent {
+    /// 构造取消事件；detail 为调用方附带的说明，缺省时使用原因的默认描述
+    pub fn cancelled(
+        task_id: impl Into<String>,
+        reason: CancelReason,
+        detail: Option<&str>,
+    ) -> Self {
+        let message = detail
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .unwrap_or_else(|| reason.description())
+            .to_string();
+        TaskEvent::TaskCancelled {
+            task_id: task_id.into(),
+            reason,
+            message,
+        }
+    }
+}
+
 /// 任务进度事件（前端唯一输入）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -134,6 +210,10 @@ pub enum TaskEvent {
         workspace_path: String,
     },
 
+    /// 并发任务数已满，任务进入队列等待；position 从 1 开始
+    #[serde(rename_all = "camelCase")]
+    TaskQueued { task_id: String, position: usize },
+
     #[serde(rename_all = "camelCase")]
     MessageCreated { message: Message },
 
@@ -159,11 +239,28 @@ pub enum TaskEvent {
     #[serde(rename_all = "camelCase")]
     TaskCompleted { task_id: String },
 
+    /// 回复因 max_tokens 被截断，前端可提示并提供"继续"
+    #[serde(rename_all = "camelCase")]
+    ResponseTruncated { task_id: String },
+
     #[serde(rename_all = "camelCase")]
     TaskError { task_id: String, error: ErrorBlock },
 
+    /// 达到最大迭代次数而停止，summarized 表示是否已生成进展总结
     #[serde(rename_all = "camelCase")]
-    TaskCancelled { task_id: String },
+    IterationLimitReached {
+        task_id: String,
+        max_iterations: u32,
+        summarized: bool,
+    },
+
+    /// reason 供前端区分取消来源，message 为可直接展示的说明
+    #[serde(rename_all = "camelCase")]
+    TaskCancelled {
+        task_id: String,
+        reason: CancelReason,
+        message: String,
+    },
 
     /// 工具执行确认请求（前端需要弹窗并回传 decision）
     #[serde(rename_all = "camelCase")]
@@ -174,4 +271,70 @@ pub enum TaskEvent {
         tool_name: String,
         summary: String,
     },
+
+    /// 写入预览（前端展示 diff 并通过 agent_resolve_write_preview 回传各文件的决定）
+    #[serde(rename_all = "camelCase")]
+    FileWritePreview {
+        task_id: String,
+        request_id: String,
+        files: Vec<FileWritePreview>,
+    },
+}
+
+/// 不属于单个任务的系统通知（如批量取消），通过应用级事件广播
+pub const AGENT_SYSTEM_MESSAGE_EVENT: &str = "agent-system-message";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemMessage {
+    pub message: String,
+    pub task_ids: Vec<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelled_event_carries_reason_and_text() {
+        let cases = [
+            (CancelReason::UserCancelled, "user_cancelled"),
+            (CancelReason::Timeout, "timeout"),
+            (CancelReason::ParentCancelled, "parent_cancelled"),
+            (CancelReason::SoftStop, "soft_stop"),
+            (CancelReason::ForceReset, "force_reset"),
+        ];
+        for (reason, code) in cases {
+            let value = serde_json::to_value(TaskEvent::cancelled("t1", reason, None)).unwrap();
+            assert_eq!(value["type"], "task_cancelled");
+            assert_eq!(value["taskId"], "t1");
+            assert_eq!(value["reason"], code);
+            assert_eq!(value["message"], reason.description());
+        }
+
+        let value = serde_json::to_value(TaskEvent::cancelled(
+            "t1",
+            CancelReason::UserCancelled,
+            Some("  switched topic "),
+        ))
+        .unwrap();
+        assert_eq!(value["message"], "switched topic");
+    }
+
+    #[test]
+    fn resolves_requested_then_recorded_reason() {
+        assert_eq!(
+            CancelReason::resolve(None, None),
+            CancelReason::UserCancelled
+        );
+        assert_eq!(
+            CancelReason::resolve(None, Some(CancelReason::SoftStop)),
+            CancelReason::SoftStop
+        );
+        assert_eq!(
+            CancelReason::resolve(Some(CancelReason::Timeout), Some(CancelReason::SoftStop)),
+            CancelReason::Timeout
+        );
+    }
 }
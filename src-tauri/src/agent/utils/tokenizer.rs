@@ -10,6 +10,11 @@ static TOKEN_ENCODER: LazyLock<Arc<CoreBPE>> = LazyLock::new(|| {
         .into()
 });
 
+/// 提前加载 BPE 词表，避免首个任务在计数时才承担加载耗时
+pub fn prewarm() {
+    LazyLock::force(&TOKEN_ENCODER);
+}
+
 pub fn count_text_tokens(text: &str) -> usize {
     TOKEN_ENCODER.encode_with_special_tokens(text).len()
 }
@@ -0,0 +1,40 @@
+use tauri::State;
+use tracing::warn;
+
+use crate::ai::inline_ask::{self, InlineAskOutcome};
+use crate::llm::commands::LLMManagerState;
+use crate::mux::{get_mux, PaneId};
+use crate::utils::{EmptyData, TauriApiResult};
+use crate::{api_error, api_success};
+
+/// 在终端面板内联提问：回答以注释块形式流式显示在面板中，完成或取消后返回
+#[tauri::command]
+pub async fn ai_ask_inline(
+    pane_id: u32,
+    prompt: String,
+    model_id: String,
+    state: State<'_, LLMManagerState>,
+) -> TauriApiResult<InlineAskOutcome> {
+    let prompt = prompt.trim().to_string();
+    if prompt.is_empty() {
+        return Ok(api_error!("common.empty_content"));
+    }
+    if !get_mux().pane_exists(PaneId::new(pane_id)) {
+        return Ok(api_error!("terminal.pane_not_found"));
+    }
+
+    match inline_ask::ask_inline(&state.service, pane_id, model_id, prompt).await {
+        Ok(outcome) => Ok(api_success!(outcome)),
+        Err(e) => {
+            warn!("终端内联提问失败: pane_id={}, err={}", pane_id, e);
+            Ok(api_error!("llm.stream_failed"))
+        }
+    }
+}
+
+/// 取消面板上正在进行的内联提问
+#[tauri::command]
+pub async fn ai_ask_inline_cancel(pane_id: u32) -> TauriApiResult<EmptyData> {
+    inline_ask::cancel(pane_id);
+    Ok(api_success!())
+}
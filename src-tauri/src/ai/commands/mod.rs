@@ -1,5 +1,7 @@
+pub mod inline;
 pub mod model;
 
+pub use inline::*;
 pub use model::*;
 
 use crate::ai::AIService;
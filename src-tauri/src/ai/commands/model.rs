@@ -1,11 +1,16 @@
 //! AI模型管理命令
 
 use super::AIManagerState;
+use crate::ai::model_metadata::invalidate_model_metadata;
 use crate::ai::types::AIModelConfig;
+use crate::llm::commands::LLMManagerState;
+use crate::llm::types::{EmbeddingModelTestResult, ModelValidationResult};
+use crate::storage::cache::UnifiedCache;
 use crate::utils::{EmptyData, TauriApiResult};
 use crate::{api_error, api_success, validate_not_empty};
 
-use tauri::State;
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime, State};
 use tracing::warn;
 
 /// 获取所有AI模型配置
@@ -39,19 +44,25 @@ pub async fn ai_models_add(
     }
 }
 
-/// 删除AI模型配置
+/// 删除AI模型配置；删除的是向量索引正在使用的 embedding 模型时暂停向量服务
 #[tauri::command]
-pub async fn ai_models_remove(
+pub async fn ai_models_remove<R: Runtime>(
     model_id: String,
+    app: AppHandle<R>,
     state: State<'_, AIManagerState>,
+    cache: State<'_, Arc<UnifiedCache>>,
 ) -> TauriApiResult<EmptyData> {
     validate_not_empty!(model_id, "common.invalid_params");
 
     match state.ai_service.remove_model(&model_id).await {
-        Ok(_) => Ok(api_success!(
-            EmptyData::default(),
-            "ai.remove_model_success"
-        )),
+        Ok(_) => {
+            invalidate_model_metadata(&cache).await;
+            crate::vector_db::commands::on_ai_model_removed(&app).await;
+            Ok(api_success!(
+                EmptyData::default(),
+                "ai.remove_model_success"
+            ))
+        }
         Err(error) => {
             warn!(error = %error, model_id = %model_id, "删除AI模型失败");
             Ok(api_error!("ai.remove_model_failed"))
@@ -59,20 +70,24 @@ pub async fn ai_models_remove(
     }
 }
 
-/// 更新AI模型配置
+/// 更新AI模型配置，并清空模型元数据缓存
 #[tauri::command]
 pub async fn ai_models_update(
     model_id: String,
     updates: serde_json::Value,
     state: State<'_, AIManagerState>,
+    cache: State<'_, Arc<UnifiedCache>>,
 ) -> TauriApiResult<EmptyData> {
     validate_not_empty!(model_id, "common.invalid_params");
 
     match state.ai_service.update_model(&model_id, updates).await {
-        Ok(_) => Ok(api_success!(
-            EmptyData::default(),
-            "ai.update_model_success"
-        )),
+        Ok(_) => {
+            invalidate_model_metadata(&cache).await;
+            Ok(api_success!(
+                EmptyData::default(),
+                "ai.update_model_success"
+            ))
+        }
         Err(error) => {
             warn!(error = %error, model_id = %model_id, "更新AI模型失败");
             Ok(api_error!("ai.update_model_failed"))
@@ -104,3 +119,43 @@ pub async fn ai_models_test_connection(
         Err(e) => Ok(api_error!("ai.test_connection_error", "error" => e.to_string())),
     }
 }
+
+/// 测试 embedding 模型并返回向量维度
+///
+/// 失败时仍返回成功响应，具体原因放在结果的 error / errorKind 中，便于配置界面展示。
+#[tauri::command]
+pub async fn ai_test_embedding_model(
+    model_id: String,
+    llm_state: State<'_, LLMManagerState>,
+) -> TauriApiResult<EmbeddingModelTestResult> {
+    validate_not_empty!(model_id, "common.invalid_params");
+
+    let result = llm_state.service.test_embedding_model(&model_id).await;
+    if let Some(error) = &result.error {
+        warn!(model_id = %model_id, error = %error, "Embedding 模型测试失败");
+    }
+    Ok(api_success!(result))
+}
+
+/// 保存前校验模型配置并探测能力（流式、工具调用、embedding 维度）
+///
+/// 与 ai_test_embedding_model 一样，校验失败仍返回成功响应，原因放在 error / errorKind 中。
+#[tauri::command]
+pub async fn ai_validate_model_config(
+    config: AIModelConfig,
+    llm_state: State<'_, LLMManagerState>,
+) -> TauriApiResult<ModelValidationResult> {
+    if config.api_key.trim().is_empty() {
+        return Ok(api_error!("ai.api_key_empty"));
+    }
+    if config.model.trim().is_empty() {
+        return Ok(api_error!("ai.model_name_empty"));
+    }
+
+    let model = config.model.clone();
+    let result = llm_state.service.validate_model_config(config).await;
+    if let Some(error) = &result.error {
+        warn!(model = %model, error = %error, "模型配置校验失败");
+    }
+    Ok(api_success!(result))
+}
@@ -0,0 +1,214 @@
+//! 终端内联提问
+//!
+//! 单轮流式调用模型，把回答作为面板输出注入终端（不经过 PTY，shell 不会执行），
+//! 每行以 `# ` 开头显示为注释块。模型输出中的转义序列与控制字符在写入前去除。
+//! 面板中按下 Ctrl+C 会取消该面板正在进行的提问。
+
+use bytes::Bytes;
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio_stream::StreamExt;
+use tokio_util::sync::CancellationToken;
+
+use crate::llm::anthropic_types::{
+    ContentDelta, CreateMessageRequest, MessageContent, MessageParam, MessageRole, StreamEvent,
+    SystemPrompt,
+};
+use crate::llm::service::LLMService;
+use crate::mux::{get_mux, PaneId, TerminalMuxResult};
+use crate::utils::ansi::strip_ansi;
+
+const INLINE_MAX_TOKENS: u32 = 1024;
+
+const INLINE_SYSTEM_PROMPT: &str = "You are a terminal assistant. The answer is printed directly \
+inside the user's terminal, so reply concisely in plain text without Markdown tables or headings. \
+Put commands on their own lines.";
+
+const BLOCK_START: &str = "\r\n\x1b[2m";
+const LINE_PREFIX: &str = "# ";
+const BLOCK_END: &str = "\x1b[0m\r\n";
+
+static NEXT_ASK_ID: AtomicU64 = AtomicU64::new(1);
+static INLINE_ASKS: OnceCell<Mutex<HashMap<u32, (u64, CancellationToken)>>> = OnceCell::new();
+
+fn inline_asks() -> &'static Mutex<HashMap<u32, (u64, CancellationToken)>> {
+    INLINE_ASKS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 取消面板上正在进行的内联提问，返回是否存在
+pub fn cancel(pane_id: u32) -> bool {
+    match inline_asks().lock().remove(&pane_id) {
+        Some((_, token)) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// 内联提问的结束方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InlineAskOutcome {
+    Completed,
+    Cancelled,
+}
+
+/// 把流式文本转成终端注释块
+#[derive(Debug, Default)]
+struct InlineFormatter {
+    started: bool,
+    at_line_start: bool,
+}
+
+impl InlineFormatter {
+    fn feed(&mut self, text: &str) -> String {
+        let text = strip_ansi(text);
+        let mut out = String::with_capacity(text.len() + 16);
+        if !self.started {
+            self.started = true;
+            self.at_line_start = true;
+            out.push_str(BLOCK_START);
+        }
+        for c in text.chars() {
+            if c == '\n' {
+                if self.at_line_start {
+                    out.push_str(LINE_PREFIX.trim_end());
+                }
+                out.push_str("\r\n");
+                self.at_line_start = true;
+                continue;
+            }
+            if self.at_line_start {
+                out.push_str(LINE_PREFIX);
+                self.at_line_start = false;
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    fn finish(&mut self, note: Option<&str>) -> String {
+        let mut out = String::new();
+        if let Some(note) = note {
+            if !self.at_line_start {
+                out.push_str(&self.feed("\n"));
+            }
+            out.push_str(&self.feed(note));
+        }
+        if self.started {
+            out.push_str(BLOCK_END);
+        }
+        out
+    }
+}
+
+fn inject(pane_id: PaneId, text: String) -> TerminalMuxResult<()> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    get_mux().inject_pane_output(pane_id, Bytes::from(text))
+}
+
+/// 向模型提问并把回答流式写入面板；同一面板上的上一次提问会被取消
+pub async fn ask_inline(
+    service: &LLMService,
+    pane_id: u32,
+    model_id: String,
+    prompt: String,
+) -> Result<InlineAskOutcome, String> {
+    let pane = PaneId::new(pane_id);
+    let ask_id = NEXT_ASK_ID.fetch_add(1, Ordering::Relaxed);
+    let token = CancellationToken::new();
+    if let Some((_, previous)) = inline_asks()
+        .lock()
+        .insert(pane_id, (ask_id, token.clone()))
+    {
+        previous.cancel();
+    }
+
+    let request = CreateMessageRequest {
+        model: model_id,
+        messages: vec![MessageParam {
+            role: MessageRole::User,
+            content: MessageContent::Text(prompt),
+        }],
+        max_tokens: INLINE_MAX_TOKENS,
+        system: Some(SystemPrompt::Text(INLINE_SYSTEM_PROMPT.to_string())),
+        tools: None,
+        temperature: Some(0.3),
+        stop_sequences: None,
+        thinking: None,
+        stream: true,
+        top_p: None,
+        top_k: None,
+        metadata: None,
+    };
+
+    let mut formatter = InlineFormatter::default();
+    let result = async {
+        let mut stream = service
+            .call_stream(request, token.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        while let Some(event) = stream.next().await {
+            match event.map_err(|e| e.to_string())? {
+                StreamEvent::ContentBlockDelta {
+                    delta: ContentDelta::TextDelta { text },
+                    ..
+                } if inject(pane, formatter.feed(&text)).is_err() => {
+                    // 面板已关闭
+                    token.cancel();
+                    break;
+                }
+                StreamEvent::Error { error } => return Err(error.message),
+                _ => {}
+            }
+        }
+        Ok::<(), String>(())
+    }
+    .await;
+
+    {
+        let mut asks = inline_asks().lock();
+        if asks.get(&pane_id).is_some_and(|(id, _)| *id == ask_id) {
+            asks.remove(&pane_id);
+        }
+    }
+
+    let cancelled = token.is_cancelled();
+    let note = match (&result, cancelled) {
+        (Err(_), _) => Some("[request failed]"),
+        (Ok(()), true) => Some("[cancelled]"),
+        (Ok(()), false) => None,
+    };
+    let _ = inject(pane, formatter.finish(note));
+
+    result.map(|()| {
+        if cancelled {
+            InlineAskOutcome::Cancelled
+        } else {
+            InlineAskOutcome::Completed
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_stream_as_comment_block_and_strips_escapes() {
+        let mut formatter = InlineFormatter::default();
+        let mut out = formatter.feed("Run:\n\n  ls -la");
+        out.push_str(&formatter.feed("\x1b]0;pwned\x07\u{1b}[2J\rdone\u{7}"));
+        out.push_str(&formatter.finish(Some("[cancelled]")));
+        assert_eq!(
+            out,
+            "\r\n\x1b[2m# Run:\r\n#\r\n#   ls -la\r\n# done\r\n# [cancelled]\x1b[0m\r\n"
+        );
+        assert_eq!(InlineFormatter::default().finish(None), "");
+    }
+}
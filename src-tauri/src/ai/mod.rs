@@ -1,5 +1,7 @@
 pub mod commands;
 pub mod error;
+pub mod inline_ask;
+pub mod model_metadata;
 pub mod service;
 pub mod tool;
 pub mod types;
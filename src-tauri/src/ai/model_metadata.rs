@@ -0,0 +1,153 @@
+//! 模型元数据缓存
+//!
+//! ReAct 循环每轮都要读取模型的上下文窗口，直接查库代价不小。
+//! 元数据从 AIModels 读取后放入 UnifiedCache 的 Models 命名空间，模型配置变更时整体失效。
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::llm::providers::{DEFAULT_STREAM_REQUEST_TIMEOUT, MAX_STREAM_REQUEST_TIMEOUT};
+
+use crate::storage::cache::{CacheNamespace, UnifiedCache};
+use crate::storage::repositories::{AIModelConfig, AIModels, AppPreferences};
+use crate::storage::DatabaseManager;
+
+/// 是否在启动时预热 tokenizer 与模型元数据，值为 "true" / "false"，默认开启
+pub const PREWARM_ON_STARTUP_KEY: &str = "agent.prewarm_on_startup";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelMetadata {
+    /// 模型选项中的 maxContextTokens
+    pub context_window: Option<u32>,
+    /// 模型选项中的 pricePerMillionTokens
+    pub price_per_million_tokens: Option<f64>,
+    /// 模型选项中的 requestTimeoutMs，流式请求的整体超时
+    #[serde(default)]
+    pub request_timeout_ms: Option<u64>,
+}
+
+impl ModelMetadata {
+    pub fn from_model(model: &AIModelConfig) -> Self {
+        let option = |key: &str| model.options.as_ref().and_then(|opts| opts.get(key));
+        Self {
+            context_window: option("maxContextTokens")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32),
+            price_per_million_tokens: option("pricePerMillionTokens").and_then(|v| v.as_f64()),
+            request_timeout_ms: option("requestTimeoutMs")
+                .and_then(|v| v.as_u64())
+                .filter(|ms| *ms > 0),
+        }
+    }
+
+    /// 流式请求的整体超时：模型未配置时使用默认值，且不超过 HTTP 层上限
+    pub fn stream_request_timeout(&self) -> Duration {
+        self.request_timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_STREAM_REQUEST_TIMEOUT)
+            .min(MAX_STREAM_REQUEST_TIMEOUT)
+    }
+}
+
+pub async fn prewarm_enabled(database: &DatabaseManager) -> bool {
+    AppPreferences::new(database)
+        .get(PREWARM_ON_STARTUP_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_none_or(|v| v != "false")
+}
+
+/// 读取模型元数据：先查缓存，未命中时查库并回填；模型不存在时返回 None
+pub async fn model_metadata(
+    database: &DatabaseManager,
+    cache: &UnifiedCache,
+    model_id: &str,
+) -> Option<ModelMetadata> {
+    if let Ok(Some(cached)) = cache
+        .get_deserialized_ns::<ModelMetadata>(CacheNamespace::Models, model_id)
+        .await
+    {
+        return Some(cached);
+    }
+
+    let model = AIModels::new(database).find_by_id(model_id).await.ok()??;
+    let metadata = ModelMetadata::from_model(&model);
+    if let Err(e) = cache
+        .set_serialized_ns(CacheNamespace::Models, model_id, &metadata)
+        .await
+    {
+        warn!("缓存模型元数据失败: {}", e);
+    }
+    Some(metadata)
+}
+
+/// 把所有模型的元数据写入缓存，返回写入数量
+pub async fn prewarm_model_metadata(database: &DatabaseManager, cache: &UnifiedCache) -> usize {
+    let models = match AIModels::new(database).find_all().await {
+        Ok(models) => models,
+        Err(e) => {
+            warn!("预热模型元数据失败: {}", e);
+            return 0;
+        }
+    };
+
+    let mut count = 0;
+    for model in &models {
+        let metadata = ModelMetadata::from_model(model);
+        if cache
+            .set_serialized_ns(CacheNamespace::Models, &model.id, &metadata)
+            .await
+            .is_ok()
+        {
+            count += 1;
+        }
+    }
+    debug!("已预热 {} 个模型的元数据", count);
+    count
+}
+
+/// 模型增删改后清空缓存，下次读取时重新查库
+pub async fn invalidate_model_metadata(cache: &UnifiedCache) {
+    cache.clear_namespace(CacheNamespace::Models).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::repositories::AIProvider;
+    use serde_json::json;
+
+    #[test]
+    fn metadata_reads_model_options() {
+        let mut model = AIModelConfig::new(
+            AIProvider::Anthropic,
+            "https://api.example.com".to_string(),
+            "key".to_string(),
+            "m".to_string(),
+        );
+        assert_eq!(ModelMetadata::from_model(&model), ModelMetadata::default());
+        assert_eq!(
+            ModelMetadata::default().stream_request_timeout(),
+            DEFAULT_STREAM_REQUEST_TIMEOUT
+        );
+
+        model.options = Some(json!({
+            "maxContextTokens": 200000,
+            "pricePerMillionTokens": 3.0,
+            "requestTimeoutMs": 1_800_000
+        }));
+        let metadata = ModelMetadata::from_model(&model);
+        assert_eq!(metadata.context_window, Some(200_000));
+        assert_eq!(metadata.price_per_million_tokens, Some(3.0));
+        assert_eq!(metadata.stream_request_timeout(), Duration::from_secs(1800));
+
+        model.options = Some(json!({ "requestTimeoutMs": 86_400_000 }));
+        assert_eq!(
+            ModelMetadata::from_model(&model).stream_request_timeout(),
+            MAX_STREAM_REQUEST_TIMEOUT
+        );
+    }
+}
@@ -5,14 +5,23 @@
  * This module now focuses solely on terminal command implementations.
  */
 
+use std::sync::Arc;
+
 use tauri::{AppHandle, Runtime, State};
-use tracing::error;
+use tracing::{error, warn};
 
+use crate::config::ansi_palette::apply_palette_to_pane;
+use crate::config::commands::ConfigManagerState;
+use crate::config::cursor::apply_cursor_to_pane;
+use crate::config::types::TerminalConfig as AppTerminalConfig;
 use crate::mux::{
-    get_mux, PaneId, PtySize, ShellConfig, ShellInfo, ShellManager, ShellManagerStats,
-    TerminalConfig,
+    get_mux, resolve_output_encoding, CellCoord, PaneId, PtySize, SelectionOptions, ShellConfig,
+    ShellInfo, ShellManager, ShellManagerStats, TerminalConfig,
 };
+use crate::shell::ShellTypeOverrides;
+use crate::storage::DatabaseManager;
 use crate::utils::{ApiResponse, EmptyData, TauriApiResult};
+use crate::workspace::WorkspaceService;
 use crate::{api_error, api_success};
 
 /// 参数验证辅助函数
@@ -48,6 +57,35 @@ impl TerminalState {
     }
 }
 
+/// 读取应用配置中的终端配置，用于新建面板的输出编码与光标
+async fn configured_terminal(config: &ConfigManagerState) -> Option<AppTerminalConfig> {
+    match config.toml_manager.config_get().await {
+        Ok(app_config) => Some(app_config.terminal),
+        Err(e) => {
+            warn!("读取终端配置失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 设置面板初始目录，并合并该目录所属工作区的环境变量
+///
+/// 工作区环境变量覆盖继承环境，但不覆盖显式设置的变量
+async fn apply_initial_directory(
+    shell_config: &mut ShellConfig,
+    working_dir: &str,
+    database: &Arc<DatabaseManager>,
+) {
+    shell_config.working_directory = Some(working_dir.into());
+    match WorkspaceService::new(Arc::clone(database))
+        .resolve_pty_env_for_cwd(working_dir)
+        .await
+    {
+        Ok(env) => shell_config.merge_env_defaults(env),
+        Err(e) => warn!("Failed to resolve workspace pty env: {}", e),
+    }
+}
+
 /// 创建新终端会话
 ///
 #[tauri::command]
@@ -57,6 +95,8 @@ pub async fn terminal_create<R: Runtime>(
     cwd: Option<String>,
     _app: AppHandle<R>,
     _state: State<'_, TerminalState>,
+    database: State<'_, Arc<DatabaseManager>>,
+    config_state: State<'_, ConfigManagerState>,
 ) -> TauriApiResult<u32> {
     if !terminal_size_valid(rows, cols) {
         return Ok(api_error!("shell.terminal_size_invalid"));
@@ -64,18 +104,28 @@ pub async fn terminal_create<R: Runtime>(
 
     let mux = get_mux();
     let size = PtySize::new(rows, cols);
+    let app_terminal = configured_terminal(&config_state).await;
+    let output_encoding = app_terminal.as_ref().map(|t| t.output_encoding.clone());
 
     // 根据是否指定初始目录选择创建方式
     let result = if let Some(working_dir) = cwd {
         let mut shell_config = ShellConfig::with_default_shell();
-        shell_config.working_directory = Some(working_dir.clone().into());
-        let config = TerminalConfig::with_shell(shell_config);
+        apply_initial_directory(&mut shell_config, &working_dir, &database).await;
+
+        let mut config = TerminalConfig::with_shell(shell_config);
+        config.output_encoding = output_encoding;
 
         mux.create_pane_with_config(size, &config)
             .await
             .map(|pane_id| (pane_id, Some(working_dir)))
     } else {
-        mux.create_pane(size).await.map(|pane_id| (pane_id, None))
+        let config = TerminalConfig {
+            output_encoding,
+            ..TerminalConfig::default()
+        };
+        mux.create_pane_with_config(size, &config)
+            .await
+            .map(|pane_id| (pane_id, None))
     };
 
     match result {
@@ -84,6 +134,15 @@ pub async fn terminal_create<R: Runtime>(
             if let Some(initial_cwd) = &working_dir {
                 mux.shell_update_pane_cwd(pane_id, initial_cwd.clone());
             }
+            if let Some(app_terminal) = &app_terminal {
+                apply_cursor_to_pane(&mux, pane_id, &app_terminal.cursor);
+                apply_palette_to_pane(
+                    &mux,
+                    pane_id,
+                    &app_terminal.ansi_overrides,
+                    &Default::default(),
+                );
+            }
 
             Ok(api_success!(pane_id.as_u32()))
         }
@@ -91,24 +150,88 @@ pub async fn terminal_create<R: Runtime>(
     }
 }
 
+/// 单次写入 PTY 的最大字节数，超过则分块写入
+const WRITE_CHUNK_SIZE: usize = 4096;
+const PASTE_START: &str = "\x1b[200~";
+const PASTE_END: &str = "\x1b[201~";
+
+/// 用 bracketed paste 标记包裹文本，并去掉内容中伪造的结束标记，避免粘贴内容提前“逃逸”执行
+fn wrap_bracketed_paste(data: &str) -> String {
+    let sanitized = data.replace(PASTE_END, "");
+    let mut wrapped = String::with_capacity(sanitized.len() + PASTE_START.len() + PASTE_END.len());
+    wrapped.push_str(PASTE_START);
+    wrapped.push_str(&sanitized);
+    wrapped.push_str(PASTE_END);
+    wrapped
+}
+
 /// 向终端写入数据
 ///
+/// - `paste`: 作为粘贴内容写入，shell 声明支持时使用 bracketed paste 包裹
+/// - `raw`: 强制原样一次性写入（程序化控制序列），跳过包裹与分块
 #[tauri::command]
 pub async fn terminal_write(
     pane_id: u32,
     data: String,
+    paste: Option<bool>,
+    raw: Option<bool>,
     _state: State<'_, TerminalState>,
 ) -> TauriApiResult<EmptyData> {
     if data.is_empty() {
         return Ok(api_error!("common.empty_content"));
     }
 
+    // Ctrl+C 同时中断该面板上的内联提问
+    if data.contains('\u{3}') {
+        crate::ai::inline_ask::cancel(pane_id);
+    }
+
     let mux = get_mux();
     let pane_id_obj = PaneId::from(pane_id);
 
-    match mux.write_to_pane(pane_id_obj, data.as_bytes()) {
-        Ok(_) => Ok(api_success!()),
-        Err(_) => Ok(api_error!("shell.write_terminal_failed")),
+    if raw.unwrap_or(false) {
+        return match mux.write_to_pane(pane_id_obj, data.as_bytes()) {
+            Ok(_) => Ok(api_success!()),
+            Err(_) => Ok(api_error!("shell.write_terminal_failed")),
+        };
+    }
+
+    let payload = if paste.unwrap_or(false)
+        && mux
+            .shell_integration()
+            .is_bracketed_paste_enabled(pane_id_obj)
+    {
+        wrap_bracketed_paste(&data)
+    } else {
+        data
+    };
+
+    if payload.len() <= WRITE_CHUNK_SIZE {
+        return match mux.write_to_pane(pane_id_obj, payload.as_bytes()) {
+            Ok(_) => Ok(api_success!()),
+            Err(_) => Ok(api_error!("shell.write_terminal_failed")),
+        };
+    }
+
+    // 大块写入放到阻塞线程：PTY 输入缓冲满时 write 会阻塞，天然形成背压
+    let result = tokio::task::spawn_blocking(move || {
+        for chunk in payload.as_bytes().chunks(WRITE_CHUNK_SIZE) {
+            mux.write_to_pane(pane_id_obj, chunk)?;
+        }
+        Ok::<(), crate::mux::TerminalMuxError>(())
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => Ok(api_success!()),
+        Ok(Err(e)) => {
+            warn!("Chunked write to pane {} failed: {}", pane_id, e);
+            Ok(api_error!("shell.write_terminal_failed"))
+        }
+        Err(e) => {
+            error!("Chunked write task panicked: {}", e);
+            Ok(api_error!("shell.write_terminal_failed"))
+        }
     }
 }
 
@@ -129,7 +252,8 @@ pub async fn terminal_resize(
     let pane_id_obj = PaneId::from(pane_id);
     let size = PtySize::new(rows, cols);
 
-    match mux.resize_pane(pane_id_obj, size) {
+    // 拖动窗口时会连续触发大量 resize，这里防抖后只应用最终尺寸
+    match mux.resize_pane_debounced(pane_id_obj, size).await {
         Ok(_) => Ok(api_success!()),
         Err(err) => match err {
             crate::mux::error::TerminalMuxError::PaneNotFound { .. } => Ok(api_success!()),
@@ -138,16 +262,132 @@ pub async fn terminal_resize(
     }
 }
 
+/// 设置终端的 PTY 输出编码，返回规范化后的编码名
+///
+/// 显式指定后该面板不再自动检测编码。
+#[tauri::command]
+pub async fn terminal_set_output_encoding(
+    pane_id: u32,
+    encoding: String,
+) -> TauriApiResult<String> {
+    let Some(encoding) = resolve_output_encoding(&encoding) else {
+        return Ok(api_error!("shell.invalid_output_encoding"));
+    };
+
+    match get_mux().set_pane_output_encoding(PaneId::from(pane_id), encoding) {
+        Ok(()) => Ok(api_success!(encoding.name().to_lowercase())),
+        Err(err) => {
+            warn!("设置终端输出编码失败: {}", err);
+            Ok(api_error!("shell.pane_not_exist"))
+        }
+    }
+}
+
+/// 获取终端当前的 PTY 输出编码（可能已被自动检测切换）
+#[tauri::command]
+pub async fn terminal_get_output_encoding(pane_id: u32) -> TauriApiResult<String> {
+    match get_mux().get_pane_output_encoding(PaneId::from(pane_id)) {
+        Some(encoding) => Ok(api_success!(encoding.name().to_lowercase())),
+        None => Ok(api_error!("shell.pane_not_exist")),
+    }
+}
+
+/// 取出终端中 start 到 end 单元格之间的文本（行号从滚动历史第一行开始），
+/// rectangular 为 true 时按块选处理
+#[tauri::command]
+pub async fn terminal_get_selection_text(
+    pane_id: u32,
+    start: CellCoord,
+    end: CellCoord,
+    rectangular: Option<bool>,
+    trim_trailing_whitespace: Option<bool>,
+) -> TauriApiResult<String> {
+    let options = SelectionOptions {
+        rectangular: rectangular.unwrap_or(false),
+        trim_trailing_whitespace: trim_trailing_whitespace.unwrap_or(true),
+    };
+    match get_mux().get_selection_text(PaneId::from(pane_id), start, end, options) {
+        Ok(text) => Ok(api_success!(text)),
+        Err(err) => {
+            warn!("获取终端选区文本失败: {}", err);
+            Ok(api_error!("terminal.pane_not_found"))
+        }
+    }
+}
+
+/// 设置终端标题
+///
+/// `sticky` 为 true 时标题会一直保留，否则 shell 之后上报的标题会重新接管。
+#[tauri::command]
+pub async fn terminal_set_title<R: Runtime>(
+    pane_id: u32,
+    title: String,
+    sticky: Option<bool>,
+    app: AppHandle<R>,
+) -> TauriApiResult<EmptyData> {
+    use tauri::Manager;
+
+    let title = title.trim().to_string();
+    if title.is_empty() {
+        return Ok(api_error!("common.empty_content"));
+    }
+    let sticky = sticky.unwrap_or(false);
+
+    let mux = get_mux();
+    if let Err(err) = mux.set_pane_title(PaneId::from(pane_id), title.clone(), sticky) {
+        warn!("设置终端标题失败: {}", err);
+        return Ok(api_error!("terminal.pane_not_found"));
+    }
+
+    if let Some(dock_manager) = app.try_state::<crate::dock::DockManager<R>>() {
+        if let Err(e) = dock_manager.set_pane_title(pane_id, &title, sticky) {
+            warn!("同步 Dock 标签标题失败: {}", e);
+        }
+    }
+
+    Ok(api_success!())
+}
+
+/// 获取终端最近一次已知的工作目录
+#[tauri::command]
+pub async fn terminal_get_cwd(
+    pane_id: u32,
+    state: State<'_, crate::terminal::commands::TerminalContextState>,
+) -> TauriApiResult<Option<String>> {
+    match state
+        .context_service
+        .shell_get_pane_cwd(PaneId::from(pane_id))
+        .await
+    {
+        Ok(cwd) => Ok(api_success!(Some(cwd))),
+        Err(crate::terminal::ContextServiceError::WorkingDirectoryMissing) => {
+            Ok(api_success!(None))
+        }
+        Err(e) => {
+            warn!("获取终端工作目录失败: {}", e);
+            Ok(api_error!("terminal.pane_not_found"))
+        }
+    }
+}
+
 /// 关闭终端会话
 ///
 #[tauri::command]
 pub async fn terminal_close(
     pane_id: u32,
     _state: State<'_, TerminalState>,
+    shell_overrides: State<'_, Arc<ShellTypeOverrides>>,
 ) -> TauriApiResult<EmptyData> {
     let mux = get_mux();
     let pane_id_obj = PaneId::from(pane_id);
 
+    // 面板关闭后其 Shell 类型覆盖不再需要（应用退出不会走这里，覆盖得以保留到恢复）
+    if let Err(e) = shell_overrides.clear_pane(pane_id_obj).await {
+        warn!("清除Shell类型覆盖失败: {}", e);
+    }
+
+    crate::ai::inline_ask::cancel(pane_id);
+
     // 原子操作：直接尝试删除面板，避免检查和删除之间的竞态条件
     match mux.remove_pane(pane_id_obj) {
         Ok(_) => Ok(api_success!()),
@@ -210,8 +450,11 @@ pub async fn terminal_create_with_shell<R: Runtime>(
     shell_name: Option<String>,
     rows: u16,
     cols: u16,
+    cwd: Option<String>,
     _app: AppHandle<R>,
     _state: State<'_, TerminalState>,
+    database: State<'_, Arc<DatabaseManager>>,
+    config_state: State<'_, ConfigManagerState>,
 ) -> TauriApiResult<u32> {
     if rows == 0 || cols == 0 {
         return Ok(api_error!("shell.terminal_size_invalid"));
@@ -231,12 +474,31 @@ pub async fn terminal_create_with_shell<R: Runtime>(
     let mux = get_mux();
     let size = PtySize::new(rows, cols);
 
-    let shell_config = ShellConfig::with_shell(shell_info);
-    let config = TerminalConfig::with_shell(shell_config);
+    let mut shell_config = ShellConfig::with_shell(shell_info);
+    if let Some(working_dir) = &cwd {
+        apply_initial_directory(&mut shell_config, working_dir, &database).await;
+    }
+    let mut config = TerminalConfig::with_shell(shell_config);
+    let app_terminal = configured_terminal(&config_state).await;
+    config.output_encoding = app_terminal.as_ref().map(|t| t.output_encoding.clone());
 
     // 使用配置创建面板
     match mux.create_pane_with_config(size, &config).await {
-        Ok(pane_id) => Ok(api_success!(pane_id.as_u32())),
+        Ok(pane_id) => {
+            if let Some(initial_cwd) = cwd {
+                mux.shell_update_pane_cwd(pane_id, initial_cwd);
+            }
+            if let Some(app_terminal) = &app_terminal {
+                apply_cursor_to_pane(&mux, pane_id, &app_terminal.cursor);
+                apply_palette_to_pane(
+                    &mux,
+                    pane_id,
+                    &app_terminal.ansi_overrides,
+                    &Default::default(),
+                );
+            }
+            Ok(api_success!(pane_id.as_u32()))
+        }
         Err(_) => {
             error!("创建终端失败");
             Ok(api_error!("shell.create_terminal_failed"))
@@ -18,5 +18,6 @@ pub use models::{
     Checkpoint, CheckpointError, CheckpointResult, CheckpointSummary, FileChangeType, FileDiff,
     FileSnapshot, NewCheckpoint, NewFileSnapshot, RollbackResult,
 };
+pub(crate) use service::compute_diff;
 pub use service::CheckpointService;
 pub use storage::CheckpointStorage;
@@ -483,7 +483,7 @@ fn path_to_unix_string(path: &Path) -> String {
         .join("/")
 }
 
-fn compute_diff(from: &[u8], to: &[u8]) -> String {
+pub(crate) fn compute_diff(from: &[u8], to: &[u8]) -> String {
     let from_str = String::from_utf8_lossy(from).into_owned();
     let to_str = String::from_utf8_lossy(to).into_owned();
     let patch = create_patch(&from_str, &to_str);
@@ -32,8 +32,20 @@ pub async fn file_handle_open(path: String) -> TauriApiResult<String> {
     }
 }
 
+/// 在命令分发前为用户主动发起的命令记录一次交互
+fn track_user_input<R: tauri::Runtime>(
+    handler: impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        if crate::setup::idle::is_user_input_command(invoke.message.command()) {
+            crate::setup::idle::record_activity();
+        }
+        handler(invoke)
+    }
+}
+
 pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> tauri::Builder<R> {
-    builder.invoke_handler(tauri::generate_handler![
+    builder.invoke_handler(track_user_input(tauri::generate_handler![
         // 文件拖拽命令
         file_handle_open,
         // Dock 菜单命令
@@ -42,6 +54,7 @@ pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> t
         crate::dock::commands::dock_clear_tabs,
         // 工作区管理命令（来自 workspace 模块）
         crate::workspace::commands::workspace_get_recent,
+        crate::workspace::commands::workspace_get_recent_detailed,
         crate::workspace::commands::workspace_add_recent,
         crate::workspace::commands::workspace_remove_recent,
         crate::workspace::commands::workspace_maintain,
@@ -51,9 +64,12 @@ pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> t
         crate::workspace::commands::workspace_get_active_session,
         crate::workspace::commands::workspace_create_session,
         crate::workspace::commands::workspace_set_active_session,
+        crate::workspace::commands::workspace_set_session_model,
         crate::workspace::commands::workspace_get_project_rules,
         crate::workspace::commands::workspace_set_project_rules,
         crate::workspace::commands::workspace_list_rules_files,
+        crate::workspace::commands::workspace_get_pty_env,
+        crate::workspace::commands::workspace_set_pty_env,
         // 窗口管理命令
         crate::window::commands::window_manage_state,
         crate::window::commands::window_get_current_directory,
@@ -65,10 +81,17 @@ pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> t
         crate::window::commands::window_get_platform_info,
         crate::window::commands::window_set_opacity,
         crate::window::commands::window_get_opacity,
+        crate::window::commands::window_save_layout,
+        crate::window::commands::window_restore_layout,
         // 终端管理命令
         crate::ai::tool::shell::terminal_create,
         crate::ai::tool::shell::terminal_write,
         crate::ai::tool::shell::terminal_resize,
+        crate::ai::tool::shell::terminal_set_output_encoding,
+        crate::ai::tool::shell::terminal_get_output_encoding,
+        crate::ai::tool::shell::terminal_get_selection_text,
+        crate::ai::tool::shell::terminal_set_title,
+        crate::ai::tool::shell::terminal_get_cwd,
         crate::ai::tool::shell::terminal_close,
         crate::ai::tool::shell::terminal_list,
         crate::ai::tool::shell::terminal_get_available_shells,
@@ -98,10 +121,14 @@ pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> t
         crate::shell::commands::shell_execute_background_command,
         crate::shell::commands::shell_execute_background_program,
         crate::shell::commands::shell_setup_integration,
+        crate::shell::commands::shell_setup_integration_all,
+        crate::shell::commands::shell_preview_integration,
+        crate::shell::commands::generate_integration_script_preview,
         crate::shell::commands::shell_check_integration_status,
         crate::shell::commands::shell_update_pane_cwd,
         crate::shell::commands::get_pane_shell_state,
         crate::shell::commands::set_pane_shell_type,
+        crate::shell::commands::restore_pane_shell_type,
         crate::shell::commands::generate_shell_integration_script,
         crate::shell::commands::generate_shell_env_vars,
         crate::shell::commands::enable_pane_integration,
@@ -124,6 +151,9 @@ pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> t
         crate::git::commands::git_get_diff,
         crate::git::commands::git_watch_start,
         crate::git::commands::git_watch_stop,
+        crate::git::commands::git_watch_pause,
+        crate::git::commands::git_watch_resume,
+        crate::git::commands::git_watch_stats,
         crate::git::commands::git_watch_status,
         // 配置管理命令
         crate::config::commands::config_get,
@@ -131,6 +161,8 @@ pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> t
         crate::config::commands::config_save,
         crate::config::commands::config_validate,
         crate::config::commands::config_reset_to_defaults,
+        crate::config::commands::config_diff_from_defaults,
+        crate::config::commands::config_reset_key,
         crate::config::commands::config_get_file_path,
         crate::config::commands::config_get_file_info,
         crate::config::commands::config_open_file,
@@ -181,6 +213,10 @@ pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> t
         crate::ai::commands::ai_models_update,
         crate::ai::commands::ai_models_remove,
         crate::ai::commands::ai_models_test_connection,
+        crate::ai::commands::ai_test_embedding_model,
+        crate::ai::commands::ai_validate_model_config,
+        crate::ai::commands::ai_ask_inline,
+        crate::ai::commands::ai_ask_inline_cancel,
         // 新Agent双轨上下文命令由 agent::core::commands 提供
         // LLM 调用命令
         crate::llm::commands::llm_call,
@@ -190,12 +226,40 @@ pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> t
         crate::llm::commands::llm_get_providers,
         // Agent 执行器命令（注册以供前端调用）
         crate::agent::core::commands::agent_execute_task,
+        crate::agent::core::commands::agent_explain_terminal_error,
+        crate::agent::core::commands::agent_summarize_path,
         crate::agent::core::commands::agent_cancel_task,
+        crate::agent::core::commands::agent_cancel_all_tasks,
+        crate::agent::core::commands::agent_force_reset_task,
+        crate::agent::core::commands::agent_rebuild_messages_from_ui,
+        crate::agent::core::commands::agent_export_conversation,
+        crate::agent::core::commands::agent_import_conversation,
+        crate::agent::core::commands::agent_get_final_answer,
+        crate::agent::core::commands::agent_get_effective_config,
+        crate::agent::core::commands::agent_get_rendered_system_prompt,
+        crate::agent::core::commands::agent_cancel_tool,
+        crate::agent::core::commands::agent_pause_task,
+        crate::agent::core::commands::agent_resume_task,
+        crate::agent::core::commands::agent_retry_tool,
+        crate::agent::core::commands::agent_get_memory,
+        crate::agent::core::commands::agent_get_react_trace,
         crate::agent::core::commands::agent_tool_confirm,
+        crate::agent::core::commands::agent_resolve_write_preview,
         crate::agent::core::commands::agent_list_tasks,
+        crate::agent::core::commands::agent_get_max_concurrent_tasks,
+        crate::agent::core::commands::agent_set_max_concurrent_tasks,
+        crate::agent::core::commands::agent_get_execution_messages,
         crate::agent::core::commands::agent_get_file_context_status,
         crate::agent::core::commands::agent_get_user_rules,
         crate::agent::core::commands::agent_set_user_rules,
+        crate::agent::core::commands::agent_get_web_fetch_domains,
+        crate::agent::core::commands::agent_set_web_fetch_domains,
+        crate::agent::core::commands::agent_get_recall_history_enabled,
+        crate::agent::core::commands::agent_set_recall_history_enabled,
+        crate::agent::core::commands::agent_get_git_branch_push_enabled,
+        crate::agent::core::commands::agent_set_git_branch_push_enabled,
+        crate::agent::core::commands::agent_get_preview_writes_enabled,
+        crate::agent::core::commands::agent_set_preview_writes_enabled,
         crate::agent::core::commands::agent_trigger_session_summary,
         // 项目规则命令已迁移到 workspace 模块
         // 存储系统命令（State/Runtime）
@@ -214,11 +278,24 @@ pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> t
         crate::node::commands::node_get_switch_command,
         // 向量数据库命令
         crate::vector_db::commands::semantic_search,
+        crate::vector_db::commands::semantic_search_jsonl,
+        crate::vector_db::commands::semantic_search_global,
         crate::vector_db::commands::get_index_status,
+        crate::vector_db::commands::vector_index_list_files,
+        crate::vector_db::commands::vector_index_find_duplicates,
+        crate::vector_db::commands::vector_index_benchmark,
+        crate::vector_db::commands::vector_index_list_collections,
+        crate::vector_db::commands::vector_index_supported_languages,
+        crate::vector_db::commands::vector_db_get_status,
+        crate::vector_db::commands::vector_db_select_embedding_model,
+        crate::vector_db::commands::vector_index_file_renamed,
+        crate::vector_db::commands::vector_index_estimate,
         crate::vector_db::commands::delete_workspace_index,
         crate::vector_db::commands::vector_build_index_start,
         crate::vector_db::commands::vector_build_index_status,
         crate::vector_db::commands::vector_build_index_subscribe,
+        crate::vector_db::commands::vector_build_index_run,
+        crate::vector_db::commands::vector_build_index_set_spend_cap,
         crate::vector_db::commands::vector_build_index_cancel,
         // Checkpoint 系统命令
         crate::checkpoint::commands::checkpoint_create,
@@ -230,5 +307,13 @@ pub fn register_all_commands<R: tauri::Runtime>(builder: tauri::Builder<R>) -> t
         crate::checkpoint::commands::checkpoint_delete,
         // 文件系统命令
         crate::filesystem::commands::fs_read_dir,
-    ])
+        crate::filesystem::commands::filesystem_tail,
+        crate::filesystem::commands::filesystem_tail_cancel,
+        // 日志命令
+        crate::setup::logging::logs_get_path,
+        crate::setup::safe_mode::app_get_safe_mode_status,
+        crate::setup::idle::app_is_idle,
+        crate::setup::idle::app_get_idle_threshold,
+        crate::setup::idle::app_set_idle_threshold,
+    ]))
 }
@@ -11,7 +11,9 @@ use crate::storage::repositories::CompletionModelRepo;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tracing::warn;
 
 #[derive(Debug, Clone, Copy)]
@@ -74,19 +76,23 @@ impl CompletionLearningState {
             tauri::async_runtime::spawn(async move {
                 let mut per_pane_prev: HashMap<u32, i64> = HashMap::new();
                 let mut seen = 0_u64;
+                let mut maintenance: Option<JoinHandle<()>> = None;
 
                 while let Some(event) = receiver.recv().await {
-                    if let Err(err) = apply_finished_event(
-                        &database,
-                        config,
-                        &mut per_pane_prev,
-                        &event,
-                        &mut seen,
-                    )
-                    .await
+                    if let Err(err) =
+                        apply_finished_event(&database, config, &mut per_pane_prev, &event).await
                     {
                         warn!(error = %err, "completion.learning.apply_failed");
                     }
+
+                    // 维护到期后单独排队，等用户空闲再执行；已有排队任务时不重复创建
+                    seen = seen.saturating_add(1);
+                    if seen >= config.maintenance_every
+                        && maintenance.as_ref().is_none_or(JoinHandle::is_finished)
+                    {
+                        seen = 0;
+                        maintenance = Some(spawn_maintenance(Arc::clone(&database), config));
+                    }
                 }
             });
 
@@ -100,7 +106,6 @@ async fn apply_finished_event(
     config: CompletionLearningConfig,
     per_pane_prev: &mut HashMap<u32, i64>,
     event: &CommandFinishedEvent,
-    seen: &mut u64,
 ) -> crate::storage::error::RepositoryResult<()> {
     let Some(key) = extract_command_key(&event.command_line) else {
         return Ok(());
@@ -129,15 +134,33 @@ async fn apply_finished_event(
 
     per_pane_prev.insert(event.pane_id, current_id);
 
-    *seen = seen.saturating_add(1);
-    if *seen % config.maintenance_every == 0 {
-        let cutoff_ts = event
-            .finished_ts
-            .saturating_sub(config.ttl_days.saturating_mul(24 * 60 * 60));
-        repo.prune_older_than(cutoff_ts).await?;
-        repo.enforce_command_key_limit(config.max_command_keys)
-            .await?;
-    }
-
     Ok(())
 }
+
+/// 清理过期数据并限制模型体积；推迟到用户空闲后执行
+fn spawn_maintenance(
+    database: Arc<DatabaseManager>,
+    config: CompletionLearningConfig,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        crate::setup::idle::wait_until_idle().await;
+        if let Err(err) = run_maintenance(&database, config).await {
+            warn!(error = %err, "completion.learning.maintenance_failed");
+        }
+    })
+}
+
+async fn run_maintenance(
+    database: &DatabaseManager,
+    config: CompletionLearningConfig,
+) -> crate::storage::error::RepositoryResult<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let cutoff_ts = now.saturating_sub(config.ttl_days.saturating_mul(24 * 60 * 60));
+    let repo = CompletionModelRepo::new(database);
+    repo.prune_older_than(cutoff_ts).await?;
+    repo.enforce_command_key_limit(config.max_command_keys)
+        .await
+}
@@ -0,0 +1,153 @@
+/*!
+ * 终端 ANSI 调色板覆盖
+ *
+ * 在当前主题的调色板之上覆盖个别颜色索引（0-255），与主题相互独立，切换主题后仍然保留。
+ * 新建面板与运行中修改时通过 OSC 4 注入面板输出，被移除的索引用 OSC 104 恢复为主题颜色；
+ * 同时广播事件，前端在重新应用主题时合并这些覆盖。
+ */
+
+use std::collections::{BTreeMap, HashMap};
+
+use bytes::Bytes;
+use serde::{Deserialize, Deserializer, Serializer};
+use tracing::warn;
+
+use crate::mux::{PaneId, TerminalMux};
+
+/// 调色板覆盖变更事件，payload 为新的覆盖表（索引 -> 颜色）
+pub const ANSI_OVERRIDES_CHANGED_EVENT: &str = "terminal_ansi_overrides_changed";
+
+pub type AnsiOverrides = HashMap<u8, String>;
+
+/// 校验覆盖颜色，只接受 `#rrggbb`，返回第一个不合法项的描述
+pub fn validate_ansi_overrides(overrides: &AnsiOverrides) -> Result<(), String> {
+    let mut indexes: Vec<_> = overrides.keys().copied().collect();
+    indexes.sort_unstable();
+    for index in indexes {
+        let color = &overrides[&index];
+        let hex = color.strip_prefix('#').unwrap_or_default();
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(format!("调色板颜色 {} 格式无效: {}", index, color));
+        }
+    }
+    Ok(())
+}
+
+/// 覆盖调色板的控制序列；previous 中存在而 overrides 中已移除的索引恢复为主题颜色
+pub fn palette_escape_sequence(overrides: &AnsiOverrides, previous: &AnsiOverrides) -> String {
+    let mut reset: Vec<u8> = previous
+        .keys()
+        .filter(|index| !overrides.contains_key(index))
+        .copied()
+        .collect();
+    reset.sort_unstable();
+    let set: BTreeMap<_, _> = overrides.iter().collect();
+
+    let mut sequence = String::new();
+    for index in reset {
+        sequence.push_str(&format!("\x1b]104;{}\x07", index));
+    }
+    for (index, color) in set {
+        sequence.push_str(&format!("\x1b]4;{};{}\x07", index, color));
+    }
+    sequence
+}
+
+/// 把调色板覆盖注入指定面板
+pub fn apply_palette_to_pane(
+    mux: &TerminalMux,
+    pane_id: PaneId,
+    overrides: &AnsiOverrides,
+    previous: &AnsiOverrides,
+) {
+    let sequence = palette_escape_sequence(overrides, previous);
+    if sequence.is_empty() {
+        return;
+    }
+    if let Err(e) = mux.inject_pane_output(pane_id, Bytes::from(sequence)) {
+        warn!(
+            "应用调色板覆盖失败: pane_id={}, err={}",
+            pane_id.as_u32(),
+            e
+        );
+    }
+}
+
+/// 把调色板覆盖注入所有已有面板
+pub fn apply_palette_to_all_panes(
+    mux: &TerminalMux,
+    overrides: &AnsiOverrides,
+    previous: &AnsiOverrides,
+) {
+    for pane_id in mux.list_panes() {
+        apply_palette_to_pane(mux, pane_id, overrides, previous);
+    }
+}
+
+/// TOML 的表键只能是字符串，索引按十进制字符串读写
+pub mod overrides_serde {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        overrides: &AnsiOverrides,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let sorted: BTreeMap<u8, &String> = overrides.iter().map(|(k, v)| (*k, v)).collect();
+        serializer.collect_map(sorted.into_iter().map(|(k, v)| (k.to_string(), v)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<AnsiOverrides, D::Error> {
+        let raw = HashMap::<String, String>::deserialize(deserializer)?;
+        raw.into_iter()
+            .map(|(k, v)| {
+                k.trim()
+                    .parse::<u8>()
+                    .map(|index| (index, v))
+                    .map_err(|_| serde::de::Error::custom(format!("无效的调色板索引: {}", k)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(entries: &[(u8, &str)]) -> AnsiOverrides {
+        entries.iter().map(|(k, v)| (*k, v.to_string())).collect()
+    }
+
+    #[test]
+    fn builds_osc4_and_resets_removed_indexes() {
+        let previous = overrides(&[(1, "#ff0000"), (8, "#888888")]);
+        let next = overrides(&[(8, "#aaaaaa"), (3, "#00ff00")]);
+        assert_eq!(
+            palette_escape_sequence(&next, &previous),
+            "\x1b]104;1\x07\x1b]4;3;#00ff00\x07\x1b]4;8;#aaaaaa\x07"
+        );
+        assert_eq!(
+            palette_escape_sequence(&overrides(&[]), &overrides(&[])),
+            ""
+        );
+    }
+
+    #[test]
+    fn overrides_round_trip_through_toml_string_keys() {
+        let mut config = crate::config::defaults::create_default_terminal_config();
+        config.ansi_overrides = overrides(&[(8, "#a0a0a0"), (10, "#00ff00")]);
+        let text = toml::to_string(&config).unwrap();
+        assert!(text.contains("[ansiOverrides]"));
+        let parsed: crate::config::types::TerminalConfig = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.ansi_overrides, config.ansi_overrides);
+    }
+
+    #[test]
+    fn validates_hex_colors() {
+        assert!(validate_ansi_overrides(&overrides(&[(8, "#A0a0a0"), (200, "#000000")])).is_ok());
+        assert!(validate_ansi_overrides(&overrides(&[(8, "a0a0a0")])).is_err());
+        assert!(validate_ansi_overrides(&overrides(&[(8, "#a0a0")])).is_err());
+        assert!(validate_ansi_overrides(&overrides(&[(8, "red")])).is_err());
+    }
+}
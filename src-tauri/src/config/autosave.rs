@@ -0,0 +1,121 @@
+//! 配置防抖自动保存
+//!
+//! config_update 只标记 dirty 并在静默期结束后写盘，连续更新合并为一次写入；
+//! config_save 与应用退出时调用 flush 立即落盘。
+
+use crate::config::error::ConfigResult;
+use crate::config::TomlConfigManager;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+pub struct ConfigAutoSaver {
+    manager: Arc<TomlConfigManager>,
+    dirty: AtomicBool,
+    /// 每次调度递增，定时器到期时只有最新一次调度会真正写盘
+    generation: AtomicU64,
+    write_lock: Mutex<()>,
+}
+
+impl ConfigAutoSaver {
+    pub fn new(manager: Arc<TomlConfigManager>) -> Arc<Self> {
+        Arc::new(Self {
+            manager,
+            dirty: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.load(Ordering::Acquire)
+    }
+
+    /// 标记缓存已修改，并在 delay 静默期后写盘；期间的新调度会顶替旧调度
+    pub fn schedule(self: &Arc<Self>, delay: Duration) {
+        self.dirty.store(true, Ordering::Release);
+        let generation = self.generation.fetch_add(1, Ordering::AcqRel) + 1;
+
+        let saver = Arc::clone(self);
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if saver.generation.load(Ordering::Acquire) != generation {
+                return;
+            }
+            if let Err(e) = saver.flush().await {
+                warn!("Debounced config save failed: {}", e);
+            }
+        });
+    }
+
+    /// 有未保存的修改时立即写盘（每次合并写入只产生一次 Saved 事件）
+    pub async fn flush(&self) -> ConfigResult<()> {
+        let _guard = self.write_lock.lock().await;
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return Ok(());
+        }
+
+        let result = match self.manager.config_get().await {
+            Ok(config) => self.manager.config_save(&config).await,
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            self.dirty.store(true, Ordering::Release);
+        }
+        result
+    }
+
+    /// 外部已完成一次完整写盘（如 config_save），丢弃挂起的防抖写入
+    pub fn mark_clean(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.dirty.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::paths::ConfigPaths;
+    use crate::config::ConfigEvent;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn coalesces_rapid_updates_into_one_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = ConfigPaths::with_app_data_dir(temp_dir.path()).unwrap();
+        let manager = Arc::new(
+            TomlConfigManager::new_for_test(paths.config_file())
+                .await
+                .unwrap(),
+        );
+        let mut events = manager.subscribe_changes();
+        let saver = ConfigAutoSaver::new(Arc::clone(&manager));
+
+        for opacity in [0.5, 0.6, 0.7] {
+            manager
+                .config_update_cached(|config| {
+                    config.appearance.opacity = opacity;
+                    Ok(())
+                })
+                .unwrap();
+            saver.schedule(Duration::from_millis(50));
+        }
+        assert!(saver.is_dirty());
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!saver.is_dirty());
+
+        let mut saved = 0;
+        while let Ok(event) = events.try_recv() {
+            if matches!(event, ConfigEvent::Saved { .. }) {
+                saved += 1;
+            }
+        }
+        assert_eq!(saved, 1);
+
+        let loaded = manager.load_config().await.unwrap();
+        assert_eq!(loaded.appearance.opacity, 0.7);
+    }
+}
@@ -1,8 +1,11 @@
+use crate::config::autosave::ConfigAutoSaver;
+use crate::config::diff::{diff_configs, reset_key, ConfigDiffEntry};
 use crate::config::error::ConfigResult;
 use crate::config::{defaults::create_default_config, types::AppConfig, TomlConfigManager};
 use crate::utils::{EmptyData, TauriApiResult};
 use crate::{api_error, api_success};
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::State;
 use tokio::sync::Mutex;
 
@@ -10,18 +13,26 @@ use tokio::sync::Mutex;
 pub struct ConfigManagerState {
     pub toml_manager: Arc<TomlConfigManager>,
     pub theme_manager: Mutex<Option<()>>,
+    pub autosaver: Arc<ConfigAutoSaver>,
 }
 
 impl ConfigManagerState {
     pub async fn new() -> ConfigResult<Self> {
         let toml_manager = Arc::new(TomlConfigManager::new().await?);
         toml_manager.load_config().await?;
+        let autosaver = ConfigAutoSaver::new(Arc::clone(&toml_manager));
 
         Ok(Self {
             toml_manager,
             theme_manager: Mutex::new(None),
+            autosaver,
         })
     }
+
+    /// 立即写入挂起的防抖修改（应用退出时调用）
+    pub async fn flush_pending(&self) -> ConfigResult<()> {
+        self.autosaver.flush().await
+    }
 }
 
 #[tauri::command]
@@ -37,15 +48,33 @@ pub async fn config_update(
     new_config: AppConfig,
     state: State<'_, ConfigManagerState>,
 ) -> TauriApiResult<EmptyData> {
-    match state
-        .toml_manager
-        .config_update(|config| {
-            *config = new_config.clone();
-            Ok(())
-        })
-        .await
-    {
-        Ok(_) => Ok(api_success!()),
+    let debounce_ms = new_config.app.autosave_debounce_ms;
+    if debounce_ms == 0 {
+        return match state
+            .toml_manager
+            .config_update(|config| {
+                *config = new_config.clone();
+                Ok(())
+            })
+            .await
+        {
+            Ok(_) => {
+                state.autosaver.mark_clean();
+                Ok(api_success!())
+            }
+            Err(_) => Ok(api_error!("config.update_failed")),
+        };
+    }
+
+    // 防抖模式：先更新缓存，静默期后合并写盘
+    match state.toml_manager.config_update_cached(|config| {
+        *config = new_config;
+        Ok(())
+    }) {
+        Ok(_) => {
+            state.autosaver.schedule(Duration::from_millis(debounce_ms));
+            Ok(api_success!())
+        }
         Err(_) => Ok(api_error!("config.update_failed")),
     }
 }
@@ -53,7 +82,10 @@ pub async fn config_update(
 #[tauri::command]
 pub async fn config_save(state: State<'_, ConfigManagerState>) -> TauriApiResult<EmptyData> {
     match state.toml_manager.config_update(|_config| Ok(())).await {
-        Ok(_) => Ok(api_success!()),
+        Ok(_) => {
+            state.autosaver.mark_clean();
+            Ok(api_success!())
+        }
         Err(_) => Ok(api_error!("config.save_failed")),
     }
 }
@@ -88,6 +120,64 @@ pub async fn config_reset_to_defaults(
     }
 }
 
+/// 列出当前配置中偏离默认值的键
+#[tauri::command]
+pub async fn config_diff_from_defaults(
+    state: State<'_, ConfigManagerState>,
+) -> TauriApiResult<Vec<ConfigDiffEntry>> {
+    let current = match state.toml_manager.config_get().await {
+        Ok(config) => config,
+        Err(_) => return Ok(api_error!("config.get_failed")),
+    };
+    match diff_configs(&current, &create_default_config()) {
+        Ok(entries) => Ok(api_success!(entries)),
+        Err(e) => {
+            tracing::error!("Failed to diff config against defaults: {}", e);
+            Ok(api_error!("config.diff_failed"))
+        }
+    }
+}
+
+/// 把单个键（点分隔路径）恢复为默认值
+#[tauri::command]
+pub async fn config_reset_key(
+    path: String,
+    state: State<'_, ConfigManagerState>,
+) -> TauriApiResult<EmptyData> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Ok(api_error!("config.key_not_found"));
+    }
+
+    let current = match state.toml_manager.config_get().await {
+        Ok(config) => config,
+        Err(_) => return Ok(api_error!("config.get_failed")),
+    };
+    let updated = match reset_key(&current, &create_default_config(), path) {
+        Ok(Some(config)) => config,
+        Ok(None) => return Ok(api_error!("config.key_not_found")),
+        Err(e) => {
+            tracing::error!("Failed to reset config key {}: {}", path, e);
+            return Ok(api_error!("config.reset_failed"));
+        }
+    };
+
+    match state
+        .toml_manager
+        .config_update(|config| {
+            *config = updated;
+            Ok(())
+        })
+        .await
+    {
+        Ok(_) => {
+            state.autosaver.mark_clean();
+            Ok(api_success!())
+        }
+        Err(_) => Ok(api_error!("config.reset_failed")),
+    }
+}
+
 #[tauri::command]
 pub async fn config_get_file_path() -> TauriApiResult<String> {
     Ok(api_success!("config/config.toml".to_string()))
@@ -0,0 +1,140 @@
+/*!
+ * 终端光标配置的应用
+ *
+ * 新建面板时把光标配置转换为控制序列注入面板输出：DECSCUSR 设置形状与闪烁，
+ * OSC 12 设置颜色。运行中修改配置时同样注入到所有已有面板，并广播事件供前端同步其余选项。
+ */
+
+use bytes::Bytes;
+use tracing::warn;
+
+use crate::config::types::{CursorConfig, CursorStyle};
+use crate::mux::{PaneId, TerminalMux};
+
+/// 光标配置变更事件，payload 为新的 CursorConfig
+pub const CURSOR_CONFIG_CHANGED_EVENT: &str = "terminal_cursor_config_changed";
+
+/// 光标粗细允许范围（像素）
+const CURSOR_THICKNESS_RANGE: std::ops::RangeInclusive<f32> = 0.1..=5.0;
+
+/// DECSCUSR 参数：1/2 方块、3/4 下划线、5/6 竖线，奇数为闪烁
+fn decscusr_param(style: &CursorStyle, blink: bool) -> u8 {
+    let steady = match style {
+        CursorStyle::Block => 2,
+        CursorStyle::Underline => 4,
+        CursorStyle::Beam => 6,
+    };
+    if blink {
+        steady - 1
+    } else {
+        steady
+    }
+}
+
+/// 校验光标配置，返回第一个不合法项的描述
+pub fn validate_cursor_config(cursor: &CursorConfig) -> Result<(), String> {
+    if !CURSOR_THICKNESS_RANGE.contains(&cursor.thickness) {
+        return Err(format!(
+            "光标粗细必须在0.1-5.0之间，当前值: {}",
+            cursor.thickness
+        ));
+    }
+
+    let hex = cursor.color.strip_prefix('#').unwrap_or_default();
+    if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("光标颜色格式无效: {}", cursor.color));
+    }
+
+    Ok(())
+}
+
+/// 方块光标占满整个字符格，粗细只对下划线和竖线生效；写入前把方块光标的粗细收敛到 1.0
+///
+/// 只在写入时调用，已有配置里的旧值不影响加载
+pub fn normalize_cursor_config(cursor: &mut CursorConfig) {
+    if cursor.style == CursorStyle::Block && cursor.thickness > 1.0 {
+        warn!(
+            "方块光标不支持设置粗细，已从 {} 调整为 1.0",
+            cursor.thickness
+        );
+        cursor.thickness = 1.0;
+    }
+}
+
+/// 应用光标配置的控制序列
+pub fn cursor_escape_sequence(cursor: &CursorConfig) -> String {
+    format!(
+        "\x1b[{} q\x1b]12;{}\x07",
+        decscusr_param(&cursor.style, cursor.blink),
+        cursor.color
+    )
+}
+
+/// 把光标配置注入指定面板
+pub fn apply_cursor_to_pane(mux: &TerminalMux, pane_id: PaneId, cursor: &CursorConfig) {
+    let sequence = Bytes::from(cursor_escape_sequence(cursor));
+    if let Err(e) = mux.inject_pane_output(pane_id, sequence) {
+        warn!("应用光标配置失败: pane_id={}, err={}", pane_id.as_u32(), e);
+    }
+}
+
+/// 把光标配置注入所有已有面板
+pub fn apply_cursor_to_all_panes(mux: &TerminalMux, cursor: &CursorConfig) {
+    for pane_id in mux.list_panes() {
+        apply_cursor_to_pane(mux, pane_id, cursor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor(style: CursorStyle, blink: bool, thickness: f32) -> CursorConfig {
+        CursorConfig {
+            style,
+            blink,
+            color: "#ffcc00".to_string(),
+            thickness,
+        }
+    }
+
+    #[test]
+    fn builds_decscusr_and_color_sequence() {
+        assert_eq!(
+            cursor_escape_sequence(&cursor(CursorStyle::Block, true, 0.15)),
+            "\x1b[1 q\x1b]12;#ffcc00\x07"
+        );
+        assert!(
+            cursor_escape_sequence(&cursor(CursorStyle::Underline, false, 1.0))
+                .starts_with("\x1b[4 q")
+        );
+        assert!(
+            cursor_escape_sequence(&cursor(CursorStyle::Beam, true, 2.0)).starts_with("\x1b[5 q")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_cursor_configs() {
+        assert!(validate_cursor_config(&cursor(CursorStyle::Beam, true, 2.0)).is_ok());
+        assert!(validate_cursor_config(&cursor(CursorStyle::Beam, true, 6.0)).is_err());
+        // 旧配置中方块光标的粗细不影响校验
+        assert!(validate_cursor_config(&cursor(CursorStyle::Block, true, 2.0)).is_ok());
+
+        let mut bad_color = cursor(CursorStyle::Beam, true, 1.0);
+        bad_color.color = "#gggggg".to_string();
+        assert!(validate_cursor_config(&bad_color).is_err());
+        bad_color.color = "ffcc00".to_string();
+        assert!(validate_cursor_config(&bad_color).is_err());
+    }
+
+    #[test]
+    fn block_thickness_is_clamped_on_write() {
+        let mut block = cursor(CursorStyle::Block, true, 3.0);
+        normalize_cursor_config(&mut block);
+        assert_eq!(block.thickness, 1.0);
+
+        let mut beam = cursor(CursorStyle::Beam, true, 3.0);
+        normalize_cursor_config(&mut beam);
+        assert_eq!(beam.thickness, 3.0);
+    }
+}
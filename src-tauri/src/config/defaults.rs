@@ -17,11 +17,16 @@ pub fn create_default_config() -> AppConfig {
     }
 }
 
+/// 配置自动保存默认防抖时长（毫秒）
+pub const DEFAULT_AUTOSAVE_DEBOUNCE_MS: u64 = 500;
+
 fn create_default_app_config() -> AppConfigApp {
     AppConfigApp {
         language: "zh-CN".to_string(),
         confirm_on_exit: true,
         startup_behavior: "restore".to_string(),
+        autosave_debounce_ms: DEFAULT_AUTOSAVE_DEBOUNCE_MS,
+        quit_on_window_close: false,
     }
 }
 
@@ -41,6 +46,9 @@ pub fn create_default_terminal_config() -> TerminalConfig {
         shell: create_default_shell_config(),
         cursor: create_default_cursor_config(),
         behavior: create_default_terminal_behavior_config(),
+        output_encoding: crate::mux::DEFAULT_OUTPUT_ENCODING.to_string(),
+        ansi_overrides: Default::default(),
+        output_flush: Default::default(),
     }
 }
 
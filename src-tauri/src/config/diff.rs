@@ -0,0 +1,134 @@
+//! 当前配置与默认配置的差异比较，以及单个键的重置
+//!
+//! 基于 AppConfig 的 serde 序列化结果比较，键路径与 config.toml 中的写法一致（点分隔）。
+
+use crate::config::error::ConfigResult;
+use crate::config::types::AppConfig;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// 单个偏离默认值的键
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiffEntry {
+    /// 点分隔的键路径，如 `appearance.font.size`
+    pub path: String,
+    /// 当前值；键不存在时为 null
+    pub current: Value,
+    /// 默认值；默认配置中没有该键时为 null
+    pub default: Value,
+}
+
+/// 列出 current 相对 defaults 的所有差异，嵌套表递归展开，数组整体比较
+pub fn diff_configs(
+    current: &AppConfig,
+    defaults: &AppConfig,
+) -> ConfigResult<Vec<ConfigDiffEntry>> {
+    let current = serde_json::to_value(current)?;
+    let defaults = serde_json::to_value(defaults)?;
+    let mut entries = Vec::new();
+    diff_values("", &current, &defaults, &mut entries);
+    Ok(entries)
+}
+
+fn diff_values(prefix: &str, current: &Value, default: &Value, out: &mut Vec<ConfigDiffEntry>) {
+    match (current, default) {
+        (Value::Object(cur), Value::Object(def)) => {
+            let mut keys: Vec<&String> = cur.keys().chain(def.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                diff_values(
+                    &path,
+                    cur.get(key).unwrap_or(&Value::Null),
+                    def.get(key).unwrap_or(&Value::Null),
+                    out,
+                );
+            }
+        }
+        _ if current != default => out.push(ConfigDiffEntry {
+            path: prefix.to_string(),
+            current: current.clone(),
+            default: default.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn value_at<'a>(root: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(root, |node, segment| node.as_object()?.get(segment))
+}
+
+/// 把 path 对应的值恢复为默认值；默认配置中不存在该路径时返回 None
+pub fn reset_key(
+    current: &AppConfig,
+    defaults: &AppConfig,
+    path: &str,
+) -> ConfigResult<Option<AppConfig>> {
+    let defaults = serde_json::to_value(defaults)?;
+    let Some(default_value) = value_at(&defaults, path).cloned() else {
+        return Ok(None);
+    };
+
+    let mut current = serde_json::to_value(current)?;
+    let mut node = &mut current;
+    let mut segments = path.split('.').peekable();
+    while let Some(segment) = segments.next() {
+        let Some(map) = node.as_object_mut() else {
+            return Ok(None);
+        };
+        if segments.peek().is_none() {
+            map.insert(segment.to_string(), default_value);
+            break;
+        }
+        node = map
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+
+    Ok(Some(serde_json::from_value(current)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::defaults::create_default_config;
+
+    #[test]
+    fn reports_nested_changes_with_dotted_paths() {
+        let defaults = create_default_config();
+        let mut current = defaults.clone();
+        current.appearance.font.size = 18.0;
+        current.app.language = "en-US".to_string();
+
+        let diff = diff_configs(&current, &defaults).unwrap();
+        let paths: Vec<&str> = diff.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["app.language", "appearance.font.size"]);
+        assert_eq!(diff[0].current, Value::from("en-US"));
+        assert_eq!(diff[0].default, Value::from("zh-CN"));
+    }
+
+    #[test]
+    fn reset_key_reverts_single_deviation() {
+        let defaults = create_default_config();
+        let mut current = defaults.clone();
+        current.appearance.font.size = 18.0;
+        current.app.language = "en-US".to_string();
+
+        let reset = reset_key(&current, &defaults, "appearance.font.size")
+            .unwrap()
+            .unwrap();
+        assert_eq!(reset.appearance.font.size, defaults.appearance.font.size);
+        assert_eq!(reset.app.language, "en-US");
+
+        assert!(reset_key(&current, &defaults, "appearance.nope")
+            .unwrap()
+            .is_none());
+    }
+}
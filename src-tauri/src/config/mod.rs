@@ -1,7 +1,11 @@
 // 配置系统模块
 
+pub mod ansi_palette;
+pub mod autosave;
 pub mod commands;
+pub mod cursor;
 pub mod defaults;
+pub mod diff;
 pub mod error;
 pub mod paths;
 pub mod shortcuts;
@@ -11,9 +15,10 @@ pub mod toml;
 pub mod types;
 
 pub use commands::{
-    config_get, config_get_file_info, config_get_file_path, config_get_folder_path,
-    config_open_file, config_open_folder, config_reset_to_defaults, config_save,
-    config_subscribe_events, config_update, config_validate, ConfigManagerState,
+    config_diff_from_defaults, config_get, config_get_file_info, config_get_file_path,
+    config_get_folder_path, config_open_file, config_open_folder, config_reset_key,
+    config_reset_to_defaults, config_save, config_subscribe_events, config_update, config_validate,
+    ConfigManagerState,
 };
 pub use defaults::*;
 pub use error::{
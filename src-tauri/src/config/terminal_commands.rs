@@ -6,16 +6,26 @@
  */
 
 use crate::config::{
+    ansi_palette::{
+        apply_palette_to_all_panes, validate_ansi_overrides, AnsiOverrides,
+        ANSI_OVERRIDES_CHANGED_EVENT,
+    },
     commands::ConfigManagerState,
+    cursor::{
+        apply_cursor_to_all_panes, normalize_cursor_config, validate_cursor_config,
+        CURSOR_CONFIG_CHANGED_EVENT,
+    },
     defaults::create_default_terminal_config,
-    types::{CursorConfig, ShellConfig, TerminalBehaviorConfig, TerminalConfig},
+    types::{CursorConfig, OutputFlushConfig, ShellConfig, TerminalBehaviorConfig, TerminalConfig},
 };
-use crate::mux::ShellManager;
+use crate::mux::{get_mux, ShellManager};
+use crate::terminal::output_coalescer::validate_output_flush;
+use crate::terminal::TerminalChannelState;
 use crate::utils::{EmptyData, TauriApiResult};
 use crate::{api_error, api_success};
 
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
 use tracing::warn;
 
 /// 终端配置更新请求
@@ -30,6 +40,12 @@ pub struct TerminalConfigUpdateRequest {
     pub cursor: Option<CursorConfig>,
     /// 终端行为配置
     pub behavior: Option<TerminalBehaviorConfig>,
+    /// PTY 输出编码
+    pub output_encoding: Option<String>,
+    /// ANSI 调色板覆盖，整体替换；传空表清除全部覆盖
+    pub ansi_overrides: Option<AnsiOverrides>,
+    /// 输出合并策略
+    pub output_flush: Option<OutputFlushConfig>,
 }
 
 /// 终端配置验证结果
@@ -74,10 +90,35 @@ pub async fn config_terminal_get(
 
 /// 更新终端配置
 #[tauri::command]
-pub async fn config_terminal_update(
-    update_request: TerminalConfigUpdateRequest,
+pub async fn config_terminal_update<R: Runtime>(
+    mut update_request: TerminalConfigUpdateRequest,
     state: State<'_, ConfigManagerState>,
+    app_handle: AppHandle<R>,
 ) -> TauriApiResult<EmptyData> {
+    if let Some(cursor) = update_request.cursor.as_mut() {
+        normalize_cursor_config(cursor);
+    }
+    let updated_cursor = update_request.cursor.clone();
+    if let Some(cursor) = &updated_cursor {
+        if let Err(reason) = validate_cursor_config(cursor) {
+            warn!("光标配置无效: {}", reason);
+            return Ok(api_error!("config.invalid_cursor"));
+        }
+    }
+    if let Some(overrides) = &update_request.ansi_overrides {
+        if let Err(reason) = validate_ansi_overrides(overrides) {
+            warn!("调色板覆盖无效: {}", reason);
+            return Ok(api_error!("config.invalid_ansi_overrides"));
+        }
+    }
+    if let Some(output_flush) = &update_request.output_flush {
+        if let Err(reason) = validate_output_flush(output_flush) {
+            warn!("输出合并配置无效: {}", reason);
+            return Ok(api_error!("config.invalid_output_flush"));
+        }
+    }
+    let mut previous_overrides = None;
+
     // 使用config_update方法更新配置
     let result = state
         .toml_manager
@@ -102,13 +143,68 @@ pub async fn config_terminal_update(
                 config.terminal.behavior = behavior;
             }
 
+            // 更新输出编码
+            if let Some(output_encoding) = update_request.output_encoding {
+                config.terminal.output_encoding = output_encoding;
+            }
+
+            // 更新调色板覆盖
+            if let Some(overrides) = &update_request.ansi_overrides {
+                previous_overrides = Some(std::mem::replace(
+                    &mut config.terminal.ansi_overrides,
+                    overrides.clone(),
+                ));
+            }
+
+            // 更新输出合并策略
+            if let Some(output_flush) = &update_request.output_flush {
+                config.terminal.output_flush = output_flush.clone();
+            }
+
             Ok(())
         })
         .await;
 
-    match result {
-        Ok(_) => Ok(api_success!()),
-        Err(_) => Ok(api_error!("config.update_failed")),
+    if result.is_err() {
+        return Ok(api_error!("config.update_failed"));
+    }
+
+    if let Some(cursor) = updated_cursor {
+        broadcast_cursor_config(&app_handle, &cursor);
+    }
+
+    if let (Some(overrides), Some(previous)) = (&update_request.ansi_overrides, previous_overrides)
+    {
+        broadcast_ansi_overrides(&app_handle, overrides, &previous);
+    }
+
+    // 合并策略立即作用于已打开的终端
+    if let Some(output_flush) = update_request.output_flush {
+        if let Some(channel_state) = app_handle.try_state::<TerminalChannelState>() {
+            channel_state.manager.set_flush_config(output_flush);
+        }
+    }
+
+    Ok(api_success!())
+}
+
+/// 把光标配置应用到已打开的终端，并通知前端
+fn broadcast_cursor_config<R: Runtime>(app_handle: &AppHandle<R>, cursor: &CursorConfig) {
+    apply_cursor_to_all_panes(&get_mux(), cursor);
+    if let Err(e) = app_handle.emit(CURSOR_CONFIG_CHANGED_EVENT, cursor) {
+        warn!("发送光标配置变更事件失败: {}", e);
+    }
+}
+
+/// 把调色板覆盖应用到已打开的终端，并通知前端在重新应用主题时合并
+fn broadcast_ansi_overrides<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    overrides: &AnsiOverrides,
+    previous: &AnsiOverrides,
+) {
+    apply_palette_to_all_panes(&get_mux(), overrides, previous);
+    if let Err(e) = app_handle.emit(ANSI_OVERRIDES_CHANGED_EVENT, overrides) {
+        warn!("发送调色板覆盖变更事件失败: {}", e);
     }
 }
 
@@ -140,18 +236,25 @@ pub async fn config_terminal_validate(
     }
 
     // 验证光标配置
-    if !(0.1..=5.0).contains(&terminal_config.cursor.thickness) {
-        errors.push(format!(
-            "光标粗细必须在0.1-5.0之间，当前值: {}",
-            terminal_config.cursor.thickness
-        ));
+    if let Err(reason) = validate_cursor_config(&terminal_config.cursor) {
+        errors.push(reason);
     }
 
-    // 验证颜色格式
-    if !terminal_config.cursor.color.starts_with('#') || terminal_config.cursor.color.len() != 7 {
+    // 验证调色板覆盖
+    if let Err(reason) = validate_ansi_overrides(&terminal_config.ansi_overrides) {
+        errors.push(reason);
+    }
+
+    // 验证输出合并策略
+    if let Err(reason) = validate_output_flush(&terminal_config.output_flush) {
+        errors.push(reason);
+    }
+
+    // 验证输出编码
+    if crate::mux::resolve_output_encoding(&terminal_config.output_encoding).is_none() {
         errors.push(format!(
-            "光标颜色格式无效: {}",
-            terminal_config.cursor.color
+            "未知的输出编码: {}",
+            terminal_config.output_encoding
         ));
     }
 
@@ -249,12 +352,19 @@ pub async fn config_terminal_detect_system_shells() -> TauriApiResult<SystemShel
     }))
 }
 
-/// 更新光标配置
+/// 更新光标配置，并立即应用到所有已打开的终端
 #[tauri::command]
-pub async fn config_terminal_update_cursor(
-    cursor_config: CursorConfig,
+pub async fn config_terminal_update_cursor<R: Runtime>(
+    mut cursor_config: CursorConfig,
     state: State<'_, ConfigManagerState>,
+    app_handle: AppHandle<R>,
 ) -> TauriApiResult<EmptyData> {
+    normalize_cursor_config(&mut cursor_config);
+    if let Err(reason) = validate_cursor_config(&cursor_config) {
+        warn!("光标配置无效: {}", reason);
+        return Ok(api_error!("config.invalid_cursor"));
+    }
+
     // 更新配置
     let result = state
         .toml_manager
@@ -264,10 +374,12 @@ pub async fn config_terminal_update_cursor(
         })
         .await;
 
-    match result {
-        Ok(_) => Ok(api_success!()),
-        Err(_) => Ok(api_error!("config.update_failed")),
+    if result.is_err() {
+        return Ok(api_error!("config.update_failed"));
     }
+
+    broadcast_cursor_config(&app_handle, &cursor_config);
+    Ok(api_success!())
 }
 
 /// 更新终端行为配置
@@ -227,6 +227,29 @@ impl TomlConfigManager {
         Ok(())
     }
 
+    /// 只更新内存中的配置（校验后写入缓存，不落盘、不发事件），配合防抖自动保存使用
+    pub fn config_update_cached<F>(&self, updater: F) -> ConfigResult<()>
+    where
+        F: FnOnce(&mut AppConfig) -> ConfigResult<()> + Send,
+    {
+        let mut cache = self
+            .config_cache
+            .write()
+            .map_err(TomlConfigError::from_poison)
+            .map_err(ConfigError::from)?;
+        let mut next = cache.clone();
+        updater(&mut next)?;
+
+        if let Err(e) = self.validator.config_validate(&next) {
+            self.event_sender
+                .send_validation_failed(vec![e.to_string()]);
+            return Err(ConfigError::from(e));
+        }
+
+        *cache = next;
+        Ok(())
+    }
+
     /// 合并配置
     pub fn merge_config(
         &self,
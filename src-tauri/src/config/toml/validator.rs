@@ -3,6 +3,9 @@
 use crate::config::error::{TomlConfigError, TomlConfigResult};
 use crate::config::{theme::ThemeConfig, types::AppConfig};
 
+/// 自动保存防抖上限，避免配置长时间停留在内存中未落盘
+const MAX_AUTOSAVE_DEBOUNCE_MS: u64 = 10_000;
+
 /// TOML配置验证器
 pub struct TomlConfigValidator;
 
@@ -71,6 +74,15 @@ impl TomlConfigValidator {
             });
         }
 
+        if app_config.autosave_debounce_ms > MAX_AUTOSAVE_DEBOUNCE_MS {
+            return Err(TomlConfigError::Validation {
+                reason: format!(
+                    "Autosave debounce must be at most {}ms",
+                    MAX_AUTOSAVE_DEBOUNCE_MS
+                ),
+            });
+        }
+
         Ok(())
     }
 
@@ -185,9 +197,21 @@ impl TomlConfigValidator {
             });
         }
 
+        if crate::mux::resolve_output_encoding(&terminal_config.output_encoding).is_none() {
+            return Err(TomlConfigError::Validation {
+                reason: format!(
+                    "Unknown terminal output encoding: {}",
+                    terminal_config.output_encoding
+                ),
+            });
+        }
+
         // 验证光标配置
         self.validate_cursor_config(&terminal_config.cursor)?;
 
+        crate::config::ansi_palette::validate_ansi_overrides(&terminal_config.ansi_overrides)
+            .map_err(|reason| TomlConfigError::Validation { reason })?;
+
         Ok(())
     }
 
@@ -196,22 +220,8 @@ impl TomlConfigValidator {
         &self,
         cursor_config: &crate::config::types::CursorConfig,
     ) -> TomlConfigResult<()> {
-        // 验证光标粗细
-        if !(0.1..=5.0).contains(&cursor_config.thickness) {
-            return Err(TomlConfigError::Validation {
-                reason: format!(
-                    "光标粗细必须在0.1-5.0之间，当前值: {}",
-                    cursor_config.thickness
-                ),
-            });
-        }
-
-        // 验证颜色格式（简单的十六进制颜色检查）
-        if !cursor_config.color.starts_with('#') || cursor_config.color.len() != 7 {
-            return Err(TomlConfigError::Validation {
-                reason: format!("光标颜色格式无效: {}", cursor_config.color),
-            });
-        }
+        crate::config::cursor::validate_cursor_config(cursor_config)
+            .map_err(|reason| TomlConfigError::Validation { reason })?;
 
         Ok(())
     }
@@ -20,6 +20,16 @@ pub struct AppConfigApp {
     pub language: String,
     pub confirm_on_exit: bool,
     pub startup_behavior: String,
+    /// config_update 的自动保存防抖时长（毫秒），0 表示每次更新立即写盘
+    #[serde(default = "default_autosave_debounce_ms")]
+    pub autosave_debounce_ms: u64,
+    /// macOS 上关闭主窗口时直接退出应用，默认仅隐藏窗口；其他平台始终退出
+    #[serde(default)]
+    pub quit_on_window_close: bool,
+}
+
+fn default_autosave_debounce_ms() -> u64 {
+    crate::config::defaults::DEFAULT_AUTOSAVE_DEBOUNCE_MS
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -39,6 +49,42 @@ pub struct TerminalConfig {
     pub shell: ShellConfig,
     pub cursor: CursorConfig,
     pub behavior: TerminalBehaviorConfig,
+    /// 新建终端的 PTY 输出编码（WHATWG 标签，如 utf-8、gbk、latin1）
+    #[serde(default = "default_output_encoding")]
+    pub output_encoding: String,
+    /// 在主题调色板之上覆盖的 ANSI 颜色（索引 0-255 -> `#rrggbb`），切换主题后保留
+    #[serde(
+        default,
+        skip_serializing_if = "std::collections::HashMap::is_empty",
+        with = "crate::config::ansi_palette::overrides_serde"
+    )]
+    pub ansi_overrides: crate::config::ansi_palette::AnsiOverrides,
+    /// 输出推送到前端前的合并策略
+    #[serde(default)]
+    pub output_flush: OutputFlushConfig,
+}
+
+fn default_output_encoding() -> String {
+    crate::mux::DEFAULT_OUTPUT_ENCODING.to_string()
+}
+
+/// PTY 输出合并：缓冲到阈值或间隔到期后再发送，输出停顿时立即发送
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputFlushConfig {
+    /// 最长缓冲时间（毫秒），0 表示不合并
+    pub interval_ms: u64,
+    /// 缓冲达到该字节数时立即发送
+    pub max_batch_bytes: usize,
+}
+
+impl Default for OutputFlushConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 8,
+            max_batch_bytes: 64 * 1024,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -0,0 +1,74 @@
+//! 待审批角标
+//!
+//! Agent 等待用户审批工具调用时在 Dock/任务栏显示角标，审批结束或任务终止后清除。
+//! 审批所在的工具注册表拿不到 AppHandle，这里通过安装时捕获的回调间接调用 `DockManager`。
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::DockManager;
+
+type BadgeSink = Box<dyn Fn(usize, bool) + Send + Sync>;
+
+static SINK: OnceLock<BadgeSink> = OnceLock::new();
+static PENDING_APPROVALS: AtomicUsize = AtomicUsize::new(0);
+
+/// 在 DockManager 注册之后调用
+pub fn install<R: Runtime>(app_handle: &AppHandle<R>) {
+    let app_handle = app_handle.clone();
+    let _ = SINK.set(Box::new(move |pending, newly_requested| {
+        let Some(dock_manager) = app_handle.try_state::<DockManager<R>>() else {
+            return;
+        };
+        let count = (pending > 0).then_some(pending as i64);
+        if let Err(e) = dock_manager.set_badge_count(count) {
+            tracing::debug!("Dock badge unavailable: {}", e);
+        }
+        if newly_requested {
+            dock_manager.request_attention();
+        }
+    }));
+}
+
+fn notify(newly_requested: bool) {
+    if let Some(sink) = SINK.get() {
+        sink(PENDING_APPROVALS.load(Ordering::SeqCst), newly_requested);
+    }
+}
+
+/// 按当前待审批数量刷新角标（窗口获得焦点时调用）
+pub fn refresh() {
+    notify(false);
+}
+
+/// 一次待审批的生命周期，drop 时自动撤销角标计数
+///
+/// 审批通过、拒绝、超时或任务取消导致等待被中断时都会 drop。
+pub struct ApprovalBadgeGuard {
+    _private: (),
+}
+
+impl ApprovalBadgeGuard {
+    pub fn new() -> Self {
+        PENDING_APPROVALS.fetch_add(1, Ordering::SeqCst);
+        notify(true);
+        Self { _private: () }
+    }
+}
+
+impl Default for ApprovalBadgeGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ApprovalBadgeGuard {
+    fn drop(&mut self) {
+        let _ = PENDING_APPROVALS.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+            Some(n.saturating_sub(1))
+        });
+        notify(false);
+    }
+}
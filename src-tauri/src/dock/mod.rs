@@ -1,3 +1,4 @@
+pub mod badge;
 pub mod commands;
 pub mod state;
 
@@ -14,6 +15,7 @@ use state::{DockState, TabEntry};
 
 pub struct DockManager<R: tauri::Runtime = tauri::Wry> {
     state: DockState,
+    app_handle: tauri::AppHandle<R>,
     #[cfg(target_os = "macos")]
     _macos_impl: macos::MacOSDockMenu<R>,
     #[cfg(target_os = "windows")]
@@ -32,6 +34,7 @@ impl<R: tauri::Runtime> DockManager<R> {
             let macos_impl = macos::MacOSDockMenu::new(state.clone(), app_handle)?;
             Ok(Self {
                 state,
+                app_handle: app_handle.clone(),
                 _macos_impl: macos_impl,
             })
         }
@@ -41,6 +44,7 @@ impl<R: tauri::Runtime> DockManager<R> {
             let windows_impl = windows::WindowsJumpList::new(state.clone(), app_handle)?;
             Ok(Self {
                 state,
+                app_handle: app_handle.clone(),
                 _windows_impl: windows_impl,
             })
         }
@@ -50,6 +54,7 @@ impl<R: tauri::Runtime> DockManager<R> {
             let linux_impl = linux::LinuxDockMenu::new(state.clone(), app_handle)?;
             Ok(Self {
                 state,
+                app_handle: app_handle.clone(),
                 _linux_impl: linux_impl,
             })
         }
@@ -66,7 +71,18 @@ impl<R: tauri::Runtime> DockManager<R> {
         active_tab_id: Option<String>,
     ) -> Result<(), String> {
         self.state.update_tabs(tabs, active_tab_id)?;
+        self.refresh_menu()
+    }
 
+    /// 同步面板标题到 Dock 菜单
+    pub fn set_pane_title(&self, pane_id: u32, title: &str, sticky: bool) -> Result<(), String> {
+        if self.state.set_pane_title(pane_id, title, sticky)? {
+            self.refresh_menu()?;
+        }
+        Ok(())
+    }
+
+    fn refresh_menu(&self) -> Result<(), String> {
         #[cfg(target_os = "macos")]
         {
             self._macos_impl.refresh_menu()?;
@@ -88,4 +104,49 @@ impl<R: tauri::Runtime> DockManager<R> {
     pub fn state(&self) -> &DockState {
         &self.state
     }
+
+    /// 设置 Dock/任务栏角标数字，None 清除
+    ///
+    /// Windows 任务栏不支持数字角标，降级为无操作。
+    pub fn set_badge_count(&self, count: Option<i64>) -> Result<(), String> {
+        use tauri::Manager;
+
+        let Some(window) = self.app_handle.get_webview_window("main") else {
+            return Ok(());
+        };
+
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        {
+            window.set_badge_count(count).map_err(|e| e.to_string())?;
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+        {
+            let _ = (window, count);
+        }
+
+        Ok(())
+    }
+
+    /// 窗口不在前台时请求用户注意（macOS 上 Dock 图标弹跳一次）
+    pub fn request_attention(&self) {
+        use tauri::Manager;
+
+        let Some(window) = self.app_handle.get_webview_window("main") else {
+            return;
+        };
+        if window.is_focused().unwrap_or(false) {
+            return;
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            let _ = window.request_user_attention(Some(tauri::UserAttentionType::Informational));
+        }
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = window;
+        }
+    }
 }
@@ -1,16 +1,22 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TabEntry {
     pub id: String,
     pub title: String,
+    /// 终端标签对应的面板，用于按面板同步标题
+    #[serde(default, rename = "paneId")]
+    pub pane_id: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
 pub struct DockState {
     tabs: Arc<RwLock<Vec<TabEntry>>>,
     active_tab_id: Arc<RwLock<Option<String>>>,
+    /// sticky 标题覆盖，前端重新推送标签列表时仍然生效
+    pinned_titles: Arc<RwLock<HashMap<u32, String>>>,
 }
 
 impl DockState {
@@ -18,14 +24,27 @@ impl DockState {
         Self {
             tabs: Arc::new(RwLock::new(Vec::new())),
             active_tab_id: Arc::new(RwLock::new(None)),
+            pinned_titles: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
     pub fn update_tabs(
         &self,
-        tabs: Vec<TabEntry>,
+        mut tabs: Vec<TabEntry>,
         active_tab_id: Option<String>,
     ) -> Result<(), String> {
+        {
+            let pinned = self
+                .pinned_titles
+                .read()
+                .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+            for tab in tabs.iter_mut() {
+                if let Some(title) = tab.pane_id.and_then(|id| pinned.get(&id)) {
+                    tab.title = title.clone();
+                }
+            }
+        }
+
         let mut state = self
             .tabs
             .write()
@@ -41,6 +60,34 @@ impl DockState {
         Ok(())
     }
 
+    /// 更新指定面板对应标签的标题，返回是否有标签被修改
+    pub fn set_pane_title(&self, pane_id: u32, title: &str, sticky: bool) -> Result<bool, String> {
+        {
+            let mut pinned = self
+                .pinned_titles
+                .write()
+                .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+            if sticky {
+                pinned.insert(pane_id, title.to_string());
+            } else {
+                pinned.remove(&pane_id);
+            }
+        }
+
+        let mut state = self
+            .tabs
+            .write()
+            .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+        let mut changed = false;
+        for tab in state.iter_mut().filter(|t| t.pane_id == Some(pane_id)) {
+            if tab.title != title {
+                tab.title = title.to_string();
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+
     pub fn get_tabs(&self) -> Result<Vec<TabEntry>, String> {
         let state = self
             .tabs
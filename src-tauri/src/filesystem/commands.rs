@@ -1,8 +1,10 @@
-use crate::utils::TauriApiResult;
+use super::tail::{self, TailEvent};
+use crate::utils::{EmptyData, TauriApiResult};
 use crate::{api_error, api_success};
 use ignore::gitignore::GitignoreBuilder;
 use ignore::WalkBuilder;
 use std::path::PathBuf;
+use tauri::ipc::Channel;
 
 /// 扩展的目录条目，包含 gitignore 状态
 #[derive(serde::Serialize)]
@@ -159,3 +161,48 @@ pub(crate) async fn fs_list_directory(
     let out: Vec<String> = entries.into_iter().map(|(s, _)| s).collect();
     Ok(api_success!(out))
 }
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TailArgs {
+    pub tail_id: String,
+    pub path: String,
+    /// 初始快照的行数，默认 100，最多 5000
+    pub lines: Option<usize>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TailCancelArgs {
+    pub tail_id: String,
+}
+
+/// 跟踪文件追加内容：先推送末尾若干行，之后持续推送新增行，直到取消
+#[tauri::command]
+pub async fn filesystem_tail(
+    args: TailArgs,
+    channel: Channel<TailEvent>,
+) -> TauriApiResult<EmptyData> {
+    let path = PathBuf::from(&args.path);
+    if !path.exists() {
+        return Ok(api_error!("common.not_found"));
+    }
+    if !path.is_file() {
+        return Ok(api_error!("common.invalid_path"));
+    }
+
+    let lines = args.lines.unwrap_or(tail::DEFAULT_TAIL_LINES);
+    match tail::start(args.tail_id, path, lines, channel) {
+        Ok(()) => Ok(api_success!()),
+        Err(e) => {
+            tracing::warn!("Failed to tail file {}: {}", args.path, e);
+            Ok(api_error!("filesystem.tail_failed"))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn filesystem_tail_cancel(args: TailCancelArgs) -> TauriApiResult<EmptyData> {
+    tail::cancel(&args.tail_id);
+    Ok(api_success!())
+}
@@ -0,0 +1,374 @@
+//! 跟踪文件追加内容（tail -F）
+//!
+//! 订阅时先发送文件末尾 N 行的快照，之后监听文件所在目录，文件增长时只读取新增部分并按行推送。
+//! 文件被截断（大小变小）或被替换（inode 变化，常见于日志轮转）时从头重新读取。
+//! notify 事件可能合并或丢失，另有定时轮询兜底。
+
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::ipc::Channel;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+pub const DEFAULT_TAIL_LINES: usize = 100;
+pub const MAX_TAIL_LINES: usize = 5000;
+/// 单行最多保留的字节数，超出部分截断
+const MAX_LINE_BYTES: usize = 16 * 1024;
+/// 单次读取新增内容的上限，避免大文件一次写入时占用过多内存
+const MAX_READ_BYTES: u64 = 4 * 1024 * 1024;
+const READ_BLOCK: u64 = 8 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+static NEXT_TAIL_ID: AtomicU64 = AtomicU64::new(1);
+static TAILS: OnceCell<Mutex<HashMap<String, (u64, CancellationToken)>>> = OnceCell::new();
+
+fn tails() -> &'static Mutex<HashMap<String, (u64, CancellationToken)>> {
+    TAILS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationKind {
+    Truncated,
+    Replaced,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TailEvent {
+    /// 订阅时文件末尾的若干行
+    Snapshot { lines: Vec<String> },
+    /// 新追加的完整行
+    Lines { lines: Vec<String> },
+    /// 检测到截断或替换，之后的行从新文件开头读取
+    Rotated { kind: RotationKind },
+    /// 文件暂时不可读（如轮转过程中被删除），恢复后继续
+    Error { message: String },
+}
+
+/// 用于识别文件是否被替换
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileIdentity {
+    dev: u64,
+    ino: u64,
+}
+
+#[cfg(unix)]
+fn file_identity(meta: &std::fs::Metadata) -> Option<FileIdentity> {
+    use std::os::unix::fs::MetadataExt;
+    Some(FileIdentity {
+        dev: meta.dev(),
+        ino: meta.ino(),
+    })
+}
+
+#[cfg(not(unix))]
+fn file_identity(_meta: &std::fs::Metadata) -> Option<FileIdentity> {
+    None
+}
+
+fn to_line(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    let bytes = &bytes[..bytes.len().min(MAX_LINE_BYTES)];
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// 从文件末尾向前读取最后 n 行，返回这些行与文件长度
+fn read_last_lines(file: &mut File, n: usize) -> io::Result<(Vec<String>, u64)> {
+    let len = file.metadata()?.len();
+    if n == 0 || len == 0 {
+        return Ok((Vec::new(), len));
+    }
+
+    // 末尾换行不算作新的一行
+    let mut buf: Vec<u8> = Vec::new();
+    let mut pos = len;
+    loop {
+        let start = pos.saturating_sub(READ_BLOCK);
+        let mut block = vec![0u8; (pos - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut block)?;
+        block.extend_from_slice(&buf);
+        buf = block;
+        pos = start;
+
+        let body = buf.strip_suffix(b"\n").unwrap_or(&buf);
+        let newlines = body.iter().filter(|&&b| b == b'\n').count();
+        if newlines >= n || pos == 0 {
+            break;
+        }
+    }
+
+    let body = buf.strip_suffix(b"\n").unwrap_or(&buf);
+    let mut lines: Vec<String> = body.split(|&b| b == b'\n').map(to_line).collect();
+    if pos > 0 || lines.len() > n {
+        // 第一段可能是被截断的行
+        let skip = lines.len().saturating_sub(n);
+        lines.drain(..skip);
+    }
+    Ok((lines, len))
+}
+
+/// 增量读取文件的状态
+struct TailReader {
+    path: PathBuf,
+    file: File,
+    identity: Option<FileIdentity>,
+    offset: u64,
+    /// 尚未遇到换行的末尾内容
+    partial: Vec<u8>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TailUpdate {
+    rotated: Option<RotationKind>,
+    lines: Vec<String>,
+}
+
+impl TailReader {
+    fn open(path: &Path, initial_lines: usize) -> io::Result<(Self, Vec<String>)> {
+        let mut file = File::open(path)?;
+        let identity = file_identity(&file.metadata()?);
+        let (lines, len) = read_last_lines(&mut file, initial_lines)?;
+        Ok((
+            Self {
+                path: path.to_path_buf(),
+                file,
+                identity,
+                offset: len,
+                partial: Vec::new(),
+            },
+            lines,
+        ))
+    }
+
+    /// 读取自上次以来追加的完整行，必要时处理截断与替换
+    fn poll(&mut self) -> io::Result<TailUpdate> {
+        let mut update = TailUpdate::default();
+
+        let meta = std::fs::metadata(&self.path)?;
+        let identity = file_identity(&meta);
+        if identity.is_some() && identity != self.identity {
+            self.file = File::open(&self.path)?;
+            self.identity = identity;
+            self.offset = 0;
+            self.partial.clear();
+            update.rotated = Some(RotationKind::Replaced);
+        } else if meta.len() < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+            update.rotated = Some(RotationKind::Truncated);
+        }
+
+        let len = self.file.metadata()?.len();
+        if len <= self.offset {
+            return Ok(update);
+        }
+        let to_read = (len - self.offset).min(MAX_READ_BYTES);
+        let mut chunk = vec![0u8; to_read as usize];
+        self.file.seek(SeekFrom::Start(self.offset))?;
+        self.file.read_exact(&mut chunk)?;
+        self.offset += to_read;
+
+        self.partial.extend_from_slice(&chunk);
+        if let Some(last_newline) = self.partial.iter().rposition(|&b| b == b'\n') {
+            let rest = self.partial.split_off(last_newline + 1);
+            let complete = std::mem::replace(&mut self.partial, rest);
+            update.lines = complete[..complete.len() - 1]
+                .split(|&b| b == b'\n')
+                .map(to_line)
+                .collect();
+        } else if self.partial.len() > MAX_LINE_BYTES {
+            // 超长且没有换行的内容按一行输出，避免无限累积
+            update
+                .lines
+                .push(to_line(&std::mem::take(&mut self.partial)));
+        }
+        Ok(update)
+    }
+}
+
+/// 开始跟踪文件；同一 tail_id 的旧订阅会被取消。subscriber 断开或调用 cancel 后停止
+pub fn start(
+    tail_id: String,
+    path: PathBuf,
+    initial_lines: usize,
+    channel: Channel<TailEvent>,
+) -> io::Result<()> {
+    let (mut reader, snapshot) = TailReader::open(&path, initial_lines.min(MAX_TAIL_LINES))?;
+    if channel
+        .send(TailEvent::Snapshot { lines: snapshot })
+        .is_err()
+    {
+        return Ok(());
+    }
+
+    let generation = NEXT_TAIL_ID.fetch_add(1, Ordering::Relaxed);
+    let token = CancellationToken::new();
+    if let Some((_, previous)) = tails()
+        .lock()
+        .insert(tail_id.clone(), (generation, token.clone()))
+    {
+        previous.cancel();
+    }
+
+    // 监听父目录而非文件本身：文件被替换后对旧文件的监听会失效
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+    let watched_name = path.file_name().map(|n| n.to_os_string());
+    let watcher = RecommendedWatcher::new(
+        move |res: Result<notify::Event, notify::Error>| {
+            if let Ok(event) = res {
+                let relevant = event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name().map(|n| n.to_os_string()) == watched_name);
+                if relevant {
+                    let _ = tx.try_send(());
+                }
+            }
+        },
+        Config::default(),
+    )
+    .and_then(|mut watcher| {
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        Ok(watcher)
+    });
+    let watcher = match watcher {
+        Ok(w) => Some(w),
+        Err(e) => {
+            warn!(
+                "文件监听失败，改为定时轮询: path={}, err={}",
+                path.display(),
+                e
+            );
+            None
+        }
+    };
+
+    tokio::spawn(async move {
+        let _watcher = watcher;
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        let mut failing = false;
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                // 监听失败时 tx 已被丢弃，recv 立即返回 None，只能靠定时轮询
+                Some(_) = rx.recv() => {}
+                _ = interval.tick() => {}
+            }
+
+            let events = match reader.poll() {
+                Ok(update) => {
+                    failing = false;
+                    let mut events = Vec::new();
+                    if let Some(kind) = update.rotated {
+                        events.push(TailEvent::Rotated { kind });
+                    }
+                    if !update.lines.is_empty() {
+                        events.push(TailEvent::Lines {
+                            lines: update.lines,
+                        });
+                    }
+                    events
+                }
+                // 轮转期间文件可能短暂不存在，只报告一次
+                Err(e) if !failing => {
+                    failing = true;
+                    vec![TailEvent::Error {
+                        message: e.to_string(),
+                    }]
+                }
+                Err(_) => Vec::new(),
+            };
+
+            if events.into_iter().any(|event| channel.send(event).is_err()) {
+                debug!("tail 订阅已断开: {}", reader.path.display());
+                break;
+            }
+        }
+
+        let mut tails = tails().lock();
+        if tails.get(&tail_id).is_some_and(|(id, _)| *id == generation) {
+            tails.remove(&tail_id);
+        }
+    });
+    Ok(())
+}
+
+/// 停止跟踪，返回订阅是否存在
+pub fn cancel(tail_id: &str) -> bool {
+    match tails().lock().remove(tail_id) {
+        Some((_, token)) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn snapshot_returns_last_lines() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for i in 0..2000 {
+            writeln!(file, "line {i}").unwrap();
+        }
+        let (lines, len) = read_last_lines(file.as_file_mut(), 3).unwrap();
+        assert_eq!(lines, vec!["line 1997", "line 1998", "line 1999"]);
+        assert_eq!(len, file.as_file().metadata().unwrap().len());
+
+        let (all, _) = read_last_lines(file.as_file_mut(), 5000).unwrap();
+        assert_eq!(all.len(), 2000);
+        assert_eq!(all[0], "line 0");
+    }
+
+    #[test]
+    fn poll_emits_complete_lines_and_detects_truncation() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "old").unwrap();
+        let (mut reader, snapshot) = TailReader::open(file.path(), 10).unwrap();
+        assert_eq!(snapshot, vec!["old"]);
+
+        write!(file, "a\r\nb\npart").unwrap();
+        let update = reader.poll().unwrap();
+        assert_eq!(update.lines, vec!["a", "b"]);
+        writeln!(file, "ial").unwrap();
+        assert_eq!(reader.poll().unwrap().lines, vec!["partial"]);
+
+        file.as_file().set_len(0).unwrap();
+        file.as_file_mut().seek(SeekFrom::Start(0)).unwrap();
+        writeln!(file, "new").unwrap();
+        let update = reader.poll().unwrap();
+        assert_eq!(update.rotated, Some(RotationKind::Truncated));
+        assert_eq!(update.lines, vec!["new"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn poll_reopens_replaced_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "first\n").unwrap();
+        let (mut reader, _) = TailReader::open(&path, 10).unwrap();
+
+        std::fs::rename(&path, dir.path().join("app.log.1")).unwrap();
+        std::fs::write(&path, "rotated\n").unwrap();
+        let update = reader.poll().unwrap();
+        assert_eq!(update.rotated, Some(RotationKind::Replaced));
+        assert_eq!(update.lines, vec!["rotated"]);
+    }
+}
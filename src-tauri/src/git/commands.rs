@@ -113,6 +113,36 @@ pub async fn git_watch_stop(watcher: State<'_, GitWatcher>) -> TauriApiResult<()
     Ok(api_success!(()))
 }
 
+/// 暂停监听事件处理，底层 watcher 保持不变
+#[tauri::command]
+pub async fn git_watch_pause(watcher: State<'_, GitWatcher>) -> TauriApiResult<()> {
+    if watcher.pause().await {
+        Ok(api_success!(()))
+    } else {
+        Ok(api_error!("git.watcher_not_running"))
+    }
+}
+
+/// 恢复监听；reconcile 默认为 true，会补发一次暂停期间的变化
+#[tauri::command]
+pub async fn git_watch_resume(
+    watcher: State<'_, GitWatcher>,
+    reconcile: Option<bool>,
+) -> TauriApiResult<()> {
+    if watcher.resume(reconcile.unwrap_or(true)).await {
+        Ok(api_success!(()))
+    } else {
+        Ok(api_error!("git.watcher_not_running"))
+    }
+}
+
+#[tauri::command]
+pub async fn git_watch_stats(
+    watcher: State<'_, GitWatcher>,
+) -> TauriApiResult<Option<super::MonitorStats>> {
+    Ok(api_success!(watcher.stats().await))
+}
+
 #[tauri::command]
 pub async fn git_watch_status(watcher: State<'_, GitWatcher>) -> TauriApiResult<Option<String>> {
     let path = watcher.watched_path().await;
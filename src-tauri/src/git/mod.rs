@@ -5,4 +5,4 @@ pub mod watcher;
 
 pub use service::GitService;
 pub use types::*;
-pub use watcher::{GitChangeEvent, GitChangeType, GitWatcher};
+pub use watcher::{GitChangeEvent, GitChangeType, GitWatcher, MonitorStats};
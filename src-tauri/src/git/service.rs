@@ -221,6 +221,65 @@ impl GitService {
         Ok(branches)
     }
 
+    /// 校验分支名；以 '-' 开头的名字会被 git 当作选项，直接拒绝
+    pub async fn validate_branch_name(path: &str, name: &str) -> Result<(), GitError> {
+        let invalid = || GitError {
+            code: GitErrorCode::CommandFailed,
+            message: format!("Invalid branch name: {}", name),
+        };
+        if name.trim().is_empty() || name.starts_with('-') {
+            return Err(invalid());
+        }
+        Self::execute(&["check-ref-format", "--branch", name], path)
+            .await
+            .map(|_| ())
+            .map_err(|e| match e.code {
+                GitErrorCode::CommandFailed => invalid(),
+                _ => e,
+            })
+    }
+
+    /// 新建分支，start_point 缺省为 HEAD；checkout 为 true 时同时切换过去
+    pub async fn create_branch(
+        path: &str,
+        name: &str,
+        start_point: Option<&str>,
+        checkout: bool,
+    ) -> Result<(), GitError> {
+        let root = Self::ensure_repo_root(path).await?;
+        Self::validate_branch_name(&root, name).await?;
+        if start_point.is_some_and(|s| s.starts_with('-')) {
+            return Err(GitError {
+                code: GitErrorCode::CommandFailed,
+                message: "Invalid start point".to_string(),
+            });
+        }
+
+        let mut args = if checkout {
+            vec!["switch", "-c", name]
+        } else {
+            vec!["branch", name]
+        };
+        args.extend(start_point);
+        Self::execute(&args, &root).await.map(|_| ())
+    }
+
+    /// 切换到已有的本地分支；不使用 --force，会被覆盖的本地修改由 git 拒绝
+    pub async fn checkout_branch(path: &str, name: &str) -> Result<(), GitError> {
+        let root = Self::ensure_repo_root(path).await?;
+        Self::validate_branch_name(&root, name).await?;
+        Self::execute(&["switch", name], &root).await.map(|_| ())
+    }
+
+    /// 推送分支到 origin 并设置上游；不提供强制推送
+    pub async fn push_branch(path: &str, name: &str) -> Result<(), GitError> {
+        let root = Self::ensure_repo_root(path).await?;
+        Self::validate_branch_name(&root, name).await?;
+        Self::execute(&["push", "--set-upstream", "origin", name], &root)
+            .await
+            .map(|_| ())
+    }
+
     pub async fn get_commits(
         path: &str,
         limit: u32,
@@ -5,12 +5,12 @@ use notify::{
 use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::{Emitter, Runtime};
 use tokio::sync::mpsc;
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
 use tracing::{debug, error, info};
 
 // VSCode-style: treat bursts as "one logical change".
@@ -42,10 +42,33 @@ struct GitPaths {
     common_dir: PathBuf,
 }
 
+/// 监听统计
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorStats {
+    pub paused: bool,
+    /// 已参与 debounce 的有效事件数
+    pub processed_events: u64,
+    /// 暂停期间被跳过的有效事件数
+    pub skipped_events: u64,
+}
+
+/// 暂停控制，监听任务与命令之间共享；每次 start 重新创建
+#[derive(Default)]
+struct PauseControl {
+    paused: AtomicBool,
+    /// 恢复时是否把暂停期间的变化合并为一次事件
+    reconcile: AtomicBool,
+    resumed: Notify,
+    processed_events: AtomicU64,
+    skipped_events: AtomicU64,
+}
+
 struct WatcherState {
     watcher: RecommendedWatcher,
     watched_paths: Vec<PathBuf>,
     watched_path: PathBuf,
+    control: Arc<PauseControl>,
 }
 
 pub struct GitWatcher {
@@ -93,10 +116,12 @@ impl GitWatcher {
         )
         .map_err(|e| e.to_string())?;
 
+        let control = Arc::new(PauseControl::default());
         let mut state = WatcherState {
             watcher,
             watched_paths: Vec::new(),
             watched_path: repo_path.clone(),
+            control: Arc::clone(&control),
         };
 
         // Watch git dir (index/HEAD etc) - non-recursive keeps noise low.
@@ -140,6 +165,8 @@ impl GitWatcher {
         // Spawn debounced + throttled event processor
         tokio::spawn(async move {
             let mut pending_changes: HashSet<GitChangeType> = HashSet::new();
+            // 暂停期间只记录发生过哪些类型的变化，恢复时按需补发
+            let mut paused_changes: HashSet<GitChangeType> = HashSet::new();
             let mut last_emit_time: Option<tokio::time::Instant> = None;
             let debounce = tokio::time::sleep(Duration::from_secs(3600));
             tokio::pin!(debounce);
@@ -150,12 +177,28 @@ impl GitWatcher {
                 }
 
                 tokio::select! {
-                    Some(event) = rx.recv() => {
+                    event = rx.recv() => {
+                        // watcher 被释放后通道关闭，任务随之退出
+                        let Some(event) = event else { break };
                         if let Some(change_type) = classify_event(&event, &git_paths_for_task) {
+                            if control.paused.load(Ordering::SeqCst) {
+                                control.skipped_events.fetch_add(1, Ordering::Relaxed);
+                                paused_changes.insert(change_type);
+                                continue;
+                            }
+                            control.processed_events.fetch_add(1, Ordering::Relaxed);
                             pending_changes.insert(change_type);
                             debounce.as_mut().reset(tokio::time::Instant::now() + Duration::from_millis(DEBOUNCE_MS));
                         }
                     }
+                    _ = control.resumed.notified() => {
+                        if control.reconcile.load(Ordering::SeqCst) && !paused_changes.is_empty() {
+                            pending_changes.extend(paused_changes.drain());
+                            debounce.as_mut().reset(tokio::time::Instant::now());
+                        } else {
+                            paused_changes.clear();
+                        }
+                    }
                     _ = &mut debounce, if !pending_changes.is_empty() => {
                         // Throttle: skip if we emitted too recently
                         let now = tokio::time::Instant::now();
@@ -204,6 +247,40 @@ impl GitWatcher {
         }
     }
 
+    /// 暂停事件处理但保留底层 watcher；未在监听时返回 false
+    pub async fn pause(&self) -> bool {
+        match self.state.read().await.as_ref() {
+            Some(s) => {
+                s.control.paused.store(true, Ordering::SeqCst);
+                info!("Git watcher paused");
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 恢复事件处理；reconcile 为 true 时把暂停期间的变化合并为一次 `git:changed`
+    pub async fn resume(&self, reconcile: bool) -> bool {
+        match self.state.read().await.as_ref() {
+            Some(s) => {
+                s.control.reconcile.store(reconcile, Ordering::SeqCst);
+                s.control.paused.store(false, Ordering::SeqCst);
+                s.control.resumed.notify_one();
+                info!("Git watcher resumed (reconcile: {})", reconcile);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub async fn stats(&self) -> Option<MonitorStats> {
+        self.state.read().await.as_ref().map(|s| MonitorStats {
+            paused: s.control.paused.load(Ordering::SeqCst),
+            processed_events: s.control.processed_events.load(Ordering::Relaxed),
+            skipped_events: s.control.skipped_events.load(Ordering::Relaxed),
+        })
+    }
+
     pub async fn is_watching(&self) -> bool {
         self.state.read().await.is_some()
     }
@@ -137,6 +137,13 @@ pub fn run() {
             // 监听应用退出事件（Command+Q 或菜单退出）
             // 在应用真正退出前清理资源
             tauri::RunEvent::ExitRequested { .. } => {
+                if let Some(config_state) =
+                    app_handle.try_state::<crate::config::ConfigManagerState>()
+                {
+                    if let Err(e) = tauri::async_runtime::block_on(config_state.flush_pending()) {
+                        eprintln!("保存待写入配置失败: {}", e);
+                    }
+                }
                 if let Err(e) = crate::mux::singleton::shutdown_mux() {
                     eprintln!("清理 TerminalMux 失败: {}", e);
                 }
@@ -287,6 +287,10 @@ pub struct CreateMessageRequest {
     /// 元数据（用于追踪）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Metadata>,
+
+    /// Extended Thinking 配置（仅支持的模型）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<ThinkingConfig>,
 }
 
 /// System Prompt - 可以是字符串或包含 cache control 的块
@@ -330,6 +334,41 @@ pub struct Metadata {
     pub user_id: Option<String>,
 }
 
+/// Extended Thinking 配置
+///
+/// 参考: https://docs.claude.com/en/docs/build-with-claude/extended-thinking
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ThinkingConfig {
+    /// 启用思考，budget_tokens 须 >= 1024 且小于 max_tokens
+    Enabled { budget_tokens: u32 },
+}
+
+impl ThinkingConfig {
+    /// API 允许的最小思考预算
+    pub const MIN_BUDGET_TOKENS: u32 = 1024;
+
+    /// 模型是否支持 Extended Thinking：Claude 3.7 以及 4 及以上的 Opus/Sonnet/Haiku
+    pub fn supported_by(model: &str) -> bool {
+        let model = model.to_ascii_lowercase();
+        if !model.contains("claude") {
+            return false;
+        }
+        if model.contains("3-7") || model.contains("3.7") {
+            return true;
+        }
+        // claude-sonnet-4-20250514 / claude-opus-4-1：家族名后紧跟主版本号
+        ["opus", "sonnet", "haiku"].iter().any(|family| {
+            model
+                .split(family)
+                .nth(1)
+                .and_then(|rest| rest.trim_start_matches(['-', '.']).chars().next())
+                .and_then(|c| c.to_digit(10))
+                .is_some_and(|major| major >= 4)
+        })
+    }
+}
+
 /// API 响应消息
 ///
 /// 对应 `Anthropic.Messages.Message`
@@ -379,7 +418,8 @@ pub enum StopReason {
 /// Token 使用统计
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Usage {
-    /// 输入token数
+    /// 输入token数（message_delta 事件中可能缺省）
+    #[serde(default)]
     pub input_tokens: u32,
     /// 输出token数
     pub output_tokens: u32,
@@ -477,6 +517,8 @@ pub enum ContentDelta {
     InputJsonDelta { partial_json: String },
     /// Thinking增量
     ThinkingDelta { thinking: String },
+    /// Thinking块签名（块结束前发送，回传历史时必须保留）
+    SignatureDelta { signature: String },
 }
 
 /// 消息Delta数据
@@ -702,4 +744,43 @@ mod tests {
         assert_eq!(message.role, MessageRole::Assistant);
         assert_eq!(message.usage.total_tokens(), 30);
     }
+
+    #[test]
+    fn test_thinking_config_and_signature_delta() {
+        let json = serde_json::to_value(ThinkingConfig::Enabled {
+            budget_tokens: 2048,
+        })
+        .unwrap();
+        assert_eq!(json["type"], "enabled");
+        assert_eq!(json["budget_tokens"], 2048);
+
+        assert!(ThinkingConfig::supported_by("claude-3-7-sonnet-20250219"));
+        assert!(ThinkingConfig::supported_by("claude-sonnet-4-20250514"));
+        assert!(ThinkingConfig::supported_by("claude-opus-4-1"));
+        assert!(!ThinkingConfig::supported_by("claude-3-5-sonnet-20241022"));
+        assert!(!ThinkingConfig::supported_by("claude-3-5-haiku-20241022"));
+        assert!(!ThinkingConfig::supported_by("gpt-4o"));
+
+        let event: StreamEvent = serde_json::from_str(
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"signature_delta","signature":"sig"}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            event,
+            StreamEvent::ContentBlockDelta {
+                delta: ContentDelta::SignatureDelta { .. },
+                ..
+            }
+        ));
+
+        // message_delta 的 usage 只带 output_tokens
+        let event: StreamEvent = serde_json::from_str(
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":42}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            event,
+            StreamEvent::MessageDelta { usage, .. } if usage.output_tokens == 42
+        ));
+    }
 }
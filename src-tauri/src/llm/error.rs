@@ -25,6 +25,27 @@ pub enum LlmError {
     Provider(#[from] LlmProviderError),
 }
 
+impl LlmError {
+    /// 对 embedding 调用失败分类，便于区分“不是 embedding 模型”和鉴权/网络问题
+    pub fn embedding_error_kind(&self) -> &'static str {
+        match self {
+            Self::ModelNotFound { .. } => "model_not_found",
+            Self::Provider(LlmProviderError::UnsupportedOperation { .. }) => "not_embedding_model",
+            Self::Provider(err) => err.embedding_error_kind(),
+            _ => "other",
+        }
+    }
+
+    /// 对未保存配置的校验失败分类：auth / wrong_url / model_not_found / rate_limited / network / other
+    pub fn validation_error_kind(&self) -> &'static str {
+        match self {
+            Self::ModelNotFound { .. } => "model_not_found",
+            Self::Provider(err) => err.validation_error_kind(),
+            _ => "other",
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum LlmProviderError {
     #[error(transparent)]
@@ -40,6 +61,84 @@ pub enum LlmProviderError {
         provider: &'static str,
         operation: &'static str,
     },
+    /// 流式请求超过整体超时，即使仍在持续输出也会被中止
+    #[error("LLM request exceeded timeout of {timeout_ms} ms")]
+    RequestTimeout { timeout_ms: u64 },
+}
+
+impl LlmProviderError {
+    /// 暂时性失败（如请求超时），重试可能成功
+    pub fn is_recoverable(&self) -> bool {
+        matches!(self, Self::RequestTimeout { .. })
+    }
+
+    pub(crate) fn embedding_error_kind(&self) -> &'static str {
+        let (status, message) = match self {
+            Self::OpenAi(OpenAiError::Http { .. })
+            | Self::Anthropic(AnthropicError::Http { .. })
+            | Self::Gemini(GeminiError::Http { .. })
+            | Self::RequestTimeout { .. } => return "network",
+            // 响应能收到但结构不是 embedding 格式
+            Self::OpenAi(OpenAiError::EmbeddingField { .. })
+            | Self::OpenAi(OpenAiError::MissingField { .. })
+            | Self::OpenAi(OpenAiError::Json { .. })
+            | Self::UnsupportedOperation { .. } => return "not_embedding_model",
+            Self::OpenAi(OpenAiError::Api { status, message })
+            | Self::Anthropic(AnthropicError::Api { status, message })
+            | Self::Gemini(GeminiError::Api { status, message }) => (*status, message.as_str()),
+            _ => return "other",
+        };
+
+        match status.as_u16() {
+            401 | 403 => "auth",
+            429 => "rate_limited",
+            400 | 404 | 422 if looks_like_unsupported_model(message) => "not_embedding_model",
+            404 => "model_not_found",
+            500..=599 => "network",
+            _ => "other",
+        }
+    }
+
+    pub(crate) fn validation_error_kind(&self) -> &'static str {
+        let (status, message) = match self {
+            Self::OpenAi(OpenAiError::Http { source })
+            | Self::Anthropic(AnthropicError::Http { source })
+            | Self::Gemini(GeminiError::Http { source }) => {
+                // 连不上或地址本身不合法视为 URL 错误，超时等视为网络问题
+                return if source.is_connect() || source.is_builder() || source.is_redirect() {
+                    "wrong_url"
+                } else {
+                    "network"
+                };
+            }
+            Self::RequestTimeout { .. } => return "network",
+            // 收到了响应但不是 API 的 JSON（常见于地址指向网页）
+            Self::OpenAi(OpenAiError::Json { .. })
+            | Self::Anthropic(AnthropicError::Json { .. })
+            | Self::Gemini(GeminiError::Json { .. }) => return "wrong_url",
+            Self::OpenAi(OpenAiError::Api { status, message })
+            | Self::Anthropic(AnthropicError::Api { status, message })
+            | Self::Gemini(GeminiError::Api { status, message }) => (*status, message.as_str()),
+            _ => return "other",
+        };
+
+        let mentions_model = message.to_ascii_lowercase().contains("model");
+        match status.as_u16() {
+            401 | 403 => "auth",
+            429 => "rate_limited",
+            400 | 404 | 422 if mentions_model => "model_not_found",
+            404 | 405 => "wrong_url",
+            500..=599 => "network",
+            _ => "other",
+        }
+    }
+}
+
+fn looks_like_unsupported_model(message: &str) -> bool {
+    let lower = message.to_ascii_lowercase();
+    ["embedding", "not support", "unsupported", "invalid model"]
+        .iter()
+        .any(|needle| lower.contains(needle))
 }
 
 #[derive(Debug, Error)]
@@ -106,3 +205,62 @@ pub enum GeminiError {
     #[error("Gemini streaming error: {message}")]
     Stream { message: String },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_error(status: u16, message: &str) -> LlmError {
+        LlmError::Provider(LlmProviderError::OpenAi(OpenAiError::Api {
+            status: StatusCode::from_u16(status).unwrap(),
+            message: message.to_string(),
+        }))
+    }
+
+    #[test]
+    fn classifies_embedding_failures() {
+        assert_eq!(
+            api_error(401, "invalid api key").embedding_error_kind(),
+            "auth"
+        );
+        assert_eq!(
+            api_error(400, "This model does not support embeddings").embedding_error_kind(),
+            "not_embedding_model"
+        );
+        assert_eq!(
+            api_error(404, "no such route").embedding_error_kind(),
+            "model_not_found"
+        );
+        assert_eq!(
+            api_error(503, "overloaded").embedding_error_kind(),
+            "network"
+        );
+        assert_eq!(
+            LlmError::ModelNotFound {
+                model_id: "x".to_string()
+            }
+            .embedding_error_kind(),
+            "model_not_found"
+        );
+    }
+    #[test]
+    fn classifies_validation_failures() {
+        assert_eq!(api_error(403, "forbidden").validation_error_kind(), "auth");
+        assert_eq!(
+            api_error(404, "The model `gpt-x` does not exist").validation_error_kind(),
+            "model_not_found"
+        );
+        assert_eq!(
+            api_error(404, "Not Found").validation_error_kind(),
+            "wrong_url"
+        );
+        assert_eq!(
+            api_error(429, "slow down").validation_error_kind(),
+            "rate_limited"
+        );
+        assert_eq!(
+            api_error(400, "bad request").validation_error_kind(),
+            "other"
+        );
+    }
+}
@@ -50,7 +50,7 @@ static SHARED_HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
     Client::builder()
         .pool_max_idle_per_host(20)
         .pool_idle_timeout(Duration::from_secs(90))
-        .timeout(Duration::from_secs(120))
+        .timeout(super::REQUEST_TIMEOUT)
         .build()
         .expect("Failed to create shared HTTP client")
 });
@@ -131,6 +131,7 @@ impl AnthropicProvider {
             .post(self.get_endpoint())
             .headers(self.build_headers())
             .json(&request)
+            .timeout(super::MAX_STREAM_REQUEST_TIMEOUT)
             .send()
             .await
             .map_err(|e| AnthropicError::Http { source: e })?;
@@ -312,6 +313,7 @@ mod tests {
             tools: None,
             temperature: None,
             stop_sequences: None,
+            thinking: None,
             stream: false,
             top_p: None,
             top_k: None,
@@ -345,6 +347,7 @@ mod tests {
             tools: None,
             temperature: None,
             stop_sequences: None,
+            thinking: None,
             stream: false,
             top_p: None,
             top_k: None,
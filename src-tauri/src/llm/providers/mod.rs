@@ -17,6 +17,13 @@ use crate::llm::{
     types::{EmbeddingRequest, EmbeddingResponse},
 };
 
+/// LLM HTTP 请求的超时时间
+pub const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+/// 流式请求默认的整体超时（墙钟）；推理模型可能持续输出数分钟，取值宽松
+pub const DEFAULT_STREAM_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+/// 流式请求在 HTTP 层的超时上限；实际超时由 LLMService 按模型配置控制
+pub const MAX_STREAM_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3600);
+
 /// Provider 枚举 - 零成本抽象，静态分发
 ///
 /// 替代 Box<dyn LLMProvider>，消除 vtable 开销
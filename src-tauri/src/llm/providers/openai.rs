@@ -19,7 +19,7 @@ static SHARED_HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
     Client::builder()
         .pool_max_idle_per_host(20)
         .pool_idle_timeout(Duration::from_secs(90))
-        .timeout(Duration::from_secs(120))
+        .timeout(super::REQUEST_TIMEOUT)
         .build()
         .expect("Failed to create shared HTTP client")
 });
@@ -69,6 +69,9 @@ fn build_openai_chat_body(
         body["temperature"] = json!(temp);
     }
     body["max_tokens"] = json!(req.max_tokens);
+    if let Some(stop) = req.stop_sequences.as_ref().filter(|s| !s.is_empty()) {
+        body["stop"] = json!(stop);
+    }
     if let Some(tv) = tools_val {
         body["tools"] = tv;
         body["tool_choice"] = json!("auto");
@@ -307,7 +310,11 @@ impl LLMProvider for OpenAIProvider {
         let headers = self.get_headers();
         let body = build_openai_chat_body(&request, true);
 
-        let mut req = self.client().post(&url).json(&body);
+        let mut req = self
+            .client()
+            .post(&url)
+            .json(&body)
+            .timeout(super::MAX_STREAM_REQUEST_TIMEOUT);
         for (k, v) in headers {
             req = req.header(&k, &v);
         }
@@ -2,13 +2,20 @@ use std::sync::Arc;
 use tokio_stream::StreamExt;
 use tokio_util::sync::CancellationToken;
 
+use crate::ai::model_metadata::ModelMetadata;
 use crate::llm::{
-    anthropic_types::{CreateMessageRequest, Message, MessageContent, MessageParam, StreamEvent},
-    error::{LlmError, LlmProviderResult, LlmResult},
+    anthropic_types::{
+        CreateMessageRequest, Message, MessageContent, MessageParam, StreamEvent, Tool,
+    },
+    error::{LlmError, LlmProviderError, LlmProviderResult, LlmResult},
     provider_registry::ProviderRegistry,
-    types::{EmbeddingRequest, EmbeddingResponse, LLMProviderConfig},
+    providers::Provider,
+    types::{
+        EmbeddingModelTestResult, EmbeddingRequest, EmbeddingResponse, LLMProviderConfig,
+        ModelCapabilities, ModelValidationResult,
+    },
 };
-use crate::storage::repositories::AIModels;
+use crate::storage::repositories::{AIModelConfig, AIModels, ModelType};
 use crate::storage::DatabaseManager;
 
 pub struct LLMService {
@@ -20,48 +27,21 @@ impl LLMService {
         Self { database }
     }
 
-    /// 获取 Provider 配置和模型名：model_id → (config, model_name)
-    async fn get_provider_config_and_model(
-        &self,
-        model_id: &str,
-    ) -> LlmResult<(LLMProviderConfig, String)> {
-        let model = AIModels::new(&self.database)
+    async fn find_model(&self, model_id: &str) -> LlmResult<AIModelConfig> {
+        AIModels::new(&self.database)
             .find_by_id(model_id)
             .await?
             .ok_or_else(|| LlmError::ModelNotFound {
                 model_id: model_id.to_string(),
-            })?;
-
-        let provider_type = model.provider.as_str().to_string();
-
-        if !ProviderRegistry::global().supports(&provider_type) {
-            return Err(LlmError::UnsupportedProvider {
-                provider: provider_type.clone(),
-            });
-        }
-
-        let options = match model.options {
-            Some(value) => Some(
-                serde_json::from_value::<std::collections::HashMap<String, serde_json::Value>>(
-                    value,
-                )
-                .map_err(|source| LlmError::OptionsParse { source })?,
-            ),
-            None => None,
-        };
-
-        let config = LLMProviderConfig {
-            provider_type,
-            api_key: model.api_key,
-            api_url: if model.api_url.is_empty() {
-                None
-            } else {
-                Some(model.api_url)
-            },
-            options,
-        };
+            })
+    }
 
-        Ok((config, model.model))
+    /// 获取 Provider 配置和模型名：model_id → (config, model_name)
+    async fn get_provider_config_and_model(
+        &self,
+        model_id: &str,
+    ) -> LlmResult<(LLMProviderConfig, String)> {
+        provider_config_for(self.find_model(model_id).await?)
     }
 
     /// 非流式调用
@@ -92,6 +72,9 @@ impl LLMService {
     }
 
     /// 流式调用（带取消令牌）
+    ///
+    /// 整个请求受模型的 requestTimeoutMs 约束（未配置时为 DEFAULT_STREAM_REQUEST_TIMEOUT）：
+    /// 超时后取消令牌、中止 HTTP 请求，并在流中返回 RequestTimeout 错误
     pub async fn call_stream(
         &self,
         request: CreateMessageRequest,
@@ -99,7 +82,10 @@ impl LLMService {
     ) -> LlmResult<impl tokio_stream::Stream<Item = LlmProviderResult<StreamEvent>>> {
         self.validate_request(&request)?;
 
-        let (config, model_name) = self.get_provider_config_and_model(&request.model).await?;
+        let model = self.find_model(&request.model).await?;
+        let timeout = ModelMetadata::from_model(&model).stream_request_timeout();
+        let deadline = tokio::time::Instant::now() + timeout;
+        let (config, model_name) = provider_config_for(model)?;
 
         let provider = ProviderRegistry::global()
             .create(config.clone())
@@ -128,6 +114,13 @@ impl LLMService {
                         _ = token.cancelled() => {
                             break;
                         }
+                        _ = tokio::time::sleep_until(deadline) => {
+                            let timeout_ms = timeout.as_millis() as u64;
+                            tracing::warn!("LLM stream exceeded request timeout of {} ms", timeout_ms);
+                            token.cancel();
+                            let _ = tx.send(Err(LlmProviderError::RequestTimeout { timeout_ms })).await;
+                            break;
+                        }
                         item = stream.next() => {
                             if let Some(item) = item {
                                 if tx.send(item).await.is_err() {
@@ -169,6 +162,82 @@ impl LLMService {
         result.map_err(LlmError::from)
     }
 
+    /// 用一条短文本测试 embedding 模型，返回实际维度与耗时
+    pub async fn test_embedding_model(&self, model_id: &str) -> EmbeddingModelTestResult {
+        let start = std::time::Instant::now();
+        let request = EmbeddingRequest {
+            model: model_id.to_string(),
+            input: vec!["OrbitX embedding test".to_string()],
+            encoding_format: None,
+            dimensions: None,
+        };
+
+        let result = self.create_embeddings(request).await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(response) => match response.data.first() {
+                Some(data) if !data.embedding.is_empty() => EmbeddingModelTestResult {
+                    ok: true,
+                    dimension: Some(data.embedding.len()),
+                    latency_ms,
+                    error: None,
+                    error_kind: None,
+                },
+                _ => EmbeddingModelTestResult {
+                    ok: false,
+                    dimension: None,
+                    latency_ms,
+                    error: Some("Empty embedding returned".to_string()),
+                    error_kind: Some("not_embedding_model".to_string()),
+                },
+            },
+            Err(err) => EmbeddingModelTestResult {
+                ok: false,
+                dimension: None,
+                latency_ms,
+                error_kind: Some(err.embedding_error_kind().to_string()),
+                error: Some(err.to_string()),
+            },
+        }
+    }
+
+    /// 校验未保存的模型配置：对话模型依次探测普通调用、流式与工具调用，
+    /// embedding 模型探测向量维度。失败原因放在结果中，不返回错误
+    pub async fn validate_model_config(&self, model: AIModelConfig) -> ModelValidationResult {
+        let start = std::time::Instant::now();
+        let model_type = model.model_type.clone();
+        let capabilities = async {
+            let (config, model_name) = provider_config_for(model)?;
+            let provider = ProviderRegistry::global()
+                .create(config)
+                .map_err(LlmError::from)?;
+            match model_type {
+                ModelType::Embedding => probe_embedding(&provider, model_name).await,
+                ModelType::Chat => probe_chat(&provider, model_name).await,
+            }
+        }
+        .await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match capabilities {
+            Ok(capabilities) => ModelValidationResult {
+                ok: true,
+                latency_ms,
+                error: None,
+                error_kind: None,
+                capabilities: Some(capabilities),
+            },
+            Err(err) => ModelValidationResult {
+                ok: false,
+                latency_ms,
+                error_kind: Some(err.validation_error_kind().to_string()),
+                error: Some(err.to_string()),
+                capabilities: None,
+            },
+        }
+    }
+
     /// 获取可用的模型列表
     pub async fn get_available_models(&self) -> LlmResult<Vec<String>> {
         let ai_models = AIModels::new(&self.database);
@@ -191,6 +260,7 @@ impl LLMService {
             temperature: Some(0.1),
             stream: false,
             stop_sequences: None,
+            thinking: None,
             top_p: None,
             top_k: None,
             metadata: None,
@@ -237,3 +307,112 @@ impl LLMService {
         Ok(())
     }
 }
+
+fn probe_request(model: String, tools: Option<Vec<Tool>>) -> CreateMessageRequest {
+    CreateMessageRequest {
+        model,
+        messages: vec![MessageParam {
+            role: crate::llm::anthropic_types::MessageRole::User,
+            content: MessageContent::Text("Hello".to_string()),
+        }],
+        max_tokens: 16,
+        system: None,
+        tools,
+        temperature: None,
+        stream: false,
+        stop_sequences: None,
+        top_p: None,
+        top_k: None,
+        metadata: None,
+        thinking: None,
+    }
+}
+
+/// 普通调用失败即整体失败；流式与工具调用只影响对应能力
+async fn probe_chat(provider: &Provider, model: String) -> LlmResult<ModelCapabilities> {
+    provider
+        .call(probe_request(model.clone(), None))
+        .await
+        .map_err(LlmError::from)?;
+
+    let streaming = match provider
+        .call_stream(CreateMessageRequest {
+            stream: true,
+            ..probe_request(model.clone(), None)
+        })
+        .await
+    {
+        Ok(mut stream) => matches!(stream.next().await, Some(Ok(_))),
+        Err(_) => false,
+    };
+
+    let probe_tool = Tool::new(
+        "get_current_time",
+        "Returns the current time.",
+        serde_json::json!({ "type": "object", "properties": {} }),
+    );
+    let tools = provider
+        .call(probe_request(model, Some(vec![probe_tool])))
+        .await
+        .is_ok();
+
+    Ok(ModelCapabilities {
+        streaming: Some(streaming),
+        tools: Some(tools),
+        embedding_dimension: None,
+    })
+}
+
+async fn probe_embedding(provider: &Provider, model: String) -> LlmResult<ModelCapabilities> {
+    let response = provider
+        .create_embeddings(EmbeddingRequest {
+            model,
+            input: vec!["OrbitX embedding test".to_string()],
+            encoding_format: None,
+            dimensions: None,
+        })
+        .await
+        .map_err(LlmError::from)?;
+    let dimension = response
+        .data
+        .first()
+        .map(|d| d.embedding.len())
+        .filter(|&len| len > 0);
+    Ok(ModelCapabilities {
+        streaming: None,
+        tools: None,
+        embedding_dimension: dimension,
+    })
+}
+
+/// 由模型配置（已保存或未保存）得到 Provider 配置和模型名
+fn provider_config_for(model: AIModelConfig) -> LlmResult<(LLMProviderConfig, String)> {
+    let provider_type = model.provider.as_str().to_string();
+
+    if !ProviderRegistry::global().supports(&provider_type) {
+        return Err(LlmError::UnsupportedProvider {
+            provider: provider_type.clone(),
+        });
+    }
+
+    let options = match model.options {
+        Some(value) => Some(
+            serde_json::from_value::<std::collections::HashMap<String, serde_json::Value>>(value)
+                .map_err(|source| LlmError::OptionsParse { source })?,
+        ),
+        None => None,
+    };
+
+    let config = LLMProviderConfig {
+        provider_type,
+        api_key: model.api_key,
+        api_url: if model.api_url.is_empty() {
+            None
+        } else {
+            Some(model.api_url)
+        },
+        options,
+    };
+
+    Ok((config, model.model))
+}
@@ -19,6 +19,41 @@ pub struct EmbeddingRequest {
     pub dimensions: Option<usize>,
 }
 
+/// Embedding 模型测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingModelTestResult {
+    pub ok: bool,
+    /// 实际返回的向量维度
+    pub dimension: Option<usize>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    /// 失败类别：auth / network / not_embedding_model / model_not_found / rate_limited / other
+    pub error_kind: Option<String>,
+}
+
+/// 未保存模型配置的校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelValidationResult {
+    pub ok: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    /// 失败类别：auth / wrong_url / model_not_found / rate_limited / network / other
+    pub error_kind: Option<String>,
+    /// 校验通过时探测到的能力
+    pub capabilities: Option<ModelCapabilities>,
+}
+
+/// 探测到的模型能力；None 表示该类模型不适用
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCapabilities {
+    pub streaming: Option<bool>,
+    pub tools: Option<bool>,
+    pub embedding_dimension: Option<usize>,
+}
+
 /// Embedding 响应
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingResponse {
@@ -1,16 +1,17 @@
 use crate::{
     mux::{
         error::{IoHandlerError, IoHandlerResult},
-        MuxNotification, Pane, PaneId,
+        MuxNotification, OutputDecoder, Pane, PaneId,
     },
     shell::ShellIntegrationManager,
 };
 use bytes::Bytes;
 use crossbeam_channel::Sender;
+use encoding_rs::Encoding;
 use std::{
     collections::HashMap,
     io::{self, Read},
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
     thread,
     time::Duration,
 };
@@ -22,6 +23,8 @@ pub struct IoHandler {
     shell_integration: Arc<ShellIntegrationManager>,
     /// 存储每个面板的读取线程句柄
     reader_threads: Arc<RwLock<HashMap<PaneId, thread::JoinHandle<()>>>>,
+    /// 每个面板的输出解码器，与读取线程共享以便运行时切换编码
+    decoders: Arc<RwLock<HashMap<PaneId, Arc<Mutex<OutputDecoder>>>>>,
 }
 
 impl IoHandler {
@@ -34,6 +37,7 @@ impl IoHandler {
             notification_sender,
             shell_integration,
             reader_threads: Arc::new(RwLock::new(HashMap::new())),
+            decoders: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -47,6 +51,7 @@ impl IoHandler {
             notification_sender,
             shell_integration,
             reader_threads: Arc::new(RwLock::new(HashMap::new())),
+            decoders: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -54,13 +59,22 @@ impl IoHandler {
         self.buffer_size
     }
 
-    pub fn spawn_io_threads(&self, pane: Arc<dyn Pane>) -> IoHandlerResult<()> {
+    pub fn spawn_io_threads(
+        &self,
+        pane: Arc<dyn Pane>,
+        decoder: OutputDecoder,
+    ) -> IoHandlerResult<()> {
         let pane_id = pane.pane_id();
         let reader = pane.reader().map_err(|err| IoHandlerError::PaneReader {
             reason: format!("Failed to acquire reader for {:?}: {err}", pane_id),
         })?;
 
-        let handle = self.spawn_reader_thread(pane_id, reader, pane);
+        let decoder = Arc::new(Mutex::new(decoder));
+        if let Ok(mut decoders) = self.decoders.write() {
+            decoders.insert(pane_id, decoder.clone());
+        }
+
+        let handle = self.spawn_reader_thread(pane_id, reader, pane, decoder);
 
         // 存储线程句柄
         if let Ok(mut threads) = self.reader_threads.write() {
@@ -72,7 +86,32 @@ impl IoHandler {
         Ok(())
     }
 
+    /// 切换面板的输出编码，面板不存在时返回 false
+    pub fn set_output_encoding(&self, pane_id: PaneId, encoding: &'static Encoding) -> bool {
+        let decoder = self
+            .decoders
+            .read()
+            .ok()
+            .and_then(|decoders| decoders.get(&pane_id).cloned());
+        match decoder {
+            Some(decoder) => {
+                lock_decoder(&decoder).set_encoding(encoding);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn output_encoding(&self, pane_id: PaneId) -> Option<&'static Encoding> {
+        let decoder = self.decoders.read().ok()?.get(&pane_id).cloned()?;
+        let encoding = lock_decoder(&decoder).encoding();
+        Some(encoding)
+    }
+
     pub fn stop_pane_io(&self, pane_id: PaneId) -> IoHandlerResult<()> {
+        if let Ok(mut decoders) = self.decoders.write() {
+            decoders.remove(&pane_id);
+        }
         if let Ok(mut threads) = self.reader_threads.write() {
             if let Some(handle) = threads.remove(&pane_id) {
                 // 使用 thread::spawn 在后台 join，避免阻塞
@@ -136,14 +175,13 @@ impl IoHandler {
         pane_id: PaneId,
         mut reader: Box<dyn Read + Send>,
         pane: Arc<dyn Pane>,
+        decoder: Arc<Mutex<OutputDecoder>>,
     ) -> thread::JoinHandle<()> {
         let mut buffer = vec![0u8; self.buffer_size];
         let sender = self.notification_sender.clone();
         let integration = self.shell_integration.clone();
 
         thread::spawn(move || {
-            let mut pending = Vec::new();
-
             loop {
                 // 检查面板是否已死亡
                 if pane.is_dead() {
@@ -153,24 +191,27 @@ impl IoHandler {
                 match reader.read(&mut buffer) {
                     Ok(0) => break,
                     Ok(len) => {
-                        for chunk in decode_utf8_stream(&mut pending, &buffer[..len]) {
-                            // Shell事件现在通过broadcast channel发送,不再返回
-                            integration.process_output(pane_id, &chunk);
+                        let chunk = lock_decoder(&decoder).decode(&buffer[..len], false);
+                        if chunk.is_empty() {
+                            continue;
+                        }
 
-                            let cleaned = integration.strip_osc_sequences(&chunk);
+                        // Shell事件现在通过broadcast channel发送,不再返回
+                        integration.process_output(pane_id, &chunk);
 
-                            if cleaned.is_empty() {
-                                continue;
-                            }
+                        let cleaned = integration.strip_osc_sequences(&chunk);
 
-                            let notification = MuxNotification::PaneOutput {
-                                pane_id,
-                                data: Bytes::from(cleaned.into_bytes()),
-                            };
+                        if cleaned.is_empty() {
+                            continue;
+                        }
+
+                        let notification = MuxNotification::PaneOutput {
+                            pane_id,
+                            data: Bytes::from(cleaned.into_bytes()),
+                        };
 
-                            if sender.send(notification).is_err() {
-                                return;
-                            }
+                        if sender.send(notification).is_err() {
+                            return;
                         }
                     }
                     Err(err) => {
@@ -183,21 +224,21 @@ impl IoHandler {
                 }
             }
 
-            for chunk in decode_utf8_stream(&mut pending, &[]) {
+            // 冲刷解码器中残留的不完整序列
+            let chunk = lock_decoder(&decoder).decode(&[], true);
+            if !chunk.is_empty() {
                 integration.process_output(pane_id, &chunk);
                 let cleaned = integration.strip_osc_sequences(&chunk);
 
-                if cleaned.is_empty() {
-                    continue;
-                }
-
-                let notification = MuxNotification::PaneOutput {
-                    pane_id,
-                    data: Bytes::from(cleaned.into_bytes()),
-                };
+                if !cleaned.is_empty() {
+                    let notification = MuxNotification::PaneOutput {
+                        pane_id,
+                        data: Bytes::from(cleaned.into_bytes()),
+                    };
 
-                if sender.send(notification).is_err() {
-                    return;
+                    if sender.send(notification).is_err() {
+                        return;
+                    }
                 }
             }
 
@@ -213,64 +254,9 @@ impl IoHandler {
     }
 }
 
-/// 优化的 UTF-8 流解码函数
-///
-/// 使用更高效的方式处理字节流到字符串的转换：
-/// - 减少 Vec 操作，使用 split_off 替代 drain
-/// - 预分配字符串容量
-/// - 减少中间分配
-fn decode_utf8_stream(pending: &mut Vec<u8>, input: &[u8]) -> Vec<String> {
-    if input.is_empty() && pending.is_empty() {
-        return Vec::new();
-    }
-
-    pending.extend_from_slice(input);
-
-    // 预分配结果向量（通常只有1-2个片段）
-    let mut frames = Vec::with_capacity(2);
-
-    loop {
-        if pending.is_empty() {
-            break;
-        }
-
-        match std::str::from_utf8(pending) {
-            Ok(valid) => {
-                // 整个缓冲区都是有效 UTF-8
-                if !valid.is_empty() {
-                    frames.push(valid.to_string());
-                }
-                pending.clear();
-                break;
-            }
-            Err(err) => {
-                let valid_up_to = err.valid_up_to();
-
-                if valid_up_to > 0 {
-                    // 有部分有效的 UTF-8 数据
-                    let valid = unsafe { std::str::from_utf8_unchecked(&pending[..valid_up_to]) };
-                    if !valid.is_empty() {
-                        frames.push(valid.to_string());
-                    }
-
-                    // 使用 split_off 代替 drain，更高效
-                    *pending = pending.split_off(valid_up_to);
-                    continue;
-                }
-
-                // 处理无效字节
-                if let Some(error_len) = err.error_len() {
-                    // 跳过无效字节
-                    let drop_len = error_len.max(1).min(pending.len());
-                    *pending = pending.split_off(drop_len);
-                    continue;
-                }
-
-                // 不完整的 UTF-8 序列，保留在缓冲区中等待更多数据
-                break;
-            }
-        }
-    }
-
-    frames
+/// 解码器只做纯计算，锁被毒化时继续使用内部数据
+fn lock_decoder(decoder: &Mutex<OutputDecoder>) -> std::sync::MutexGuard<'_, OutputDecoder> {
+    decoder
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
 }
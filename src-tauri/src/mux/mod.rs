@@ -6,8 +6,10 @@ pub mod config;
 pub mod error;
 
 pub mod io_handler;
+pub mod output_decoder;
 pub mod pane;
 pub mod performance_monitor;
+pub mod selection;
 pub mod shell_manager;
 pub mod singleton;
 // Note: tauri_integration module removed - event handling now unified in terminal::event_handler
@@ -21,8 +23,10 @@ pub use error::{
     TerminalMuxResult,
 };
 pub use io_handler::*;
+pub use output_decoder::*;
 pub use pane::*;
 pub use performance_monitor::*;
+pub use selection::{CellCoord, SelectionOptions, TextGrid};
 pub use shell_manager::*;
 pub use singleton::*;
 pub use terminal_mux::*;
@@ -0,0 +1,302 @@
+//! PTY 输出解码
+//!
+//! 按面板配置的编码把 PTY 字节流解码为 UTF-8 文本。解码器跨读取边界保留
+//! 未完成的多字节序列。默认 UTF-8 时在最近一段输出中统计替换字符，比例过高时
+//! 自动切换到 GBK 或 Latin-1；之后再次出现有效的 UTF-8 多字节文本时切回 UTF-8。
+
+use encoding_rs::{Decoder, Encoding, GBK, UTF_8, WINDOWS_1252};
+use tracing::info;
+
+/// 默认输出编码
+pub const DEFAULT_OUTPUT_ENCODING: &str = "utf-8";
+
+/// 触发自动检测所需的最少替换字符数
+const DETECT_MIN_REPLACEMENTS: usize = 4;
+/// 替换字符占比达到 1/10 时认为编码不匹配
+const DETECT_REPLACEMENT_RATIO: usize = 10;
+/// 统计窗口的字符数，超出后计数减半，使较早的输出逐渐失去权重
+const DETECT_WINDOW_CHARS: usize = 4096;
+/// 回退编码下出现至少这么多个有效的 UTF-8 多字节字符时切回 UTF-8
+const UTF8_RECOVER_MIN_CHARS: usize = 2;
+
+/// 按 WHATWG 标签解析编码（如 "utf-8"、"gbk"、"latin1"）
+pub fn resolve_output_encoding(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+}
+
+pub struct OutputDecoder {
+    encoding: &'static Encoding,
+    /// 显式指定编码时使用的流式解码器；为 None 时处于自动检测模式
+    decoder: Option<Decoder>,
+    /// 自动检测模式下尚未解码的不完整尾部字节，切换编码时一并带到下一段
+    pending: Vec<u8>,
+    window_chars: usize,
+    window_replacements: usize,
+}
+
+impl OutputDecoder {
+    /// UTF-8 允许自动检测，其它编码视为显式指定
+    pub fn new(encoding: &'static Encoding) -> Self {
+        Self {
+            encoding,
+            decoder: (encoding != UTF_8).then(|| encoding.new_decoder_without_bom_handling()),
+            pending: Vec::new(),
+            window_chars: 0,
+            window_replacements: 0,
+        }
+    }
+
+    /// 按标签创建，标签为空或无法识别时回退到 UTF-8
+    pub fn from_label(label: Option<&str>) -> Self {
+        Self::new(label.and_then(resolve_output_encoding).unwrap_or(UTF_8))
+    }
+
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+
+    /// 显式切换编码并关闭自动检测；未完成的字节序列被丢弃
+    pub fn set_encoding(&mut self, encoding: &'static Encoding) {
+        self.encoding = encoding;
+        self.decoder = Some(encoding.new_decoder_without_bom_handling());
+        self.pending.clear();
+    }
+
+    /// 解码一段输出；`last` 为 true 时冲刷缓冲的不完整序列
+    pub fn decode(&mut self, input: &[u8], last: bool) -> String {
+        if let Some(decoder) = self.decoder.as_mut() {
+            return decode_with(decoder, input, last);
+        }
+
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(input);
+
+        if self.encoding != UTF_8 && recovers_utf8(&bytes) {
+            info!("PTY 输出恢复为有效的 UTF-8，切回 UTF-8");
+            self.switch_detected(UTF_8);
+        }
+
+        let complete = if last {
+            bytes.len()
+        } else {
+            bytes.len() - incomplete_tail(self.encoding, &bytes)
+        };
+        let (text, _) = self
+            .encoding
+            .decode_without_bom_handling(&bytes[..complete]);
+
+        if self.encoding == UTF_8 && self.record_replacements(&text) {
+            let detected = detect_encoding(&bytes[..complete]);
+            info!(
+                "PTY 输出不是有效的 {}，自动切换为 {}",
+                self.encoding.name(),
+                detected.name()
+            );
+            self.switch_detected(detected);
+            // 当前段按新编码重新计算完整部分，不完整的尾部留给下一段
+            let complete = if last {
+                bytes.len()
+            } else {
+                bytes.len() - incomplete_tail(detected, &bytes)
+            };
+            let text = detected
+                .decode_without_bom_handling(&bytes[..complete])
+                .0
+                .into_owned();
+            self.pending = bytes[complete..].to_vec();
+            return text;
+        }
+
+        let text = text.into_owned();
+        self.pending = bytes[complete..].to_vec();
+        text
+    }
+
+    /// 自动检测下切换编码，并重新开始统计
+    fn switch_detected(&mut self, encoding: &'static Encoding) {
+        self.encoding = encoding;
+        self.window_chars = 0;
+        self.window_replacements = 0;
+    }
+
+    /// 把本段的替换字符计入窗口，返回窗口内是否已达到切换阈值
+    fn record_replacements(&mut self, text: &str) -> bool {
+        for ch in text.chars() {
+            self.window_chars += 1;
+            if ch == char::REPLACEMENT_CHARACTER {
+                self.window_replacements += 1;
+            }
+        }
+        while self.window_chars > DETECT_WINDOW_CHARS {
+            self.window_chars /= 2;
+            self.window_replacements /= 2;
+        }
+        self.window_replacements >= DETECT_MIN_REPLACEMENTS
+            && self.window_replacements * DETECT_REPLACEMENT_RATIO >= self.window_chars
+    }
+}
+
+impl Default for OutputDecoder {
+    fn default() -> Self {
+        Self::new(UTF_8)
+    }
+}
+
+fn decode_with(decoder: &mut Decoder, input: &[u8], last: bool) -> String {
+    let capacity = decoder
+        .max_utf8_buffer_length(input.len())
+        .unwrap_or(input.len() * 3);
+    let mut output = String::with_capacity(capacity);
+    // 容量足够时一次即可消费全部输入，不完整的尾部留在 decoder 内部
+    let _ = decoder.decode_to_string(input, &mut output, last);
+    output
+}
+
+/// 末尾被截断的多字节序列长度，这部分字节留到下一段再解码
+fn incomplete_tail(encoding: &'static Encoding, bytes: &[u8]) -> usize {
+    if encoding == UTF_8 {
+        utf8_incomplete_tail(bytes)
+    } else if encoding == GBK {
+        gbk_incomplete_tail(bytes)
+    } else {
+        // windows-1252 为单字节编码
+        0
+    }
+}
+
+fn utf8_incomplete_tail(bytes: &[u8]) -> usize {
+    let start = bytes.len().saturating_sub(3);
+    for i in (start..bytes.len()).rev() {
+        let byte = bytes[i];
+        let needed = match byte {
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            // 续字节，继续向前寻找首字节
+            0x80..=0xBF => continue,
+            _ => return 0,
+        };
+        let available = bytes.len() - i;
+        return if available < needed { available } else { 0 };
+    }
+    0
+}
+
+/// GBK（GB18030）首字节 0x81..=0xFE，第二字节为数字时为四字节序列
+fn gbk_incomplete_tail(bytes: &[u8]) -> usize {
+    let mut i = 0;
+    while i < bytes.len() {
+        let needed = match bytes[i] {
+            0x81..=0xFE => match bytes.get(i + 1) {
+                Some(0x30..=0x39) => 4,
+                _ => 2,
+            },
+            _ => 1,
+        };
+        if i + needed > bytes.len() {
+            return bytes.len() - i;
+        }
+        i += needed;
+    }
+    0
+}
+
+/// 去掉不完整尾部后是有效的 UTF-8，且包含足够多的多字节字符
+fn recovers_utf8(bytes: &[u8]) -> bool {
+    let complete = &bytes[..bytes.len() - utf8_incomplete_tail(bytes)];
+    match std::str::from_utf8(complete) {
+        Ok(text) => text.chars().filter(|c| !c.is_ascii()).count() >= UTF8_RECOVER_MIN_CHARS,
+        Err(_) => false,
+    }
+}
+
+/// 依次尝试 GBK 与 Latin-1；GBK 允许末尾一个被截断的双字节字符
+fn detect_encoding(input: &[u8]) -> &'static Encoding {
+    let (gbk, _) = GBK.decode_without_bom_handling(input);
+    let gbk_errors = gbk
+        .chars()
+        .filter(|c| *c == char::REPLACEMENT_CHARACTER)
+        .count();
+    if gbk_errors <= 1 {
+        GBK
+    } else {
+        // windows-1252 可以映射任意字节
+        WINDOWS_1252
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_multibyte_sequences_split_across_reads() {
+        let bytes = "你好".as_bytes();
+        let mut decoder = OutputDecoder::default();
+        assert_eq!(decoder.decode(&bytes[..2], false), "");
+        assert_eq!(decoder.decode(&bytes[2..4], false), "你");
+        assert_eq!(decoder.decode(&bytes[4..], false), "好");
+
+        let (gbk, _, _) = GBK.encode("中文");
+        let mut decoder = OutputDecoder::from_label(Some("gbk"));
+        assert_eq!(decoder.decode(&gbk[..1], false), "");
+        assert_eq!(decoder.decode(&gbk[1..], true), "中文");
+    }
+
+    #[test]
+    fn falls_back_when_utf8_output_is_garbled() {
+        let (gbk, _, _) = GBK.encode("编译完成，没有错误");
+        let mut decoder = OutputDecoder::default();
+        assert_eq!(decoder.decode(&gbk, false), "编译完成，没有错误");
+        assert_eq!(decoder.encoding(), GBK);
+
+        let mut decoder = OutputDecoder::default();
+        let latin1 = b"caf\xe9 cr\xe8me br\xfbl\xe9e \xe0 la fran\xe7aise";
+        assert_eq!(
+            decoder.decode(latin1, false),
+            "café crème brûlée à la française"
+        );
+        assert_eq!(decoder.encoding(), WINDOWS_1252);
+    }
+
+    #[test]
+    fn explicit_encoding_disables_detection() {
+        let mut decoder = OutputDecoder::default();
+        decoder.set_encoding(UTF_8);
+        let text = decoder.decode(b"\xe9\xe8\xfb\xe0\xe7", false);
+        assert!(text.chars().all(|c| c == char::REPLACEMENT_CHARACTER));
+        assert_eq!(decoder.encoding(), UTF_8);
+        assert!(resolve_output_encoding("latin1").is_some());
+        assert!(resolve_output_encoding("not-an-encoding").is_none());
+    }
+
+    #[test]
+    fn detection_uses_a_window_and_returns_to_utf8() {
+        // 大量正常输出中夹杂的少量无效字节不会触发切换
+        let mut decoder = OutputDecoder::default();
+        decoder.decode("ok\n".repeat(200).as_bytes(), false);
+        decoder.decode(b"\xff\xfe\xff\xfe", false);
+        assert_eq!(decoder.encoding(), UTF_8);
+
+        let (gbk, _, _) = GBK.encode("编译完成，没有错误");
+        let mut decoder = OutputDecoder::default();
+        assert_eq!(decoder.decode(&gbk, false), "编译完成，没有错误");
+        assert_eq!(decoder.encoding(), GBK);
+
+        // 切换后被截断的 UTF-8 多字节序列留到下一段
+        let utf8 = "构建成功".as_bytes();
+        assert_eq!(decoder.decode(&utf8[..7], false), "构建");
+        assert_eq!(decoder.encoding(), UTF_8);
+        assert_eq!(decoder.decode(&utf8[7..], false), "成功");
+    }
+
+    #[test]
+    fn incomplete_tails_are_carried_over() {
+        let (gbk, _, _) = GBK.encode("中文");
+        assert_eq!(gbk_incomplete_tail(&gbk[..3]), 1);
+        assert_eq!(gbk_incomplete_tail(&gbk), 0);
+        assert_eq!(utf8_incomplete_tail(&"你".as_bytes()[..2]), 2);
+        assert_eq!(utf8_incomplete_tail("你".as_bytes()), 0);
+        assert_eq!(utf8_incomplete_tail(b"abc"), 0);
+    }
+}
@@ -144,6 +144,9 @@ impl LocalPane {
             crate::shell::ShellType::Fish => {
                 cmd.env("ORBITX_INTEGRATION_SCRIPT", integration_script);
             }
+            crate::shell::ShellType::Cmd => {
+                Self::setup_cmd_integration(cmd);
+            }
             _ => {
                 cmd.env("ORBITX_INTEGRATION_SCRIPT", integration_script);
             }
@@ -156,6 +159,17 @@ impl LocalPane {
         Ok(())
     }
 
+    /// 设置 cmd Shell Integration：直接包装 PROMPT，不依赖 AutoRun 是否已安装
+    fn setup_cmd_integration(cmd: &mut CommandBuilder) {
+        use crate::shell::script_generator::cmd as cmd_script;
+
+        let base = std::env::var("PROMPT").unwrap_or_else(|_| cmd_script::DEFAULT_PROMPT.into());
+        let prompt =
+            cmd_script::wrap_prompt(&crate::shell::ShellIntegrationConfig::default(), &base);
+        cmd.env("PROMPT", prompt);
+        cmd.env(cmd_script::PROMPT_WRAPPED_ENV, "1");
+    }
+
     /// 设置 Zsh Shell Integration
     fn setup_zsh_integration(cmd: &mut CommandBuilder, integration_script: &str) -> PaneResult<()> {
         let temp_dir = std::env::temp_dir().join(format!("orbitx-{}", process::id()));
@@ -275,14 +289,6 @@ impl Pane for LocalPane {
             return Err(PaneError::PaneDead);
         }
 
-        // 原子更新尺寸
-        self.rows.store(size.rows, Ordering::Relaxed);
-        self.cols.store(size.cols, Ordering::Relaxed);
-        self.pixel_width.store(size.pixel_width, Ordering::Relaxed);
-        self.pixel_height
-            .store(size.pixel_height, Ordering::Relaxed);
-
-        // 调整PTY大小
         let pty_size = PortablePtySize {
             rows: size.rows,
             cols: size.cols,
@@ -290,6 +296,8 @@ impl Pane for LocalPane {
             pixel_height: size.pixel_height,
         };
 
+        // 持有 master 锁完成 TIOCSWINSZ（内核随之向前台进程组发送 SIGWINCH）与尺寸记录，
+        // 并发 resize 不会交错，记录的尺寸始终与 PTY 实际尺寸一致
         let master = self
             .master
             .lock()
@@ -300,6 +308,12 @@ impl Pane for LocalPane {
             PaneError::Internal(format!("Pane {:?} PTY resize failed: {err}", self.pane_id))
         })?;
 
+        self.rows.store(size.rows, Ordering::Relaxed);
+        self.cols.store(size.cols, Ordering::Relaxed);
+        self.pixel_width.store(size.pixel_width, Ordering::Relaxed);
+        self.pixel_height
+            .store(size.pixel_height, Ordering::Relaxed);
+
         Ok(())
     }
 
@@ -0,0 +1,197 @@
+//! 终端选区文本提取
+//!
+//! 后端不维护终端网格，这里按面板列数回放输出缓冲（含滚动历史）重建字符网格，
+//! 记录每一行是否为软换行，再按单元格坐标取出选区文本。每个字符按一个单元格计算，
+//! 光标定位等全屏程序的控制序列会被忽略。
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::ansi::skip_escape_sequence;
+
+const TAB_WIDTH: usize = 8;
+
+/// 网格单元格坐标，row 从输出缓冲的第一行开始计数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CellCoord {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectionOptions {
+    /// 块选：每行取相同的列范围，不合并软换行
+    pub rectangular: bool,
+    /// 去掉每行末尾的空白
+    pub trim_trailing_whitespace: bool,
+}
+
+impl Default for SelectionOptions {
+    fn default() -> Self {
+        Self {
+            rectangular: false,
+            trim_trailing_whitespace: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct GridRow {
+    cells: Vec<char>,
+    /// 该行因到达列宽而折到下一行
+    wrapped: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TextGrid {
+    rows: Vec<GridRow>,
+    cols: usize,
+}
+
+impl TextGrid {
+    /// 按列宽回放终端输出
+    pub fn from_output(output: &str, cols: usize) -> Self {
+        let cols = cols.max(1);
+        let mut rows = vec![GridRow::default()];
+        let mut col = 0usize;
+        let mut chars = output.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\u{1b}' => skip_escape_sequence(&mut chars),
+                '\n' => {
+                    rows.push(GridRow::default());
+                    col = 0;
+                }
+                '\r' => col = 0,
+                '\u{8}' => col = col.saturating_sub(1),
+                '\t' => col = ((col / TAB_WIDTH + 1) * TAB_WIDTH).min(cols - 1),
+                c if c.is_control() => {}
+                c => {
+                    if col >= cols {
+                        rows.last_mut().expect("grid has a row").wrapped = true;
+                        rows.push(GridRow::default());
+                        col = 0;
+                    }
+                    let cells = &mut rows.last_mut().expect("grid has a row").cells;
+                    if cells.len() <= col {
+                        cells.resize(col, ' ');
+                        cells.push(c);
+                    } else {
+                        cells[col] = c;
+                    }
+                    col += 1;
+                }
+            }
+        }
+
+        Self { rows, cols }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// 取出 start 到 end（均包含）之间的文本；坐标顺序可颠倒，超出网格的部分被截断
+    pub fn selection_text(
+        &self,
+        start: CellCoord,
+        end: CellCoord,
+        options: SelectionOptions,
+    ) -> String {
+        let last_row = self.rows.len() - 1;
+        let mut lines: Vec<String> = Vec::new();
+
+        if options.rectangular {
+            let (first, last) = (start.row.min(end.row), start.row.max(end.row).min(last_row));
+            let (left, right) = (start.col.min(end.col), start.col.max(end.col));
+            for row in self.rows.iter().take(last + 1).skip(first) {
+                lines.push(cells_between(&row.cells, left, right + 1));
+            }
+        } else {
+            let (start, end) = if start <= end {
+                (start, end)
+            } else {
+                (end, start)
+            };
+            let last = end.row.min(last_row);
+            let mut current = String::new();
+            for index in start.row..=last {
+                let row = &self.rows[index];
+                let from = if index == start.row { start.col } else { 0 };
+                let to = if index == end.row {
+                    end.col + 1
+                } else {
+                    row.cells.len()
+                };
+                current.push_str(&cells_between(&row.cells, from, to));
+                // 软换行的行与下一行属于同一逻辑行
+                if !row.wrapped || index == last {
+                    lines.push(std::mem::take(&mut current));
+                }
+            }
+        }
+
+        if options.trim_trailing_whitespace {
+            for line in &mut lines {
+                line.truncate(line.trim_end().len());
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+fn cells_between(cells: &[char], from: usize, to: usize) -> String {
+    let to = to.min(cells.len());
+    if from >= to {
+        return String::new();
+    }
+    cells[from..to].iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(row: usize, col: usize) -> CellCoord {
+        CellCoord { row, col }
+    }
+
+    #[test]
+    fn linear_selection_joins_wrapped_rows() {
+        // 12 个字符在 5 列下折成 3 行
+        let grid = TextGrid::from_output("\u{1b}[32mhello world!\u{1b}[0m\r\nnext \r\n", 5);
+        assert_eq!(grid.row_count(), 5);
+        assert_eq!(
+            grid.selection_text(at(0, 0), at(3, 4), SelectionOptions::default()),
+            "hello world!\nnext"
+        );
+        // 起止坐标可以颠倒
+        assert_eq!(
+            grid.selection_text(at(2, 1), at(0, 3), SelectionOptions::default()),
+            "lo world!"
+        );
+        let untrimmed = SelectionOptions {
+            trim_trailing_whitespace: false,
+            ..SelectionOptions::default()
+        };
+        assert_eq!(grid.selection_text(at(3, 0), at(3, 9), untrimmed), "next ");
+    }
+
+    #[test]
+    fn rectangular_selection_takes_column_block() {
+        let grid = TextGrid::from_output("abcdef\r\nxy\r\n123456\rZ", 10);
+        let block = SelectionOptions {
+            rectangular: true,
+            ..SelectionOptions::default()
+        };
+        assert_eq!(
+            grid.selection_text(at(2, 2), at(0, 0), block),
+            "abc\nxy\nZ23"
+        );
+        assert_eq!(grid.selection_text(at(0, 3), at(2, 5), block), "def\n\n456");
+    }
+}
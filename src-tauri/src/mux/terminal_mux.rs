@@ -2,22 +2,28 @@
 //!
 //! 提供统一的终端会话管理、事件通知和PTY I/O处理
 
+use bytes::Bytes;
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
 use std::time::Duration;
-use tracing::{error, instrument, warn};
+use tracing::{debug, error, instrument, warn};
 
 use crate::mux::{
     error::{TerminalMuxError, TerminalMuxResult},
-    IoHandler, LocalPane, MuxNotification, Pane, PaneId, PtySize, TerminalConfig,
+    CellCoord, IoHandler, LocalPane, MuxNotification, OutputDecoder, Pane, PaneId, PtySize,
+    SelectionOptions, TerminalConfig, TextGrid,
 };
 use crate::shell::ShellIntegrationManager;
+use encoding_rs::Encoding;
 
 pub type SubscriberCallback = Box<dyn Fn(&MuxNotification) -> bool + Send + Sync>;
 
+/// resize 防抖窗口：窗口内连续的请求只有最后一个会作用到 PTY
+const RESIZE_DEBOUNCE: Duration = Duration::from_millis(30);
+
 #[derive(Debug, Clone)]
 pub struct TerminalMuxStatus {
     pub pane_count: usize,
@@ -56,6 +62,10 @@ pub struct TerminalMux {
 
     /// 是否正在关闭（用于通知处理线程优雅退出）
     shutting_down: std::sync::atomic::AtomicBool,
+
+    /// 每个面板最近一次 resize 请求的序号，用于防抖
+    pending_resizes: Mutex<HashMap<PaneId, u64>>,
+    next_resize_generation: AtomicU64,
 }
 
 impl TerminalMux {
@@ -83,6 +93,8 @@ impl TerminalMux {
             io_handler,
             shell_integration,
             shutting_down: std::sync::atomic::AtomicBool::new(false),
+            pending_resizes: Mutex::new(HashMap::new()),
+            next_resize_generation: AtomicU64::new(1),
         }
     }
 
@@ -161,7 +173,8 @@ impl TerminalMux {
             .set_pane_shell_type(pane_id, shell_type.clone());
 
         // 启动I/O处理线程
-        self.io_handler.spawn_io_threads(pane.clone())?;
+        let decoder = OutputDecoder::from_label(config.output_encoding.as_deref());
+        self.io_handler.spawn_io_threads(pane.clone(), decoder)?;
 
         // 发送面板添加通知
         self.notify(MuxNotification::PaneAdded(pane_id));
@@ -239,6 +252,33 @@ impl TerminalMux {
         Ok(())
     }
 
+    /// 把控制序列作为面板输出发送给前端渲染（不经过 PTY，shell 不会收到）
+    pub fn inject_pane_output(&self, pane_id: PaneId, data: Bytes) -> TerminalMuxResult<()> {
+        if !self.pane_exists(pane_id) {
+            return Err(TerminalMuxError::PaneNotFound { pane_id });
+        }
+        self.notify(MuxNotification::PaneOutput { pane_id, data });
+        Ok(())
+    }
+
+    /// 切换面板的 PTY 输出编码（显式指定后不再自动检测）
+    pub fn set_pane_output_encoding(
+        &self,
+        pane_id: PaneId,
+        encoding: &'static Encoding,
+    ) -> TerminalMuxResult<()> {
+        if self.io_handler.set_output_encoding(pane_id, encoding) {
+            Ok(())
+        } else {
+            Err(TerminalMuxError::PaneNotFound { pane_id })
+        }
+    }
+
+    /// 面板当前使用的输出编码（可能已被自动检测切换）
+    pub fn get_pane_output_encoding(&self, pane_id: PaneId) -> Option<&'static Encoding> {
+        self.io_handler.output_encoding(pane_id)
+    }
+
     /// 调整面板大小
     ///
     /// - 使用结构化日志格式
@@ -249,6 +289,11 @@ impl TerminalMux {
             .get_pane(pane_id)
             .ok_or_else(|| TerminalMuxError::PaneNotFound { pane_id })?;
 
+        if !size.is_valid() {
+            debug!("忽略无效的终端尺寸 {:?}", size);
+            return Ok(());
+        }
+
         pane.resize(size)?;
 
         // 发送大小调整通知
@@ -256,6 +301,48 @@ impl TerminalMux {
         Ok(())
     }
 
+    /// 防抖地调整面板大小
+    ///
+    /// 等待一个防抖窗口，期间有更新的请求则放弃本次，由最后一个请求负责应用，
+    /// 因此最终尺寸总会生效。返回 true 表示本次请求实际调整了 PTY。
+    pub async fn resize_pane_debounced(
+        &self,
+        pane_id: PaneId,
+        size: PtySize,
+    ) -> TerminalMuxResult<bool> {
+        if !size.is_valid() {
+            debug!("忽略无效的终端尺寸 {:?}", size);
+            return Ok(false);
+        }
+
+        let generation = self.next_resize_generation.fetch_add(1, Ordering::Relaxed);
+        self.pending_resizes
+            .lock()
+            .map_err(|err| TerminalMuxError::from_write_poison("pending_resizes", err))?
+            .insert(pane_id, generation);
+
+        tokio::time::sleep(RESIZE_DEBOUNCE).await;
+
+        let is_latest = {
+            let mut pending = self
+                .pending_resizes
+                .lock()
+                .map_err(|err| TerminalMuxError::from_write_poison("pending_resizes", err))?;
+            if pending.get(&pane_id) == Some(&generation) {
+                pending.remove(&pane_id);
+                true
+            } else {
+                false
+            }
+        };
+        if !is_latest {
+            return Ok(false);
+        }
+
+        self.resize_pane(pane_id, size)?;
+        Ok(true)
+    }
+
     /// 订阅事件通知
     pub fn subscribe<F>(&self, subscriber: F) -> usize
     where
@@ -451,6 +538,21 @@ impl TerminalMux {
             .get_current_working_directory(pane_id)
     }
 
+    /// 设置面板标题，sticky 为 false 时 shell 之后上报的标题会覆盖它
+    pub fn set_pane_title(
+        &self,
+        pane_id: PaneId,
+        title: String,
+        sticky: bool,
+    ) -> TerminalMuxResult<()> {
+        if self.get_pane(pane_id).is_none() {
+            return Err(TerminalMuxError::PaneNotFound { pane_id });
+        }
+        self.shell_integration
+            .set_window_title(pane_id, title, sticky);
+        Ok(())
+    }
+
     /// 更新面板的当前工作目录
     pub fn shell_update_pane_cwd(&self, pane_id: PaneId, cwd: String) {
         self.shell_integration
@@ -466,6 +568,35 @@ impl TerminalMux {
             .set_pane_shell_type(pane_id, shell_type);
     }
 
+    /// 为系统中已安装的所有受支持 shell 写入 rc 集成块
+    pub fn install_shell_integration_all(
+        &self,
+        installed: &[crate::shell::ShellType],
+    ) -> Vec<crate::shell::IntegrationInstallReport> {
+        self.shell_integration.install_integration_all(installed)
+    }
+
+    /// 预览 rc 集成块的改动，不写入文件
+    pub fn preview_shell_integration(
+        &self,
+        shell_type: &crate::shell::ShellType,
+        action: crate::shell::IntegrationAction,
+    ) -> TerminalMuxResult<crate::shell::IntegrationPreview> {
+        self.shell_integration
+            .preview_integration(shell_type, action)
+            .map_err(|err| TerminalMuxError::Internal(format!("Shell integration error: {}", err)))
+    }
+
+    /// 将要写入 rc 文件的集成块及目标路径，不写入文件
+    pub fn preview_shell_integration_script(
+        &self,
+        shell_type: &crate::shell::ShellType,
+    ) -> TerminalMuxResult<crate::shell::IntegrationScriptPreview> {
+        self.shell_integration
+            .preview_integration_script(shell_type)
+            .map_err(|err| TerminalMuxError::Internal(format!("Shell integration error: {}", err)))
+    }
+
     /// 生成Shell集成脚本
     pub fn generate_shell_integration_script(
         &self,
@@ -508,6 +639,24 @@ impl TerminalMux {
         self.shell_integration.get_command_history(pane_id)
     }
 
+    /// 按面板当前列宽从输出缓冲（含滚动历史）中取出选区文本
+    pub fn get_selection_text(
+        &self,
+        pane_id: PaneId,
+        start: CellCoord,
+        end: CellCoord,
+        options: SelectionOptions,
+    ) -> TerminalMuxResult<String> {
+        let pane = self
+            .get_pane(pane_id)
+            .ok_or(TerminalMuxError::PaneNotFound { pane_id })?;
+        let output = crate::completion::output_analyzer::OutputAnalyzer::global()
+            .get_pane_buffer(pane_id.as_u32())
+            .map_err(|e| TerminalMuxError::Internal(e.to_string()))?;
+        let grid = TextGrid::from_output(&output, pane.get_size().cols as usize);
+        Ok(grid.selection_text(start, end, options))
+    }
+
     /// 清理所有资源
     pub fn shutdown(&self) -> TerminalMuxResult<()> {
         let shutdown_start = std::time::Instant::now();
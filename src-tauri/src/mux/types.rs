@@ -57,6 +57,11 @@ impl PtySize {
         }
     }
 
+    /// 行列都为正才是有效尺寸，0 会让全屏程序计算出错误布局
+    pub fn is_valid(&self) -> bool {
+        self.rows > 0 && self.cols > 0
+    }
+
     pub fn with_pixels(rows: u16, cols: u16, pixel_width: u16, pixel_height: u16) -> Self {
         Self {
             rows,
@@ -98,6 +103,9 @@ impl PaneInfo {
 #[serde(rename_all = "camelCase")]
 pub struct TerminalConfig {
     pub shell_config: ShellConfig,
+    /// PTY 输出编码标签，None 时使用 UTF-8（并允许自动检测）
+    #[serde(default)]
+    pub output_encoding: Option<String>,
 }
 
 impl TerminalConfig {
@@ -113,6 +121,7 @@ impl Default for TerminalConfig {
     fn default() -> Self {
         Self {
             shell_config: ShellConfig::default(),
+            output_encoding: None,
         }
     }
 }
@@ -155,6 +164,17 @@ impl ShellConfig {
             env: None,
         }
     }
+
+    /// 合并额外环境变量，已显式设置的键保持不变
+    pub fn merge_env_defaults(&mut self, extra: HashMap<String, String>) {
+        if extra.is_empty() {
+            return;
+        }
+        let env = self.env.get_or_insert_with(HashMap::new);
+        for (key, value) in extra {
+            env.entry(key).or_insert(value);
+        }
+    }
 }
 
 // MuxNotification 已移至 crate::events::mux 模块
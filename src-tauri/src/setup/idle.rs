@@ -0,0 +1,218 @@
+//! 用户空闲检测
+//!
+//! 记录最近一次用户交互（向面板输入、发送消息等用户主动发起的命令）的时间。后台任务（消息向量化、补全模型维护等）
+//! 在做重活前先检查 `is_idle`，用户正在使用时推迟执行，避免与前台操作争抢资源。
+//! 空闲阈值可在偏好设置中配置，启动时加载到内存，检查时不访问数据库。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::storage::repositories::AppPreferences;
+use crate::storage::DatabaseManager;
+use crate::utils::TauriApiResult;
+use crate::{api_error, api_success};
+
+/// 偏好设置中的键，值为秒数
+pub const IDLE_THRESHOLD_KEY: &str = "app.idle_threshold_secs";
+/// 默认空闲阈值（秒）
+pub const DEFAULT_IDLE_THRESHOLD_SECS: u64 = 60;
+/// 阈值允许的范围（秒）
+pub const IDLE_THRESHOLD_RANGE: std::ops::RangeInclusive<u64> = 5..=3600;
+/// 等待空闲时的最长轮询间隔
+const MAX_IDLE_POLL: Duration = Duration::from_secs(5);
+
+/// 视为用户交互的前端命令；轮询、状态查询和后台触发的命令不计入
+const USER_INPUT_COMMANDS: &[&str] = &[
+    "terminal_write",
+    "terminal_create",
+    "terminal_create_with_shell",
+    "terminal_close",
+    "completion_get",
+    "agent_execute_task",
+    "agent_explain_terminal_error",
+    "agent_summarize_path",
+    "ai_ask_inline",
+    "shortcuts_execute_action",
+    "semantic_search",
+];
+
+/// 交互时间与空闲阈值；进程内使用全局实例，测试可创建独立实例
+struct IdleTracker {
+    epoch: Instant,
+    /// 最近一次交互相对 epoch 的毫秒数；0 表示启动后还没有交互
+    last_activity_ms: AtomicU64,
+    threshold_secs: AtomicU64,
+}
+
+impl IdleTracker {
+    fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            last_activity_ms: AtomicU64::new(0),
+            threshold_secs: AtomicU64::new(DEFAULT_IDLE_THRESHOLD_SECS),
+        }
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.epoch.elapsed().as_millis() as u64
+    }
+
+    fn record_activity(&self) {
+        self.last_activity_ms
+            .store(self.elapsed_ms().max(1), Ordering::Relaxed);
+    }
+
+    fn idle_for(&self) -> Duration {
+        let last = self.last_activity_ms.load(Ordering::Relaxed);
+        Duration::from_millis(self.elapsed_ms().saturating_sub(last))
+    }
+
+    fn threshold(&self) -> Duration {
+        Duration::from_secs(self.threshold_secs.load(Ordering::Relaxed))
+    }
+
+    fn set_threshold(&self, secs: u64) {
+        self.threshold_secs.store(
+            secs.clamp(*IDLE_THRESHOLD_RANGE.start(), *IDLE_THRESHOLD_RANGE.end()),
+            Ordering::Relaxed,
+        );
+    }
+}
+
+static TRACKER: LazyLock<IdleTracker> = LazyLock::new(IdleTracker::new);
+
+/// 记录一次用户交互
+pub fn record_activity() {
+    TRACKER.record_activity();
+}
+
+/// 前端命令是否由用户主动发起
+pub fn is_user_input_command(command: &str) -> bool {
+    USER_INPUT_COMMANDS.contains(&command)
+}
+
+/// 距最近一次交互的时间；启动后还没有交互时按启动时长计算
+pub fn idle_for() -> Duration {
+    TRACKER.idle_for()
+}
+
+pub fn idle_threshold() -> Duration {
+    TRACKER.threshold()
+}
+
+pub fn set_idle_threshold(secs: u64) {
+    TRACKER.set_threshold(secs);
+}
+
+/// 用户已至少 threshold 没有交互
+pub fn is_idle_for(threshold: Duration) -> bool {
+    idle_for() >= threshold
+}
+
+/// 按配置的阈值判断是否空闲
+pub fn is_idle() -> bool {
+    is_idle_for(idle_threshold())
+}
+
+/// 推迟到用户空闲后返回；用于可以延后的后台重活
+pub async fn wait_until_idle() {
+    loop {
+        let remaining = idle_threshold().saturating_sub(idle_for());
+        if remaining.is_zero() {
+            return;
+        }
+        tokio::time::sleep(remaining.min(MAX_IDLE_POLL)).await;
+    }
+}
+
+/// 启动时加载用户配置的阈值
+pub async fn load_idle_threshold(database: &DatabaseManager) {
+    let secs = AppPreferences::new(database)
+        .get(IDLE_THRESHOLD_KEY)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|raw| raw.parse::<u64>().ok());
+    if let Some(secs) = secs {
+        set_idle_threshold(secs);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleStatus {
+    pub idle: bool,
+    pub idle_for_secs: u64,
+    pub threshold_secs: u64,
+}
+
+/// 查询用户是否空闲；threshold_secs 缺省时使用配置的阈值
+#[tauri::command]
+pub async fn app_is_idle(threshold_secs: Option<u64>) -> TauriApiResult<IdleStatus> {
+    let threshold = threshold_secs
+        .map(Duration::from_secs)
+        .unwrap_or_else(idle_threshold);
+    Ok(api_success!(IdleStatus {
+        idle: is_idle_for(threshold),
+        idle_for_secs: idle_for().as_secs(),
+        threshold_secs: threshold.as_secs(),
+    }))
+}
+
+/// 读取空闲阈值（秒）
+#[tauri::command]
+pub async fn app_get_idle_threshold() -> TauriApiResult<u64> {
+    Ok(api_success!(idle_threshold().as_secs()))
+}
+
+/// 设置空闲阈值（秒）
+#[tauri::command]
+pub async fn app_set_idle_threshold(
+    secs: u64,
+    database: State<'_, std::sync::Arc<DatabaseManager>>,
+) -> TauriApiResult<u64> {
+    if !IDLE_THRESHOLD_RANGE.contains(&secs) {
+        return Ok(api_error!("app.invalid_idle_threshold"));
+    }
+    if let Err(e) = AppPreferences::new(&database)
+        .set(IDLE_THRESHOLD_KEY, Some(&secs.to_string()))
+        .await
+    {
+        tracing::error!("Failed to save idle threshold: {}", e);
+        return Ok(api_error!("app.save_idle_threshold_failed"));
+    }
+    set_idle_threshold(secs);
+    Ok(api_success!(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activity_resets_idle_time() {
+        let tracker = IdleTracker::new();
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(tracker.idle_for() >= Duration::from_millis(20));
+
+        tracker.record_activity();
+        assert!(tracker.idle_for() < Duration::from_millis(20));
+
+        tracker.set_threshold(0);
+        assert_eq!(tracker.threshold().as_secs(), *IDLE_THRESHOLD_RANGE.start());
+        tracker.set_threshold(u64::MAX);
+        assert_eq!(tracker.threshold().as_secs(), *IDLE_THRESHOLD_RANGE.end());
+    }
+
+    #[test]
+    fn only_user_initiated_commands_count_as_activity() {
+        assert!(is_user_input_command("terminal_write"));
+        assert!(is_user_input_command("agent_execute_task"));
+        assert!(!is_user_input_command("app_is_idle"));
+        assert!(!is_user_input_command("vector_build_index_status"));
+    }
+}
@@ -0,0 +1,179 @@
+//! 日志系统初始化：控制台输出 + 按天滚动的文件日志
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+use crate::utils::TauriApiResult;
+use crate::{api_error, api_success};
+
+/// 设置为 `0` / `false` / `off` 时关闭文件日志
+const FILE_LOG_ENV: &str = "ORBITX_FILE_LOG";
+const LOG_FILE_PREFIX: &str = "orbitx";
+const LOG_FILE_SUFFIX: &str = "log";
+/// 最多保留的滚动文件数量
+const MAX_LOG_FILES: usize = 7;
+/// 日志目录总大小上限，启动时超出部分从最旧的文件开始清理
+const MAX_LOG_DIR_BYTES: u64 = 50 * 1024 * 1024;
+/// Agent 事件追踪的日志 target，文件日志中始终保留
+const TASK_EVENT_DIRECTIVE: &str = "task::event=debug";
+
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+/// 非阻塞写入器的后台线程守卫，drop 后会丢失缓冲中的日志，需存活到进程结束
+static LOG_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+pub fn init_logging() {
+    let console_layer = fmt::layer()
+        .with_target(true)
+        .with_thread_ids(false)
+        .with_file(false)
+        .with_line_number(false)
+        .with_level(true)
+        .with_filter(default_filter());
+
+    let file_layer = init_file_writer().map(|writer| {
+        fmt::layer()
+            .with_writer(writer)
+            .with_ansi(false)
+            .with_target(true)
+            .with_thread_ids(false)
+            .with_level(true)
+            .with_filter(file_filter())
+    });
+
+    let result = tracing_subscriber::registry()
+        .with(console_layer)
+        .with(file_layer)
+        .try_init();
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Log system initialization failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// 当前日志目录（文件日志未启用时仍返回默认位置）
+pub fn log_dir() -> Option<PathBuf> {
+    LOG_DIR.get().cloned().or_else(resolve_log_dir)
+}
+
+fn default_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+        #[cfg(debug_assertions)]
+        let default_level = "debug,ignore=warn,globset=warn";
+        #[cfg(not(debug_assertions))]
+        let default_level = "info";
+
+        EnvFilter::new(default_level)
+    })
+}
+
+fn file_filter() -> EnvFilter {
+    let filter = default_filter();
+    match TASK_EVENT_DIRECTIVE.parse() {
+        Ok(directive) => filter.add_directive(directive),
+        Err(_) => filter,
+    }
+}
+
+fn file_logging_enabled() -> bool {
+    match std::env::var(FILE_LOG_ENV) {
+        Ok(value) => !matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "0" | "false" | "off" | "no"
+        ),
+        Err(_) => true,
+    }
+}
+
+fn resolve_log_dir() -> Option<PathBuf> {
+    let app_dir = if let Ok(dir) = std::env::var("OrbitX_DATA_DIR") {
+        PathBuf::from(dir)
+    } else {
+        dirs::data_dir()?.join("OrbitX")
+    };
+    Some(app_dir.join("logs"))
+}
+
+fn init_file_writer() -> Option<NonBlocking> {
+    if !file_logging_enabled() {
+        return None;
+    }
+
+    let dir = resolve_log_dir()?;
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        eprintln!("Failed to create log directory {}: {}", dir.display(), e);
+        return None;
+    }
+
+    prune_log_dir(&dir, MAX_LOG_DIR_BYTES);
+
+    let appender = match RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+    {
+        Ok(appender) => appender,
+        Err(e) => {
+            eprintln!("Failed to create rolling log file: {}", e);
+            return None;
+        }
+    };
+
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let _ = LOG_GUARD.set(guard);
+    let _ = LOG_DIR.set(dir);
+    Some(writer)
+}
+
+/// 按修改时间从新到旧累计大小，超出上限的旧文件直接删除
+fn prune_log_dir(dir: &Path, max_bytes: u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut files: Vec<(PathBuf, u64, std::time::SystemTime)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            if !meta.is_file() {
+                return None;
+            }
+            let modified = meta.modified().unwrap_or(std::time::UNIX_EPOCH);
+            Some((entry.path(), meta.len(), modified))
+        })
+        .collect();
+
+    files.sort_by_key(|file| std::cmp::Reverse(file.2));
+
+    let mut total = 0u64;
+    for (path, size, _) in files {
+        total = total.saturating_add(size);
+        if total > max_bytes {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+/// 获取日志目录路径，供前端“打开日志文件夹”使用
+#[tauri::command]
+pub async fn logs_get_path() -> TauriApiResult<String> {
+    match log_dir() {
+        Some(dir) => Ok(api_success!(dir.to_string_lossy().to_string())),
+        None => Ok(api_error!("logs.path_unavailable")),
+    }
+}
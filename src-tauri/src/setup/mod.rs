@@ -1,8 +1,13 @@
 //! 应用程序初始化
 
 pub mod error;
+pub mod idle;
+pub mod logging;
+pub mod safe_mode;
 
 pub use error::{SetupError, SetupResult};
+pub use logging::init_logging;
+pub use safe_mode::{OptionalSubsystem, SafeModeStatus};
 
 use crate::ai::tool::shell::TerminalState;
 use crate::ai::AIManagerState;
@@ -10,43 +15,14 @@ use crate::completion::commands::CompletionState;
 use crate::config::{ConfigManagerState, ShortcutManagerState};
 use crate::llm::commands::LLMManagerState;
 use crate::terminal::{
-    commands::TerminalContextState, ActiveTerminalContextRegistry, TerminalChannelState,
-    TerminalContextService,
+    commands::TerminalContextState, output_coalescer::validate_output_flush,
+    ActiveTerminalContextRegistry, TerminalChannelState, TerminalContextService,
 };
 use crate::window::commands::WindowState;
 
 use std::sync::Arc;
 use tauri::{Emitter, Manager};
 use tracing::warn;
-use tracing_subscriber::{self, EnvFilter};
-
-pub fn init_logging() {
-    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-        #[cfg(debug_assertions)]
-        let default_level = "debug,ignore=warn,globset=warn";
-        #[cfg(not(debug_assertions))]
-        let default_level = "info";
-
-        EnvFilter::new(default_level)
-    });
-
-    let result = tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
-        .with_target(true)
-        .with_thread_ids(false)
-        .with_file(false)
-        .with_line_number(false)
-        .with_level(true)
-        .try_init();
-
-    match result {
-        Ok(_) => {}
-        Err(e) => {
-            eprintln!("Log system initialization failed: {}", e);
-            std::process::exit(1);
-        }
-    }
-}
 
 /// 初始化所有应用状态管理器
 pub fn initialize_app_states<R: tauri::Runtime>(app: &tauri::App<R>) -> SetupResult<()> {
@@ -91,6 +67,9 @@ pub fn initialize_app_states<R: tauri::Runtime>(app: &tauri::App<R>) -> SetupRes
         })?)
     };
     app.manage(database_manager.clone());
+    app.manage(Arc::new(crate::shell::ShellTypeOverrides::new(
+        database_manager.clone(),
+    )));
 
     // 初始化 MessagePackManager
     let messagepack_manager = {
@@ -116,6 +95,29 @@ pub fn initialize_app_states<R: tauri::Runtime>(app: &tauri::App<R>) -> SetupRes
     let cache = Arc::new(crate::storage::cache::UnifiedCache::new());
     app.manage(cache.clone());
 
+    // 恢复用户配置的空闲阈值
+    {
+        let database = database_manager.clone();
+        tauri::async_runtime::spawn(async move {
+            idle::load_idle_threshold(&database).await;
+        });
+    }
+
+    // 后台预热 tokenizer 与模型元数据，降低首个 Agent 任务的延迟
+    {
+        let database = database_manager.clone();
+        let cache = cache.clone();
+        tauri::async_runtime::spawn(async move {
+            use crate::ai::model_metadata::{prewarm_enabled, prewarm_model_metadata};
+
+            if !prewarm_enabled(&database).await {
+                return;
+            }
+            let _ = tokio::task::spawn_blocking(crate::agent::utils::tokenizer::prewarm).await;
+            prewarm_model_metadata(&database, &cache).await;
+        });
+    }
+
     // 在 ThemeManager 初始化前复制主题文件
     tauri::async_runtime::block_on(async {
         let _ = copy_themes_from_resources(app.handle()).await;
@@ -162,6 +164,7 @@ pub fn initialize_app_states<R: tauri::Runtime>(app: &tauri::App<R>) -> SetupRes
 
     let terminal_context_state = {
         let registry = Arc::new(ActiveTerminalContextRegistry::new());
+        crate::terminal::context_registry::install_global_registry(&registry);
         let cache = app
             .state::<Arc<crate::storage::cache::UnifiedCache>>()
             .inner()
@@ -229,30 +232,10 @@ pub fn initialize_app_states<R: tauri::Runtime>(app: &tauri::App<R>) -> SetupRes
         Arc::new(CheckpointService::new(storage, blob_store))
     };
 
-    // 初始化TaskExecutor状态（带有 Checkpoint 服务）
-    let task_executor_state = {
-        let database_manager = app
-            .state::<Arc<crate::storage::DatabaseManager>>()
-            .inner()
-            .clone();
-        let agent_persistence = Arc::new(crate::agent::persistence::AgentPersistence::new(
-            Arc::clone(&database_manager),
-        ));
-        let cache = app
-            .state::<Arc<crate::storage::UnifiedCache>>()
-            .inner()
-            .clone();
-
-        let executor = Arc::new(crate::agent::core::TaskExecutor::with_checkpoint_service(
-            Arc::clone(&database_manager),
-            Arc::clone(&cache),
-            Arc::clone(&agent_persistence),
-            Arc::clone(&checkpoint_service),
-        ));
-
-        crate::agent::core::commands::TaskExecutorState::new(executor)
-    };
-    app.manage(task_executor_state);
+    let mut safe_mode = safe_mode::SafeMode::from_env();
+    safe_mode.init_optional(OptionalSubsystem::AgentExecutor, || {
+        init_task_executor(app, &checkpoint_service)
+    });
 
     let window_state = WindowState::new().map_err(SetupError::WindowState)?;
     app.manage(window_state);
@@ -262,12 +245,25 @@ pub fn initialize_app_states<R: tauri::Runtime>(app: &tauri::App<R>) -> SetupRes
 
     // Manage Terminal Channel State for streaming bytes via Tauri Channel
     let terminal_channel_state = TerminalChannelState::new();
+    {
+        let config_state = app.state::<ConfigManagerState>();
+        if let Ok(config) = tauri::async_runtime::block_on(config_state.toml_manager.config_get()) {
+            if validate_output_flush(&config.terminal.output_flush).is_ok() {
+                terminal_channel_state
+                    .manager
+                    .set_flush_config(config.terminal.output_flush);
+            } else {
+                warn!("终端输出合并配置无效，使用默认值");
+            }
+        }
+    }
     app.manage(terminal_channel_state);
 
     // Initialize Dock Manager for platform-specific dock/jump list menus
     match crate::dock::DockManager::new(&app.handle()) {
         Ok(dock_manager) => {
             app.manage(dock_manager);
+            crate::dock::badge::install(app.handle());
         }
         Err(e) => {
             tracing::warn!("Failed to initialize dock manager: {}", e);
@@ -285,88 +281,143 @@ pub fn initialize_app_states<R: tauri::Runtime>(app: &tauri::App<R>) -> SetupRes
     let git_watcher = crate::git::GitWatcher::new();
     app.manage(git_watcher);
 
-    // 初始化向量数据库状态
-    {
-        use crate::llm::types::LLMProviderConfig;
-        use crate::storage::repositories::{AIModels, ModelType};
-        use crate::vector_db::{
-            commands::VectorDbState,
-            core::{RemoteEmbeddingConfig, VectorDbConfig},
-            search::SemanticSearchEngine,
-        };
-        use std::sync::Arc;
+    safe_mode.init_optional(OptionalSubsystem::VectorDb, || init_vector_db(app));
 
-        // 从数据库读取 embedding 模型配置
-        let database = app
+    let safe_mode = safe_mode.into_status();
+    if safe_mode.enabled {
+        let _ = app.emit(safe_mode::SAFE_MODE_EVENT, &safe_mode);
+    }
+    app.manage(safe_mode);
+
+    Ok(())
+}
+
+/// 初始化 TaskExecutor 状态（带有 Checkpoint 服务）
+fn init_task_executor<R: tauri::Runtime>(
+    app: &tauri::App<R>,
+    checkpoint_service: &Arc<crate::checkpoint::CheckpointService>,
+) {
+    let task_executor_state = {
+        let database_manager = app
             .state::<Arc<crate::storage::DatabaseManager>>()
             .inner()
             .clone();
-        let embedding_config = tauri::async_runtime::block_on(async {
-            let models = AIModels::new(&database)
-                .find_all()
-                .await
-                .unwrap_or_default();
-            models
-                .into_iter()
-                .find(|m| m.model_type == ModelType::Embedding)
-        });
+        let agent_persistence = Arc::new(crate::agent::persistence::AgentPersistence::new(
+            Arc::clone(&database_manager),
+        ));
+        let cache = app
+            .state::<Arc<crate::storage::UnifiedCache>>()
+            .inner()
+            .clone();
+
+        let executor = Arc::new(crate::agent::core::TaskExecutor::with_checkpoint_service(
+            Arc::clone(&database_manager),
+            Arc::clone(&cache),
+            Arc::clone(&agent_persistence),
+            Arc::clone(checkpoint_service),
+        ));
 
-        let config =
-            if let Some(model) = embedding_config {
-                // 从 options 中读取维度，默认 1024
-                let dimension = model
-                    .options
-                    .as_ref()
-                    .and_then(|opts| opts.get("dimension"))
-                    .and_then(|v| v.as_u64())
-                    .map(|v| v as usize)
-                    .unwrap_or(1024);
-
-                tracing::info!(
-                    "使用配置的 embedding 模型: {} @ {}, 维度: {}",
-                    model.model,
-                    model.api_url,
-                    dimension
-                );
-                VectorDbConfig {
-                    embedding: RemoteEmbeddingConfig {
-                        provider_config: LLMProviderConfig {
-                            provider_type: model.provider.as_str().to_string(),
-                            api_key: model.api_key,
-                            api_url: Some(model.api_url),
-                            options: model.options.as_ref().and_then(|v| v.as_object()).map(
-                                |obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
-                            ),
+        // 恢复用户配置的并发任务上限
+        let max_running = tauri::async_runtime::block_on(
+            crate::storage::repositories::AppPreferences::new(&database_manager)
+                .get(crate::agent::core::executor::MAX_RUNNING_TASKS_KEY),
+        )
+        .ok()
+        .flatten()
+        .and_then(|raw| raw.parse::<usize>().ok());
+        if let Some(max_running) = max_running {
+            executor.scheduler().set_max_running(max_running);
+        }
+
+        crate::agent::core::commands::TaskExecutorState::new(executor)
+    };
+    app.manage(task_executor_state);
+}
+
+/// 初始化向量数据库状态；配置无效或 embedder 创建失败时只记录警告。
+/// 上次使用的 embedding 模型已被删除时以其模型名与维度启动并暂停服务，等待用户重新选择
+fn init_vector_db<R: tauri::Runtime>(app: &tauri::App<R>) {
+    use crate::storage::repositories::{AIModels, ModelType};
+    use crate::vector_db::{
+        commands::{
+            config_from_model, emit_service_status, load_embedding_model_record,
+            save_embedding_model_record, VectorDbState,
+        },
+        core::{RemoteEmbeddingConfig, VectorDbConfig},
+        search::SemanticSearchEngine,
+    };
+    use std::sync::Arc;
+
+    // 从数据库读取 embedding 模型配置
+    let database = app
+        .state::<Arc<crate::storage::DatabaseManager>>()
+        .inner()
+        .clone();
+    let (config, removed_model) = tauri::async_runtime::block_on(async {
+        let models = AIModels::new(&database)
+            .find_all()
+            .await
+            .unwrap_or_default();
+        let mut embedding_models = models
+            .into_iter()
+            .filter(|m| m.model_type == ModelType::Embedding);
+
+        let (model, accepted_index_models) = match load_embedding_model_record(&database).await {
+            Some(record) => match embedding_models.find(|m| m.model == record.model) {
+                Some(model) => (Some(model), record.accepted_index_models),
+                None => {
+                    tracing::warn!("embedding 模型 {} 已被删除，等待重新选择", record.model);
+                    let config = VectorDbConfig {
+                        embedding: RemoteEmbeddingConfig {
+                            model_name: record.model.clone(),
+                            dimension: record.dimension,
+                            accepted_index_models: record.accepted_index_models,
+                            ..RemoteEmbeddingConfig::default()
                         },
-                        model_name: model.model,
-                        dimension,
-                        chunk_size: 512,
-                        chunk_overlap: 100,
-                    },
-                    ..VectorDbConfig::default()
+                        ..VectorDbConfig::default()
+                    };
+                    return (config, Some(record.model));
                 }
-            } else {
-                tracing::warn!("未找到 embedding 模型配置，使用默认值");
-                VectorDbConfig::default()
-            };
+            },
+            None => (embedding_models.next(), Vec::new()),
+        };
 
+        let Some(model) = model else {
+            tracing::warn!("未找到 embedding 模型配置，使用默认值");
+            return (VectorDbConfig::default(), None);
+        };
+        let mut config = config_from_model(model);
+        config.embedding.accepted_index_models = accepted_index_models;
+        tracing::info!(
+            "使用配置的 embedding 模型: {} @ {:?}, 维度: {}",
+            config.embedding.model_name,
+            config.embedding.provider_config.api_url,
+            config.embedding.dimension
+        );
+        save_embedding_model_record(&database, &config).await;
+        (config, None)
+    });
+
+    if removed_model.is_none() {
         if let Err(e) = config.validate() {
             warn!("Vector DB config validate failed: {}", e);
         }
+    }
 
-        if let Ok(state) = (|| -> Result<VectorDbState, crate::vector_db::core::VectorDbError> {
-            let embedder = crate::vector_db::embedding::create_embedder(&config.embedding)?;
-            let search_engine = Arc::new(SemanticSearchEngine::new(embedder, config));
-            crate::vector_db::commands::set_global_state(search_engine.clone());
-            Ok(VectorDbState::new(search_engine))
-        })() {
-            app.manage(state);
-        } else {
-            warn!("Failed to initialize vector DB");
+    if let Ok(state) = (|| -> Result<VectorDbState, crate::vector_db::core::VectorDbError> {
+        let embedder = crate::vector_db::embedding::create_embedder(&config.embedding)?;
+        let search_engine = Arc::new(SemanticSearchEngine::new(embedder, config));
+        if let Some(model) = removed_model {
+            search_engine.mark_embedding_model_removed(model);
+            emit_service_status(app.handle(), &search_engine);
         }
+        crate::vector_db::commands::set_global_state(search_engine.clone());
+        Ok(VectorDbState::new(search_engine))
+    })() {
+        app.manage(state);
+    } else {
+        warn!("Failed to initialize vector DB");
     }
-
-    Ok(())
 }
 
 /// 设置应用程序事件和监听器
@@ -378,18 +429,23 @@ pub fn setup_app_events<R: tauri::Runtime>(app: &tauri::App<R>) {
 
     // 配置窗口关闭行为：macOS 上隐藏窗口，其他平台退出应用
     if let Some(window) = app.get_webview_window("main") {
+        // 回到窗口时按实际待审批数刷新 Dock 角标
+        window.on_window_event(|event| {
+            if let tauri::WindowEvent::Focused(true) = event {
+                crate::dock::badge::refresh();
+            }
+        });
+
         #[cfg(target_os = "macos")]
         {
             // macOS: 点击关闭按钮时隐藏窗口，应用保持在 Dock 栏运行
-            // 用户可以通过 Command+Q 或菜单退出来真正退出应用
+            // 用户可以通过 Command+Q 或菜单退出来真正退出应用；
+            // 开启 quit_on_window_close 后与其他平台一致，关闭即退出
             let window_clone = window.clone();
             let app_handle = app.handle().clone();
             window.on_window_event(move |event| {
                 use tauri::WindowEvent;
                 if let WindowEvent::CloseRequested { api, .. } = event {
-                    // 阻止默认的关闭行为
-                    api.prevent_close();
-
                     // 清空所有标签页
                     if let Some(dock_manager) = app_handle.try_state::<crate::dock::DockManager>() {
                         if let Err(e) = dock_manager.state().clear() {
@@ -397,6 +453,16 @@ pub fn setup_app_events<R: tauri::Runtime>(app: &tauri::App<R>) {
                         }
                     }
 
+                    if quit_on_window_close(&app_handle) {
+                        if let Err(e) = crate::mux::singleton::shutdown_mux() {
+                            warn!("Failed to shutdown TerminalMux: {}", e);
+                        }
+                        return;
+                    }
+
+                    // 阻止默认的关闭行为
+                    api.prevent_close();
+
                     // 通知前端清空所有标签页
                     if let Err(e) = window_clone.emit("clear-all-tabs", ()) {
                         warn!("Failed to emit clear-all-tabs event: {}", e);
@@ -425,6 +491,16 @@ pub fn setup_app_events<R: tauri::Runtime>(app: &tauri::App<R>) {
     }
 }
 
+/// 读取 quit_on_window_close 配置，配置不可用时保持默认的隐藏行为
+#[cfg(target_os = "macos")]
+fn quit_on_window_close<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) -> bool {
+    app_handle
+        .try_state::<crate::config::ConfigManagerState>()
+        .and_then(|state| tauri::async_runtime::block_on(state.toml_manager.config_get()).ok())
+        .map(|config| config.app.quit_on_window_close)
+        .unwrap_or(false)
+}
+
 /// 设置深度链接处理
 pub fn setup_deep_links<R: tauri::Runtime>(app: &tauri::App<R>) {
     #[cfg(desktop)]
@@ -477,8 +553,12 @@ pub fn setup_deep_links<R: tauri::Runtime>(app: &tauri::App<R>) {
 /// 处理启动时的命令行参数
 pub fn handle_startup_args<R: tauri::Runtime>(app: &tauri::App<R>) {
     let env = app.env();
-    if env.args_os.len() > 1 {
-        let file_path = &env.args_os[1];
+    let file_arg = env
+        .args_os
+        .iter()
+        .skip(1)
+        .find(|arg| arg.as_os_str() != safe_mode::SAFE_MODE_FLAG);
+    if let Some(file_path) = file_arg {
         if let Some(window) = app.get_webview_window("main") {
             let path_str = file_path.to_string_lossy().to_string();
             let _ = window.emit("startup-file", path_str);
@@ -493,16 +573,7 @@ pub fn ensure_main_window_visible<R: tauri::Runtime>(app: &tauri::App<R>) {
         tauri::async_runtime::spawn(async move {
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-            if let Ok(position) = window_clone.outer_position() {
-                let x = position.x;
-                let y = position.y;
-
-                if x < -500 || y < -500 || x > 5000 || y > 5000 {
-                    let _ = window_clone.set_position(tauri::Position::Logical(
-                        tauri::LogicalPosition { x: 100.0, y: 100.0 },
-                    ));
-                }
-            }
+            move_window_on_screen(&window_clone);
 
             let _ = window_clone.show();
             let _ = window_clone.set_focus();
@@ -510,6 +581,21 @@ pub fn ensure_main_window_visible<R: tauri::Runtime>(app: &tauri::App<R>) {
     }
 }
 
+/// 窗口跑到屏幕外很远处时移回左上角附近
+pub fn move_window_on_screen<R: tauri::Runtime>(window: &tauri::WebviewWindow<R>) {
+    if let Ok(position) = window.outer_position() {
+        let x = position.x;
+        let y = position.y;
+
+        if x < -500 || y < -500 || x > 5000 || y > 5000 {
+            let _ = window.set_position(tauri::Position::Logical(tauri::LogicalPosition {
+                x: 100.0,
+                y: 100.0,
+            }));
+        }
+    }
+}
+
 /// 设置统一的终端事件处理器
 fn setup_unified_terminal_events<R: tauri::Runtime>(app_handle: tauri::AppHandle<R>) {
     use crate::mux::singleton::get_mux;
@@ -0,0 +1,115 @@
+//! 安全模式启动
+//!
+//! 设置环境变量 `ORBITX_SAFE_MODE` 或以 `--safe-mode` 启动时，跳过可选子系统（向量数据库、
+//! agent 执行器）的初始化，只启动终端等核心功能，便于在配置损坏时进入应用修复。
+//! 被跳过的子系统记录在 `SafeModeStatus` 中，前端可查询或监听事件后提示用户。
+
+use serde::Serialize;
+
+use crate::api_success;
+use crate::utils::TauriApiResult;
+
+pub const SAFE_MODE_ENV: &str = "ORBITX_SAFE_MODE";
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+/// 启动完成后广播的安全模式状态事件
+pub const SAFE_MODE_EVENT: &str = "safe-mode-status";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionalSubsystem {
+    VectorDb,
+    AgentExecutor,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeModeStatus {
+    pub enabled: bool,
+    pub skipped: Vec<OptionalSubsystem>,
+}
+
+#[derive(Debug, Default)]
+pub struct SafeMode {
+    status: SafeModeStatus,
+}
+
+impl SafeMode {
+    pub fn from_env() -> Self {
+        let env = std::env::var(SAFE_MODE_ENV).ok();
+        Self::from_sources(env.as_deref(), std::env::args())
+    }
+
+    fn from_sources(env: Option<&str>, mut args: impl Iterator<Item = String>) -> Self {
+        let env_enabled = env.is_some_and(|v| {
+            let v = v.trim();
+            !v.is_empty() && v != "0" && !v.eq_ignore_ascii_case("false")
+        });
+        let enabled = env_enabled || args.any(|arg| arg == SAFE_MODE_FLAG);
+        Self {
+            status: SafeModeStatus {
+                enabled,
+                skipped: Vec::new(),
+            },
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.status.enabled
+    }
+
+    /// 安全模式下跳过 init 并记录；正常模式直接执行
+    pub fn init_optional(&mut self, subsystem: OptionalSubsystem, init: impl FnOnce()) {
+        if self.status.enabled {
+            tracing::warn!("安全模式：跳过 {:?} 初始化", subsystem);
+            self.status.skipped.push(subsystem);
+        } else {
+            init();
+        }
+    }
+
+    pub fn into_status(self) -> SafeModeStatus {
+        self.status
+    }
+}
+
+/// 查询本次启动是否处于安全模式及被跳过的子系统
+#[tauri::command]
+pub async fn app_get_safe_mode_status(
+    status: tauri::State<'_, SafeModeStatus>,
+) -> TauriApiResult<SafeModeStatus> {
+    Ok(api_success!(status.inner().clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn enabled_by_env_or_flag_and_records_skipped() {
+        assert!(!SafeMode::from_sources(None, args(&["orbitx"])).enabled());
+        assert!(!SafeMode::from_sources(Some("0"), args(&["orbitx"])).enabled());
+        assert!(!SafeMode::from_sources(Some("false"), args(&["orbitx"])).enabled());
+        assert!(SafeMode::from_sources(None, args(&["orbitx", "--safe-mode"])).enabled());
+
+        let mut safe = SafeMode::from_sources(Some("1"), args(&["orbitx"]));
+        let mut ran = false;
+        safe.init_optional(OptionalSubsystem::VectorDb, || ran = true);
+        assert!(!ran);
+        assert_eq!(
+            safe.into_status().skipped,
+            vec![OptionalSubsystem::VectorDb]
+        );
+
+        let mut normal = SafeMode::from_sources(None, args(&["orbitx"]));
+        normal.init_optional(OptionalSubsystem::AgentExecutor, || ran = true);
+        assert!(ran);
+        assert!(normal.into_status().skipped.is_empty());
+    }
+}
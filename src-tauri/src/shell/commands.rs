@@ -10,7 +10,10 @@ use tauri::State;
 use tokio::process::Command as AsyncCommand;
 use tracing::error;
 
-use super::{CommandInfo, PaneShellState, ShellType};
+use super::{
+    CommandInfo, IntegrationAction, IntegrationInstallReport, IntegrationPreview,
+    IntegrationScriptPreview, PaneShellState, ShellType, ShellTypeOverrides,
+};
 use crate::mux::{PaneId, TerminalMux};
 
 /// 使用shell-words解析命令行 - 零开销,不重复造轮子
@@ -70,6 +73,7 @@ pub struct FrontendPaneState {
     pub current_command: Option<FrontendCommandInfo>,
     pub command_history: Vec<FrontendCommandInfo>,
     pub window_title: Option<String>,
+    pub title_sticky: bool,
     pub last_activity: u64,
     pub node_version: Option<String>,
 }
@@ -104,6 +108,7 @@ impl From<&PaneShellState> for FrontendPaneState {
                 .map(|cmd| FrontendCommandInfo::from(&**cmd))
                 .collect(),
             window_title: state.window_title.clone(),
+            title_sticky: state.title_sticky,
             last_activity,
             node_version: state.node_version.clone(),
         }
@@ -183,11 +188,14 @@ pub async fn get_pane_shell_state(
     Ok(api_success!(shell_state))
 }
 
+/// 设置面板的 Shell 类型；传入 tab_id 时作为用户覆盖持久化，会话恢复后重新应用
 #[tauri::command]
 pub async fn set_pane_shell_type(
     pane_id: u32,
     shell_type: String,
+    tab_id: Option<String>,
     state: State<'_, Arc<TerminalMux>>,
+    overrides: State<'_, Arc<ShellTypeOverrides>>,
 ) -> TauriApiResult<EmptyData> {
     let mux = &*state;
     let pane_id = PaneId::from(pane_id);
@@ -197,10 +205,110 @@ pub async fn set_pane_shell_type(
     }
 
     let shell_type = ShellType::from_program(&shell_type);
+    if let Some(tab_id) = tab_id.as_deref() {
+        if let Err(e) = overrides.set(pane_id, tab_id, &shell_type).await {
+            error!("Failed to persist shell type override: {}", e);
+            return Ok(api_error!("shell.save_shell_override_failed"));
+        }
+    }
     mux.set_pane_shell_type(pane_id, shell_type);
     Ok(api_success!())
 }
 
+/// 会话恢复时把新面板绑定到 tab，并重新应用已存储的 Shell 类型覆盖
+#[tauri::command]
+pub async fn restore_pane_shell_type(
+    pane_id: u32,
+    tab_id: String,
+    state: State<'_, Arc<TerminalMux>>,
+    overrides: State<'_, Arc<ShellTypeOverrides>>,
+) -> TauriApiResult<Option<String>> {
+    let mux = &*state;
+    let pane_id = PaneId::from(pane_id);
+
+    if !mux.pane_exists(pane_id) {
+        return Ok(api_error!("shell.pane_not_exist"));
+    }
+
+    match overrides.bind(pane_id, &tab_id).await {
+        Ok(Some(shell_type)) => {
+            let name = shell_type.display_name().to_string();
+            mux.set_pane_shell_type(pane_id, shell_type);
+            Ok(api_success!(Some(name)))
+        }
+        Ok(None) => Ok(api_success!(None)),
+        Err(e) => {
+            error!("Failed to load shell type override: {}", e);
+            Ok(api_error!("shell.load_shell_override_failed"))
+        }
+    }
+}
+
+/// 为所有已安装且受支持的 shell 安装（或升级）rc 文件中的集成块
+#[tauri::command]
+pub async fn shell_setup_integration_all(
+    state: State<'_, Arc<TerminalMux>>,
+) -> TauriApiResult<Vec<IntegrationInstallReport>> {
+    let mux = Arc::clone(&state);
+    let result = tokio::task::spawn_blocking(move || {
+        let installed: Vec<ShellType> = crate::mux::ShellManager::detect_available_shells()
+            .iter()
+            .map(|shell| ShellType::from_program(&shell.path))
+            .collect();
+        mux.install_shell_integration_all(&installed)
+    })
+    .await;
+
+    match result {
+        Ok(reports) => Ok(api_success!(reports)),
+        Err(e) => {
+            error!("Failed to set up shell integration for all shells: {}", e);
+            Ok(api_error!("shell.setup_integration_failed"))
+        }
+    }
+}
+
+/// 预览安装/卸载/升级集成对 rc 文件的改动，不修改文件
+#[tauri::command]
+pub async fn shell_preview_integration(
+    shell_type: String,
+    action: IntegrationAction,
+    state: State<'_, Arc<TerminalMux>>,
+) -> TauriApiResult<IntegrationPreview> {
+    let shell_type = ShellType::from_program(&shell_type);
+    if !shell_type.supports_integration() {
+        return Ok(api_error!("shell.shell_not_supported"));
+    }
+
+    match state.preview_shell_integration(&shell_type, action) {
+        Ok(preview) => Ok(api_success!(preview)),
+        Err(e) => {
+            error!("Failed to preview shell integration: {}", e);
+            Ok(api_error!("shell.preview_integration_failed"))
+        }
+    }
+}
+
+/// 预览将要追加到 rc 文件的集成脚本及目标路径，不修改文件
+#[tauri::command]
+pub async fn generate_integration_script_preview(
+    shell: String,
+    state: State<'_, Arc<TerminalMux>>,
+) -> TauriApiResult<IntegrationScriptPreview> {
+    let shell_type = ShellType::from_program(&shell);
+    if !shell_type.supports_integration() {
+        return Ok(api_error!("shell.shell_not_supported"));
+    }
+
+    match state.preview_shell_integration_script(&shell_type) {
+        Ok(preview) => Ok(api_success!(preview)),
+        Err(e) => {
+            error!("Failed to preview shell integration script: {}", e);
+            Ok(api_error!("shell.generate_script_failed"))
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn generate_shell_integration_script(
     shell_type: String,
@@ -301,9 +409,21 @@ pub async fn get_pane_command_history(
     Ok(api_success!(history))
 }
 
+/// 检测 Shell 类型；tab 存在用户覆盖时优先使用覆盖
 #[tauri::command]
-pub async fn detect_shell_type(shell_program: String) -> TauriApiResult<String> {
-    let shell_type = ShellType::from_program(&shell_program);
+pub async fn detect_shell_type(
+    shell_program: String,
+    tab_id: Option<String>,
+    overrides: State<'_, Arc<ShellTypeOverrides>>,
+) -> TauriApiResult<String> {
+    let stored = match tab_id.as_deref() {
+        Some(tab_id) => overrides.get(tab_id).await.unwrap_or_else(|e| {
+            error!("Failed to load shell type override: {}", e);
+            None
+        }),
+        None => None,
+    };
+    let shell_type = stored.unwrap_or_else(|| ShellType::from_program(&shell_program));
     Ok(api_success!(shell_type.display_name().to_string()))
 }
 
@@ -14,4 +14,6 @@ pub enum ShellScriptError {
         #[source]
         source: std::io::Error,
     },
+    #[error("Failed to update cmd AutoRun: {0}")]
+    AutoRun(String),
 }
@@ -65,8 +65,12 @@ pub struct PaneShellState {
     pub command_history: VecDeque<Arc<CommandInfo>>,
     pub next_command_id: u64,
     pub window_title: Option<String>,
+    /// 标题由外部固定设置，shell 上报的标题不再覆盖
+    pub title_sticky: bool,
     pub last_activity: SystemTime,
     pub node_version: Option<String>,
+    /// shell 是否通过 DECSET 2004 声明支持 bracketed paste
+    pub bracketed_paste: bool,
 }
 
 impl PaneShellState {
@@ -79,8 +83,10 @@ impl PaneShellState {
             command_history: VecDeque::new(),
             next_command_id: 1,
             window_title: None,
+            title_sticky: false,
             last_activity: SystemTime::now(),
             node_version: None,
+            bracketed_paste: false,
         }
     }
 }
@@ -134,6 +140,13 @@ impl ShellIntegrationManager {
     }
 
     pub fn process_output(&self, pane_id: PaneId, data: &str) {
+        if let Some(enabled) = bracketed_paste_toggle(data) {
+            self.states
+                .entry(pane_id)
+                .or_insert_with(PaneShellState::new)
+                .bracketed_paste = enabled;
+        }
+
         for sequence in self.parser.parse(data) {
             match sequence {
                 OscSequence::CurrentWorkingDirectory { path } => {
@@ -195,6 +208,35 @@ impl ShellIntegrationManager {
         self.states.get(&pane_id).map(|state| state.clone())
     }
 
+    /// 以编程方式设置面板标题
+    ///
+    /// 非 sticky 的标题会在 shell 下一次上报标题时被替换；sticky 标题会一直保留，
+    /// 直到再次以非 sticky 方式设置。
+    pub fn set_window_title(&self, pane_id: PaneId, title: String, sticky: bool) {
+        let changed = {
+            let mut entry = self
+                .states
+                .entry(pane_id)
+                .or_insert_with(PaneShellState::new);
+            let state = entry.value_mut();
+            state.title_sticky = sticky;
+            if state.window_title.as_ref() == Some(&title) {
+                false
+            } else {
+                state.window_title = Some(title.clone());
+                state.last_activity = SystemTime::now();
+                true
+            }
+        };
+
+        if changed {
+            self.notify_context_service_cache_invalidation(pane_id);
+            let _ = self
+                .event_sender
+                .send((pane_id, ShellEvent::TitleChanged { new_title: title }));
+        }
+    }
+
     pub fn set_pane_shell_type(&self, pane_id: PaneId, shell_type: ShellType) {
         let changed = {
             let mut state = self
@@ -214,6 +256,29 @@ impl ShellIntegrationManager {
         }
     }
 
+    pub fn install_integration_all(
+        &self,
+        installed: &[ShellType],
+    ) -> Vec<super::script_generator::IntegrationInstallReport> {
+        self.script_generator.install_integration_all(installed)
+    }
+
+    pub fn preview_integration(
+        &self,
+        shell_type: &ShellType,
+        action: super::script_generator::IntegrationAction,
+    ) -> ShellScriptResult<super::script_generator::IntegrationPreview> {
+        self.script_generator
+            .preview_integration(shell_type, action)
+    }
+
+    pub fn preview_integration_script(
+        &self,
+        shell_type: &ShellType,
+    ) -> ShellScriptResult<super::script_generator::IntegrationScriptPreview> {
+        self.script_generator.preview_integration_script(shell_type)
+    }
+
     pub fn generate_shell_script(&self, shell_type: &ShellType) -> ShellScriptResult<String> {
         self.script_generator
             .generate_integration_script(shell_type)
@@ -273,6 +338,13 @@ impl ShellIntegrationManager {
             .unwrap_or(false)
     }
 
+    pub fn is_bracketed_paste_enabled(&self, pane_id: PaneId) -> bool {
+        self.states
+            .get(&pane_id)
+            .map(|state| state.bracketed_paste)
+            .unwrap_or(false)
+    }
+
     pub fn with_current_command<F, R>(&self, pane_id: PaneId, f: F) -> Option<R>
     where
         F: FnOnce(&CommandInfo) -> R,
@@ -364,7 +436,7 @@ impl ShellIntegrationManager {
                 .entry(pane_id)
                 .or_insert_with(PaneShellState::new);
             let state = entry.value_mut();
-            if state.window_title.as_ref() == Some(&title) {
+            if state.title_sticky || state.window_title.as_ref() == Some(&title) {
                 None
             } else {
                 state.window_title = Some(title.clone());
@@ -600,6 +672,22 @@ impl Default for ShellIntegrationManager {
     }
 }
 
+const BRACKETED_PASTE_ON: &str = "\u{1b}[?2004h";
+const BRACKETED_PASTE_OFF: &str = "\u{1b}[?2004l";
+
+/// 输出中最后一次 DECSET/DECRST 2004 决定 bracketed paste 状态；未出现返回 None
+fn bracketed_paste_toggle(data: &str) -> Option<bool> {
+    match (
+        data.rfind(BRACKETED_PASTE_ON),
+        data.rfind(BRACKETED_PASTE_OFF),
+    ) {
+        (Some(on), Some(off)) => Some(on > off),
+        (Some(_), None) => Some(true),
+        (None, Some(_)) => Some(false),
+        (None, None) => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -628,4 +716,43 @@ mod tests {
             Some("/tmp".to_string())
         );
     }
+
+    #[test]
+    fn tracks_bracketed_paste_mode() {
+        let manager = ShellIntegrationManager::new();
+        let pane_id = PaneId::new(3);
+        assert!(!manager.is_bracketed_paste_enabled(pane_id));
+
+        manager.process_output(pane_id, "prompt\u{1b}[?2004h$ ");
+        assert!(manager.is_bracketed_paste_enabled(pane_id));
+
+        manager.process_output(pane_id, "plain output");
+        assert!(manager.is_bracketed_paste_enabled(pane_id));
+
+        manager.process_output(pane_id, "\u{1b}[?2004h\u{1b}[?2004l");
+        assert!(!manager.is_bracketed_paste_enabled(pane_id));
+    }
+
+    #[test]
+    fn shell_title_reclaims_unless_sticky() {
+        let manager = ShellIntegrationManager::new();
+        let pane_id = PaneId::new(4);
+        let title = |m: &ShellIntegrationManager| {
+            m.get_pane_shell_state(pane_id)
+                .and_then(|s| s.window_title)
+                .unwrap()
+        };
+
+        manager.set_window_title(pane_id, "Running tests".to_string(), false);
+        manager.process_output(pane_id, "\u{1b}]2;zsh\u{7}");
+        assert_eq!(title(&manager), "zsh");
+
+        manager.set_window_title(pane_id, "Running tests".to_string(), true);
+        manager.process_output(pane_id, "\u{1b}]2;vim\u{7}");
+        assert_eq!(title(&manager), "Running tests");
+
+        manager.set_window_title(pane_id, "Running tests".to_string(), false);
+        manager.process_output(pane_id, "\u{1b}]2;vim\u{7}");
+        assert_eq!(title(&manager), "vim");
+    }
 }
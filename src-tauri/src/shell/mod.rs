@@ -6,6 +6,7 @@ pub mod commands;
 pub mod error;
 pub mod integration;
 pub mod osc_parser;
+pub mod overrides;
 pub mod script_generator;
 
 #[cfg(test)]
@@ -15,6 +16,7 @@ pub use commands::*;
 pub use error::*;
 pub use integration::*;
 pub use osc_parser::*;
+pub use overrides::ShellTypeOverrides;
 pub use script_generator::*;
 
 // 从统一events模块导出Shell事件
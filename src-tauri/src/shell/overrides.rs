@@ -0,0 +1,125 @@
+//! 用户显式指定的 Shell 类型覆盖
+//!
+//! 面板 ID 在重启后会变化，因此覆盖按 tab 身份持久化到偏好设置中，
+//! 运行期再维护面板到 tab 的绑定。会话恢复时重新应用，面板关闭时清除。
+
+use dashmap::DashMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::ShellType;
+use crate::mux::PaneId;
+use crate::storage::error::RepositoryResult;
+use crate::storage::repositories::AppPreferences;
+use crate::storage::DatabaseManager;
+
+/// 偏好设置中的键，值为 `{ tab_id: shell 程序名 }` 的 JSON
+pub const SHELL_TYPE_OVERRIDES_KEY: &str = "shell.type_overrides";
+
+pub struct ShellTypeOverrides {
+    database: Arc<DatabaseManager>,
+    pane_tabs: DashMap<PaneId, String>,
+    /// 串行化读-改-写，避免并发覆盖
+    write_lock: Mutex<()>,
+}
+
+impl ShellTypeOverrides {
+    pub fn new(database: Arc<DatabaseManager>) -> Self {
+        Self {
+            database,
+            pane_tabs: DashMap::new(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    async fn load(&self) -> RepositoryResult<HashMap<String, String>> {
+        let raw = AppPreferences::new(&self.database)
+            .get(SHELL_TYPE_OVERRIDES_KEY)
+            .await?;
+        Ok(raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default())
+    }
+
+    async fn save(&self, overrides: &HashMap<String, String>) -> RepositoryResult<()> {
+        let raw = if overrides.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(overrides).unwrap_or_default())
+        };
+        AppPreferences::new(&self.database)
+            .set(SHELL_TYPE_OVERRIDES_KEY, raw.as_deref())
+            .await
+    }
+
+    /// 记录 tab 的覆盖并绑定面板
+    pub async fn set(
+        &self,
+        pane_id: PaneId,
+        tab_id: &str,
+        shell_type: &ShellType,
+    ) -> RepositoryResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let mut overrides = self.load().await?;
+        overrides.insert(tab_id.to_string(), program_name(shell_type));
+        self.save(&overrides).await?;
+        self.pane_tabs.insert(pane_id, tab_id.to_string());
+        Ok(())
+    }
+
+    pub async fn get(&self, tab_id: &str) -> RepositoryResult<Option<ShellType>> {
+        Ok(self
+            .load()
+            .await?
+            .get(tab_id)
+            .map(|name| ShellType::from_program(name)))
+    }
+
+    /// 恢复会话时调用：把新面板绑定到 tab，返回已存储的覆盖
+    pub async fn bind(&self, pane_id: PaneId, tab_id: &str) -> RepositoryResult<Option<ShellType>> {
+        let shell_type = self.get(tab_id).await?;
+        if shell_type.is_some() {
+            self.pane_tabs.insert(pane_id, tab_id.to_string());
+        }
+        Ok(shell_type)
+    }
+
+    /// 面板关闭时清除其 tab 的覆盖
+    pub async fn clear_pane(&self, pane_id: PaneId) -> RepositoryResult<()> {
+        let Some((_, tab_id)) = self.pane_tabs.remove(&pane_id) else {
+            return Ok(());
+        };
+        let _guard = self.write_lock.lock().await;
+        let mut overrides = self.load().await?;
+        if overrides.remove(&tab_id).is_some() {
+            self.save(&overrides).await?;
+        }
+        Ok(())
+    }
+}
+
+/// 可被 `ShellType::from_program` 还原的名称
+fn program_name(shell_type: &ShellType) -> String {
+    shell_type.display_name().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn program_name_round_trips() {
+        for shell_type in [
+            ShellType::Bash,
+            ShellType::Zsh,
+            ShellType::Fish,
+            ShellType::Other("nu".to_string()),
+        ] {
+            assert_eq!(
+                ShellType::from_program(&program_name(&shell_type)),
+                shell_type
+            );
+        }
+    }
+}
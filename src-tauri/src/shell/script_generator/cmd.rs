@@ -0,0 +1,212 @@
+//! cmd.exe 集成脚本生成器
+//!
+//! cmd 没有 rc 文件和钩子，只能通过 PROMPT 在每次显示提示符时输出转义序列：
+//! 提示符前 OSC 133;A 与 OSC 9;9 当前目录，提示符后 OSC 133;B。
+//! PROMPT 无法取得退出码，命令在下一次提示符出现时以未知退出码结束。
+//! 安装时脚本写入独立的 .cmd 文件，再通过注册表 AutoRun 在 cmd 启动时执行。
+
+use std::path::Path;
+
+use super::ShellIntegrationConfig;
+use crate::shell::error::ShellScriptResult;
+
+/// 用户未设置 PROMPT 时 cmd 的默认提示符
+pub const DEFAULT_PROMPT: &str = "$P$G";
+
+/// 已包装过 PROMPT 的标记，子进程继承后不再重复包装
+pub const PROMPT_WRAPPED_ENV: &str = "ORBITX_CMD_PROMPT_WRAPPED";
+
+/// 在用户提示符前后加入集成序列（`$E` 为 ESC，`$P` 为当前目录）
+pub fn wrap_prompt(config: &ShellIntegrationConfig, base: &str) -> String {
+    let mut prompt = String::new();
+    if config.enable_command_tracking {
+        prompt.push_str("$E]133;A$E\\");
+    }
+    if config.enable_cwd_sync {
+        prompt.push_str("$E]9;9;$P$E\\");
+    }
+    if config.enable_title_updates {
+        prompt.push_str("$E]2;$P$E\\");
+    }
+    prompt.push_str(base);
+    if config.enable_command_tracking {
+        prompt.push_str("$E]133;B$E\\");
+    }
+    prompt
+}
+
+/// 生成 cmd 集成脚本，每行以 @ 开头，不改变用户的 echo 状态
+pub fn generate_script(config: &ShellIntegrationConfig) -> String {
+    let mut script = String::new();
+
+    script.push_str("@REM OrbitX Shell Integration for cmd.exe\n");
+    script.push_str(&format!("@if defined {PROMPT_WRAPPED_ENV} goto :eof\n"));
+    script.push_str(&format!(
+        "@if not defined PROMPT set \"PROMPT={DEFAULT_PROMPT}\"\n"
+    ));
+    script.push_str(&format!(
+        "@set \"PROMPT={}\"\n",
+        wrap_prompt(config, "%PROMPT%")
+    ));
+    script.push_str(&format!("@set \"{PROMPT_WRAPPED_ENV}=1\"\n"));
+
+    if !config.custom_env_vars.is_empty() {
+        script.push_str("@REM Custom environment variables\n");
+        for (key, value) in &config.custom_env_vars {
+            script.push_str(&format!("@set \"{}={}\"\n", key, value));
+        }
+    }
+
+    script
+}
+
+/// AutoRun 中执行集成脚本的命令；文件被删除时不报错
+fn autorun_entry(script_path: &Path) -> String {
+    let path = script_path.display();
+    format!("(if exist \"{path}\" call \"{path}\")")
+}
+
+/// 把集成脚本追加到已有的 AutoRun 命令之后；已包含时返回 None
+pub fn merge_autorun(existing: Option<&str>, script_path: &Path) -> Option<String> {
+    let entry = autorun_entry(script_path);
+    match existing.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) if value.contains(&entry) => None,
+        Some(value) => Some(format!("{value} & {entry}")),
+        None => Some(entry),
+    }
+}
+
+/// 从 AutoRun 中去掉集成脚本，保留用户原有的命令；未包含时返回 None
+pub fn remove_from_autorun(existing: &str, script_path: &Path) -> Option<String> {
+    let entry = autorun_entry(script_path);
+    if !existing.contains(&entry) {
+        return None;
+    }
+    let cleaned = existing
+        .replace(&format!(" & {entry}"), "")
+        .replace(&entry, "");
+    Some(cleaned.trim().trim_start_matches('&').trim().to_string())
+}
+
+#[cfg(windows)]
+mod registry {
+    use std::process::Command;
+
+    use crate::shell::error::{ShellScriptError, ShellScriptResult};
+
+    const COMMAND_PROCESSOR_KEY: &str = r"HKCU\Software\Microsoft\Command Processor";
+
+    fn reg_error(operation: &str, err: impl std::fmt::Display) -> ShellScriptError {
+        ShellScriptError::AutoRun(format!("{operation}: {err}"))
+    }
+
+    /// 当前 AutoRun 的值与类型（REG_SZ / REG_EXPAND_SZ）；未设置时返回 None
+    pub fn read_autorun() -> ShellScriptResult<Option<(String, String)>> {
+        let output = Command::new("reg")
+            .args(["query", COMMAND_PROCESSOR_KEY, "/v", "AutoRun"])
+            .output()
+            .map_err(|e| reg_error("query AutoRun", e))?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().find_map(|line| {
+            let rest = line.trim().strip_prefix("AutoRun")?.trim_start();
+            let (kind, value) = rest.split_once(char::is_whitespace)?;
+            Some((value.trim().to_string(), kind.to_string()))
+        }))
+    }
+
+    pub fn write_autorun(value: &str, kind: &str) -> ShellScriptResult<()> {
+        let status = if value.is_empty() {
+            Command::new("reg")
+                .args(["delete", COMMAND_PROCESSOR_KEY, "/v", "AutoRun", "/f"])
+                .status()
+        } else {
+            Command::new("reg")
+                .args(["add", COMMAND_PROCESSOR_KEY, "/v", "AutoRun"])
+                .args(["/t", kind, "/d", value, "/f"])
+                .status()
+        }
+        .map_err(|e| reg_error("write AutoRun", e))?;
+        if !status.success() {
+            return Err(reg_error("write AutoRun", status));
+        }
+        Ok(())
+    }
+}
+
+/// 在当前用户的 AutoRun 中注册集成脚本；返回是否修改了注册表
+#[cfg(windows)]
+pub fn register_autorun(script_path: &Path) -> ShellScriptResult<bool> {
+    let existing = registry::read_autorun()?;
+    let kind = existing
+        .as_ref()
+        .map_or("REG_SZ", |(_, kind)| kind.as_str())
+        .to_string();
+    match merge_autorun(
+        existing.as_ref().map(|(value, _)| value.as_str()),
+        script_path,
+    ) {
+        Some(value) => registry::write_autorun(&value, &kind).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn register_autorun(_script_path: &Path) -> ShellScriptResult<bool> {
+    Ok(false)
+}
+
+/// 从 AutoRun 中移除集成脚本，用户原有的命令保持不变
+#[cfg(windows)]
+pub fn unregister_autorun(script_path: &Path) -> ShellScriptResult<()> {
+    let Some((existing, kind)) = registry::read_autorun()? else {
+        return Ok(());
+    };
+    match remove_from_autorun(&existing, script_path) {
+        Some(value) => registry::write_autorun(&value, &kind),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn unregister_autorun(_script_path: &Path) -> ShellScriptResult<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn prompt_wraps_user_prompt_with_markers() {
+        let prompt = wrap_prompt(&ShellIntegrationConfig::default(), "$P$G");
+        assert!(prompt.starts_with("$E]133;A$E\\"));
+        assert!(prompt.contains("$E]9;9;$P$E\\"));
+        assert!(prompt.ends_with("$P$G$E]133;B$E\\"));
+
+        let script = generate_script(&ShellIntegrationConfig::default());
+        assert!(script.lines().all(|line| line.starts_with('@')));
+        assert!(script.contains("%PROMPT%"));
+    }
+
+    #[test]
+    fn autorun_is_appended_and_removed_without_touching_user_commands() {
+        let script = PathBuf::from(r"C:\Users\me\.orbitx\cmd_integration.cmd");
+        let entry = autorun_entry(&script);
+
+        assert_eq!(merge_autorun(None, &script), Some(entry.clone()));
+        let merged = merge_autorun(Some("doskey /macrofile=aliases.txt"), &script).unwrap();
+        assert_eq!(merged, format!("doskey /macrofile=aliases.txt & {entry}"));
+        assert_eq!(merge_autorun(Some(&merged), &script), None);
+
+        assert_eq!(
+            remove_from_autorun(&merged, &script).as_deref(),
+            Some("doskey /macrofile=aliases.txt")
+        );
+        assert_eq!(remove_from_autorun(&entry, &script).as_deref(), Some(""));
+        assert_eq!(remove_from_autorun("doskey", &script), None);
+    }
+}
@@ -1,23 +1,93 @@
 use super::error::{ShellScriptError, ShellScriptResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs::{self, OpenOptions};
-use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// rc 文件中集成块的版本；脚本内容变化时递增，已安装的旧版本会被升级
+pub const INTEGRATION_SCRIPT_VERSION: u32 = 1;
+
+const START_MARKER: &str = "# OrbitX Integration Start";
+const END_MARKER: &str = "# OrbitX Integration End";
+const VERSION_PREFIX: &str = "# OrbitX Integration Version: ";
+const CMD_MARKER_PREFIX: &str = "@REM ";
+
+/// 单个 shell 的集成安装结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationInstallStatus {
+    Installed,
+    AlreadyCurrent,
+    Upgraded,
+    SkippedUnsupported,
+    /// 系统中未安装该 shell，不创建 rc 文件
+    SkippedNotInstalled,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationInstallReport {
+    pub shell: String,
+    pub status: IntegrationInstallStatus,
+    /// 写入或检查过的 rc 文件
+    pub config_path: Option<PathBuf>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationAction {
+    Install,
+    Uninstall,
+    Upgrade,
+}
+
+/// 集成改动的预览（不修改文件）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationPreview {
+    pub shell: String,
+    pub action: IntegrationAction,
+    pub config_path: PathBuf,
+    /// None 表示尚未安装；0 表示没有版本行的旧集成块
+    pub installed_version: Option<u32>,
+    pub target_version: u32,
+    pub changed: bool,
+    pub added_lines: Vec<String>,
+    pub removed_lines: Vec<String>,
+    /// unified diff 格式
+    pub diff: String,
+}
+
+/// 将要写入 rc 文件的集成块（不修改文件）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationScriptPreview {
+    pub shell: String,
+    /// 带起止标记和版本行的完整集成块
+    pub script: String,
+    pub target_path: String,
+    /// rc 文件中已有集成块（任意版本）
+    pub already_installed: bool,
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ShellType {
     Bash,
     Zsh,
     Fish,
+    Cmd,
     Other(String),
 }
 
 impl ShellType {
     pub fn from_program(program: &str) -> Self {
-        let program_name = std::path::Path::new(program)
-            .file_name()
-            .and_then(|n| n.to_str())
+        // 手动按两种分隔符切分，非 Windows 平台上 Path 不识别 `\`
+        let program_name = program
+            .rsplit(['/', '\\'])
+            .next()
             .unwrap_or(program)
             .to_lowercase();
 
@@ -25,6 +95,7 @@ impl ShellType {
             "bash" => Self::Bash,
             "zsh" => Self::Zsh,
             "fish" => Self::Fish,
+            "cmd" | "cmd.exe" => Self::Cmd,
             name => Self::Other(name.to_string()),
         }
     }
@@ -34,12 +105,21 @@ impl ShellType {
             Self::Bash => "Bash",
             Self::Zsh => "Zsh",
             Self::Fish => "Fish",
+            Self::Cmd => "Cmd",
             Self::Other(name) => name,
         }
     }
 
     pub fn supports_integration(&self) -> bool {
-        matches!(self, Self::Bash | Self::Zsh | Self::Fish)
+        matches!(self, Self::Bash | Self::Zsh | Self::Fish | Self::Cmd)
+    }
+
+    /// 集成块起止标记前的注释前缀；cmd 不认识 `#`，标记行需要写成注释
+    fn marker_prefix(&self) -> &'static str {
+        match self {
+            Self::Cmd => CMD_MARKER_PREFIX,
+            _ => "",
+        }
     }
 }
 
@@ -76,6 +156,7 @@ impl ShellScriptGenerator {
             ShellType::Bash => bash::generate_script(&self.config),
             ShellType::Zsh => zsh::generate_script(&self.config),
             ShellType::Fish => fish::generate_script(&self.config),
+            ShellType::Cmd => cmd::generate_script(&self.config),
             ShellType::Other(_) => String::new(),
         };
 
@@ -104,20 +185,66 @@ impl ShellScriptGenerator {
                 source: err,
             })?;
 
-        Ok(content.contains("# OrbitX Integration Start"))
+        Ok(content.contains(START_MARKER))
     }
 
-    pub fn install_integration(&self, shell_type: &ShellType) -> ShellScriptResult<()> {
-        if !shell_type.supports_integration() {
-            return Ok(());
+    /// 写入 rc 文件的集成块：带起止标记和版本行
+    fn integration_block(&self, shell_type: &ShellType) -> ShellScriptResult<String> {
+        let script = self.generate_integration_script(shell_type)?;
+        if script.is_empty() {
+            return Ok(script);
         }
+        let prefix = shell_type.marker_prefix();
+        let version_line = format!("{prefix}{VERSION_PREFIX}{INTEGRATION_SCRIPT_VERSION}");
+        let block = if script.contains(START_MARKER) {
+            script.replacen(START_MARKER, &format!("{START_MARKER}\n{version_line}"), 1)
+        } else {
+            format!(
+                "{prefix}{START_MARKER}\n{version_line}\n{}\n{prefix}{END_MARKER}\n",
+                script.trim_matches('\n')
+            )
+        };
+        Ok(block)
+    }
 
-        let script_content = self.generate_integration_script(shell_type)?;
-        if script_content.is_empty() {
-            return Ok(());
+    /// 幂等安装：未安装则追加，旧版本则替换，当前版本不做改动
+    pub fn install_integration(
+        &self,
+        shell_type: &ShellType,
+    ) -> ShellScriptResult<IntegrationInstallStatus> {
+        if !shell_type.supports_integration() {
+            return Ok(IntegrationInstallStatus::SkippedUnsupported);
         }
-
         let config_path = self.get_shell_config_path(shell_type)?;
+        let status = self.install_integration_at(shell_type, &config_path)?;
+        // cmd 没有 rc 文件，脚本通过 AutoRun 在启动时执行
+        if *shell_type == ShellType::Cmd
+            && cmd::register_autorun(&config_path)?
+            && status == IntegrationInstallStatus::AlreadyCurrent
+        {
+            return Ok(IntegrationInstallStatus::Installed);
+        }
+        Ok(status)
+    }
+
+    fn install_integration_at(
+        &self,
+        shell_type: &ShellType,
+        config_path: &Path,
+    ) -> ShellScriptResult<IntegrationInstallStatus> {
+        let existing = read_config(config_path)?;
+        let status = match installed_version(&existing) {
+            None => IntegrationInstallStatus::Installed,
+            Some(INTEGRATION_SCRIPT_VERSION) => {
+                return Ok(IntegrationInstallStatus::AlreadyCurrent)
+            }
+            Some(_) => IntegrationInstallStatus::Upgraded,
+        };
+        let Some(content) =
+            self.planned_content(shell_type, &existing, IntegrationAction::Install)?
+        else {
+            return Ok(IntegrationInstallStatus::SkippedUnsupported);
+        };
 
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent).map_err(|err| ShellScriptError::Io {
@@ -125,26 +252,187 @@ impl ShellScriptGenerator {
                 source: err,
             })?;
         }
+        fs::write(config_path, content).map_err(|err| ShellScriptError::Io {
+            operation: format!("write integration script {}", config_path.display()),
+            source: err,
+        })?;
+        Ok(status)
+    }
 
-        if self.is_integration_already_setup(shell_type)? {
-            return Ok(());
+    /// 计算执行 action 后 rc 文件的完整内容；不需要改动时返回 None。
+    /// 安装、卸载与预览共用这一份逻辑，保证预览与实际写入一致
+    fn planned_content(
+        &self,
+        shell_type: &ShellType,
+        existing: &str,
+        action: IntegrationAction,
+    ) -> ShellScriptResult<Option<String>> {
+        let version = installed_version(existing);
+        let content = match action {
+            IntegrationAction::Uninstall => {
+                version.map(|_| self.remove_integration_block(existing))
+            }
+            IntegrationAction::Install | IntegrationAction::Upgrade => {
+                if version == Some(INTEGRATION_SCRIPT_VERSION) {
+                    return Ok(None);
+                }
+                // 未安装时 upgrade 不做任何改动
+                if version.is_none() && action == IntegrationAction::Upgrade {
+                    return Ok(None);
+                }
+                let block = self.integration_block(shell_type)?;
+                if block.is_empty() {
+                    return Ok(None);
+                }
+                match version {
+                    None => Some(format!("{existing}\n{block}\n")),
+                    Some(_) => {
+                        let base = self.remove_integration_block(existing);
+                        Some(format!("{base}\n{block}\n"))
+                    }
+                }
+            }
+        };
+        Ok(content)
+    }
+
+    /// 预览 action 对 rc 文件的改动，不写入文件
+    pub fn preview_integration(
+        &self,
+        shell_type: &ShellType,
+        action: IntegrationAction,
+    ) -> ShellScriptResult<IntegrationPreview> {
+        if !shell_type.supports_integration() {
+            return Err(ShellScriptError::UnsupportedShell(
+                shell_type.display_name().to_string(),
+            ));
         }
+        let config_path = self.get_shell_config_path(shell_type)?;
+        self.preview_integration_at(shell_type, &config_path, action)
+    }
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&config_path)
-            .map_err(|err| ShellScriptError::Io {
-                operation: format!("open config file {}", config_path.display()),
-                source: err,
-            })?;
+    fn preview_integration_at(
+        &self,
+        shell_type: &ShellType,
+        config_path: &Path,
+        action: IntegrationAction,
+    ) -> ShellScriptResult<IntegrationPreview> {
+        let existing = read_config(config_path)?;
+        let planned = self.planned_content(shell_type, &existing, action)?;
+        let mut preview = IntegrationPreview {
+            shell: shell_type.display_name().to_string(),
+            action,
+            config_path: config_path.to_path_buf(),
+            installed_version: installed_version(&existing),
+            target_version: INTEGRATION_SCRIPT_VERSION,
+            changed: false,
+            added_lines: Vec::new(),
+            removed_lines: Vec::new(),
+            diff: String::new(),
+        };
+        let Some(planned) = planned else {
+            return Ok(preview);
+        };
 
-        writeln!(file, "\n{}", script_content).map_err(|err| ShellScriptError::Io {
-            operation: format!("write integration script {}", config_path.display()),
-            source: err,
-        })?;
+        let patch = diffy::create_patch(&existing, &planned);
+        for hunk in patch.hunks() {
+            for line in hunk.lines() {
+                match line {
+                    diffy::Line::Insert(text) => preview
+                        .added_lines
+                        .push(text.trim_end_matches('\n').to_string()),
+                    diffy::Line::Delete(text) => preview
+                        .removed_lines
+                        .push(text.trim_end_matches('\n').to_string()),
+                    diffy::Line::Context(_) => {}
+                }
+            }
+        }
+        preview.changed = planned != existing;
+        preview.diff = patch.to_string();
+        Ok(preview)
+    }
 
-        Ok(())
+    /// 生成将要追加的集成块及目标 rc 文件，不写入文件
+    pub fn preview_integration_script(
+        &self,
+        shell_type: &ShellType,
+    ) -> ShellScriptResult<IntegrationScriptPreview> {
+        if !shell_type.supports_integration() {
+            return Err(ShellScriptError::UnsupportedShell(
+                shell_type.display_name().to_string(),
+            ));
+        }
+        let config_path = self.get_shell_config_path(shell_type)?;
+        self.preview_integration_script_at(shell_type, &config_path)
+    }
+
+    fn preview_integration_script_at(
+        &self,
+        shell_type: &ShellType,
+        config_path: &Path,
+    ) -> ShellScriptResult<IntegrationScriptPreview> {
+        let existing = read_config(config_path)?;
+        Ok(IntegrationScriptPreview {
+            shell: shell_type.display_name().to_string(),
+            script: self.integration_block(shell_type)?,
+            target_path: config_path.to_string_lossy().into_owned(),
+            already_installed: installed_version(&existing).is_some(),
+        })
+    }
+
+    /// 为所有支持集成的 shell 安装集成块；`installed` 为系统中检测到的 shell，
+    /// 未安装的 shell 跳过且不创建 rc 文件
+    pub fn install_integration_all(
+        &self,
+        installed: &[ShellType],
+    ) -> Vec<IntegrationInstallReport> {
+        let mut reports: Vec<IntegrationInstallReport> = [
+            ShellType::Bash,
+            ShellType::Zsh,
+            ShellType::Fish,
+            ShellType::Cmd,
+        ]
+        .into_iter()
+        .map(|shell_type| {
+            if !installed.contains(&shell_type) {
+                return IntegrationInstallReport {
+                    shell: shell_type.display_name().to_string(),
+                    status: IntegrationInstallStatus::SkippedNotInstalled,
+                    config_path: None,
+                    error: None,
+                };
+            }
+            let config_path = self.get_shell_config_path(&shell_type).ok();
+            let (status, error) = match self.install_integration(&shell_type) {
+                Ok(status) => (status, None),
+                Err(err) => (IntegrationInstallStatus::Failed, Some(err.to_string())),
+            };
+            IntegrationInstallReport {
+                shell: shell_type.display_name().to_string(),
+                status,
+                config_path,
+                error,
+            }
+        })
+        .collect();
+
+        let mut unsupported: Vec<&ShellType> = installed
+            .iter()
+            .filter(|shell_type| !shell_type.supports_integration())
+            .collect();
+        unsupported.dedup();
+        reports.extend(
+            unsupported
+                .into_iter()
+                .map(|shell_type| IntegrationInstallReport {
+                    shell: shell_type.display_name().to_string(),
+                    status: IntegrationInstallStatus::SkippedUnsupported,
+                    config_path: None,
+                    error: None,
+                }),
+        );
+        reports
     }
 
     pub fn uninstall_integration(&self, shell_type: &ShellType) -> ShellScriptResult<()> {
@@ -153,17 +441,20 @@ impl ShellScriptGenerator {
         }
 
         let config_path = self.get_shell_config_path(shell_type)?;
+        if *shell_type == ShellType::Cmd {
+            cmd::unregister_autorun(&config_path)?;
+        }
 
         if !config_path.exists() {
             return Ok(());
         }
 
-        let content = fs::read_to_string(&config_path).map_err(|err| ShellScriptError::Io {
-            operation: format!("read shell config {}", config_path.display()),
-            source: err,
-        })?;
-
-        let cleaned_content = self.remove_integration_block(&content);
+        let content = read_config(&config_path)?;
+        let Some(cleaned_content) =
+            self.planned_content(shell_type, &content, IntegrationAction::Uninstall)?
+        else {
+            return Ok(());
+        };
 
         fs::write(&config_path, cleaned_content).map_err(|err| ShellScriptError::Io {
             operation: format!("write cleaned config {}", config_path.display()),
@@ -180,6 +471,7 @@ impl ShellScriptGenerator {
             ShellType::Bash => ".bashrc",
             ShellType::Zsh => ".zshrc",
             ShellType::Fish => ".config/fish/config.fish",
+            ShellType::Cmd => ".orbitx/cmd_integration.cmd",
             ShellType::Other(name) => {
                 return Err(ShellScriptError::UnsupportedShell(name.clone()));
             }
@@ -189,20 +481,20 @@ impl ShellScriptGenerator {
     }
 
     fn remove_integration_block(&self, content: &str) -> String {
-        let start_marker = "# OrbitX Integration Start";
-        let end_marker = "# OrbitX Integration End";
+        let start_marker = START_MARKER;
+        let end_marker = END_MARKER;
 
         let lines: Vec<&str> = content.lines().collect();
         let mut result_lines = Vec::new();
         let mut in_integration_block = false;
 
         for line in lines {
-            if line.trim() == start_marker {
+            if marker_text(line) == start_marker {
                 in_integration_block = true;
                 continue;
             }
 
-            if line.trim() == end_marker {
+            if marker_text(line) == end_marker {
                 in_integration_block = false;
                 continue;
             }
@@ -212,7 +504,11 @@ impl ShellScriptGenerator {
             }
         }
 
-        result_lines.join("\n")
+        let mut result = result_lines.join("\n");
+        if content.ends_with('\n') && !result.is_empty() {
+            result.push('\n');
+        }
+        result
     }
 
     pub fn get_integration_status(&self, shell_type: &ShellType) -> ShellScriptResult<bool> {
@@ -247,6 +543,36 @@ impl ShellScriptGenerator {
     }
 }
 
+/// rc 文件不存在时视为空
+fn read_config(config_path: &Path) -> ShellScriptResult<String> {
+    if !config_path.exists() {
+        return Ok(String::new());
+    }
+    fs::read_to_string(config_path).map_err(|err| ShellScriptError::Io {
+        operation: format!("read shell config {}", config_path.display()),
+        source: err,
+    })
+}
+
+/// 去掉注释前缀后的标记行内容
+fn marker_text(line: &str) -> &str {
+    let line = line.trim();
+    line.strip_prefix(CMD_MARKER_PREFIX).unwrap_or(line)
+}
+
+/// 已安装集成块的版本；没有版本行的旧块视为版本 0
+fn installed_version(content: &str) -> Option<u32> {
+    if !content.contains(START_MARKER) {
+        return None;
+    }
+    let version = content
+        .lines()
+        .find_map(|line| marker_text(line).strip_prefix(VERSION_PREFIX))
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(0);
+    Some(version)
+}
+
 impl Default for ShellScriptGenerator {
     fn default() -> Self {
         Self::new(ShellIntegrationConfig::default())
@@ -254,10 +580,12 @@ impl Default for ShellScriptGenerator {
 }
 
 pub mod bash;
+pub mod cmd;
 pub mod fish;
 pub mod zsh;
 
 pub use bash::generate_script as generate_bash_script;
+pub use cmd::generate_script as generate_cmd_script;
 pub use fish::generate_script as generate_fish_script;
 pub use zsh::generate_script as generate_zsh_script;
 
@@ -275,6 +603,10 @@ mod tests {
             ShellType::Zsh
         );
         assert_eq!(ShellType::from_program("fish"), ShellType::Fish);
+        assert_eq!(
+            ShellType::from_program("C:\\Windows\\System32\\cmd.exe"),
+            ShellType::Cmd
+        );
         assert_eq!(
             ShellType::from_program("/opt/homebrew/bin/fish"),
             ShellType::Fish
@@ -301,9 +633,188 @@ mod tests {
         assert!(ShellType::Bash.supports_integration());
         assert!(ShellType::Zsh.supports_integration());
         assert!(ShellType::Fish.supports_integration());
+        assert!(ShellType::Cmd.supports_integration());
         assert!(!ShellType::Other("sh".to_string()).supports_integration());
     }
 
+    #[test]
+    fn install_is_idempotent_and_upgrades_old_blocks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let rc = dir.path().join(".zshrc");
+        std::fs::write(&rc, "export PATH=/usr/bin\n").unwrap();
+        let generator = ShellScriptGenerator::default();
+
+        assert_eq!(
+            generator
+                .install_integration_at(&ShellType::Zsh, &rc)
+                .unwrap(),
+            IntegrationInstallStatus::Installed
+        );
+        assert_eq!(
+            generator
+                .install_integration_at(&ShellType::Zsh, &rc)
+                .unwrap(),
+            IntegrationInstallStatus::AlreadyCurrent
+        );
+        let content = std::fs::read_to_string(&rc).unwrap();
+        assert_eq!(content.matches(START_MARKER).count(), 1);
+        assert_eq!(
+            installed_version(&content),
+            Some(INTEGRATION_SCRIPT_VERSION)
+        );
+
+        std::fs::write(
+            &rc,
+            format!("export PATH=/usr/bin\n{START_MARKER}\nold\n{END_MARKER}\n"),
+        )
+        .unwrap();
+        assert_eq!(
+            generator
+                .install_integration_at(&ShellType::Zsh, &rc)
+                .unwrap(),
+            IntegrationInstallStatus::Upgraded
+        );
+        let content = std::fs::read_to_string(&rc).unwrap();
+        assert!(content.starts_with("export PATH=/usr/bin"));
+        assert!(!content.contains("\nold\n"));
+        assert_eq!(content.matches(START_MARKER).count(), 1);
+    }
+
+    #[test]
+    fn cmd_block_uses_comment_markers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let script = dir.path().join("cmd_integration.cmd");
+        let generator = ShellScriptGenerator::default();
+
+        assert_eq!(
+            generator
+                .install_integration_at(&ShellType::Cmd, &script)
+                .unwrap(),
+            IntegrationInstallStatus::Installed
+        );
+        let content = std::fs::read_to_string(&script).unwrap();
+        assert!(content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .all(|line| line.starts_with('@')));
+        assert_eq!(
+            installed_version(&content),
+            Some(INTEGRATION_SCRIPT_VERSION)
+        );
+        assert_eq!(
+            generator
+                .install_integration_at(&ShellType::Cmd, &script)
+                .unwrap(),
+            IntegrationInstallStatus::AlreadyCurrent
+        );
+        assert!(generator
+            .remove_integration_block(&content)
+            .trim()
+            .is_empty());
+    }
+
+    #[test]
+    fn preview_matches_actual_content() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let rc = dir.path().join(".bashrc");
+        let original = "alias ll='ls -l'\n";
+        std::fs::write(&rc, original).unwrap();
+        let generator = ShellScriptGenerator::default();
+
+        let preview = generator
+            .preview_integration_at(&ShellType::Bash, &rc, IntegrationAction::Install)
+            .unwrap();
+        assert!(preview.changed);
+        assert!(preview.removed_lines.is_empty());
+        // 预览不修改文件
+        assert_eq!(std::fs::read_to_string(&rc).unwrap(), original);
+
+        generator
+            .install_integration_at(&ShellType::Bash, &rc)
+            .unwrap();
+        let installed = std::fs::read_to_string(&rc).unwrap();
+        assert_eq!(
+            diffy::apply(original, &diffy::Patch::from_str(&preview.diff).unwrap()).unwrap(),
+            installed
+        );
+        assert_eq!(
+            installed.lines().count(),
+            original.lines().count() + preview.added_lines.len()
+        );
+
+        let upgrade = generator
+            .preview_integration_at(&ShellType::Bash, &rc, IntegrationAction::Upgrade)
+            .unwrap();
+        assert!(!upgrade.changed);
+
+        let legacy = format!("{original}{START_MARKER}\nold\n{END_MARKER}\n");
+        std::fs::write(&rc, &legacy).unwrap();
+        let upgrade = generator
+            .preview_integration_at(&ShellType::Bash, &rc, IntegrationAction::Upgrade)
+            .unwrap();
+        assert_eq!(upgrade.installed_version, Some(0));
+        assert!(upgrade.removed_lines.contains(&"old".to_string()));
+        assert!(upgrade
+            .added_lines
+            .iter()
+            .any(|l| l.starts_with(VERSION_PREFIX)));
+
+        let uninstall = generator
+            .preview_integration_at(&ShellType::Bash, &rc, IntegrationAction::Uninstall)
+            .unwrap();
+        assert!(uninstall.added_lines.is_empty());
+        assert_eq!(
+            uninstall.removed_lines,
+            vec![START_MARKER, "old", END_MARKER]
+        );
+    }
+
+    #[test]
+    fn script_preview_does_not_touch_the_rc_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let rc = dir.path().join(".bashrc");
+        let generator = ShellScriptGenerator::default();
+
+        let preview = generator
+            .preview_integration_script_at(&ShellType::Bash, &rc)
+            .unwrap();
+        assert!(!preview.already_installed);
+        assert!(preview.script.contains(START_MARKER));
+        assert_eq!(preview.target_path, rc.to_string_lossy());
+        assert!(!rc.exists());
+
+        generator
+            .install_integration_at(&ShellType::Bash, &rc)
+            .unwrap();
+        let preview = generator
+            .preview_integration_script_at(&ShellType::Bash, &rc)
+            .unwrap();
+        assert!(preview.already_installed);
+        assert!(std::fs::read_to_string(&rc)
+            .unwrap()
+            .contains(&preview.script));
+    }
+
+    #[test]
+    fn install_all_skips_missing_and_unsupported_shells() {
+        let generator = ShellScriptGenerator::default();
+        let reports = generator.install_integration_all(&[ShellType::Other("pwsh".into())]);
+
+        assert!(reports
+            .iter()
+            .filter(|r| r.shell != "pwsh")
+            .all(
+                |r| r.status == IntegrationInstallStatus::SkippedNotInstalled
+                    && r.config_path.is_none()
+            ));
+        assert!(
+            reports
+                .iter()
+                .any(|r| r.shell == "pwsh"
+                    && r.status == IntegrationInstallStatus::SkippedUnsupported)
+        );
+    }
+
     #[test]
     fn test_other_shell_serialization() {
         let value = ShellType::Other("sh".to_string());
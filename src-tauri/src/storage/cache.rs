@@ -66,6 +66,7 @@ pub enum CacheNamespace {
     Agent,      // Agent 临时数据
     Completion, // 补全缓存
     Terminal,   // 终端相关
+    Models,     // 模型元数据（上下文窗口、价格）
     Global,     // 全局命名空间（默认）
 }
 
@@ -78,6 +79,7 @@ impl CacheNamespace {
             Self::Agent => "agent:",
             Self::Completion => "completion:",
             Self::Terminal => "terminal:",
+            Self::Models => "models:",
             Self::Global => "",
         }
     }
@@ -1,10 +1,12 @@
 use std::collections::{HashMap, VecDeque};
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 use tauri::ipc::Channel;
 
+use super::output_coalescer::OutputCoalescer;
 use super::replay;
 use super::types::TerminalChannelMessage;
 use crate::completion::output_analyzer::OutputAnalyzer;
+use crate::config::types::OutputFlushConfig;
 
 const MAX_PENDING_CHUNKS: usize = 64;
 const MAX_PENDING_BYTES: usize = 64 * 1024;
@@ -39,6 +41,7 @@ impl PendingQueue {
 pub struct TerminalChannelManager {
     channels: RwLock<HashMap<u32, Channel<TerminalChannelMessage>>>,
     pending: RwLock<HashMap<u32, PendingQueue>>,
+    coalescer: OutputCoalescer,
 }
 
 impl TerminalChannelManager {
@@ -90,6 +93,7 @@ impl TerminalChannelManager {
     }
 
     pub fn remove(&self, pane_id: u32) {
+        self.coalescer.discard(pane_id);
         if let Ok(mut map) = self.channels.write() {
             map.remove(&pane_id);
         }
@@ -98,7 +102,46 @@ impl TerminalChannelManager {
         }
     }
 
+    /// 输出先进入合并缓冲，由阈值或后台刷新线程决定实际发送时机
     pub fn send_data(&self, pane_id: u32, data: &[u8]) {
+        self.coalescer
+            .push(pane_id, data, |batch| self.send_now(pane_id, batch));
+    }
+
+    /// 立即发送所有面板的缓冲输出
+    pub fn flush_all(&self) {
+        self.coalescer
+            .flush_all(|pane_id, batch| self.send_now(pane_id, batch));
+    }
+
+    pub fn flush_config(&self) -> OutputFlushConfig {
+        self.coalescer.config()
+    }
+
+    /// 更新合并策略，旧策略下缓冲的输出立即发出
+    pub fn set_flush_config(&self, config: OutputFlushConfig) {
+        self.coalescer.set_config(config);
+        self.flush_all();
+    }
+
+    pub(super) fn start_flusher(self: &Arc<Self>) {
+        let manager = Arc::downgrade(self);
+        self.coalescer
+            .spawn_flusher(move || match manager.upgrade() {
+                Some(manager) => {
+                    manager.flush_all();
+                    true
+                }
+                None => false,
+            });
+    }
+
+    fn flush_pane(&self, pane_id: u32) {
+        self.coalescer
+            .flush(pane_id, |batch| self.send_now(pane_id, batch));
+    }
+
+    fn send_now(&self, pane_id: u32, data: &[u8]) {
         let mut should_buffer = true;
         let mut should_remove = false;
 
@@ -133,6 +176,7 @@ impl TerminalChannelManager {
     }
 
     pub fn send_error(&self, pane_id: u32, error: String) {
+        self.flush_pane(pane_id);
         if let Ok(map) = self.channels.read() {
             if let Some(ch) = map.get(&pane_id) {
                 let _ = ch.send(TerminalChannelMessage::Error { pane_id, error });
@@ -141,6 +185,7 @@ impl TerminalChannelManager {
     }
 
     pub fn close(&self, pane_id: u32) {
+        self.flush_pane(pane_id);
         if let Ok(map) = self.channels.read() {
             if let Some(ch) = map.get(&pane_id) {
                 let _ = ch.send(TerminalChannelMessage::Close { pane_id });
@@ -8,8 +8,8 @@ pub struct TerminalChannelState {
 
 impl TerminalChannelState {
     pub fn new() -> Self {
-        Self {
-            manager: Arc::new(TerminalChannelManager::new()),
-        }
+        let manager = Arc::new(TerminalChannelManager::new());
+        manager.start_flusher();
+        Self { manager }
     }
 }
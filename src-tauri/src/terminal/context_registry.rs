@@ -9,10 +9,23 @@ use crate::mux::PaneId;
 use crate::terminal::error::{ContextRegistryError, ContextRegistryResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, OnceLock, RwLock};
 use tokio::sync::broadcast;
 use tracing::warn;
 
+/// 应用启动时安装的全局注册表，供拿不到 Tauri State 的模块（如 Agent 工具）查询活跃终端
+static GLOBAL_REGISTRY: OnceLock<ActiveTerminalContextRegistry> = OnceLock::new();
+
+/// 安装全局注册表，重复调用时保留第一次安装的实例
+pub fn install_global_registry(registry: &ActiveTerminalContextRegistry) {
+    let _ = GLOBAL_REGISTRY.set(registry.clone());
+}
+
+/// 获取全局注册表，未安装时为 None
+pub fn global_registry() -> Option<&'static ActiveTerminalContextRegistry> {
+    GLOBAL_REGISTRY.get()
+}
+
 /// 窗口ID类型（为未来多窗口支持预留）
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct WindowId(pub u32);
@@ -426,6 +426,7 @@ impl TerminalContextService {
             crate::shell::ShellType::Bash => ShellType::Bash,
             crate::shell::ShellType::Zsh => ShellType::Zsh,
             crate::shell::ShellType::Fish => ShellType::Fish,
+            crate::shell::ShellType::Cmd => ShellType::Other("cmd".to_string()),
             crate::shell::ShellType::Other(name) => ShellType::Other(name),
         }
     }
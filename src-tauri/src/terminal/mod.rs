@@ -9,6 +9,7 @@ pub mod error;
 pub mod event_handler;
 #[cfg(test)]
 pub mod integration_test;
+pub mod output_coalescer;
 pub mod replay;
 pub mod types;
 
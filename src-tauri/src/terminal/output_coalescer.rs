@@ -0,0 +1,205 @@
+//! 终端输出合并
+//!
+//! 高输出命令（`yes`、冗长的构建日志）会以大量小块写满 Channel，导致前端卡顿。
+//! PTY 输出先按面板缓冲，达到字节阈值立即发送，否则由后台线程在刷新间隔到期时发送；
+//! 输出停止超过 `IDLE_FLUSH` 时提前发送，保证交互回显与提示符不受延迟影响。
+//! 发送在持有缓冲锁时进行，同一面板的数据不会乱序。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender};
+
+use crate::config::types::OutputFlushConfig;
+
+/// 输出停顿超过该时长视为空闲，立即发送已缓冲的数据
+pub const IDLE_FLUSH: Duration = Duration::from_millis(2);
+
+pub const MAX_FLUSH_INTERVAL_MS: u64 = 100;
+pub const MIN_BATCH_BYTES: usize = 1024;
+pub const MAX_BATCH_BYTES: usize = 1024 * 1024;
+
+pub fn validate_output_flush(config: &OutputFlushConfig) -> Result<(), String> {
+    if config.interval_ms > MAX_FLUSH_INTERVAL_MS {
+        return Err(format!(
+            "刷新间隔必须在 0-{} 毫秒之间，当前值: {}",
+            MAX_FLUSH_INTERVAL_MS, config.interval_ms
+        ));
+    }
+    if !(MIN_BATCH_BYTES..=MAX_BATCH_BYTES).contains(&config.max_batch_bytes) {
+        return Err(format!(
+            "合并阈值必须在 {}-{} 字节之间，当前值: {}",
+            MIN_BATCH_BYTES, MAX_BATCH_BYTES, config.max_batch_bytes
+        ));
+    }
+    Ok(())
+}
+
+pub struct OutputCoalescer {
+    config: Arc<RwLock<OutputFlushConfig>>,
+    buffers: Mutex<HashMap<u32, Vec<u8>>>,
+    wake_tx: Sender<()>,
+    wake_rx: Receiver<()>,
+}
+
+impl Default for OutputCoalescer {
+    fn default() -> Self {
+        // 容量 1 即可：等待期间只需知道“又有新输出”
+        let (wake_tx, wake_rx) = bounded(1);
+        Self {
+            config: Arc::new(RwLock::new(OutputFlushConfig::default())),
+            buffers: Mutex::new(HashMap::new()),
+            wake_tx,
+            wake_rx,
+        }
+    }
+}
+
+impl OutputCoalescer {
+    pub fn config(&self) -> OutputFlushConfig {
+        self.config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: OutputFlushConfig) {
+        if let Ok(mut current) = self.config.write() {
+            *current = config;
+        }
+    }
+
+    /// 缓冲一段输出；未启用合并或达到阈值时通过 send 立即发送该面板的全部缓冲
+    pub fn push(&self, pane_id: u32, data: &[u8], send: impl FnOnce(&[u8])) {
+        let config = self.config();
+        let Ok(mut buffers) = self.buffers.lock() else {
+            send(data);
+            return;
+        };
+
+        if config.interval_ms == 0 {
+            // 关闭合并前残留的缓冲先发出，保持顺序
+            match buffers.remove(&pane_id) {
+                Some(mut pending) => {
+                    pending.extend_from_slice(data);
+                    send(&pending);
+                }
+                None => send(data),
+            }
+            return;
+        }
+
+        let buffer = buffers.entry(pane_id).or_default();
+        buffer.extend_from_slice(data);
+        if buffer.len() >= config.max_batch_bytes {
+            let batch = std::mem::take(buffer);
+            send(&batch);
+        } else {
+            let _ = self.wake_tx.try_send(());
+        }
+    }
+
+    /// 立即发送某个面板的缓冲（关闭或报错前调用）
+    pub fn flush(&self, pane_id: u32, send: impl FnOnce(&[u8])) {
+        if let Ok(mut buffers) = self.buffers.lock() {
+            if let Some(batch) = buffers.remove(&pane_id).filter(|b| !b.is_empty()) {
+                send(&batch);
+            }
+        }
+    }
+
+    pub fn flush_all(&self, mut send: impl FnMut(u32, &[u8])) {
+        if let Ok(mut buffers) = self.buffers.lock() {
+            for (pane_id, batch) in buffers.drain() {
+                if !batch.is_empty() {
+                    send(pane_id, &batch);
+                }
+            }
+        }
+    }
+
+    pub fn discard(&self, pane_id: u32) {
+        if let Ok(mut buffers) = self.buffers.lock() {
+            buffers.remove(&pane_id);
+        }
+    }
+
+    /// 启动后台刷新线程。flush 返回 false（目标已释放）或所有发送端关闭时线程退出
+    pub fn spawn_flusher(&self, flush: impl Fn() -> bool + Send + 'static) {
+        let wake_rx = self.wake_rx.clone();
+        let config = Arc::clone(&self.config);
+        let spawned = thread::Builder::new()
+            .name("terminal-output-flush".into())
+            .spawn(move || {
+                while wake_rx.recv().is_ok() {
+                    let interval = config
+                        .read()
+                        .map(|c| Duration::from_millis(c.interval_ms))
+                        .unwrap_or_default();
+                    let deadline = Instant::now() + interval;
+                    loop {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            break;
+                        }
+                        match wake_rx.recv_timeout(IDLE_FLUSH.min(deadline - now)) {
+                            Ok(()) => continue,
+                            Err(RecvTimeoutError::Timeout) => break,
+                            Err(RecvTimeoutError::Disconnected) => return,
+                        }
+                    }
+                    if !flush() {
+                        return;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::error!("启动终端输出刷新线程失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffers_until_threshold_and_flushes_in_order() {
+        let coalescer = OutputCoalescer::default();
+        coalescer.set_config(OutputFlushConfig {
+            interval_ms: 8,
+            max_batch_bytes: 8,
+        });
+
+        let mut sent: Vec<Vec<u8>> = Vec::new();
+        coalescer.push(1, b"abc", |d| sent.push(d.to_vec()));
+        coalescer.push(2, b"x", |d| sent.push(d.to_vec()));
+        assert!(sent.is_empty());
+
+        coalescer.push(1, b"defgh", |d| sent.push(d.to_vec()));
+        assert_eq!(sent, vec![b"abcdefgh".to_vec()]);
+
+        let mut flushed = Vec::new();
+        coalescer.flush_all(|pane, d| flushed.push((pane, d.to_vec())));
+        assert_eq!(flushed, vec![(2, b"x".to_vec())]);
+
+        // 关闭合并时残留缓冲与新数据一起发出
+        coalescer.push(3, b"old", |_| unreachable!());
+        coalescer.set_config(OutputFlushConfig {
+            interval_ms: 0,
+            max_batch_bytes: 8,
+        });
+        let mut direct = Vec::new();
+        coalescer.push(3, b"new", |d| direct.push(d.to_vec()));
+        assert_eq!(direct, vec![b"oldnew".to_vec()]);
+
+        assert!(validate_output_flush(&OutputFlushConfig::default()).is_ok());
+        assert!(validate_output_flush(&OutputFlushConfig {
+            interval_ms: 500,
+            max_batch_bytes: 8192,
+        })
+        .is_err());
+    }
+}
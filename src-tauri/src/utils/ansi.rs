@@ -0,0 +1,65 @@
+//! 终端转义序列处理
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// 读到 ESC 之后调用，跳过 CSI / OSC 序列的剩余部分；其它 ESC 序列只跳过紧随其后的一个字符
+pub fn skip_escape_sequence(chars: &mut Peekable<Chars<'_>>) {
+    match chars.next() {
+        // CSI: ESC [ ... 终止字节 0x40-0x7E
+        Some('[') => {
+            for c in chars.by_ref() {
+                if ('\u{40}'..='\u{7e}').contains(&c) {
+                    break;
+                }
+            }
+        }
+        // OSC: ESC ] ... BEL 或 ESC \
+        Some(']') => {
+            while let Some(c) = chars.next() {
+                if c == '\u{7}' {
+                    break;
+                }
+                if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                    chars.next();
+                    break;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 去掉 CSI / OSC 等转义序列以及除换行、制表符以外的控制字符
+pub fn strip_ansi(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => skip_escape_sequence(&mut chars),
+            '\r' => {
+                // 单独的 \r 用于覆盖当前行（进度条等），保留 \r\n 的换行语义
+                if chars.peek() != Some(&'\n') {
+                    out.push('\n');
+                }
+            }
+            '\n' | '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_csi_and_osc_sequences() {
+        let raw = "\u{1b}[31merror\u{1b}[0m: not found\r\n\u{1b}]133;D;1\u{7}$ ";
+        assert_eq!(strip_ansi(raw), "error: not found\n$ ");
+        assert_eq!(strip_ansi("\u{1b}]2;title\u{1b}\\ok"), "ok");
+    }
+}
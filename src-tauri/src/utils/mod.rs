@@ -1,5 +1,7 @@
 // 工具模块
 
+pub mod ansi;
+
 pub mod api_response;
 
 pub mod language;
@@ -4,4 +4,6 @@ pub mod tree_sitter_chunker;
 
 pub use text_chunker::TextChunker;
 pub use token_estimator::TokenEstimator;
-pub use tree_sitter_chunker::TreeSitterChunker;
+pub use tree_sitter_chunker::{
+    supported_languages, ChunkFilterStats, SupportedLanguage, TreeSitterChunker, TreeSitterChunks,
+};
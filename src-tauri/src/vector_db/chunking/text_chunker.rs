@@ -1,10 +1,17 @@
-use super::{TokenEstimator, TreeSitterChunker};
-use crate::vector_db::core::{Chunk, ChunkConfig, ChunkType, Language, Result, Span, StrideInfo};
+use super::{ChunkFilterStats, TokenEstimator, TreeSitterChunker};
+use crate::vector_db::core::{
+    Chunk, ChunkConfig, ChunkFilterConfig, ChunkSizeRange, ChunkType, Language,
+    RemoteEmbeddingConfig, Result, Span, StrideInfo,
+};
+use std::collections::HashMap;
 use std::path::Path;
 
 pub struct TextChunker {
     config: ChunkConfig,
     tree_sitter_chunker: TreeSitterChunker,
+    /// 按语言覆盖的分块大小，未覆盖的语言使用 config
+    language_sizes: HashMap<Language, ChunkSizeRange>,
+    filter: ChunkFilterConfig,
 }
 
 impl TextChunker {
@@ -12,10 +19,21 @@ impl TextChunker {
         Self {
             config: ChunkConfig {
                 max_tokens: chunk_size,
+                min_tokens: 0,
                 stride_overlap: chunk_size / 5, // 20% overlap
                 enable_striding: true,
             },
             tree_sitter_chunker: TreeSitterChunker::new(chunk_size),
+            language_sizes: HashMap::new(),
+            filter: ChunkFilterConfig::default(),
+        }
+    }
+
+    /// 按 embedding 配置创建 chunker，包含按语言的分块大小覆盖
+    pub fn from_embedding_config(config: &RemoteEmbeddingConfig) -> Self {
+        Self {
+            language_sizes: config.language_chunk_sizes.clone(),
+            ..Self::new(config.chunk_size)
         }
     }
 
@@ -25,6 +43,8 @@ impl TextChunker {
         Self {
             tree_sitter_chunker: TreeSitterChunker::new(config.max_tokens),
             config,
+            language_sizes: HashMap::new(),
+            filter: ChunkFilterConfig::default(),
         }
     }
 
@@ -33,64 +53,112 @@ impl TextChunker {
         Self {
             tree_sitter_chunker: TreeSitterChunker::new(config.max_tokens),
             config,
+            language_sizes: HashMap::new(),
+            filter: ChunkFilterConfig::default(),
+        }
+    }
+
+    /// 设置建索引前的块过滤规则
+    pub fn with_filter(mut self, filter: ChunkFilterConfig) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// 指定语言实际使用的分块配置
+    fn config_for(&self, language: Option<Language>) -> ChunkConfig {
+        match language.and_then(|l| self.language_sizes.get(&l)) {
+            Some(range) => ChunkConfig {
+                max_tokens: range.max_tokens,
+                min_tokens: range.min_tokens,
+                stride_overlap: range.max_tokens / 5,
+                enable_striding: self.config.enable_striding,
+            },
+            None => self.config.clone(),
         }
     }
 
     pub fn chunk(&self, content: &str, file_path: &Path) -> Result<Vec<Chunk>> {
+        Ok(self.chunk_with_stats(content, file_path)?.0)
+    }
+
+    /// 分块并返回被过滤的块数
+    pub fn chunk_with_stats(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<(Vec<Chunk>, ChunkFilterStats)> {
+        let language = Language::from_path(file_path);
+        let config = self.config_for(language);
+        let mut filtered = ChunkFilterStats::default();
+
         // 尝试使用 tree-sitter 智能分块
-        let mut chunks = if let Some(language) = Language::from_path(file_path) {
+        let mut chunks = if let Some(language) = language {
             // 对支持的语言使用 tree-sitter
-            if matches!(
-                language,
-                Language::Python
-                    | Language::TypeScript
-                    | Language::JavaScript
-                    | Language::Rust
-                    | Language::Go
-                    | Language::Java
-                    | Language::C
-                    | Language::Cpp
-                    | Language::CSharp
-                    | Language::Ruby
-                    | Language::Php
-                    | Language::Swift
-            ) {
+            if super::tree_sitter_chunker::has_grammar(language) {
                 tracing::debug!("Using tree-sitter chunking for {:?}", language);
-                if let Ok(chunks) = self.tree_sitter_chunker.chunk(content, file_path, language) {
-                    if !chunks.is_empty() {
-                        chunks
+                let tree_sitter_chunker = self
+                    .tree_sitter_chunker
+                    .clone()
+                    .with_min_tokens(config.min_tokens)
+                    .with_filter(self.filter.clone());
+                if let Ok(result) =
+                    tree_sitter_chunker.chunk_with_diagnostics(content, file_path, language)
+                {
+                    for error in &result.errors {
+                        tracing::warn!("{}: {}", file_path.display(), error);
+                    }
+                    filtered.merge(result.filtered);
+                    // 块全部被过滤时不回退到通用分块
+                    if !result.chunks.is_empty() || filtered.total() > 0 {
+                        result.chunks
                     } else {
-                        self.chunk_generic(content, file_path)?
+                        self.chunk_generic(content, file_path, &config)?
                     }
                 } else {
                     // 如果 tree-sitter 失败，回退到简单分块
                     tracing::warn!("Tree-sitter failed, fallback to simple chunking");
-                    self.chunk_generic(content, file_path)?
+                    self.chunk_generic(content, file_path, &config)?
                 }
             } else {
-                self.chunk_generic(content, file_path)?
+                self.chunk_generic(content, file_path, &config)?
             }
         } else {
-            self.chunk_generic(content, file_path)?
+            self.chunk_generic(content, file_path, &config)?
         };
 
+        // 通用分块产出的块同样应用过滤规则（tree-sitter 的块已在遍历时过滤）
+        if !self.filter.is_noop() {
+            chunks.retain(|chunk| match self.filter.reject_reason(chunk) {
+                Some(reason) => {
+                    filtered.record(reason);
+                    false
+                }
+                None => true,
+            });
+        }
+
         // 应用 striding（拆分超过 token 限制的大 chunk）
-        if self.config.enable_striding {
-            chunks = self.apply_striding(chunks, file_path)?;
+        if config.enable_striding {
+            chunks = self.apply_striding(chunks, file_path, &config)?;
         }
 
-        Ok(chunks)
+        Ok((chunks, filtered))
     }
 
     /// 通用分块（带 overlap）
-    fn chunk_generic(&self, content: &str, file_path: &Path) -> Result<Vec<Chunk>> {
+    fn chunk_generic(
+        &self,
+        content: &str,
+        file_path: &Path,
+        config: &ChunkConfig,
+    ) -> Result<Vec<Chunk>> {
         let mut chunks = Vec::new();
         let lines: Vec<&str> = content.lines().collect();
 
         // 根据 token 目标估算行数
         let avg_tokens_per_line = 10.0;
-        let target_lines = ((self.config.max_tokens as f32) / avg_tokens_per_line) as usize;
-        let overlap_lines = ((self.config.stride_overlap as f32) / avg_tokens_per_line) as usize;
+        let target_lines = ((config.max_tokens as f32) / avg_tokens_per_line) as usize;
+        let overlap_lines = ((config.stride_overlap as f32) / avg_tokens_per_line) as usize;
 
         let chunk_size = target_lines.max(5); // 最少 5 行
         let overlap = overlap_lines.max(1); // 最少 1 行重叠
@@ -132,13 +200,18 @@ impl TextChunker {
     }
 
     /// 应用 striding - 拆分超过 token 限制的大 chunk
-    fn apply_striding(&self, chunks: Vec<Chunk>, file_path: &Path) -> Result<Vec<Chunk>> {
+    fn apply_striding(
+        &self,
+        chunks: Vec<Chunk>,
+        file_path: &Path,
+        config: &ChunkConfig,
+    ) -> Result<Vec<Chunk>> {
         let mut result = Vec::new();
 
         for chunk in chunks {
             let estimated_tokens = TokenEstimator::estimate_tokens(&chunk.content);
 
-            if estimated_tokens <= self.config.max_tokens {
+            if estimated_tokens <= config.max_tokens {
                 // Chunk 在限制内，不需要拆分
                 result.push(chunk);
             } else {
@@ -146,10 +219,10 @@ impl TextChunker {
                 tracing::debug!(
                     "Chunk with {} tokens exceeds limit of {}, applying striding",
                     estimated_tokens,
-                    self.config.max_tokens
+                    config.max_tokens
                 );
 
-                let strided_chunks = self.stride_large_chunk(chunk, file_path)?;
+                let strided_chunks = self.stride_large_chunk(chunk, file_path, config)?;
                 result.extend(strided_chunks);
             }
         }
@@ -158,7 +231,12 @@ impl TextChunker {
     }
 
     /// 拆分大 chunk 为多个带重叠的小 chunk
-    fn stride_large_chunk(&self, chunk: Chunk, file_path: &Path) -> Result<Vec<Chunk>> {
+    fn stride_large_chunk(
+        &self,
+        chunk: Chunk,
+        file_path: &Path,
+        config: &ChunkConfig,
+    ) -> Result<Vec<Chunk>> {
         let text = &chunk.content;
 
         if text.is_empty() {
@@ -175,8 +253,8 @@ impl TextChunker {
             char_count as f32 / estimated_tokens as f32
         };
 
-        let window_chars = ((self.config.max_tokens as f32 * 0.9) * chars_per_token) as usize; // 10% 缓冲
-        let overlap_chars = (self.config.stride_overlap as f32 * chars_per_token) as usize;
+        let window_chars = ((config.max_tokens as f32 * 0.9) * chars_per_token) as usize; // 10% 缓冲
+        let overlap_chars = (config.stride_overlap as f32 * chars_per_token) as usize;
         let stride_chars = window_chars.saturating_sub(overlap_chars);
 
         if stride_chars == 0 {
@@ -236,18 +314,21 @@ impl TextChunker {
                 overlap_end,
             };
 
-            strided_chunks.push(Chunk::with_stride(
-                file_path.to_path_buf(),
-                Span::new(
-                    byte_offset_start,
-                    byte_offset_end,
-                    chunk.span.line_start + line_offset_start,
-                    chunk.span.line_start + line_offset_start + stride_lines.saturating_sub(1),
-                ),
-                stride_text.to_string(),
-                chunk.chunk_type.clone(),
-                stride_info,
-            ));
+            strided_chunks.push(
+                Chunk::with_stride(
+                    file_path.to_path_buf(),
+                    Span::new(
+                        byte_offset_start,
+                        byte_offset_end,
+                        chunk.span.line_start + line_offset_start,
+                        chunk.span.line_start + line_offset_start + stride_lines.saturating_sub(1),
+                    ),
+                    stride_text.to_string(),
+                    chunk.chunk_type.clone(),
+                    stride_info,
+                )
+                .with_symbol(chunk.symbol.clone()),
+            );
 
             start_char_idx += stride_chars;
             stride_index += 1;
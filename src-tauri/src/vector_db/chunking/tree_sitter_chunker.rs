@@ -1,143 +1,135 @@
-use crate::vector_db::core::{Chunk, ChunkType, Language, Result, Span, VectorDbError};
+use super::TokenEstimator;
+use crate::vector_db::core::{
+    Chunk, ChunkFilterConfig, ChunkFilterReason, ChunkType, Language, Result, Span, VectorDbError,
+};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
-use tree_sitter::{Parser, TreeCursor};
+use tree_sitter::{Node, Parser, TreeCursor};
+
+/// 语法树遍历的默认最大深度
+const DEFAULT_MAX_DEPTH: usize = 512;
+/// 单个文件默认最多产出的块数
+const DEFAULT_MAX_CHUNKS: usize = 2000;
 
 /// Tree-sitter 智能分块器
+#[derive(Debug, Clone)]
 pub struct TreeSitterChunker {
     _chunk_size: usize,
+    min_tokens: usize,
+    max_depth: usize,
+    max_chunks: usize,
+    filter: ChunkFilterConfig,
+}
+
+/// 按原因统计被过滤的块数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkFilterStats {
+    pub too_short: usize,
+    pub skipped_type: usize,
+}
+
+impl ChunkFilterStats {
+    pub fn record(&mut self, reason: ChunkFilterReason) {
+        match reason {
+            ChunkFilterReason::TooShort => self.too_short += 1,
+            ChunkFilterReason::SkippedType => self.skipped_type += 1,
+        }
+    }
+
+    pub fn merge(&mut self, other: ChunkFilterStats) {
+        self.too_short += other.too_short;
+        self.skipped_type += other.skipped_type;
+    }
+
+    pub fn total(&self) -> usize {
+        self.too_short + self.skipped_type
+    }
+}
+
+/// 分块结果及遍历过程中的诊断信息
+#[derive(Debug, Default)]
+pub struct TreeSitterChunks {
+    pub chunks: Vec<Chunk>,
+    /// 遍历被截断等非致命问题
+    pub errors: Vec<String>,
+    /// 被块过滤规则排除的块
+    pub filtered: ChunkFilterStats,
 }
 
 impl TreeSitterChunker {
     pub fn new(chunk_size: usize) -> Self {
         Self {
             _chunk_size: chunk_size,
+            min_tokens: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_chunks: DEFAULT_MAX_CHUNKS,
+            filter: ChunkFilterConfig::default(),
         }
     }
 
+    /// 设置遍历深度与块数上限，防止深度嵌套或超大文件拖垮索引
+    pub fn with_limits(mut self, max_depth: usize, max_chunks: usize) -> Self {
+        self.max_depth = max_depth.max(1);
+        self.max_chunks = max_chunks.max(1);
+        self
+    }
+
+    /// 设置嵌套语法块单独成块的最小 token 数；外层块已覆盖的小块将被跳过
+    pub fn with_min_tokens(mut self, min_tokens: usize) -> Self {
+        self.min_tokens = min_tokens;
+        self
+    }
+
+    /// 设置块过滤规则，被过滤的块不产出，数量记录在 `filtered`
+    pub fn with_filter(mut self, filter: ChunkFilterConfig) -> Self {
+        self.filter = filter;
+        self
+    }
+
     /// 使用 tree-sitter 按语法结构分块
     pub fn chunk(&self, content: &str, file_path: &Path, language: Language) -> Result<Vec<Chunk>> {
-        let mut parser = Parser::new();
-
-        // 设置语言解析器
-        match language {
-            Language::Python => {
-                parser
-                    .set_language(&tree_sitter_python::LANGUAGE.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!(
-                            "Failed to set Python language: {}",
-                            e
-                        ))
-                    })?;
-            }
-            Language::TypeScript => {
-                let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                let ts_lang = if ext.eq_ignore_ascii_case("tsx") {
-                    tree_sitter_typescript::LANGUAGE_TSX
-                } else {
-                    tree_sitter_typescript::LANGUAGE_TYPESCRIPT
-                };
-                parser.set_language(&ts_lang.into()).map_err(|e| {
-                    VectorDbError::ChunkingError(format!(
-                        "Failed to set TypeScript language: {}",
-                        e
-                    ))
-                })?;
-            }
-            Language::JavaScript => {
-                parser
-                    .set_language(&tree_sitter_javascript::LANGUAGE.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!(
-                            "Failed to set JavaScript language: {}",
-                            e
-                        ))
-                    })?;
-            }
-            Language::Rust => {
-                parser
-                    .set_language(&tree_sitter_rust::LANGUAGE.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!("Failed to set Rust language: {}", e))
-                    })?;
-            }
-            Language::Go => {
-                parser
-                    .set_language(&tree_sitter_go::LANGUAGE.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!("Failed to set Go language: {}", e))
-                    })?;
-            }
-            Language::Java => {
-                parser
-                    .set_language(&tree_sitter_java::LANGUAGE.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!("Failed to set Java language: {}", e))
-                    })?;
-            }
-            Language::C => {
-                parser
-                    .set_language(&tree_sitter_c::LANGUAGE.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!("Failed to set C language: {}", e))
-                    })?;
-            }
-            Language::Cpp => {
-                parser
-                    .set_language(&tree_sitter_cpp::LANGUAGE.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!("Failed to set C++ language: {}", e))
-                    })?;
-            }
-            Language::CSharp => {
-                parser
-                    .set_language(&tree_sitter_c_sharp::LANGUAGE.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!("Failed to set C# language: {}", e))
-                    })?;
-            }
-            Language::Ruby => {
-                parser
-                    .set_language(&tree_sitter_ruby::LANGUAGE.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!("Failed to set Ruby language: {}", e))
-                    })?;
-            }
-            Language::Php => {
-                parser
-                    .set_language(&tree_sitter_php::LANGUAGE_PHP.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!("Failed to set PHP language: {}", e))
-                    })?;
-            }
-            Language::Swift => {
-                parser
-                    .set_language(&tree_sitter_swift::LANGUAGE.into())
-                    .map_err(|e| {
-                        VectorDbError::ChunkingError(format!("Failed to set Swift language: {}", e))
-                    })?;
-            }
-            _ => {
-                return Err(VectorDbError::ChunkingError(format!(
-                    "Language {:?} not supported for tree-sitter parsing",
-                    language
-                )))
-            }
+        let result = self.chunk_with_diagnostics(content, file_path, language)?;
+        for error in &result.errors {
+            tracing::warn!("{}: {}", file_path.display(), error);
         }
+        Ok(result.chunks)
+    }
+
+    /// 分块并返回诊断信息
+    pub fn chunk_with_diagnostics(
+        &self,
+        content: &str,
+        file_path: &Path,
+        language: Language,
+    ) -> Result<TreeSitterChunks> {
+        let grammar = grammar_for(language, file_path).ok_or_else(|| {
+            VectorDbError::ChunkingError(format!(
+                "Language {:?} not supported for tree-sitter parsing",
+                language
+            ))
+        })?;
+        let mut parser = Parser::new();
+        parser.set_language(&grammar).map_err(|e| {
+            VectorDbError::ChunkingError(format!(
+                "Failed to set {} language: {}",
+                language.display_name(),
+                e
+            ))
+        })?;
 
         // 解析代码
         let tree = parser.parse(content, None).ok_or_else(|| {
             VectorDbError::ChunkingError(format!("Failed to parse {:?} code", language))
         })?;
 
-        let mut chunks = Vec::new();
+        let mut result = TreeSitterChunks::default();
         let mut cursor = tree.root_node().walk();
 
-        self.extract_code_chunks(&mut cursor, content, &mut chunks, file_path, language);
+        self.extract_code_chunks(&mut cursor, content, &mut result, file_path, language);
 
-        // 如果没有提取到任何块，返回整个文件作为一个块
-        if chunks.is_empty() {
-            chunks.push(Chunk::new(
+        // 如果没有提取到任何块，返回整个文件作为一个块；块全部被过滤时不回退
+        if result.chunks.is_empty() && result.filtered.total() == 0 {
+            result.chunks.push(Chunk::new(
                 file_path.to_path_buf(),
                 Span::new(0, content.len(), 1, content.lines().count()),
                 content.to_string(),
@@ -145,117 +137,271 @@ impl TreeSitterChunker {
             ));
         }
 
-        Ok(chunks)
+        Ok(result)
     }
 
-    /// 递归提取代码块
+    /// 深度优先提取代码块
+    ///
+    /// 使用游标迭代而非函数递归，避免深度嵌套的文件耗尽原生栈；
+    /// 超过深度上限的子树被跳过，达到块数上限时停止遍历，两者都会记录到 `errors`。
     fn extract_code_chunks(
         &self,
         cursor: &mut TreeCursor,
         source: &str,
-        chunks: &mut Vec<Chunk>,
+        result: &mut TreeSitterChunks,
         file_path: &Path,
         language: Language,
     ) {
-        let node = cursor.node();
-        let node_kind = node.kind();
+        let mut depth = 0usize;
+        let mut depth_truncated = false;
+        // 已产出块覆盖到的字节位置；深度优先按文档顺序遍历，落在其内的节点即为嵌套节点
+        let mut covered_until = 0usize;
 
-        // 根据语言判断是否为有意义的代码块
-        let is_chunk = match language {
-            Language::Python => {
-                matches!(node_kind, "function_definition" | "class_definition")
-            }
-            Language::TypeScript | Language::JavaScript => {
-                matches!(
-                    node_kind,
-                    "function_declaration"
-                        | "class_declaration"
-                        | "method_definition"
-                        | "arrow_function"
-                )
-            }
-            Language::Rust => {
-                matches!(
-                    node_kind,
-                    "function_item"
-                        | "impl_item"
-                        | "struct_item"
-                        | "enum_item"
-                        | "trait_item"
-                        | "mod_item"
-                )
+        loop {
+            let node = cursor.node();
+            if let Some(chunk) = self.chunk_for_node(&node, source, file_path, language) {
+                let nested = chunk.span.byte_end <= covered_until;
+                if let Some(reason) = self.filter.reject_reason(&chunk) {
+                    // 被过滤的块不计入覆盖范围，其中嵌套的块仍可单独成块
+                    result.filtered.record(reason);
+                } else if !(nested && self.is_below_min(&chunk)) {
+                    covered_until = covered_until.max(chunk.span.byte_end);
+                    result.chunks.push(chunk);
+                }
+                if result.chunks.len() >= self.max_chunks {
+                    result.errors.push(format!(
+                        "truncated: reached max chunks per file ({})",
+                        self.max_chunks
+                    ));
+                    break;
+                }
             }
-            Language::Go => {
-                matches!(
-                    node_kind,
-                    "function_declaration" | "method_declaration" | "type_declaration"
-                )
+
+            if depth < self.max_depth {
+                if cursor.goto_first_child() {
+                    depth += 1;
+                    continue;
+                }
+            } else if node.child_count() > 0 {
+                depth_truncated = true;
             }
-            Language::Java => {
-                matches!(
-                    node_kind,
-                    "method_declaration" | "class_declaration" | "interface_declaration"
-                )
+
+            // 回溯到下一个兄弟节点
+            let mut finished = false;
+            while !cursor.goto_next_sibling() {
+                if depth == 0 || !cursor.goto_parent() {
+                    finished = true;
+                    break;
+                }
+                depth -= 1;
             }
-            Language::C | Language::Cpp => {
-                matches!(
-                    node_kind,
-                    "function_definition" | "struct_specifier" | "class_specifier"
-                )
+            if finished {
+                break;
             }
-            Language::CSharp => {
-                matches!(
-                    node_kind,
-                    "method_declaration" | "class_declaration" | "interface_declaration"
-                )
+        }
+
+        if depth_truncated {
+            result.errors.push(format!(
+                "truncated: syntax tree deeper than max depth ({})",
+                self.max_depth
+            ));
+        }
+    }
+
+    fn is_below_min(&self, chunk: &Chunk) -> bool {
+        self.min_tokens > 0 && TokenEstimator::estimate_tokens(&chunk.content) < self.min_tokens
+    }
+
+    /// 判断节点是否构成代码块，是则构造对应的 Chunk
+    fn chunk_for_node(
+        &self,
+        node: &Node,
+        source: &str,
+        file_path: &Path,
+        language: Language,
+    ) -> Option<Chunk> {
+        let node_kind = node.kind();
+
+        // 根据语言判断是否为有意义的代码块；Ruby 的 class/module 关键字本身也是同名的匿名节点
+        let is_chunk = node.is_named() && chunk_node_kinds(language).contains(&node_kind);
+
+        if !is_chunk {
+            return None;
+        }
+        // C/C++ 中 `struct point *p` 这类类型引用也是 struct_specifier，只有带定义体的才成块
+        if matches!(node_kind, "struct_specifier" | "class_specifier")
+            && node.child_by_field_name("body").is_none()
+        {
+            return None;
+        }
+
+        let start_byte = node.start_byte();
+        let end_byte = node.end_byte();
+        let start_pos = node.start_position();
+        let end_pos = node.end_position();
+
+        let text = &source[start_byte..end_byte];
+
+        // 确定块类型
+        let chunk_type = match node_kind {
+            "function_definition" | "function_declaration" | "arrow_function" | "function_item" => {
+                ChunkType::Function
             }
-            Language::Ruby => {
-                matches!(node_kind, "method" | "class" | "module")
+            "class_definition" | "class_declaration" | "struct_item" | "enum_item"
+            | "class_specifier" | "struct_specifier" | "class" => ChunkType::Class,
+            "method_definition" | "method_declaration" | "method" | "singleton_method" => {
+                ChunkType::Method
             }
-            _ => false,
+            "impl_item"
+            | "trait_item"
+            | "mod_item"
+            | "module"
+            | "interface_declaration"
+            | "trait_declaration" => ChunkType::Struct,
+            _ => ChunkType::Generic,
         };
 
-        if is_chunk {
-            let start_byte = node.start_byte();
-            let end_byte = node.end_byte();
-            let start_pos = node.start_position();
-            let end_pos = node.end_position();
-
-            let text = &source[start_byte..end_byte];
-
-            // 确定块类型
-            let chunk_type = match node_kind {
-                "function_definition"
-                | "function_declaration"
-                | "arrow_function"
-                | "function_item" => ChunkType::Function,
-                "class_definition" | "class_declaration" | "struct_item" | "enum_item"
-                | "class_specifier" | "class" => ChunkType::Class,
-                "method_definition" | "method_declaration" | "method" => ChunkType::Method,
-                "impl_item" | "trait_item" | "mod_item" | "module" | "interface_declaration" => {
-                    ChunkType::Struct
-                }
-                _ => ChunkType::Generic,
-            };
-
-            chunks.push(Chunk::new(
+        Some(
+            Chunk::new(
                 file_path.to_path_buf(),
                 Span::new(start_byte, end_byte, start_pos.row + 1, end_pos.row + 1),
                 text.to_string(),
                 chunk_type,
-            ));
-        }
+            )
+            .with_symbol(node_symbol(node, source)),
+        )
+    }
+}
 
-        // 递归处理子节点
-        if cursor.goto_first_child() {
-            loop {
-                self.extract_code_chunks(cursor, source, chunks, file_path, language);
-                if !cursor.goto_next_sibling() {
-                    break;
-                }
+/// 语言对应的 Tree-sitter 语法；TypeScript 按扩展名区分 tsx。未注册语法的语言返回 None
+pub fn grammar_for(language: Language, file_path: &Path) -> Option<tree_sitter::Language> {
+    let grammar = match language {
+        Language::Python => tree_sitter_python::LANGUAGE,
+        Language::TypeScript => {
+            let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if ext.eq_ignore_ascii_case("tsx") {
+                tree_sitter_typescript::LANGUAGE_TSX
+            } else {
+                tree_sitter_typescript::LANGUAGE_TYPESCRIPT
             }
-            cursor.goto_parent();
         }
+        Language::JavaScript => tree_sitter_javascript::LANGUAGE,
+        Language::Rust => tree_sitter_rust::LANGUAGE,
+        Language::Go => tree_sitter_go::LANGUAGE,
+        Language::Java => tree_sitter_java::LANGUAGE,
+        Language::C => tree_sitter_c::LANGUAGE,
+        Language::Cpp => tree_sitter_cpp::LANGUAGE,
+        Language::CSharp => tree_sitter_c_sharp::LANGUAGE,
+        Language::Ruby => tree_sitter_ruby::LANGUAGE,
+        Language::Php => tree_sitter_php::LANGUAGE_PHP,
+        Language::Swift => tree_sitter_swift::LANGUAGE,
+        Language::Kotlin => return None,
+    };
+    Some(grammar.into())
+}
+
+/// 是否注册了 Tree-sitter 语法；未注册的语言按通用规则分块
+pub fn has_grammar(language: Language) -> bool {
+    grammar_for(language, Path::new("")).is_some()
+}
+
+/// 单独成块的语法节点类型；为空表示该语言不提取符号，整个文件作为一个块
+fn chunk_node_kinds(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::Python => &["function_definition", "class_definition"],
+        Language::TypeScript | Language::JavaScript => &[
+            "function_declaration",
+            "class_declaration",
+            "method_definition",
+            "arrow_function",
+        ],
+        Language::Rust => &[
+            "function_item",
+            "impl_item",
+            "struct_item",
+            "enum_item",
+            "trait_item",
+            "mod_item",
+        ],
+        Language::Go => &[
+            "function_declaration",
+            "method_declaration",
+            "type_declaration",
+        ],
+        Language::Java | Language::CSharp => &[
+            "method_declaration",
+            "class_declaration",
+            "interface_declaration",
+        ],
+        Language::C | Language::Cpp => {
+            &["function_definition", "struct_specifier", "class_specifier"]
+        }
+        Language::Ruby => &["method", "singleton_method", "class", "module"],
+        Language::Php => &[
+            "function_definition",
+            "class_declaration",
+            "method_declaration",
+            "interface_declaration",
+            "trait_declaration",
+        ],
+        Language::Swift | Language::Kotlin => &[],
+    }
+}
+
+/// 是否按语法结构切块并提取符号名
+pub fn supports_symbols(language: Language) -> bool {
+    has_grammar(language) && !chunk_node_kinds(language).is_empty()
+}
+
+/// 可索引语言的说明，由 `Language::ALL` 与语法注册表推导
+#[derive(Debug, Clone, Serialize)]
+pub struct SupportedLanguage {
+    pub language: Language,
+    pub display_name: &'static str,
+    pub extensions: &'static [&'static str],
+    /// 是否注册了 Tree-sitter 语法；否则按通用规则分块
+    pub grammar_registered: bool,
+    /// 是否按语法结构切块并提取符号名
+    pub symbol_extraction: bool,
+}
+
+pub fn supported_languages() -> Vec<SupportedLanguage> {
+    Language::ALL
+        .into_iter()
+        .map(|language| SupportedLanguage {
+            language,
+            display_name: language.display_name(),
+            extensions: language.extensions(),
+            grammar_registered: has_grammar(language),
+            symbol_extraction: supports_symbols(language),
+        })
+        .collect()
+}
+
+/// 提取节点对应的符号名
+///
+/// 大多数语法使用 `name` 字段；Rust 的 impl 块使用 `type` 字段，
+/// C/C++ 函数名嵌套在 declarator 中。
+fn node_symbol(node: &Node, source: &str) -> Option<String> {
+    // C/C++ 函数的 type 字段是返回类型，函数名在 declarator 中，需先于 type 查找
+    let name_node = node
+        .child_by_field_name("name")
+        .or_else(|| {
+            let mut declarator = node.child_by_field_name("declarator")?;
+            while let Some(inner) = declarator.child_by_field_name("declarator") {
+                declarator = inner;
+            }
+            Some(declarator)
+        })
+        .or_else(|| node.child_by_field_name("type"))?;
+
+    let text = source
+        .get(name_node.start_byte()..name_node.end_byte())?
+        .trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
     }
 }
 
@@ -309,4 +455,234 @@ impl MyStruct {
 
         assert!(chunks.len() >= 3);
     }
+
+    #[test]
+    fn test_symbol_extraction() {
+        let code = r#"
+fn build_index() {}
+
+impl MyStruct {
+    fn new() -> Self {
+        Self
+    }
+}
+"#;
+
+        let chunker = TreeSitterChunker::new(512);
+        let chunks = chunker
+            .chunk(code, Path::new("test.rs"), Language::Rust)
+            .unwrap();
+
+        let symbols: Vec<_> = chunks.iter().filter_map(|c| c.symbol.as_deref()).collect();
+        assert!(symbols.contains(&"build_index"));
+        assert!(symbols.contains(&"MyStruct"));
+        assert!(symbols.contains(&"new"));
+    }
+
+    #[test]
+    fn test_c_chunking() {
+        let code = r#"
+struct point {
+    int x;
+    int y;
+};
+
+static int *distance(struct point *a, struct point *b) {
+    return 0;
+}
+"#;
+
+        let chunker = TreeSitterChunker::new(512);
+        let chunks = chunker
+            .chunk(code, Path::new("geometry.c"), Language::C)
+            .unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].chunk_type, ChunkType::Class);
+        assert_eq!(chunks[0].symbol.as_deref(), Some("point"));
+        assert_eq!(chunks[1].chunk_type, ChunkType::Function);
+        assert_eq!(chunks[1].symbol.as_deref(), Some("distance"));
+
+        assert_eq!(
+            Language::from_path(Path::new("geometry.h")),
+            Some(Language::Cpp)
+        );
+        assert_eq!(
+            Language::from_path(Path::new("geometry.hpp")),
+            Some(Language::Cpp)
+        );
+    }
+
+    #[test]
+    fn test_ruby_and_php_symbols() {
+        let ruby = r#"
+module Billing
+  class Invoice
+    def total
+      0
+    end
+
+    def self.build
+      new
+    end
+  end
+end
+"#;
+        let chunker = TreeSitterChunker::new(512);
+        let chunks = chunker
+            .chunk(ruby, Path::new("invoice.rb"), Language::Ruby)
+            .unwrap();
+        let symbols: Vec<_> = chunks.iter().filter_map(|c| c.symbol.as_deref()).collect();
+        assert_eq!(symbols, vec!["Billing", "Invoice", "total", "build"]);
+        assert_eq!(chunks[2].chunk_type, ChunkType::Method);
+
+        let php = r#"<?php
+function format_amount($value) {
+    return $value;
+}
+
+class InvoiceController {
+    public function show($id) {
+        return $id;
+    }
+}
+"#;
+        let chunks = chunker
+            .chunk(php, Path::new("InvoiceController.php"), Language::Php)
+            .unwrap();
+        let kinds: Vec<_> = chunks
+            .iter()
+            .map(|c| (c.symbol.as_deref(), c.chunk_type.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (Some("format_amount"), ChunkType::Function),
+                (Some("InvoiceController"), ChunkType::Class),
+                (Some("show"), ChunkType::Method),
+            ]
+        );
+        assert!(supports_symbols(Language::Ruby) && supports_symbols(Language::Php));
+    }
+
+    #[test]
+    fn test_deeply_nested_input_is_truncated() {
+        let depth = 2000;
+        let code = format!("const f = {}0;", "() => ".repeat(depth));
+
+        let chunker = TreeSitterChunker::new(512).with_limits(64, 10_000);
+        let result = chunker
+            .chunk_with_diagnostics(&code, Path::new("nested.js"), Language::JavaScript)
+            .unwrap();
+
+        assert!(!result.chunks.is_empty());
+        assert!(result.chunks.len() < depth);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.contains("truncated") && e.contains("depth")));
+    }
+
+    #[test]
+    fn test_max_chunks_per_file() {
+        let code: String = (0..20)
+            .map(|i| format!("def f{}():\n    pass\n\n", i))
+            .collect();
+
+        let chunker = TreeSitterChunker::new(512).with_limits(512, 5);
+        let result = chunker
+            .chunk_with_diagnostics(&code, Path::new("many.py"), Language::Python)
+            .unwrap();
+
+        assert_eq!(result.chunks.len(), 5);
+        assert!(result.errors.iter().any(|e| e.contains("max chunks")));
+    }
+
+    #[test]
+    fn test_min_tokens_skips_small_nested_chunks() {
+        let code = r#"
+fn tiny() {}
+
+impl MyStruct {
+    fn new() -> Self {
+        Self { field: 0 }
+    }
+}
+"#;
+
+        let chunker = TreeSitterChunker::new(512).with_min_tokens(1000);
+        let chunks = chunker
+            .chunk(code, Path::new("test.rs"), Language::Rust)
+            .unwrap();
+
+        // 顶层块即使很小也保留，嵌套的 new 被 impl 块覆盖后跳过
+        let symbols: Vec<_> = chunks.iter().filter_map(|c| c.symbol.as_deref()).collect();
+        assert_eq!(symbols, vec!["tiny", "MyStruct"]);
+    }
+
+    #[test]
+    fn test_filter_skips_types_and_short_chunks() {
+        let code = r#"
+struct Unit;
+
+impl MyStruct {
+    fn new() -> Self {
+        Self { field: 0 }
+    }
+}
+"#;
+
+        let chunker = TreeSitterChunker::new(512).with_filter(ChunkFilterConfig {
+            min_indexable_chars: 20,
+            skip_chunk_types: vec![ChunkType::Struct],
+        });
+        let result = chunker
+            .chunk_with_diagnostics(code, Path::new("test.rs"), Language::Rust)
+            .unwrap();
+
+        // impl 块按类型跳过，struct Unit 过短，嵌套的 new 仍单独成块
+        let symbols: Vec<_> = result
+            .chunks
+            .iter()
+            .filter_map(|c| c.symbol.as_deref())
+            .collect();
+        assert_eq!(symbols, vec!["new"]);
+        assert_eq!(
+            result.filtered,
+            ChunkFilterStats {
+                too_short: 1,
+                skipped_type: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn supported_languages_follow_registered_grammars() {
+        let languages = supported_languages();
+        assert_eq!(languages.len(), Language::ALL.len());
+
+        let rust = languages
+            .iter()
+            .find(|l| l.language == Language::Rust)
+            .unwrap();
+        assert!(rust.grammar_registered && rust.symbol_extraction);
+
+        let swift = languages
+            .iter()
+            .find(|l| l.language == Language::Swift)
+            .unwrap();
+        assert!(swift.grammar_registered && !swift.symbol_extraction);
+
+        let kotlin = languages
+            .iter()
+            .find(|l| l.language == Language::Kotlin)
+            .unwrap();
+        assert!(!kotlin.grammar_registered && !kotlin.symbol_extraction);
+
+        for language in &languages {
+            for ext in language.extensions {
+                assert_eq!(Language::from_extension(ext), Some(language.language));
+            }
+        }
+    }
 }
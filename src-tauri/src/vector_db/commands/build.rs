@@ -1,15 +1,25 @@
 use crate::utils::{EmptyData, TauriApiResult};
+use crate::vector_db::chunking::{ChunkFilterStats, TokenEstimator};
 use crate::vector_db::commands::VectorDbState;
+use crate::vector_db::core::{EmbeddingOutageConfig, VectorDbError};
+use crate::vector_db::embedding::Embedder;
+use crate::vector_db::storage::index_manager::EMBED_BATCH_SIZE;
+use crate::vector_db::storage::{
+    embedding_price_per_million, BuildThroughput, BuildThroughputSummary, IndexFileOutcome,
+    IndexManager, PreparedFile, ThroughputTracker, THROUGHPUT_WINDOW,
+};
 use crate::{api_error, api_success};
+use futures::StreamExt;
 use parking_lot::Mutex;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{ipc::Channel, State};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Notify};
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, warn};
+use tracing::{error, info, warn};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -18,13 +28,17 @@ pub enum VectorBuildPhase {
     CollectingFiles,
     Chunking,
     Embedding,
+    /// embedding 服务疑似中断，暂停构建等待恢复
+    WaitingForProvider,
+    /// 已达到本次构建的 embedding token 上限，等待用户确认（调高上限）后继续
+    SpendCapReached,
     Writing,
     Completed,
     Cancelled,
     Failed,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct VectorBuildProgress {
     pub phase: VectorBuildPhase,
     pub root: String,
@@ -37,12 +51,29 @@ pub struct VectorBuildProgress {
     pub current_file_chunks_total: usize,
     pub current_file_chunks_done: usize,
 
+    /// 所有文件累计的 embedding 批次重试次数
+    pub embed_retries: u32,
+    /// 所有文件累计被块过滤规则排除的块
+    pub filtered_chunks: ChunkFilterStats,
+    /// 等待 embedding 服务恢复时，距下一次探测的秒数
+    pub retry_in_secs: Option<u64>,
+    /// 最近一段时间的 embedding 与写入速率
+    pub throughput: BuildThroughput,
+    /// 构建结束时的吞吐汇总
+    pub throughput_summary: Option<BuildThroughputSummary>,
+    /// 本次构建累计送入 embedding 的 token 数（估算）
+    pub embedded_tokens: u64,
+    /// 本次构建的 token 上限，None 表示不限制
+    pub embedding_token_cap: Option<u64>,
+    /// 按模型价格折算的累计费用，价格未知时为 None
+    pub estimated_cost_usd: Option<f64>,
+
     pub is_done: bool,
     pub error: Option<String>,
 }
 
 impl VectorBuildProgress {
-    fn new(root: String) -> Self {
+    fn new(root: String, embedding_token_cap: Option<u64>) -> Self {
         Self {
             phase: VectorBuildPhase::Pending,
             root,
@@ -52,6 +83,14 @@ impl VectorBuildProgress {
             current_file: None,
             current_file_chunks_total: 0,
             current_file_chunks_done: 0,
+            embed_retries: 0,
+            filtered_chunks: ChunkFilterStats::default(),
+            retry_in_secs: None,
+            throughput: BuildThroughput::default(),
+            throughput_summary: None,
+            embedded_tokens: 0,
+            embedding_token_cap,
+            estimated_cost_usd: None,
             is_done: false,
             error: None,
         }
@@ -61,16 +100,19 @@ impl VectorBuildProgress {
 struct BuildState {
     progress: Mutex<VectorBuildProgress>,
     tx: broadcast::Sender<VectorBuildProgress>,
+    /// 调整 token 上限时唤醒暂停中的构建
+    cap_changed: Notify,
 }
 
 impl BuildState {
-    fn new(root: String) -> Self {
+    fn new(root: String, embedding_token_cap: Option<u64>) -> Self {
         let (tx, _rx) = broadcast::channel::<VectorBuildProgress>(64);
-        let progress = VectorBuildProgress::new(root);
+        let progress = VectorBuildProgress::new(root, embedding_token_cap);
         let _ = tx.send(progress.clone());
         Self {
             progress: Mutex::new(progress),
             tx,
+            cap_changed: Notify::new(),
         }
     }
 
@@ -102,6 +144,98 @@ fn build_tasks() -> &'static Arc<Mutex<HashMap<String, BuildEntry>>> {
     BUILD_TASKS.get_or_init(|| Arc::new(Mutex::new(HashMap::new())))
 }
 
+enum ProviderWait {
+    Recovered,
+    Cancelled,
+    GaveUp,
+}
+
+/// 按退避间隔探测 embedding 服务直到恢复；waited 为本次构建累计的等待时长
+async fn wait_for_provider(
+    embedder: &dyn Embedder,
+    outage: &EmbeddingOutageConfig,
+    waited: &mut Duration,
+    token: &CancellationToken,
+    state: &BuildState,
+) -> ProviderWait {
+    let budget = Duration::from_secs(outage.max_total_wait_secs);
+    let mut attempt = 0u32;
+    loop {
+        let delay = outage.backoff(attempt);
+        if *waited + delay > budget {
+            return ProviderWait::GaveUp;
+        }
+        let secs = delay.as_secs().max(1);
+        warn!("embedding 服务疑似中断，{} 秒后探测", secs);
+        state.update(|p| {
+            p.phase = VectorBuildPhase::WaitingForProvider;
+            p.retry_in_secs = Some(secs);
+            p.error = Some(format!(
+                "Embedding provider appears down, retrying in {}s",
+                secs
+            ));
+        });
+        tokio::select! {
+            _ = token.cancelled() => return ProviderWait::Cancelled,
+            _ = tokio::time::sleep(delay) => {}
+        }
+        *waited += delay;
+        attempt += 1;
+
+        match embedder.embed(&["ping"]).await {
+            Ok(_) => {
+                info!("embedding 服务已恢复，继续构建");
+                state.update(|p| {
+                    p.retry_in_secs = None;
+                    p.error = None;
+                });
+                return ProviderWait::Recovered;
+            }
+            Err(e) => warn!("embedding 服务探测失败: {}", e),
+        }
+    }
+}
+
+/// 下一个文件会超出 token 上限时暂停，直到上限被调高或取消；取消时返回 false
+async fn wait_for_spend_cap(
+    file_tokens: u64,
+    token: &CancellationToken,
+    state: &BuildState,
+) -> bool {
+    let mut paused = false;
+    loop {
+        let (spent, cap) = {
+            let p = state.progress.lock();
+            (p.embedded_tokens, p.embedding_token_cap)
+        };
+        if cap.is_none_or(|cap| spent + file_tokens <= cap) {
+            if paused {
+                info!("embedding token 上限已调整，继续构建");
+                state.update(|p| {
+                    p.phase = VectorBuildPhase::Chunking;
+                    p.error = None;
+                });
+            }
+            return true;
+        }
+        if !paused {
+            warn!(
+                "已送入 {} 个 token，下一个文件约 {} 个 token，超出上限 {:?}，暂停构建",
+                spent, file_tokens, cap
+            );
+            paused = true;
+            state.update(|p| {
+                p.phase = VectorBuildPhase::SpendCapReached;
+                p.error = Some("embedding_spend_cap_reached".into());
+            });
+        }
+        tokio::select! {
+            _ = token.cancelled() => return false,
+            _ = state.cap_changed.notified() => {}
+        }
+    }
+}
+
 fn send_progress(channel: &Channel<VectorBuildProgress>, p: VectorBuildProgress) -> bool {
     if let Err(e) = channel.send(p) {
         warn!("Failed to send vector build progress: {}", e);
@@ -114,6 +248,7 @@ fn start_build_locked(
     store: &mut HashMap<String, BuildEntry>,
     path: String,
     state: Arc<crate::vector_db::SemanticSearchEngine>,
+    reindex: bool,
 ) {
     if let Some(existing) = store.remove(&path) {
         existing.token.cancel();
@@ -121,10 +256,13 @@ fn start_build_locked(
     }
 
     let token = CancellationToken::new();
-    let task_state = Arc::new(BuildState::new(path.clone()));
-
     let root = PathBuf::from(&path);
-    let config = state.config().clone();
+    let config = state.config();
+    let task_state = Arc::new(BuildState::new(
+        path.clone(),
+        config.max_embedding_tokens_per_build,
+    ));
+
     let embedder = state.embedder();
     let token_for_task = token.clone();
     let task_state_for_task = Arc::clone(&task_state);
@@ -138,7 +276,7 @@ fn start_build_locked(
             p.is_done = false;
         });
 
-        let manager = match crate::vector_db::storage::IndexManager::new(&root, config.clone()) {
+        let manager = match IndexManager::new(&root, config.clone()) {
             Ok(m) => Arc::new(m),
             Err(e) => {
                 error!("创建工作区索引管理器失败: {}", e);
@@ -151,6 +289,26 @@ fn start_build_locked(
             }
         };
 
+        // 切换了 embedding 模型时只能显式重建，避免不同模型的向量混在同一索引中
+        let prepared = if reindex {
+            manager.reset()
+        } else {
+            manager.check_embedding_model()
+        };
+        if let Err(e) = prepared {
+            warn!("索引无法在当前 embedding 模型下继续构建: {}", e);
+            let code = match e {
+                VectorDbError::EmbeddingModelMismatch { .. } => "embedding_model_mismatch",
+                _ => "index_reset_failed",
+            };
+            task_state_for_task.update(|p| {
+                p.phase = VectorBuildPhase::Failed;
+                p.is_done = true;
+                p.error = Some(code.into());
+            });
+            return;
+        }
+
         let file_list_res = tokio::task::spawn_blocking({
             let root = root.clone();
             let max = config.max_file_size;
@@ -181,7 +339,45 @@ fn start_build_locked(
             p.phase = VectorBuildPhase::Chunking;
         });
 
-        for file_path in files {
+        // 读取与分块在阻塞线程池中提前并发进行，embedding 与写入按文件顺序执行
+        let started = Instant::now();
+        let file_count = files.len();
+        let prepared_files = IndexManager::prepare_files(Arc::new(config.clone()), files);
+        futures::pin_mut!(prepared_files);
+
+        // 连续因 embedding 服务不可用而失败的文件先暂存：达到阈值时等待服务恢复后重试，
+        // 中途有文件成功则视为偶发失败，计入失败数
+        let outage = config.embedding_outage;
+        let mut unavailable_run: Vec<PreparedFile> = Vec::new();
+        let mut retry_queue: VecDeque<PreparedFile> = VecDeque::new();
+        let mut outage_waited = Duration::ZERO;
+        let mut throughput = ThroughputTracker::new(THROUGHPUT_WINDOW);
+        let price_per_million = embedding_price_per_million(&config);
+        let flush_run = |run: &mut Vec<PreparedFile>| {
+            if run.is_empty() {
+                return;
+            }
+            let failed = run.len();
+            run.clear();
+            task_state_for_task.update(|p| {
+                p.files_failed += failed;
+                p.files_done += failed;
+            });
+        };
+
+        loop {
+            let (file_path, prepared) = match retry_queue.pop_front() {
+                Some(file) => (file.path.clone(), Ok(Some(file))),
+                None => {
+                    let waiting = Instant::now();
+                    let next = prepared_files.next().await;
+                    throughput.record_parse_wait(waiting.elapsed());
+                    match next {
+                        Some(next) => next,
+                        None => break,
+                    }
+                }
+            };
             if token_for_task.is_cancelled() {
                 task_state_for_task.update(|p| {
                     p.phase = VectorBuildPhase::Cancelled;
@@ -201,22 +397,114 @@ fn start_build_locked(
                 p.error = None;
             });
 
-            let res = manager
-                .index_file_with_progress(&file_path, &*embedder, |done, total| {
-                    task_state_for_task.update(|p| {
-                        p.phase = VectorBuildPhase::Embedding;
-                        p.current_file_chunks_total = total;
-                        p.current_file_chunks_done = done;
-                    });
-                })
-                .await;
+            let res = match prepared {
+                Ok(Some(file)) => {
+                    let chunk_tokens: Vec<u64> = file
+                        .chunks
+                        .iter()
+                        .map(|c| TokenEstimator::estimate_tokens(&c.content) as u64)
+                        .collect();
+                    let file_tokens = chunk_tokens.iter().sum();
+                    if !wait_for_spend_cap(file_tokens, &token_for_task, &task_state_for_task).await
+                    {
+                        task_state_for_task.update(|p| {
+                            p.phase = VectorBuildPhase::Cancelled;
+                            p.is_done = true;
+                            p.current_file = None;
+                            p.current_file_chunks_total = 0;
+                            p.current_file_chunks_done = 0;
+                        });
+                        return;
+                    }
+                    // 按已完成的批次累计花费：失败或重新排队的文件已请求的批次同样计入上限
+                    let mut embedded_chunks = 0usize;
+                    let res = manager
+                        .index_prepared(&file, &*embedder, |done, total| {
+                            // provider 多返回向量时 done 可能超出块数，截断以免越界
+                            let done_chunks = done.min(chunk_tokens.len());
+                            let batch_tokens: u64 = chunk_tokens
+                                .get(embedded_chunks..done_chunks)
+                                .map_or(0, |tokens| tokens.iter().sum());
+                            embedded_chunks = done_chunks;
+                            task_state_for_task.update(|p| {
+                                p.phase = VectorBuildPhase::Embedding;
+                                p.current_file_chunks_total = total;
+                                p.current_file_chunks_done = done;
+                                p.embedded_tokens += batch_tokens;
+                                p.estimated_cost_usd = price_per_million
+                                    .map(|price| p.embedded_tokens as f64 / 1_000_000.0 * price);
+                            });
+                        })
+                        .await;
+                    match res {
+                        Err(e) if e.is_retryable() => {
+                            unavailable_run.push(file);
+                            task_state_for_task.update(|p| p.error = Some(e.to_string()));
+                            if unavailable_run.len() < outage.consecutive_failures {
+                                continue;
+                            }
+                            match wait_for_provider(
+                                &*embedder,
+                                &outage,
+                                &mut outage_waited,
+                                &token_for_task,
+                                &task_state_for_task,
+                            )
+                            .await
+                            {
+                                ProviderWait::Recovered => {
+                                    retry_queue.extend(unavailable_run.drain(..));
+                                    continue;
+                                }
+                                ProviderWait::Cancelled => {
+                                    task_state_for_task.update(|p| {
+                                        p.phase = VectorBuildPhase::Cancelled;
+                                        p.is_done = true;
+                                        p.retry_in_secs = None;
+                                        p.current_file = None;
+                                        p.current_file_chunks_total = 0;
+                                        p.current_file_chunks_done = 0;
+                                    });
+                                    return;
+                                }
+                                ProviderWait::GaveUp => {
+                                    error!(
+                                        "embedding 服务在 {:?} 内未恢复，放弃构建",
+                                        outage_waited
+                                    );
+                                    flush_run(&mut unavailable_run);
+                                    task_state_for_task.update(|p| {
+                                        p.phase = VectorBuildPhase::Failed;
+                                        p.is_done = true;
+                                        p.retry_in_secs = None;
+                                        p.error = Some("embedding_provider_unavailable".into());
+                                        p.current_file = None;
+                                        p.current_file_chunks_total = 0;
+                                        p.current_file_chunks_done = 0;
+                                    });
+                                    return;
+                                }
+                            }
+                        }
+                        res => res,
+                    }
+                }
+                Ok(None) => Ok(IndexFileOutcome::default()),
+                Err(e) => Err(e),
+            };
+            flush_run(&mut unavailable_run);
 
             match res {
                 Ok(outcome) => {
+                    throughput.record_file(&outcome);
+                    let rates = throughput.current();
                     task_state_for_task.update(|p| {
+                        p.throughput = rates;
                         p.phase = VectorBuildPhase::Writing;
                         p.current_file_chunks_total = outcome.indexed_chunks;
                         p.current_file_chunks_done = outcome.indexed_chunks;
+                        p.embed_retries += outcome.retries;
+                        p.filtered_chunks.merge(outcome.filtered);
                         p.files_done += 1;
                     });
                 }
@@ -230,6 +518,19 @@ fn start_build_locked(
             }
         }
 
+        flush_run(&mut unavailable_run);
+
+        let summary = throughput.summary(embedder.id(), EMBED_BATCH_SIZE);
+        info!(
+            "工作区索引构建完成: {} 个文件, 耗时 {:?}, 分块并发 {}, {:.1} 块/秒, 平均批大小 {:.1}, 瓶颈 {:?}",
+            file_count,
+            started.elapsed(),
+            config.parsing.max_concurrent_files,
+            summary.chunks_per_sec,
+            summary.avg_batch_size,
+            summary.bottleneck
+        );
+
         task_state_for_task.update(|p| {
             p.phase = if p.files_failed > 0 {
                 VectorBuildPhase::Failed
@@ -240,6 +541,7 @@ fn start_build_locked(
             p.current_file = None;
             p.current_file_chunks_total = 0;
             p.current_file_chunks_done = 0;
+            p.throughput_summary = Some(summary);
         });
     });
 
@@ -256,10 +558,19 @@ fn start_build_locked(
 #[tauri::command]
 pub async fn vector_build_index_start(
     path: String,
+    reindex: Option<bool>,
     state: State<'_, VectorDbState>,
 ) -> TauriApiResult<EmptyData> {
+    if state.search_engine.ensure_embedding_model().is_err() {
+        return Ok(api_error!("vector_db.embedding_model_removed"));
+    }
     let mut store = build_tasks().lock();
-    start_build_locked(&mut store, path, state.search_engine.clone());
+    start_build_locked(
+        &mut store,
+        path,
+        state.search_engine.clone(),
+        reindex.unwrap_or(false),
+    );
     Ok(api_success!(EmptyData::default()))
 }
 
@@ -276,25 +587,56 @@ pub async fn vector_build_index_subscribe(
     path: String,
     channel: Channel<VectorBuildProgress>,
 ) -> TauriApiResult<EmptyData> {
+    if !stream_progress(&path, &channel).await {
+        return Ok(api_error!("vector_db.progress_unavailable"));
+    }
+    Ok(api_success!(EmptyData::default()))
+}
+
+/// 启动构建并通过调用方的 channel 推送该次构建的进度，直到构建结束或 channel 关闭；
+/// 相当于 start + subscribe，避免多个窗口或多个构建共享同一路进度
+#[tauri::command]
+pub async fn vector_build_index_run(
+    path: String,
+    reindex: Option<bool>,
+    channel: Channel<VectorBuildProgress>,
+    state: State<'_, VectorDbState>,
+) -> TauriApiResult<EmptyData> {
+    if state.search_engine.ensure_embedding_model().is_err() {
+        return Ok(api_error!("vector_db.embedding_model_removed"));
+    }
+    {
+        let mut store = build_tasks().lock();
+        start_build_locked(
+            &mut store,
+            path.clone(),
+            state.search_engine.clone(),
+            reindex.unwrap_or(false),
+        );
+    }
+    stream_progress(&path, &channel).await;
+    Ok(api_success!(EmptyData::default()))
+}
+
+/// 把指定构建的进度转发到 channel；没有该构建时返回 false
+async fn stream_progress(path: &str, channel: &Channel<VectorBuildProgress>) -> bool {
     let (mut rx, initial) = {
         let store = build_tasks().lock();
-        let Some(entry) = store.get(&path) else {
-            return Ok(api_error!("vector_db.progress_unavailable"));
+        let Some(entry) = store.get(path) else {
+            return false;
         };
         (entry.state.subscribe(), entry.state.snapshot())
     };
 
-    if !send_progress(&channel, initial) {
-        return Ok(api_success!(EmptyData::default()));
+    if !send_progress(channel, initial) {
+        return true;
     }
 
     loop {
         match rx.recv().await {
             Ok(p) => {
-                if !send_progress(&channel, p.clone()) {
-                    break;
-                }
-                if p.is_done {
+                let done = p.is_done;
+                if !send_progress(channel, p) || done {
                     break;
                 }
             }
@@ -302,7 +644,25 @@ pub async fn vector_build_index_subscribe(
             Err(broadcast::error::RecvError::Lagged(_)) => continue,
         }
     }
+    true
+}
 
+/// 调整进行中构建的 embedding token 上限；None 表示不再限制。达到上限暂停的构建随之继续
+#[tauri::command]
+pub async fn vector_build_index_set_spend_cap(
+    path: String,
+    max_tokens: Option<u64>,
+) -> TauriApiResult<EmptyData> {
+    let store = build_tasks().lock();
+    let Some(entry) = store.get(&path) else {
+        return Ok(api_error!("vector_db.progress_unavailable"));
+    };
+    let spent = entry.state.snapshot().embedded_tokens;
+    if max_tokens.is_some_and(|cap| cap <= spent) {
+        return Ok(api_error!("vector_db.invalid_spend_cap"));
+    }
+    entry.state.update(|p| p.embedding_token_cap = max_tokens);
+    entry.state.cap_changed.notify_one();
     Ok(api_success!(EmptyData::default()))
 }
 
@@ -322,3 +682,30 @@ pub async fn vector_build_index_cancel(path: String) -> TauriApiResult<EmptyData
     }
     Ok(api_success!(EmptyData::default()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn spend_cap_pauses_until_raised() {
+        let state = Arc::new(BuildState::new("/tmp/project".into(), Some(100)));
+        state.update(|p| p.embedded_tokens = 80);
+        let token = CancellationToken::new();
+
+        assert!(wait_for_spend_cap(20, &token, &state).await);
+
+        let waiter = tokio::spawn({
+            let state = Arc::clone(&state);
+            let token = token.clone();
+            async move { wait_for_spend_cap(30, &token, &state).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(state.snapshot().phase, VectorBuildPhase::SpendCapReached);
+
+        state.update(|p| p.embedding_token_cap = Some(200));
+        state.cap_changed.notify_one();
+        assert!(waiter.await.unwrap());
+        assert!(state.snapshot().error.is_none());
+    }
+}
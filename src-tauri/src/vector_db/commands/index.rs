@@ -1,7 +1,20 @@
+use crate::storage::DatabaseManager;
 use crate::utils::{EmptyData, TauriApiResult};
+use crate::vector_db::chunking::{supported_languages, SupportedLanguage};
 use crate::vector_db::commands::VectorDbState;
+use crate::vector_db::core::VectorDbError;
+use crate::vector_db::search::{
+    BenchmarkQuery, BenchmarkReport, DuplicateReport, SearchOptions, DEFAULT_DUPLICATE_THRESHOLD,
+    MAX_BENCHMARK_QUERIES,
+};
+use crate::vector_db::storage::{
+    estimate_index_build, IndexCollection, IndexEstimate, IndexManager, IndexedFilePage,
+    RenameOutcome,
+};
+use crate::workspace::WorkspaceService;
 use crate::{api_error, api_success};
 use std::path::PathBuf;
+use std::sync::Arc;
 use tauri::State;
 use tracing::{error, warn};
 
@@ -13,17 +26,10 @@ pub async fn get_index_status(
     let workspace_path = PathBuf::from(&path);
 
     if !workspace_path.join(".oxi").exists() {
-        return Ok(api_success!(crate::vector_db::storage::IndexStatus {
-            total_files: 0,
-            total_chunks: 0,
-            embedding_model: String::new(),
-            vector_dimension: 0,
-            size_bytes: 0,
-        }));
+        return Ok(api_success!(state.search_engine.empty_status()));
     }
 
-    let config = state.search_engine.config().clone();
-    match crate::vector_db::storage::IndexManager::new(&workspace_path, config) {
+    match state.search_engine.index_manager(&workspace_path) {
         Ok(manager) => Ok(api_success!(manager.get_status_with_size_bytes())),
         Err(e) => {
             warn!(error = %e, path = %path, "获取索引状态失败");
@@ -32,6 +38,245 @@ pub async fn get_index_status(
     }
 }
 
+const DEFAULT_LIST_LIMIT: usize = 100;
+const MAX_LIST_LIMIT: usize = 1000;
+
+/// 分页列出工作区索引中的文件及其块数量
+#[tauri::command]
+pub async fn vector_index_list_files(
+    path: String,
+    prefix: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    state: State<'_, VectorDbState>,
+) -> TauriApiResult<IndexedFilePage> {
+    let workspace_path = PathBuf::from(&path);
+    let limit = limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    if !IndexManager::exists(&workspace_path) {
+        return Ok(api_success!(IndexedFilePage {
+            total: 0,
+            offset,
+            files: Vec::new(),
+        }));
+    }
+
+    let manager = match state.search_engine.index_manager(&workspace_path) {
+        Ok(manager) => manager,
+        Err(e) => {
+            warn!(error = %e, path = %path, "读取索引失败");
+            return Ok(api_error!("vector_db.status_failed"));
+        }
+    };
+
+    let prefix = prefix
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+    let page = tokio::task::spawn_blocking(move || {
+        manager.list_indexed_files(prefix.as_deref(), limit, offset)
+    })
+    .await;
+
+    match page {
+        Ok(page) => Ok(api_success!(page)),
+        Err(e) => {
+            error!("列出索引文件任务 join 失败: {}", e);
+            Ok(api_error!("vector_db.status_failed"))
+        }
+    }
+}
+
+/// 检测索引中的重复/近似重复块并按簇返回；dedup 为 true 时删除每组相同哈希中多余的块。
+/// 不带 dedup 调用即可先查看 removable_chunks 再决定是否删除
+#[tauri::command]
+pub async fn vector_index_find_duplicates(
+    path: String,
+    threshold: Option<f32>,
+    dedup: Option<bool>,
+    state: State<'_, VectorDbState>,
+) -> TauriApiResult<DuplicateReport> {
+    let threshold = threshold.unwrap_or(DEFAULT_DUPLICATE_THRESHOLD);
+    if !(threshold > 0.0 && threshold <= 1.0) {
+        return Ok(api_error!("vector_db.invalid_duplicate_threshold"));
+    }
+
+    match state
+        .search_engine
+        .find_duplicates(&PathBuf::from(&path), threshold, dedup.unwrap_or(false))
+        .await
+    {
+        Ok(report) => Ok(api_success!(report)),
+        Err(VectorDbError::IndexNotFound(_)) => Ok(api_error!("vector_db.index_missing")),
+        Err(VectorDbError::EmbeddingModelMismatch { .. }) => {
+            Ok(api_error!("vector_db.embedding_model_mismatch"))
+        }
+        Err(VectorDbError::EmbeddingModelRemoved(_)) => {
+            Ok(api_error!("vector_db.embedding_model_removed"))
+        }
+        Err(e) => {
+            warn!(error = %e, path = %path, "检测重复块失败");
+            Ok(api_error!("vector_db.find_duplicates_failed"))
+        }
+    }
+}
+
+/// 文件重命名后同步索引；相对路径以工作区根目录为基准。内容未变时不重新 embedding
+#[tauri::command]
+pub async fn vector_index_file_renamed(
+    path: String,
+    old_path: String,
+    new_path: String,
+    state: State<'_, VectorDbState>,
+) -> TauriApiResult<RenameOutcome> {
+    let root = PathBuf::from(&path);
+    let resolve = |p: &str| {
+        let p = PathBuf::from(p);
+        if p.is_absolute() {
+            p
+        } else {
+            root.join(p)
+        }
+    };
+    let (old_file, new_file) = (resolve(&old_path), resolve(&new_path));
+    if !new_file.is_file() {
+        return Ok(api_error!("vector_db.invalid_path"));
+    }
+    let writes = crate::vector_db::agent_writes();
+    if writes.is_suppressed(&old_file) || writes.is_suppressed(&new_file) {
+        return Ok(api_success!(RenameOutcome::Suppressed));
+    }
+
+    match state
+        .search_engine
+        .handle_file_renamed(&root, &old_file, &new_file)
+        .await
+    {
+        Ok(outcome) => Ok(api_success!(outcome)),
+        Err(VectorDbError::IndexNotFound(_)) => Ok(api_error!("vector_db.index_missing")),
+        Err(VectorDbError::EmbeddingModelMismatch { .. }) => {
+            Ok(api_error!("vector_db.embedding_model_mismatch"))
+        }
+        Err(VectorDbError::EmbeddingModelRemoved(_)) => {
+            Ok(api_error!("vector_db.embedding_model_removed"))
+        }
+        Err(e) => {
+            warn!(error = %e, old = %old_path, new = %new_path, "更新重命名文件的索引失败");
+            Ok(api_error!("vector_db.rename_update_failed"))
+        }
+    }
+}
+
+/// 用一组样例查询测量搜索延迟分位数与 recall@k，便于比较不同索引配置。只读，
+/// 查询数量不超过 MAX_BENCHMARK_QUERIES
+#[tauri::command]
+pub async fn vector_index_benchmark(
+    path: String,
+    queries: Vec<BenchmarkQuery>,
+    options: Option<SearchOptions>,
+    state: State<'_, VectorDbState>,
+) -> TauriApiResult<BenchmarkReport> {
+    let queries: Vec<_> = queries
+        .into_iter()
+        .filter(|q| !q.query.trim().is_empty())
+        .collect();
+    if queries.is_empty() || queries.len() > MAX_BENCHMARK_QUERIES {
+        return Ok(api_error!("vector_db.invalid_benchmark_queries"));
+    }
+
+    match state
+        .search_engine
+        .benchmark(&PathBuf::from(&path), queries, options.unwrap_or_default())
+        .await
+    {
+        Ok(report) => Ok(api_success!(report)),
+        Err(VectorDbError::IndexNotFound(_)) => Ok(api_error!("vector_db.index_missing")),
+        Err(VectorDbError::EmbeddingModelMismatch { .. }) => {
+            Ok(api_error!("vector_db.embedding_model_mismatch"))
+        }
+        Err(VectorDbError::EmbeddingModelRemoved(_)) => {
+            Ok(api_error!("vector_db.embedding_model_removed"))
+        }
+        Err(e) => {
+            warn!(error = %e, path = %path, "搜索基准测试失败");
+            Ok(api_error!("vector_db.benchmark_failed"))
+        }
+    }
+}
+
+/// 在构建前估算索引的块数、token 数、费用和耗时（不调用 embedding）
+#[tauri::command]
+pub async fn vector_index_estimate(
+    workspace_path: String,
+    state: State<'_, VectorDbState>,
+) -> TauriApiResult<IndexEstimate> {
+    let root = PathBuf::from(&workspace_path);
+    if !root.is_dir() {
+        return Ok(api_error!("vector_db.invalid_path"));
+    }
+
+    let config = state.search_engine.config();
+    match tokio::task::spawn_blocking(move || estimate_index_build(&root, &config)).await {
+        Ok(Ok(estimate)) => Ok(api_success!(estimate)),
+        Ok(Err(e)) => {
+            warn!(error = %e, path = %workspace_path, "估算索引成本失败");
+            Ok(api_error!("vector_db.estimate_failed"))
+        }
+        Err(e) => {
+            error!("估算索引成本任务 join 失败: {}", e);
+            Ok(api_error!("vector_db.estimate_failed"))
+        }
+    }
+}
+
+const MAX_LISTED_WORKSPACES: i64 = 50;
+
+/// 列出最近工作区与当前工作区的索引，标注各自使用的 embedding 模型及是否与当前模型一致
+#[tauri::command]
+pub async fn vector_index_list_collections(
+    state: State<'_, VectorDbState>,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<Vec<IndexCollection>> {
+    let service = WorkspaceService::new(Arc::clone(&database));
+    let workspaces = match service.list_recent_workspaces(MAX_LISTED_WORKSPACES).await {
+        Ok(workspaces) => workspaces,
+        Err(e) => {
+            warn!(error = %e, "读取最近工作区失败");
+            return Ok(api_error!("vector_db.list_collections_failed"));
+        }
+    };
+
+    let mut roots: Vec<PathBuf> = state.search_engine.active_workspace().into_iter().collect();
+    for workspace in workspaces {
+        let root = PathBuf::from(workspace.path);
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+
+    let config = state.search_engine.config();
+    let task = tokio::task::spawn_blocking(move || {
+        roots
+            .iter()
+            .filter_map(|root| match IndexManager::read_collection(root, &config) {
+                Ok(collection) => collection,
+                Err(e) => {
+                    warn!(error = %e, path = %root.display(), "读取索引清单失败");
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    });
+    match task.await {
+        Ok(collections) => Ok(api_success!(collections)),
+        Err(e) => {
+            error!("列出索引任务 join 失败: {}", e);
+            Ok(api_error!("vector_db.list_collections_failed"))
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn delete_workspace_index(
     path: String,
@@ -58,3 +303,9 @@ pub async fn delete_workspace_index(
     }
     Ok(api_success!(EmptyData::default()))
 }
+
+/// 列出可索引的语言、对应扩展名，以及是否注册语法、是否提取符号
+#[tauri::command]
+pub async fn vector_index_supported_languages() -> TauriApiResult<Vec<SupportedLanguage>> {
+    Ok(api_success!(supported_languages()))
+}
@@ -1,9 +1,11 @@
 pub mod build;
 pub mod index;
+pub mod model;
 pub mod search;
 
 pub use build::*;
 pub use index::*;
+pub use model::*;
 pub use search::*;
 
 use crate::vector_db::SemanticSearchEngine;
@@ -0,0 +1,271 @@
+use crate::llm::types::LLMProviderConfig;
+use crate::storage::repositories::{AIModelConfig, AIModels, AppPreferences, ModelType};
+use crate::storage::DatabaseManager;
+use crate::utils::TauriApiResult;
+use crate::vector_db::commands::VectorDbState;
+use crate::vector_db::core::{
+    RemoteEmbeddingConfig, VectorDbConfig, VectorDbError, MAX_CONCURRENT_EMBED_REQUESTS,
+};
+use crate::vector_db::SemanticSearchEngine;
+use crate::{api_error, api_success};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use tracing::warn;
+
+/// 向量索引最近一次使用的 embedding 模型（JSON），用于在模型被删除后识别并保留已有索引
+pub const EMBEDDING_MODEL_KEY: &str = "vector_db.embedding_model";
+
+/// 向量服务状态变化时推送的事件
+pub const SERVICE_STATUS_EVENT: &str = "vector_db:status";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingModelRecord {
+    pub model: String,
+    pub dimension: usize,
+    /// 选择该模型时接受的旧索引模型，重启后继续生效
+    #[serde(default)]
+    pub accepted_index_models: Vec<String>,
+}
+
+/// 向量服务状态；initialized 为 false 时需要用户重新选择 embedding 模型
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceStatus {
+    pub initialized: bool,
+    pub embedding_model: String,
+    pub vector_dimension: usize,
+    /// 已被删除的 embedding 模型
+    pub removed_model: Option<String>,
+}
+
+impl ServiceStatus {
+    pub fn of(engine: &SemanticSearchEngine) -> Self {
+        let config = engine.config();
+        let removed_model = engine.removed_model();
+        Self {
+            initialized: removed_model.is_none(),
+            embedding_model: config.embedding.model_name,
+            vector_dimension: config.embedding.dimension,
+            removed_model,
+        }
+    }
+}
+
+pub fn emit_service_status<R: Runtime>(app: &AppHandle<R>, engine: &SemanticSearchEngine) {
+    if let Err(e) = app.emit(SERVICE_STATUS_EVENT, ServiceStatus::of(engine)) {
+        warn!("发送向量服务状态失败: {}", e);
+    }
+}
+
+/// 读取记录的 embedding 模型；没有记录或格式不对时返回 None
+pub async fn load_embedding_model_record(
+    database: &DatabaseManager,
+) -> Option<EmbeddingModelRecord> {
+    let raw = AppPreferences::new(database)
+        .get(EMBEDDING_MODEL_KEY)
+        .await
+        .ok()
+        .flatten()?;
+    serde_json::from_str(&raw).ok()
+}
+
+pub async fn save_embedding_model_record(database: &DatabaseManager, config: &VectorDbConfig) {
+    let record = EmbeddingModelRecord {
+        model: config.embedding.model_name.clone(),
+        dimension: config.embedding.dimension,
+        accepted_index_models: config.embedding.accepted_index_models.clone(),
+    };
+    let Ok(raw) = serde_json::to_string(&record) else {
+        return;
+    };
+    if let Err(e) = AppPreferences::new(database)
+        .set(EMBEDDING_MODEL_KEY, Some(&raw))
+        .await
+    {
+        warn!("保存 embedding 模型记录失败: {}", e);
+    }
+}
+
+/// 由 embedding 模型配置生成向量数据库配置；维度未配置时默认 1024
+pub fn config_from_model(model: AIModelConfig) -> VectorDbConfig {
+    let option = |key: &str| {
+        model
+            .options
+            .as_ref()
+            .and_then(|opts| opts.get(key))
+            .cloned()
+    };
+    let dimension = option("dimension")
+        .and_then(|v| v.as_u64())
+        .map(|v| v as usize)
+        .unwrap_or(1024);
+
+    VectorDbConfig {
+        embedding: RemoteEmbeddingConfig {
+            provider_config: LLMProviderConfig {
+                provider_type: model.provider.as_str().to_string(),
+                api_key: model.api_key.clone(),
+                api_url: Some(model.api_url.clone()),
+                options: model
+                    .options
+                    .as_ref()
+                    .and_then(|v| v.as_object())
+                    .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect()),
+            },
+            model_name: model.model.clone(),
+            dimension,
+            chunk_size: 512,
+            chunk_overlap: 100,
+            language_chunk_sizes: Default::default(),
+            price_per_million_tokens: option("pricePerMillionTokens").and_then(|v| v.as_f64()),
+            requests_per_minute: option("requestsPerMinute")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32),
+            max_concurrent_requests: option("maxConcurrentEmbedRequests")
+                .and_then(|v| v.as_u64())
+                .map(|v| (v as usize).clamp(1, MAX_CONCURRENT_EMBED_REQUESTS))
+                .unwrap_or_else(|| RemoteEmbeddingConfig::default().max_concurrent_requests),
+            accepted_index_models: Vec::new(),
+        },
+        include_git_metadata: option("includeGitMetadata")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        score_normalization: option("scoreNormalization")
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default(),
+        score_offset: option("scoreOffset")
+            .and_then(|v| v.as_f64())
+            .map(|v| v as f32)
+            .unwrap_or(0.0),
+        chunk_filter: option("chunkFilter")
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default(),
+        parsing: option("parsing")
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default(),
+        embedding_outage: option("embeddingOutage")
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default(),
+        max_embedding_tokens_per_build: option("maxEmbeddingTokensPerBuild")
+            .and_then(|v| v.as_u64())
+            .filter(|v| *v > 0),
+        ..VectorDbConfig::default()
+    }
+}
+
+/// AI 模型被删除后检查向量索引使用的 embedding 模型是否仍然存在；
+/// 不存在时暂停向量服务并通知前端重新选择模型
+pub async fn on_ai_model_removed<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<VectorDbState>() else {
+        return;
+    };
+    let engine = &state.search_engine;
+    let active_model = engine.config().embedding.model_name;
+    if active_model.is_empty() || engine.removed_model().is_some() {
+        return;
+    }
+
+    let database = app.state::<Arc<DatabaseManager>>();
+    let models = match AIModels::new(&database).find_all().await {
+        Ok(models) => models,
+        Err(e) => {
+            warn!("读取 AI 模型失败: {}", e);
+            return;
+        }
+    };
+    let still_configured = models
+        .iter()
+        .any(|m| m.model_type == ModelType::Embedding && m.model == active_model);
+    if !still_configured {
+        engine.mark_embedding_model_removed(active_model);
+        emit_service_status(app, engine);
+    }
+}
+
+/// 获取向量服务状态
+#[tauri::command]
+pub async fn vector_db_get_status(
+    state: State<'_, VectorDbState>,
+) -> TauriApiResult<ServiceStatus> {
+    Ok(api_success!(ServiceStatus::of(&state.search_engine)))
+}
+
+/// 选择 embedding 模型替换已被删除的模型。
+///
+/// 新模型的维度必须与原模型一致，并通过一次测试 embedding 确认实际返回的维度，
+/// 校验通过后才恢复服务；已有索引原样保留，模型名一致时无需重建
+#[tauri::command]
+pub async fn vector_db_select_embedding_model<R: Runtime>(
+    model_id: String,
+    app: AppHandle<R>,
+    state: State<'_, VectorDbState>,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<ServiceStatus> {
+    let model = match AIModels::new(&database).find_by_id(&model_id).await {
+        Ok(Some(model)) if model.model_type == ModelType::Embedding => model,
+        Ok(_) => return Ok(api_error!("vector_db.embedding_model_not_found")),
+        Err(e) => {
+            warn!(error = %e, model_id = %model_id, "读取 embedding 模型失败");
+            return Ok(api_error!("vector_db.embedding_model_not_found"));
+        }
+    };
+
+    let config = config_from_model(model);
+    if let Err(e) = config.validate() {
+        warn!(error = %e, model_id = %model_id, "embedding 模型配置无效");
+        return Ok(api_error!("vector_db.embedding_model_invalid"));
+    }
+
+    let engine = &state.search_engine;
+    let expected = engine.config().embedding.dimension;
+    if expected != 0 && config.embedding.dimension != expected {
+        return Ok(api_error!(
+            "vector_db.embedding_dimension_incompatible",
+            "expected" => expected.to_string(),
+            "actual" => config.embedding.dimension.to_string()
+        ));
+    }
+
+    let embedder = match crate::vector_db::embedding::create_embedder(&config.embedding) {
+        Ok(embedder) => embedder,
+        Err(e) => {
+            warn!(error = %e, model_id = %model_id, "创建 embedder 失败");
+            return Ok(api_error!("vector_db.embedding_model_invalid"));
+        }
+    };
+    // 配置的维度可能与模型实际输出不符，探测一次再切换
+    match embedder.embed(&["ping"]).await {
+        Ok(vectors) if vectors.first().map(Vec::len) == Some(config.embedding.dimension) => {}
+        Ok(vectors) => {
+            let actual = vectors.first().map(Vec::len).unwrap_or(0);
+            return Ok(api_error!(
+                "vector_db.embedding_dimension_incompatible",
+                "expected" => config.embedding.dimension.to_string(),
+                "actual" => actual.to_string()
+            ));
+        }
+        Err(e) => {
+            warn!(error = %e, model_id = %model_id, "embedding 模型探测失败");
+            return Ok(api_error!("vector_db.embedding_probe_failed"));
+        }
+    }
+
+    match engine.replace_embedding_model(embedder, config.clone()) {
+        Ok(()) => {}
+        Err(VectorDbError::InvalidDimension { expected, actual }) => {
+            return Ok(api_error!(
+                "vector_db.embedding_dimension_incompatible",
+                "expected" => expected.to_string(),
+                "actual" => actual.to_string()
+            ));
+        }
+        Err(e) => {
+            warn!(error = %e, model_id = %model_id, "切换 embedding 模型失败");
+            return Ok(api_error!("vector_db.embedding_model_invalid"));
+        }
+    }
+
+    save_embedding_model_record(&database, &config).await;
+    emit_service_status(&app, engine);
+    Ok(api_success!(ServiceStatus::of(engine)))
+}
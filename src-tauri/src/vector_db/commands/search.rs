@@ -1,23 +1,47 @@
-use crate::utils::TauriApiResult;
-use crate::vector_db::commands::VectorDbState;
-use crate::vector_db::core::SearchResult;
-use crate::vector_db::search::SearchOptions;
+use crate::storage::DatabaseManager;
+use crate::utils::{ApiResponse, TauriApiResult};
+use crate::vector_db::commands::{emit_service_status, VectorDbState};
+use crate::vector_db::core::{SearchResult, VectorDbError};
+use crate::vector_db::search::jsonl::to_jsonl;
+use crate::vector_db::search::{GlobalSearchResults, SearchOptions};
+use crate::workspace::WorkspaceService;
 use crate::{api_error, api_success};
 use std::path::PathBuf;
-use tauri::State;
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime, State};
 use tracing::warn;
 
+/// 索引与当前模型维度不同时给出两边的维度，否则提示模型不一致
+fn model_mismatch_error<T>(error: &VectorDbError) -> ApiResponse<T> {
+    match error {
+        VectorDbError::EmbeddingModelMismatch {
+            indexed_dimension,
+            active_dimension,
+            ..
+        } if indexed_dimension != active_dimension => api_error!(
+            "vector_db.dimension_mismatch",
+            "indexed" => indexed_dimension,
+            "active" => active_dimension
+        ),
+        _ => api_error!("vector_db.embedding_model_mismatch"),
+    }
+}
+
 /// 语义搜索命令
 #[tauri::command]
-pub async fn semantic_search(
+pub async fn semantic_search<R: Runtime>(
     query: String,
     path: String,
     options: Option<SearchOptions>,
+    app: AppHandle<R>,
     state: State<'_, VectorDbState>,
 ) -> TauriApiResult<Vec<SearchResult>> {
     let workspace_path = PathBuf::from(&path);
     let default_options = SearchOptions::default();
     let search_options = options.unwrap_or(default_options);
+    if search_options.validate().is_err() {
+        return Ok(api_error!("vector_db.invalid_symbol_filter"));
+    }
 
     match state
         .search_engine
@@ -25,8 +49,103 @@ pub async fn semantic_search(
         .await
     {
         Ok(results) => Ok(api_success!(results)),
+        Err(VectorDbError::IndexNotFound(_)) => Ok(api_error!("vector_db.index_missing")),
+        Err(e @ VectorDbError::EmbeddingModelMismatch { .. }) => Ok(model_mismatch_error(&e)),
+        Err(VectorDbError::EmbeddingModelRemoved(_)) => {
+            emit_service_status(&app, &state.search_engine);
+            Ok(api_error!("vector_db.embedding_model_removed"))
+        }
+        Err(e) => {
+            warn!(error = %e, path = %path, "语义搜索失败");
+            Ok(api_error!("vector_db.search_failed"))
+        }
+    }
+}
+
+/// 语义搜索并以 JSON Lines 返回（每行一个结果，schema 见 `search::jsonl`），供脚本消费
+#[tauri::command]
+pub async fn semantic_search_jsonl(
+    query: String,
+    path: String,
+    options: Option<SearchOptions>,
+    state: State<'_, VectorDbState>,
+) -> TauriApiResult<String> {
+    let workspace_path = PathBuf::from(&path);
+    let search_options = options.unwrap_or_default();
+    if search_options.validate().is_err() {
+        return Ok(api_error!("vector_db.invalid_symbol_filter"));
+    }
+    let include_snippet = search_options.include_snippet;
+
+    let results = match state
+        .search_engine
+        .search_in_workspace(&workspace_path, &query, search_options)
+        .await
+    {
+        Ok(results) => results,
+        Err(VectorDbError::IndexNotFound(_)) => return Ok(api_error!("vector_db.index_missing")),
+        Err(e @ VectorDbError::EmbeddingModelMismatch { .. }) => {
+            return Ok(model_mismatch_error(&e));
+        }
+        Err(VectorDbError::EmbeddingModelRemoved(_)) => {
+            return Ok(api_error!("vector_db.embedding_model_removed"));
+        }
         Err(e) => {
             warn!(error = %e, path = %path, "语义搜索失败");
+            return Ok(api_error!("vector_db.search_failed"));
+        }
+    };
+
+    match to_jsonl(&results, &workspace_path, include_snippet) {
+        Ok(jsonl) => Ok(api_success!(jsonl)),
+        Err(e) => {
+            warn!(error = %e, "搜索结果导出失败");
+            Ok(api_error!("vector_db.search_failed"))
+        }
+    }
+}
+
+const MAX_GLOBAL_WORKSPACES: i64 = 50;
+
+/// 在所有已知工作区（当前工作区与最近工作区）的索引中搜索，结果按分数合并并标注所属工作区
+#[tauri::command]
+pub async fn semantic_search_global<R: Runtime>(
+    query: String,
+    limit: Option<usize>,
+    app: AppHandle<R>,
+    state: State<'_, VectorDbState>,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<GlobalSearchResults> {
+    let service = WorkspaceService::new(Arc::clone(&database));
+    let workspaces = match service.list_recent_workspaces(MAX_GLOBAL_WORKSPACES).await {
+        Ok(workspaces) => workspaces,
+        Err(e) => {
+            warn!(error = %e, "读取最近工作区失败");
+            return Ok(api_error!("vector_db.search_failed"));
+        }
+    };
+
+    let mut roots: Vec<PathBuf> = state.search_engine.active_workspace().into_iter().collect();
+    for workspace in workspaces {
+        let root = PathBuf::from(workspace.path);
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+
+    let limit = limit.unwrap_or(SearchOptions::default().top_k);
+    match state
+        .search_engine
+        .search_global(&roots, &query, limit)
+        .await
+    {
+        Ok(results) => Ok(api_success!(results)),
+        Err(VectorDbError::EmbeddingModelRemoved(_)) => {
+            emit_service_status(&app, &state.search_engine);
+            Ok(api_error!("vector_db.embedding_model_removed"))
+        }
+        Err(e) => {
+            warn!(error = %e, "全局语义搜索失败");
             Ok(api_error!("vector_db.search_failed"))
         }
     }
@@ -1,7 +1,159 @@
 use crate::llm::types::LLMProviderConfig;
+use crate::vector_db::core::{Chunk, ChunkType, Language};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
+/// 单个 chunk 允许的最大 token 数上限，超过多数 embedding 模型的输入窗口
+const MAX_CHUNK_TOKENS_LIMIT: usize = 32_768;
+
+/// 分块大小范围 (token 数量)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkSizeRange {
+    /// 小于该值且被外层块完整包含的语法块不再单独成块
+    #[serde(default)]
+    pub min_tokens: usize,
+    /// 超过该值的块会被 striding 拆分
+    pub max_tokens: usize,
+}
+
+/// 实际生效的分块大小，供诊断展示
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveChunkSizes {
+    /// 未覆盖语言使用的全局范围
+    pub default: ChunkSizeRange,
+    /// 按语言覆盖的范围
+    pub languages: HashMap<Language, ChunkSizeRange>,
+}
+
+/// 建索引前过滤低价值块（单行导入、只有一个字段的结构体等）；默认全部索引
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChunkFilterConfig {
+    /// 去掉首尾空白后少于该字符数的块不建索引，0 表示不限制
+    pub min_indexable_chars: usize,
+    /// 不建索引的块类型
+    pub skip_chunk_types: Vec<ChunkType>,
+}
+
+/// 块被过滤的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkFilterReason {
+    TooShort,
+    SkippedType,
+}
+
+impl ChunkFilterConfig {
+    pub fn is_noop(&self) -> bool {
+        self.min_indexable_chars == 0 && self.skip_chunk_types.is_empty()
+    }
+
+    /// 块应被过滤时返回原因
+    pub fn reject_reason(&self, chunk: &Chunk) -> Option<ChunkFilterReason> {
+        if self.skip_chunk_types.contains(&chunk.chunk_type) {
+            return Some(ChunkFilterReason::SkippedType);
+        }
+        if self.min_indexable_chars > 0
+            && chunk.content.trim().chars().count() < self.min_indexable_chars
+        {
+            return Some(ChunkFilterReason::TooShort);
+        }
+        None
+    }
+}
+
+/// embedding 批次失败时的重试策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbedRetryConfig {
+    /// 每个批次最多尝试的次数（含首次）
+    pub max_attempts: u32,
+    /// 首次重试前的等待时间，之后每次翻倍
+    pub base_delay_ms: u64,
+}
+
+impl Default for EmbedRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+        }
+    }
+}
+
+/// 建索引时 embedding 服务中断的处理：连续多个文件因服务不可用失败时暂停构建，
+/// 按指数退避等待并用一次测试 embedding 探测，恢复后重试这些文件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbeddingOutageConfig {
+    /// 连续失败多少个文件视为服务中断
+    pub consecutive_failures: usize,
+    /// 首次探测前的等待时间，之后每次翻倍
+    pub initial_backoff_ms: u64,
+    /// 单次等待的上限
+    pub max_backoff_ms: u64,
+    /// 一次构建中等待服务恢复的总时长上限，超过后构建失败
+    pub max_total_wait_secs: u64,
+}
+
+impl Default for EmbeddingOutageConfig {
+    fn default() -> Self {
+        Self {
+            consecutive_failures: 5,
+            initial_backoff_ms: 5_000,
+            max_backoff_ms: 120_000,
+            max_total_wait_secs: 900,
+        }
+    }
+}
+
+impl EmbeddingOutageConfig {
+    /// 第 attempt 次（从 0 开始）探测前的等待时间
+    pub fn backoff(&self, attempt: u32) -> std::time::Duration {
+        let delay = self
+            .initial_backoff_ms
+            .saturating_mul(1u64 << attempt.min(16))
+            .min(self.max_backoff_ms);
+        std::time::Duration::from_millis(delay)
+    }
+}
+
+/// 建索引时读取与分块的并发控制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParseConcurrencyConfig {
+    /// 同时在阻塞线程池中读取、分块的文件数
+    pub max_concurrent_files: usize,
+    /// 已读入内存、等待 embedding 的文件数上限，与分块并发独立控制内存占用
+    pub max_buffered_files: usize,
+}
+
+impl Default for ParseConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_files: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4)
+                .min(8),
+            max_buffered_files: 32,
+        }
+    }
+}
+
+/// 相似度分数归一化方式
+///
+/// 不同 embedding 模型的余弦分数分布区间不同（有的集中在 0.7-0.9，有的在 0.2-0.6），
+/// 固定阈值在切换模型后表现不一致。归一化在召回候选后、按阈值过滤前进行。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreNormalization {
+    /// 使用原始余弦相似度（加上 score_offset）
+    #[default]
+    None,
+    /// 按单次查询的候选集 min-max 缩放到 [0, 1]，最相关的结果为 1
+    MinMax,
+}
+
 /// 远程向量模型配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RemoteEmbeddingConfig {
@@ -19,6 +171,57 @@ pub struct RemoteEmbeddingConfig {
 
     /// 分块重叠 (token 数量)
     pub chunk_overlap: usize,
+
+    /// 按语言覆盖分块大小，未配置的语言使用 chunk_size
+    #[serde(default)]
+    pub language_chunk_sizes: HashMap<Language, ChunkSizeRange>,
+
+    /// 每百万 token 的价格（美元），未配置时按内置价格表估算
+    #[serde(default)]
+    pub price_per_million_tokens: Option<f64>,
+
+    /// provider 的每分钟请求数限制，用于估算构建耗时
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+
+    /// 单个文件同时进行的 embedding 请求数；负载较高的服务可能拒绝过多并发请求
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+
+    /// 用户显式选择本模型替换过的模型名；这些模型构建的同维度索引可直接使用
+    #[serde(default)]
+    pub accepted_index_models: Vec<String>,
+}
+
+/// 同时进行的 embedding 请求数上限
+pub const MAX_CONCURRENT_EMBED_REQUESTS: usize = 16;
+
+fn default_max_concurrent_requests() -> usize {
+    2
+}
+
+impl RemoteEmbeddingConfig {
+    /// 全局分块大小范围
+    pub fn default_chunk_size_range(&self) -> ChunkSizeRange {
+        ChunkSizeRange {
+            min_tokens: 0,
+            max_tokens: self.chunk_size,
+        }
+    }
+
+    pub fn effective_chunk_sizes(&self) -> EffectiveChunkSizes {
+        EffectiveChunkSizes {
+            default: self.default_chunk_size_range(),
+            languages: self.language_chunk_sizes.clone(),
+        }
+    }
+
+    /// 指定语言实际生效的分块大小范围
+    pub fn chunk_size_range_for(&self, language: Option<Language>) -> ChunkSizeRange {
+        language
+            .and_then(|l| self.language_chunk_sizes.get(&l).copied())
+            .unwrap_or_else(|| self.default_chunk_size_range())
+    }
 }
 
 impl Default for RemoteEmbeddingConfig {
@@ -34,6 +237,11 @@ impl Default for RemoteEmbeddingConfig {
             dimension: 0,
             chunk_size: 512,
             chunk_overlap: 100,
+            language_chunk_sizes: HashMap::new(),
+            price_per_million_tokens: None,
+            requests_per_minute: None,
+            max_concurrent_requests: default_max_concurrent_requests(),
+            accepted_index_models: Vec::new(),
         }
     }
 }
@@ -50,6 +258,14 @@ pub struct VectorDbConfig {
     /// 相似度阈值
     pub similarity_threshold: f32,
 
+    /// 分数归一化方式
+    #[serde(default)]
+    pub score_normalization: ScoreNormalization,
+
+    /// 与模型相关的分数偏移，归一化前加到原始分数上（结果截断到 [0, 1]）
+    #[serde(default)]
+    pub score_offset: f32,
+
     /// 文件大小限制 (bytes)
     pub max_file_size: u64,
 
@@ -58,6 +274,30 @@ pub struct VectorDbConfig {
 
     /// 关键词搜索权重 (0.0-1.0)
     pub keyword_weight: f32,
+
+    /// embedding 批次重试策略
+    #[serde(default)]
+    pub embed_retry: EmbedRetryConfig,
+
+    /// 索引时通过 git blame 记录每个块最近的提交、作者和时间（大仓库可关闭）
+    #[serde(default)]
+    pub include_git_metadata: bool,
+
+    /// 建索引前的块过滤
+    #[serde(default)]
+    pub chunk_filter: ChunkFilterConfig,
+
+    /// 读取与分块的并发
+    #[serde(default)]
+    pub parsing: ParseConcurrencyConfig,
+
+    /// embedding 服务中断时的暂停与恢复策略
+    #[serde(default)]
+    pub embedding_outage: EmbeddingOutageConfig,
+
+    /// 单次构建最多送入 embedding 的 token 数（估算），达到后暂停等待确认；None 表示不限制
+    #[serde(default)]
+    pub max_embedding_tokens_per_build: Option<u64>,
 }
 
 impl Default for VectorDbConfig {
@@ -66,9 +306,17 @@ impl Default for VectorDbConfig {
             embedding: RemoteEmbeddingConfig::default(),
             max_results: 20,
             similarity_threshold: 0.3,
+            score_normalization: ScoreNormalization::None,
+            score_offset: 0.0,
             max_file_size: 10 * 1024 * 1024,
             semantic_weight: 0.7,
             keyword_weight: 0.3,
+            embed_retry: EmbedRetryConfig::default(),
+            include_git_metadata: false,
+            chunk_filter: ChunkFilterConfig::default(),
+            parsing: ParseConcurrencyConfig::default(),
+            embedding_outage: EmbeddingOutageConfig::default(),
+            max_embedding_tokens_per_build: None,
         }
     }
 }
@@ -114,11 +362,79 @@ impl VectorDbConfig {
                 "Chunk overlap must be < chunk size".to_string(),
             ));
         }
+        for (language, range) in &self.embedding.language_chunk_sizes {
+            if range.max_tokens == 0 || range.max_tokens > MAX_CHUNK_TOKENS_LIMIT {
+                return Err(crate::vector_db::core::VectorDbError::Config(format!(
+                    "Chunk size for {:?} must be in [1, {}]",
+                    language, MAX_CHUNK_TOKENS_LIMIT
+                )));
+            }
+            if range.min_tokens > range.max_tokens {
+                return Err(crate::vector_db::core::VectorDbError::Config(format!(
+                    "Minimum chunk size for {:?} must be <= maximum",
+                    language
+                )));
+            }
+        }
+        if self.embed_retry.max_attempts == 0 || self.embed_retry.max_attempts > 10 {
+            return Err(crate::vector_db::core::VectorDbError::Config(
+                "Embedding retry attempts must be in [1, 10]".to_string(),
+            ));
+        }
+        if !(1..=MAX_CONCURRENT_EMBED_REQUESTS).contains(&self.embedding.max_concurrent_requests) {
+            return Err(crate::vector_db::core::VectorDbError::Config(format!(
+                "Concurrent embedding requests must be in [1, {}]",
+                MAX_CONCURRENT_EMBED_REQUESTS
+            )));
+        }
         if self.similarity_threshold < 0.0 || self.similarity_threshold > 1.0 {
             return Err(crate::vector_db::core::VectorDbError::Config(
                 "Similarity threshold must be in [0, 1]".to_string(),
             ));
         }
+        if !(-1.0..=1.0).contains(&self.score_offset) {
+            return Err(crate::vector_db::core::VectorDbError::Config(
+                "Score offset must be in [-1, 1]".to_string(),
+            ));
+        }
+        if self.parsing.max_concurrent_files == 0 || self.parsing.max_buffered_files == 0 {
+            return Err(crate::vector_db::core::VectorDbError::Config(
+                "Parsing concurrency and buffered files must be > 0".to_string(),
+            ));
+        }
+        let outage = &self.embedding_outage;
+        if outage.consecutive_failures == 0
+            || outage.initial_backoff_ms == 0
+            || outage.max_backoff_ms < outage.initial_backoff_ms
+        {
+            return Err(crate::vector_db::core::VectorDbError::Config(
+                "Embedding outage threshold and backoff must be > 0".to_string(),
+            ));
+        }
+        if self.max_embedding_tokens_per_build == Some(0) {
+            return Err(crate::vector_db::core::VectorDbError::Config(
+                "Embedding token cap per build must be > 0".to_string(),
+            ));
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn outage_backoff_doubles_up_to_cap() {
+        let outage = EmbeddingOutageConfig {
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 5_000,
+            ..EmbeddingOutageConfig::default()
+        };
+        assert_eq!(outage.backoff(0), Duration::from_secs(1));
+        assert_eq!(outage.backoff(2), Duration::from_secs(4));
+        assert_eq!(outage.backoff(3), Duration::from_secs(5));
+        assert_eq!(outage.backoff(40), Duration::from_secs(5));
+    }
+}
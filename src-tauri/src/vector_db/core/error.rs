@@ -20,6 +20,10 @@ pub enum VectorDbError {
     #[error("Embedding error: {0}")]
     Embedding(String),
 
+    /// 网络超时、限流、服务端 5xx 等可重试的 embedding 失败
+    #[error("Embedding service unavailable: {0}")]
+    EmbeddingUnavailable(String),
+
     #[error("Search error: {0}")]
     Search(String),
 
@@ -37,6 +41,29 @@ pub enum VectorDbError {
 
     #[error("Chunking error: {0}")]
     ChunkingError(String),
+
+    #[error("Index not found: {0}")]
+    IndexNotFound(String),
+
+    /// 索引由其他 embedding 模型构建，需要显式重建
+    #[error("Index was built with {indexed_model} ({indexed_dimension}d) but the active embedding model is {active_model} ({active_dimension}d)")]
+    EmbeddingModelMismatch {
+        indexed_model: String,
+        indexed_dimension: usize,
+        active_model: String,
+        active_dimension: usize,
+    },
+
+    /// 索引配置的 embedding 模型已被删除，需要用户重新选择模型
+    #[error("Embedding model {0} has been removed; select a replacement model")]
+    EmbeddingModelRemoved(String),
+}
+
+impl VectorDbError {
+    /// 是否值得重试；维度不匹配、鉴权失败等错误重试也不会成功
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::EmbeddingUnavailable(_))
+    }
 }
 
 pub type Result<T> = std::result::Result<T, VectorDbError>;
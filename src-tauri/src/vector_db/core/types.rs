@@ -1,5 +1,6 @@
+use crate::vector_db::utils::GitChunkMetadata;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// 文件语言类型
@@ -21,24 +22,68 @@ pub enum Language {
 }
 
 impl Language {
+    /// 所有已分类的语言
+    pub const ALL: [Language; 13] = [
+        Language::Rust,
+        Language::TypeScript,
+        Language::JavaScript,
+        Language::Python,
+        Language::Go,
+        Language::Java,
+        Language::C,
+        Language::Cpp,
+        Language::CSharp,
+        Language::Ruby,
+        Language::Php,
+        Language::Swift,
+        Language::Kotlin,
+    ];
+
+    /// 映射到该语言的文件扩展名（小写）
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["rs"],
+            Language::TypeScript => &["ts", "tsx"],
+            Language::JavaScript => &["js", "jsx"],
+            Language::Python => &["py"],
+            Language::Go => &["go"],
+            Language::Java => &["java"],
+            Language::C => &["c"],
+            // .h 在 C 与 C++ 之间有歧义，按 C++ 解析；C++ 语法能覆盖常见的 C 头文件
+            Language::Cpp => &["cpp", "cc", "cxx", "c++", "h", "hpp", "hh", "hxx"],
+            Language::CSharp => &["cs"],
+            Language::Ruby => &["rb"],
+            Language::Php => &["php", "phtml", "php3", "php4", "php5", "phps", "phar"],
+            Language::Swift => &["swift"],
+            Language::Kotlin => &["kt", "kts"],
+        }
+    }
+
+    /// 展示给用户的语言名
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::TypeScript => "TypeScript",
+            Language::JavaScript => "JavaScript",
+            Language::Python => "Python",
+            Language::Go => "Go",
+            Language::Java => "Java",
+            Language::C => "C",
+            Language::Cpp => "C++",
+            Language::CSharp => "C#",
+            Language::Ruby => "Ruby",
+            Language::Php => "PHP",
+            Language::Swift => "Swift",
+            Language::Kotlin => "Kotlin",
+        }
+    }
+
     /// 从文件扩展名推断语言
     pub fn from_extension(ext: &str) -> Option<Self> {
-        match ext.to_lowercase().as_str() {
-            "rs" => Some(Language::Rust),
-            "ts" | "tsx" => Some(Language::TypeScript),
-            "js" | "jsx" => Some(Language::JavaScript),
-            "py" => Some(Language::Python),
-            "go" => Some(Language::Go),
-            "java" => Some(Language::Java),
-            "c" => Some(Language::C),
-            "cpp" | "cc" | "cxx" | "c++" => Some(Language::Cpp),
-            "cs" => Some(Language::CSharp),
-            "rb" => Some(Language::Ruby),
-            "php" | "phtml" | "php3" | "php4" | "php5" | "phps" | "phar" => Some(Language::Php),
-            "swift" => Some(Language::Swift),
-            "kt" | "kts" => Some(Language::Kotlin),
-            _ => None,
-        }
+        let ext = ext.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|language| language.extensions().contains(&ext.as_str()))
     }
 
     /// 从文件路径推断语言
@@ -125,7 +170,7 @@ impl FileMetadata {
 pub type ChunkId = Uuid;
 
 /// 块类型
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ChunkType {
     Function,
     Class,
@@ -168,6 +213,8 @@ pub struct StrideInfo {
 pub struct ChunkConfig {
     /// 每个 chunk 的最大 token 数
     pub max_tokens: usize,
+    /// 嵌套语法块单独成块所需的最小 token 数
+    pub min_tokens: usize,
     /// stride 重叠的 token 数
     pub stride_overlap: usize,
     /// 是否启用 striding（大 chunk 拆分）
@@ -179,6 +226,7 @@ impl Default for ChunkConfig {
         Self {
             max_tokens: 8192,     // 默认使用大模型限制
             stride_overlap: 1024, // 12.5% 重叠
+            min_tokens: 0,
             enable_striding: true,
         }
     }
@@ -191,6 +239,7 @@ impl ChunkConfig {
             crate::vector_db::chunking::TokenEstimator::get_model_chunk_config(model_name);
         Self {
             max_tokens,
+            min_tokens: 0,
             stride_overlap,
             enable_striding: true,
         }
@@ -207,6 +256,9 @@ pub struct Chunk {
     pub chunk_type: ChunkType,
     /// Stride 信息（如果这个 chunk 是从大 chunk 拆分出来的）
     pub stride_info: Option<StrideInfo>,
+    /// 所属符号名（函数/类/方法等），仅 tree-sitter 分块时可用
+    #[serde(default)]
+    pub symbol: Option<String>,
 }
 
 impl Chunk {
@@ -219,6 +271,7 @@ impl Chunk {
             content,
             chunk_type,
             stride_info: None,
+            symbol: None,
         }
     }
 
@@ -237,8 +290,15 @@ impl Chunk {
             content,
             chunk_type,
             stride_info: Some(stride_info),
+            symbol: None,
         }
     }
+
+    /// 设置所属符号名
+    pub fn with_symbol(mut self, symbol: Option<String>) -> Self {
+        self.symbol = symbol;
+        self
+    }
 }
 
 /// 搜索结果
@@ -247,9 +307,24 @@ pub struct SearchResult {
     pub file_path: PathBuf,
     pub span: Span,
     pub score: f32,
+    /// 按块类型加权前的分数，仅设置了 chunk_type_weights 时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_score: Option<f32>,
     pub preview: String,
     pub language: Option<Language>,
     pub chunk_type: Option<ChunkType>,
+    /// 所属符号名（来自索引元数据）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// 最近一次提交、作者与时间，仅启用 include_git_metadata 时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<GitChunkMetadata>,
+    /// 用于展示的路径：相对工作区根目录（`/` 分隔），仅启用 relative_paths 时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_path: Option<String>,
+    /// 文件不在工作区根目录下（如符号链接的依赖），此时 display_path 为绝对路径
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub outside_workspace: bool,
 }
 
 impl SearchResult {
@@ -266,9 +341,42 @@ impl SearchResult {
             file_path,
             span,
             score,
+            raw_score: None,
             preview,
             language,
             chunk_type,
+            symbol: None,
+            metadata: None,
+            display_path: None,
+            outside_workspace: false,
+        }
+    }
+
+    pub fn with_symbol(mut self, symbol: Option<String>) -> Self {
+        self.symbol = symbol;
+        self
+    }
+
+    pub fn with_git_metadata(mut self, metadata: Option<GitChunkMetadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// 按工作区根目录计算 display_path；file_path 保持原样，供打开文件使用
+    pub fn relativize(&mut self, workspace_root: &Path) {
+        match self.file_path.strip_prefix(workspace_root) {
+            Ok(relative) => {
+                self.display_path = Some(relative.to_string_lossy().replace('\\', "/"));
+                self.outside_workspace = false;
+            }
+            Err(_) if self.file_path.is_relative() => {
+                self.display_path = Some(self.file_path.to_string_lossy().replace('\\', "/"));
+                self.outside_workspace = false;
+            }
+            Err(_) => {
+                self.display_path = Some(self.file_path.to_string_lossy().into_owned());
+                self.outside_workspace = true;
+            }
         }
     }
 }
@@ -49,6 +49,9 @@ impl Embedder for RemoteEmbedder {
             .create_embeddings(request)
             .await
             .map(|resp| resp.data.into_iter().map(|d| d.embedding).collect())
-            .map_err(|e| VectorDbError::Embedding(e.to_string()))
+            .map_err(|e| match e.embedding_error_kind() {
+                "network" | "rate_limited" => VectorDbError::EmbeddingUnavailable(e.to_string()),
+                _ => VectorDbError::Embedding(e.to_string()),
+            })
     }
 }
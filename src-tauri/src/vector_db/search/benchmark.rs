@@ -0,0 +1,193 @@
+//! 搜索基准测试
+//!
+//! 对一组样例查询逐条执行正常的搜索路径，统计端到端延迟分位数；查询附带期望命中的
+//! 文件时计算 recall@k，用于比较量化、HNSW 参数等配置对延迟与召回的影响。只读操作。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::vector_db::core::SearchResult;
+
+/// 单次基准测试最多接受的查询数量
+pub const MAX_BENCHMARK_QUERIES: usize = 100;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchmarkQuery {
+    pub query: String,
+    /// 期望命中的文件（绝对路径或相对工作区根目录），为空时不参与召回率统计
+    #[serde(default)]
+    pub expected_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencyStats {
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        Self {
+            min_ms: sorted[0],
+            mean_ms: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50_ms: percentile(&sorted, 0.50),
+            p90_ms: percentile(&sorted, 0.90),
+            p99_ms: percentile(&sorted, 0.99),
+            max_ms: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// 最近秩分位数，sorted 需已升序且非空
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryBenchmark {
+    pub query: String,
+    pub latency_ms: f64,
+    pub result_count: usize,
+    /// 前 k 个结果覆盖的期望文件比例；未提供期望文件或查询失败时为 None
+    pub recall: Option<f32>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkReport {
+    pub query_count: usize,
+    pub failed_queries: usize,
+    pub top_k: usize,
+    pub latency: LatencyStats,
+    /// 参与召回率统计的查询数量
+    pub evaluated_queries: usize,
+    /// 参与统计的查询的平均 recall@k
+    pub recall_at_k: Option<f32>,
+    pub queries: Vec<QueryBenchmark>,
+}
+
+impl BenchmarkReport {
+    /// 汇总逐条结果；失败的查询不计入延迟统计
+    pub fn aggregate(top_k: usize, queries: Vec<QueryBenchmark>) -> Self {
+        let latencies: Vec<f64> = queries
+            .iter()
+            .filter(|q| q.error.is_none())
+            .map(|q| q.latency_ms)
+            .collect();
+        let recalls: Vec<f32> = queries.iter().filter_map(|q| q.recall).collect();
+        let recall_at_k =
+            (!recalls.is_empty()).then(|| recalls.iter().sum::<f32>() / recalls.len() as f32);
+
+        Self {
+            query_count: queries.len(),
+            failed_queries: queries.len() - latencies.len(),
+            top_k,
+            latency: LatencyStats::from_samples(&latencies),
+            evaluated_queries: recalls.len(),
+            recall_at_k,
+            queries,
+        }
+    }
+}
+
+/// 结果中命中的期望文件比例；相对路径按工作区根目录解析
+pub fn recall_at_k(
+    workspace_root: &Path,
+    expected_files: &[String],
+    results: &[SearchResult],
+) -> Option<f32> {
+    let resolve = |path: &Path| -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            workspace_root.join(path)
+        }
+    };
+    let expected: HashSet<PathBuf> = expected_files
+        .iter()
+        .map(|f| f.trim())
+        .filter(|f| !f.is_empty())
+        .map(|f| resolve(Path::new(f)))
+        .collect();
+    if expected.is_empty() {
+        return None;
+    }
+
+    let found: HashSet<PathBuf> = results.iter().map(|r| resolve(&r.file_path)).collect();
+    let hits = expected.iter().filter(|f| found.contains(*f)).count();
+    Some(hits as f32 / expected.len() as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_db::core::Span;
+
+    fn result(path: &str) -> SearchResult {
+        SearchResult::new(
+            PathBuf::from(path),
+            Span::new(0, 10, 1, 2),
+            0.9,
+            String::new(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn computes_latency_percentiles() {
+        let samples: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+        let stats = LatencyStats::from_samples(&samples);
+        assert_eq!(stats.min_ms, 1.0);
+        assert_eq!(stats.p50_ms, 50.0);
+        assert_eq!(stats.p90_ms, 90.0);
+        assert_eq!(stats.p99_ms, 99.0);
+        assert_eq!(stats.max_ms, 100.0);
+        assert!((stats.mean_ms - 50.5).abs() < 1e-9);
+        assert_eq!(LatencyStats::from_samples(&[]), LatencyStats::default());
+    }
+
+    #[test]
+    fn recall_resolves_relative_expected_files() {
+        let root = Path::new("/ws");
+        let results = vec![result("/ws/src/a.rs"), result("/ws/src/b.rs")];
+        let expected = vec!["src/a.rs".to_string(), "/ws/src/c.rs".to_string()];
+        assert_eq!(recall_at_k(root, &expected, &results), Some(0.5));
+        assert_eq!(recall_at_k(root, &[], &results), None);
+
+        let report = BenchmarkReport::aggregate(
+            5,
+            vec![
+                QueryBenchmark {
+                    query: "a".into(),
+                    latency_ms: 10.0,
+                    result_count: 2,
+                    recall: Some(0.5),
+                    error: None,
+                },
+                QueryBenchmark {
+                    query: "b".into(),
+                    latency_ms: 0.0,
+                    result_count: 0,
+                    recall: None,
+                    error: Some("boom".into()),
+                },
+            ],
+        );
+        assert_eq!(report.failed_queries, 1);
+        assert_eq!(report.evaluated_queries, 1);
+        assert_eq!(report.recall_at_k, Some(0.5));
+        assert_eq!(report.latency.max_ms, 10.0);
+    }
+}
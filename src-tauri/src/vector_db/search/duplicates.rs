@@ -0,0 +1,270 @@
+//! 重复块检测
+//!
+//! 复制粘贴的样板代码会让搜索结果充满几乎相同的命中。内容哈希相同的块构成精确重复；
+//! 向量相似度超过阈值的块通过 HNSW 近邻查询连边，用并查集合并为近似重复簇，
+//! 避免 O(n²) 的两两比较。
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::vector_db::storage::ChunkMetadata;
+
+/// 默认的近似重复相似度阈值
+pub const DEFAULT_DUPLICATE_THRESHOLD: f32 = 0.97;
+/// 每个块查询的近邻数量
+pub(crate) const NEIGHBORS_PER_CHUNK: usize = 8;
+/// 报告中最多返回的簇数量（按大小降序）
+const MAX_REPORTED_CLUSTERS: usize = 200;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateChunk {
+    pub file_path: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub symbol: Option<String>,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCluster {
+    /// 所有块内容哈希相同
+    pub exact: bool,
+    /// 簇内最高的向量相似度，精确重复为 1
+    pub max_similarity: f32,
+    pub chunks: Vec<DuplicateChunk>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateReport {
+    pub total_chunks: usize,
+    pub threshold: f32,
+    pub total_clusters: usize,
+    /// 属于某个簇的块数量
+    pub duplicate_chunks: usize,
+    /// dedup 会删除的块数量：每组相同哈希只保留一个
+    pub removable_chunks: usize,
+    /// 本次实际删除的块数量（未启用 dedup 时为 0）
+    pub removed_chunks: usize,
+    pub clusters: Vec<DuplicateCluster>,
+}
+
+/// 并查集（路径压缩）
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut cur = x;
+        while self.parent[cur] != root {
+            let next = self.parent[cur];
+            self.parent[cur] = root;
+            cur = next;
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[ra.max(rb)] = ra.min(rb);
+        }
+    }
+}
+
+/// 一个簇：成员为内部下标
+pub(crate) struct ClusterMembers {
+    pub members: Vec<usize>,
+    pub exact: bool,
+    pub max_similarity: f32,
+}
+
+/// 聚类结果
+pub(crate) struct Clusters {
+    pub clusters: Vec<ClusterMembers>,
+    /// 每组相同哈希中除保留项外的块
+    pub removable: Vec<usize>,
+}
+
+/// 按内容哈希与近邻边聚类；`edges` 为 (a, b, similarity)
+pub(crate) fn cluster_duplicates(
+    metas: &[&ChunkMetadata],
+    edges: &[(usize, usize, f32)],
+) -> Clusters {
+    let mut set = DisjointSet::new(metas.len());
+
+    let mut by_hash: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (idx, meta) in metas.iter().enumerate() {
+        if !meta.hash.is_empty() {
+            by_hash.entry(meta.hash.as_str()).or_default().push(idx);
+        }
+    }
+
+    let mut removable = Vec::new();
+    for members in by_hash.values_mut().filter(|m| m.len() > 1) {
+        // 保留路径与行号最靠前的一个
+        members.sort_by(|a, b| {
+            (&metas[*a].file_path, metas[*a].span.line_start)
+                .cmp(&(&metas[*b].file_path, metas[*b].span.line_start))
+        });
+        for other in &members[1..] {
+            set.union(members[0], *other);
+        }
+        removable.extend_from_slice(&members[1..]);
+    }
+
+    for (a, b, _) in edges {
+        set.union(*a, *b);
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for idx in 0..metas.len() {
+        let root = set.find(idx);
+        groups.entry(root).or_default().push(idx);
+    }
+
+    let mut max_similarity: HashMap<usize, f32> = HashMap::new();
+    for (a, _, similarity) in edges {
+        let root = set.find(*a);
+        let entry = max_similarity.entry(root).or_insert(0.0);
+        *entry = entry.max(*similarity);
+    }
+
+    let mut clusters: Vec<ClusterMembers> = groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(root, members)| {
+            let exact = members
+                .iter()
+                .all(|m| metas[*m].hash == metas[members[0]].hash);
+            let max_similarity = if exact {
+                1.0
+            } else {
+                max_similarity.get(&root).copied().unwrap_or(1.0)
+            };
+            ClusterMembers {
+                members,
+                exact,
+                max_similarity,
+            }
+        })
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.members
+            .len()
+            .cmp(&a.members.len())
+            .then(a.members[0].cmp(&b.members[0]))
+    });
+
+    Clusters {
+        clusters,
+        removable,
+    }
+}
+
+/// 生成报告，簇按大小降序截断到 MAX_REPORTED_CLUSTERS；removed_chunks 由调用方在删除后填写
+pub(crate) fn build_report(
+    metas: &[&ChunkMetadata],
+    clusters: &Clusters,
+    threshold: f32,
+) -> DuplicateReport {
+    let to_chunk = |idx: usize| {
+        let meta = metas[idx];
+        DuplicateChunk {
+            file_path: meta.file_path.to_string_lossy().to_string(),
+            line_start: meta.span.line_start,
+            line_end: meta.span.line_end,
+            symbol: meta.symbol.clone(),
+            hash: meta.hash.clone(),
+        }
+    };
+
+    DuplicateReport {
+        total_chunks: metas.len(),
+        threshold,
+        total_clusters: clusters.clusters.len(),
+        duplicate_chunks: clusters.clusters.iter().map(|c| c.members.len()).sum(),
+        removable_chunks: clusters.removable.len(),
+        removed_chunks: 0,
+        clusters: clusters
+            .clusters
+            .iter()
+            .take(MAX_REPORTED_CLUSTERS)
+            .map(|cluster| DuplicateCluster {
+                exact: cluster.exact,
+                max_similarity: cluster.max_similarity,
+                chunks: cluster.members.iter().map(|m| to_chunk(*m)).collect(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_db::core::{ChunkType, Span};
+    use std::path::PathBuf;
+
+    fn meta(path: &str, line: usize, hash: &str) -> ChunkMetadata {
+        ChunkMetadata {
+            file_path: PathBuf::from(path),
+            span: Span::new(0, 10, line, line + 5),
+            chunk_type: ChunkType::Function,
+            hash: hash.to_string(),
+            symbol: None,
+            git: None,
+        }
+    }
+
+    #[test]
+    fn groups_exact_and_near_duplicates() {
+        let metas = [
+            meta("b.rs", 1, "h1"),
+            meta("a.rs", 10, "h1"),
+            meta("c.rs", 1, "h2"),
+            meta("d.rs", 1, "h3"),
+            meta("e.rs", 1, "h4"),
+        ];
+        let refs: Vec<&ChunkMetadata> = metas.iter().collect();
+        // c 与 d 向量相近；e 孤立
+        let clusters = cluster_duplicates(&refs, &[(2, 3, 0.98)]);
+
+        assert_eq!(clusters.clusters.len(), 2);
+        // 精确重复保留 a.rs，删除 b.rs
+        assert_eq!(clusters.removable, vec![0]);
+
+        let report = build_report(&refs, &clusters, 0.97);
+        assert_eq!(report.duplicate_chunks, 4);
+        assert_eq!(report.removable_chunks, 1);
+        let exact = report.clusters.iter().find(|c| c.exact).unwrap();
+        assert_eq!(exact.max_similarity, 1.0);
+        let near = report.clusters.iter().find(|c| !c.exact).unwrap();
+        assert_eq!(near.max_similarity, 0.98);
+        assert_eq!(near.chunks.len(), 2);
+    }
+
+    #[test]
+    fn near_edges_merge_transitively() {
+        let metas = [
+            meta("a.rs", 1, "x"),
+            meta("b.rs", 1, "y"),
+            meta("c.rs", 1, "z"),
+        ];
+        let refs: Vec<&ChunkMetadata> = metas.iter().collect();
+        let clusters = cluster_duplicates(&refs, &[(0, 1, 0.99), (1, 2, 0.975)]);
+        assert_eq!(clusters.clusters.len(), 1);
+        assert_eq!(clusters.clusters[0].members, vec![0, 1, 2]);
+        assert!(!clusters.clusters[0].exact);
+        assert!(clusters.removable.is_empty());
+    }
+}
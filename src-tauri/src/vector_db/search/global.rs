@@ -0,0 +1,98 @@
+//! 跨工作区搜索结果的合并
+//!
+//! 只有与当前 embedding 模型一致的索引参与检索，各工作区的分数处于同一向量空间，可直接合并排序。
+
+use crate::vector_db::core::SearchResult;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// 全局搜索最多返回的结果数
+pub const GLOBAL_SEARCH_MAX_RESULTS: usize = 100;
+
+/// 带工作区归属的搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalSearchResult {
+    pub workspace_path: PathBuf,
+    #[serde(flatten)]
+    pub result: SearchResult,
+}
+
+/// 未参与本次搜索的索引及原因
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedCollection {
+    pub workspace_path: PathBuf,
+    pub reason: String,
+}
+
+impl SkippedCollection {
+    pub fn new(workspace_path: &Path, reason: impl Into<String>) -> Self {
+        Self {
+            workspace_path: workspace_path.to_path_buf(),
+            reason: reason.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalSearchResults {
+    /// 按分数降序
+    pub results: Vec<GlobalSearchResult>,
+    /// 实际检索过的工作区
+    pub searched: Vec<PathBuf>,
+    pub skipped: Vec<SkippedCollection>,
+}
+
+/// 合并各工作区的结果，按分数降序截断到 limit；同分时保持工作区顺序
+pub fn merge_ranked(
+    per_workspace: Vec<(PathBuf, Vec<SearchResult>)>,
+    limit: usize,
+) -> Vec<GlobalSearchResult> {
+    let mut merged: Vec<GlobalSearchResult> = per_workspace
+        .into_iter()
+        .flat_map(|(workspace_path, results)| {
+            results.into_iter().map(move |result| GlobalSearchResult {
+                workspace_path: workspace_path.clone(),
+                result,
+            })
+        })
+        .collect();
+    merged.sort_by(|a, b| b.result.score.total_cmp(&a.result.score));
+    merged.truncate(limit);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_db::core::Span;
+
+    fn hit(path: &str, score: f32) -> SearchResult {
+        SearchResult::new(
+            PathBuf::from(path),
+            Span::new(0, 1, 1, 1),
+            score,
+            String::new(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn merges_by_score_with_attribution() {
+        let merged = merge_ranked(
+            vec![
+                (
+                    PathBuf::from("/work/a"),
+                    vec![hit("/work/a/x.rs", 0.9), hit("/work/a/y.rs", 0.4)],
+                ),
+                (PathBuf::from("/work/b"), vec![hit("/work/b/z.rs", 0.7)]),
+            ],
+            2,
+        );
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].workspace_path, PathBuf::from("/work/a"));
+        assert_eq!(merged[1].workspace_path, PathBuf::from("/work/b"));
+        assert_eq!(merged[1].result.file_path, PathBuf::from("/work/b/z.rs"));
+    }
+}
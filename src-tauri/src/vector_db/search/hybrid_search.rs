@@ -135,6 +135,7 @@ impl SearchResult {
 mod tests {
     use super::*;
     use crate::vector_db::core::{ChunkType, Span};
+    use crate::vector_db::utils::GitChunkMetadata;
     use std::path::PathBuf;
 
     fn create_test_result(
@@ -144,14 +145,14 @@ mod tests {
         preview: &str,
         score: f32,
     ) -> SearchResult {
-        SearchResult {
-            file_path: PathBuf::from(file),
-            span: Span::new(0, 100, line_start, line_end),
+        SearchResult::new(
+            PathBuf::from(file),
+            Span::new(0, 100, line_start, line_end),
             score,
-            preview: preview.to_string(),
-            language: None,
-            chunk_type: Some(ChunkType::Function),
-        }
+            preview.to_string(),
+            None,
+            Some(ChunkType::Function),
+        )
     }
 
     #[test]
@@ -181,6 +182,25 @@ mod tests {
         assert_eq!(results[0].file_path, PathBuf::from("file1.rs"));
     }
 
+    #[test]
+    fn test_hybrid_search_keeps_git_metadata() {
+        let metadata = GitChunkMetadata {
+            last_commit: "abc1234".to_string(),
+            last_author: "alice".to_string(),
+            last_modified: 1_700_000_000,
+        };
+        let mut semantic = create_test_result("file1.rs", 1, 10, "semantic match", 0.9);
+        semantic.metadata = Some(metadata.clone());
+        let keyword = semantic.clone();
+
+        let results =
+            HybridSearchEngine::hybrid_search("test", vec![semantic], vec![keyword], 0.7, 0.3, 60)
+                .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].metadata, Some(metadata));
+    }
+
     #[test]
     fn test_keyword_search() {
         let all_results = vec![
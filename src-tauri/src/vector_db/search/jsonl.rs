@@ -0,0 +1,142 @@
+//! 面向脚本的搜索结果导出（JSON Lines）
+//!
+//! 每个结果一行 JSON，字段固定如下（schema_version = 1）：
+//!
+//! | 字段             | 类型            | 说明                               |
+//! |------------------|-----------------|------------------------------------|
+//! | `schema_version` | integer         | 当前为 1                           |
+//! | `file_path`      | string          | 文件路径（与索引中记录的一致）     |
+//! | `start_line`     | integer         | 起始行                             |
+//! | `end_line`       | integer         | 结束行                             |
+//! | `score`          | number          | 原始相似度分数（未格式化的浮点数） |
+//! | `symbol`         | string \| null  | 所属符号名                         |
+//! | `snippet`        | string \| null  | 片段原文，超过上限时截断           |
+//!
+//! 后续变更只允许新增字段；删除或改变字段语义必须提升 schema_version。
+//! 该格式与 UI 使用的 `SearchResult` 相互独立。
+
+use crate::vector_db::core::{Result, SearchResult};
+use serde::Serialize;
+use std::path::Path;
+
+pub const SEARCH_JSONL_SCHEMA_VERSION: u32 = 1;
+
+/// 单个片段最多导出的字节数
+const MAX_SNIPPET_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResultRecord {
+    pub schema_version: u32,
+    pub file_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+    pub symbol: Option<String>,
+    pub snippet: Option<String>,
+}
+
+impl SearchResultRecord {
+    pub fn from_result(
+        result: &SearchResult,
+        workspace_root: &Path,
+        include_snippet: bool,
+    ) -> Self {
+        let snippet = if include_snippet {
+            read_snippet(workspace_root, result)
+        } else {
+            None
+        };
+        Self {
+            schema_version: SEARCH_JSONL_SCHEMA_VERSION,
+            file_path: result.file_path.to_string_lossy().replace('\\', "/"),
+            start_line: result.span.line_start,
+            end_line: result.span.line_end,
+            score: result.score,
+            symbol: result.symbol.clone(),
+            snippet,
+        }
+    }
+}
+
+/// 按 span 的字节范围从磁盘读取片段；文件已变化或不可读时返回 None
+fn read_snippet(workspace_root: &Path, result: &SearchResult) -> Option<String> {
+    let path = if result.file_path.is_absolute() {
+        result.file_path.clone()
+    } else {
+        workspace_root.join(&result.file_path)
+    };
+    let content = std::fs::read(path).ok()?;
+    let start = result.span.byte_start.min(content.len());
+    let end = result
+        .span
+        .byte_end
+        .min(content.len())
+        .min(start + MAX_SNIPPET_BYTES);
+    let text = String::from_utf8_lossy(&content[start..end]);
+    Some(text.into_owned())
+}
+
+/// 把结果序列化为 JSON Lines，每行以 `\n` 结尾
+pub fn to_jsonl(
+    results: &[SearchResult],
+    workspace_root: &Path,
+    include_snippet: bool,
+) -> Result<String> {
+    let mut out = String::new();
+    for result in results {
+        let record = SearchResultRecord::from_result(result, workspace_root, include_snippet);
+        out.push_str(&serde_json::to_string(&record)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_db::core::{ChunkType, Span};
+    use tempfile::TempDir;
+
+    #[test]
+    fn emits_one_stable_record_per_line() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn alpha() {}\nfn beta() {}\n").unwrap();
+
+        let results = vec![
+            SearchResult::new(
+                "lib.rs".into(),
+                Span::new(0, 13, 1, 1),
+                0.875,
+                String::new(),
+                None,
+                Some(ChunkType::Function),
+            )
+            .with_symbol(Some("alpha".to_string())),
+            SearchResult::new(
+                "missing.rs".into(),
+                Span::new(0, 10, 3, 4),
+                0.5,
+                String::new(),
+                None,
+                None,
+            ),
+        ];
+
+        let jsonl = to_jsonl(&results, dir.path(), true).unwrap();
+        let lines: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        assert_eq!(lines[0]["schema_version"], 1);
+        assert_eq!(lines[0]["file_path"], "lib.rs");
+        assert_eq!(lines[0]["start_line"], 1);
+        assert_eq!(lines[0]["score"].as_f64().unwrap(), 0.875);
+        assert_eq!(lines[0]["symbol"], "alpha");
+        assert_eq!(lines[0]["snippet"], "fn alpha() {}");
+
+        assert!(lines[1]["symbol"].is_null());
+        assert!(lines[1]["snippet"].is_null());
+    }
+}
@@ -1,15 +1,37 @@
+pub mod benchmark;
+pub mod duplicates;
+pub mod global;
 pub mod hybrid_search;
+pub mod jsonl;
 pub mod semantic_search;
 mod workspace_index;
 
-use crate::vector_db::core::Language;
+use crate::vector_db::core::{ChunkType, Language, Result, SearchResult, VectorDbError};
+use crate::vector_db::storage::ChunkMetadata;
+use std::collections::HashMap;
+use std::path::Path;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SearchOptions {
     pub top_k: usize,
+    /// 最低相关度：默认是（归一化后的）绝对分数；`threshold_is_percentile` 为 true 时
+    /// 表示百分位，如 0.8 只保留分数排在候选前 20% 的结果
     pub threshold: f32,
+    #[serde(default)]
+    pub threshold_is_percentile: bool,
     pub include_snippet: bool,
     pub filter_languages: Vec<Language>,
+    /// 按所属符号名过滤：默认精确匹配，以 `*` 结尾时按前缀匹配
+    #[serde(default)]
+    pub symbol_filter: Option<String>,
+    /// 为结果填充相对工作区根目录的 display_path，file_path 仍为绝对路径
+    #[serde(default)]
+    pub relative_paths: bool,
+    /// 按块类型对分数加权后重新排序，未列出的类型权重为 1.0。与过滤不同，
+    /// 被降权的块仍会返回。代码搜索可参考 Function/Method 1.2、Class/Struct/Enum 1.0、
+    /// Generic 0.8：优先返回具体实现，整文件或无结构的块排在后面
+    #[serde(default)]
+    pub chunk_type_weights: HashMap<ChunkType, f32>,
 }
 
 impl Default for SearchOptions {
@@ -17,12 +39,241 @@ impl Default for SearchOptions {
         Self {
             top_k: 20,
             threshold: 0.3,
+            threshold_is_percentile: false,
             include_snippet: true,
             filter_languages: vec![],
+            symbol_filter: None,
+            relative_paths: false,
+            chunk_type_weights: HashMap::new(),
         }
     }
 }
 
+impl SearchOptions {
+    /// 校验过滤条件
+    pub fn validate(&self) -> Result<()> {
+        if let Some(filter) = &self.symbol_filter {
+            if filter.trim().trim_end_matches('*').is_empty() {
+                return Err(VectorDbError::Search(
+                    "symbol_filter must not be empty".to_string(),
+                ));
+            }
+        }
+        if self
+            .chunk_type_weights
+            .values()
+            .any(|w| !w.is_finite() || *w < 0.0)
+        {
+            return Err(VectorDbError::Search(
+                "chunk_type_weights must be non-negative numbers".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 块类型的分数权重，默认 1.0
+    pub fn chunk_type_weight(&self, chunk_type: Option<&ChunkType>) -> f32 {
+        chunk_type
+            .and_then(|t| self.chunk_type_weights.get(t))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// 是否设置了块类型权重；加权会改变排序，需要在截断前进行
+    pub fn has_weights(&self) -> bool {
+        !self.chunk_type_weights.is_empty()
+    }
+
+    /// 是否设置了需要在召回后过滤的条件
+    pub fn has_filters(&self) -> bool {
+        !self.filter_languages.is_empty() || self.symbol_filter.is_some()
+    }
+
+    /// 判断块是否满足全部过滤条件（AND）
+    pub fn matches(&self, metadata: &ChunkMetadata) -> bool {
+        if !self.filter_languages.is_empty() {
+            match Language::from_path(&metadata.file_path) {
+                Some(lang) if self.filter_languages.contains(&lang) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(filter) = &self.symbol_filter {
+            let filter = filter.trim();
+            let Some(symbol) = metadata.symbol.as_deref() else {
+                return false;
+            };
+            let matched = match filter.strip_suffix('*') {
+                Some(prefix) => symbol.starts_with(prefix),
+                None => symbol == filter,
+            };
+            if !matched {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// 按块类型加权并重新排序；原始分数保留在 raw_score
+pub fn weight_search_results(results: &mut [SearchResult], options: &SearchOptions) {
+    if !options.has_weights() {
+        return;
+    }
+    for result in results.iter_mut() {
+        let raw = result.score;
+        result.raw_score = Some(raw);
+        result.score = raw * options.chunk_type_weight(result.chunk_type.as_ref());
+    }
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
+/// 按索引记录的工作区根目录整理结果路径。根目录可能经过符号链接，
+/// 原样匹配失败时再用规范化后的根目录匹配
+pub fn format_search_results(results: &mut [SearchResult], workspace_root: &Path) {
+    let canonical_root = workspace_root
+        .canonicalize()
+        .ok()
+        .filter(|root| root != workspace_root);
+    for result in results.iter_mut() {
+        result.relativize(workspace_root);
+        if result.outside_workspace {
+            if let Some(root) = &canonical_root {
+                result.relativize(root);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_db::core::{ChunkType, Span};
+    use std::path::PathBuf;
+
+    fn metadata(path: &str, symbol: Option<&str>) -> ChunkMetadata {
+        ChunkMetadata {
+            file_path: PathBuf::from(path),
+            span: Span::new(0, 10, 1, 2),
+            chunk_type: ChunkType::Function,
+            hash: String::new(),
+            symbol: symbol.map(str::to_string),
+            git: None,
+        }
+    }
+
+    #[test]
+    fn symbol_filter_exact_and_prefix() {
+        let exact = SearchOptions {
+            symbol_filter: Some("build".to_string()),
+            ..Default::default()
+        };
+        assert!(exact.matches(&metadata("a.rs", Some("build"))));
+        assert!(!exact.matches(&metadata("a.rs", Some("build_index"))));
+        assert!(!exact.matches(&metadata("a.rs", None)));
+
+        let prefix = SearchOptions {
+            symbol_filter: Some("build*".to_string()),
+            ..Default::default()
+        };
+        assert!(prefix.matches(&metadata("a.rs", Some("build_index"))));
+        assert!(!prefix.matches(&metadata("a.rs", Some("rebuild"))));
+    }
+
+    #[test]
+    fn filters_are_combined() {
+        let options = SearchOptions {
+            filter_languages: vec![Language::Rust],
+            symbol_filter: Some("new".to_string()),
+            ..Default::default()
+        };
+        assert!(options.matches(&metadata("a.rs", Some("new"))));
+        assert!(!options.matches(&metadata("a.py", Some("new"))));
+    }
+
+    #[test]
+    fn results_are_relativized_with_outside_marker() {
+        let result = |path: &str| {
+            SearchResult::new(
+                PathBuf::from(path),
+                Span::new(0, 10, 1, 2),
+                0.9,
+                String::new(),
+                None,
+                None,
+            )
+        };
+        let mut results = vec![result("/work/repo/src/main.rs"), result("/opt/deps/lib.rs")];
+        format_search_results(&mut results, Path::new("/work/repo"));
+
+        assert_eq!(results[0].display_path.as_deref(), Some("src/main.rs"));
+        assert!(!results[0].outside_workspace);
+        assert_eq!(
+            results[0].file_path,
+            PathBuf::from("/work/repo/src/main.rs")
+        );
+        assert_eq!(results[1].display_path.as_deref(), Some("/opt/deps/lib.rs"));
+        assert!(results[1].outside_workspace);
+    }
+
+    #[test]
+    fn chunk_type_weights_rerank_and_keep_raw_score() {
+        let result = |score: f32, chunk_type: ChunkType| {
+            SearchResult::new(
+                PathBuf::from("/work/repo/a.rs"),
+                Span::new(0, 10, 1, 2),
+                score,
+                String::new(),
+                None,
+                Some(chunk_type),
+            )
+        };
+        let mut results = vec![
+            result(0.8, ChunkType::Generic),
+            result(0.7, ChunkType::Function),
+            result(0.6, ChunkType::Struct),
+        ];
+        let options = SearchOptions {
+            chunk_type_weights: HashMap::from([
+                (ChunkType::Function, 1.2),
+                (ChunkType::Generic, 0.8),
+            ]),
+            ..Default::default()
+        };
+        weight_search_results(&mut results, &options);
+
+        let order: Vec<_> = results.iter().map(|r| r.chunk_type.clone()).collect();
+        assert_eq!(
+            order,
+            vec![
+                Some(ChunkType::Function),
+                Some(ChunkType::Generic),
+                Some(ChunkType::Struct)
+            ]
+        );
+        assert_eq!(results[0].raw_score, Some(0.7));
+        assert!((results[0].score - 0.84).abs() < 1e-6);
+        assert_eq!(results[2].score, 0.6);
+
+        let mut unweighted = vec![result(0.5, ChunkType::Generic)];
+        weight_search_results(&mut unweighted, &SearchOptions::default());
+        assert_eq!(unweighted[0].raw_score, None);
+    }
+
+    #[test]
+    fn blank_symbol_filter_is_rejected() {
+        let options = SearchOptions {
+            symbol_filter: Some("  ".to_string()),
+            ..Default::default()
+        };
+        assert!(options.validate().is_err());
+    }
+}
+
+pub use benchmark::*;
+pub use duplicates::*;
+pub use global::*;
 pub use hybrid_search::*;
 pub use semantic_search::*;
 pub(crate) use workspace_index::*;
@@ -1,15 +1,117 @@
-use super::SearchOptions;
-use crate::vector_db::core::{Result, SearchResult, VectorDbConfig};
+use super::benchmark::{recall_at_k, BenchmarkQuery, BenchmarkReport, QueryBenchmark};
+use super::duplicates::{build_report, cluster_duplicates, DuplicateReport, NEIGHBORS_PER_CHUNK};
+use super::global::{
+    merge_ranked, GlobalSearchResults, SkippedCollection, GLOBAL_SEARCH_MAX_RESULTS,
+};
+use super::workspace_index::CachedWorkspaceIndex;
+use super::{format_search_results, weight_search_results, SearchOptions};
+use crate::vector_db::core::{
+    Result, ScoreNormalization, SearchResult, VectorDbConfig, VectorDbError,
+};
 use crate::vector_db::embedding::Embedder;
 use crate::vector_db::search::WorkspaceIndexCache;
-use crate::vector_db::storage::IndexManager;
-use std::path::Path;
+use crate::vector_db::storage::{
+    IndexManager, IndexManagerPool, IndexStatus, RenameOutcome, RenameStats,
+};
+use futures::stream::{self, StreamExt};
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
+
+/// 带过滤条件时的候选放大倍数
+const FILTER_OVERSAMPLE: usize = 4;
+/// 跨工作区搜索时同时加载的索引数
+const GLOBAL_SEARCH_CONCURRENCY: usize = 2;
+
+/// 分数调整与阈值规则
+///
+/// - 原始余弦分数先加上 `score_offset` 并截断到 [0, 1]
+/// - `MinMax` 时按本次查询的候选集缩放，最高分为 1、最低分为 0（只有一个候选时为 1）
+/// - 阈值默认按绝对分数比较，取配置与请求中的较大值；百分位模式只按请求的百分位保留，
+///   至少保留一条，便于前端“最低相关度”滑块在不同模型下表现一致
+struct ScorePolicy {
+    normalization: ScoreNormalization,
+    offset: f32,
+    threshold: f32,
+    percentile: bool,
+}
+
+impl ScorePolicy {
+    fn new(config: &VectorDbConfig, options: &SearchOptions) -> Self {
+        let percentile = options.threshold_is_percentile;
+        Self {
+            normalization: config.score_normalization,
+            offset: config.score_offset,
+            threshold: if percentile {
+                options.threshold.clamp(0.0, 1.0)
+            } else {
+                config.similarity_threshold.max(options.threshold)
+            },
+            percentile,
+        }
+    }
+
+    /// 是否直接在召回阶段按原始分数截断
+    fn is_raw(&self) -> bool {
+        self.normalization == ScoreNormalization::None && self.offset == 0.0 && !self.percentile
+    }
+
+    /// 召回阶段使用的阈值；需要后处理时不在召回阶段截断
+    fn recall_threshold(&self) -> f32 {
+        if self.is_raw() {
+            self.threshold
+        } else {
+            0.0
+        }
+    }
+
+    /// 调整分数并过滤；输入按分数降序
+    fn apply<T>(&self, mut scored: Vec<(T, f32)>) -> Vec<(T, f32)> {
+        if scored.is_empty() || self.is_raw() {
+            return scored;
+        }
+
+        for (_, score) in scored.iter_mut() {
+            *score = (*score + self.offset).clamp(0.0, 1.0);
+        }
+
+        if self.normalization == ScoreNormalization::MinMax {
+            let (min, max) = scored
+                .iter()
+                .fold((f32::MAX, f32::MIN), |(min, max), (_, s)| {
+                    (min.min(*s), max.max(*s))
+                });
+            let range = max - min;
+            for (_, score) in scored.iter_mut() {
+                *score = if range > f32::EPSILON {
+                    (*score - min) / range
+                } else {
+                    1.0
+                };
+            }
+        }
+
+        if self.percentile {
+            let keep = ((scored.len() as f32) * (1.0 - self.threshold)).ceil() as usize;
+            scored.truncate(keep.max(1));
+        } else {
+            scored.retain(|(_, score)| *score >= self.threshold);
+        }
+        scored
+    }
+}
 
 pub struct SemanticSearchEngine {
-    embedder: Arc<dyn Embedder>,
-    config: VectorDbConfig,
+    embedder: RwLock<Arc<dyn Embedder>>,
+    config: RwLock<Arc<VectorDbConfig>>,
     index_cache: WorkspaceIndexCache,
+    managers: IndexManagerPool,
+    /// 当前前端打开的工作区，切换时预热其索引
+    active_workspace: RwLock<Option<PathBuf>>,
+    /// 配置的 embedding 模型被删除后记录其名称；重新选择模型前拒绝搜索与索引，已有索引保持不变
+    removed_model: RwLock<Option<String>>,
 }
 
 impl SemanticSearchEngine {
@@ -17,22 +119,127 @@ impl SemanticSearchEngine {
         // Keep memory bounded: cache only a few workspaces and cap total vector bytes.
         let index_cache = WorkspaceIndexCache::new(3, 256 * 1024 * 1024);
         Self {
-            embedder,
-            config,
+            embedder: RwLock::new(embedder),
+            config: RwLock::new(Arc::new(config)),
             index_cache,
+            managers: IndexManagerPool::new(),
+            active_workspace: RwLock::new(None),
+            removed_model: RwLock::new(None),
         }
     }
 
     pub fn embedder(&self) -> Arc<dyn Embedder> {
-        self.embedder.clone()
+        self.embedder.read().clone()
+    }
+
+    pub fn config(&self) -> VectorDbConfig {
+        (*self.current_config()).clone()
     }
 
-    pub fn config(&self) -> &VectorDbConfig {
-        &self.config
+    fn current_config(&self) -> Arc<VectorDbConfig> {
+        self.config.read().clone()
+    }
+
+    /// 被删除的 embedding 模型名称；None 表示服务可用
+    pub fn removed_model(&self) -> Option<String> {
+        self.removed_model.read().clone()
+    }
+
+    /// 标记配置的 embedding 模型已被删除。保留当前配置中的模型名与维度，
+    /// 使已有索引在选回兼容模型后可以直接使用
+    pub fn mark_embedding_model_removed(&self, model: String) {
+        tracing::warn!(model = %model, "embedding 模型已被删除，向量搜索暂停");
+        *self.removed_model.write() = Some(model);
+    }
+
+    /// 模型被删除时返回 EmbeddingModelRemoved
+    pub fn ensure_embedding_model(&self) -> Result<()> {
+        match self.removed_model() {
+            Some(model) => Err(VectorDbError::EmbeddingModelRemoved(model)),
+            None => Ok(()),
+        }
+    }
+
+    /// 换用新的 embedding 模型。维度必须与被替换模型一致，否则已有索引无法使用；
+    /// 成功后清除删除标记，并记录被替换的模型名，使其构建的索引在名称不同的情况下仍可使用。
+    /// 句柄池按模型配置自动重新打开索引，已加载的向量无需重建
+    pub fn replace_embedding_model(
+        &self,
+        embedder: Arc<dyn Embedder>,
+        mut config: VectorDbConfig,
+    ) -> Result<()> {
+        let previous = self.current_config();
+        let expected = previous.embedding.dimension;
+        if expected != 0 && config.embedding.dimension != expected {
+            return Err(VectorDbError::InvalidDimension {
+                expected,
+                actual: config.embedding.dimension,
+            });
+        }
+
+        let accepted = &mut config.embedding.accepted_index_models;
+        for model in previous
+            .embedding
+            .accepted_index_models
+            .iter()
+            .chain(std::iter::once(&previous.embedding.model_name))
+        {
+            if !model.is_empty()
+                && *model != config.embedding.model_name
+                && !accepted.contains(model)
+            {
+                accepted.push(model.clone());
+            }
+        }
+
+        *self.embedder.write() = embedder;
+        *self.config.write() = Arc::new(config);
+        *self.removed_model.write() = None;
+        Ok(())
     }
 
     pub fn invalidate_workspace_index(&self, workspace_root: &Path) {
         self.index_cache.invalidate(workspace_root);
+        self.managers.invalidate(workspace_root);
+    }
+
+    pub fn active_workspace(&self) -> Option<PathBuf> {
+        self.active_workspace.read().clone()
+    }
+
+    /// 尚未建立索引的工作区使用的状态
+    pub fn empty_status(&self) -> IndexStatus {
+        IndexStatus {
+            total_files: 0,
+            total_chunks: 0,
+            embedding_model: String::new(),
+            vector_dimension: 0,
+            size_bytes: 0,
+            chunk_sizes: self.current_config().embedding.effective_chunk_sizes(),
+            rename_stats: RenameStats::default(),
+        }
+    }
+
+    /// 切换到工作区：记录为当前工作区并预热其索引，使随后的搜索无需再加载
+    pub async fn activate_workspace(&self, workspace_root: &Path) -> Result<IndexStatus> {
+        *self.active_workspace.write() = Some(workspace_root.to_path_buf());
+
+        if !IndexManager::exists(workspace_root) {
+            return Ok(self.empty_status());
+        }
+
+        let manager = self.index_manager(workspace_root)?;
+        if manager.get_status().total_chunks > 0 {
+            self.index_cache
+                .get_or_build(workspace_root, &manager, &self.current_config())
+                .await?;
+        }
+        Ok(manager.get_status_with_size_bytes())
+    }
+
+    /// 从句柄池获取工作区索引（复用已打开的 IndexManager）
+    pub fn index_manager(&self, workspace_root: &Path) -> Result<Arc<IndexManager>> {
+        self.managers.get(workspace_root, &self.current_config())
     }
 
     pub async fn search_in_workspace(
@@ -41,36 +248,591 @@ impl SemanticSearchEngine {
         query: &str,
         options: SearchOptions,
     ) -> Result<Vec<SearchResult>> {
-        let index_manager = IndexManager::new(workspace_root, self.config.clone())?;
+        options.validate()?;
+        self.ensure_embedding_model()?;
+
+        // 未构建索引时直接返回明确的错误，避免 IndexManager::new 顺带创建空的 .oxi 目录
+        if !IndexManager::exists(workspace_root) {
+            return Err(VectorDbError::IndexNotFound(
+                workspace_root.display().to_string(),
+            ));
+        }
+
+        let index_manager = self.index_manager(workspace_root)?;
         if index_manager.get_status().total_chunks == 0 {
             return Ok(Vec::new());
         }
+        // 先比较索引记录的模型与维度，不一致时无需为查询调用 embedding
+        index_manager.check_embedding_model()?;
 
+        let query_embedding = self.embedder().embed(&[query]).await?;
         let cached = self
             .index_cache
-            .get_or_build(workspace_root, &self.config)
+            .get_or_build(workspace_root, &index_manager, &self.current_config())
             .await?;
+        self.search_with_embedding(
+            workspace_root,
+            &index_manager,
+            &cached,
+            &query_embedding[0],
+            &options,
+        )
+    }
+
+    /// 跨工作区搜索：查询只嵌入一次，各工作区并发检索后合并排序。
+    /// 没有索引的工作区直接忽略；索引模型或维度与当前模型不一致、加载失败的工作区跳过并记录原因
+    pub async fn search_global(
+        &self,
+        workspace_roots: &[PathBuf],
+        query: &str,
+        limit: usize,
+    ) -> Result<GlobalSearchResults> {
+        self.ensure_embedding_model()?;
+        let limit = limit.clamp(1, GLOBAL_SEARCH_MAX_RESULTS);
+        let config = self.current_config();
+        let options = SearchOptions {
+            top_k: limit,
+            relative_paths: true,
+            ..SearchOptions::default()
+        };
+
+        let mut skipped = Vec::new();
+        let mut targets = Vec::new();
+        for root in workspace_roots {
+            match IndexManager::read_collection(root, &config) {
+                Ok(Some(collection)) if !collection.matches_active_model => {
+                    tracing::warn!(
+                        path = %root.display(),
+                        indexed_model = %collection.embedding_model,
+                        indexed_dimension = collection.vector_dimension,
+                        "索引与当前 embedding 模型不一致，跳过全局搜索"
+                    );
+                    skipped.push(SkippedCollection::new(
+                        root,
+                        format!(
+                            "indexed with {} ({}d)",
+                            collection.embedding_model, collection.vector_dimension
+                        ),
+                    ));
+                }
+                Ok(Some(collection)) if collection.total_chunks > 0 => targets.push(root.clone()),
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(error = %e, path = %root.display(), "读取索引清单失败");
+                    skipped.push(SkippedCollection::new(root, e.to_string()));
+                }
+            }
+        }
 
-        let query_embedding = self.embedder.embed(&[query]).await?;
+        if targets.is_empty() {
+            return Ok(GlobalSearchResults {
+                results: Vec::new(),
+                searched: Vec::new(),
+                skipped,
+            });
+        }
+
+        let query_embedding = self.embedder().embed(&[query]).await?;
         let query_vec = &query_embedding[0];
 
-        let threshold = self.config.similarity_threshold.max(options.threshold);
-        let hits = cached.search(query_vec, options.top_k, threshold)?;
+        // 逐个工作区检索：不经过句柄池，也不写入索引缓存，同一时刻最多加载少量索引
+        let config = &config;
+        let options = &options;
+        let outcomes: Vec<Result<Vec<SearchResult>>> = stream::iter(targets.clone())
+            .map(move |root| async move {
+                let manager = IndexManager::new(&root, (**config).clone())?;
+                let cached = self
+                    .index_cache
+                    .get_or_build_uncached(&root, &manager, config)
+                    .await?;
+                self.search_with_embedding(&root, &manager, &cached, query_vec, options)
+            })
+            .buffered(GLOBAL_SEARCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut searched = Vec::new();
+        let mut per_workspace = Vec::new();
+        for (root, outcome) in targets.into_iter().zip(outcomes) {
+            match outcome {
+                Ok(results) => {
+                    searched.push(root.clone());
+                    per_workspace.push((root, results));
+                }
+                Err(e) => {
+                    // 维度不一致等错误只影响该工作区
+                    tracing::warn!(error = %e, path = %root.display(), "工作区搜索失败，已跳过");
+                    skipped.push(SkippedCollection::new(&root, e.to_string()));
+                }
+            }
+        }
 
-        let mut search_results = Vec::with_capacity(hits.len());
-        for (internal_idx, score) in hits {
-            if let Some((_chunk_id, metadata)) = cached.chunk_meta_by_internal(internal_idx) {
-                search_results.push(SearchResult::new(
+        Ok(GlobalSearchResults {
+            results: merge_ranked(per_workspace, limit),
+            searched,
+            skipped,
+        })
+    }
+
+    fn search_with_embedding(
+        &self,
+        workspace_root: &Path,
+        index_manager: &IndexManager,
+        cached: &CachedWorkspaceIndex,
+        query_vec: &[f32],
+        options: &SearchOptions,
+    ) -> Result<Vec<SearchResult>> {
+        let config = self.current_config();
+
+        let policy = ScorePolicy::new(&config, options);
+        // 存在过滤条件或权重时多召回一些候选，过滤、加权后再截断
+        let candidate_k = if options.has_filters() || options.has_weights() {
+            options.top_k.saturating_mul(FILTER_OVERSAMPLE)
+        } else {
+            options.top_k
+        };
+        let hits = cached.search(query_vec, candidate_k, policy.recall_threshold())?;
+
+        // 先按过滤条件筛选，再在剩余候选上做归一化和阈值判断
+        let candidates: Vec<_> = hits
+            .into_iter()
+            .filter_map(|(internal_idx, score)| {
+                cached
+                    .chunk_meta_by_internal(internal_idx)
+                    .map(|(_chunk_id, metadata)| (metadata, score))
+            })
+            .filter(|(metadata, _)| options.matches(metadata))
+            .take(if options.has_weights() {
+                candidate_k
+            } else {
+                options.top_k
+            })
+            .collect();
+
+        let mut search_results: Vec<SearchResult> = policy
+            .apply(candidates)
+            .into_iter()
+            .map(|(metadata, score)| {
+                SearchResult::new(
                     metadata.file_path.clone(),
                     metadata.span.clone(),
                     score,
                     format!("Chunk {:?}", metadata.chunk_type),
                     None,
                     Some(metadata.chunk_type.clone()),
-                ));
-            }
+                )
+                .with_symbol(metadata.symbol.clone())
+                .with_git_metadata(metadata.git.clone())
+            })
+            .collect();
+        weight_search_results(&mut search_results, options);
+        search_results.truncate(options.top_k);
+
+        if options.relative_paths {
+            let root = index_manager
+                .workspace_root()
+                .unwrap_or_else(|| workspace_root.to_path_buf());
+            format_search_results(&mut search_results, &root);
         }
 
         Ok(search_results)
     }
+
+    /// 检测重复块：哈希相同为精确重复，向量相似度不低于 threshold 为近似重复。
+    /// dedup 为 true 时每组相同哈希只保留路径最靠前的一个，其余从索引中删除
+    pub async fn find_duplicates(
+        &self,
+        workspace_root: &Path,
+        threshold: f32,
+        dedup: bool,
+    ) -> Result<DuplicateReport> {
+        if !IndexManager::exists(workspace_root) {
+            return Err(VectorDbError::IndexNotFound(
+                workspace_root.display().to_string(),
+            ));
+        }
+
+        let manager = self.index_manager(workspace_root)?;
+        let cached = self
+            .index_cache
+            .get_or_build(workspace_root, &manager, &self.current_config())
+            .await?;
+
+        let scan_manager = manager.clone();
+        let (report, removable_ids) = tokio::task::spawn_blocking(move || {
+            // 下标与 HNSW 内部 id 一致
+            let metas: Vec<_> = (0..cached.len())
+                .filter_map(|idx| cached.chunk_meta_by_internal(idx))
+                .collect();
+
+            // 逐文件读取向量，用 HNSW 查询近邻作为候选边
+            let mut by_file: HashMap<&Path, Vec<usize>> = HashMap::new();
+            for (idx, (_, meta)) in metas.iter().enumerate() {
+                by_file
+                    .entry(meta.file_path.as_path())
+                    .or_default()
+                    .push(idx);
+            }
+            let mut edges = Vec::new();
+            for (file_path, members) in by_file {
+                let Ok(vectors) = scan_manager.store().load_file_vectors(file_path) else {
+                    continue;
+                };
+                for idx in members {
+                    let Some(vector) = vectors.chunks.get(metas[idx].0) else {
+                        continue;
+                    };
+                    for (neighbor, similarity) in
+                        cached.search(vector, NEIGHBORS_PER_CHUNK + 1, threshold)?
+                    {
+                        if neighbor != idx && neighbor < metas.len() {
+                            edges.push((idx.min(neighbor), idx.max(neighbor), similarity));
+                        }
+                    }
+                }
+            }
+
+            let meta_refs: Vec<_> = metas.iter().map(|(_, meta)| *meta).collect();
+            let clusters = cluster_duplicates(&meta_refs, &edges);
+            let removable_ids: Vec<_> = clusters
+                .removable
+                .iter()
+                .map(|idx| *metas[*idx].0)
+                .collect();
+            Ok::<_, VectorDbError>((
+                build_report(&meta_refs, &clusters, threshold),
+                removable_ids,
+            ))
+        })
+        .await
+        .map_err(|e| VectorDbError::Index(format!("duplicate scan join failed: {e}")))??;
+
+        if !dedup || removable_ids.is_empty() {
+            return Ok(report);
+        }
+
+        let removed = manager.remove_chunks(&removable_ids)?;
+        self.index_cache.invalidate(workspace_root);
+        Ok(DuplicateReport {
+            removed_chunks: removed,
+            ..report
+        })
+    }
+
+    /// 文件重命名后更新索引：内容未变时复用已有向量，否则重新索引新路径
+    pub async fn handle_file_renamed(
+        &self,
+        workspace_root: &Path,
+        old_path: &Path,
+        new_path: &Path,
+    ) -> Result<RenameOutcome> {
+        self.ensure_embedding_model()?;
+        if !IndexManager::exists(workspace_root) {
+            return Err(VectorDbError::IndexNotFound(
+                workspace_root.display().to_string(),
+            ));
+        }
+
+        let manager = self.index_manager(workspace_root)?;
+        let outcome = manager
+            .handle_file_renamed(old_path, new_path, self.embedder().as_ref())
+            .await?;
+        self.index_cache.invalidate(workspace_root);
+        Ok(outcome)
+    }
+
+    /// 同步一组被外部修改过的文件：已删除的移出索引，仍存在的重新索引（内容未变时由哈希跳过）。
+    /// 只处理已在索引中的文件，新文件留给下一次构建；返回更新的文件数
+    pub async fn reconcile_files(&self, files: &[PathBuf]) -> usize {
+        if self.removed_model().is_some() {
+            return 0;
+        }
+        let embedder = self.embedder();
+        let mut updated = 0;
+        let mut roots: HashSet<PathBuf> = HashSet::new();
+        for file in files {
+            let Some(root) = file
+                .ancestors()
+                .skip(1)
+                .find(|dir| IndexManager::exists(dir))
+            else {
+                continue;
+            };
+            let manager = match self.index_manager(root) {
+                Ok(manager) => manager,
+                Err(e) => {
+                    tracing::warn!(error = %e, root = %root.display(), "打开索引失败");
+                    continue;
+                }
+            };
+            if !manager.contains_file(file) {
+                continue;
+            }
+            let result = if file.is_file() {
+                manager.update_index(file, embedder.as_ref()).await
+            } else {
+                manager.remove_file(file)
+            };
+            match result {
+                Ok(()) => {
+                    updated += 1;
+                    roots.insert(root.to_path_buf());
+                }
+                Err(e) => tracing::warn!(error = %e, file = %file.display(), "同步文件索引失败"),
+            }
+        }
+        for root in roots {
+            self.index_cache.invalidate(&root);
+        }
+        updated
+    }
+
+    /// 逐条执行样例查询，统计端到端延迟与 recall@k；单条查询失败只记录在该条结果中
+    pub async fn benchmark(
+        &self,
+        workspace_root: &Path,
+        queries: Vec<BenchmarkQuery>,
+        options: SearchOptions,
+    ) -> Result<BenchmarkReport> {
+        options.validate()?;
+        if !IndexManager::exists(workspace_root) {
+            return Err(VectorDbError::IndexNotFound(
+                workspace_root.display().to_string(),
+            ));
+        }
+
+        let mut results = Vec::with_capacity(queries.len());
+        for item in queries {
+            let started = Instant::now();
+            let outcome = self
+                .search_in_workspace(workspace_root, &item.query, options.clone())
+                .await;
+            let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+            results.push(match outcome {
+                Ok(hits) => QueryBenchmark {
+                    recall: recall_at_k(workspace_root, &item.expected_files, &hits),
+                    result_count: hits.len(),
+                    query: item.query,
+                    latency_ms,
+                    error: None,
+                },
+                Err(e) => QueryBenchmark {
+                    query: item.query,
+                    latency_ms,
+                    result_count: 0,
+                    recall: None,
+                    error: Some(e.to_string()),
+                },
+            });
+        }
+
+        Ok(BenchmarkReport::aggregate(options.top_k, results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_db::core::RemoteEmbeddingConfig;
+    use crate::vector_db::embedding::create_embedder;
+
+    fn model_config(model_name: &str, dimension: usize) -> VectorDbConfig {
+        VectorDbConfig {
+            embedding: RemoteEmbeddingConfig {
+                model_name: model_name.into(),
+                dimension,
+                ..RemoteEmbeddingConfig::default()
+            },
+            ..VectorDbConfig::default()
+        }
+    }
+
+    fn policy(normalization: ScoreNormalization, threshold: f32, percentile: bool) -> ScorePolicy {
+        let config = VectorDbConfig {
+            score_normalization: normalization,
+            ..VectorDbConfig::default()
+        };
+        let options = SearchOptions {
+            threshold,
+            threshold_is_percentile: percentile,
+            ..SearchOptions::default()
+        };
+        ScorePolicy::new(&config, &options)
+    }
+
+    #[test]
+    fn min_max_rescales_candidates_before_threshold() {
+        let hits = vec![("a", 0.82), ("b", 0.79), ("c", 0.76), ("d", 0.74)];
+
+        // 原始分数都高于阈值，归一化后只保留相对靠前的
+        let raw = policy(ScoreNormalization::None, 0.5, false);
+        assert!(raw.is_raw());
+        assert_eq!(raw.apply(hits.clone()).len(), 4);
+
+        let min_max = policy(ScoreNormalization::MinMax, 0.5, false);
+        assert_eq!(min_max.recall_threshold(), 0.0);
+        let kept = min_max.apply(hits);
+        assert_eq!(
+            kept.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert_eq!(kept[0].1, 1.0);
+
+        // 单个候选归一化为 1
+        assert_eq!(min_max.apply(vec![("a", 0.4)])[0].1, 1.0);
+    }
+
+    #[test]
+    fn percentile_threshold_keeps_top_fraction() {
+        let hits: Vec<_> = (0..10).map(|i| (i, 0.9 - i as f32 * 0.05)).collect();
+
+        let top_fifth = policy(ScoreNormalization::None, 0.8, true);
+        assert_eq!(top_fifth.apply(hits.clone()).len(), 2);
+
+        // 至少保留一条，且不受配置中的绝对阈值影响
+        let strict = policy(ScoreNormalization::None, 1.0, true);
+        assert_eq!(strict.apply(hits.clone()).len(), 1);
+        let all = policy(ScoreNormalization::None, 0.0, true);
+        assert_eq!(all.apply(hits).len(), 10);
+    }
+
+    #[test]
+    fn offset_shifts_raw_scores() {
+        let config = VectorDbConfig {
+            score_offset: 0.2,
+            ..VectorDbConfig::default()
+        };
+        let options = SearchOptions {
+            threshold: 0.5,
+            ..SearchOptions::default()
+        };
+        let policy = ScorePolicy::new(&config, &options);
+        let kept = policy.apply(vec![("a", 0.9), ("b", 0.35), ("c", 0.2)]);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].1, 1.0);
+        assert!((kept[1].1 - 0.55).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn removed_model_blocks_search_until_compatible_replacement() {
+        let config = model_config("bge-m3", 1024);
+        let engine = SemanticSearchEngine::new(create_embedder(&config.embedding).unwrap(), config);
+        engine.mark_embedding_model_removed("bge-m3".into());
+
+        let outcome = engine
+            .search_in_workspace(Path::new("/nonexistent"), "query", SearchOptions::default())
+            .await;
+        assert!(matches!(
+            outcome,
+            Err(VectorDbError::EmbeddingModelRemoved(model)) if model == "bge-m3"
+        ));
+
+        // 维度不同的模型无法使用已有索引，服务保持暂停
+        let other = model_config("text-embedding-3-small", 1536);
+        let replaced =
+            engine.replace_embedding_model(create_embedder(&other.embedding).unwrap(), other);
+        assert!(matches!(
+            replaced,
+            Err(VectorDbError::InvalidDimension {
+                expected: 1024,
+                actual: 1536
+            })
+        ));
+        assert_eq!(engine.removed_model().as_deref(), Some("bge-m3"));
+
+        let same = model_config("bge-m3", 1024);
+        engine
+            .replace_embedding_model(create_embedder(&same.embedding).unwrap(), same)
+            .unwrap();
+        assert!(engine.ensure_embedding_model().is_ok());
+    }
+
+    #[tokio::test]
+    async fn replacement_model_can_search_the_existing_index() {
+        use crate::vector_db::core::{ChunkId, ChunkType, Span};
+        use crate::vector_db::storage::{ChunkMetadata, IndexManifest};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join(".oxi")).unwrap();
+        let manifest_path = dir.path().join(".oxi").join("manifest.json");
+        let mut manifest = IndexManifest::new("bge-m3".into(), 1024);
+        manifest.add_chunk(
+            ChunkId::new_v4(),
+            ChunkMetadata {
+                file_path: dir.path().join("a.rs"),
+                span: Span::new(0, 1, 1, 1),
+                chunk_type: ChunkType::Generic,
+                hash: String::new(),
+                symbol: None,
+                git: None,
+            },
+        );
+        manifest.save(&manifest_path).unwrap();
+
+        let config = model_config("bge-m3", 1024);
+        let engine = SemanticSearchEngine::new(create_embedder(&config.embedding).unwrap(), config);
+        engine.mark_embedding_model_removed("bge-m3".into());
+        let replacement = model_config("bge-m3-hosted", 1024);
+        engine
+            .replace_embedding_model(
+                create_embedder(&replacement.embedding).unwrap(),
+                replacement,
+            )
+            .unwrap();
+        assert_eq!(
+            engine.config().embedding.accepted_index_models,
+            vec!["bge-m3".to_string()]
+        );
+
+        // 测试环境无法访问 embedding 服务，搜索会在查询 embedding 时失败，但不应是模型不一致
+        let outcome = engine
+            .search_in_workspace(dir.path(), "query", SearchOptions::default())
+            .await;
+        assert!(!matches!(
+            outcome,
+            Err(VectorDbError::EmbeddingModelMismatch { .. })
+        ));
+        let recorded = IndexManifest::load(&manifest_path).unwrap();
+        assert_eq!(recorded.embedding_model, "bge-m3-hosted");
+    }
+
+    #[tokio::test]
+    async fn dimension_drift_is_reported_before_embedding_the_query() {
+        use crate::vector_db::core::{ChunkId, ChunkType, Span};
+        use crate::vector_db::storage::{ChunkMetadata, IndexManifest};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join(".oxi")).unwrap();
+        let mut manifest = IndexManifest::new("text-embedding-3-small".into(), 1536);
+        manifest.add_chunk(
+            ChunkId::new_v4(),
+            ChunkMetadata {
+                file_path: dir.path().join("a.rs"),
+                span: Span::new(0, 1, 1, 1),
+                chunk_type: ChunkType::Generic,
+                hash: String::new(),
+                symbol: None,
+                git: None,
+            },
+        );
+        manifest
+            .save(&dir.path().join(".oxi").join("manifest.json"))
+            .unwrap();
+
+        // 测试环境无法访问 embedding 服务，若先发起查询 embedding 会得到其他错误
+        let config = model_config("bge-m3", 1024);
+        let engine = SemanticSearchEngine::new(create_embedder(&config.embedding).unwrap(), config);
+        let outcome = engine
+            .search_in_workspace(dir.path(), "query", SearchOptions::default())
+            .await;
+        assert!(matches!(
+            outcome,
+            Err(VectorDbError::EmbeddingModelMismatch {
+                indexed_dimension: 1536,
+                active_dimension: 1024,
+                ..
+            })
+        ));
+    }
 }
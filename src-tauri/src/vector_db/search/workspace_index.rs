@@ -64,12 +64,13 @@ impl WorkspaceIndexCache {
     pub async fn get_or_build(
         &self,
         workspace_root: &Path,
+        manager: &IndexManager,
         config: &VectorDbConfig,
     ) -> Result<Arc<CachedWorkspaceIndex>> {
         let workspace_root = workspace_root.to_path_buf();
 
         // Fast path: check cache with current signature.
-        if let Some(entry) = self.try_get_if_fresh(&workspace_root, config)? {
+        if let Some(entry) = self.try_get_if_fresh(&workspace_root, manager, config) {
             return Ok(entry);
         }
 
@@ -82,7 +83,7 @@ impl WorkspaceIndexCache {
         let _guard = lock.lock().await;
 
         // Re-check after acquiring build lock.
-        if let Some(entry) = self.try_get_if_fresh(&workspace_root, config)? {
+        if let Some(entry) = self.try_get_if_fresh(&workspace_root, manager, config) {
             return Ok(entry);
         }
 
@@ -99,27 +100,48 @@ impl WorkspaceIndexCache {
         Ok(built)
     }
 
+    /// 命中新鲜缓存时复用，否则临时构建且不写入缓存。
+    /// 供跨工作区搜索使用，避免一次性加载的索引挤掉活跃工作区的缓存
+    pub async fn get_or_build_uncached(
+        &self,
+        workspace_root: &Path,
+        manager: &IndexManager,
+        config: &VectorDbConfig,
+    ) -> Result<Arc<CachedWorkspaceIndex>> {
+        if let Some(entry) = self.try_get_if_fresh(workspace_root, manager, config) {
+            return Ok(entry);
+        }
+
+        let config = config.clone();
+        let workspace_root = workspace_root.to_path_buf();
+        let built =
+            tokio::task::spawn_blocking(move || build_workspace_index(&workspace_root, &config))
+                .await
+                .map_err(|e| VectorDbError::Index(format!("index build join failed: {e}")))??;
+        Ok(Arc::new(built))
+    }
+
     fn try_get_if_fresh(
         &self,
         workspace_root: &Path,
+        manager: &IndexManager,
         config: &VectorDbConfig,
-    ) -> Result<Option<Arc<CachedWorkspaceIndex>>> {
-        let manager = IndexManager::new(workspace_root, config.clone())?;
+    ) -> Option<Arc<CachedWorkspaceIndex>> {
         if manager.get_status().total_chunks == 0 {
-            return Ok(Some(Arc::new(CachedWorkspaceIndex::empty(
-                IndexSignature::from_manager(&manager),
+            return Some(Arc::new(CachedWorkspaceIndex::empty(
+                IndexSignature::from_manager(manager),
                 config.embedding.dimension,
-            ))));
+            )));
         }
-        let signature = IndexSignature::from_manager(&manager);
+        let signature = IndexSignature::from_manager(manager);
 
         let mut inner = self.inner.lock();
         if let Some(existing) = inner.lru.get(workspace_root) {
             if existing.signature == signature {
-                return Ok(Some(existing.clone()));
+                return Some(existing.clone());
             }
         }
-        Ok(None)
+        None
     }
 
     fn insert(&self, workspace_root: PathBuf, entry: Arc<CachedWorkspaceIndex>) {
@@ -206,6 +228,11 @@ impl CachedWorkspaceIndex {
     pub fn chunk_meta_by_internal(&self, idx: usize) -> Option<(&ChunkId, &ChunkMetadata)> {
         self.ids.get(idx).zip(self.metas.get(idx))
     }
+
+    /// 已载入 HNSW 的块数量
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
 }
 
 fn build_workspace_index(
@@ -222,12 +249,7 @@ fn build_workspace_index(
         ));
     }
 
-    if status.vector_dimension != config.embedding.dimension {
-        return Err(VectorDbError::InvalidDimension {
-            expected: config.embedding.dimension,
-            actual: status.vector_dimension,
-        });
-    }
+    manager.check_embedding_model()?;
 
     let store = manager.store();
 
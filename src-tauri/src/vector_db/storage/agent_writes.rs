@@ -0,0 +1,107 @@
+//! Agent 写入路径登记
+//!
+//! agent 的写文件工具在修改前登记路径，登记后的一段时间（TTL）内文件变化事件应被忽略，
+//! 避免增量重建与 agent 随后的读取竞争、反复触发。一轮工具执行结束后，
+//! 该任务登记过的路径统一交给 `SemanticSearchEngine::reconcile_files` 做一次增量索引。
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// 登记后抑制文件变化事件的时长
+pub const AGENT_WRITE_TTL: Duration = Duration::from_secs(120);
+
+struct PendingWrite {
+    task_id: String,
+    expires_at: Instant,
+}
+
+pub struct AgentWriteRegistry {
+    ttl: Duration,
+    entries: Mutex<HashMap<PathBuf, PendingWrite>>,
+}
+
+static AGENT_WRITES: OnceLock<AgentWriteRegistry> = OnceLock::new();
+
+/// 进程内共享的登记表
+pub fn agent_writes() -> &'static AgentWriteRegistry {
+    AGENT_WRITES.get_or_init(|| AgentWriteRegistry::new(AGENT_WRITE_TTL))
+}
+
+impl AgentWriteRegistry {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 登记即将被修改的路径；重复登记会刷新 TTL。过期项在此顺带清理
+    pub fn register(&self, task_id: &str, path: &Path) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock();
+        entries.retain(|_, entry| entry.expires_at > now);
+        entries.insert(
+            path.to_path_buf(),
+            PendingWrite {
+                task_id: task_id.to_string(),
+                expires_at: now + self.ttl,
+            },
+        );
+    }
+
+    /// 路径的文件变化事件是否应被忽略
+    pub fn is_suppressed(&self, path: &Path) -> bool {
+        self.entries
+            .lock()
+            .get(path)
+            .is_some_and(|entry| entry.expires_at > Instant::now())
+    }
+
+    /// 取出任务登记过且仍在有效期内的路径，取出后不再抑制
+    pub fn take_task(&self, task_id: &str) -> Vec<PathBuf> {
+        let now = Instant::now();
+        let mut taken = Vec::new();
+        self.entries.lock().retain(|path, entry| {
+            if entry.task_id != task_id {
+                return entry.expires_at > now;
+            }
+            if entry.expires_at > now {
+                taken.push(path.clone());
+            }
+            false
+        });
+        taken.sort();
+        taken
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suppresses_until_taken_or_expired() {
+        let registry = AgentWriteRegistry::new(Duration::from_secs(60));
+        registry.register("task-a", Path::new("/ws/b.rs"));
+        registry.register("task-a", Path::new("/ws/a.rs"));
+        registry.register("task-b", Path::new("/ws/c.rs"));
+        assert!(registry.is_suppressed(Path::new("/ws/a.rs")));
+        assert!(!registry.is_suppressed(Path::new("/ws/other.rs")));
+
+        assert_eq!(
+            registry.take_task("task-a"),
+            vec![PathBuf::from("/ws/a.rs"), PathBuf::from("/ws/b.rs")]
+        );
+        assert!(!registry.is_suppressed(Path::new("/ws/a.rs")));
+        assert!(registry.is_suppressed(Path::new("/ws/c.rs")));
+        assert!(registry.take_task("task-a").is_empty());
+
+        let expired = AgentWriteRegistry::new(Duration::ZERO);
+        expired.register("task-a", Path::new("/ws/a.rs"));
+        assert!(!expired.is_suppressed(Path::new("/ws/a.rs")));
+        assert!(expired.take_task("task-a").is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+//! 索引构建成本估算
+//!
+//! 使用与真实构建相同的文件扫描和分块流程（遵循 .gitignore 与目录排除规则），
+//! 但不调用 embedding，只统计块数和 token 数，再按模型价格与批次估算费用和耗时。
+
+use super::index_manager::EMBED_BATCH_SIZE;
+use crate::vector_db::chunking::{ChunkFilterStats, TextChunker, TokenEstimator};
+use crate::vector_db::core::{Language, Result, VectorDbConfig};
+use crate::vector_db::utils::collect_source_files;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 未配置速率限制时假定的单批 embedding 请求耗时
+const ASSUMED_BATCH_LATENCY_MS: u64 = 1_000;
+
+/// 常见 embedding 模型的公开价格（美元 / 百万 token）
+const BUILTIN_PRICES: &[(&str, f64)] = &[
+    ("text-embedding-3-small", 0.02),
+    ("text-embedding-3-large", 0.13),
+    ("text-embedding-ada-002", 0.10),
+];
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageEstimate {
+    /// None 表示无法识别语言、按纯文本分块的文件
+    pub language: Option<Language>,
+    pub files: usize,
+    pub chunks: usize,
+    pub tokens: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEstimate {
+    pub total_files: usize,
+    /// 非 UTF-8 或读取失败而不会被索引的文件
+    pub skipped_files: usize,
+    pub total_chunks: usize,
+    /// 被块过滤规则排除、不计入费用的块
+    pub filtered_chunks: ChunkFilterStats,
+    pub estimated_tokens: usize,
+    pub embedding_model: String,
+    pub price_per_million_tokens: Option<f64>,
+    /// 模型价格未知时为 None
+    pub estimated_cost_usd: Option<f64>,
+    pub estimated_batches: usize,
+    pub estimated_seconds: u64,
+    /// 按 token 数降序
+    pub languages: Vec<LanguageEstimate>,
+}
+
+/// 配置的价格优先，否则按模型名匹配内置价格表
+pub fn embedding_price_per_million(config: &VectorDbConfig) -> Option<f64> {
+    if let Some(price) = config.embedding.price_per_million_tokens {
+        return Some(price);
+    }
+    let model = config.embedding.model_name.to_lowercase();
+    BUILTIN_PRICES
+        .iter()
+        .find(|(name, _)| model.ends_with(name))
+        .map(|(_, price)| *price)
+}
+
+/// 按批次数、并发请求数与速率限制估算耗时（秒）；并发只能摊薄延迟，不能突破速率限制
+fn estimate_seconds(batches: usize, requests_per_minute: Option<u32>, concurrency: usize) -> u64 {
+    let latency_ms = ASSUMED_BATCH_LATENCY_MS / concurrency.max(1) as u64;
+    let per_batch_ms = match requests_per_minute {
+        Some(rpm) if rpm > 0 => latency_ms.max(60_000 / rpm as u64),
+        _ => latency_ms,
+    };
+    (batches as u64 * per_batch_ms).div_ceil(1000)
+}
+
+/// 扫描并分块工作区，估算构建索引的费用与耗时（阻塞调用）
+pub fn estimate_index_build(root: &Path, config: &VectorDbConfig) -> Result<IndexEstimate> {
+    let files = collect_source_files(root, config.max_file_size);
+    let chunker = TextChunker::from_embedding_config(&config.embedding)
+        .with_filter(config.chunk_filter.clone());
+
+    let mut by_language: HashMap<Option<Language>, LanguageEstimate> = HashMap::new();
+    let mut skipped_files = 0usize;
+    let mut total_chunks = 0usize;
+    let mut filtered_chunks = ChunkFilterStats::default();
+    let mut estimated_tokens = 0usize;
+    let mut estimated_batches = 0usize;
+
+    for file in &files {
+        let content = match std::fs::read(file).map(String::from_utf8) {
+            Ok(Ok(content)) => content,
+            _ => {
+                skipped_files += 1;
+                continue;
+            }
+        };
+        let chunks = match chunker.chunk_with_stats(&content, file) {
+            Ok((chunks, filtered)) => {
+                filtered_chunks.merge(filtered);
+                chunks
+            }
+            Err(e) => {
+                tracing::debug!(file = %file.display(), "估算时分块失败: {}", e);
+                skipped_files += 1;
+                continue;
+            }
+        };
+        if chunks.is_empty() {
+            continue;
+        }
+
+        let tokens: usize = chunks
+            .iter()
+            .map(|c| TokenEstimator::estimate_tokens(&c.content))
+            .sum();
+        let language = Language::from_path(file);
+        let entry = by_language
+            .entry(language)
+            .or_insert_with(|| LanguageEstimate {
+                language,
+                ..Default::default()
+            });
+        entry.files += 1;
+        entry.chunks += chunks.len();
+        entry.tokens += tokens;
+
+        total_chunks += chunks.len();
+        estimated_tokens += tokens;
+        // 真实构建按文件分批请求
+        estimated_batches += chunks.len().div_ceil(EMBED_BATCH_SIZE);
+    }
+
+    let mut languages: Vec<LanguageEstimate> = by_language.into_values().collect();
+    languages.sort_by_key(|language| std::cmp::Reverse(language.tokens));
+
+    let price = embedding_price_per_million(config);
+    Ok(IndexEstimate {
+        total_files: files.len() - skipped_files,
+        skipped_files,
+        total_chunks,
+        filtered_chunks,
+        estimated_tokens,
+        embedding_model: config.embedding.model_name.clone(),
+        price_per_million_tokens: price,
+        estimated_cost_usd: price.map(|p| estimated_tokens as f64 / 1_000_000.0 * p),
+        estimated_batches,
+        estimated_seconds: estimate_seconds(
+            estimated_batches,
+            config.embedding.requests_per_minute,
+            config.embedding.max_concurrent_requests,
+        ),
+        languages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn estimates_without_embedding_and_respects_ignores() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("main.rs"),
+            "fn main() {\n    println!(\"hi\");\n}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "some plain notes\n").unwrap();
+        std::fs::create_dir(dir.path().join("node_modules")).unwrap();
+        std::fs::write(
+            dir.path().join("node_modules").join("dep.js"),
+            "var x = 1;\n",
+        )
+        .unwrap();
+
+        let mut config = VectorDbConfig::default();
+        config.embedding.model_name = "text-embedding-3-small".to_string();
+
+        let estimate = estimate_index_build(dir.path(), &config).unwrap();
+        assert_eq!(estimate.total_files, 2);
+        assert!(estimate.total_chunks >= 2);
+        assert!(estimate.estimated_tokens > 0);
+        assert_eq!(estimate.price_per_million_tokens, Some(0.02));
+        assert!(estimate.estimated_cost_usd.unwrap() > 0.0);
+        assert!(estimate
+            .languages
+            .iter()
+            .any(|l| l.language == Some(Language::Rust)));
+        assert!(estimate
+            .languages
+            .iter()
+            .all(|l| l.language != Some(Language::JavaScript)));
+    }
+
+    #[test]
+    fn rate_limit_bounds_batch_time() {
+        assert_eq!(estimate_seconds(10, None, 1), 10);
+        assert_eq!(estimate_seconds(10, Some(30), 1), 20);
+        assert_eq!(estimate_seconds(10, Some(6000), 1), 10);
+        assert_eq!(estimate_seconds(10, None, 4), 3);
+        assert_eq!(estimate_seconds(10, Some(30), 4), 20);
+    }
+}
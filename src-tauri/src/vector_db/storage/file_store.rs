@@ -170,6 +170,21 @@ impl FileStore {
         Ok(())
     }
 
+    /// 向量文件的写入时间（Unix 秒），即该文件最近一次被索引的时间
+    pub fn vectors_written_at(&self, source_file: &Path) -> Option<u64> {
+        fs::metadata(self.get_vector_file_path(source_file))
+            .and_then(|m| m.modified())
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs())
+    }
+
+    /// 获取项目根目录
+    pub fn project_root(&self) -> &Path {
+        &self.project_root
+    }
+
     /// 获取存储根目录
     pub fn root_path(&self) -> &Path {
         &self.root_path
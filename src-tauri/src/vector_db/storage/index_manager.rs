@@ -1,15 +1,98 @@
-use super::{ChunkMetadata, FileStore, IndexManifest};
-use crate::vector_db::chunking::TextChunker;
-use crate::vector_db::core::{Chunk, Result, VectorDbConfig, VectorDbError};
+use super::{ChunkMetadata, FileStore, IndexCollection, IndexManifest, RenameStats};
+use crate::vector_db::chunking::{ChunkFilterStats, TextChunker};
+use crate::vector_db::core::{Chunk, EmbedRetryConfig, Result, VectorDbConfig, VectorDbError};
 use crate::vector_db::embedding::Embedder;
-use crate::vector_db::utils::{blake3_hash_bytes, collect_source_files};
+use crate::vector_db::utils::{blake3_hash_bytes, blame_file, collect_source_files};
+use futures::stream::{self, Stream, StreamExt};
 use parking_lot::RwLock;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tokio::sync::Semaphore;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct IndexFileOutcome {
     pub indexed_chunks: usize,
+    /// embedding 批次的重试次数
+    pub retries: u32,
+    /// 被块过滤规则排除、未送入 embedding 的块
+    pub filtered: ChunkFilterStats,
+    /// 成功的 embedding 请求数
+    pub embed_batches: u32,
+    /// embedding 请求耗时（含重试）
+    pub embed_time: std::time::Duration,
+    /// 写入向量、元数据与清单的耗时
+    pub write_time: std::time::Duration,
+}
+
+/// 已读取并分块、尚未生成向量的文件
+#[derive(Debug, Clone)]
+pub struct PreparedFile {
+    pub path: PathBuf,
+    pub content_hash: String,
+    pub last_modified: u64,
+    pub size: u64,
+    pub chunks: Vec<Chunk>,
+    pub filtered: ChunkFilterStats,
+}
+
+/// 重命名文件的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RenameOutcome {
+    /// 内容未变，只更新块的路径，复用已有向量
+    PathOnly { chunks: usize },
+    /// 内容有变化或旧路径未索引，删除旧数据后重新索引新路径
+    Reindexed { chunks: usize, retries: u32 },
+    /// agent 正在写入的路径，事件被忽略，由任务在本轮工具结束后统一同步
+    Suppressed,
+}
+
+/// 单批 embedding 的退避等待上限
+const MAX_RETRY_DELAY_MS: u64 = 30_000;
+
+/// 每次 embedding 请求包含的块数
+pub(crate) const EMBED_BATCH_SIZE: usize = 64;
+
+/// 调用 embedder，可重试错误按指数退避重试；返回结果与重试次数
+async fn embed_with_retry(
+    embedder: &dyn Embedder,
+    texts: &[&str],
+    retry: &EmbedRetryConfig,
+) -> Result<(Vec<Vec<f32>>, u32)> {
+    let max_attempts = retry.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match embedder.embed(texts).await {
+            Ok(batch) => return Ok((batch, attempt - 1)),
+            Err(e) if e.is_retryable() && attempt < max_attempts => {
+                let delay = retry
+                    .base_delay_ms
+                    .saturating_mul(1 << (attempt - 1).min(16))
+                    .min(MAX_RETRY_DELAY_MS);
+                tracing::warn!(
+                    "embedding 批次失败（第 {}/{} 次），{}ms 后重试: {}",
+                    attempt,
+                    max_attempts,
+                    delay,
+                    e
+                );
+                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// 以自有文本调用 embed_with_retry，返回的 future 不借用块数据，可放入并发流
+async fn embed_owned_batch(
+    embedder: &dyn Embedder,
+    texts: Vec<String>,
+    retry: &EmbedRetryConfig,
+) -> Result<(Vec<Vec<f32>>, u32)> {
+    let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+    embed_with_retry(embedder, &texts, retry).await
 }
 
 pub struct IndexManager {
@@ -24,7 +107,7 @@ impl IndexManager {
         store.initialize()?;
 
         let manifest_path = store.root_path().join("manifest.json");
-        let manifest = if manifest_path.exists() {
+        let mut manifest = if manifest_path.exists() {
             IndexManifest::load(&manifest_path)?
         } else {
             IndexManifest::new(
@@ -32,6 +115,10 @@ impl IndexManager {
                 config.embedding.dimension,
             )
         };
+        // 旧版清单没有记录根目录，补上后随下一次保存写入
+        if manifest.workspace_root.is_none() {
+            manifest.workspace_root = Some(project_root.to_path_buf());
+        }
 
         Ok(Self {
             store,
@@ -40,6 +127,30 @@ impl IndexManager {
         })
     }
 
+    /// 工作区是否已经构建过索引（只检查清单，不会创建 .oxi 目录）
+    pub fn exists(project_root: &Path) -> bool {
+        project_root.join(".oxi").join("manifest.json").exists()
+    }
+
+    /// 只读取清单生成索引摘要，不加载向量；工作区未建立索引时返回 None
+    pub fn read_collection(
+        project_root: &Path,
+        config: &VectorDbConfig,
+    ) -> Result<Option<IndexCollection>> {
+        let manifest_path = project_root.join(".oxi").join("manifest.json");
+        if !manifest_path.exists() {
+            return Ok(None);
+        }
+        let manifest = IndexManifest::load(&manifest_path)?;
+        Ok(Some(IndexCollection::from_manifest(
+            project_root.to_path_buf(),
+            &manifest,
+            &config.embedding.model_name,
+            config.embedding.dimension,
+            &config.embedding.accepted_index_models,
+        )))
+    }
+
     fn manifest_path(&self) -> PathBuf {
         self.store.root_path().join("manifest.json")
     }
@@ -49,6 +160,61 @@ impl IndexManager {
         manifest.save(&self.manifest_path())
     }
 
+    /// 清单记录的工作区根目录
+    pub fn workspace_root(&self) -> Option<PathBuf> {
+        self.manifest.read().workspace_root.clone()
+    }
+
+    /// 索引是否由当前配置的 embedding 模型（或用户接受的被替换模型）构建。
+    /// 由被替换模型构建时把清单中的模型名更新为当前模型，记录这次替换
+    pub fn check_embedding_model(&self) -> Result<()> {
+        let embedding = &self.config.embedding;
+        {
+            let manifest = self.manifest.read();
+            manifest.check_model_accepting(
+                &embedding.model_name,
+                embedding.dimension,
+                &embedding.accepted_index_models,
+            )?;
+            if !embedding
+                .accepted_index_models
+                .contains(&manifest.embedding_model)
+            {
+                return Ok(());
+            }
+        }
+        self.manifest.write().embedding_model = embedding.model_name.clone();
+        if let Err(e) = self.save_manifest() {
+            tracing::warn!(error = %e, "记录 embedding 模型替换失败");
+        }
+        Ok(())
+    }
+
+    /// 清空索引数据并以当前模型重新开始，用于切换 embedding 模型后的显式重建
+    pub fn reset(&self) -> Result<()> {
+        let dirs = [
+            self.store.vectors_path().to_path_buf(),
+            self.store.root_path().join("metadata"),
+        ];
+        for dir in dirs {
+            if dir.exists() {
+                std::fs::remove_dir_all(&dir)?;
+            }
+        }
+        self.store.initialize()?;
+        let mut manifest = self.manifest.write();
+        let workspace_root = manifest.workspace_root.take();
+        let rename_stats = manifest.rename_stats;
+        *manifest = IndexManifest::new(
+            self.config.embedding.model_name.clone(),
+            self.config.embedding.dimension,
+        );
+        manifest.workspace_root = workspace_root;
+        manifest.rename_stats = rename_stats;
+        drop(manifest);
+        self.save_manifest()
+    }
+
     pub async fn index_file_with(&self, file_path: &Path, embedder: &dyn Embedder) -> Result<()> {
         let _ = self
             .index_file_with_progress(file_path, embedder, |_done, _total| {})
@@ -60,27 +226,31 @@ impl IndexManager {
         &self,
         file_path: &Path,
         embedder: &dyn Embedder,
-        mut on_progress: F,
+        on_progress: F,
     ) -> Result<IndexFileOutcome>
     where
         F: FnMut(usize, usize) + Send,
     {
-        // 0. 限制：尺寸
+        match Self::prepare_file(&self.config, file_path)? {
+            Some(prepared) => self.index_prepared(&prepared, embedder, on_progress).await,
+            None => Ok(IndexFileOutcome::default()),
+        }
+    }
+
+    /// 读取并分块单个文件（CPU 密集，不访问索引状态）；过大或非 UTF-8 的文件返回 None
+    pub fn prepare_file(config: &VectorDbConfig, file_path: &Path) -> Result<Option<PreparedFile>> {
         let meta = std::fs::metadata(file_path).map_err(VectorDbError::Io)?;
-        if meta.len() > self.config.max_file_size {
-            return Ok(IndexFileOutcome { indexed_chunks: 0 }); // 跳过过大文件
+        if meta.len() > config.max_file_size {
+            return Ok(None);
         }
 
-        // 1. 读取内容
         let content = match std::fs::read(file_path) {
             Ok(bytes) => match String::from_utf8(bytes) {
                 Ok(s) => s,
-                Err(_) => return Ok(IndexFileOutcome { indexed_chunks: 0 }), // 跳过非 UTF-8 文件
+                Err(_) => return Ok(None),
             },
             Err(e) => return Err(VectorDbError::Io(e)),
         };
-        let file_hash = blake3_hash_bytes(content.as_bytes());
-        let _language = crate::vector_db::core::Language::from_path(file_path);
         let last_modified = meta
             .modified()
             .ok()
@@ -88,7 +258,77 @@ impl IndexManager {
             .map(|d| d.as_secs())
             .unwrap_or(0);
 
-        // 2. 旧块清理（如果已存在）
+        let chunker = TextChunker::from_embedding_config(&config.embedding)
+            .with_filter(config.chunk_filter.clone());
+        let (chunks, filtered) = chunker.chunk_with_stats(&content, file_path)?;
+
+        Ok(Some(PreparedFile {
+            path: file_path.to_path_buf(),
+            content_hash: blake3_hash_bytes(content.as_bytes()),
+            last_modified,
+            size: meta.len(),
+            chunks,
+            filtered,
+        }))
+    }
+
+    /// 在阻塞线程池中并发读取、分块，结果按输入顺序产出，单个文件的错误随结果返回。
+    /// 同时分块的文件数受 max_concurrent_files 限制，已读入内存的文件数受 max_buffered_files 限制
+    pub fn prepare_files(
+        config: Arc<VectorDbConfig>,
+        files: Vec<PathBuf>,
+    ) -> impl Stream<Item = (PathBuf, Result<Option<PreparedFile>>)> {
+        let workers = Arc::new(Semaphore::new(config.parsing.max_concurrent_files.max(1)));
+        let buffered = config.parsing.max_buffered_files.max(1);
+
+        stream::iter(files)
+            .map(move |path| {
+                let config = Arc::clone(&config);
+                let workers = Arc::clone(&workers);
+                async move {
+                    let Ok(_permit) = workers.acquire_owned().await else {
+                        return (path, Err(VectorDbError::Index("parser pool closed".into())));
+                    };
+                    let task_path = path.clone();
+                    let result = tokio::task::spawn_blocking(move || {
+                        Self::prepare_file(&config, &task_path)
+                    })
+                    .await
+                    .unwrap_or_else(|e| {
+                        Err(VectorDbError::ChunkingError(format!(
+                            "parse task failed: {}",
+                            e
+                        )))
+                    });
+                    (path, result)
+                }
+            })
+            .buffered(buffered)
+    }
+
+    /// 为已分块的文件生成向量并写入索引，替换该文件已有的块
+    pub async fn index_prepared<F>(
+        &self,
+        prepared: &PreparedFile,
+        embedder: &dyn Embedder,
+        mut on_progress: F,
+    ) -> Result<IndexFileOutcome>
+    where
+        F: FnMut(usize, usize) + Send,
+    {
+        // 模型一致性：空索引直接采用当前模型，否则拒绝混入其他模型的向量
+        {
+            let mut manifest = self.manifest.write();
+            if manifest.chunks.is_empty() {
+                manifest.embedding_model = self.config.embedding.model_name.clone();
+                manifest.vector_dimension = self.config.embedding.dimension;
+            }
+        }
+        self.check_embedding_model()?;
+
+        let file_path = prepared.path.as_path();
+
+        // 旧块清理（如果已存在）
         {
             let guard = self.manifest.read();
             let existing_ids: Vec<_> = guard
@@ -108,29 +348,42 @@ impl IndexManager {
             }
         }
 
-        // 3. 分块
-        let chunker = TextChunker::new(self.config.embedding.chunk_size);
-        let chunks: Vec<Chunk> = chunker.chunk(&content, file_path)?;
-
+        let chunks = &prepared.chunks;
         if chunks.is_empty() {
-            return Ok(IndexFileOutcome { indexed_chunks: 0 });
+            return Ok(IndexFileOutcome {
+                filtered: prepared.filtered,
+                ..IndexFileOutcome::default()
+            });
         }
 
-        // 4. 生成嵌入（分批 + 进度）
-        const EMBED_BATCH_SIZE: usize = 64;
+        // 生成嵌入（分批 + 进度）
         let total_chunks = chunks.len();
         let mut embeddings: Vec<Vec<f32>> = Vec::with_capacity(total_chunks);
         let mut done_chunks = 0usize;
+        let mut retries = 0u32;
+        let mut embed_batches = 0u32;
+        let embed_started = std::time::Instant::now();
         on_progress(0, total_chunks);
 
-        while done_chunks < total_chunks {
-            let end = (done_chunks + EMBED_BATCH_SIZE).min(total_chunks);
-            let texts: Vec<&str> = chunks[done_chunks..end]
-                .iter()
-                .map(|c| c.content.as_str())
-                .collect();
+        // 最多 max_concurrent_requests 个批次同时请求，按批次顺序取回结果；
+        // 每个批次独立重试，任一批次最终失败时未完成的请求随之取消
+        let retry = &self.config.embed_retry;
+        // 流中只保存批次起点，文本在批次开始请求时才复制，避免闭包参数借用块切片
+        let mut batches = stream::iter((0..total_chunks).step_by(EMBED_BATCH_SIZE))
+            .map(|start| {
+                let end = (start + EMBED_BATCH_SIZE).min(total_chunks);
+                let texts = chunks[start..end]
+                    .iter()
+                    .map(|c| c.content.clone())
+                    .collect();
+                embed_owned_batch(embedder, texts, retry)
+            })
+            .buffered(self.config.embedding.max_concurrent_requests.max(1));
 
-            let mut batch = embedder.embed(&texts).await?;
+        while let Some(result) = batches.next().await {
+            let (mut batch, batch_retries) = result?;
+            retries += batch_retries;
+            embed_batches += 1;
             if batch.is_empty() {
                 return Err(VectorDbError::Embedding("No embeddings returned".into()));
             }
@@ -152,13 +405,28 @@ impl IndexManager {
             done_chunks = embeddings.len();
             on_progress(done_chunks, total_chunks);
         }
+        drop(batches);
+        if done_chunks != total_chunks {
+            return Err(VectorDbError::Embedding(format!(
+                "Expected {} embeddings, got {}",
+                total_chunks, done_chunks
+            )));
+        }
 
-        // 5. 写入索引与清单
+        let embed_time = embed_started.elapsed();
+
+        // 写入索引与清单（可选附带 git blame，每个文件只调用一次）
+        let write_started = std::time::Instant::now();
+        let blame = if self.config.include_git_metadata {
+            blame_file(file_path, &prepared.content_hash).await
+        } else {
+            None
+        };
         let mut file_vectors: Vec<(crate::vector_db::core::ChunkId, Vec<f32>)> =
             Vec::with_capacity(total_chunks);
         {
             let mut manifest = self.manifest.write();
-            manifest.add_file(file_path.to_path_buf(), file_hash);
+            manifest.add_file(file_path.to_path_buf(), prepared.content_hash.clone());
             for (chunk, vecf) in chunks.iter().zip(embeddings.into_iter()) {
                 let chunk_hash = blake3_hash_bytes(chunk.content.as_bytes());
                 let metadata = ChunkMetadata {
@@ -166,6 +434,10 @@ impl IndexManager {
                     span: chunk.span.clone(),
                     chunk_type: chunk.chunk_type.clone(),
                     hash: chunk_hash,
+                    symbol: chunk.symbol.clone(),
+                    git: blame
+                        .as_ref()
+                        .and_then(|b| b.range(chunk.span.line_start, chunk.span.line_end)),
                 };
                 // 收集向量数据
                 file_vectors.push((chunk.id, vecf));
@@ -177,20 +449,25 @@ impl IndexManager {
         // 一次性保存该文件的所有向量
         self.store.save_file_vectors(file_path, &file_vectors)?;
 
-        // 6. 文件元数据保存
+        // 文件元数据保存
         let file_meta = crate::vector_db::core::FileMetadata::new(
             file_path.to_path_buf(),
-            blake3_hash_bytes(content.as_bytes()),
-            last_modified as u64,
-            meta.len(),
+            prepared.content_hash.clone(),
+            prepared.last_modified,
+            prepared.size,
         );
         self.store.save_file_metadata(&file_meta)?;
 
-        // 7. 保存清单
+        // 保存清单
         self.save_manifest()?;
 
         Ok(IndexFileOutcome {
             indexed_chunks: total_chunks,
+            retries,
+            filtered: prepared.filtered,
+            embed_batches,
+            embed_time,
+            write_time: write_started.elapsed(),
         })
     }
 
@@ -199,8 +476,6 @@ impl IndexManager {
         file_paths: &[PathBuf],
         embedder: &dyn Embedder,
     ) -> Result<()> {
-        use futures::stream::{self, StreamExt};
-
         // 收集所有需要索引的文件
         let mut files_to_index = Vec::new();
         for p in file_paths {
@@ -212,13 +487,19 @@ impl IndexManager {
             }
         }
 
-        // 并行索引（最多 4 个并发任务）
-        let concurrency = 4;
-        let results: Vec<Result<()>> = stream::iter(files_to_index)
-            .map(|file_path| async move { self.index_file_with(&file_path, embedder).await })
-            .buffer_unordered(concurrency)
-            .collect()
-            .await;
+        // 分块在阻塞线程池中并发进行，之后最多 4 个文件同时 embedding
+        let results: Vec<Result<()>> =
+            Self::prepare_files(Arc::new(self.config.clone()), files_to_index)
+                .map(|(_, prepared)| async move {
+                    if let Some(file) = prepared? {
+                        self.index_prepared(&file, embedder, |_done, _total| {})
+                            .await?;
+                    }
+                    Ok(())
+                })
+                .buffer_unordered(4)
+                .collect()
+                .await;
 
         // 检查是否有错误
         for result in results {
@@ -232,6 +513,98 @@ impl IndexManager {
         self.index_file_with(file_path, embedder).await
     }
 
+    /// 处理文件重命名：内容哈希与旧路径一致时只迁移向量并更新块路径，
+    /// 否则删除旧路径的数据并重新索引新路径
+    pub async fn handle_file_renamed(
+        &self,
+        old_path: &Path,
+        new_path: &Path,
+        embedder: &dyn Embedder,
+    ) -> Result<RenameOutcome> {
+        // 覆盖了已索引的文件时先清掉目标路径的旧数据
+        if old_path != new_path && self.manifest.read().files.contains_key(new_path) {
+            self.remove_file(new_path)?;
+        }
+        if let Some(chunks) = self.try_move_file(old_path, new_path)? {
+            self.manifest.write().rename_stats.path_only += 1;
+            self.save_manifest()?;
+            return Ok(RenameOutcome::PathOnly { chunks });
+        }
+
+        if self.manifest.read().files.contains_key(old_path) {
+            self.remove_file(old_path)?;
+        }
+        let outcome = self
+            .index_file_with_progress(new_path, embedder, |_done, _total| {})
+            .await?;
+        self.manifest.write().rename_stats.reindexed += 1;
+        self.save_manifest()?;
+        Ok(RenameOutcome::Reindexed {
+            chunks: outcome.indexed_chunks,
+            retries: outcome.retries,
+        })
+    }
+
+    /// 新路径内容与旧路径索引时一致则迁移向量与清单，返回迁移的块数；不满足条件时返回 None
+    fn try_move_file(&self, old_path: &Path, new_path: &Path) -> Result<Option<usize>> {
+        let Some(old_hash) = self.manifest.read().files.get(old_path).cloned() else {
+            return Ok(None);
+        };
+        let meta = std::fs::metadata(new_path).map_err(VectorDbError::Io)?;
+        if !meta.is_file() || meta.len() > self.config.max_file_size {
+            return Ok(None);
+        }
+        let content = std::fs::read(new_path).map_err(VectorDbError::Io)?;
+        let new_hash = blake3_hash_bytes(&content);
+        if new_hash != old_hash {
+            return Ok(None);
+        }
+        let Ok(vectors) = self.store.load_file_vectors(old_path) else {
+            return Ok(None);
+        };
+
+        let file_vectors: Vec<_> = vectors.chunks.into_iter().collect();
+        self.store.save_file_vectors(new_path, &file_vectors)?;
+        self.store.delete_file_data(old_path)?;
+        let last_modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.store
+            .save_file_metadata(&crate::vector_db::core::FileMetadata::new(
+                new_path.to_path_buf(),
+                new_hash.clone(),
+                last_modified,
+                meta.len(),
+            ))?;
+
+        let moved = {
+            let mut manifest = self.manifest.write();
+            let mut moved = 0;
+            for metadata in manifest.chunks.values_mut() {
+                if metadata.file_path == old_path {
+                    metadata.file_path = new_path.to_path_buf();
+                    moved += 1;
+                }
+            }
+            manifest.files.remove(old_path);
+            manifest.add_file(new_path.to_path_buf(), new_hash);
+            moved
+        };
+        self.save_manifest()?;
+        Ok(Some(moved))
+    }
+
+    pub fn rename_stats(&self) -> RenameStats {
+        self.manifest.read().rename_stats
+    }
+
+    pub fn contains_file(&self, file_path: &Path) -> bool {
+        self.manifest.read().files.contains_key(file_path)
+    }
+
     pub fn remove_file(&self, file_path: &Path) -> Result<()> {
         // 删除该文件的向量文件
         let _ = self.store.delete_file_vectors(file_path);
@@ -251,16 +624,45 @@ impl IndexManager {
         Ok(())
     }
 
-    pub async fn rebuild(&self, root: &Path, embedder: &dyn Embedder) -> Result<()> {
-        // 重置清单
+    /// 删除指定的块（向量与清单），返回实际删除的数量；文件本身仍保留在清单中
+    pub fn remove_chunks(&self, chunk_ids: &[crate::vector_db::core::ChunkId]) -> Result<usize> {
+        let mut by_file: HashMap<PathBuf, Vec<crate::vector_db::core::ChunkId>> = HashMap::new();
         {
+            let manifest = self.manifest.read();
+            for id in chunk_ids {
+                if let Some(metadata) = manifest.chunks.get(id) {
+                    by_file
+                        .entry(metadata.file_path.clone())
+                        .or_default()
+                        .push(*id);
+                }
+            }
+        }
+
+        let mut removed = 0;
+        for (file_path, ids) in by_file {
+            let mut file_vectors = self.store.load_file_vectors(&file_path)?;
+            for id in &ids {
+                file_vectors.chunks.remove(id);
+            }
+            let remaining: Vec<_> = file_vectors.chunks.into_iter().collect();
+            self.store.save_file_vectors(&file_path, &remaining)?;
+
             let mut manifest = self.manifest.write();
-            *manifest = IndexManifest::new(
-                self.config.embedding.model_name.clone(),
-                self.config.embedding.dimension,
-            );
+            for id in &ids {
+                manifest.remove_chunk(id);
+            }
+            removed += ids.len();
         }
-        self.save_manifest()?;
+
+        if removed > 0 {
+            self.save_manifest()?;
+        }
+        Ok(removed)
+    }
+
+    pub async fn rebuild(&self, root: &Path, embedder: &dyn Embedder) -> Result<()> {
+        self.reset()?;
 
         let files = collect_source_files(root, self.config.max_file_size);
         self.index_files_with(&files, embedder).await
@@ -274,6 +676,61 @@ impl IndexManager {
             embedding_model: manifest.embedding_model.clone(),
             vector_dimension: manifest.vector_dimension,
             size_bytes: 0,
+            chunk_sizes: self.config.embedding.effective_chunk_sizes(),
+            rename_stats: self.rename_stats(),
+        }
+    }
+
+    /// 按路径排序分页列出已索引文件；prefix 为相对路径时以项目根目录为基准
+    pub fn list_indexed_files(
+        &self,
+        prefix: Option<&Path>,
+        limit: usize,
+        offset: usize,
+    ) -> IndexedFilePage {
+        let prefix = prefix.map(|p| {
+            if p.is_absolute() {
+                p.to_path_buf()
+            } else {
+                self.store.project_root().join(p)
+            }
+        });
+
+        // 只读清单中的块元数据，不加载向量
+        let mut counts: HashMap<PathBuf, usize> = HashMap::new();
+        {
+            let manifest = self.manifest.read();
+            for path in manifest.files.keys() {
+                counts.insert(path.clone(), 0);
+            }
+            for metadata in manifest.chunks.values() {
+                *counts.entry(metadata.file_path.clone()).or_insert(0) += 1;
+            }
+        }
+
+        let mut paths: Vec<(PathBuf, usize)> = counts
+            .into_iter()
+            .filter(|(path, _)| prefix.as_ref().is_none_or(|p| path.starts_with(p)))
+            .collect();
+        paths.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let total = paths.len();
+        let files = paths
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(path, chunk_count)| IndexedFileEntry {
+                last_indexed_at: self.store.vectors_written_at(&path),
+                exists: path.is_file(),
+                file_path: path.to_string_lossy().to_string(),
+                chunk_count,
+            })
+            .collect();
+
+        IndexedFilePage {
+            total,
+            offset,
+            files,
         }
     }
 
@@ -305,6 +762,24 @@ impl IndexManager {
     }
 }
 
+#[derive(Debug, serde::Serialize)]
+pub struct IndexedFileEntry {
+    pub file_path: String,
+    pub chunk_count: usize,
+    /// 最近一次写入向量的时间（Unix 秒），向量文件缺失时为 None
+    pub last_indexed_at: Option<u64>,
+    /// 源文件是否仍存在；false 表示索引中残留了已删除文件
+    pub exists: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct IndexedFilePage {
+    /// 过滤后的文件总数
+    pub total: usize,
+    pub offset: usize,
+    pub files: Vec<IndexedFileEntry>,
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct IndexStatus {
     pub total_files: usize,
@@ -312,4 +787,325 @@ pub struct IndexStatus {
     pub embedding_model: String,
     pub vector_dimension: usize,
     pub size_bytes: u64,
+    /// 当前配置下实际生效的分块大小
+    pub chunk_sizes: crate::vector_db::core::EffectiveChunkSizes,
+    /// 本次运行中重命名文件的处理统计
+    pub rename_stats: RenameStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+    /// 前 failures 次返回指定错误的 embedder
+    struct FlakyEmbedder {
+        failures: u32,
+        calls: AtomicU32,
+        retryable: bool,
+    }
+
+    #[async_trait]
+    impl Embedder for FlakyEmbedder {
+        fn id(&self) -> &str {
+            "flaky"
+        }
+
+        fn dim(&self) -> usize {
+            2
+        }
+
+        fn model_name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(if self.retryable {
+                    VectorDbError::EmbeddingUnavailable("timeout".into())
+                } else {
+                    VectorDbError::InvalidDimension {
+                        expected: 2,
+                        actual: 3,
+                    }
+                });
+            }
+            Ok(texts.iter().map(|_| vec![0.0, 1.0]).collect())
+        }
+    }
+
+    fn retry_config(max_attempts: u32) -> EmbedRetryConfig {
+        EmbedRetryConfig {
+            max_attempts,
+            base_delay_ms: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures_until_success() {
+        let embedder = FlakyEmbedder {
+            failures: 2,
+            calls: AtomicU32::new(0),
+            retryable: true,
+        };
+        let (batch, retries) = embed_with_retry(&embedder, &["a", "b"], &retry_config(3))
+            .await
+            .unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(retries, 2);
+
+        let embedder = FlakyEmbedder {
+            failures: 5,
+            calls: AtomicU32::new(0),
+            retryable: true,
+        };
+        assert!(embed_with_retry(&embedder, &["a"], &retry_config(3))
+            .await
+            .is_err());
+        assert_eq!(embedder.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_fatal_errors() {
+        let embedder = FlakyEmbedder {
+            failures: 1,
+            calls: AtomicU32::new(0),
+            retryable: false,
+        };
+        let err = embed_with_retry(&embedder, &["a"], &retry_config(5))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VectorDbError::InvalidDimension { .. }));
+        assert_eq!(embedder.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rename_reuses_vectors_when_content_is_unchanged() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        let mut config = VectorDbConfig::default();
+        config.embedding.dimension = 2;
+        let manager = IndexManager::new(root, config).unwrap();
+        let embedder = FlakyEmbedder {
+            failures: 0,
+            calls: AtomicU32::new(0),
+            retryable: true,
+        };
+
+        let old_path = root.join("a.rs");
+        std::fs::write(&old_path, "fn a() {\n    println!(\"a\");\n}\n").unwrap();
+        manager.index_file_with(&old_path, &embedder).await.unwrap();
+        let calls = embedder.calls.load(Ordering::SeqCst);
+        assert!(calls > 0);
+
+        let new_path = root.join("b.rs");
+        std::fs::rename(&old_path, &new_path).unwrap();
+        let outcome = manager
+            .handle_file_renamed(&old_path, &new_path, &embedder)
+            .await
+            .unwrap();
+        assert!(matches!(outcome, RenameOutcome::PathOnly { chunks } if chunks > 0));
+        assert_eq!(embedder.calls.load(Ordering::SeqCst), calls);
+        assert!(manager
+            .manifest
+            .read()
+            .get_file_chunks(&old_path)
+            .is_empty());
+        assert!(!manager
+            .manifest
+            .read()
+            .get_file_chunks(&new_path)
+            .is_empty());
+        assert!(manager.store().load_file_vectors(&new_path).is_ok());
+        assert!(manager.store().load_file_vectors(&old_path).is_err());
+
+        let moved_path = root.join("c.rs");
+        std::fs::write(&moved_path, "fn c() {\n    println!(\"changed\");\n}\n").unwrap();
+        std::fs::remove_file(&new_path).unwrap();
+        let outcome = manager
+            .handle_file_renamed(&new_path, &moved_path, &embedder)
+            .await
+            .unwrap();
+        assert!(matches!(outcome, RenameOutcome::Reindexed { .. }));
+        assert!(embedder.calls.load(Ordering::SeqCst) > calls);
+        assert!(manager
+            .manifest
+            .read()
+            .get_file_chunks(&new_path)
+            .is_empty());
+        assert_eq!(
+            manager.rename_stats(),
+            RenameStats {
+                path_only: 1,
+                reindexed: 1
+            }
+        );
+
+        // 统计保存在清单中，重新打开索引后仍然保留
+        drop(manager);
+        let reopened = IndexManager::new(root, VectorDbConfig::default()).unwrap();
+        assert_eq!(reopened.rename_stats().path_only, 1);
+        assert_eq!(reopened.rename_stats().reindexed, 1);
+    }
+
+    #[test]
+    fn lists_indexed_files_with_prefix_and_pagination() {
+        use crate::vector_db::core::{ChunkId, ChunkType, Span};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.rs"), "fn a() {}").unwrap();
+        std::fs::write(root.join("src/b.rs"), "fn b() {}").unwrap();
+
+        let manager = IndexManager::new(root, VectorDbConfig::default()).unwrap();
+        {
+            let mut manifest = manager.manifest.write();
+            for (name, chunks) in [("src/a.rs", 2), ("src/b.rs", 1), ("gone.rs", 1)] {
+                let path = root.join(name);
+                manifest.add_file(path.clone(), String::new());
+                for i in 0..chunks {
+                    manifest.add_chunk(
+                        ChunkId::new_v4(),
+                        ChunkMetadata {
+                            file_path: path.clone(),
+                            span: Span::new(0, 1, i + 1, i + 1),
+                            chunk_type: ChunkType::Generic,
+                            hash: String::new(),
+                            symbol: None,
+                            git: None,
+                        },
+                    );
+                }
+            }
+        }
+
+        let all = manager.list_indexed_files(None, 10, 0);
+        assert_eq!(all.total, 3);
+        assert!(all.files[0].file_path.ends_with("gone.rs"));
+        assert!(!all.files[0].exists);
+
+        let page = manager.list_indexed_files(Some(Path::new("src")), 1, 1);
+        assert_eq!(page.total, 2);
+        assert_eq!(page.files.len(), 1);
+        assert!(page.files[0].file_path.ends_with("b.rs"));
+        assert_eq!(page.files[0].chunk_count, 1);
+        assert!(page.files[0].exists);
+    }
+
+    #[tokio::test]
+    async fn prepares_files_concurrently_in_input_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = Vec::new();
+        for i in 0..12 {
+            let path = dir.path().join(format!("f{i}.rs"));
+            std::fs::write(&path, format!("fn f{i}() {{\n    let x = {i};\n}}\n")).unwrap();
+            files.push(path);
+        }
+        let missing = dir.path().join("missing.rs");
+        files.insert(5, missing.clone());
+
+        let mut config = VectorDbConfig::default();
+        config.parsing.max_concurrent_files = 3;
+        config.parsing.max_buffered_files = 4;
+        let results: Vec<_> = IndexManager::prepare_files(Arc::new(config), files.clone())
+            .collect()
+            .await;
+
+        let order: Vec<_> = results.iter().map(|(path, _)| path.clone()).collect();
+        assert_eq!(order, files);
+        for (path, result) in &results {
+            if path == &missing {
+                assert!(result.is_err());
+            } else {
+                let prepared = result.as_ref().unwrap().as_ref().unwrap();
+                assert_eq!(&prepared.path, path);
+                assert!(!prepared.chunks.is_empty());
+            }
+        }
+    }
+
+    /// 记录同时进行的请求数，向量第一维为输入文本解析出的序号
+    #[derive(Default)]
+    struct ConcurrencyProbe {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Embedder for ConcurrencyProbe {
+        fn id(&self) -> &str {
+            "probe"
+        }
+
+        fn dim(&self) -> usize {
+            2
+        }
+
+        fn model_name(&self) -> &str {
+            "probe"
+        }
+
+        async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(texts
+                .iter()
+                .map(|t| vec![t.parse::<f32>().unwrap(), 0.0])
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn embeds_batches_concurrently_in_order() {
+        use crate::vector_db::core::{ChunkType, Span};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.rs");
+        std::fs::write(&path, "// big").unwrap();
+        let mut config = VectorDbConfig::default();
+        config.embedding.dimension = 2;
+        config.embedding.max_concurrent_requests = 3;
+        let manager = IndexManager::new(dir.path(), config).unwrap();
+
+        let chunks: Vec<Chunk> = (0..EMBED_BATCH_SIZE * 4 + 1)
+            .map(|i| {
+                Chunk::new(
+                    path.clone(),
+                    Span::new(0, 1, i + 1, i + 1),
+                    i.to_string(),
+                    ChunkType::Generic,
+                )
+            })
+            .collect();
+        let prepared = PreparedFile {
+            path: path.clone(),
+            content_hash: "hash".into(),
+            last_modified: 0,
+            size: 6,
+            chunks: chunks.clone(),
+            filtered: ChunkFilterStats::default(),
+        };
+
+        let embedder = ConcurrencyProbe::default();
+        let mut progress = Vec::new();
+        let outcome = manager
+            .index_prepared(&prepared, &embedder, |done, _| progress.push(done))
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.indexed_chunks, chunks.len());
+        assert_eq!(outcome.embed_batches, 5);
+        let peak = embedder.peak.load(Ordering::SeqCst);
+        assert!(peak > 1 && peak <= 3, "peak concurrency {peak}");
+        assert!(progress.windows(2).all(|w| w[0] <= w[1]));
+
+        let vectors = manager.store().load_file_vectors(&path).unwrap();
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(vectors.chunks[&chunk.id][0], i as f32);
+        }
+    }
 }
@@ -0,0 +1,137 @@
+//! IndexManager 句柄池
+//!
+//! 按工作区根目录复用已打开的 IndexManager，避免每次搜索都重新读取 manifest。
+//! 取用时做一次轻量健康检查：manifest 被改写（重建/增量更新）、被删除或 embedding 配置变化时透明地重新打开。
+
+use super::IndexManager;
+use crate::vector_db::core::{Result, VectorDbConfig};
+use dashmap::DashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// manifest 文件的修改时间与大小，任一变化即视为索引已被其它写入方更新
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ManifestStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl ManifestStamp {
+    fn read(project_root: &Path) -> Option<Self> {
+        let meta = std::fs::metadata(project_root.join(".oxi").join("manifest.json")).ok()?;
+        Some(Self {
+            modified: meta.modified().ok()?,
+            len: meta.len(),
+        })
+    }
+}
+
+struct PooledManager {
+    manager: Arc<IndexManager>,
+    config_key: String,
+    stamp: ManifestStamp,
+}
+
+#[derive(Default)]
+pub struct IndexManagerPool {
+    entries: DashMap<PathBuf, PooledManager>,
+}
+
+fn config_key(config: &VectorDbConfig) -> String {
+    format!(
+        "{}:{}",
+        config.embedding.model_name, config.embedding.dimension
+    )
+}
+
+impl IndexManagerPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取工作区的 IndexManager；缓存的句柄失效时重新打开并替换
+    pub fn get(&self, project_root: &Path, config: &VectorDbConfig) -> Result<Arc<IndexManager>> {
+        let key = config_key(config);
+        let stamp = ManifestStamp::read(project_root);
+
+        if let (Some(stamp), Some(entry)) = (stamp, self.entries.get(project_root)) {
+            if entry.config_key == key && entry.stamp == stamp {
+                return Ok(Arc::clone(&entry.manager));
+            }
+        }
+
+        let manager = Arc::new(IndexManager::new(project_root, config.clone())?);
+        // 还没有 manifest 的索引不入池，下次仍然重新打开
+        match stamp {
+            Some(stamp) => {
+                self.entries.insert(
+                    project_root.to_path_buf(),
+                    PooledManager {
+                        manager: Arc::clone(&manager),
+                        config_key: key,
+                        stamp,
+                    },
+                );
+            }
+            None => {
+                self.entries.remove(project_root);
+            }
+        }
+        Ok(manager)
+    }
+
+    pub fn invalidate(&self, project_root: &Path) {
+        self.entries.remove(project_root);
+    }
+
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_db::storage::IndexManifest;
+    use tempfile::TempDir;
+
+    fn write_manifest(root: &Path, config: &VectorDbConfig) {
+        std::fs::create_dir_all(root.join(".oxi")).unwrap();
+        IndexManifest::new(
+            config.embedding.model_name.clone(),
+            config.embedding.dimension,
+        )
+        .save(&root.join(".oxi").join("manifest.json"))
+        .unwrap();
+    }
+
+    #[test]
+    fn reuses_manager_until_manifest_or_config_changes() {
+        let dir = TempDir::new().unwrap();
+        let config = VectorDbConfig::default();
+        write_manifest(dir.path(), &config);
+
+        let pool = IndexManagerPool::new();
+        let first = pool.get(dir.path(), &config).unwrap();
+        let second = pool.get(dir.path(), &config).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let mut other = config.clone();
+        other.embedding.dimension += 1;
+        let third = pool.get(dir.path(), &other).unwrap();
+        assert!(!Arc::ptr_eq(&second, &third));
+
+        std::fs::remove_file(dir.path().join(".oxi").join("manifest.json")).unwrap();
+        let _ = pool.get(dir.path(), &other).unwrap();
+        assert!(pool.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::vector_db::core::{ChunkId, ChunkType, Result, Span};
+use crate::vector_db::core::{ChunkId, ChunkType, Result, Span, VectorDbError};
+use crate::vector_db::utils::GitChunkMetadata;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -26,6 +27,23 @@ pub struct IndexManifest {
 
     /// 块索引映射 (块 ID -> 块元数据)
     pub chunks: HashMap<ChunkId, ChunkMetadata>,
+
+    /// 构建索引时的工作区根目录，用于把结果路径转换为相对路径；旧版清单中不存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_root: Option<PathBuf>,
+
+    /// 重命名处理次数，保存在清单中，索引管理器被重新打开后仍然保留
+    #[serde(default)]
+    pub rename_stats: RenameStats,
+}
+
+/// 重命名处理次数统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenameStats {
+    /// 只更新路径、未调用 embedding 的次数
+    pub path_only: usize,
+    /// 内容变化后重新 embedding 的次数
+    pub reindexed: usize,
 }
 
 /// 块元数据
@@ -35,6 +53,47 @@ pub struct ChunkMetadata {
     pub span: Span,
     pub chunk_type: ChunkType,
     pub hash: String,
+    /// 所属符号名，旧版清单中不存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// 最近一次提交信息，仅启用 include_git_metadata 时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitChunkMetadata>,
+}
+
+/// 单个工作区索引的模型与规模摘要
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexCollection {
+    pub workspace_path: PathBuf,
+    pub embedding_model: String,
+    pub vector_dimension: usize,
+    pub total_files: usize,
+    pub total_chunks: usize,
+    pub updated_at: u64,
+    /// 是否可被当前 embedding 模型直接使用
+    pub matches_active_model: bool,
+}
+
+impl IndexCollection {
+    pub fn from_manifest(
+        workspace_path: PathBuf,
+        manifest: &IndexManifest,
+        active_model: &str,
+        active_dimension: usize,
+        accepted_models: &[String],
+    ) -> Self {
+        Self {
+            workspace_path,
+            embedding_model: manifest.embedding_model.clone(),
+            vector_dimension: manifest.vector_dimension,
+            total_files: manifest.files.len(),
+            total_chunks: manifest.chunks.len(),
+            updated_at: manifest.updated_at,
+            matches_active_model: manifest
+                .check_model_accepting(active_model, active_dimension, accepted_models)
+                .is_ok(),
+        }
+    }
 }
 
 impl IndexManifest {
@@ -53,6 +112,8 @@ impl IndexManifest {
             vector_dimension,
             files: HashMap::new(),
             chunks: HashMap::new(),
+            workspace_root: None,
+            rename_stats: RenameStats::default(),
         }
     }
 
@@ -70,6 +131,37 @@ impl IndexManifest {
         Ok(())
     }
 
+    /// 检查清单记录的模型与当前模型是否一致；空索引视为一致。
+    /// 旧版清单可能没有记录模型名，此时只比较维度
+    pub fn check_model(&self, model: &str, dimension: usize) -> Result<()> {
+        self.check_model_accepting(model, dimension, &[])
+    }
+
+    /// 同 check_model，但 accepted 中的模型（用户显式选择过的替换关系）构建的索引也视为一致
+    pub fn check_model_accepting(
+        &self,
+        model: &str,
+        dimension: usize,
+        accepted: &[String],
+    ) -> Result<()> {
+        if self.chunks.is_empty() {
+            return Ok(());
+        }
+        let model_differs = !self.embedding_model.is_empty()
+            && !model.is_empty()
+            && self.embedding_model != model
+            && !accepted.contains(&self.embedding_model);
+        if model_differs || self.vector_dimension != dimension {
+            return Err(VectorDbError::EmbeddingModelMismatch {
+                indexed_model: self.embedding_model.clone(),
+                indexed_dimension: self.vector_dimension,
+                active_model: model.to_string(),
+                active_dimension: dimension,
+            });
+        }
+        Ok(())
+    }
+
     /// 添加文件
     pub fn add_file(&mut self, file_path: PathBuf, file_hash: String) {
         self.files.insert(file_path, file_hash);
@@ -114,3 +206,49 @@ impl IndexManifest {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_model_and_dimension_changes() {
+        let mut manifest = IndexManifest::new("text-embedding-3-small".into(), 1536);
+        assert!(manifest.check_model("bge-m3", 1024).is_ok());
+
+        manifest.add_chunk(
+            ChunkId::new_v4(),
+            ChunkMetadata {
+                file_path: PathBuf::from("a.rs"),
+                span: Span::new(0, 1, 1, 1),
+                chunk_type: ChunkType::Generic,
+                hash: String::new(),
+                symbol: None,
+                git: None,
+            },
+        );
+        assert!(manifest.check_model("text-embedding-3-small", 1536).is_ok());
+        assert!(matches!(
+            manifest.check_model("bge-m3", 1024),
+            Err(VectorDbError::EmbeddingModelMismatch {
+                indexed_dimension: 1536,
+                ..
+            })
+        ));
+        // 同维度的不同模型向量空间也不兼容
+        assert!(manifest.check_model("other-1536", 1536).is_err());
+
+        // 显式接受的替换模型可以使用同维度的索引
+        let accepted = vec!["text-embedding-3-small".to_string()];
+        assert!(manifest
+            .check_model_accepting("other-1536", 1536, &accepted)
+            .is_ok());
+        assert!(manifest
+            .check_model_accepting("other-1536", 768, &accepted)
+            .is_err());
+
+        manifest.embedding_model.clear();
+        assert!(manifest.check_model("other-1536", 1536).is_ok());
+        assert!(manifest.check_model("other-1536", 768).is_err());
+    }
+}
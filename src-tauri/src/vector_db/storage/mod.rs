@@ -1,7 +1,15 @@
+pub mod agent_writes;
+pub mod estimate;
 pub mod file_store;
 pub mod index_manager;
+pub mod manager_pool;
 pub mod manifest;
+pub mod throughput;
 
+pub use agent_writes::*;
+pub use estimate::*;
 pub use file_store::*;
 pub use index_manager::*;
+pub use manager_pool::IndexManagerPool;
 pub use manifest::*;
+pub use throughput::*;
@@ -0,0 +1,230 @@
+//! 索引构建吞吐统计
+//!
+//! 构建循环按文件顺序执行，把每个文件的等待分块、embedding 与写入耗时交给 `ThroughputTracker`。
+//! 统计只在构建任务内部累加，不加锁；滑动窗口内的速率随进度推送，构建结束时给出汇总，
+//! 用于判断瓶颈在分块、embedding 服务还是索引写入。
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::IndexFileOutcome;
+
+/// 计算实时速率的滑动窗口
+pub const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+/// 滑动窗口内的实时速率
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildThroughput {
+    pub chunks_per_sec: f64,
+    /// 每秒写入索引的文件数
+    pub writes_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildBottleneck {
+    /// 构建循环主要在等待文件读取与分块
+    Parsing,
+    Embedding,
+    Writing,
+}
+
+/// 构建结束时的吞吐汇总
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildThroughputSummary {
+    pub provider: String,
+    pub elapsed_ms: u64,
+    pub files_written: u64,
+    pub chunks_embedded: u64,
+    pub embed_batches: u64,
+    /// 配置的每批块数上限
+    pub configured_batch_size: usize,
+    /// 实际每批的平均块数（文件末尾的批次通常不满）
+    pub avg_batch_size: f64,
+    pub avg_embed_latency_ms: f64,
+    pub avg_write_latency_ms: f64,
+    pub chunks_per_sec: f64,
+    /// 等待分块、embedding、写入各自占用的总时长
+    pub parse_wait_ms: u64,
+    pub embed_ms: u64,
+    pub write_ms: u64,
+    pub bottleneck: Option<BuildBottleneck>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    at: Instant,
+    chunks: u64,
+    writes: u64,
+}
+
+#[derive(Debug)]
+pub struct ThroughputTracker {
+    started: Instant,
+    window: Duration,
+    samples: VecDeque<Sample>,
+    files_written: u64,
+    chunks_embedded: u64,
+    embed_batches: u64,
+    parse_wait: Duration,
+    embed_time: Duration,
+    write_time: Duration,
+}
+
+impl ThroughputTracker {
+    pub fn new(window: Duration) -> Self {
+        Self::starting_at(Instant::now(), window)
+    }
+
+    fn starting_at(started: Instant, window: Duration) -> Self {
+        let mut samples = VecDeque::new();
+        samples.push_back(Sample {
+            at: started,
+            chunks: 0,
+            writes: 0,
+        });
+        Self {
+            started,
+            window,
+            samples,
+            files_written: 0,
+            chunks_embedded: 0,
+            embed_batches: 0,
+            parse_wait: Duration::ZERO,
+            embed_time: Duration::ZERO,
+            write_time: Duration::ZERO,
+        }
+    }
+
+    /// 记录构建循环等待下一个已分块文件的时长
+    pub fn record_parse_wait(&mut self, waited: Duration) {
+        self.parse_wait += waited;
+    }
+
+    /// 记录一个成功索引的文件
+    pub fn record_file(&mut self, outcome: &IndexFileOutcome) {
+        self.record_file_at(Instant::now(), outcome);
+    }
+
+    fn record_file_at(&mut self, now: Instant, outcome: &IndexFileOutcome) {
+        self.chunks_embedded += outcome.indexed_chunks as u64;
+        self.embed_batches += u64::from(outcome.embed_batches);
+        self.embed_time += outcome.embed_time;
+        self.write_time += outcome.write_time;
+        if outcome.indexed_chunks > 0 {
+            self.files_written += 1;
+        }
+
+        self.samples.push_back(Sample {
+            at: now,
+            chunks: self.chunks_embedded,
+            writes: self.files_written,
+        });
+        // 保留一个窗口外的样本作为起点
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].at) >= self.window {
+            self.samples.pop_front();
+        }
+    }
+
+    /// 以最近一次记录为终点的窗口速率
+    pub fn current(&self) -> BuildThroughput {
+        let (Some(first), Some(last)) = (self.samples.front(), self.samples.back()) else {
+            return BuildThroughput::default();
+        };
+        let secs = last.at.duration_since(first.at).as_secs_f64();
+        if secs <= 0.0 {
+            return BuildThroughput::default();
+        }
+        BuildThroughput {
+            chunks_per_sec: (last.chunks - first.chunks) as f64 / secs,
+            writes_per_sec: (last.writes - first.writes) as f64 / secs,
+        }
+    }
+
+    pub fn summary(&self, provider: &str, configured_batch_size: usize) -> BuildThroughputSummary {
+        let elapsed = self.started.elapsed();
+        let per = |total: Duration, count: u64| {
+            if count == 0 {
+                0.0
+            } else {
+                total.as_secs_f64() * 1000.0 / count as f64
+            }
+        };
+        let stages = [
+            (BuildBottleneck::Parsing, self.parse_wait),
+            (BuildBottleneck::Embedding, self.embed_time),
+            (BuildBottleneck::Writing, self.write_time),
+        ];
+        let bottleneck = stages
+            .iter()
+            .filter(|(_, spent)| !spent.is_zero())
+            .max_by_key(|(_, spent)| *spent)
+            .map(|(stage, _)| *stage);
+
+        BuildThroughputSummary {
+            provider: provider.to_string(),
+            elapsed_ms: elapsed.as_millis() as u64,
+            files_written: self.files_written,
+            chunks_embedded: self.chunks_embedded,
+            embed_batches: self.embed_batches,
+            configured_batch_size,
+            avg_batch_size: if self.embed_batches == 0 {
+                0.0
+            } else {
+                self.chunks_embedded as f64 / self.embed_batches as f64
+            },
+            avg_embed_latency_ms: per(self.embed_time, self.embed_batches),
+            avg_write_latency_ms: per(self.write_time, self.files_written),
+            chunks_per_sec: if elapsed.is_zero() {
+                0.0
+            } else {
+                self.chunks_embedded as f64 / elapsed.as_secs_f64()
+            },
+            parse_wait_ms: self.parse_wait.as_millis() as u64,
+            embed_ms: self.embed_time.as_millis() as u64,
+            write_ms: self.write_time.as_millis() as u64,
+            bottleneck,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(chunks: usize, batches: u32, embed_ms: u64, write_ms: u64) -> IndexFileOutcome {
+        IndexFileOutcome {
+            indexed_chunks: chunks,
+            embed_batches: batches,
+            embed_time: Duration::from_millis(embed_ms),
+            write_time: Duration::from_millis(write_ms),
+            ..IndexFileOutcome::default()
+        }
+    }
+
+    #[test]
+    fn window_rate_drops_old_samples_and_summary_finds_bottleneck() {
+        let start = Instant::now();
+        let mut tracker = ThroughputTracker::starting_at(start, Duration::from_secs(10));
+        tracker.record_file_at(start + Duration::from_secs(2), &outcome(100, 2, 800, 20));
+        tracker.record_file_at(start + Duration::from_secs(12), &outcome(40, 1, 300, 10));
+        tracker.record_file_at(start + Duration::from_secs(14), &outcome(60, 1, 350, 10));
+        tracker.record_parse_wait(Duration::from_millis(100));
+
+        // 窗口起点为第 2 秒的样本：(40 + 60) / 12s
+        let rate = tracker.current();
+        assert!((rate.chunks_per_sec - 100.0 / 12.0).abs() < 1e-9);
+        assert!((rate.writes_per_sec - 2.0 / 12.0).abs() < 1e-9);
+
+        let summary = tracker.summary("remote", 64);
+        assert_eq!(summary.chunks_embedded, 200);
+        assert_eq!(summary.embed_batches, 4);
+        assert!((summary.avg_batch_size - 50.0).abs() < 1e-9);
+        assert!((summary.avg_embed_latency_ms - 362.5).abs() < 1e-9);
+        assert_eq!(summary.bottleneck, Some(BuildBottleneck::Embedding));
+    }
+}
@@ -28,7 +28,8 @@ pub fn collect_source_files(root: &Path, max_size: u64) -> Vec<PathBuf> {
     files
 }
 
-fn filter_dirs(e: &DirEntry) -> bool {
+/// 索引扫描时跳过的目录（VCS、依赖、构建产物等），其余条目放行
+pub fn filter_dirs(e: &DirEntry) -> bool {
     let path = e.path();
     if !path.is_dir() {
         return true;
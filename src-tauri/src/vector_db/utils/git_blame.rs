@@ -0,0 +1,212 @@
+//! 索引时附加的 git 元数据
+//!
+//! 每个文件只调用一次 `git blame --porcelain`，再按 chunk 的行范围取最近一次提交。
+//! 结果按 (文件, 内容哈希) 缓存；非 git 目录、未跟踪文件或 git 不可用时返回 None。
+
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// 单个文件 blame 的超时，避免超大仓库拖慢索引
+const BLAME_TIMEOUT: Duration = Duration::from_secs(10);
+const BLAME_CACHE_CAPACITY: usize = 512;
+
+/// 未提交行的占位 commit
+const UNCOMMITTED_SHA: &str = "0000000000000000000000000000000000000000";
+
+/// chunk 行范围内最近一次提交的信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitChunkMetadata {
+    pub last_commit: String,
+    pub last_author: String,
+    /// 提交时间（Unix 秒）
+    pub last_modified: i64,
+}
+
+#[derive(Debug, Clone)]
+struct CommitInfo {
+    author: String,
+    author_time: i64,
+}
+
+/// 一个文件的逐行 blame 结果
+#[derive(Debug, Default)]
+pub struct FileBlame {
+    commits: HashMap<String, CommitInfo>,
+    /// 下标 i 对应第 i+1 行的 commit；未提交的行为 None
+    lines: Vec<Option<String>>,
+}
+
+impl FileBlame {
+    /// 解析 `git blame --porcelain` 输出
+    fn parse(output: &str) -> Self {
+        let mut blame = FileBlame::default();
+        let mut current: Option<String> = None;
+        let mut pending_author: Option<String> = None;
+
+        for line in output.lines() {
+            if line.starts_with('\t') {
+                // 行内容，已由 header 记录位置
+                continue;
+            }
+            let mut parts = line.split(' ');
+            let head = parts.next().unwrap_or_default();
+            if head.len() == 40 && head.bytes().all(|b| b.is_ascii_hexdigit()) {
+                let final_line = parts.nth(1).and_then(|n| n.parse::<usize>().ok());
+                let count = parts
+                    .next()
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .unwrap_or(1);
+                if let Some(final_line) = final_line.filter(|n| *n > 0) {
+                    current = Some(head.to_string());
+                    let sha = (head != UNCOMMITTED_SHA).then(|| head.to_string());
+                    let end = final_line + count - 1;
+                    if blame.lines.len() < end {
+                        blame.lines.resize(end, None);
+                    }
+                    for slot in &mut blame.lines[final_line - 1..end] {
+                        *slot = sha.clone();
+                    }
+                }
+                continue;
+            }
+            let Some(sha) = current.as_ref() else {
+                continue;
+            };
+            if let Some(author) = line.strip_prefix("author ") {
+                pending_author = Some(author.to_string());
+            } else if let Some(time) = line.strip_prefix("author-time ") {
+                if let (Some(author), Ok(author_time)) = (pending_author.take(), time.parse()) {
+                    blame.commits.insert(
+                        sha.clone(),
+                        CommitInfo {
+                            author,
+                            author_time,
+                        },
+                    );
+                }
+            }
+        }
+        blame
+    }
+
+    /// 行范围（1 起始，闭区间）内最近一次提交
+    pub fn range(&self, line_start: usize, line_end: usize) -> Option<GitChunkMetadata> {
+        let start = line_start.max(1) - 1;
+        let end = line_end.min(self.lines.len());
+        if start >= end {
+            return None;
+        }
+        self.lines[start..end]
+            .iter()
+            .flatten()
+            .filter_map(|sha| self.commits.get(sha).map(|info| (sha, info)))
+            .max_by_key(|(_, info)| info.author_time)
+            .map(|(sha, info)| GitChunkMetadata {
+                last_commit: sha.clone(),
+                last_author: info.author.clone(),
+                last_modified: info.author_time,
+            })
+    }
+}
+
+/// 文件路径与内容哈希
+type BlameCacheKey = (PathBuf, String);
+
+static BLAME_CACHE: Lazy<Mutex<LruCache<BlameCacheKey, Option<Arc<FileBlame>>>>> =
+    Lazy::new(|| {
+        Mutex::new(LruCache::new(
+            NonZeroUsize::new(BLAME_CACHE_CAPACITY).unwrap(),
+        ))
+    });
+
+/// 读取文件的 blame；content_hash 用作缓存键的一部分，内容变化后重新计算
+pub async fn blame_file(file_path: &Path, content_hash: &str) -> Option<Arc<FileBlame>> {
+    let key = (file_path.to_path_buf(), content_hash.to_string());
+    if let Some(cached) = BLAME_CACHE.lock().get(&key) {
+        return cached.clone();
+    }
+
+    let blame = run_blame(file_path).await.map(Arc::new);
+    BLAME_CACHE.lock().put(key, blame.clone());
+    blame
+}
+
+async fn run_blame(file_path: &Path) -> Option<FileBlame> {
+    let dir = file_path.parent()?;
+    let file_name = file_path.file_name()?;
+    let output = tokio::time::timeout(
+        BLAME_TIMEOUT,
+        Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["blame", "--porcelain", "--"])
+            .arg(file_name)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+
+    if !output.status.success() {
+        // 非 git 目录或未跟踪文件
+        tracing::debug!(
+            file = %file_path.display(),
+            "git blame 不可用: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    Some(FileBlame::parse(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA_A: &str = "1111111111111111111111111111111111111111";
+    const SHA_B: &str = "2222222222222222222222222222222222222222";
+
+    fn porcelain() -> String {
+        format!(
+            "{SHA_A} 1 1 2\nauthor Alice\nauthor-mail <a@x>\nauthor-time 100\nfilename lib.rs\n\tfn a() {{\n\
+             {SHA_A} 2 2\n\t}}\n\
+             {SHA_B} 3 3 1\nauthor Bob\nauthor-time 200\nfilename lib.rs\n\tfn b() {{}}\n\
+             {UNCOMMITTED_SHA} 4 4 1\nauthor Not Committed Yet\nauthor-time 300\nfilename lib.rs\n\t// wip\n"
+        )
+    }
+
+    #[test]
+    fn picks_latest_commit_in_range() {
+        let blame = FileBlame::parse(&porcelain());
+
+        let first = blame.range(1, 2).unwrap();
+        assert_eq!(first.last_commit, SHA_A);
+        assert_eq!(first.last_author, "Alice");
+        assert_eq!(first.last_modified, 100);
+
+        let all = blame.range(1, 4).unwrap();
+        assert_eq!(all.last_author, "Bob");
+        assert_eq!(all.last_modified, 200);
+
+        // 只有未提交的行
+        assert!(blame.range(4, 4).is_none());
+        assert!(blame.range(10, 12).is_none());
+    }
+
+    #[tokio::test]
+    async fn degrades_outside_git_repository() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "fn a() {}\n").unwrap();
+        assert!(blame_file(&file, "hash").await.is_none());
+    }
+}
@@ -1,5 +1,7 @@
 pub mod file_walker;
+pub mod git_blame;
 pub mod hash;
 
 pub use file_walker::*;
+pub use git_blame::{blame_file, GitChunkMetadata};
 pub use hash::*;
@@ -0,0 +1,187 @@
+// 窗口布局快照：按显示器配置保存/恢复各窗口的位置与大小
+
+use super::*;
+use crate::storage::repositories::AppPreferences;
+use crate::storage::DatabaseManager;
+use crate::utils::{EmptyData, TauriApiResult};
+use crate::{api_error, api_success};
+use std::collections::HashMap;
+use tauri::{Monitor, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+const LAYOUT_KEY_PREFIX: &str = "window.layout:";
+
+/// 单个窗口的外框位置与内容区大小（物理像素），与 set_position / set_size 的语义一致
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 持久化的布局
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowLayout {
+    /// 保存时的显示器配置指纹，恢复时必须一致
+    pub monitor_fingerprint: String,
+    /// 窗口 label -> 位置大小
+    pub windows: HashMap<String, WindowBounds>,
+}
+
+/// 恢复结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowLayoutRestoreResult {
+    /// false 表示显示器配置不一致，仅做了可见性修正
+    pub restored: bool,
+    pub window_count: usize,
+}
+
+fn layout_key(name: &str) -> String {
+    format!("{}{}", LAYOUT_KEY_PREFIX, name.trim())
+}
+
+/// 由所有显示器的名称、位置、分辨率和缩放组成的指纹，与枚举顺序无关
+fn monitor_fingerprint(monitors: &[Monitor]) -> String {
+    let mut parts: Vec<String> = monitors
+        .iter()
+        .map(|m| {
+            format!(
+                "{}@{},{}:{}x{}*{}",
+                m.name().map(String::as_str).unwrap_or("unknown"),
+                m.position().x,
+                m.position().y,
+                m.size().width,
+                m.size().height,
+                m.scale_factor()
+            )
+        })
+        .collect();
+    parts.sort();
+    parts.join("|")
+}
+
+fn current_bounds<R: Runtime>(window: &WebviewWindow<R>) -> Option<WindowBounds> {
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(WindowBounds {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+/// 保存当前所有窗口的布局
+#[tauri::command]
+pub async fn window_save_layout<R: Runtime>(
+    name: String,
+    app: AppHandle<R>,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<EmptyData> {
+    if name.trim().is_empty() {
+        return Ok(api_error!("window.layout_name_empty"));
+    }
+
+    let monitors = match app.available_monitors() {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            warn!("Failed to enumerate monitors: {}", e);
+            return Ok(api_error!("window.layout_save_failed"));
+        }
+    };
+
+    let windows: HashMap<String, WindowBounds> = app
+        .webview_windows()
+        .into_iter()
+        .filter_map(|(label, window)| current_bounds(&window).map(|b| (label, b)))
+        .collect();
+
+    let layout = WindowLayout {
+        monitor_fingerprint: monitor_fingerprint(&monitors),
+        windows,
+    };
+
+    let json = match serde_json::to_string(&layout) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("Failed to serialize window layout: {}", e);
+            return Ok(api_error!("window.layout_save_failed"));
+        }
+    };
+
+    match AppPreferences::new(&database)
+        .set(&layout_key(&name), Some(&json))
+        .await
+    {
+        Ok(_) => Ok(api_success!()),
+        Err(e) => {
+            error!("Failed to persist window layout: {}", e);
+            Ok(api_error!("window.layout_save_failed"))
+        }
+    }
+}
+
+/// 恢复指定布局；显示器配置变化时只做可见性修正
+#[tauri::command]
+pub async fn window_restore_layout<R: Runtime>(
+    name: String,
+    app: AppHandle<R>,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<WindowLayoutRestoreResult> {
+    if name.trim().is_empty() {
+        return Ok(api_error!("window.layout_name_empty"));
+    }
+
+    let layout: WindowLayout = match AppPreferences::new(&database).get(&layout_key(&name)).await {
+        Ok(Some(json)) => match serde_json::from_str(&json) {
+            Ok(layout) => layout,
+            Err(e) => {
+                warn!("Corrupted window layout '{}': {}", name, e);
+                return Ok(api_error!("window.layout_restore_failed"));
+            }
+        },
+        Ok(None) => return Ok(api_error!("window.layout_not_found")),
+        Err(e) => {
+            error!("Failed to load window layout: {}", e);
+            return Ok(api_error!("window.layout_restore_failed"));
+        }
+    };
+
+    let monitors = match app.available_monitors() {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            warn!("Failed to enumerate monitors: {}", e);
+            return Ok(api_error!("window.layout_restore_failed"));
+        }
+    };
+
+    let restored = monitor_fingerprint(&monitors) == layout.monitor_fingerprint;
+    let mut window_count = 0;
+
+    for (label, window) in app.webview_windows() {
+        if !restored {
+            crate::setup::move_window_on_screen(&window);
+            continue;
+        }
+
+        if let Some(bounds) = layout.windows.get(&label) {
+            let _ = window.set_size(PhysicalSize {
+                width: bounds.width,
+                height: bounds.height,
+            });
+            let _ = window.set_position(PhysicalPosition {
+                x: bounds.x,
+                y: bounds.y,
+            });
+            window_count += 1;
+        }
+    }
+
+    Ok(api_success!(WindowLayoutRestoreResult {
+        restored,
+        window_count,
+    }))
+}
@@ -1,11 +1,13 @@
 // Window command handlers exposed to Tauri
 
 pub mod directory;
+pub mod layout;
 pub mod opacity;
 pub mod platform;
 pub mod state;
 
 pub use directory::*;
+pub use layout::*;
 pub use opacity::*;
 pub use platform::*;
 pub use state::*;
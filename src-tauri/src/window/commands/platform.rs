@@ -45,7 +45,7 @@ pub async fn window_get_platform_info(
 }
 
 // 检测操作系统版本
-fn detect_os_version() -> String {
+pub(crate) fn detect_os_version() -> String {
     #[cfg(target_os = "macos")]
     {
         if let Ok(output) = std::process::Command::new("sw_vers")
@@ -6,14 +6,15 @@
  */
 
 use super::rules::get_available_rules_files;
-use super::{SessionRecord, WorkspaceRecord, WorkspaceService};
+use super::{RecentWorkspaceDetail, SessionRecord, WorkspaceRecord, WorkspaceService};
 use crate::agent::types::Message;
-use crate::storage::repositories::AppPreferences;
+use crate::storage::repositories::{AIModels, AppPreferences};
 use crate::storage::{DatabaseManager, UnifiedCache};
 use crate::utils::{EmptyData, TauriApiResult};
 use crate::{api_error, api_success};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Runtime, State};
 
 // ===== 工作区管理命令 =====
 
@@ -33,14 +34,35 @@ pub async fn workspace_get_recent(
     }
 }
 
+/// 最近工作区及项目元数据；每个条目都要探测目录，上限比 workspace_get_recent 更低
 #[tauri::command]
-pub async fn workspace_add_recent(
+pub async fn workspace_get_recent_detailed(
+    limit: Option<i64>,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<Vec<RecentWorkspaceDetail>> {
+    let limit = limit.unwrap_or(10).max(1).min(20);
+    let service = WorkspaceService::new(Arc::clone(&database));
+    match service.list_recent_workspace_details(limit).await {
+        Ok(workspaces) => Ok(api_success!(workspaces)),
+        Err(e) => {
+            tracing::error!("Failed to get recent workspace details: {}", e);
+            Ok(api_error!("workspace.recent.get_failed"))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn workspace_add_recent<R: Runtime>(
     path: String,
+    app: AppHandle<R>,
     database: State<'_, Arc<DatabaseManager>>,
 ) -> TauriApiResult<EmptyData> {
     let service = WorkspaceService::new(Arc::clone(&database));
     match service.get_or_create_workspace(&path).await {
-        Ok(_) => Ok(api_success!()),
+        Ok(_) => {
+            super::switch_workspace(&app, &path).await;
+            Ok(api_success!())
+        }
         Err(e) => {
             tracing::error!("Failed to add recent workspace: {}", e);
             Ok(api_error!("workspace.recent.add_failed"))
@@ -171,6 +193,52 @@ pub async fn workspace_set_active_session(
     }
 }
 
+/// 固定会话使用的模型；model_id 为 None 时取消固定，跟随全局默认模型
+#[tauri::command]
+pub async fn workspace_set_session_model(
+    session_id: i64,
+    model_id: Option<String>,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<SessionRecord> {
+    let service = WorkspaceService::new(Arc::clone(&database));
+    match service.get_session(session_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Ok(api_error!("workspace.session_not_found")),
+        Err(err) => {
+            tracing::error!("workspace_set_session_model failed: {}", err);
+            return Ok(api_error!("workspace.set_session_model_failed"));
+        }
+    }
+
+    let model_id = model_id.filter(|id| !id.trim().is_empty());
+    if let Some(id) = model_id.as_deref() {
+        match AIModels::new(&database).find_by_id(id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return Ok(api_error!("workspace.model_not_found")),
+            Err(err) => {
+                tracing::error!("workspace_set_session_model failed: {}", err);
+                return Ok(api_error!("workspace.set_session_model_failed"));
+            }
+        }
+    }
+
+    let result = async {
+        service
+            .set_session_model(session_id, model_id.as_deref())
+            .await?;
+        service.get_session(session_id).await
+    }
+    .await;
+    match result {
+        Ok(Some(session)) => Ok(api_success!(session)),
+        Ok(None) => Ok(api_error!("workspace.session_not_found")),
+        Err(err) => {
+            tracing::error!("workspace_set_session_model failed: {}", err);
+            Ok(api_error!("workspace.set_session_model_failed"))
+        }
+    }
+}
+
 // ===== 项目规则管理命令 =====
 
 /// 获取当前项目规则
@@ -223,3 +291,45 @@ pub async fn workspace_list_rules_files(cwd: String) -> TauriApiResult<Vec<Strin
     let files = get_available_rules_files(cwd);
     Ok(api_success!(files))
 }
+
+// ===== 终端环境变量命令 =====
+
+/// 获取工作区的 PTY 环境变量配置
+#[tauri::command]
+pub async fn workspace_get_pty_env(
+    path: String,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<HashMap<String, String>> {
+    let service = WorkspaceService::new(Arc::clone(&database));
+    match service.get_pty_env(&path).await {
+        Ok(env) => Ok(api_success!(env)),
+        Err(err) => {
+            tracing::error!("workspace_get_pty_env failed: {}", err);
+            Ok(api_error!("workspace.pty_env.load_failed"))
+        }
+    }
+}
+
+/// 设置工作区的 PTY 环境变量，仅对之后新建的终端生效
+#[tauri::command]
+pub async fn workspace_set_pty_env(
+    path: String,
+    env: HashMap<String, String>,
+    database: State<'_, Arc<DatabaseManager>>,
+) -> TauriApiResult<EmptyData> {
+    if env
+        .keys()
+        .any(|key| key.trim().is_empty() || key.contains('='))
+    {
+        return Ok(api_error!("workspace.pty_env.invalid_key"));
+    }
+
+    let service = WorkspaceService::new(Arc::clone(&database));
+    match service.set_pty_env(&path, &env).await {
+        Ok(()) => Ok(api_success!()),
+        Err(err) => {
+            tracing::error!("workspace_set_pty_env failed: {}", err);
+            Ok(api_error!("workspace.pty_env.save_failed"))
+        }
+    }
+}
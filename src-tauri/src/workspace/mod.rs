@@ -2,16 +2,22 @@
  * Workspace Module
  *
  * 工作区管理模块
- * 负责：最近工作区历史、项目规则管理、工作区上下文
+ * 负责：最近工作区历史、项目规则管理、工作区上下文、终端环境变量
  */
 
 pub mod commands;
+mod pty_env;
+mod recent;
 mod rules;
 mod service;
+mod switch;
 mod types;
 
 // 导出常用类型和函数
 pub use commands::*;
+pub use pty_env::{expand_env_refs, expand_pty_env};
+pub use recent::RecentWorkspaceDetail;
 pub use rules::get_available_rules_files;
 pub use service::*;
+pub use switch::{switch_workspace, WorkspaceChangedPayload, WORKSPACE_CHANGED_EVENT};
 pub use types::RULES_FILES;
@@ -0,0 +1,91 @@
+/*!
+ * 工作区 PTY 环境变量
+ *
+ * 每个工作区可以配置一组额外的环境变量，在该工作区内创建终端时注入。
+ * 修改只对之后新建的终端生效，已经存在的 pane 不会被更新。
+ */
+
+use std::collections::HashMap;
+
+/// 偏好设置中的存储键前缀，后接规范化后的工作区路径
+pub(crate) const PTY_ENV_KEY_PREFIX: &str = "workspace.pty_env:";
+
+pub(crate) fn pty_env_key(workspace_path: &str) -> String {
+    format!("{}{}", PTY_ENV_KEY_PREFIX, workspace_path)
+}
+
+/// 展开值中的 `${VAR}` 引用
+///
+/// 未定义的变量展开为空字符串；缺少右括号时原样保留。
+pub fn expand_env_refs<F>(value: &str, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                if let Some(resolved) = lookup(name) {
+                    result.push_str(&resolved);
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// 基于当前进程（继承）环境展开整组变量
+pub fn expand_pty_env(env: &HashMap<String, String>) -> HashMap<String, String> {
+    env.iter()
+        .map(|(key, value)| {
+            (
+                key.clone(),
+                expand_env_refs(value, |name| std::env::var(name).ok()),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/dev".to_string()),
+            "PATH" => Some("/usr/bin".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_known_variables() {
+        assert_eq!(
+            expand_env_refs("${HOME}/bin:${PATH}", lookup),
+            "/home/dev/bin:/usr/bin"
+        );
+    }
+
+    #[test]
+    fn unknown_variables_expand_to_empty() {
+        assert_eq!(expand_env_refs("a${MISSING}b", lookup), "ab");
+    }
+
+    #[test]
+    fn unterminated_reference_is_kept() {
+        assert_eq!(expand_env_refs("x${HOME", lookup), "x${HOME");
+        assert_eq!(expand_env_refs("plain", lookup), "plain");
+    }
+}
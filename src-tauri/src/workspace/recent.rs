@@ -0,0 +1,211 @@
+/*!
+ * 最近工作区详情
+ *
+ * 为"最近项目"列表补充元数据：主要语言、是否已建立向量索引、固定的模型。
+ * 语言来自对项目顶层的快速扫描，每个条目最多检查 MAX_SCAN_ENTRIES 个目录项，
+ * 结果按根目录修改时间缓存。目录已不存在的工作区标记为 missing 而不是丢弃，方便用户清理。
+ */
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use anyhow::Result;
+use serde::Serialize;
+use tokio::task;
+
+use crate::vector_db::core::Language;
+use crate::vector_db::storage::IndexManager;
+
+use super::WorkspaceService;
+
+/// 单个工作区最多检查的目录项数（顶层及一级子目录合计）
+const MAX_SCAN_ENTRIES: usize = 256;
+/// 语言缓存最多保留的工作区数，超出后整体清空
+const MAX_CACHED_WORKSPACES: usize = 128;
+/// 扫描时跳过的目录
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "vendor"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentWorkspaceDetail {
+    pub path: String,
+    pub display_name: Option<String>,
+    pub last_accessed_at: i64,
+    /// 目录已不存在，其余元数据均为空
+    pub missing: bool,
+    pub primary_language: Option<String>,
+    pub has_vector_index: bool,
+    /// 当前会话固定的模型，None 表示跟随全局默认模型
+    pub model_override: Option<String>,
+}
+
+struct CachedLanguage {
+    modified: Option<SystemTime>,
+    language: Option<Language>,
+}
+
+static LANGUAGE_CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedLanguage>>> = OnceLock::new();
+
+/// 统计目录下各语言的文件数；subdirs 为 Some 时收集可继续扫描的子目录
+fn count_languages(
+    dir: &Path,
+    counts: &mut HashMap<Language, usize>,
+    mut subdirs: Option<&mut Vec<PathBuf>>,
+    budget: &mut usize,
+) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if *budget == 0 {
+            return;
+        }
+        *budget -= 1;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if let Some(subdirs) = subdirs.as_deref_mut() {
+                if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                    subdirs.push(entry.path());
+                }
+            }
+        } else if let Some(language) = Language::from_path(&entry.path()) {
+            *counts.entry(language).or_default() += 1;
+        }
+    }
+}
+
+/// 统计顶层及一级子目录中各语言的文件数，取最多者
+fn scan_primary_language(root: &Path) -> Option<Language> {
+    let mut counts = HashMap::new();
+    let mut budget = MAX_SCAN_ENTRIES;
+    let mut subdirs = Vec::new();
+
+    count_languages(root, &mut counts, Some(&mut subdirs), &mut budget);
+    subdirs.sort();
+    for dir in &subdirs {
+        if budget == 0 {
+            break;
+        }
+        count_languages(dir, &mut counts, None, &mut budget);
+    }
+
+    // 数量相同时按 Language::ALL 的顺序取，保证结果稳定
+    Language::ALL
+        .into_iter()
+        .filter_map(|language| counts.get(&language).map(|&n| (language, n)))
+        .fold(
+            None,
+            |best: Option<(Language, usize)>, (language, n)| match best {
+                Some((_, max)) if max >= n => best,
+                _ => Some((language, n)),
+            },
+        )
+        .map(|(language, _)| language)
+}
+
+/// 带缓存的主要语言检测；根目录修改时间变化后重新扫描
+fn primary_language(root: &Path) -> Option<Language> {
+    let modified = std::fs::metadata(root).and_then(|m| m.modified()).ok();
+    let cache = LANGUAGE_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Ok(cache) = cache.lock() {
+        if let Some(cached) = cache.get(root) {
+            if cached.modified.is_some() && cached.modified == modified {
+                return cached.language;
+            }
+        }
+    }
+
+    let language = scan_primary_language(root);
+    if let Ok(mut cache) = cache.lock() {
+        if cache.len() >= MAX_CACHED_WORKSPACES {
+            cache.clear();
+        }
+        cache.insert(root.to_path_buf(), CachedLanguage { modified, language });
+    }
+    language
+}
+
+/// 文件系统相关的探测：(missing, primary_language, has_vector_index)
+fn probe_directory(path: &str) -> (bool, Option<Language>, bool) {
+    let root = Path::new(path);
+    if !root.is_dir() {
+        return (true, None, false);
+    }
+    (false, primary_language(root), IndexManager::exists(root))
+}
+
+impl WorkspaceService {
+    /// 最近工作区及其元数据，目录不存在的条目保留并标记为 missing
+    pub async fn list_recent_workspace_details(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<RecentWorkspaceDetail>> {
+        let workspaces = self.list_recent_workspaces(limit).await?;
+        let mut details = Vec::with_capacity(workspaces.len());
+        for workspace in workspaces {
+            let path = workspace.path.clone();
+            let (missing, language, has_vector_index) =
+                task::spawn_blocking(move || probe_directory(&path)).await?;
+            let model_override = match workspace.active_session_id {
+                Some(session_id) if !missing => self.get_session_model(session_id).await?,
+                _ => None,
+            };
+            details.push(RecentWorkspaceDetail {
+                path: workspace.path,
+                display_name: workspace.display_name,
+                last_accessed_at: workspace.last_accessed_at,
+                missing,
+                primary_language: language.map(|l| l.display_name().to_string()),
+                has_vector_index,
+                model_override,
+            });
+        }
+        Ok(details)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_language_from_top_level_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("build.py"), "").unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        for name in ["main.rs", "lib.rs", "util.rs"] {
+            std::fs::write(root.join("src").join(name), "").unwrap();
+        }
+        // 被跳过的目录不参与统计
+        std::fs::create_dir(root.join("node_modules")).unwrap();
+        for i in 0..5 {
+            std::fs::write(root.join("node_modules").join(format!("{i}.js")), "").unwrap();
+        }
+
+        assert_eq!(primary_language(root), Some(Language::Rust));
+        assert_eq!(
+            scan_primary_language(&root.join("node_modules")),
+            Some(Language::JavaScript)
+        );
+    }
+
+    #[test]
+    fn missing_directory_is_flagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let gone = dir.path().join("deleted-project");
+        let (missing, language, has_index) = probe_directory(&gone.to_string_lossy());
+        assert!(missing);
+        assert!(language.is_none());
+        assert!(!has_index);
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -9,8 +10,11 @@ use tokio::task;
 
 use crate::agent::persistence::AgentPersistence;
 use crate::agent::types::{Block, Message};
+use crate::storage::repositories::AppPreferences;
 use crate::storage::DatabaseManager;
 
+use super::pty_env::{expand_pty_env, pty_env_key};
+
 /// 未分组工作区的特殊路径标识
 pub const UNGROUPED_WORKSPACE_PATH: &str = "__ungrouped__";
 
@@ -32,6 +36,8 @@ pub struct SessionRecord {
     pub workspace_path: String,
     pub title: Option<String>,
     pub message_count: i64,
+    /// 会话固定的模型，None 表示跟随全局默认模型
+    pub model_id: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -120,8 +126,10 @@ impl WorkspaceService {
         let normalized = self.normalize_path(workspace_path).await?;
         let rows = sqlx::query(
             "SELECT s.id, s.workspace_path, s.title, s.created_at, s.updated_at,
-                    (SELECT COUNT(*) FROM messages WHERE session_id = s.id AND role = 'user') as message_count
+                    (SELECT COUNT(*) FROM messages WHERE session_id = s.id AND role = 'user') as message_count,
+                    ss.model_id
              FROM sessions s
+             LEFT JOIN session_settings ss ON ss.session_id = s.id
              WHERE s.workspace_path = ?
              ORDER BY s.updated_at DESC, s.id DESC",
         )
@@ -156,6 +164,33 @@ impl WorkspaceService {
             .ok_or_else(|| anyhow!("Failed to retrieve session {}", id))
     }
 
+    /// 会话固定的模型
+    pub async fn get_session_model(&self, session_id: i64) -> Result<Option<String>> {
+        let model_id: Option<Option<String>> =
+            sqlx::query_scalar("SELECT model_id FROM session_settings WHERE session_id = ?")
+                .bind(session_id)
+                .fetch_optional(self.pool())
+                .await?;
+        Ok(model_id.flatten())
+    }
+
+    /// 固定会话使用的模型；None 取消固定，之后跟随全局默认模型
+    pub async fn set_session_model(&self, session_id: i64, model_id: Option<&str>) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO session_settings (session_id, model_id, updated_at)
+             VALUES (?, ?, ?)
+             ON CONFLICT(session_id) DO UPDATE SET
+                model_id = excluded.model_id,
+                updated_at = excluded.updated_at",
+        )
+        .bind(session_id)
+        .bind(model_id)
+        .bind(Self::now_timestamp())
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
     pub async fn get_active_session(&self, workspace_path: &str) -> Result<Option<SessionRecord>> {
         let workspace = self.get_or_create_workspace(workspace_path).await?;
         match workspace.active_session_id {
@@ -243,6 +278,54 @@ impl WorkspaceService {
         Ok(())
     }
 
+    /// 读取工作区配置的 PTY 环境变量（未展开）
+    pub async fn get_pty_env(&self, workspace_path: &str) -> Result<HashMap<String, String>> {
+        let normalized = self.normalize_path(workspace_path).await?;
+        let raw = AppPreferences::new(&self.database)
+            .get(&pty_env_key(&normalized))
+            .await?;
+
+        match raw {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    /// 保存工作区 PTY 环境变量；为空时删除配置
+    pub async fn set_pty_env(
+        &self,
+        workspace_path: &str,
+        env: &HashMap<String, String>,
+    ) -> Result<()> {
+        let normalized = self.normalize_path(workspace_path).await?;
+        let value = if env.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(env)?)
+        };
+
+        AppPreferences::new(&self.database)
+            .set(&pty_env_key(&normalized), value.as_deref())
+            .await?;
+        Ok(())
+    }
+
+    /// 解析终端 cwd 所属工作区（自身或最近的祖先目录）的环境变量，并完成 `${VAR}` 展开
+    pub async fn resolve_pty_env_for_cwd(&self, cwd: &str) -> Result<HashMap<String, String>> {
+        let normalized = self.normalize_path(cwd).await?;
+        let preferences = AppPreferences::new(&self.database);
+
+        for dir in Path::new(&normalized).ancestors() {
+            let key = pty_env_key(&path_to_string(dir)?);
+            if let Some(json) = preferences.get(&key).await? {
+                let env: HashMap<String, String> = serde_json::from_str(&json)?;
+                return Ok(expand_pty_env(&env));
+            }
+        }
+
+        Ok(HashMap::new())
+    }
+
     pub async fn maintain(&self, max_age_days: i64, max_entries: i64) -> Result<(u64, u64)> {
         let cutoff = Self::now_timestamp() - max_age_days * 24 * 60 * 60;
 
@@ -289,8 +372,11 @@ impl WorkspaceService {
     pub async fn get_session(&self, id: i64) -> Result<Option<SessionRecord>> {
         let row = sqlx::query(
             "SELECT s.id, s.workspace_path, s.title, s.created_at, s.updated_at,
-                    (SELECT COUNT(*) FROM messages WHERE session_id = s.id AND role = 'user') as message_count
-             FROM sessions s WHERE s.id = ?",
+                    (SELECT COUNT(*) FROM messages WHERE session_id = s.id AND role = 'user') as message_count,
+                    ss.model_id
+             FROM sessions s
+             LEFT JOIN session_settings ss ON ss.session_id = s.id
+             WHERE s.id = ?",
         )
         .bind(id)
         .fetch_optional(self.pool())
@@ -326,6 +412,7 @@ fn build_session(row: sqlx::sqlite::SqliteRow) -> SessionRecord {
         workspace_path: row.try_get("workspace_path").unwrap_or_default(),
         title: row.try_get("title").unwrap_or(None),
         message_count: row.try_get("message_count").unwrap_or(0),
+        model_id: row.try_get("model_id").unwrap_or(None),
         created_at: row.try_get("created_at").unwrap_or_default(),
         updated_at: row.try_get("updated_at").unwrap_or_default(),
     }
@@ -0,0 +1,51 @@
+/*!
+ * 工作区切换
+ *
+ * 打开工作区时统一完成：激活该工作区的向量索引、重置 Agent 的工作区上下文，
+ * 最后发出 `workspace-changed` 事件，前端收到事件时索引已可直接搜索。
+ */
+
+use crate::agent::core::commands::TaskExecutorState;
+use crate::vector_db::storage::IndexStatus;
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+pub const WORKSPACE_CHANGED_EVENT: &str = "workspace-changed";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceChangedPayload {
+    pub path: String,
+    /// 新工作区的索引状态；向量模块未初始化或读取失败时为 None
+    pub index_status: Option<IndexStatus>,
+}
+
+pub async fn switch_workspace<R: Runtime>(app: &AppHandle<R>, path: &str) {
+    let index_status = match crate::vector_db::commands::get_global_state() {
+        Some(global) => match global
+            .search_engine
+            .activate_workspace(Path::new(path))
+            .await
+        {
+            Ok(status) => Some(status),
+            Err(e) => {
+                tracing::warn!(path = %path, "激活工作区索引失败: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    if let Some(state) = app.try_state::<TaskExecutorState>() {
+        state.executor.on_workspace_changed(path).await;
+    }
+
+    let payload = WorkspaceChangedPayload {
+        path: path.to_string(),
+        index_status,
+    };
+    if let Err(e) = app.emit(WORKSPACE_CHANGED_EVENT, &payload) {
+        tracing::warn!("Failed to emit workspace-changed event: {}", e);
+    }
+}
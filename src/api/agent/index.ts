@@ -6,7 +6,22 @@
 
 import { invoke } from '@/utils/request'
 import { agentChannelApi } from '@/api/channel/agent'
-import type { ExecuteTaskParams, TaskListFilter, TaskProgressPayload, TaskProgressStream, TaskSummary } from './types'
+import type {
+  BulkCancelReport,
+  ConversationExport,
+  EffectiveAgentConfig,
+  ExecuteTaskParams,
+  FinalAnswer,
+  MessageRebuildReport,
+  PathSummary,
+  RenderedSystemPrompt,
+  TaskListFilter,
+  TaskProgressPayload,
+  TaskProgressStream,
+  TaskResetReport,
+  TaskSummary,
+} from './types'
+import type { CancelReason, WritePreviewDecision } from '@/types'
 
 /**
  * Agent API 主类
@@ -31,10 +46,113 @@ export class AgentApi {
   /**
    * 取消任务
    * @param taskId 任务ID
-   * @param reason 取消原因
+   * @param reason 取消说明
+   * @param kind 取消原因，缺省时由后端判定
    */
-  cancelTask = async (taskId: string, reason?: string): Promise<void> => {
-    await invoke('agent_cancel_task', { taskId, reason })
+  cancelTask = async (taskId: string, reason?: string, kind?: CancelReason): Promise<void> => {
+    await invoke('agent_cancel_task', { taskId, reason, kind })
+  }
+
+  /**
+   * 取消全部运行中与排队中的任务
+   * @returns 被取消的任务数量及 ID
+   */
+  cancelAllTasks = async (): Promise<BulkCancelReport> => {
+    return await invoke<BulkCancelReport>('agent_cancel_all_tasks')
+  }
+
+  /**
+   * 暂停任务
+   * @param taskId 任务ID
+   * @param abortCurrentStep 同时中断正在执行的工具调用（软停止）
+   */
+  pauseTask = async (taskId: string, abortCurrentStep = false): Promise<void> => {
+    await invoke('agent_pause_task', { taskId, abortCurrentStep })
+  }
+
+  /**
+   * 恢复暂停的任务
+   * @param taskId 任务ID
+   */
+  resumeTask = async (taskId: string): Promise<void> => {
+    await invoke('agent_resume_task', { taskId })
+  }
+
+  /**
+   * 查看会话发起任务时实际生效的配置及来源
+   * @param sessionId 会话ID
+   * @param modelId 当前的全局默认模型
+   */
+  getEffectiveConfig = async (sessionId: number, modelId?: string): Promise<EffectiveAgentConfig> => {
+    return await invoke<EffectiveAgentConfig>('agent_get_effective_config', { sessionId, modelId })
+  }
+
+  /**
+   * 查看会话当前的 system prompt（不调用模型）
+   * @param sessionId 会话ID
+   * @param offset 起始字符位置，用于分页
+   */
+  getRenderedSystemPrompt = async (sessionId: number, offset?: number): Promise<RenderedSystemPrompt> => {
+    return await invoke<RenderedSystemPrompt>('agent_get_rendered_system_prompt', { sessionId, offset })
+  }
+
+  /**
+   * 强制重置卡住的任务（崩溃恢复用）
+   * @param taskId 任务ID
+   * @param confirm 任务仍在运行时需要为 true 才会中止
+   */
+  forceResetTask = async (taskId: string, confirm = false): Promise<TaskResetReport> => {
+    return await invoke<TaskResetReport>('agent_force_reset_task', { taskId, confirm })
+  }
+
+  /**
+   * 用 UI 消息重建会话的模型侧历史（修复用）
+   * @param sessionId 会话ID
+   * @param confirm 为 true 时覆盖已存储的消息，否则只返回预览
+   */
+  rebuildMessagesFromUi = async (sessionId: number, confirm = false): Promise<MessageRebuildReport> => {
+    return await invoke<MessageRebuildReport>('agent_rebuild_messages_from_ui', { sessionId, confirm })
+  }
+
+  /**
+   * 导出会话为带版本号的 JSON
+   * @param sessionId 会话ID
+   */
+  exportConversation = async (sessionId: number): Promise<ConversationExport> => {
+    return await invoke<ConversationExport>('agent_export_conversation', { sessionId })
+  }
+
+  /**
+   * 从导出的 JSON 创建新会话
+   * @param json exportConversation 导出的内容
+   * @param workspacePath 导入到的工作区，缺省时沿用导出时的工作区
+   * @returns 新会话ID
+   */
+  importConversation = async (json: string, workspacePath?: string): Promise<number> => {
+    return await invoke<number>('agent_import_conversation', { json, workspacePath })
+  }
+
+  /**
+   * 获取会话最新的最终回答文本，供朗读、复制等使用
+   * @param sessionId 会话ID
+   * @returns 会话没有助手文字时为 null
+   */
+  getFinalAnswer = async (sessionId: number): Promise<FinalAnswer | null> => {
+    return await invoke<FinalAnswer | null>('agent_get_final_answer', { sessionId })
+  }
+
+  /**
+   * 单轮总结文件或目录（不启动任务）
+   * @param path 绝对路径，或相对会话工作区的路径
+   * @param includeRelated 是否附带语义搜索找到的相关代码
+   */
+  summarizePath = async (
+    path: string,
+    sessionId: number,
+    modelId: string,
+    includeRelated = true
+  ): Promise<PathSummary> => {
+    return await invoke<PathSummary>('agent_summarize_path', { path, sessionId, modelId, includeRelated })
   }
 
   confirmTool = async (
@@ -47,6 +165,12 @@ export class AgentApi {
     })
   }
 
+  resolveWritePreview = async (taskId: string, requestId: string, decisions: WritePreviewDecision[]): Promise<void> => {
+    await invoke('agent_resolve_write_preview', {
+      params: { taskId, requestId, decisions },
+    })
+  }
+
   /**
    * 列出任务
    * @param filters 过滤条件
@@ -59,6 +183,21 @@ export class AgentApi {
     })
   }
 
+  /**
+   * 获取可同时运行的任务数上限
+   */
+  getMaxConcurrentTasks = async (): Promise<number> => {
+    return await invoke<number>('agent_get_max_concurrent_tasks')
+  }
+
+  /**
+   * 设置可同时运行的任务数上限（1-16），超出的任务排队等待
+   * @returns 实际生效的上限
+   */
+  setMaxConcurrentTasks = async (limit: number): Promise<number> => {
+    return await invoke<number>('agent_set_max_concurrent_tasks', { limit })
+  }
+
   /**
    * 获取任务详情
    * @param taskId 任务ID
@@ -4,7 +4,7 @@
  * 定义Agent系统的所有接口类型，与后端TaskExecutor保持一致
  */
 
-import type { TaskEvent } from '@/types'
+import type { Message, TaskEvent } from '@/types'
 
 // ===== 核心类型定义 =====
 
@@ -27,8 +27,23 @@ export interface ExecuteTaskParams {
   modelId: string
   /** 图片附件（可选） */
   images?: Array<{ type: 'image'; dataUrl: string; mimeType: string }>
+  /** 达到最大迭代次数时的行为（可选，默认 hard_stop） */
+  onMaxIterations?: 'hard_stop' | 'summarize_then_stop'
+  /** 跨迭代复用相同只读工具调用结果的迭代数，0 关闭 */
+  toolDedupWindow?: number
+  /** 消息压缩策略（可选，默认 overflow_only）：proactive 会按轮数定期压缩较早的消息 */
+  compactionPolicy?: CompactionPolicy
+  /** proactive 策略每累计多少轮压缩一次（可选，默认 10，0 关闭） */
+  compactionEveryTurns?: number
+  /** proactive 策略原样保留的最近轮数（可选，默认 4） */
+  compactionKeepTurns?: number
+  /** 文件类工具可访问的根目录（可选），越界路径会被拒绝 */
+  filesystemRoot?: string
 }
 
+/** 接近上下文窗口时的溢出压缩始终生效，策略只决定是否额外按轮数主动压缩 */
+export type CompactionPolicy = 'overflow_only' | 'proactive'
+
 /**
  * 任务摘要信息
  */
@@ -51,6 +66,128 @@ export interface TaskSummary {
   userPrompt?: string
   /** 完成时间 */
   completedAt?: string
+  /** 排队位置（从 1 开始），仅 status 为 queued 时存在 */
+  queuePosition?: number
+}
+
+/**
+ * 强制重置任务的结果
+ */
+/** 配置项的来源 */
+export type ConfigSource = 'default' | 'model' | 'conversation'
+
+export interface Resolved<T> {
+  value: T
+  source: ConfigSource
+}
+
+/** 会话发起任务时实际生效的配置 */
+export interface EffectiveAgentConfig {
+  sessionId: number
+  modelId: Resolved<string | null>
+  maxIterations: Resolved<number>
+  maxErrors: Resolved<number>
+  maxConcurrentTools: Resolved<number>
+  onMaxIterations: Resolved<'hard_stop' | 'summarize_then_stop'>
+  toolDedupWindow: Resolved<number>
+  contextWindow: Resolved<number>
+  compactionKeepRecent: Resolved<number>
+  compactionPolicy: Resolved<CompactionPolicy>
+  compactionEveryTurns: Resolved<number>
+  compactionKeepTurns: Resolved<number>
+  llmRequestTimeoutSecs: Resolved<number>
+  llmStreamTimeoutMs: Resolved<number>
+  shellTimeoutMs: Resolved<number>
+}
+
+/** 会话当前的 system prompt（分页） */
+export interface RenderedSystemPrompt {
+  sessionId: number
+  /** 来自运行中任务时为其任务ID */
+  taskId: string | null
+  source: 'active_task' | 'rebuilt'
+  text: string
+  offset: number
+  totalChars: number
+  /** 还有剩余内容时为下一页的 offset */
+  nextOffset: number | null
+}
+
+export interface TaskResetReport {
+  taskId: string
+  /** 重置前的状态 */
+  previousStatus?: TaskStatus
+  /** 是否中止了仍在运行的任务 */
+  abortedRunning: boolean
+  /** 被结束的残留 streaming 消息数 */
+  settledMessages: number
+}
+
+export interface MessageRebuildReport {
+  sessionId: number
+  /** 写入重建历史的任务 */
+  executionId: string
+  uiMessages: number
+  rebuiltMessages: number
+  /** 还原的 tool_use/tool_result 对数 */
+  toolPairs: number
+  /** 因没有结果而丢弃的工具调用数 */
+  droppedToolCalls: number
+  /** 是否已覆盖存储的消息 */
+  applied: boolean
+}
+
+/** 会话导出格式（version 1），导入时只接受相同版本 */
+export interface ConversationExport {
+  version: number
+  exportedAt: string
+  conversation: {
+    title: string | null
+    workspacePath: string
+    createdAt: string
+    updatedAt: string
+  }
+  messages: Omit<Message, 'id' | 'sessionId'>[]
+}
+
+/** 会话最新的最终回答（不含思考与工具调用） */
+export interface FinalAnswer {
+  sessionId: number
+  messageId: number
+  status: Message['status']
+  content: string
+  /** false 表示任务没有单独的最终回答，content 为最后一段助手文字 */
+  isFinal: boolean
+}
+
+export interface BulkCancelReport {
+  /** 被取消的任务总数（含排队中的任务） */
+  cancelled: number
+  /** 其中尚未启动、直接出队的任务数 */
+  queued: number
+  taskIds: string[]
+}
+
+/** 应用级事件 agent-system-message 的负载 */
+export interface AgentSystemMessage {
+  message: string
+  taskIds: string[]
+  timestamp: string
+}
+
+export interface PathSummary {
+  path: string
+  summary: string
+  /** 参与总结的文件 */
+  files: string[]
+  /** 因数量上限、过大或非 UTF-8 被跳过的文件数 */
+  skippedFiles: number
+  /** 作为参考附带的相关代码位置 */
+  related: string[]
+  /** LLM 调用次数，大于 1 表示使用了分批总结 */
+  llmCalls: number
+  inputTokens: number
+  outputTokens: number
 }
 
 /**
@@ -58,6 +195,7 @@ export interface TaskSummary {
  */
 export type TaskStatus =
   | 'created' // 已创建
+  | 'queued' // 排队等待中
   | 'running' // 运行中
   | 'paused' // 已暂停
   | 'completed' // 已完成
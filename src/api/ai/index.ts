@@ -1,6 +1,14 @@
 import type { AIModelConfig, AISettings } from '@/types'
 import { invoke } from '@/utils/request'
-import type { AIModelCreateInput, AIModelUpdateInput, AIModelTestConnectionInput } from './types'
+import type {
+  AIModelCreateInput,
+  AIModelUpdateInput,
+  AIModelTestConnectionInput,
+  InlineAskOutcome,
+  ModelValidationResult,
+  ReactTrace,
+  WebFetchDomainPolicy,
+} from './types'
 
 export class AiApi {
   getModels = async (): Promise<AIModelConfig[]> => {
@@ -53,6 +61,26 @@ export class AiApi {
     await invoke<void>('ai_models_test_connection', { config: payload })
   }
 
+  /**
+   * 保存前校验模型配置，返回失败类别与探测到的模型能力
+   */
+  validateModelConfig = async (config: AIModelTestConnectionInput): Promise<ModelValidationResult> => {
+    const payload: AIModelConfig = {
+      id: crypto.randomUUID(),
+      provider: config.provider,
+      apiUrl: config.apiUrl,
+      apiKey: config.apiKey,
+      model: config.model,
+      modelType: config.modelType,
+      options: config.options,
+      useCustomBaseUrl: config.useCustomBaseUrl,
+      createdAt: new Date(),
+      updatedAt: new Date(),
+    }
+
+    return await invoke<ModelValidationResult>('ai_validate_model_config', { config: payload })
+  }
+
   getUserRules = async (): Promise<string | null> => {
     return await invoke<string | null>('agent_get_user_rules')
   }
@@ -61,6 +89,58 @@ export class AiApi {
     await invoke<void>('agent_set_user_rules', { rules })
   }
 
+  getWebFetchDomains = async (): Promise<WebFetchDomainPolicy> => {
+    return await invoke<WebFetchDomainPolicy>('agent_get_web_fetch_domains')
+  }
+
+  setWebFetchDomains = async (policy: WebFetchDomainPolicy): Promise<WebFetchDomainPolicy> => {
+    return await invoke<WebFetchDomainPolicy>('agent_set_web_fetch_domains', { policy })
+  }
+
+  /** 历史回忆：启用后对话消息会被向量化，供 recall_history 工具检索 */
+  getRecallHistoryEnabled = async (): Promise<boolean> => {
+    return await invoke<boolean>('agent_get_recall_history_enabled')
+  }
+
+  setRecallHistoryEnabled = async (enabled: boolean): Promise<void> => {
+    await invoke<void>('agent_set_recall_history_enabled', { enabled })
+  }
+
+  /** 写入预览：启用后 agent 写文件前先展示 diff，等待逐个文件确认 */
+  getPreviewWritesEnabled = async (): Promise<boolean> => {
+    return await invoke<boolean>('agent_get_preview_writes_enabled')
+  }
+
+  setPreviewWritesEnabled = async (enabled: boolean): Promise<void> => {
+    await invoke<void>('agent_set_preview_writes_enabled', { enabled })
+  }
+
+  getGitBranchPushEnabled = async (): Promise<boolean> => {
+    return await invoke<boolean>('agent_get_git_branch_push_enabled')
+  }
+
+  setGitBranchPushEnabled = async (enabled: boolean): Promise<void> => {
+    await invoke<void>('agent_set_git_branch_push_enabled', { enabled })
+  }
+
+  /**
+   * 在终端面板内联提问，回答以注释块形式流式显示在面板中；面板内 Ctrl+C 可中断
+   */
+  askInline = async (paneId: number, prompt: string, modelId: string): Promise<InlineAskOutcome> => {
+    return await invoke<InlineAskOutcome>('ai_ask_inline', { paneId, prompt, modelId })
+  }
+
+  cancelAskInline = async (paneId: number): Promise<void> => {
+    await invoke<void>('ai_ask_inline_cancel', { paneId })
+  }
+
+  /**
+   * 获取运行中或最近结束任务的 ReAct 轨迹，last 只返回最近 N 轮
+   */
+  getReactTrace = async (taskId: string, last?: number): Promise<ReactTrace> => {
+    return await invoke<ReactTrace>('agent_get_react_trace', { taskId, last })
+  }
+
   getSettings = async (): Promise<AISettings> => {
     return await invoke<AISettings>('get_ai_settings')
   }
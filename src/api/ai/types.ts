@@ -22,3 +22,72 @@ export interface AIModelUpdateInput {
 }
 
 export interface AIModelTestConnectionInput extends AIModelCreateInput {}
+
+/** web_fetch 域名策略：黑名单优先，白名单非空时只允许其中的域名（含子域名） */
+export interface WebFetchDomainPolicy {
+  allowed: string[]
+  denied: string[]
+}
+
+/** 内联提问的结束方式 */
+export type InlineAskOutcome = 'completed' | 'cancelled'
+
+/** 模型配置校验结果 */
+export interface ModelValidationResult {
+  ok: boolean
+  latencyMs: number
+  error: string | null
+  /** auth / wrong_url / model_not_found / rate_limited / network / other */
+  errorKind: string | null
+  capabilities: ModelCapabilities | null
+}
+
+/** 探测到的模型能力，null 表示该类模型不适用 */
+export interface ModelCapabilities {
+  streaming: boolean | null
+  tools: boolean | null
+  embeddingDimension: number | null
+}
+
+/** 任务的 ReAct 推理/工具调用轨迹（调试用，区别于 UI 步骤） */
+export interface ReactTrace {
+  taskId: string
+  running: boolean
+  totalIterations: number
+  /** 因数量限制省略的较早迭代数 */
+  omittedIterations: number
+  consecutiveErrors: number
+  stopReason: string | null
+  aborted: boolean
+  iterations: ReactTraceIteration[]
+  /** 本任务发生的消息压缩 */
+  compactions: ReactTraceCompaction[]
+}
+
+export interface ReactTraceCompaction {
+  iteration: number
+  trigger: 'overflow' | 'proactive'
+  messagesCompacted: number
+  tokensSaved: number
+  compactedAt: number
+}
+
+export interface ReactTraceIteration {
+  index: number
+  startedAt: number
+  outcome: 'reasoning' | 'action' | 'observation' | 'completion' | 'failed'
+  thought: string | null
+  action: { toolName: string; arguments: unknown; issuedAt: number } | null
+  observation: {
+    toolName: string
+    status: string
+    output: string
+    executionTimeMs: number | null
+    observedAt: number
+  } | null
+  response: string | null
+  finishReason: string | null
+  errorMessage: string | null
+  requestTimeoutMs: number | null
+  hasError: boolean
+}
@@ -5,6 +5,18 @@
  */
 
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import { invoke } from '@/utils/request'
+
+export interface SafeModeStatus {
+  enabled: boolean
+  skipped: Array<'vector_db' | 'agent_executor'>
+}
+
+export interface IdleStatus {
+  idle: boolean
+  idleForSecs: number
+  thresholdSecs: number
+}
 
 /**
  * 应用 API 接口类
@@ -19,6 +31,35 @@ export class AppApi {
     })
   }
 
+  /**
+   * 查询本次启动是否处于安全模式及被跳过的子系统
+   */
+  getSafeModeStatus = async (): Promise<SafeModeStatus> => {
+    return await invoke<SafeModeStatus>('app_get_safe_mode_status')
+  }
+
+  /**
+   * 查询用户是否空闲（距最近一次交互超过阈值）
+   * @param thresholdSecs 阈值秒数，缺省使用配置值
+   */
+  isIdle = async (thresholdSecs?: number): Promise<IdleStatus> => {
+    return await invoke<IdleStatus>('app_is_idle', { thresholdSecs })
+  }
+
+  /**
+   * 读取空闲阈值（秒）
+   */
+  getIdleThreshold = async (): Promise<number> => {
+    return await invoke<number>('app_get_idle_threshold')
+  }
+
+  /**
+   * 设置空闲阈值（秒，5-3600），后台任务在用户空闲超过该时长后才执行
+   */
+  setIdleThreshold = async (secs: number): Promise<number> => {
+    return await invoke<number>('app_set_idle_threshold', { secs })
+  }
+
   /**
    * 监听自定义事件
    */
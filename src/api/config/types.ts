@@ -28,6 +28,8 @@ export interface AppConfig {
     language: string
     confirm_on_exit: boolean
     startup_behavior: string
+    autosave_debounce_ms?: number
+    quit_on_window_close?: boolean
   }
   appearance: {
     ui_scale: number
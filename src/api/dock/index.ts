@@ -4,6 +4,7 @@ import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 export interface TabEntry {
   id: string
   title: string
+  paneId?: number
 }
 
 export const dockApi = {
@@ -9,6 +9,16 @@
 
 import { invoke } from '@tauri-apps/api/core'
 import { invoke as appInvoke } from '@/utils/request'
+import { channelApi } from '@/api/channel'
+import type { ChannelSubscription } from '@/api/channel/types'
+
+export type TailEvent =
+  | { type: 'snapshot'; lines: string[] }
+  | { type: 'lines'; lines: string[] }
+  | { type: 'rotated'; kind: 'truncated' | 'replaced' }
+  | { type: 'error'; message: string }
+
+let nextTailId = 0
 
 /**
  * 文件系统 API 接口类
@@ -95,6 +105,22 @@ export class FilesystemApi {
   listDirectory = async (path: string, recursive: boolean = false): Promise<string[]> => {
     return await appInvoke<string[]>('fs_list_directory', { path, recursive })
   }
+
+  /**
+   * 跟踪文件追加内容（类似 tail -F）：先收到末尾 lines 行的快照，之后持续收到新增行
+   */
+  tail = (
+    path: string,
+    onEvent: (event: TailEvent) => void,
+    options?: { lines?: number; onError?: (error: unknown) => void }
+  ): ChannelSubscription => {
+    const tailId = `tail-${Date.now()}-${nextTailId++}`
+    return channelApi.subscribe<TailEvent>(
+      'filesystem_tail',
+      { args: { tailId, path, lines: options?.lines } },
+      { onMessage: onEvent, onError: options?.onError }
+    )
+  }
 }
 
 export const filesystemApi = new FilesystemApi()
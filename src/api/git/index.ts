@@ -14,6 +14,12 @@ export interface GitChangedPayload {
   changeType: 'index' | 'head' | 'refs' | 'worktree'
 }
 
+export interface GitMonitorStats {
+  paused: boolean
+  processedEvents: number
+  skippedEvents: number
+}
+
 export class GitApi {
   checkRepository = async (path: string): Promise<string | null> => {
     return invoke<string | null>('git_check_repository', { path })
@@ -52,6 +58,18 @@ export class GitApi {
     return invoke<void>('git_watch_stop')
   }
 
+  watchPause = async (): Promise<void> => {
+    return invoke<void>('git_watch_pause')
+  }
+
+  watchResume = async (reconcile?: boolean): Promise<void> => {
+    return invoke<void>('git_watch_resume', { reconcile })
+  }
+
+  watchStats = async (): Promise<GitMonitorStats | null> => {
+    return invoke<GitMonitorStats | null>('git_watch_stats')
+  }
+
   watchStatus = async (): Promise<string | null> => {
     return invoke<string | null>('git_watch_status')
   }
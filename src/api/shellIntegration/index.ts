@@ -9,6 +9,46 @@
 
 import { invoke } from '@/utils/request'
 
+export type IntegrationInstallStatus =
+  | 'installed'
+  | 'already_current'
+  | 'upgraded'
+  | 'skipped_unsupported'
+  | 'skipped_not_installed'
+  | 'failed'
+
+export interface IntegrationInstallReport {
+  shell: string
+  status: IntegrationInstallStatus
+  configPath: string | null
+  error: string | null
+}
+
+export type IntegrationAction = 'install' | 'uninstall' | 'upgrade'
+
+export interface IntegrationPreview {
+  shell: string
+  action: IntegrationAction
+  configPath: string
+  /** null 表示尚未安装；0 表示没有版本行的旧集成块 */
+  installedVersion: number | null
+  targetVersion: number
+  changed: boolean
+  addedLines: string[]
+  removedLines: string[]
+  /** unified diff */
+  diff: string
+}
+
+export interface IntegrationScriptPreview {
+  shell: string
+  /** 带起止标记和版本行的完整集成块 */
+  script: string
+  targetPath: string
+  /** rc 文件中已有集成块 */
+  alreadyInstalled: boolean
+}
+
 /**
  * Shell Integration API 接口类
  */
@@ -24,6 +64,28 @@ export class ShellIntegrationApi {
     await invoke('shell_setup_integration', { paneId, silent })
   }
 
+  /**
+   * 为所有已安装且受支持的 Shell 安装或升级 rc 文件中的集成块
+   * @returns 每个 Shell 的结果及涉及的 rc 文件路径
+   */
+  setupShellIntegrationAll = async (): Promise<IntegrationInstallReport[]> => {
+    return await invoke<IntegrationInstallReport[]>('shell_setup_integration_all')
+  }
+
+  /**
+   * 预览安装/卸载/升级对 rc 文件的改动，不修改文件
+   */
+  previewShellIntegration = async (shellType: string, action: IntegrationAction): Promise<IntegrationPreview> => {
+    return await invoke<IntegrationPreview>('shell_preview_integration', { shellType, action })
+  }
+
+  /**
+   * 预览将要追加到 rc 文件的集成脚本及目标路径，不修改文件
+   */
+  previewIntegrationScript = async (shell: string): Promise<IntegrationScriptPreview> => {
+    return await invoke<IntegrationScriptPreview>('generate_integration_script_preview', { shell })
+  }
+
   /**
    * 检查Shell Integration状态
    * @param paneId 终端面板ID
@@ -40,6 +102,23 @@ export class ShellIntegrationApi {
     // 返回 FrontendPaneState，可按需解构 node_version 字段
     return await invoke<T>('get_pane_shell_state', { paneId })
   }
+
+  // ===== Shell 类型覆盖 =====
+
+  /**
+   * 设置面板的 Shell 类型
+   * @param tabId 传入时作为用户覆盖持久化，会话恢复后重新应用
+   */
+  setPaneShellType = async (paneId: number, shellType: string, tabId?: string): Promise<void> => {
+    await invoke('set_pane_shell_type', { paneId, shellType, tabId })
+  }
+
+  /**
+   * 会话恢复时重新应用 tab 已存储的 Shell 类型覆盖，返回覆盖的 Shell 名称
+   */
+  restorePaneShellType = async (paneId: number, tabId: string): Promise<string | null> => {
+    return await invoke<string | null>('restore_pane_shell_type', { paneId, tabId })
+  }
 }
 
 export const shellIntegrationApi = new ShellIntegrationApi()
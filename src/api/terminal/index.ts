@@ -10,6 +10,7 @@
 import { invoke } from '@/utils/request'
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import type {
+  CellCoord,
   CreateTerminalWithShellOptions,
   ShellInfo,
   TerminalCreateOptions,
@@ -17,8 +18,11 @@ import type {
   TerminalWriteOptions,
   TerminalConfig,
   CursorConfig,
+  AnsiOverrides,
+  OutputFlushConfig,
   TerminalConfigValidationResult,
   SystemShellsResult,
+  SelectionTextOptions,
 } from './types'
 
 /**
@@ -40,11 +44,17 @@ export class TerminalApi {
       shellName: options.shellName,
       rows: options.rows,
       cols: options.cols,
+      cwd: options.cwd,
     })
   }
 
   writeToTerminal = async (options: TerminalWriteOptions): Promise<void> => {
-    await invoke<void>('terminal_write', { paneId: options.paneId, data: options.data })
+    await invoke<void>('terminal_write', {
+      paneId: options.paneId,
+      data: options.data,
+      paste: options.paste,
+      raw: options.raw,
+    })
   }
 
   resizeTerminal = async (options: TerminalResizeOptions): Promise<void> => {
@@ -55,6 +65,37 @@ export class TerminalApi {
     })
   }
 
+  setTitle = async (paneId: number, title: string, sticky = false): Promise<void> => {
+    await invoke<void>('terminal_set_title', { paneId, title, sticky })
+  }
+
+  /**
+   * 设置 PTY 输出编码（如 utf-8、gbk、latin1），返回规范化后的编码名
+   */
+  setOutputEncoding = async (paneId: number, encoding: string): Promise<string> => {
+    return await invoke<string>('terminal_set_output_encoding', { paneId, encoding })
+  }
+
+  getOutputEncoding = async (paneId: number): Promise<string> => {
+    return await invoke<string>('terminal_get_output_encoding', { paneId })
+  }
+
+  /**
+   * 取出 start 到 end 单元格之间的纯文本，软换行的行会被合并
+   */
+  getSelectionText = async (
+    paneId: number,
+    start: CellCoord,
+    end: CellCoord,
+    options: SelectionTextOptions = {}
+  ): Promise<string> => {
+    return await invoke<string>('terminal_get_selection_text', { paneId, start, end, ...options })
+  }
+
+  getCwd = async (paneId: number): Promise<string | null> => {
+    return await invoke<string | null>('terminal_get_cwd', { paneId })
+  }
+
   closeTerminal = async (paneId: number): Promise<void> => {
     await invoke<void>('terminal_close', { paneId })
   }
@@ -94,6 +135,20 @@ export class TerminalApi {
     await invoke<void>('config_terminal_update', { terminalConfig: config })
   }
 
+  /**
+   * 整体替换 ANSI 调色板覆盖（索引 0-255 -> #rrggbb），传空对象清除
+   */
+  setAnsiOverrides = async (ansiOverrides: AnsiOverrides): Promise<void> => {
+    await invoke<void>('config_terminal_update', { updateRequest: { ansiOverrides } })
+  }
+
+  /**
+   * 更新输出合并策略（intervalMs 0-100，maxBatchBytes 1KiB-1MiB），立即作用于已打开的终端
+   */
+  setOutputFlush = async (outputFlush: OutputFlushConfig): Promise<void> => {
+    await invoke<void>('config_terminal_update', { updateRequest: { outputFlush } })
+  }
+
   validateTerminalConfig = async (): Promise<TerminalConfigValidationResult> => {
     return await invoke<TerminalConfigValidationResult>('config_terminal_validate')
   }
@@ -125,6 +180,20 @@ export class TerminalApi {
     return listen<{ paneId: number; exitCode: number | null }>('terminal_exit', event => callback(event.payload))
   }
 
+  /**
+   * 监听光标配置变更事件（配置更新后广播到所有终端）
+   */
+  onCursorConfigChanged = async (callback: (cursor: CursorConfig) => void): Promise<UnlistenFn> => {
+    return listen<CursorConfig>('terminal_cursor_config_changed', event => callback(event.payload))
+  }
+
+  /**
+   * 监听 ANSI 调色板覆盖变更事件
+   */
+  onAnsiOverridesChanged = async (callback: (overrides: AnsiOverrides) => void): Promise<UnlistenFn> => {
+    return listen<AnsiOverrides>('terminal_ansi_overrides_changed', event => callback(event.payload))
+  }
+
   /**
    * 监听 CWD 变化事件
    */
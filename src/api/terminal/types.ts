@@ -13,6 +13,10 @@ export interface TerminalCreateOptions {
 export interface TerminalWriteOptions {
   paneId: number
   data: string
+  /** 作为粘贴内容写入（shell 支持时使用 bracketed paste） */
+  paste?: boolean
+  /** 强制原样写入，跳过包裹与分块 */
+  raw?: boolean
 }
 
 export interface ReplayEvent {
@@ -31,6 +35,21 @@ export interface TerminalResizeOptions {
   cols: number
 }
 
+// ===== 选区类型 =====
+
+/** 终端单元格坐标，row 从滚动历史第一行开始计数 */
+export interface CellCoord {
+  row: number
+  col: number
+}
+
+export interface SelectionTextOptions {
+  /** 块选：每行取相同的列范围 */
+  rectangular?: boolean
+  /** 去掉每行末尾的空白，默认 true */
+  trimTrailingWhitespace?: boolean
+}
+
 // ===== Shell相关类型（从shell模块重新导出） =====
 
 export type { ShellInfo } from '../shell/types'
@@ -39,6 +58,7 @@ export interface CreateTerminalWithShellOptions {
   shellName?: string
   rows: number
   cols: number
+  cwd?: string
 }
 
 // ===== 事件相关类型 =====
@@ -92,6 +112,21 @@ export interface TerminalConfig {
   shell: ShellConfig
   cursor: CursorConfig
   behavior: TerminalBehaviorConfig
+  /** 新建终端的 PTY 输出编码，默认 utf-8 */
+  outputEncoding?: string
+  /** 在主题调色板之上覆盖的 ANSI 颜色，切换主题后保留 */
+  ansiOverrides?: AnsiOverrides
+  /** 输出推送到前端前的合并策略 */
+  outputFlush?: OutputFlushConfig
+}
+
+/** ANSI 调色板覆盖：键为颜色索引 0-255，值为 #rrggbb */
+export type AnsiOverrides = Record<string, string>
+
+/** 输出合并：缓冲到阈值或间隔到期后发送，intervalMs 为 0 表示不合并 */
+export interface OutputFlushConfig {
+  intervalMs: number
+  maxBatchBytes: number
 }
 
 export interface ShellConfig {
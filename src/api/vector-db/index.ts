@@ -13,8 +13,42 @@ export interface VectorIndexStatus {
   dim: number
 }
 
+/** 最近一段时间的构建速率 */
+export interface BuildThroughput {
+  chunksPerSec: number
+  writesPerSec: number
+}
+
+/** 构建结束时的吞吐汇总 */
+export interface BuildThroughputSummary {
+  provider: string
+  elapsedMs: number
+  filesWritten: number
+  chunksEmbedded: number
+  embedBatches: number
+  configuredBatchSize: number
+  avgBatchSize: number
+  avgEmbedLatencyMs: number
+  avgWriteLatencyMs: number
+  chunksPerSec: number
+  parseWaitMs: number
+  embedMs: number
+  writeMs: number
+  bottleneck: 'parsing' | 'embedding' | 'writing' | null
+}
+
 export interface VectorBuildProgress {
-  phase: 'pending' | 'collecting_files' | 'chunking' | 'embedding' | 'writing' | 'completed' | 'cancelled' | 'failed'
+  phase:
+    | 'pending'
+    | 'collecting_files'
+    | 'chunking'
+    | 'embedding'
+    | 'waiting_for_provider'
+    | 'spend_cap_reached'
+    | 'writing'
+    | 'completed'
+    | 'cancelled'
+    | 'failed'
   root: string
   totalFiles: number
   filesDone: number
@@ -22,10 +56,133 @@ export interface VectorBuildProgress {
   currentFile?: string
   currentFileChunksTotal: number
   currentFileChunksDone: number
+  embedRetries: number
+  /** 被块过滤规则排除的块数 */
+  filteredChunks: ChunkFilterStats
+  /** embedding 服务疑似中断时，距下一次探测的秒数 */
+  retryInSecs?: number
+  throughput: BuildThroughput
+  throughputSummary?: BuildThroughputSummary
+  /** 本次构建累计送入 embedding 的 token 数（估算） */
+  embeddedTokens: number
+  /** token 上限，未设置表示不限制 */
+  embeddingTokenCap?: number
+  estimatedCostUsd?: number
   isDone: boolean
   error?: string
 }
 
+export interface IndexedFilePage {
+  total: number
+  offset: number
+  files: { filePath: string; chunkCount: number; lastIndexedAt?: number; exists: boolean }[]
+}
+
+export interface ChunkFilterStats {
+  too_short: number
+  skipped_type: number
+}
+
+export interface IndexEstimate {
+  totalFiles: number
+  skippedFiles: number
+  totalChunks: number
+  filteredChunks: ChunkFilterStats
+  estimatedTokens: number
+  embeddingModel: string
+  pricePerMillionTokens: number | null
+  estimatedCostUsd: number | null
+  estimatedBatches: number
+  estimatedSeconds: number
+  languages: { language: string | null; files: number; chunks: number; tokens: number }[]
+}
+
+export interface DuplicateReport {
+  total_chunks: number
+  threshold: number
+  total_clusters: number
+  duplicate_chunks: number
+  removable_chunks: number
+  removed_chunks: number
+  clusters: {
+    exact: boolean
+    max_similarity: number
+    chunks: { file_path: string; line_start: number; line_end: number; symbol: string | null; hash: string }[]
+  }[]
+}
+
+export interface BenchmarkQuery {
+  query: string
+  /** 期望命中的文件（绝对路径或相对工作区），用于计算 recall@k */
+  expected_files?: string[]
+}
+
+export interface BenchmarkReport {
+  query_count: number
+  failed_queries: number
+  top_k: number
+  latency: { min_ms: number; mean_ms: number; p50_ms: number; p90_ms: number; p99_ms: number; max_ms: number }
+  evaluated_queries: number
+  recall_at_k: number | null
+  queries: { query: string; latency_ms: number; result_count: number; recall: number | null; error: string | null }[]
+}
+
+export interface IndexCollection {
+  workspace_path: string
+  embedding_model: string
+  vector_dimension: number
+  total_files: number
+  total_chunks: number
+  updated_at: number
+  /** 是否可被当前 embedding 模型直接使用，否则需要重建 */
+  matches_active_model: boolean
+}
+
+export type ChunkTypeWeights = Partial<
+  Record<'Function' | 'Class' | 'Method' | 'Struct' | 'Enum' | 'Generic', number>
+>
+
+/** 全局搜索的单条结果，字段与单工作区搜索结果一致并附带所属工作区 */
+export interface GlobalSearchResult {
+  workspace_path: string
+  file_path: string
+  /** 相对所属工作区根目录 */
+  display_path?: string
+  outside_workspace?: boolean
+  span: { byte_start: number; byte_end: number; line_start: number; line_end: number }
+  score: number
+  /** 按块类型加权前的分数，仅设置了 chunk_type_weights 时存在 */
+  raw_score?: number
+  preview: string
+  chunk_type: string | null
+  symbol?: string
+}
+
+export interface GlobalSearchResults {
+  results: GlobalSearchResult[]
+  searched: string[]
+  /** 模型或维度与当前 embedding 模型不一致、读取失败而跳过的索引 */
+  skipped: { workspace_path: string; reason: string }[]
+}
+
+/** 向量服务状态；initialized 为 false 时需要重新选择 embedding 模型，事件名为 vector_db:status */
+export interface VectorDbServiceStatus {
+  initialized: boolean
+  embedding_model: string
+  vector_dimension: number
+  /** 已被删除的 embedding 模型 */
+  removed_model: string | null
+}
+
+/** 可索引的语言；grammar_registered 为 false 时按通用规则分块 */
+export interface SupportedLanguage {
+  language: string
+  display_name: string
+  extensions: string[]
+  grammar_registered: boolean
+  symbol_extraction: boolean
+}
+
 type RawVectorBuildProgress = {
   phase: VectorBuildProgress['phase']
   root: string
@@ -35,6 +192,14 @@ type RawVectorBuildProgress = {
   current_file?: string
   current_file_chunks_total: number
   current_file_chunks_done: number
+  embed_retries: number
+  filtered_chunks: ChunkFilterStats
+  retry_in_secs?: number | null
+  throughput: BuildThroughput
+  throughput_summary?: BuildThroughputSummary | null
+  embedded_tokens: number
+  embedding_token_cap?: number | null
+  estimated_cost_usd?: number | null
   is_done: boolean
   error?: string
 }
@@ -61,6 +226,14 @@ const mapBuildProgress = (raw: RawVectorBuildProgress): VectorBuildProgress => (
   currentFile: raw.current_file,
   currentFileChunksTotal: raw.current_file_chunks_total,
   currentFileChunksDone: raw.current_file_chunks_done,
+  embedRetries: raw.embed_retries,
+  filteredChunks: raw.filtered_chunks,
+  retryInSecs: raw.retry_in_secs ?? undefined,
+  throughput: raw.throughput,
+  throughputSummary: raw.throughput_summary ?? undefined,
+  embeddedTokens: raw.embedded_tokens,
+  embeddingTokenCap: raw.embedding_token_cap ?? undefined,
+  estimatedCostUsd: raw.estimated_cost_usd ?? undefined,
   isDone: raw.is_done,
   error: raw.error,
 })
@@ -86,10 +259,82 @@ export class VectorDbApi {
     }
   }
 
+  estimateIndex = async (params: { workspacePath: string }): Promise<IndexEstimate> => {
+    return await invoke<IndexEstimate>('vector_index_estimate', params)
+  }
+
+  listIndexedFiles = async (params: {
+    path: string
+    prefix?: string
+    limit?: number
+    offset?: number
+  }): Promise<IndexedFilePage> => {
+    const raw = await invoke<{
+      total: number
+      offset: number
+      files: { file_path: string; chunk_count: number; last_indexed_at?: number; exists: boolean }[]
+    }>('vector_index_list_files', params)
+    return {
+      total: raw.total,
+      offset: raw.offset,
+      files: raw.files.map(f => ({
+        filePath: f.file_path,
+        chunkCount: f.chunk_count,
+        lastIndexedAt: f.last_indexed_at,
+        exists: f.exists,
+      })),
+    }
+  }
+
+  findDuplicates = async (params: { path: string; threshold?: number; dedup?: boolean }): Promise<DuplicateReport> =>
+    invoke<DuplicateReport>('vector_index_find_duplicates', params)
+
+  benchmark = async (params: {
+    path: string
+    queries: BenchmarkQuery[]
+    options?: {
+      top_k: number
+      threshold: number
+      include_snippet: boolean
+      filter_languages: string[]
+      threshold_is_percentile?: boolean
+      symbol_filter?: string | null
+      /** 按块类型对分数加权，未列出的类型为 1.0 */
+      chunk_type_weights?: ChunkTypeWeights
+    }
+  }): Promise<BenchmarkReport> => invoke<BenchmarkReport>('vector_index_benchmark', params)
+
+  fileRenamed = async (params: {
+    path: string
+    oldPath: string
+    newPath: string
+  }): Promise<
+    | { kind: 'path_only'; chunks: number }
+    | { kind: 'reindexed'; chunks: number; retries: number }
+    | { kind: 'suppressed' }
+  > =>
+    invoke('vector_index_file_renamed', params)
+
   deleteWorkspaceIndex = async (path: string): Promise<void> => invoke('delete_workspace_index', { path })
 
-  startBuildIndex = async (params: { root: string }): Promise<void> =>
-    invoke('vector_build_index_start', { path: params.root })
+  /** reindex 为 true 时清空旧索引后以当前 embedding 模型重建 */
+  startBuildIndex = async (params: { root: string; reindex?: boolean }): Promise<void> =>
+    invoke('vector_build_index_start', { path: params.root, reindex: params.reindex })
+
+  listCollections = async (): Promise<IndexCollection[]> => invoke<IndexCollection[]>('vector_index_list_collections')
+
+  /** 跨所有已知工作区的索引搜索，limit 上限 100 */
+  searchGlobal = async (params: { query: string; limit?: number }): Promise<GlobalSearchResults> =>
+    invoke<GlobalSearchResults>('semantic_search_global', params)
+
+  supportedLanguages = async (): Promise<SupportedLanguage[]> =>
+    invoke<SupportedLanguage[]>('vector_index_supported_languages')
+
+  getServiceStatus = async (): Promise<VectorDbServiceStatus> => invoke<VectorDbServiceStatus>('vector_db_get_status')
+
+  /** 用维度一致的 embedding 模型替换已被删除的模型，已有索引保留 */
+  selectEmbeddingModel = async (modelId: string): Promise<VectorDbServiceStatus> =>
+    invoke<VectorDbServiceStatus>('vector_db_select_embedding_model', { modelId })
 
   getBuildStatus = async (params: { root: string }): Promise<VectorBuildProgress | null> => {
     const raw = await invoke<RawVectorBuildProgress | null>('vector_build_index_status', { path: params.root })
@@ -111,6 +356,26 @@ export class VectorDbApi {
     )
   }
 
+  /** 启动构建并只向本次调用推送进度，直到构建结束 */
+  runBuild = (
+    params: { root: string; reindex?: boolean },
+    callbacks: ChannelCallbacks<VectorBuildProgress>
+  ): ChannelSubscription => {
+    return channel.subscribe<RawVectorBuildProgress>(
+      'vector_build_index_run',
+      { path: params.root, reindex: params.reindex },
+      {
+        onMessage: msg => callbacks.onMessage(mapBuildProgress(msg)),
+        onError: callbacks.onError,
+      },
+      { cancelCommand: 'vector_build_index_cancel' }
+    )
+  }
+
+  /** 调整构建的 token 上限（null 为不限制），达到上限而暂停的构建会继续 */
+  setBuildSpendCap = async (params: { root: string; maxTokens: number | null }): Promise<void> =>
+    invoke('vector_build_index_set_spend_cap', { path: params.root, maxTokens: params.maxTokens })
+
   cancelBuild = async (params: { root: string }): Promise<void> =>
     invoke('vector_build_index_cancel', { path: params.root })
 }
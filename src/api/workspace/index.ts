@@ -7,6 +7,7 @@
  */
 
 import { invoke } from '@/utils/request'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 
 /**
  * 最近工作区条目
@@ -17,6 +18,21 @@ export interface RecentWorkspace {
   last_accessed_at: number
 }
 
+/**
+ * 带项目元数据的最近工作区条目
+ */
+export interface RecentWorkspaceDetail {
+  path: string
+  displayName: string | null
+  lastAccessedAt: number
+  /** 目录已不存在，可提示用户清理 */
+  missing: boolean
+  primaryLanguage: string | null
+  hasVectorIndex: boolean
+  /** 当前会话固定的模型，null 表示跟随默认模型 */
+  modelOverride: string | null
+}
+
 /**
  * 工作区 API 封装类
  */
@@ -31,6 +47,14 @@ export class WorkspaceApi {
     return invoke<RecentWorkspace[]>('workspace_get_recent', { limit })
   }
 
+  /**
+   * 获取最近工作区及项目元数据（主要语言、向量索引、固定模型）
+   * @param limit 限制返回数量，默认10个，最多20个
+   */
+  getRecentWorkspaceDetails = async (limit?: number): Promise<RecentWorkspaceDetail[]> => {
+    return invoke<RecentWorkspaceDetail[]>('workspace_get_recent_detailed', { limit })
+  }
+
   /**
    * 添加或更新工作区访问记录
    * @param path 工作区路径
@@ -39,6 +63,13 @@ export class WorkspaceApi {
     await invoke('workspace_add_recent', { path })
   }
 
+  /**
+   * 监听工作区切换；事件到达时新工作区的索引已激活
+   */
+  onWorkspaceChanged = async (callback: (payload: { path: string }) => void): Promise<UnlistenFn> => {
+    return listen<{ path: string }>('workspace-changed', event => callback(event.payload))
+  }
+
   /**
    * 删除指定工作区记录
    * @param path 工作区路径
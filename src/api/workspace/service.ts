@@ -15,6 +15,8 @@ export interface SessionRecord {
   workspacePath: string
   title?: string | null
   messageCount: number
+  /** 会话固定的模型，为空时跟随全局默认模型 */
+  modelId?: string | null
   createdAt: number
   updatedAt: number
 }
@@ -38,6 +40,9 @@ export const workspaceService = {
   setActiveSession: async (path: string, sessionId: number): Promise<void> => {
     await invoke('workspace_set_active_session', { path, sessionId })
   },
+  setSessionModel: async (sessionId: number, modelId: string | null): Promise<SessionRecord> => {
+    return await invoke<SessionRecord>('workspace_set_session_model', { sessionId, modelId })
+  },
   listRecent: async (limit?: number): Promise<WorkspaceRecord[]> => {
     return await invoke<WorkspaceRecord[]>('workspace_get_recent', { limit })
   },
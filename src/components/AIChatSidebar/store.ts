@@ -2,6 +2,7 @@ import { agentApi } from '@/api/agent'
 import type { TaskProgressPayload, TaskProgressStream } from '@/api/agent/types'
 import { useAISettingsStore } from '@/components/settings/components/AI'
 import { useToolConfirmationDialogStore } from '@/stores/toolConfirmationDialog'
+import { useWritePreviewDialogStore } from '@/stores/writePreviewDialog'
 import { useWorkspaceStore } from '@/stores/workspace'
 import { useSessionStore } from '@/stores/session'
 import type { ImageAttachment } from '@/stores/imageLightbox'
@@ -15,6 +16,7 @@ export const useAIChatStore = defineStore('ai-chat', () => {
   const sessionStore = useSessionStore()
   const aiSettingsStore = useAISettingsStore()
   const toolConfirmStore = useToolConfirmationDialogStore()
+  const writePreviewStore = useWritePreviewDialogStore()
 
   const isVisible = ref(false)
   const sidebarWidth = ref(350)
@@ -122,9 +124,18 @@ export const useAIChatStore = defineStore('ai-chat', () => {
         })
         break
       }
+      case 'file_write_preview': {
+        writePreviewStore.open({
+          taskId: event.taskId,
+          requestId: event.requestId,
+          files: event.files,
+        })
+        break
+      }
       case 'task_completed':
       case 'task_cancelled':
       case 'task_error':
+        writePreviewStore.closeForTask(event.taskId)
         isSending.value = false
         break
     }
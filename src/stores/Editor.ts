@@ -2,7 +2,7 @@ import { defineStore } from 'pinia'
 import { computed } from 'vue'
 import { useSessionStore } from './session'
 import { useTerminalStore } from './Terminal'
-import { dockApi } from '@/api'
+import { dockApi, shellIntegrationApi } from '@/api'
 import { getTabDefinition } from '@/tabs/registry'
 import type { DiffTabState, GroupId, TabGroupState, TabId, TabState, TerminalTabState } from '@/types/domain/storage'
 import { createGroupId, createTabId } from '@/types/domain/storage'
@@ -568,6 +568,7 @@ export const useEditorStore = defineStore('Editor', () => {
         return {
           id: t.id,
           title: getPathBasename(terminal?.cwd ?? ''),
+          paneId: t.context.paneId,
         }
       })
     dockApi.updateTabs(entries, activeGroup.value?.activeTabId ?? null)
@@ -634,6 +635,9 @@ export const useEditorStore = defineStore('Editor', () => {
         }
 
         const createdPaneId = await terminalStore.createTerminalPane(tab.data.cwd)
+        await shellIntegrationApi.restorePaneShellType(createdPaneId, tab.id).catch(error => {
+          console.warn('恢复 Shell 类型覆盖失败:', error)
+        })
         const created = terminalStore.terminals.find(t => t.id === createdPaneId)
         groupChanged = true
         nextTabs.push({
@@ -272,6 +272,7 @@ export const useTerminalStore = defineStore('Terminal', () => {
             shellName: options.shellName,
             rows: 24,
             cols: 80,
+            cwd: initialDirectory,
           })
         : await terminalApi.createTerminal({
             rows: 24,
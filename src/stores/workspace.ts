@@ -98,6 +98,17 @@ export const useWorkspaceStore = defineStore('workspace-store', () => {
     await switchSession(created.id)
   }
 
+  // 固定当前会话使用的模型，传 null 取消固定
+  const setSessionModel = async (modelId: string | null) => {
+    if (!currentSession.value) return
+    const updated = await workspaceService.setSessionModel(currentSession.value.id, modelId)
+    currentSession.value = updated
+    const index = sessions.value.findIndex(session => session.id === updated.id)
+    if (index !== -1) {
+      sessions.value[index] = updated
+    }
+  }
+
   return {
     currentWorkspacePath,
     currentWorkspace,
@@ -109,6 +120,7 @@ export const useWorkspaceStore = defineStore('workspace-store', () => {
     loadWorkspaceData,
     switchSession,
     createSession,
+    setSessionModel,
     fetchMessages,
     loadRecentWorkspaces,
   }
@@ -0,0 +1,50 @@
+import { defineStore } from 'pinia'
+import { ref } from 'vue'
+import type { FileWritePreview } from '@/types'
+
+export interface WritePreviewDialogState {
+  taskId: string
+  requestId: string
+  files: FileWritePreview[]
+}
+
+export const useWritePreviewDialogStore = defineStore('writePreviewDialog', () => {
+  const visible = ref(false)
+  const submitting = ref(false)
+  const state = ref<WritePreviewDialogState | null>(null)
+  /** callId -> 是否应用 */
+  const approved = ref<Record<string, boolean>>({})
+  const feedback = ref('')
+
+  const open = (data: WritePreviewDialogState) => {
+    state.value = data
+    approved.value = Object.fromEntries(data.files.map(file => [file.callId, true]))
+    feedback.value = ''
+    submitting.value = false
+    visible.value = true
+  }
+
+  const close = () => {
+    visible.value = false
+    submitting.value = false
+    state.value = null
+    approved.value = {}
+    feedback.value = ''
+  }
+
+  /** 任务结束后后端不再等待，关闭属于该任务的预览 */
+  const closeForTask = (taskId: string) => {
+    if (state.value?.taskId === taskId) close()
+  }
+
+  return {
+    visible,
+    submitting,
+    state,
+    approved,
+    feedback,
+    open,
+    close,
+    closeForTask,
+  }
+})
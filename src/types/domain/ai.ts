@@ -16,9 +16,15 @@ export interface AIModelConfig {
     maxContextTokens?: number
     temperature?: number
     timeout?: number
+    requestTimeoutMs?: number // 流式请求的整体超时（毫秒），推理模型可适当调大
     dimension?: number // 向量模型的维度
+    scoreNormalization?: 'none' | 'min_max' // 向量模型的搜索分数归一化方式
+    scoreOffset?: number // 向量模型的分数偏移，归一化前加到原始分数上
+    maxEmbeddingTokensPerBuild?: number // 向量模型单次构建索引的 token 上限，达到后暂停等待确认
+    maxConcurrentEmbedRequests?: number // 向量模型建索引时同时进行的请求数（1-16，默认 2）
     contextWindow?: number
     maxTokens?: number
+    thinkingBudgetTokens?: number // Extended Thinking 预算，仅支持的 Claude 模型生效
   }
   useCustomBaseUrl?: boolean
   createdAt?: Date
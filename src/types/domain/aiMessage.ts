@@ -7,6 +7,8 @@ export interface TokenUsage {
   outputTokens: number
   cacheReadTokens?: number
   cacheWriteTokens?: number
+  /** 思考消耗的 token（包含在 outputTokens 中，估算值） */
+  thinkingTokens?: number
 }
 
 export interface Message {
@@ -45,8 +47,28 @@ export interface ToolOutput {
   cancelReason?: string
 }
 
+/** 写入预览中的单个文件 */
+export interface FileWritePreview {
+  callId: string
+  toolName: string
+  path: string
+  isNewFile: boolean
+  diff: string
+}
+
+/** 写入预览的审阅结果，未列出的文件按拒绝处理 */
+export interface WritePreviewDecision {
+  callId: string
+  approved: boolean
+  feedback?: string
+}
+
+/** 任务取消原因 */
+export type CancelReason = 'user_cancelled' | 'timeout' | 'parent_cancelled' | 'soft_stop' | 'force_reset'
+
 export type TaskEvent =
   | { type: 'task_created'; taskId: string; sessionId: number; workspacePath: string }
+  | { type: 'task_queued'; taskId: string; position: number }
   | { type: 'message_created'; message: Message }
   | { type: 'block_appended'; messageId: number; block: Block }
   | { type: 'block_updated'; messageId: number; blockId: string; block: Block }
@@ -58,6 +80,7 @@ export type TaskEvent =
       toolName: string
       summary: string
     }
+  | { type: 'file_write_preview'; taskId: string; requestId: string; files: FileWritePreview[] }
   | {
       type: 'message_finished'
       messageId: number
@@ -67,5 +90,7 @@ export type TaskEvent =
       tokenUsage?: TokenUsage
     }
   | { type: 'task_completed'; taskId: string }
+  | { type: 'response_truncated'; taskId: string }
   | { type: 'task_error'; taskId: string; error: { code: string; message: string; details?: string } }
-  | { type: 'task_cancelled'; taskId: string }
+  | { type: 'iteration_limit_reached'; taskId: string; maxIterations: number; summarized: boolean }
+  | { type: 'task_cancelled'; taskId: string; reason: CancelReason; message: string }
@@ -55,6 +55,47 @@ export interface XTermTheme {
   brightCyan?: string
   /** 明亮白色 */
   brightWhite?: string
+
+  /** 扩展颜色 (16-255) */
+  extendedAnsi?: string[]
+}
+
+const ANSI_KEYS: (keyof XTermTheme)[] = [
+  'black',
+  'red',
+  'green',
+  'yellow',
+  'blue',
+  'magenta',
+  'cyan',
+  'white',
+  'brightBlack',
+  'brightRed',
+  'brightGreen',
+  'brightYellow',
+  'brightBlue',
+  'brightMagenta',
+  'brightCyan',
+  'brightWhite',
+]
+
+/**
+ * 在主题之上合并 ANSI 调色板覆盖（索引 0-255 -> 颜色）
+ */
+export const applyAnsiOverrides = (theme: XTermTheme, overrides: Record<string, string>): XTermTheme => {
+  const merged: XTermTheme = { ...theme }
+  for (const [key, color] of Object.entries(overrides)) {
+    const index = Number(key)
+    if (!Number.isInteger(index) || index < 0 || index > 255) continue
+    if (index < ANSI_KEYS.length) {
+      ;(merged as Record<string, unknown>)[ANSI_KEYS[index]] = color
+    } else {
+      const extended = [...(merged.extendedAnsi ?? [])]
+      extended[index - ANSI_KEYS.length] = color
+      merged.extendedAnsi = extended
+    }
+  }
+  return merged
 }
 
 /**